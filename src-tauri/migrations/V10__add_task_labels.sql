@@ -0,0 +1,27 @@
+-- Add task labels (many-to-many between tasks and per-project labels)
+-- Migration: V10__add_task_labels
+-- Created: 2026-10-17
+
+-- Labels defined per project (e.g., bug, feature, chore)
+CREATE TABLE IF NOT EXISTS task_labels (
+    id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    color TEXT,                         -- Optional color name or hex value
+    created_at INTEGER NOT NULL,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    UNIQUE(project_id, name)
+);
+
+-- Labels applied to tasks
+CREATE TABLE IF NOT EXISTS task_label_assignments (
+    task_id TEXT NOT NULL,
+    label_id TEXT NOT NULL,
+    PRIMARY KEY (task_id, label_id),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (label_id) REFERENCES task_labels(id) ON DELETE CASCADE
+);
+
+-- Indexes
+CREATE INDEX IF NOT EXISTS idx_task_labels_project ON task_labels(project_id, name);
+CREATE INDEX IF NOT EXISTS idx_task_label_assignments_label ON task_label_assignments(label_id);
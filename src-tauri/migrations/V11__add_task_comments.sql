@@ -0,0 +1,17 @@
+-- Add task_comments table for discussion threads on tasks
+-- Migration: V11__add_task_comments
+-- Created: 2026-10-17
+
+-- Comments on a task (user notes, pasted agent output, etc.)
+CREATE TABLE IF NOT EXISTS task_comments (
+    id TEXT PRIMARY KEY,
+    task_id TEXT NOT NULL,
+    author TEXT NOT NULL,               -- 'user' or agent type
+    content TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER,                 -- Set when the comment is edited
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
+
+-- Indexes
+CREATE INDEX IF NOT EXISTS idx_task_comments_task ON task_comments(task_id, created_at);
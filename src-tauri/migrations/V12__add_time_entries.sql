@@ -0,0 +1,20 @@
+-- Add time_entries table for task time tracking
+-- Migration: V12__add_time_entries
+-- Created: 2026-10-17
+
+-- Time spent on tasks; a row with no ended_at is a running timer
+CREATE TABLE IF NOT EXISTS time_entries (
+    id TEXT PRIMARY KEY,
+    task_id TEXT NOT NULL,
+    project_id TEXT NOT NULL,
+    source TEXT NOT NULL DEFAULT 'manual',  -- 'manual' (timer) or 'agent' (task run)
+    started_at INTEGER NOT NULL,
+    ended_at INTEGER,
+    duration_seconds INTEGER,
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+-- Indexes
+CREATE INDEX IF NOT EXISTS idx_time_entries_task ON time_entries(task_id, started_at);
+CREATE INDEX IF NOT EXISTS idx_time_entries_project ON time_entries(project_id, started_at);
@@ -0,0 +1,6 @@
+-- Add metadata column to tasks for structured extras (e.g., AI estimates)
+-- Migration: V13__add_task_metadata
+-- Created: 2026-10-17
+
+-- JSON object; keys are owned by the feature that writes them
+ALTER TABLE tasks ADD COLUMN metadata TEXT;
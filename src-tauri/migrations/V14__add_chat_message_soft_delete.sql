@@ -0,0 +1,9 @@
+-- Add soft delete to chat messages so replaced replies stay recoverable
+-- Migration: V14__add_chat_message_soft_delete
+-- Created: 2026-10-17
+
+-- Unix timestamp when the message was hidden from the conversation
+ALTER TABLE chat_messages ADD COLUMN deleted_at INTEGER;
+
+-- Indexes
+CREATE INDEX IF NOT EXISTS idx_chat_messages_deleted_at ON chat_messages(deleted_at);
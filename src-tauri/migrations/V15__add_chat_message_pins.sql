@@ -0,0 +1,9 @@
+-- Add pinning to chat messages so key decisions surface above the scrollback
+-- Migration: V15__add_chat_message_pins
+-- Created: 2026-10-17
+
+ALTER TABLE chat_messages ADD COLUMN pinned BOOLEAN NOT NULL DEFAULT 0;
+ALTER TABLE chat_messages ADD COLUMN pinned_at INTEGER;
+
+-- Indexes
+CREATE INDEX IF NOT EXISTS idx_chat_messages_pinned ON chat_messages(project_id, pinned);
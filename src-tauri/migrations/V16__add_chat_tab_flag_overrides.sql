@@ -0,0 +1,6 @@
+-- Add per-tab CLI flag overrides (e.g., model, permission mode)
+-- Migration: V16__add_chat_tab_flag_overrides
+-- Created: 2026-10-17
+
+-- JSON object of flag_id -> value; takes precedence over plugin settings
+ALTER TABLE chat_tabs ADD COLUMN flag_overrides TEXT;
@@ -0,0 +1,26 @@
+-- Add tables for comparing agents on the same prompt
+-- Migration: V17__add_agent_comparisons
+-- Created: 2026-10-17
+
+-- One prompt sent to several agents
+CREATE TABLE IF NOT EXISTS agent_comparisons (
+    id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL,
+    prompt TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+-- Each agent's session in a comparison and where it worked
+CREATE TABLE IF NOT EXISTS agent_comparison_runs (
+    comparison_id TEXT NOT NULL,
+    session_id TEXT NOT NULL,
+    agent_type TEXT NOT NULL,
+    worktree_path TEXT NOT NULL,
+    isolated BOOLEAN NOT NULL DEFAULT 1,  -- false when the project isn't a git repo and runs share the project root
+    PRIMARY KEY (comparison_id, session_id),
+    FOREIGN KEY (comparison_id) REFERENCES agent_comparisons(id) ON DELETE CASCADE
+);
+
+-- Indexes
+CREATE INDEX IF NOT EXISTS idx_agent_comparisons_project ON agent_comparisons(project_id, created_at);
@@ -0,0 +1,20 @@
+-- Add table for the history of dictated prompts
+-- Migration: V18__add_transcriptions
+-- Created: 2026-10-17
+
+-- Every transcription, kept so a dictated prompt survives cancelling before send
+CREATE TABLE IF NOT EXISTS transcriptions (
+    id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL,
+    text TEXT NOT NULL,
+    duration REAL,             -- seconds of audio
+    provider TEXT NOT NULL,    -- local, openai
+    model TEXT,                -- e.g. base, whisper-1
+    language TEXT,
+    message_id TEXT,           -- message the text was sent as, once sent
+    created_at INTEGER NOT NULL,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+-- Indexes
+CREATE INDEX IF NOT EXISTS idx_transcriptions_project ON transcriptions(project_id, created_at);
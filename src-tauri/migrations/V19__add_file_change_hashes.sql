@@ -0,0 +1,11 @@
+-- Track file content per change so edits after review can be detected
+-- Migration: V19__add_file_change_hashes
+-- Created: 2026-10-17
+
+-- SHA-256 of the file as recorded with the change; NULL for deletions
+ALTER TABLE file_changes ADD COLUMN content_hash TEXT;
+-- Set once the file on disk no longer matches content_hash
+ALTER TABLE file_changes ADD COLUMN stale BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Indexes
+CREATE INDEX IF NOT EXISTS idx_file_changes_path ON file_changes(project_id, file_path);
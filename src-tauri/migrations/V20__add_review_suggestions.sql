@@ -0,0 +1,8 @@
+-- Add suggested replacements to review comments
+-- Migration: V20__add_review_suggestions
+-- Created: 2026-10-17
+
+-- Replacement text for the 1-based inclusive line range in the changed file
+ALTER TABLE review_comments ADD COLUMN suggestion_start_line INTEGER;
+ALTER TABLE review_comments ADD COLUMN suggestion_end_line INTEGER;
+ALTER TABLE review_comments ADD COLUMN suggestion TEXT;
@@ -0,0 +1,24 @@
+-- Add table for background jobs
+-- Migration: V21__add_jobs
+-- Created: 2026-10-17
+
+-- Long-running operations (analysis, exports, clones) and their outcome
+CREATE TABLE IF NOT EXISTS jobs (
+    id TEXT PRIMARY KEY,
+    kind TEXT NOT NULL,        -- e.g. project_analysis, project_export, git_clone
+    project_id TEXT,           -- NULL for jobs that aren't about one project
+    title TEXT NOT NULL,
+    status TEXT NOT NULL,      -- queued, running, completed, failed, cancelled
+    progress REAL,             -- 0.0 to 1.0, when the job reports it
+    message TEXT,              -- latest progress message
+    result TEXT,               -- JSON result of a completed job
+    error TEXT,
+    created_at INTEGER NOT NULL,
+    started_at INTEGER,
+    finished_at INTEGER,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+-- Indexes
+CREATE INDEX IF NOT EXISTS idx_jobs_status ON jobs(status);
+CREATE INDEX IF NOT EXISTS idx_jobs_project ON jobs(project_id, created_at);
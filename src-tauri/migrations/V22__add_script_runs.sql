@@ -0,0 +1,21 @@
+-- Add table for project script runs
+-- Migration: V22__add_script_runs
+-- Created: 2026-10-17
+
+-- Runs of project scripts (npm run build, cargo test, ...) started from the app
+CREATE TABLE IF NOT EXISTS script_runs (
+    id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL,
+    name TEXT NOT NULL,         -- the script as listed, e.g. "npm run build"
+    command TEXT NOT NULL,      -- full command line that ran
+    status TEXT NOT NULL,       -- running, succeeded, failed, cancelled
+    exit_code INTEGER,
+    output TEXT,                -- last lines of stdout and stderr
+    started_at INTEGER NOT NULL,
+    finished_at INTEGER,
+    duration_ms INTEGER,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+-- Indexes
+CREATE INDEX IF NOT EXISTS idx_script_runs_project ON script_runs(project_id, started_at);
@@ -0,0 +1,15 @@
+-- Add table for parsed agent session events
+-- Migration: V23__add_session_events
+-- Created: 2026-10-17
+
+-- Every event parsed from an agent session's output, in order, so the
+-- session can be replayed later with its original timing
+CREATE TABLE IF NOT EXISTS session_events (
+    session_id TEXT NOT NULL,
+    seq INTEGER NOT NULL,           -- 1-based order within the session
+    event_type TEXT NOT NULL,       -- the event's "type", e.g. file_changed
+    payload TEXT NOT NULL,          -- the event as JSON
+    recorded_at INTEGER NOT NULL,   -- Unix milliseconds when it arrived
+    PRIMARY KEY (session_id, seq),
+    FOREIGN KEY (session_id) REFERENCES agent_sessions(id) ON DELETE CASCADE
+);
@@ -0,0 +1,21 @@
+-- Add table for project PRD history
+-- Migration: V24__add_prd_revisions
+-- Created: 2026-10-17
+
+-- Every version of a project's PRD, so edits can be compared and undone
+CREATE TABLE IF NOT EXISTS prd_revisions (
+    id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL,
+    content TEXT NOT NULL,
+    author TEXT NOT NULL,               -- 'user' or 'ai'
+    created_at INTEGER NOT NULL,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_prd_revisions_project ON prd_revisions(project_id, created_at);
+
+-- Existing PRDs become each project's first revision
+INSERT INTO prd_revisions (id, project_id, content, author, created_at)
+SELECT lower(hex(randomblob(16))), id, prd_content, 'user', last_activity
+FROM projects
+WHERE prd_content IS NOT NULL AND prd_content != '';
@@ -0,0 +1,18 @@
+-- Add notes table for per-project markdown pages
+-- Migration: V25__add_notes
+-- Created: 2026-10-17
+
+-- A lightweight project wiki: decisions and background that belong neither
+-- in chat nor in the PRD
+CREATE TABLE IF NOT EXISTS notes (
+    id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL,
+    title TEXT NOT NULL,
+    content TEXT NOT NULL,              -- Markdown
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    UNIQUE(project_id, title)
+);
+
+CREATE INDEX IF NOT EXISTS idx_notes_project ON notes(project_id, updated_at DESC);
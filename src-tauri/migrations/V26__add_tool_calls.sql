@@ -0,0 +1,22 @@
+-- Add tool_calls table for agent tool uses
+-- Migration: V26__add_tool_calls
+-- Created: 2026-10-17
+
+-- Each tool an agent used, pulled out of the session's message JSON so calls
+-- can be listed and filtered (e.g. every Bash command a session ran)
+CREATE TABLE IF NOT EXISTS tool_calls (
+    id TEXT PRIMARY KEY,                -- The tool use id from the CLI
+    project_id TEXT NOT NULL,
+    cli_session_id TEXT NOT NULL,
+    message_id TEXT NOT NULL,           -- Message that made the call
+    tool_name TEXT NOT NULL,
+    input TEXT NOT NULL,                -- JSON
+    result TEXT,                        -- Null until the result comes in
+    is_error BOOLEAN NOT NULL DEFAULT FALSE,
+    started_at INTEGER NOT NULL,
+    completed_at INTEGER,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_tool_calls_session ON tool_calls(cli_session_id, started_at);
+CREATE INDEX IF NOT EXISTS idx_tool_calls_project_tool ON tool_calls(project_id, tool_name);
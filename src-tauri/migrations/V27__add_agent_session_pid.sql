@@ -0,0 +1,8 @@
+-- Add pid to agent_sessions
+-- Migration: V27__add_agent_session_pid
+-- Created: 2026-10-17
+
+-- Process that runs the session when it isn't the app itself (the
+-- ateliercode-cli binary), so cleanup can tell a live CLI run from one that
+-- died without marking its session stopped. Null for sessions the app runs.
+ALTER TABLE agent_sessions ADD COLUMN pid INTEGER;
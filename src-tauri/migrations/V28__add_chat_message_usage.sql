@@ -0,0 +1,20 @@
+-- Add usage columns to chat_messages
+-- Migration: V28__add_chat_message_usage
+-- Created: 2026-10-17
+
+-- Typed copies of what the message metadata JSON records about a reply, so
+-- stats can sum and average them without parsing the JSON
+ALTER TABLE chat_messages ADD COLUMN model TEXT;
+ALTER TABLE chat_messages ADD COLUMN input_tokens INTEGER;
+ALTER TABLE chat_messages ADD COLUMN output_tokens INTEGER;
+ALTER TABLE chat_messages ADD COLUMN cost_usd REAL;
+ALTER TABLE chat_messages ADD COLUMN duration_ms INTEGER;
+
+-- Older replies only recorded the model and processing time
+UPDATE chat_messages
+SET model = json_extract(metadata, '$.model'),
+    input_tokens = json_extract(metadata, '$.inputTokens'),
+    output_tokens = json_extract(metadata, '$.outputTokens'),
+    cost_usd = json_extract(metadata, '$.costUsd'),
+    duration_ms = json_extract(metadata, '$.processingTime')
+WHERE metadata IS NOT NULL AND json_valid(metadata);
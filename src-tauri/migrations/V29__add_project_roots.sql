@@ -0,0 +1,17 @@
+-- Add project_roots table
+-- Migration: V29__add_project_roots
+-- Created: 2026-10-17
+
+-- Folders a project spans besides its root_path, e.g. a shared library kept
+-- next to the app; they are watched and listed in the file tree with it
+CREATE TABLE IF NOT EXISTS project_roots (
+    id TEXT PRIMARY KEY NOT NULL,
+    project_id TEXT NOT NULL,
+    path TEXT NOT NULL,
+    label TEXT,
+    created_at INTEGER NOT NULL,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    UNIQUE (project_id, path)
+);
+
+CREATE INDEX IF NOT EXISTS idx_project_roots_project_id ON project_roots(project_id);
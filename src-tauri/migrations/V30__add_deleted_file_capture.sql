@@ -0,0 +1,8 @@
+-- Capture deleted files on file_changes
+-- Migration: V30__add_deleted_file_capture
+-- Created: 2026-10-17
+
+-- What a 'deleted' change needs to put the file back when it's rejected:
+-- its last content seen by the watcher, or else its git blob as staged
+ALTER TABLE file_changes ADD COLUMN deleted_content BLOB;
+ALTER TABLE file_changes ADD COLUMN deleted_blob TEXT;
@@ -0,0 +1,15 @@
+-- Add project_visits table
+-- Migration: V31__add_project_visits
+-- Created: 2026-10-17
+
+-- One row per time a project was opened in the workspace, closed_at set when
+-- it's left; the home screen lists projects by their latest visit
+CREATE TABLE IF NOT EXISTS project_visits (
+    id TEXT PRIMARY KEY NOT NULL,
+    project_id TEXT NOT NULL,
+    opened_at INTEGER NOT NULL,
+    closed_at INTEGER,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_project_visits_project_opened ON project_visits(project_id, opened_at);
@@ -0,0 +1,15 @@
+-- Add session_bookmarks table
+-- Migration: V32__add_session_bookmarks
+-- Created: 2026-10-17
+
+-- Moments marked in a session's recorded events (e.g. where a run went
+-- wrong), to jump back to when replaying or exporting it; one per event
+CREATE TABLE IF NOT EXISTS session_bookmarks (
+    id TEXT PRIMARY KEY NOT NULL,
+    session_id TEXT NOT NULL,
+    event_seq INTEGER NOT NULL,     -- seq of the bookmarked session_events row
+    note TEXT NOT NULL DEFAULT '',
+    created_at INTEGER NOT NULL,
+    FOREIGN KEY (session_id) REFERENCES agent_sessions(id) ON DELETE CASCADE,
+    UNIQUE(session_id, event_seq)
+);
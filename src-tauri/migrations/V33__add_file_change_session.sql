@@ -0,0 +1,12 @@
+-- Record which session's watcher saw each file change
+-- Migration: V33__add_file_change_session
+-- Created: 2026-10-17
+
+-- The watcher session that recorded the change ('' for changes recorded
+-- before this column existed); chat_session_id stays for the chat, if known
+ALTER TABLE file_changes ADD COLUMN session_id TEXT NOT NULL DEFAULT '';
+
+UPDATE file_changes SET session_id = chat_session_id WHERE chat_session_id IS NOT NULL;
+
+-- Indexes
+CREATE INDEX IF NOT EXISTS idx_file_changes_session ON file_changes(session_id);
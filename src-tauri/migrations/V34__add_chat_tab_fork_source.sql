@@ -0,0 +1,7 @@
+-- Let a duplicated tab fork the original's CLI session instead of sharing it
+-- Migration: V34__add_chat_tab_fork_source
+-- Created: 2026-10-17
+
+-- CLI session a duplicated tab branches from; cleared once its first resume
+-- has forked it and the tab has a cli_session_id of its own
+ALTER TABLE chat_tabs ADD COLUMN forked_from_cli_session_id TEXT;
@@ -0,0 +1,18 @@
+-- Add file_bookmarks table for pinned files per project
+-- Migration: V8__add_file_bookmarks
+-- Created: 2026-10-17
+
+-- Bookmarked files (specs, configs, etc. kept one click away)
+CREATE TABLE IF NOT EXISTS file_bookmarks (
+    id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL,
+    file_path TEXT NOT NULL,            -- Path relative to the project root
+    label TEXT,                         -- Optional display name (null = use file name)
+    auto_attach BOOLEAN NOT NULL DEFAULT FALSE,  -- Attach to prompt context automatically
+    created_at INTEGER NOT NULL,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    UNIQUE(project_id, file_path)
+);
+
+-- Indexes
+CREATE INDEX IF NOT EXISTS idx_file_bookmarks_project ON file_bookmarks(project_id, created_at);
@@ -0,0 +1,21 @@
+-- Add position column to tasks for persistent kanban ordering
+-- Migration: V9__add_task_position
+-- Created: 2026-10-17
+
+-- Position within the task's status column (lower = higher on the board)
+ALTER TABLE tasks ADD COLUMN position INTEGER NOT NULL DEFAULT 0;
+
+-- Number existing tasks within each (project, status) column, newest first,
+-- to match the previous created_at DESC ordering
+UPDATE tasks
+SET position = (
+    SELECT COUNT(*)
+    FROM tasks AS other
+    WHERE other.project_id = tasks.project_id
+      AND other.status = tasks.status
+      AND (other.created_at > tasks.created_at
+           OR (other.created_at = tasks.created_at AND other.id < tasks.id))
+);
+
+-- Indexes
+CREATE INDEX IF NOT EXISTS idx_tasks_position ON tasks(project_id, status, position);
@@ -0,0 +1,51 @@
+// Activity Feed
+// Stores activity log entries and pushes each new one to the frontend as an event
+
+use sqlx::SqlitePool;
+use std::sync::OnceLock;
+use tauri::{AppHandle, Emitter};
+
+use crate::models::ActivityLog;
+
+/// Event carrying every new activity, across all projects
+pub const ALL_ACTIVITY_EVENT: &str = "activity://all";
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+/// Event carrying new activity for one project
+pub fn project_activity_event(project_id: &str) -> String {
+    format!("activity://{}", project_id)
+}
+
+/// Set the app handle that activity events are emitted through
+pub fn init_events(app: AppHandle) {
+    let _ = APP.set(app);
+}
+
+/// Insert an activity log entry and notify listeners
+pub async fn insert_activity(pool: &SqlitePool, activity: &ActivityLog) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO activity_log (id, project_id, session_id, event_type, description, data, timestamp)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
+        "#
+    )
+    .bind(&activity.id)
+    .bind(&activity.project_id)
+    .bind(&activity.session_id)
+    .bind(&activity.event_type)
+    .bind(&activity.description)
+    .bind(&activity.data)
+    .bind(activity.timestamp)
+    .execute(pool)
+    .await?;
+
+    if let Some(app) = APP.get() {
+        for event in [project_activity_event(&activity.project_id).as_str(), ALL_ACTIVITY_EVENT] {
+            if let Err(e) = app.emit(event, activity) {
+                log::warn!("Failed to emit {}: {}", event, e);
+            }
+        }
+    }
+    Ok(())
+}
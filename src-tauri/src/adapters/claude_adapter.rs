@@ -23,7 +23,7 @@ impl ClaudeCodeAdapter {
             file_created_pattern: Regex::new(r"(?i)created?:?\s+(.+?)(?:\s|$)").unwrap(),
             file_modified_pattern: Regex::new(r"(?i)(?:modified?|edit(?:ed|ing)?|writ(?:e|ing)):?\s+(.+?)(?:\s|$)").unwrap(),
             file_deleted_pattern: Regex::new(r"(?i)deleted?:?\s+(.+?)(?:\s|$)").unwrap(),
-            command_pattern: Regex::new(r"(?i)(?:ran|executed?|running):?\s+`?([^`\n]+)`?\s*(?:\(exit code:?\s*(\d+)\))?").unwrap(),
+            command_pattern: Regex::new(r"(?i)(?:ran|executed?|running):?\s+`?([^`\n]+?)`?\s*(?:\(exit code:?\s*(\d+)\))?\s*$").unwrap(),
             code_block_pattern: Regex::new(r"```(\w+)?\n([\s\S]*?)```").unwrap(),
         }
     }
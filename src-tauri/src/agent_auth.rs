@@ -0,0 +1,239 @@
+// Agent Authentication
+// Detects whether an agent's CLI is signed in or has an API key, and runs the
+// CLI's login flow in a terminal so its prompts can be answered from the UI
+
+use serde::Serialize;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// How long a CLI's status command may take before it's treated as unknown
+const STATUS_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// How an agent CLI signs in
+struct AuthSpec {
+    cli: &'static str,
+    /// Exits successfully when the CLI is signed in
+    status_args: Option<&'static [&'static str]>,
+    /// Runs the interactive login; None when the CLI only takes API keys
+    login_args: Option<&'static [&'static str]>,
+    /// Files, relative to the home directory, the CLI keeps its login in
+    credential_files: &'static [&'static str],
+    api_key_vars: &'static [&'static str],
+}
+
+fn auth_spec(plugin_name: &str) -> Option<AuthSpec> {
+    match plugin_name.to_lowercase().as_str() {
+        "claude" | "claude-code" => Some(AuthSpec {
+            cli: "claude",
+            status_args: Some(&["auth", "status"]),
+            login_args: Some(&["auth", "login"]),
+            credential_files: &[".claude/.credentials.json"],
+            api_key_vars: &["ANTHROPIC_API_KEY"],
+        }),
+        // Gemini asks how to sign in the first time it starts interactively
+        "gemini" | "gemini-cli" => Some(AuthSpec {
+            cli: "gemini",
+            status_args: None,
+            login_args: Some(&[]),
+            credential_files: &[".gemini/oauth_creds.json"],
+            api_key_vars: &["GEMINI_API_KEY", "GOOGLE_API_KEY"],
+        }),
+        "codex" => Some(AuthSpec {
+            cli: "codex",
+            status_args: Some(&["login", "status"]),
+            login_args: Some(&["login"]),
+            credential_files: &[".codex/auth.json"],
+            api_key_vars: &["OPENAI_API_KEY"],
+        }),
+        "aider" => Some(AuthSpec {
+            cli: "aider",
+            status_args: None,
+            login_args: None,
+            credential_files: &[],
+            api_key_vars: &["ANTHROPIC_API_KEY", "OPENAI_API_KEY", "GEMINI_API_KEY"],
+        }),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthMethod {
+    CliLogin,
+    ApiKey,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AuthStatus {
+    pub plugin_name: String,
+    pub logged_in: bool,
+    /// How the agent is signed in, when it is
+    pub method: Option<AuthMethod>,
+    /// What the CLI reported, or what was found, e.g. the API key's variable
+    pub detail: String,
+    /// Whether `start_agent_login` can sign this agent in
+    pub can_login: bool,
+}
+
+/// Run the CLI's status command: whether it succeeded, with its first line of output
+async fn run_status_command(spec: &AuthSpec) -> Option<(bool, String)> {
+    let args = spec.status_args?;
+    let mut cmd = crate::cli_command::command(spec.cli, args);
+    cmd.kill_on_drop(true);
+    let output = tokio::time::timeout(STATUS_TIMEOUT, cmd.output()).await.ok()?.ok()?;
+    let text = [output.stdout, output.stderr]
+        .iter()
+        .map(|bytes| String::from_utf8_lossy(bytes).to_string())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let first_line = text.lines().map(str::trim).find(|line| !line.is_empty()).unwrap_or_default();
+    Some((output.status.success(), first_line.to_string()))
+}
+
+/// Combine what was found into a status, preferring the CLI's own login
+fn resolve_status(
+    plugin_name: &str,
+    spec: &AuthSpec,
+    cli_status: Option<(bool, String)>,
+    credential_file: Option<PathBuf>,
+    api_key_var: Option<&str>,
+) -> AuthStatus {
+    let status = |logged_in, method, detail: String| AuthStatus {
+        plugin_name: plugin_name.to_string(),
+        logged_in,
+        method,
+        detail,
+        can_login: spec.login_args.is_some(),
+    };
+    let cli_detail = match cli_status {
+        Some((true, detail)) => return status(true, Some(AuthMethod::CliLogin), detail),
+        Some((false, detail)) => Some(detail),
+        None => None,
+    };
+    if let Some(path) = credential_file {
+        return status(
+            true,
+            Some(AuthMethod::CliLogin),
+            format!("Credentials found in {}", path.display()),
+        );
+    }
+    if let Some(var) = api_key_var {
+        return status(true, Some(AuthMethod::ApiKey), format!("{} is set", var));
+    }
+
+    let hint = match (spec.login_args.is_some(), spec.api_key_vars) {
+        (true, []) => format!("Sign in to {}", spec.cli),
+        (true, vars) => format!("Sign in to {} or set {}", spec.cli, vars.join(" or ")),
+        (false, vars) => format!("Set {}", vars.join(" or ")),
+    };
+    let detail = match cli_detail {
+        Some(detail) if !detail.is_empty() => format!("{}. {}", detail, hint),
+        _ => hint,
+    };
+    status(false, None, detail)
+}
+
+pub async fn get_auth_status(plugin_name: &str) -> Result<AuthStatus, String> {
+    let spec = auth_spec(plugin_name)
+        .ok_or_else(|| format!("Don't know how {} signs in", plugin_name))?;
+    if which::which(spec.cli).is_err() {
+        return Err(format!("The {} CLI was not found", spec.cli));
+    }
+
+    let cli_status = run_status_command(&spec).await;
+    let credential_file = dirs::home_dir().and_then(|home| {
+        spec.credential_files
+            .iter()
+            .map(|file| home.join(file))
+            .find(|path| path.is_file())
+    });
+    let api_key_var = spec
+        .api_key_vars
+        .iter()
+        .copied()
+        .find(|var| std::env::var(var).is_ok_and(|value| !value.trim().is_empty()));
+    Ok(resolve_status(plugin_name, &spec, cli_status, credential_file, api_key_var))
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+use crate::terminal::{TerminalInfo, TerminalManager, TERMINAL_EXIT_EVENT, TERMINAL_OUTPUT_EVENT};
+use portable_pty::{CommandBuilder, PtySize};
+use tauri::{AppHandle, Emitter, State};
+
+/// Whether an agent's CLI is signed in or has an API key to use
+#[tauri::command]
+pub async fn get_agent_auth_status(plugin_name: String) -> Result<AuthStatus, String> {
+    get_auth_status(&plugin_name).await
+}
+
+/// Start the agent CLI's login flow in a terminal in the home directory
+///
+/// Output arrives as `terminal-output` events and input goes through
+/// `write_terminal`, so codes can be pasted back into the CLI;
+/// `terminal-exit` follows when the login finishes.
+#[tauri::command]
+pub async fn start_agent_login(
+    app: AppHandle,
+    terminals: State<'_, TerminalManager>,
+    plugin_name: String,
+    cols: Option<u16>,
+    rows: Option<u16>,
+) -> Result<TerminalInfo, String> {
+    let spec = auth_spec(&plugin_name)
+        .ok_or_else(|| format!("Don't know how {} signs in", plugin_name))?;
+    let login_args = spec
+        .login_args
+        .ok_or_else(|| format!("{} has no login; set {}", spec.cli, spec.api_key_vars.join(" or ")))?;
+    let home = dirs::home_dir().ok_or("Could not find the home directory")?;
+
+    // Resolved the same way agents are spawned, so npm shims work on Windows
+    let resolved = crate::cli_command::std_command(spec.cli, login_args);
+    let mut cmd = CommandBuilder::new(resolved.get_program());
+    cmd.args(resolved.get_args());
+
+    let output_app = app.clone();
+    terminals
+        .open_command(
+            // Login terminals belong to no project
+            "",
+            &home,
+            cmd,
+            PtySize { cols: cols.unwrap_or(80), rows: rows.unwrap_or(24), ..Default::default() },
+            move |output| {
+                let _ = output_app.emit(TERMINAL_OUTPUT_EVENT, output);
+            },
+            move |exit| {
+                let _ = app.emit(TERMINAL_EXIT_EVENT, exit);
+            },
+        )
+        .map_err(|e| format!("Failed to start {} login: {:#}", spec.cli, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_status() {
+        let claude = auth_spec("claude-code").unwrap();
+        let logged_in = resolve_status("claude-code", &claude, Some((true, "Logged in".into())), None, None);
+        assert!(logged_in.logged_in);
+        assert_eq!(logged_in.method, Some(AuthMethod::CliLogin));
+
+        let key = resolve_status("claude-code", &claude, Some((false, "Not logged in".into())), None, Some("ANTHROPIC_API_KEY"));
+        assert_eq!(key.method, Some(AuthMethod::ApiKey));
+
+        let missing = resolve_status("claude-code", &claude, Some((false, "Not logged in".into())), None, None);
+        assert!(!missing.logged_in && missing.can_login);
+        assert_eq!(missing.detail, "Not logged in. Sign in to claude or set ANTHROPIC_API_KEY");
+
+        let aider = auth_spec("aider").unwrap();
+        let status = resolve_status("aider", &aider, None, None, None);
+        assert!(!status.can_login);
+        assert!(status.detail.starts_with("Set ANTHROPIC_API_KEY or"));
+        assert!(auth_spec("unknown").is_none());
+    }
+}
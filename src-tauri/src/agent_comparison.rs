@@ -0,0 +1,191 @@
+// Agent Comparison
+// Isolates agents in git worktrees and reports what each one changed
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+use crate::cli_command;
+use crate::models::ComparisonRun;
+
+/// What one agent changed during a comparison
+#[derive(Debug, Clone)]
+pub struct RunDiff {
+    pub agent_type: String,
+    pub session_id: String,
+    pub stat: String,
+    pub diff: String,
+}
+
+/// Whether a directory is inside a git work tree, for synchronous callers
+pub fn is_git_repo(path: &str) -> bool {
+    cli_command::std_command("git", ["rev-parse", "--is-inside-work-tree"])
+        .current_dir(path)
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false)
+}
+
+/// Directory holding every comparison's worktrees
+fn comparisons_dir() -> PathBuf {
+    std::env::temp_dir().join("ateliercode-comparisons")
+}
+
+/// Directory for one agent's worktree in a comparison
+pub fn worktree_dir(comparison_id: &str, index: usize, agent_type: &str) -> PathBuf {
+    comparisons_dir()
+        .join(comparison_id)
+        .join(format!("{}-{}", index + 1, agent_type))
+}
+
+/// Run git in `dir` and return its output
+async fn git(dir: impl AsRef<Path>, args: &[&str]) -> Result<String> {
+    let output = cli_command::command("git", args)
+        .current_dir(dir)
+        .output()
+        .await
+        .with_context(|| format!("Failed to execute git {}", args.join(" ")))?;
+    if !output.status.success() {
+        anyhow::bail!("git {} failed: {}", args.join(" "), String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Fail unless `repo_path` is a git work tree, which comparisons need to
+/// give each agent a worktree of its own
+pub async fn require_git_repo(repo_path: &str) -> Result<()> {
+    git(repo_path, &["rev-parse", "--is-inside-work-tree"])
+        .await
+        .map(|_| ())
+        .with_context(|| format!("{} is not a git repository; agents can only be compared in one", repo_path))
+}
+
+/// Create a detached worktree of the repo's HEAD at `path`
+pub async fn add_worktree(repo_path: &str, path: &Path) -> Result<()> {
+    git(repo_path, &["worktree", "add", "--detach", &path.to_string_lossy(), "HEAD"]).await?;
+    Ok(())
+}
+
+/// Remove the worktrees a comparison created, along with their changes
+///
+/// Only paths under the comparisons directory are touched, so a run that
+/// worked in the project root is left alone.
+pub async fn remove_worktrees(repo_path: &str, runs: &[ComparisonRun]) -> Result<()> {
+    let root = comparisons_dir();
+    for run in runs.iter().filter(|run| Path::new(&run.worktree_path).starts_with(&root)) {
+        if let Err(e) = git(repo_path, &["worktree", "remove", "--force", &run.worktree_path]).await {
+            log::warn!("Failed to remove worktree {}: {}", run.worktree_path, e);
+            let _ = tokio::fs::remove_dir_all(&run.worktree_path).await;
+        }
+    }
+    // Drops the records of worktrees whose directories are already gone
+    git(repo_path, &["worktree", "prune"]).await?;
+
+    if let Some(run) = runs.first() {
+        let _ = tokio::fs::remove_dir(root.join(&run.comparison_id)).await;
+    }
+    Ok(())
+}
+
+/// Collect a run's changes relative to HEAD, including new files
+pub async fn collect_diff(run: &ComparisonRun) -> Result<RunDiff> {
+    if !run.isolated {
+        anyhow::bail!(
+            "{} worked in the project root without a worktree, so its changes can't be told apart",
+            run.agent_type
+        );
+    }
+
+    // Mark untracked files as intent-to-add so they show up in the diff
+    git(&run.worktree_path, &["add", "--intent-to-add", "."]).await?;
+
+    Ok(RunDiff {
+        agent_type: run.agent_type.clone(),
+        session_id: run.session_id.clone(),
+        stat: git(&run.worktree_path, &["diff", "--stat", "HEAD"]).await?,
+        diff: git(&run.worktree_path, &["diff", "HEAD"]).await?,
+    })
+}
+
+/// Render the combined Markdown report for a comparison
+pub fn render_report(prompt: &str, diffs: &[RunDiff]) -> String {
+    let mut out = String::from("# Agent Comparison\n\n");
+    out.push_str(&format!("**Prompt:**\n\n> {}\n", prompt.trim().replace('\n', "\n> ")));
+
+    for run in diffs {
+        out.push_str(&format!("\n## {} (session {})\n\n", run.agent_type, run.session_id));
+        if run.diff.trim().is_empty() {
+            out.push_str("No changes.\n");
+            continue;
+        }
+        out.push_str(&format!("```\n{}\n```\n\n", run.stat.trim_end()));
+        out.push_str(&format!("```diff\n{}\n```\n", run.diff.trim_end()));
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_diff(agent: &str, diff: &str) -> RunDiff {
+        RunDiff {
+            agent_type: agent.to_string(),
+            session_id: format!("{}-session", agent),
+            stat: " a.rs | 1 +\n".to_string(),
+            diff: diff.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_render_report() {
+        let diffs = vec![run_diff("claude", "+fn a() {}\n"), run_diff("gemini", "")];
+        let report = render_report("Add a\nfunction", &diffs);
+
+        assert!(report.contains("> Add a\n> function"));
+        assert!(report.contains("## claude (session claude-session)\n\n```\n a.rs | 1 +\n```"));
+        assert!(report.contains("```diff\n+fn a() {}\n```"));
+        assert!(report.contains("## gemini (session gemini-session)\n\nNo changes."));
+    }
+
+    #[tokio::test]
+    async fn test_worktree_lifecycle() {
+        let repo = std::env::temp_dir().join(format!("ateliercode-comparison-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&repo).unwrap();
+        let repo_path = repo.to_string_lossy().to_string();
+        assert!(require_git_repo(&repo_path).await.is_err());
+
+        git(&repo, &["init", "-q"]).await.unwrap();
+        std::fs::write(repo.join("a.rs"), "fn a() {}\n").unwrap();
+        git(&repo, &["add", "."]).await.unwrap();
+        git(&repo, &["-c", "user.name=t", "-c", "user.email=t@t", "commit", "-qm", "init"]).await.unwrap();
+        require_git_repo(&repo_path).await.unwrap();
+
+        let comparison_id = uuid::Uuid::new_v4().to_string();
+        let dir = worktree_dir(&comparison_id, 0, "claude");
+        add_worktree(&repo_path, &dir).await.unwrap();
+        std::fs::write(dir.join("b.rs"), "fn b() {}\n").unwrap();
+
+        let run = ComparisonRun {
+            comparison_id: comparison_id.clone(),
+            session_id: "s1".to_string(),
+            agent_type: "claude".to_string(),
+            worktree_path: dir.to_string_lossy().to_string(),
+            isolated: true,
+        };
+        let diff = collect_diff(&run).await.unwrap();
+        assert!(diff.diff.contains("+fn b() {}"));
+
+        remove_worktrees(&repo_path, std::slice::from_ref(&run)).await.unwrap();
+        assert!(!dir.exists());
+        assert!(!git(&repo, &["worktree", "list"]).await.unwrap().contains(&comparison_id));
+
+        let _ = std::fs::remove_dir_all(&repo);
+    }
+
+    #[test]
+    fn test_worktree_dir() {
+        let dir = worktree_dir("c1", 0, "claude");
+        assert!(dir.ends_with("ateliercode-comparisons/c1/1-claude"));
+    }
+}
@@ -0,0 +1,112 @@
+// Agent Defaults
+// Picks the agent for new chat tabs and sessions that don't name one: the
+// project's override, the agent it was created with, the app-wide default,
+// then any available agent
+
+use tauri::{AppHandle, Manager};
+
+use crate::agent_manager::BUILTIN_AGENTS;
+use crate::app_settings::AppSettingsManager;
+use crate::error::AppError;
+use crate::models::{Project, ProjectSettings};
+use crate::plugin::PluginManager;
+use crate::plugins::demo;
+
+/// The agent to use, given what's configured and which agents are available
+///
+/// An explicit request always wins. Configured agents that aren't available
+/// are skipped for the first available one (in the order given, never the
+/// demo agent); with none available, the first configured one is kept so the
+/// error surfaces when the agent is started.
+pub fn resolve(
+    requested: Option<&str>,
+    project_default: Option<&str>,
+    project_agent: &str,
+    global_default: Option<&str>,
+    available: &[String],
+) -> Option<String> {
+    if let Some(requested) = requested.filter(|name| !name.is_empty()) {
+        return Some(requested.to_string());
+    }
+    let configured: Vec<&str> = [project_default, Some(project_agent), global_default]
+        .into_iter()
+        .flatten()
+        .filter(|name| !name.is_empty())
+        .collect();
+    configured
+        .iter()
+        .find(|name| available.iter().any(|agent| agent.eq_ignore_ascii_case(name)))
+        .map(|name| name.to_string())
+        .or_else(|| available.iter().find(|name| *name != demo::PLUGIN_NAME).cloned())
+        .or_else(|| configured.first().map(|name| name.to_string()))
+}
+
+/// Installed plugins by name, then the agents run without one
+fn available_agents(app: &AppHandle) -> Vec<String> {
+    let mut plugins: Vec<String> = app
+        .state::<PluginManager>()
+        .list_plugins()
+        .into_iter()
+        .map(|plugin| plugin.name)
+        .collect();
+    plugins.sort();
+    plugins.extend(BUILTIN_AGENTS.iter().map(|name| name.to_string()));
+    plugins
+}
+
+/// The agent for a new tab or session of `project`
+pub fn resolve_for_project(app: &AppHandle, project: &Project, requested: Option<&str>) -> Result<String, AppError> {
+    let settings = ProjectSettings::from_json(project.settings.as_deref());
+    let global_default = app.state::<AppSettingsManager>().get().default_agent;
+    resolve(
+        requested,
+        settings.default_agent.as_deref(),
+        &project.agent_type,
+        global_default.as_deref(),
+        &available_agents(app),
+    )
+    .ok_or_else(|| AppError::invalid_input(format!("No agent configured for project {}", project.id)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn available(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_explicit_request_wins() {
+        let agent = resolve(Some("aider"), Some("gemini"), "claude-code", None, &available(&["claude-code"]));
+        assert_eq!(agent.as_deref(), Some("aider"));
+        // An empty request counts as none
+        let agent = resolve(Some(""), None, "claude-code", None, &available(&["claude-code"]));
+        assert_eq!(agent.as_deref(), Some("claude-code"));
+    }
+
+    #[test]
+    fn test_project_override_then_project_agent_then_global() {
+        let all = available(&["aider", "claude-code", "gemini"]);
+        assert_eq!(resolve(None, Some("gemini"), "claude-code", Some("aider"), &all).as_deref(), Some("gemini"));
+        assert_eq!(resolve(None, None, "claude-code", Some("aider"), &all).as_deref(), Some("claude-code"));
+        assert_eq!(resolve(None, None, "", Some("aider"), &all).as_deref(), Some("aider"));
+    }
+
+    #[test]
+    fn test_skips_agents_that_are_not_available() {
+        let some = available(&["aider", "gemini"]);
+        assert_eq!(resolve(None, Some("codex"), "claude-code", Some("gemini"), &some).as_deref(), Some("gemini"));
+        // Names differing only in case still match
+        assert_eq!(resolve(None, Some("Aider"), "claude-code", None, &some).as_deref(), Some("Aider"));
+        // Nothing configured is available: the first available agent, never the demo one
+        let some = available(&[demo::PLUGIN_NAME, "gemini", "aider"]);
+        assert_eq!(resolve(None, None, "codex", None, &some).as_deref(), Some("gemini"));
+    }
+
+    #[test]
+    fn test_keeps_configured_agent_when_nothing_is_available() {
+        assert_eq!(resolve(None, None, "claude-code", Some("aider"), &[]).as_deref(), Some("claude-code"));
+        assert_eq!(resolve(None, None, "", None, &[]), None);
+    }
+}
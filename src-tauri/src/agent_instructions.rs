@@ -0,0 +1,257 @@
+// Agent Instructions
+// Reads and writes the instruction file each agent CLI loads from the project
+// root (CLAUDE.md, GEMINI.md, Aider's conventions file) and drafts one from the
+// project analysis and PRD
+
+use serde::Serialize;
+use std::path::Path;
+
+use crate::error::AppError;
+use crate::scripts::ScriptConfig;
+use crate::types::ProjectAnalysisResult;
+
+/// Aider has no instruction file of its own; it reads files listed in its config
+const AIDER_CONFIG: &str = ".aider.conf.yml";
+const AIDER_CONVENTIONS: &str = "CONVENTIONS.md";
+/// Most PRD text sent along when drafting instructions
+const MAX_PRD_CHARS: usize = 6000;
+/// Most scripts listed in a draft
+const MAX_SCRIPTS: usize = 15;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AgentInstructions {
+    pub agent: String,
+    /// Relative to the project root
+    pub path: String,
+    pub exists: bool,
+    pub content: String,
+}
+
+/// The instruction file `agent` reads, relative to the project root
+///
+/// Agents without a convention of their own get AGENTS.md, which a growing
+/// number of CLIs pick up.
+pub fn instructions_file(agent: &str) -> &'static str {
+    match agent.to_lowercase().as_str() {
+        "claude" | "claude-code" => "CLAUDE.md",
+        "gemini" | "gemini-cli" => "GEMINI.md",
+        "aider" => AIDER_CONVENTIONS,
+        _ => "AGENTS.md",
+    }
+}
+
+pub fn read(root: &Path, agent: &str) -> Result<AgentInstructions, AppError> {
+    let path = instructions_file(agent);
+    let (exists, content) = match std::fs::read_to_string(root.join(path)) {
+        Ok(content) => (true, content),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => (false, String::new()),
+        Err(e) => return Err(AppError::from(e).wrap(format!("Failed to read {}", path))),
+    };
+    Ok(AgentInstructions { agent: agent.to_string(), path: path.to_string(), exists, content })
+}
+
+/// Write the agent's instruction file; for Aider, also list it under `read:`
+/// in .aider.conf.yml so it's loaded
+pub fn write(root: &Path, agent: &str, content: &str) -> Result<AgentInstructions, AppError> {
+    let path = instructions_file(agent);
+    let mut content = content.trim_end().to_string();
+    content.push('\n');
+    std::fs::write(root.join(path), &content).map_err(AppError::context("Failed to write agent instructions"))?;
+
+    if path == AIDER_CONVENTIONS {
+        let config_path = root.join(AIDER_CONFIG);
+        let config = std::fs::read_to_string(&config_path).unwrap_or_default();
+        if let Some(updated) = add_aider_read(&config) {
+            std::fs::write(&config_path, updated).map_err(AppError::context("Failed to update .aider.conf.yml"))?;
+        }
+    }
+    Ok(AgentInstructions { agent: agent.to_string(), path: path.to_string(), exists: true, content })
+}
+
+/// `config` with the conventions file added to `read:`, or `None` when it's
+/// already there or `read:` is set to something we'd have to restructure
+fn add_aider_read(config: &str) -> Option<String> {
+    if config.contains(AIDER_CONVENTIONS) {
+        return None;
+    }
+    if config.lines().any(|line| line.starts_with("read:")) {
+        log::warn!("{} already sets read:, not adding {}", AIDER_CONFIG, AIDER_CONVENTIONS);
+        return None;
+    }
+    let mut updated = config.to_string();
+    if !updated.is_empty() && !updated.ends_with('\n') {
+        updated.push('\n');
+    }
+    updated.push_str(&format!("read: {}\n", AIDER_CONVENTIONS));
+    Some(updated)
+}
+
+/// Instructions built from the analysis alone, used when no AI is available
+pub fn template_draft(name: &str, analysis: &ProjectAnalysisResult, prd: Option<&str>, scripts: &[ScriptConfig]) -> String {
+    let mut draft = format!("# {}\n\n", name);
+    let overview = prd
+        .and_then(|prd| prd.lines().map(str::trim).find(|line| !line.is_empty() && !line.starts_with('#')))
+        .unwrap_or(&analysis.suggested_description);
+    draft.push_str(&format!("## Overview\n\n{}\n\n", overview));
+
+    draft.push_str("## Tech Stack\n\n");
+    if !analysis.detected_languages.is_empty() {
+        draft.push_str(&format!("- Languages: {}\n", analysis.detected_languages.join(", ")));
+    }
+    if !analysis.detected_frameworks.is_empty() {
+        draft.push_str(&format!("- Frameworks: {}\n", analysis.detected_frameworks.join(", ")));
+    }
+    draft.push('\n');
+
+    if !scripts.is_empty() {
+        draft.push_str("## Commands\n\n");
+        for script in scripts.iter().take(MAX_SCRIPTS) {
+            draft.push_str(&format!("- `{}`\n", script.name));
+        }
+        draft.push('\n');
+    }
+
+    draft.push_str(
+        "## Conventions\n\n\
+         - Follow the style of the surrounding code\n\
+         - Keep changes focused on the task at hand\n\
+         - Run the tests before finishing a change\n",
+    );
+    draft
+}
+
+/// The prompt asking an AI to write the instruction file
+fn draft_prompt(agent_file: &str, name: &str, analysis: &ProjectAnalysisResult, prd: Option<&str>, scripts: &[ScriptConfig]) -> String {
+    let scripts: Vec<&str> = scripts.iter().take(MAX_SCRIPTS).map(|s| s.name.as_str()).collect();
+    let prd: String = prd.unwrap_or("(none)").chars().take(MAX_PRD_CHARS).collect();
+    format!(
+        r#"Write the {agent_file} file an AI coding agent reads before working on this project.
+
+Project: {name}
+Languages: {languages}
+Frameworks: {frameworks}
+Project commands: {scripts}
+
+Product requirements:
+{prd}
+
+Cover, in Markdown with short sections: what the project is, the tech stack, how to build, test and run it
+(use the commands above), code conventions to follow, and anything the agent must not do.
+Be concise and specific to this project. Reply with the file content only, without a code fence."#,
+        languages = analysis.detected_languages.join(", "),
+        frameworks = analysis.detected_frameworks.join(", "),
+        scripts = if scripts.is_empty() { "(none detected)".to_string() } else { scripts.join(", ") },
+    )
+}
+
+/// Strip a code fence the model wrapped its reply in despite being asked not to
+fn unfence(reply: &str) -> &str {
+    let reply = reply.trim();
+    reply
+        .strip_prefix("```")
+        .and_then(|rest| rest.split_once('\n'))
+        .and_then(|(_, body)| body.trim_end().strip_suffix("```"))
+        .map_or(reply, str::trim)
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+use crate::commands::get_project;
+use crate::db::Database;
+use crate::models::Project;
+use tauri::State;
+
+async fn load_project(db: State<'_, Database>, project_id: String) -> Result<Project, AppError> {
+    get_project(db, project_id.clone())
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Project not found: {}", project_id)))
+}
+
+/// The project's instruction file for `agent`, or for the project's own agent
+#[tauri::command]
+pub async fn get_agent_instructions(
+    db: State<'_, Database>,
+    project_id: String,
+    agent: Option<String>,
+) -> Result<AgentInstructions, AppError> {
+    let project = load_project(db, project_id).await?;
+    read(Path::new(&project.root_path), agent.as_deref().unwrap_or(&project.agent_type))
+}
+
+#[tauri::command]
+pub async fn set_agent_instructions(
+    db: State<'_, Database>,
+    project_id: String,
+    content: String,
+    agent: Option<String>,
+) -> Result<AgentInstructions, AppError> {
+    let project = load_project(db, project_id).await?;
+    write(Path::new(&project.root_path), agent.as_deref().unwrap_or(&project.agent_type), &content)
+}
+
+/// Draft instructions from the project analysis and PRD; nothing is saved
+///
+/// Falls back to a template when no AI provider is available.
+#[tauri::command]
+pub async fn generate_agent_instructions(
+    db: State<'_, Database>,
+    project_id: String,
+    agent: Option<String>,
+) -> Result<String, AppError> {
+    let project = load_project(db, project_id).await?;
+    let agent = agent.unwrap_or_else(|| project.agent_type.clone());
+    let root = project.root_path.clone();
+    let (analysis, scripts) = tokio::task::spawn_blocking(move || {
+        crate::project_analyzer::analyze_project(&root).map(|analysis| (analysis, crate::scripts::detect(Path::new(&root))))
+    })
+    .await?
+    .map_err(AppError::context("Project analysis failed"))?;
+
+    let prd = project.prd_content.as_deref().filter(|prd| !prd.trim().is_empty());
+    let fallback = template_draft(&project.name, &analysis, prd, &scripts);
+
+    use crate::ai_service::{AIService, ChatMessage};
+    let ai_service = AIService::from_env();
+    if !ai_service.is_available() {
+        log::info!("AI service not available, drafting agent instructions from a template");
+        return Ok(fallback);
+    }
+    let prompt = draft_prompt(instructions_file(&agent), &project.name, &analysis, prd, &scripts);
+    match ai_service.chat_completion(vec![ChatMessage { role: "user".to_string(), content: prompt }]).await {
+        Ok(reply) if !reply.trim().is_empty() => Ok(unfence(&reply).to_string()),
+        Ok(_) => Ok(fallback),
+        Err(e) => {
+            log::warn!("AI draft of agent instructions failed: {}, using template", e);
+            Ok(fallback)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_instructions_file_per_agent() {
+        assert_eq!(instructions_file("claude-code"), "CLAUDE.md");
+        assert_eq!(instructions_file("Gemini"), "GEMINI.md");
+        assert_eq!(instructions_file("aider"), "CONVENTIONS.md");
+        assert_eq!(instructions_file("codex"), "AGENTS.md");
+    }
+
+    #[test]
+    fn test_add_aider_read() {
+        assert_eq!(add_aider_read("").as_deref(), Some("read: CONVENTIONS.md\n"));
+        assert_eq!(add_aider_read("model: gpt-4o").as_deref(), Some("model: gpt-4o\nread: CONVENTIONS.md\n"));
+        assert!(add_aider_read("read: [CONVENTIONS.md]\n").is_none());
+        assert!(add_aider_read("read:\n  - NOTES.md\n").is_none());
+    }
+
+    #[test]
+    fn test_unfence() {
+        assert_eq!(unfence("```markdown\n# App\n\nText\n```\n"), "# App\n\nText");
+        assert_eq!(unfence("# App\n"), "# App");
+    }
+}
@@ -4,10 +4,18 @@ use std::collections::HashMap;
 use std::process::Stdio;
 use std::sync::Arc;
 use tokio::io::{AsyncBufReadExt, BufReader};
-use tokio::process::{Child, Command};
-use tokio::sync::RwLock;
+use tokio::process::{Child, ChildStderr, ChildStdin, Command};
+use tokio::sync::{broadcast, oneshot, Mutex, RwLock};
+use tracing::Instrument;
 
-use crate::output_parser::{AgentEvent, OutputParser};
+use crate::cli_command;
+use crate::models::ExecutionProfile;
+use crate::output_parser::{AgentEvent, ClaudeStreamParser, ErrorSeverity, OutputParser};
+use crate::plugins::demo;
+use crate::redaction::Redactor;
+
+/// Agent types run by [`AgentManager`] itself, without a plugin
+pub const BUILTIN_AGENTS: &[&str] = &["claude-code", "claude", "gemini-cli", "gemini", "aider"];
 
 /// Represents an active agent session
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -41,8 +49,55 @@ struct RunningSession {
     parsed_events: Vec<AgentEvent>,
     parser: OutputParser,
     claude_session_id: Option<String>,
+    /// Claude session to fork on the first message, until the fork has an ID
+    fork_from: Option<String>,
     /// The currently running child process (if any)
     active_child: Option<Arc<RwLock<Option<Child>>>>,
+    /// Broadcasts parsed events to live subscribers (e.g., task runners)
+    event_tx: broadcast::Sender<AgentEvent>,
+    /// Masks secrets in output lines before they're buffered or parsed
+    redactor: Redactor,
+    /// Hook that vets shell commands before the agent runs them
+    command_hook: Option<CommandHook>,
+    /// Restrictions for the agent processes this session spawns
+    execution_profile: Option<ExecutionProfile>,
+    /// Persistent Claude process, for sessions in the persistent conversation mode
+    claude_stream: Option<ClaudeStream>,
+    stream_parser: ClaudeStreamParser,
+}
+
+impl RunningSession {
+    /// Hand events to live subscribers and buffer them, and their raw lines, for readers
+    fn publish(&mut self, events: Vec<AgentEvent>) {
+        for event in &events {
+            let _ = self.event_tx.send(event.clone());
+            if let AgentEvent::RawOutput { line, .. } = event {
+                self.output_buffer.push(line.clone());
+            }
+        }
+        self.parsed_events.extend(events);
+        self.session.last_activity = chrono::Utc::now().timestamp();
+    }
+}
+
+/// A Claude process started with `--input-format stream-json`, which stays up
+/// across turns and reads each user message from stdin as a JSON line
+struct ClaudeStream {
+    stdin: Arc<Mutex<ChildStdin>>,
+    /// Flags, hook and profile it was started with; any change needs a new process
+    signature: String,
+    pid: Option<u32>,
+    /// Dropping the stream drops this, which tells its wait task to kill the process
+    _kill: oneshot::Sender<()>,
+}
+
+/// A Claude PreToolUse hook for a session, passed with `--settings`
+#[derive(Debug, Clone)]
+pub struct CommandHook {
+    /// Claude settings JSON declaring the hook
+    pub settings: String,
+    /// Environment the hook needs, set on the agent process
+    pub env: Vec<(String, String)>,
 }
 
 /// Manages all agent sessions and their lifecycle
@@ -80,6 +135,10 @@ impl AgentManager {
             anyhow::bail!("Project root path does not exist: {}", root_path);
         }
 
+        // Demo sessions answer from a transcript that exists from the start
+        let resume_session_id = resume_session_id
+            .or_else(|| (agent_type == demo::PLUGIN_NAME).then(|| uuid::Uuid::new_v4().to_string()));
+
         // Create session metadata
         let now = chrono::Utc::now().timestamp();
         let session = AgentSession {
@@ -101,69 +160,58 @@ impl AgentManager {
             parsed_events: Vec::new(),
             parser: OutputParser::new(),
             claude_session_id: resume_session_id.clone(),
+            fork_from: None,
             active_child: None,
+            event_tx: broadcast::channel(256).0,
+            redactor: Redactor::builtin().clone(),
+            command_hook: None,
+            execution_profile: None,
+            claude_stream: None,
+            stream_parser: ClaudeStreamParser::new(),
         };
 
         self.sessions.write().await.insert(session_id.clone(), running_session);
 
         log::info!("Agent session {} started successfully", session_id);
 
-        // If starting a new session (not resuming), initialize it to get the CLI session ID
-        if resume_session_id.is_none() {
-            let agent_lower = agent_type.to_lowercase();
-            if agent_lower.contains("claude") {
-                if let Err(e) = self.initialize_claude_session(&session_id, &root_path).await {
-                    log::warn!("Failed to initialize Claude session ID: {}", e);
-                    // Don't fail the whole session start, just log the warning
-                }
-            }
-            // Note: Gemini CLI v0.19+ now supports --resume and --list-sessions.
-            // We use --resume latest in get_headless_command to automatically continue
-            // the most recent session, so explicit initialization is not needed here.
-        }
+        // New Claude sessions get their CLI session ID with the first message:
+        // per-message mode initializes one, a persistent process announces its own.
+        // Note: Gemini CLI v0.19+ now supports --resume and --list-sessions.
+        // We use --resume latest in get_headless_command to automatically continue
+        // the most recent session, so explicit initialization is not needed here.
+        Ok(session)
+    }
 
-        // Return the updated session (might have claude_session_id now)
-        let sessions = self.sessions.read().await;
-        if let Some(running_session) = sessions.get(&session_id) {
-            Ok(running_session.session.clone())
-        } else {
-            Ok(session)
-        }
+    /// Answer from the demo agent's script instead of running a CLI
+    async fn send_demo_message(&self, session_id: &str, message: &str) -> Result<()> {
+        let mut sessions = self.sessions.write().await;
+        let running_session = sessions.get_mut(session_id).context("Session not found")?;
+        let cli_session_id = running_session.claude_session_id.clone().context("Demo session has no ID")?;
+        let reply = demo::reply(&cli_session_id, &running_session.root_path, message)?;
+
+        let timestamp = chrono::Utc::now().timestamp();
+        running_session.publish(vec![
+            AgentEvent::MessageReceived { content: reply, timestamp },
+            AgentEvent::TurnCompleted { success: true, timestamp },
+        ]);
+        Ok(())
     }
 
-    /// Initialize a new Claude session to get its session ID
-    async fn initialize_claude_session(&self, session_id: &str, root_path: &str) -> Result<()> {
+    /// Initialize a new Claude session to get its session ID, forking
+    /// `fork_from` when given
+    async fn initialize_claude_session(&self, session_id: &str, root_path: &str, fork_from: Option<&str>) -> Result<()> {
         log::info!("Initializing Claude session to get session ID...");
 
         // Run a simple command to initialize the session
-        let mut cmd = Command::new("claude");
-        cmd.arg("-p")
-            .arg("Starting a new session")
-            .arg("--output-format")
-            .arg("json")
-            .arg("--permission-mode")
-            .arg("bypassPermissions")
-            .current_dir(root_path)
+        let mut args = vec!["-p", "Starting a new session", "--output-format", "json", "--permission-mode", "bypassPermissions"];
+        if let Some(source) = fork_from {
+            args.extend(["--resume", source, "--fork-session"]);
+        }
+        let mut cmd = cli_command::command("claude", args);
+        cmd.current_dir(root_path)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
 
-        #[cfg(target_os = "windows")]
-        {
-            let mut cmd_wrapper = Command::new("cmd");
-            cmd_wrapper.arg("/C")
-                .arg("claude")
-                .arg("-p")
-                .arg("Starting a new session")
-                .arg("--output-format")
-                .arg("json")
-                .arg("--permission-mode")
-                .arg("bypassPermissions")
-                .current_dir(root_path)
-                .stdout(Stdio::piped())
-                .stderr(Stdio::piped());
-            cmd = cmd_wrapper;
-        }
-
         let output = cmd.output().await
             .context("Failed to execute Claude initialization command")?;
 
@@ -182,6 +230,7 @@ impl AgentManager {
                 if let Some(running_session) = sessions.get_mut(session_id) {
                     running_session.claude_session_id = Some(claude_session_id.to_string());
                     running_session.session.claude_session_id = Some(claude_session_id.to_string());
+                    running_session.fork_from = None;
                 }
 
                 return Ok(());
@@ -204,7 +253,9 @@ impl AgentManager {
     /// Send a message to an agent session using headless mode
     /// If a process is already running, it will be killed first (interrupt + resume pattern)
     ///
-    /// `flag_settings` can be provided to customize CLI flags (e.g., permission_mode, model)
+    /// `flag_settings` can be provided to customize CLI flags (e.g., permission_mode, model).
+    /// Claude sessions whose `conversation_mode` flag is "persistent" keep one
+    /// process instead; see [`Self::send_stream_message`].
     pub async fn send_message(
         &self,
         session_id: &str,
@@ -213,8 +264,36 @@ impl AgentManager {
     ) -> Result<()> {
         log::info!("Sending message to session {} (length: {} bytes)", session_id, message.len());
 
+        let status = self.get_session_status(session_id).await?;
+        if status.agent_type == demo::PLUGIN_NAME {
+            return self.send_demo_message(session_id, &message).await;
+        }
+        if Self::uses_persistent_process(&status.agent_type, flag_settings.as_ref()) {
+            return self.send_stream_message(session_id, &message, flag_settings.as_ref()).await;
+        }
+
         // First, kill any existing process for this session
         self.kill_active_process(session_id).await;
+        self.close_claude_stream(session_id).await;
+
+        // Without a session ID, `claude -c` would continue whatever conversation last ran in the project
+        if status.claude_session_id.is_none() && status.agent_type.to_lowercase().contains("claude") {
+            let pending = self
+                .sessions
+                .read()
+                .await
+                .get(session_id)
+                .map(|rs| (rs.root_path.clone(), rs.fork_from.clone()));
+            if let Some((root_path, fork_from)) = pending {
+                let initialized = self.initialize_claude_session(session_id, &root_path, fork_from.as_deref()).await;
+                match (initialized, fork_from) {
+                    (Ok(()), _) => {}
+                    // Continuing another conversation would write into the one being forked
+                    (Err(e), Some(source)) => return Err(e.context(format!("Failed to fork Claude session {}", source))),
+                    (Err(e), None) => log::warn!("Failed to initialize Claude session ID: {}", e),
+                }
+            }
+        }
 
         let sessions = self.sessions.read().await;
         let running_session = sessions
@@ -224,6 +303,14 @@ impl AgentManager {
         let agent_type = running_session.session.agent_type.clone();
         let root_path = running_session.root_path.clone();
         let claude_session_id = running_session.claude_session_id.clone();
+        let command_hook = running_session.command_hook.clone();
+        let execution_profile = running_session.execution_profile.clone();
+        // Tags the output readers' log lines with the session
+        let span = tracing::info_span!(
+            "agent_session",
+            session_id = %session_id,
+            project_id = %running_session.session.project_id
+        );
         drop(sessions); // Release the read lock
 
         // Get the command based on agent type
@@ -234,34 +321,24 @@ impl AgentManager {
             flag_settings.as_ref(),
         )?;
 
-        log::info!("Executing headless command: {} {:?} in {} (use_stdin: {})", program, args, root_path, use_stdin);
+        log::info!("Running headless command (use_stdin: {})", use_stdin);
 
         // Execute the command in headless mode
         // Use Stdio::piped() for stdin if we need to send a large message
         // Otherwise use Stdio::null() to signal no input is coming
-        let mut cmd = Command::new(&program);
-        cmd.args(&args)
-            .current_dir(&root_path)
-            .stdin(if use_stdin { Stdio::piped() } else { Stdio::null() })
+        let mut cmd = Self::agent_command(
+            &agent_type,
+            &root_path,
+            program,
+            args,
+            command_hook.as_ref(),
+            execution_profile.as_ref(),
+        )
+        .await?;
+        cmd.stdin(if use_stdin { Stdio::piped() } else { Stdio::null() })
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
 
-        #[cfg(target_os = "windows")]
-        {
-            // On Windows, we may need to use cmd.exe wrapper for .cmd files
-            if program == "claude" || program == "aider" || program == "gemini" {
-                let original_args = args.clone();
-                cmd = Command::new("cmd");
-                cmd.arg("/C")
-                    .arg(&program)
-                    .args(&original_args)
-                    .current_dir(&root_path)
-                    .stdin(if use_stdin { Stdio::piped() } else { Stdio::null() })
-                    .stdout(Stdio::piped())
-                    .stderr(Stdio::piped());
-            }
-        }
-
         let mut child = cmd.spawn().context("Failed to spawn headless command")?;
 
         // If using stdin, write the message and close stdin to signal EOF
@@ -296,9 +373,7 @@ impl AgentManager {
 
         // Spawn background tasks to read both stdout and stderr
         let sessions_clone = self.sessions.clone();
-        let sessions_clone_stderr = self.sessions.clone();
         let session_id_clone = session_id.to_string();
-        let session_id_clone_stderr = session_id.to_string();
 
         // Read stdout
         if let Some(stdout) = child.stdout.take() {
@@ -321,10 +396,15 @@ impl AgentManager {
                             }
                         }
 
+                        let line = running_session.redactor.redact(&line).into_owned();
+
                         // Parse the line into events
                         let events = running_session.parser.parse_line(&line);
 
-                        // Store parsed events
+                        // Notify live subscribers, then store parsed events
+                        for event in &events {
+                            let _ = running_session.event_tx.send(event.clone());
+                        }
                         running_session.parsed_events.extend(events);
 
                         // Also keep raw output for compatibility
@@ -332,35 +412,12 @@ impl AgentManager {
                         running_session.session.last_activity = chrono::Utc::now().timestamp();
                     }
                 }
-            });
+            }.instrument(span.clone()));
         }
 
         // Read stderr
         if let Some(stderr) = child.stderr.take() {
-            tokio::spawn(async move {
-                let reader = BufReader::new(stderr);
-                let mut lines = reader.lines();
-
-                while let Ok(Some(line)) = lines.next_line().await {
-                    log::info!("STDERR: {}", line);
-
-                    // Add to output buffer (prefixed so we know it's stderr) and parse events
-                    let mut sessions = sessions_clone_stderr.write().await;
-                    if let Some(running_session) = sessions.get_mut(&session_id_clone_stderr) {
-                        let stderr_line = format!("[stderr] {}", line);
-
-                        // Parse the line into events (stderr often contains errors)
-                        let events = running_session.parser.parse_line(&line);
-
-                        // Store parsed events
-                        running_session.parsed_events.extend(events);
-
-                        // Also keep raw output for compatibility
-                        running_session.output_buffer.push(stderr_line);
-                        running_session.session.last_activity = chrono::Utc::now().timestamp();
-                    }
-                }
-            });
+            Self::read_stderr(self.sessions.clone(), session_id.to_string(), stderr, span.clone());
         }
 
         // Store the child in the holder so it can be killed if needed
@@ -370,20 +427,366 @@ impl AgentManager {
         }
 
         // Wait for process to complete in another task
+        let sessions_clone_wait = self.sessions.clone();
+        let session_id_clone_wait = session_id.to_string();
+        let max_runtime = execution_profile
+            .and_then(|profile| profile.max_runtime_seconds)
+            .map(std::time::Duration::from_secs);
         tokio::spawn(async move {
             // Get the child from the holder
             let mut child_opt = child_holder.write().await;
             if let Some(mut child) = child_opt.take() {
-                match child.wait().await {
-                    Ok(status) => log::info!("Headless command completed with status: {}", status),
-                    Err(e) => log::error!("Error waiting for headless command: {}", e),
+                let mut timed_out = None;
+                let status = match max_runtime {
+                    Some(limit) => match tokio::time::timeout(limit, child.wait()).await {
+                        Ok(status) => status,
+                        Err(_) => {
+                            log::warn!("Agent exceeded its {}s runtime limit, killing it", limit.as_secs());
+                            let _ = child.kill().await;
+                            timed_out = Some(limit);
+                            child.wait().await
+                        }
+                    },
+                    None => child.wait().await,
+                };
+                let exit_event = match status {
+                    Ok(status) => {
+                        log::info!("Headless command completed with status: {}", status);
+                        AgentEvent::ProcessExited {
+                            success: status.success(),
+                            exit_code: status.code(),
+                            timestamp: chrono::Utc::now().timestamp(),
+                        }
+                    }
+                    Err(e) => {
+                        log::error!("Error waiting for headless command: {}", e);
+                        AgentEvent::ProcessExited {
+                            success: false,
+                            exit_code: None,
+                            timestamp: chrono::Utc::now().timestamp(),
+                        }
+                    }
+                };
+                drop(child_opt);
+
+                let mut sessions = sessions_clone_wait.write().await;
+                if let Some(running_session) = sessions.get_mut(&session_id_clone_wait) {
+                    if let Some(limit) = timed_out {
+                        let timeout_event = AgentEvent::Error {
+                            message: format!("Stopped after the execution profile's {}s runtime limit", limit.as_secs()),
+                            severity: ErrorSeverity::Error,
+                            timestamp: chrono::Utc::now().timestamp(),
+                        };
+                        let _ = running_session.event_tx.send(timeout_event.clone());
+                        running_session.parsed_events.push(timeout_event);
+                    }
+                    let _ = running_session.event_tx.send(exit_event.clone());
+                    running_session.parsed_events.push(exit_event);
                 }
             }
+        }.instrument(span));
+
+        Ok(())
+    }
+
+    /// Whether a session's Claude runs as one persistent stream-json process
+    fn uses_persistent_process(agent_type: &str, flag_settings: Option<&HashMap<String, String>>) -> bool {
+        matches!(agent_type.to_lowercase().as_str(), "claude" | "claude-code")
+            && flag_settings.and_then(|flags| flags.get("conversation_mode")).map(String::as_str) == Some("persistent")
+    }
+
+    /// Send a message to the session's persistent Claude process, starting one
+    /// if none is running or the running one has other flags
+    ///
+    /// Unlike per-message mode, a message sent mid-turn doesn't interrupt the
+    /// turn; Claude answers it next. The execution profile's runtime limit
+    /// applies to per-message processes only.
+    async fn send_stream_message(
+        &self,
+        session_id: &str,
+        message: &str,
+        flag_settings: Option<&HashMap<String, String>>,
+    ) -> Result<()> {
+        let flags = Self::claude_flag_args(flag_settings);
+        let (signature, running) = {
+            let sessions = self.sessions.read().await;
+            let running_session = sessions.get(session_id).context("Session not found")?;
+            let signature = serde_json::json!({
+                "flags": flags,
+                "hook": running_session.command_hook.as_ref().map(|hook| &hook.settings),
+                "profile": running_session.execution_profile,
+            })
+            .to_string();
+            let running = running_session
+                .claude_stream
+                .as_ref()
+                .filter(|stream| stream.signature == signature)
+                .map(|stream| stream.stdin.clone());
+            (signature, running)
+        };
+
+        // A per-message process may still be running from before the mode changed
+        self.kill_active_process(session_id).await;
+        let stdin = match running {
+            Some(stdin) => stdin,
+            None => self.start_claude_stream(session_id, flags, signature).await?,
+        };
+
+        let turn = serde_json::json!({
+            "type": "user",
+            "message": { "role": "user", "content": [{ "type": "text", "text": message }] },
         });
+        {
+            use tokio::io::AsyncWriteExt;
+            let mut stdin = stdin.lock().await;
+            stdin
+                .write_all(format!("{}\n", turn).as_bytes())
+                .await
+                .context("Failed to send message to Claude")?;
+            stdin.flush().await.context("Failed to send message to Claude")?;
+        }
 
+        if let Some(running_session) = self.sessions.write().await.get_mut(session_id) {
+            running_session.session.last_activity = chrono::Utc::now().timestamp();
+        }
         Ok(())
     }
 
+    /// Start a persistent Claude process for a session, replacing (and so
+    /// stopping) any it had, and return its stdin
+    async fn start_claude_stream(
+        &self,
+        session_id: &str,
+        flags: Vec<String>,
+        signature: String,
+    ) -> Result<Arc<Mutex<ChildStdin>>> {
+        let sessions = self.sessions.read().await;
+        let running_session = sessions.get(session_id).context("Session not found")?;
+        let agent_type = running_session.session.agent_type.clone();
+        let root_path = running_session.root_path.clone();
+        let claude_session_id = running_session.claude_session_id.clone();
+        let fork_from = running_session.fork_from.clone();
+        let command_hook = running_session.command_hook.clone();
+        let execution_profile = running_session.execution_profile.clone();
+        let span = tracing::info_span!(
+            "agent_session",
+            session_id = %session_id,
+            project_id = %running_session.session.project_id
+        );
+        drop(sessions);
+
+        if which::which("claude").is_err() {
+            anyhow::bail!("Claude CLI not found. Please ensure 'claude' is installed and in your PATH.");
+        }
+        let mut args: Vec<String> = ["-p", "--input-format", "stream-json", "--output-format", "stream-json", "--verbose"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        if let Some(claude_session_id) = &claude_session_id {
+            args.push("--resume".to_string());
+            args.push(claude_session_id.clone());
+        } else if let Some(source) = &fork_from {
+            // The fork announces its own session ID once it starts
+            args.extend(["--resume".to_string(), source.clone(), "--fork-session".to_string()]);
+        }
+        args.extend(flags);
+
+        let mut cmd = Self::agent_command(
+            &agent_type,
+            &root_path,
+            "claude".to_string(),
+            args,
+            command_hook.as_ref(),
+            execution_profile.as_ref(),
+        )
+        .await?;
+        cmd.stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::piped());
+        let mut child = cmd.spawn().context("Failed to start Claude")?;
+        let pid = child.id();
+        log::info!("Started persistent Claude process for session {} (PID: {:?})", session_id, pid);
+
+        let stdin = Arc::new(Mutex::new(child.stdin.take().context("Claude's stdin isn't piped")?));
+        let (kill_tx, kill_rx) = oneshot::channel::<()>();
+        {
+            let mut sessions = self.sessions.write().await;
+            let running_session = sessions.get_mut(session_id).context("Session not found")?;
+            running_session.session.pid = pid;
+            running_session.stream_parser = ClaudeStreamParser::new();
+            running_session.claude_stream = Some(ClaudeStream {
+                stdin: stdin.clone(),
+                signature,
+                pid,
+                _kill: kill_tx,
+            });
+        }
+
+        if let Some(stdout) = child.stdout.take() {
+            let sessions = self.sessions.clone();
+            let session_id = session_id.to_string();
+            tokio::spawn(async move {
+                let mut lines = BufReader::new(stdout).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    log::debug!("STREAM: {}", line);
+
+                    let mut sessions = sessions.write().await;
+                    let Some(running_session) = sessions.get_mut(&session_id) else {
+                        break;
+                    };
+                    let line = running_session.redactor.redact(&line).into_owned();
+                    let parsed = running_session.stream_parser.parse_line(&running_session.parser, &line);
+                    if let Some(claude_session_id) = parsed.session_id {
+                        if running_session.claude_session_id.as_ref() != Some(&claude_session_id) {
+                            log::info!("Detected Claude session ID: {}", claude_session_id);
+                        }
+                        running_session.claude_session_id = Some(claude_session_id.clone());
+                        running_session.session.claude_session_id = Some(claude_session_id);
+                        running_session.fork_from = None;
+                    }
+                    running_session.publish(parsed.events);
+                }
+            }.instrument(span.clone()));
+        }
+        if let Some(stderr) = child.stderr.take() {
+            Self::read_stderr(self.sessions.clone(), session_id.to_string(), stderr, span.clone());
+        }
+
+        let sessions = self.sessions.clone();
+        let session_id = session_id.to_string();
+        tokio::spawn(async move {
+            let status = tokio::select! {
+                status = child.wait() => Some(status),
+                _ = kill_rx => None,
+            };
+            let (success, exit_code) = match status {
+                Some(Ok(status)) => {
+                    log::info!("Persistent Claude process exited with status: {}", status);
+                    (status.success(), status.code())
+                }
+                Some(Err(e)) => {
+                    log::error!("Error waiting for persistent Claude process: {}", e);
+                    (false, None)
+                }
+                // Replaced or stopped; whoever waits on the session now waits on its successor
+                None => {
+                    log::info!("Stopping persistent Claude process (PID: {:?})", pid);
+                    let _ = child.kill().await;
+                    return;
+                }
+            };
+
+            let mut sessions = sessions.write().await;
+            if let Some(running_session) = sessions.get_mut(&session_id) {
+                // The next message starts a new process
+                if running_session.claude_stream.as_ref().is_some_and(|stream| stream.pid == pid) {
+                    running_session.claude_stream = None;
+                }
+                running_session.publish(vec![AgentEvent::ProcessExited {
+                    success,
+                    exit_code,
+                    timestamp: chrono::Utc::now().timestamp(),
+                }]);
+            }
+        }.instrument(span));
+
+        Ok(stdin)
+    }
+
+    /// Stop a session's persistent Claude process, if it has one
+    async fn close_claude_stream(&self, session_id: &str) {
+        if let Some(running_session) = self.sessions.write().await.get_mut(session_id) {
+            running_session.claude_stream = None;
+        }
+    }
+
+    /// Build the command for an agent process with the session's command hook
+    /// and execution profile (env files, sandbox or container) applied
+    async fn agent_command(
+        agent_type: &str,
+        root_path: &str,
+        program: String,
+        mut args: Vec<String>,
+        command_hook: Option<&CommandHook>,
+        execution_profile: Option<&ExecutionProfile>,
+    ) -> Result<Command> {
+        if let Some(hook) = command_hook {
+            if program == "claude" {
+                args.push("--settings".to_string());
+                args.push(hook.settings.clone());
+            } else {
+                log::warn!("{} doesn't support command hooks; its commands aren't gated", agent_type);
+            }
+        }
+
+        let project_env = execution_profile
+            .map(|profile| crate::env_files::load(std::path::Path::new(root_path), &profile.env_files))
+            .unwrap_or_default();
+
+        let (program, args) = match execution_profile.filter(|profile| profile.run_in_container) {
+            Some(profile) => {
+                let env_names: Vec<String> = project_env
+                    .iter()
+                    .map(|(k, _)| k.clone())
+                    .chain(command_hook.iter().flat_map(|hook| hook.env.iter().map(|(k, _)| k.clone())))
+                    .collect();
+                crate::container_env::wrap(profile.container_name.as_deref(), std::path::Path::new(root_path), program, args, &env_names).await?
+            }
+            None => crate::sandbox::wrap(execution_profile, program, args)?,
+        };
+
+        log::info!("Executing agent command: {} {:?} in {}", program, args, root_path);
+
+        let mut cmd = cli_command::command(&program, &args);
+        cmd.current_dir(root_path);
+        if let Some(profile) = execution_profile.filter(|profile| !profile.run_in_container) {
+            crate::sandbox::apply(&mut cmd, profile)?;
+        }
+        if !project_env.is_empty() {
+            log::info!("Setting {} variables from the project's env files", project_env.len());
+            cmd.envs(project_env);
+        }
+        if let Some(hook) = command_hook {
+            cmd.envs(hook.env.iter().map(|(k, v)| (k, v)));
+        }
+        Ok(cmd)
+    }
+
+    /// Buffer and parse an agent process's stderr into its session
+    fn read_stderr(
+        sessions: Arc<RwLock<HashMap<String, RunningSession>>>,
+        session_id: String,
+        stderr: ChildStderr,
+        span: tracing::Span,
+    ) {
+        tokio::spawn(async move {
+            let reader = BufReader::new(stderr);
+            let mut lines = reader.lines();
+
+            while let Ok(Some(line)) = lines.next_line().await {
+                log::info!("STDERR: {}", line);
+
+                // Add to output buffer (prefixed so we know it's stderr) and parse events
+                let mut sessions = sessions.write().await;
+                if let Some(running_session) = sessions.get_mut(&session_id) {
+                    let line = running_session.redactor.redact(&line).into_owned();
+                    let stderr_line = format!("[stderr] {}", line);
+
+                    // Parse the line into events (stderr often contains errors)
+                    let events = running_session.parser.parse_line(&line);
+
+                    // Notify live subscribers, then store parsed events
+                    for event in &events {
+                        let _ = running_session.event_tx.send(event.clone());
+                    }
+                    running_session.parsed_events.extend(events);
+
+                    // Also keep raw output for compatibility
+                    running_session.output_buffer.push(stderr_line);
+                    running_session.session.last_activity = chrono::Utc::now().timestamp();
+                }
+            }
+        }.instrument(span));
+    }
+
     /// Kill the active child process for a session (if any)
     async fn kill_active_process(&self, session_id: &str) {
         let sessions = self.sessions.read().await;
@@ -408,6 +811,63 @@ impl AgentManager {
         }
     }
 
+    /// Use the project's redaction settings for a session's output
+    pub async fn set_redactor(&self, session_id: &str, redactor: Redactor) -> Result<()> {
+        let mut sessions = self.sessions.write().await;
+        let running_session = sessions
+            .get_mut(session_id)
+            .context("Session not found")?;
+
+        running_session.redactor = redactor;
+        Ok(())
+    }
+
+    /// Vet the session's shell commands with a hook; only Claude supports hooks
+    pub async fn set_command_hook(&self, session_id: &str, hook: Option<CommandHook>) -> Result<()> {
+        let mut sessions = self.sessions.write().await;
+        let running_session = sessions
+            .get_mut(session_id)
+            .context("Session not found")?;
+
+        running_session.command_hook = hook;
+        Ok(())
+    }
+
+    /// Fork a Claude session on the session's first message instead of
+    /// resuming it, so the session gets a CLI session ID of its own
+    pub async fn fork_session_from(&self, session_id: &str, cli_session_id: String) -> Result<()> {
+        let mut sessions = self.sessions.write().await;
+        let running_session = sessions
+            .get_mut(session_id)
+            .context("Session not found")?;
+
+        running_session.fork_from = Some(cli_session_id);
+        Ok(())
+    }
+
+    /// Restrict the processes the session spawns from its next message on
+    pub async fn set_execution_profile(&self, session_id: &str, profile: Option<ExecutionProfile>) -> Result<()> {
+        let mut sessions = self.sessions.write().await;
+        let running_session = sessions
+            .get_mut(session_id)
+            .context("Session not found")?;
+
+        running_session.execution_profile = profile;
+        Ok(())
+    }
+
+    /// Subscribe to events parsed from a session's output as they arrive
+    ///
+    /// Unlike `read_events`, this does not drain the session's event buffer.
+    pub async fn subscribe_events(&self, session_id: &str) -> Result<broadcast::Receiver<AgentEvent>> {
+        let sessions = self.sessions.read().await;
+        let running_session = sessions
+            .get(session_id)
+            .context("Session not found")?;
+
+        Ok(running_session.event_tx.subscribe())
+    }
+
     /// Read output from an agent session (non-blocking)
     pub async fn read_output(&self, session_id: &str) -> Result<Vec<String>> {
         let mut sessions = self.sessions.write().await;
@@ -543,6 +1003,37 @@ impl AgentManager {
         None
     }
 
+    /// Claude's user-configurable flags, shared by both conversation modes
+    fn claude_flag_args(flag_settings: Option<&HashMap<String, String>>) -> Vec<String> {
+        let get_flag = |flag_id: &str, default: &str| -> String {
+            flag_settings
+                .and_then(|settings| settings.get(flag_id))
+                .filter(|v| !v.is_empty())
+                .cloned()
+                .unwrap_or_else(|| default.to_string())
+        };
+        let mut args = Vec::new();
+
+        // Permission mode (configurable)
+        args.push("--permission-mode".to_string());
+        args.push(get_flag("permission_mode", "bypassPermissions"));
+
+        // Model (configurable, only add if not empty/default)
+        let model = get_flag("model", "");
+        if !model.is_empty() {
+            args.push("--model".to_string());
+            args.push(model);
+        }
+
+        // Max turns (configurable, only add if not 0)
+        let max_turns = get_flag("max_turns", "0");
+        if max_turns != "0" {
+            args.push("--max-turns".to_string());
+            args.push(max_turns);
+        }
+        args
+    }
+
     /// Get the appropriate headless command for an agent type
     /// Get the headless command for an agent type.
     /// Returns (program, args, use_stdin) - if use_stdin is true, message should be piped via stdin
@@ -596,24 +1087,7 @@ impl AgentManager {
                     args.push("--output-format".to_string());
                     args.push(output_format);
 
-                    // Permission mode (configurable)
-                    let permission_mode = get_flag("permission_mode", "bypassPermissions");
-                    args.push("--permission-mode".to_string());
-                    args.push(permission_mode);
-
-                    // Model (configurable, only add if not empty/default)
-                    let model = get_flag("model", "");
-                    if !model.is_empty() {
-                        args.push("--model".to_string());
-                        args.push(model);
-                    }
-
-                    // Max turns (configurable, only add if not 0)
-                    let max_turns = get_flag("max_turns", "0");
-                    if max_turns != "0" {
-                        args.push("--max-turns".to_string());
-                        args.push(max_turns);
-                    }
+                    args.extend(Self::claude_flag_args(flag_settings));
 
                     Ok(("claude".to_string(), args, use_stdin))
                 } else {
@@ -718,6 +1192,29 @@ mod tests {
         assert_eq!(session.status, AgentStatus::Running);
     }
 
+    #[test]
+    fn test_persistent_process_flag() {
+        let persistent: HashMap<String, String> =
+            [("conversation_mode".to_string(), "persistent".to_string())].into_iter().collect();
+        assert!(AgentManager::uses_persistent_process("claude-code", Some(&persistent)));
+        assert!(!AgentManager::uses_persistent_process("claude", None));
+        assert!(!AgentManager::uses_persistent_process("aider", Some(&persistent)));
+    }
+
+    #[test]
+    fn test_claude_flag_args() {
+        let flags: HashMap<String, String> = [
+            ("model".to_string(), "opus".to_string()),
+            ("max_turns".to_string(), "0".to_string()),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(
+            AgentManager::claude_flag_args(Some(&flags)),
+            ["--permission-mode", "bypassPermissions", "--model", "opus"]
+        );
+    }
+
     #[test]
     fn test_extract_claude_session_id() {
         // Test various session ID patterns
@@ -7,6 +7,7 @@ use std::path::PathBuf;
 use std::process::Stdio;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, AsyncReadExt, BufReader};
 use tokio::process::Command;
+use tokio::sync::mpsc;
 use which::which;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -42,6 +43,20 @@ pub trait AIPlugin: Send + Sync {
     /// Send a chat completion request
     async fn chat_completion(&self, messages: Vec<ChatMessage>) -> Result<String, String>;
 
+    /// Send a chat completion request, forwarding the response in chunks as it
+    /// is produced. Returns the full response once complete.
+    ///
+    /// Providers that can't stream send the whole response as a single chunk.
+    async fn chat_completion_stream(
+        &self,
+        messages: Vec<ChatMessage>,
+        chunks: mpsc::UnboundedSender<String>,
+    ) -> Result<String, String> {
+        let response = self.chat_completion(messages).await?;
+        let _ = chunks.send(response.clone());
+        Ok(response)
+    }
+
     /// Get the provider name
     fn name(&self) -> &str;
 }
@@ -80,6 +95,15 @@ impl AIService {
         self.provider.chat_completion(messages).await
     }
 
+    /// Send a chat completion request, streaming chunks to `chunks`
+    pub async fn chat_completion_stream(
+        &self,
+        messages: Vec<ChatMessage>,
+        chunks: mpsc::UnboundedSender<String>,
+    ) -> Result<String, String> {
+        self.provider.chat_completion_stream(messages, chunks).await
+    }
+
     /// Get the current provider name
     pub fn provider_name(&self) -> &str {
         self.provider.name()
@@ -147,6 +171,16 @@ impl AIPlugin for ClaudePlugin {
     }
 
     async fn chat_completion(&self, messages: Vec<ChatMessage>) -> Result<String, String> {
+        // Nobody is listening for chunks, so failed sends are ignored
+        let (chunks, _) = mpsc::unbounded_channel();
+        self.chat_completion_stream(messages, chunks).await
+    }
+
+    async fn chat_completion_stream(
+        &self,
+        messages: Vec<ChatMessage>,
+        chunks: mpsc::UnboundedSender<String>,
+    ) -> Result<String, String> {
         let claude_cmd = Self::find_claude_cli()?;
 
         // Get the last user message to send to Claude
@@ -169,7 +203,9 @@ impl AIPlugin for ClaudePlugin {
             .arg("--dangerously-skip-permissions")  // Skip permission prompts
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
-            .stderr(Stdio::piped());
+            .stderr(Stdio::piped())
+            // Kill the CLI if the request is cancelled mid-stream
+            .kill_on_drop(true);
 
         // Spawn the process
         let mut child = cmd
@@ -205,10 +241,9 @@ impl AIPlugin for ClaudePlugin {
             .await
             .map_err(|e| format!("Failed to read from claude stdout: {}", e))?
         {
-            if !response.is_empty() {
-                response.push('\n');
-            }
-            response.push_str(&line);
+            let chunk = if response.is_empty() { line } else { format!("\n{}", line) };
+            response.push_str(&chunk);
+            let _ = chunks.send(chunk);
         }
 
         // Wait for the process to complete with timeout
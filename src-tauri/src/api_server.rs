@@ -0,0 +1,428 @@
+// Local API Server
+// Opt-in, token-protected HTTP/WebSocket API for checking on projects and agents from another device
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Path, Query, Request, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Listener, Manager};
+use tokio::sync::{mpsc, watch};
+
+use crate::activity::ALL_ACTIVITY_EVENT;
+use crate::app_settings::AppSettingsManager;
+use crate::db::Database;
+
+/// App events forwarded to WebSocket clients
+const LIVE_EVENTS: &[&str] = &[
+    ALL_ACTIVITY_EVENT,
+    crate::session_events::ALL_SESSION_EVENT,
+    crate::chat_stream::CHUNK_EVENT,
+    crate::chat_stream::DONE_EVENT,
+    crate::cost::BUDGET_EXCEEDED_EVENT,
+];
+
+/// Most activity entries returned per request
+const MAX_ACTIVITY: i64 = 100;
+
+/// Settings for the local API, stored with the app settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ApiServerSettings {
+    pub enabled: bool,
+    pub port: u16,
+    /// Listen on every network interface instead of only this machine
+    pub lan_access: bool,
+    /// Required as `Authorization: Bearer <token>` or `?token=<token>`;
+    /// generated when the server is first enabled
+    pub token: String,
+}
+
+impl Default for ApiServerSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 7717,
+            lan_access: false,
+            token: String::new(),
+        }
+    }
+}
+
+/// Whether the server is running and where to reach it
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiServerStatus {
+    pub running: bool,
+    /// Base URL, using this machine's LAN address when LAN access is on
+    pub url: Option<String>,
+    /// Why the server couldn't start
+    pub error: Option<String>,
+}
+
+struct RunningServer {
+    settings: ApiServerSettings,
+    url: String,
+    /// Set to true to stop the server and close its WebSockets
+    shutdown: watch::Sender<bool>,
+}
+
+#[derive(Default)]
+struct ServerState {
+    running: Option<RunningServer>,
+    error: Option<String>,
+}
+
+impl ServerState {
+    fn status(&self) -> ApiServerStatus {
+        ApiServerStatus {
+            running: self.running.is_some(),
+            url: self.running.as_ref().map(|r| r.url.clone()),
+            error: self.error.clone(),
+        }
+    }
+}
+
+/// The running server, if enabled
+#[derive(Default)]
+pub struct ApiServer(Mutex<ServerState>);
+
+#[derive(Clone)]
+struct ApiState {
+    app: AppHandle,
+    token: Arc<str>,
+    shutdown: watch::Receiver<bool>,
+}
+
+fn new_token() -> String {
+    uuid::Uuid::new_v4().simple().to_string()
+}
+
+/// Start, stop or restart the server to match the saved settings
+pub fn apply(app: &AppHandle) -> ApiServerStatus {
+    let settings_manager = app.state::<AppSettingsManager>();
+    let mut settings = settings_manager.get().api_server;
+    if settings.enabled && settings.token.is_empty() {
+        settings.token = new_token();
+        let token = settings.token.clone();
+        if let Err(e) = settings_manager.update(|s| s.api_server.token = token) {
+            log::error!("Failed to save API token: {}", e);
+        }
+    }
+
+    let server = app.state::<ApiServer>();
+    let mut state = server.0.lock().unwrap();
+    let unchanged = match &state.running {
+        Some(running) => running.settings == settings,
+        None => !settings.enabled,
+    };
+    if unchanged {
+        return state.status();
+    }
+
+    if let Some(running) = state.running.take() {
+        let _ = running.shutdown.send(true);
+        log::info!("Stopped local API at {}", running.url);
+    }
+    state.error = None;
+    if settings.enabled {
+        match start(app.clone(), settings) {
+            Ok(running) => {
+                log::info!("Local API listening at {}", running.url);
+                state.running = Some(running);
+            }
+            Err(e) => {
+                log::error!("{}", e);
+                state.error = Some(e);
+            }
+        }
+    }
+    state.status()
+}
+
+fn start(app: AppHandle, settings: ApiServerSettings) -> Result<RunningServer, String> {
+    let ip = if settings.lan_access {
+        IpAddr::V4(Ipv4Addr::UNSPECIFIED)
+    } else {
+        IpAddr::V4(Ipv4Addr::LOCALHOST)
+    };
+    // Bind here so a busy port is reported right away
+    let listener = std::net::TcpListener::bind(SocketAddr::new(ip, settings.port))
+        .and_then(|listener| listener.set_nonblocking(true).map(|_| listener))
+        .map_err(|e| format!("Failed to start local API on port {}: {}", settings.port, e))?;
+
+    let host = if settings.lan_access {
+        lan_address().unwrap_or(ip)
+    } else {
+        ip
+    };
+    let url = format!("http://{}", SocketAddr::new(host, settings.port));
+
+    let (shutdown, shutdown_rx) = watch::channel(false);
+    let router = router(ApiState {
+        app,
+        token: settings.token.as_str().into(),
+        shutdown: shutdown_rx.clone(),
+    });
+    tauri::async_runtime::spawn(async move {
+        let listener = match tokio::net::TcpListener::from_std(listener) {
+            Ok(listener) => listener,
+            Err(e) => {
+                log::error!("Failed to start local API: {}", e);
+                return;
+            }
+        };
+        let mut shutdown_rx = shutdown_rx;
+        let stopped = async move {
+            let _ = shutdown_rx.wait_for(|stop| *stop).await;
+        };
+        if let Err(e) = axum::serve(listener, router).with_graceful_shutdown(stopped).await {
+            log::error!("Local API stopped: {}", e);
+        }
+    });
+
+    Ok(RunningServer { settings, url, shutdown })
+}
+
+/// This machine's address on the local network
+///
+/// Connecting a UDP socket picks the outbound interface without sending anything.
+fn lan_address() -> Option<IpAddr> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).ok()?;
+    socket.connect((Ipv4Addr::new(192, 0, 2, 1), 9)).ok()?;
+    socket.local_addr().ok().map(|addr| addr.ip())
+}
+
+fn router(state: ApiState) -> Router {
+    Router::new()
+        .route("/api/status", get(status))
+        .route("/api/projects", get(projects))
+        .route("/api/projects/{id}", get(project))
+        .route("/api/projects/{id}/sessions", get(project_sessions))
+        .route("/api/projects/{id}/activity", get(project_activity))
+        .route("/api/sessions", get(running_sessions))
+        .route("/api/events", get(events))
+        .layer(middleware::from_fn_with_state(state.clone(), require_token))
+        .with_state(state)
+}
+
+// ============================================================================
+// Authentication
+// ============================================================================
+
+/// The token from the Authorization header, or from the query string for
+/// WebSocket clients that can't set headers
+fn request_token<'a>(headers: &'a HeaderMap, query: Option<&'a str>) -> Option<&'a str> {
+    let bearer = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    bearer.or_else(|| {
+        query?
+            .split('&')
+            .find_map(|pair| pair.strip_prefix("token="))
+    })
+}
+
+/// Compare without returning early, so timing doesn't reveal the token
+fn tokens_match(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+async fn require_token(State(state): State<ApiState>, request: Request, next: Next) -> Response {
+    match request_token(request.headers(), request.uri().query()) {
+        Some(token) if tokens_match(token, &state.token) => next.run(request).await,
+        _ => ApiError(StatusCode::UNAUTHORIZED, "Missing or invalid token".to_string()).into_response(),
+    }
+}
+
+// ============================================================================
+// Endpoints
+// ============================================================================
+
+struct ApiError(StatusCode, String);
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(serde_json::json!({ "error": self.1 }))).into_response()
+    }
+}
+
+impl From<String> for ApiError {
+    fn from(message: String) -> Self {
+        Self(StatusCode::INTERNAL_SERVER_ERROR, message)
+    }
+}
+
+impl From<crate::error::AppError> for ApiError {
+    fn from(error: crate::error::AppError) -> Self {
+        use crate::error::ErrorKind;
+        let status = match error.kind {
+            ErrorKind::NotFound => StatusCode::NOT_FOUND,
+            ErrorKind::InvalidInput => StatusCode::BAD_REQUEST,
+            ErrorKind::Conflict => StatusCode::CONFLICT,
+            ErrorKind::PermissionDenied => StatusCode::FORBIDDEN,
+            ErrorKind::DatabaseLocked => StatusCode::SERVICE_UNAVAILABLE,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        Self(status, error.message)
+    }
+}
+
+type ApiResult<T> = Result<Json<T>, ApiError>;
+
+async fn status(State(state): State<ApiState>) -> ApiResult<serde_json::Value> {
+    let running = state.app.state::<crate::agent_manager::AgentManager>().list_sessions().await;
+    Ok(Json(serde_json::json!({
+        "version": state.app.package_info().version.to_string(),
+        "running_sessions": running.iter().filter(|s| s.status == crate::agent_manager::AgentStatus::Running).count(),
+    })))
+}
+
+async fn projects(State(state): State<ApiState>) -> ApiResult<Vec<crate::models::Project>> {
+    Ok(Json(crate::commands::get_projects(state.app.state::<Database>()).await?))
+}
+
+async fn project(State(state): State<ApiState>, Path(id): Path<String>) -> ApiResult<crate::models::Project> {
+    crate::commands::get_project(state.app.state::<Database>(), id.clone())
+        .await?
+        .map(Json)
+        .ok_or_else(|| ApiError(StatusCode::NOT_FOUND, format!("Project not found: {}", id)))
+}
+
+async fn project_sessions(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+) -> ApiResult<Vec<crate::models::AgentSession>> {
+    Ok(Json(crate::commands::get_project_sessions(state.app.state::<Database>(), id).await?))
+}
+
+#[derive(Deserialize)]
+struct ActivityQuery {
+    limit: Option<i64>,
+    since: Option<i64>,
+}
+
+async fn project_activity(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    Query(query): Query<ActivityQuery>,
+) -> ApiResult<Vec<crate::models::ActivityLog>> {
+    let limit = query.limit.map(|limit| limit.clamp(1, MAX_ACTIVITY));
+    let activity = crate::commands::get_activities(
+        state.app.state::<Database>(),
+        id,
+        limit,
+        None,
+        query.since,
+        None,
+        None,
+        None,
+    )
+    .await?;
+    Ok(Json(activity))
+}
+
+async fn running_sessions(State(state): State<ApiState>) -> ApiResult<Vec<crate::agent_manager::AgentSession>> {
+    Ok(Json(state.app.state::<crate::agent_manager::AgentManager>().list_sessions().await))
+}
+
+/// Stream app events as `{ "event": name, "payload": ... }` messages
+async fn events(State(state): State<ApiState>, upgrade: WebSocketUpgrade) -> Response {
+    upgrade.on_upgrade(move |socket| forward_events(state, socket))
+}
+
+async fn forward_events(state: ApiState, mut socket: WebSocket) {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let listeners: Vec<_> = LIVE_EVENTS
+        .iter()
+        .map(|&name| {
+            let tx = tx.clone();
+            state.app.listen_any(name, move |event| {
+                let payload = serde_json::from_str(event.payload()).unwrap_or(serde_json::Value::Null);
+                let _ = tx.send(serde_json::json!({ "event": name, "payload": payload }).to_string());
+            })
+        })
+        .collect();
+
+    let mut shutdown = state.shutdown.clone();
+    loop {
+        tokio::select! {
+            Some(message) = rx.recv() => {
+                if socket.send(Message::Text(message.into())).await.is_err() {
+                    break;
+                }
+            }
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                // Read-only: anything the client sends is ignored
+                Some(Ok(_)) => {}
+            },
+            // Only ever changes to stop the server
+            _ = shutdown.changed() => break,
+        }
+    }
+
+    for id in listeners {
+        state.app.unlisten(id);
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Get whether the local API is running and its URL
+#[tauri::command]
+pub fn get_api_server_status(server: tauri::State<'_, ApiServer>) -> ApiServerStatus {
+    server.0.lock().unwrap().status()
+}
+
+/// Replace the local API token, disconnecting clients using the old one
+#[tauri::command]
+pub fn regenerate_api_token(
+    app: AppHandle,
+    settings_manager: tauri::State<'_, AppSettingsManager>,
+) -> Result<crate::app_settings::AppSettings, String> {
+    let settings = settings_manager
+        .update(|s| s.api_server.token = new_token())
+        .map_err(|e| e.to_string())?;
+    apply(&app);
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn test_request_token() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(request_token(&headers, None), None);
+        assert_eq!(request_token(&headers, Some("a=1&token=abc")), Some("abc"));
+
+        headers.insert(header::AUTHORIZATION, HeaderValue::from_static("Bearer xyz"));
+        assert_eq!(request_token(&headers, Some("token=abc")), Some("xyz"));
+
+        headers.insert(header::AUTHORIZATION, HeaderValue::from_static("Basic xyz"));
+        assert_eq!(request_token(&headers, None), None);
+    }
+
+    #[test]
+    fn test_tokens_match() {
+        assert!(tokens_match("abc123", "abc123"));
+        assert!(!tokens_match("abc124", "abc123"));
+        assert!(!tokens_match("abc", "abc123"));
+        assert!(!tokens_match("", "abc123"));
+    }
+}
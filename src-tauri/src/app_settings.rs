@@ -0,0 +1,114 @@
+// App Settings Storage
+// Handles saving and loading settings that apply to the whole app rather than one project
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+use crate::api_server::ApiServerSettings;
+use crate::ci::CiSettings;
+use crate::updater::UpdateChannel;
+
+/// App-wide settings
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct AppSettings {
+    /// Closing the window hides it to the tray, keeping watchers and sessions alive
+    pub background_mode: bool,
+    /// Release feed checked for updates
+    pub update_channel: UpdateChannel,
+    /// Local HTTP/WebSocket API for other devices
+    pub api_server: ApiServerSettings,
+    /// Tokens for fetching CI runs
+    pub ci: CiSettings,
+    /// Agent for new chat tabs of projects whose own agent isn't available
+    pub default_agent: Option<String>,
+    /// Another machine's database (e.g. synced by Syncthing or Dropbox) opened
+    /// read-only in place of this one
+    pub read_only_database: Option<String>,
+}
+
+/// App settings manager
+pub struct AppSettingsManager {
+    settings_path: PathBuf,
+    settings: RwLock<AppSettings>,
+}
+
+impl AppSettingsManager {
+    /// Create a new settings manager
+    pub fn new(app_data_dir: PathBuf) -> Result<Self> {
+        let settings_path = app_data_dir.join("app_settings.json");
+
+        // Load existing settings or create default
+        let settings = if settings_path.exists() {
+            let content = std::fs::read_to_string(&settings_path)
+                .context("Failed to read app settings file")?;
+            serde_json::from_str(&content)
+                .context("Failed to parse app settings")?
+        } else {
+            AppSettings::default()
+        };
+
+        Ok(Self {
+            settings_path,
+            settings: RwLock::new(settings),
+        })
+    }
+
+    /// Save settings to disk
+    fn save(&self) -> Result<()> {
+        let settings = self.settings.read().unwrap();
+        let content = serde_json::to_string_pretty(&*settings)
+            .context("Failed to serialize app settings")?;
+        std::fs::write(&self.settings_path, content)
+            .context("Failed to write app settings file")?;
+        Ok(())
+    }
+
+    /// Get the current settings
+    pub fn get(&self) -> AppSettings {
+        self.settings.read().unwrap().clone()
+    }
+
+    /// Apply a change to the settings and save them
+    pub fn update(&self, change: impl FnOnce(&mut AppSettings)) -> Result<AppSettings> {
+        let updated = {
+            let mut settings = self.settings.write().unwrap();
+            change(&mut settings);
+            settings.clone()
+        };
+        self.save()?;
+        Ok(updated)
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+use tauri::State;
+
+/// Get the app-wide settings
+#[tauri::command]
+pub fn get_app_settings(
+    settings_manager: State<'_, AppSettingsManager>,
+) -> Result<AppSettings, String> {
+    Ok(settings_manager.get())
+}
+
+/// Replace the app-wide settings
+///
+/// Starts, stops or restarts the local API when its settings change.
+#[tauri::command]
+pub fn set_app_settings(
+    app: tauri::AppHandle,
+    settings_manager: State<'_, AppSettingsManager>,
+    settings: AppSettings,
+) -> Result<AppSettings, String> {
+    settings_manager.update(|current| *current = settings)
+        .map_err(|e| e.to_string())?;
+    crate::api_server::apply(&app);
+    // Enabling the API generates its token
+    Ok(settings_manager.get())
+}
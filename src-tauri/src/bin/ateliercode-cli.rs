@@ -0,0 +1,351 @@
+// AtelierCode CLI
+// Headless companion to the desktop app: lists projects and runs agent sessions
+// from a terminal or CI, using the app's database and plugin directory
+
+// Backend modules that don't depend on Tauri; some of their API is only used by the app
+#[allow(dead_code)]
+#[path = "../agent_manager.rs"]
+mod agent_manager;
+#[path = "../cli_command.rs"]
+mod cli_command;
+#[allow(dead_code)]
+#[path = "../container_env.rs"]
+mod container_env;
+#[allow(dead_code)]
+#[path = "../cost.rs"]
+mod cost;
+#[allow(dead_code)]
+#[path = "../env_files.rs"]
+mod env_files;
+#[allow(dead_code)]
+#[path = "../models.rs"]
+mod models;
+#[allow(dead_code)]
+#[path = "../output_parser.rs"]
+mod output_parser;
+// Plugin constructors return a trait object across `extern "C"`, as the app's loader expects
+#[allow(dead_code, improper_ctypes_definitions)]
+#[path = "../plugin.rs"]
+mod plugin;
+#[allow(dead_code, unused_imports, improper_ctypes_definitions)]
+#[path = "../plugins/mod.rs"]
+mod plugins;
+#[allow(dead_code)]
+#[path = "../redaction.rs"]
+mod redaction;
+#[path = "../sandbox.rs"]
+mod sandbox;
+
+use anyhow::{bail, Context, Result};
+use clap::{Parser, Subcommand};
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+use std::path::PathBuf;
+use tokio::sync::broadcast::error::RecvError;
+
+use agent_manager::AgentManager;
+use output_parser::AgentEvent;
+
+/// Must match `identifier` in tauri.conf.json, which names the app data directory
+const APP_IDENTIFIER: &str = "com.ateliercode.app";
+const DATABASE_FILE: &str = "ateliercode.db";
+
+#[derive(Parser)]
+#[command(name = "ateliercode-cli", version, about = "Run AtelierCode agents from the terminal")]
+struct Cli {
+    /// App data directory holding ateliercode.db (defaults to the desktop app's)
+    #[arg(long, global = true, env = "ATELIERCODE_DATA_DIR")]
+    data_dir: Option<PathBuf>,
+
+    /// Print JSON instead of text
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// List projects, most recently active first
+    Projects,
+    /// List the agent plugins installed in the plugin directory
+    Agents,
+    /// Start an agent session, send it a prompt and stream its events until it finishes
+    Run {
+        /// Project id or name
+        project: String,
+        /// Prompt to send to the agent
+        prompt: String,
+        /// Agent to run (defaults to the project's agent)
+        #[arg(long)]
+        agent: Option<String>,
+    },
+}
+
+#[derive(Debug, sqlx::FromRow, serde::Serialize)]
+struct ProjectRow {
+    id: String,
+    name: String,
+    root_path: String,
+    agent_type: String,
+    status: String,
+    #[serde(skip)]
+    settings: Option<String>,
+}
+
+#[tokio::main]
+async fn main() {
+    let cli = Cli::parse();
+
+    // Logs go to stderr so stdout stays parseable
+    tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("warn")),
+        )
+        .init();
+
+    match run(cli).await {
+        Ok(code) => std::process::exit(code),
+        Err(e) => {
+            eprintln!("error: {:#}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Run a command and return the process exit code
+async fn run(cli: Cli) -> Result<i32> {
+    match cli.command {
+        Command::Agents => {
+            list_agents(cli.json)?;
+            Ok(0)
+        }
+        Command::Projects => {
+            let pool = connect(cli.data_dir).await?;
+            list_projects(&pool, cli.json).await?;
+            Ok(0)
+        }
+        Command::Run { project, prompt, agent } => {
+            let pool = connect(cli.data_dir).await?;
+            run_agent(&pool, &project, prompt, agent, cli.json).await
+        }
+    }
+}
+
+/// Open the desktop app's database; the app creates and migrates it on first launch
+async fn connect(data_dir: Option<PathBuf>) -> Result<SqlitePool> {
+    let data_dir = match data_dir {
+        Some(dir) => dir,
+        None => dirs::data_dir()
+            .context("Failed to find the user data directory")?
+            .join(APP_IDENTIFIER),
+    };
+    let db_path = data_dir.join(DATABASE_FILE);
+    if !db_path.exists() {
+        bail!(
+            "No database at {}; start AtelierCode once to create it, or pass --data-dir",
+            db_path.display()
+        );
+    }
+
+    SqlitePoolOptions::new()
+        .max_connections(2)
+        .connect_with(SqliteConnectOptions::new().filename(&db_path).foreign_keys(true))
+        .await
+        .with_context(|| format!("Failed to open {}", db_path.display()))
+}
+
+async fn list_projects(pool: &SqlitePool, json: bool) -> Result<()> {
+    let projects = sqlx::query_as::<_, ProjectRow>(
+        "SELECT id, name, root_path, agent_type, status, settings FROM projects ORDER BY last_activity DESC",
+    )
+    .fetch_all(pool)
+    .await
+    .context("Failed to list projects")?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&projects)?);
+    } else if projects.is_empty() {
+        println!("No projects");
+    } else {
+        for p in &projects {
+            println!("{}  {}  [{}]  {}", p.id, p.name, p.agent_type, p.root_path);
+        }
+    }
+    Ok(())
+}
+
+fn list_agents(json: bool) -> Result<()> {
+    let dir = plugins::get_default_plugin_dir();
+    let manifests = plugins::discover_plugins(&dir)
+        .with_context(|| format!("Failed to read plugins from {}", dir.display()))?;
+
+    if json {
+        let agents: Vec<_> = manifests
+            .iter()
+            .map(|m| serde_json::json!({ "name": m.config.plugin.name, "display_name": m.config.plugin.display_name }))
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&agents)?);
+    } else if manifests.is_empty() {
+        println!("No agent plugins in {}", dir.display());
+    } else {
+        for m in &manifests {
+            println!("{}  {}", m.config.plugin.name, m.config.plugin.display_name);
+        }
+    }
+    Ok(())
+}
+
+async fn find_project(pool: &SqlitePool, id_or_name: &str) -> Result<ProjectRow> {
+    let mut matches = sqlx::query_as::<_, ProjectRow>(
+        "SELECT id, name, root_path, agent_type, status, settings FROM projects WHERE id = ? OR name = ? COLLATE NOCASE",
+    )
+    .bind(id_or_name)
+    .bind(id_or_name)
+    .fetch_all(pool)
+    .await
+    .context("Failed to look up project")?;
+
+    match matches.len() {
+        0 => bail!("No project with id or name {:?}", id_or_name),
+        1 => Ok(matches.remove(0)),
+        n => bail!("{} projects are named {:?}; use the project id", n, id_or_name),
+    }
+}
+
+/// Run one agent turn, recording the session like the desktop app does
+///
+/// Returns the agent's exit code. Ctrl-C stops the agent.
+async fn run_agent(
+    pool: &SqlitePool,
+    project: &str,
+    prompt: String,
+    agent: Option<String>,
+    json: bool,
+) -> Result<i32> {
+    let project = find_project(pool, project).await?;
+    let agent_type = agent.unwrap_or_else(|| project.agent_type.clone());
+
+    let settings = models::ProjectSettings::from_json(project.settings.as_deref());
+    let profile = settings.execution_profile(None).map_err(anyhow::Error::msg)?.cloned();
+
+    let manager = AgentManager::new();
+    let session = manager
+        .start_session(project.id.clone(), agent_type.clone(), project.root_path.clone(), None)
+        .await
+        .context("Failed to start agent session")?;
+    manager.set_execution_profile(&session.session_id, profile).await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO agent_sessions (id, project_id, task_id, agent_type, started_at, ended_at, status, exit_code, claude_session_id, pid)
+        VALUES (?, ?, NULL, ?, ?, NULL, 'running', NULL, ?, ?)
+        "#,
+    )
+    .bind(&session.session_id)
+    .bind(&session.project_id)
+    .bind(&session.agent_type)
+    .bind(session.started_at)
+    .bind(&session.claude_session_id)
+    .bind(std::process::id() as i64)
+    .execute(pool)
+    .await
+    .context("Failed to save agent session")?;
+
+    sqlx::query(
+        "INSERT INTO activity_log (id, project_id, session_id, event_type, description, data, timestamp) VALUES (?, ?, NULL, 'agent_start', ?, ?, ?)",
+    )
+    .bind(uuid::Uuid::new_v4().to_string())
+    .bind(&project.id)
+    .bind(format!("Started {} agent session from the CLI", agent_type))
+    .bind(serde_json::json!({ "session_id": session.session_id, "agent_type": agent_type }).to_string())
+    .bind(chrono::Utc::now().timestamp())
+    .execute(pool)
+    .await
+    .context("Failed to log activity")?;
+
+    if !json {
+        eprintln!("Started {} session {} in {}", agent_type, session.session_id, project.name);
+    }
+
+    let result = stream_turn(&manager, &session.session_id, prompt, json).await;
+
+    manager.stop_session(&session.session_id).await?;
+    sqlx::query("UPDATE agent_sessions SET ended_at = ?, status = 'stopped', exit_code = ? WHERE id = ?")
+        .bind(chrono::Utc::now().timestamp())
+        .bind(result.as_ref().map_or(1, |code| *code))
+        .bind(&session.session_id)
+        .execute(pool)
+        .await
+        .context("Failed to update agent session")?;
+
+    result
+}
+
+/// Send the prompt and print the session's events until the agent exits
+async fn stream_turn(manager: &AgentManager, session_id: &str, prompt: String, json: bool) -> Result<i32> {
+    let mut events = manager.subscribe_events(session_id).await?;
+    manager
+        .send_message(session_id, prompt, None)
+        .await
+        .context("Failed to send prompt")?;
+
+    let exit_code = loop {
+        tokio::select! {
+            event = events.recv() => match event {
+                Ok(event) => {
+                    print_event(&event, json)?;
+                    match event {
+                        AgentEvent::ProcessExited { exit_code, success, .. } => {
+                            break exit_code.unwrap_or(if success { 0 } else { 1 });
+                        }
+                        AgentEvent::TurnCompleted { success, .. } => break if success { 0 } else { 1 },
+                        _ => {}
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => eprintln!("({} events skipped)", skipped),
+                Err(RecvError::Closed) => break 1,
+            },
+            _ = tokio::signal::ctrl_c() => {
+                eprintln!("Stopping agent");
+                break 130;
+            }
+        }
+    };
+
+    Ok(exit_code)
+}
+
+/// Print an event as a JSON line, or as text for the interesting kinds
+fn print_event(event: &AgentEvent, json: bool) -> Result<()> {
+    if json {
+        println!("{}", serde_json::to_string(event)?);
+        return Ok(());
+    }
+
+    match event {
+        // Every output line arrives raw; parsed events are extra detail on stderr
+        AgentEvent::RawOutput { line, .. } => println!("{}", line),
+        AgentEvent::FileChanged { path, change_type, .. } => eprintln!("[file {:?}] {}", change_type, path),
+        AgentEvent::CommandExecuted { command, exit_code, .. } => eprintln!("[command] {} (exit {})", command, exit_code),
+        AgentEvent::TestRan { name, passed, .. } => {
+            eprintln!("[test {}] {}", if *passed { "passed" } else { "failed" }, name)
+        }
+        AgentEvent::Error { message, severity, .. } => eprintln!("[{:?}] {}", severity, message),
+        AgentEvent::Warning { message, .. } => eprintln!("[warning] {}", message),
+        AgentEvent::InputRequired { prompt, .. } => eprintln!("[input required] {}", prompt),
+        AgentEvent::ProcessExited { success, exit_code, .. } => {
+            eprintln!("[exited] {} (code {:?})", if *success { "success" } else { "failure" }, exit_code)
+        }
+        AgentEvent::TurnCompleted { success, .. } => {
+            eprintln!("[turn finished] {}", if *success { "success" } else { "failure" })
+        }
+        AgentEvent::MessageReceived { .. }
+        | AgentEvent::TaskCompleted { .. }
+        | AgentEvent::TaskCreated { .. }
+        | AgentEvent::Thinking { .. } => {}
+    }
+    Ok(())
+}
@@ -0,0 +1,176 @@
+// Chat Export
+// Renders a chat conversation as Markdown suitable for PRs and docs
+
+use crate::models::ChatMessage;
+use crate::session_replay::SessionBookmark;
+use crate::types::ChatExportOptions;
+
+/// Render messages (oldest first) as a Markdown document, with each of the
+/// session's bookmarks (in event order) after the last message sent before it
+pub fn render_markdown(
+    title: &str,
+    messages: &[ChatMessage],
+    bookmarks: &[SessionBookmark],
+    options: &ChatExportOptions,
+) -> String {
+    let mut out = format!("# {}\n", title);
+    let mut bookmarks = bookmarks.iter().peekable();
+
+    for message in messages {
+        while let Some(bookmark) = bookmarks.next_if(|b| b.recorded_at < message.timestamp * 1000) {
+            out.push_str(&render_bookmark(bookmark));
+        }
+        out.push_str(&format!("\n## {}", role_heading(&message.role)));
+        if options.include_timestamps {
+            if let Some(time) = chrono::DateTime::from_timestamp(message.timestamp, 0) {
+                out.push_str(&format!(" — {}", time.format("%Y-%m-%d %H:%M:%S UTC")));
+            }
+        }
+        out.push_str("\n\n");
+        out.push_str(render_content(&message.content, options).trim_end());
+        out.push('\n');
+    }
+    for bookmark in bookmarks {
+        out.push_str(&render_bookmark(bookmark));
+    }
+
+    out
+}
+
+fn render_bookmark(bookmark: &SessionBookmark) -> String {
+    match bookmark.note.as_str() {
+        "" => format!("\n> **Bookmark** (event {})\n", bookmark.event_seq),
+        note => format!("\n> **Bookmark** (event {}): {}\n", bookmark.event_seq, note.replace('\n', "\n> ")),
+    }
+}
+
+fn role_heading(role: &str) -> String {
+    match role {
+        "user" => "User".to_string(),
+        "assistant" => "Assistant".to_string(),
+        "system" => "System".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Render message content, expanding content blocks when the message is a JSON block array
+fn render_content(content: &str, options: &ChatExportOptions) -> String {
+    let blocks = match serde_json::from_str::<Vec<serde_json::Value>>(content) {
+        Ok(blocks) if blocks.iter().all(|b| b.get("type").is_some()) => blocks,
+        _ => return close_fences(content),
+    };
+
+    let mut out = String::new();
+    for block in &blocks {
+        let rendered = match block.get("type").and_then(|t| t.as_str()) {
+            Some("text") => close_fences(block.get("text").and_then(|t| t.as_str()).unwrap_or_default()),
+            Some("tool_use") => {
+                let name = block.get("name").and_then(|n| n.as_str()).unwrap_or("tool");
+                let input = block
+                    .get("input")
+                    .map(|i| serde_json::to_string_pretty(i).unwrap_or_default())
+                    .unwrap_or_default();
+                tool_section(&format!("Tool use: {}", name), &fenced("json", &input), options)
+            }
+            Some("tool_result") => {
+                let body = match block.get("content") {
+                    Some(serde_json::Value::String(s)) => s.clone(),
+                    Some(serde_json::Value::Array(parts)) => parts
+                        .iter()
+                        .filter_map(|p| p.get("text").and_then(|t| t.as_str()))
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                    _ => String::new(),
+                };
+                tool_section("Tool result", &fenced("", &body), options)
+            }
+            _ => continue,
+        };
+        out.push_str(rendered.trim_end());
+        out.push_str("\n\n");
+    }
+    out
+}
+
+/// Wrap a tool section in a collapsible block, or a plain bold heading
+fn tool_section(summary: &str, body: &str, options: &ChatExportOptions) -> String {
+    if options.collapse_tool_use {
+        format!("<details>\n<summary>{}</summary>\n\n{}\n\n</details>", summary, body)
+    } else {
+        format!("**{}**\n\n{}", summary, body)
+    }
+}
+
+/// Fence text as a code block, lengthening the fence if the text contains backticks
+pub(crate) fn fenced(language: &str, text: &str) -> String {
+    let mut fence = "```".to_string();
+    while text.contains(&fence) {
+        fence.push('`');
+    }
+    format!("{}{}\n{}\n{}", fence, language, text.trim_end(), fence)
+}
+
+/// Close a trailing unterminated code fence so it doesn't swallow the rest of the document
+fn close_fences(text: &str) -> String {
+    let open = text
+        .lines()
+        .filter(|line| line.trim_start().starts_with("```"))
+        .count();
+    if open % 2 == 1 {
+        format!("{}\n```", text.trim_end())
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(role: &str, content: &str) -> ChatMessage {
+        let mut message = ChatMessage::new("p1".to_string(), role.to_string(), content.to_string());
+        message.timestamp = 0;
+        message
+    }
+
+    #[test]
+    fn test_render_markdown_roles_and_timestamps() {
+        let messages = vec![message("user", "Hi"), message("assistant", "```rust\nfn main() {}")];
+        let markdown = render_markdown("Chat", &messages, &[], &ChatExportOptions::default());
+
+        assert!(markdown.starts_with("# Chat\n"));
+        assert!(markdown.contains("## User — 1970-01-01 00:00:00 UTC\n\nHi\n"));
+        assert!(markdown.contains("```rust\nfn main() {}\n```\n"));
+
+        let options = ChatExportOptions { include_timestamps: false, ..Default::default() };
+        assert!(render_markdown("Chat", &messages, &[], &options).contains("## User\n"));
+    }
+
+    #[test]
+    fn test_tool_use_collapsed() {
+        let content = r#"[{"type":"text","text":"Reading"},{"type":"tool_use","id":"t1","name":"Read","input":{"path":"a.rs"}}]"#;
+        let markdown = render_markdown("Chat", &[message("assistant", content)], &[], &ChatExportOptions::default());
+
+        assert!(markdown.contains("Reading\n\n<details>\n<summary>Tool use: Read</summary>"));
+        assert!(markdown.contains("```json\n{\n  \"path\": \"a.rs\"\n}\n```"));
+    }
+
+    #[test]
+    fn test_bookmarks_placed_by_time() {
+        let mut messages = vec![message("user", "Fix it"), message("assistant", "Done")];
+        messages[1].timestamp = 60;
+        let bookmark = |event_seq: i64, recorded_at: i64, note: &str| SessionBookmark {
+            id: event_seq.to_string(),
+            session_id: "s1".to_string(),
+            event_seq,
+            note: note.to_string(),
+            recorded_at,
+            created_at: 0,
+        };
+        let bookmarks = [bookmark(4, 30_000, "went wrong"), bookmark(9, 90_000, "")];
+        let markdown = render_markdown("Chat", &messages, &bookmarks, &ChatExportOptions::default());
+
+        assert!(markdown.contains("Fix it\n\n> **Bookmark** (event 4): went wrong\n\n## Assistant"));
+        assert!(markdown.ends_with("Done\n\n> **Bookmark** (event 9)\n"));
+    }
+}
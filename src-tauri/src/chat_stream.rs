@@ -0,0 +1,60 @@
+// Chat Stream Manager
+// Tracks in-flight streamed AI replies so they can be cancelled from the UI
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::oneshot;
+
+/// Event emitted when a reply starts streaming, before its first chunk, so
+/// the UI can cancel it right away
+pub const START_EVENT: &str = "chat-stream-start";
+/// Event emitted for each chunk of a streamed reply
+pub const CHUNK_EVENT: &str = "chat-stream-chunk";
+/// Event emitted once a streamed reply has finished, failed or been cancelled
+pub const DONE_EVENT: &str = "chat-stream-done";
+
+/// Manages cancellation handles for streamed replies, keyed by the id of the
+/// assistant message being produced
+pub struct ChatStreamManager {
+    streams: Arc<Mutex<HashMap<String, oneshot::Sender<()>>>>,
+}
+
+impl ChatStreamManager {
+    /// Create a new ChatStreamManager
+    pub fn new() -> Self {
+        Self {
+            streams: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Register a stream and return the receiver that fires when it is cancelled
+    pub fn register(&self, message_id: &str) -> oneshot::Receiver<()> {
+        let (tx, rx) = oneshot::channel();
+        self.streams.lock().unwrap().insert(message_id.to_string(), tx);
+        rx
+    }
+
+    /// Cancel a stream; returns false if it isn't running
+    pub fn cancel(&self, message_id: &str) -> bool {
+        match self.streams.lock().unwrap().remove(message_id) {
+            Some(tx) => tx.send(()).is_ok(),
+            None => false,
+        }
+    }
+
+    /// Whether a reply is still streaming into this message
+    pub fn is_streaming(&self, message_id: &str) -> bool {
+        self.streams.lock().unwrap().contains_key(message_id)
+    }
+
+    /// Forget a stream once it has finished
+    pub fn finish(&self, message_id: &str) {
+        self.streams.lock().unwrap().remove(message_id);
+    }
+}
+
+impl Default for ChatStreamManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
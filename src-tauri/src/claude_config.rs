@@ -0,0 +1,514 @@
+// Claude Config
+// Lists and edits a project's Claude Code subagents (.claude/agents/*.md) and
+// hooks (.claude/settings.json), with templates for common reviewer and test setups
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::path::{Path, PathBuf};
+
+use crate::error::AppError;
+
+const AGENTS_DIR: &str = ".claude/agents";
+const SETTINGS_FILE: &str = ".claude/settings.json";
+/// Personal settings, usually git-ignored
+const LOCAL_SETTINGS_FILE: &str = ".claude/settings.local.json";
+
+/// Events Claude Code runs hooks on
+pub const HOOK_EVENTS: &[&str] = &[
+    "PreToolUse",
+    "PostToolUse",
+    "Notification",
+    "UserPromptSubmit",
+    "Stop",
+    "SubagentStop",
+    "PreCompact",
+    "SessionStart",
+    "SessionEnd",
+];
+
+/// A subagent definition: frontmatter plus the system prompt as the body
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Subagent {
+    /// Lowercase letters, digits and hyphens; also the file name
+    pub name: String,
+    /// When Claude should hand work to this agent
+    pub description: String,
+    /// Tools the agent may use; empty inherits all of them
+    #[serde(default)]
+    pub tools: Vec<String>,
+    /// e.g. "sonnet", "opus" or "inherit"; unset uses the default subagent model
+    #[serde(default)]
+    pub model: Option<String>,
+    pub prompt: String,
+}
+
+/// A hook command; Claude Code groups them by event and matcher
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClaudeHook {
+    pub event: String,
+    /// Tool name pattern, e.g. "Edit|Write"; only tool events use it
+    #[serde(default)]
+    pub matcher: Option<String>,
+    pub command: String,
+    /// Seconds
+    #[serde(default)]
+    pub timeout: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SubagentTemplate {
+    pub id: &'static str,
+    pub label: &'static str,
+    pub agent: Subagent,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HookTemplate {
+    pub id: &'static str,
+    pub label: &'static str,
+    pub hook: ClaudeHook,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ClaudeConfigTemplates {
+    pub subagents: Vec<SubagentTemplate>,
+    pub hooks: Vec<HookTemplate>,
+}
+
+/// Whether `name` can name a subagent
+pub fn is_valid_agent_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('-')
+        && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+fn agent_path(root: &Path, name: &str) -> PathBuf {
+    root.join(AGENTS_DIR).join(format!("{}.md", name))
+}
+
+/// Parse a subagent file; `None` when it has no frontmatter with a name
+pub fn parse_subagent(text: &str) -> Option<Subagent> {
+    let rest = text.strip_prefix("---")?.trim_start_matches('\r').strip_prefix('\n')?;
+    let end = rest.find("\n---")?;
+    let frontmatter = &rest[..end];
+    // Whatever follows the closing `---` on its line isn't part of the prompt
+    let body = rest[end + 4..].split_once('\n').map_or("", |(_, body)| body);
+
+    let mut agent = Subagent {
+        name: String::new(),
+        description: String::new(),
+        tools: Vec::new(),
+        model: None,
+        prompt: body.trim().to_string(),
+    };
+    for line in frontmatter.lines() {
+        let Some((key, value)) = line.split_once(':') else { continue };
+        let value = unquote(value.trim());
+        match key.trim() {
+            "name" => agent.name = value,
+            "description" => agent.description = value,
+            "tools" => {
+                agent.tools = value.split(',').map(str::trim).filter(|t| !t.is_empty()).map(String::from).collect()
+            }
+            "model" if !value.is_empty() => agent.model = Some(value),
+            _ => {}
+        }
+    }
+    (!agent.name.is_empty()).then_some(agent)
+}
+
+fn unquote(value: &str) -> String {
+    if let Some(inner) = value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')) {
+        return inner.replace("''", "'");
+    }
+    let Some(inner) = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) else {
+        return value.to_string();
+    };
+    let mut unescaped = String::new();
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unescaped.extend(chars.next()),
+            _ => unescaped.push(c),
+        }
+    }
+    unescaped
+}
+
+/// A subagent as written to its file
+pub fn render_subagent(agent: &Subagent) -> String {
+    let mut text = format!("---\nname: {}\ndescription: {}\n", agent.name, yaml_string(&agent.description));
+    if !agent.tools.is_empty() {
+        text.push_str(&format!("tools: {}\n", agent.tools.join(", ")));
+    }
+    if let Some(model) = agent.model.as_deref().filter(|m| !m.is_empty()) {
+        text.push_str(&format!("model: {}\n", model));
+    }
+    text.push_str("---\n\n");
+    text.push_str(agent.prompt.trim());
+    text.push('\n');
+    text
+}
+
+/// Quote a one-line YAML value when it would otherwise parse as something else
+fn yaml_string(value: &str) -> String {
+    let value = value.replace(['\r', '\n'], " ");
+    let plain = !value.is_empty()
+        && !value.starts_with(|c: char| "'\"[]{}>|*&!%@`#,?-".contains(c) || c.is_whitespace())
+        && !value.contains(": ")
+        && !value.contains(" #");
+    if plain {
+        value
+    } else {
+        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+}
+
+/// The project's subagents, sorted by name; unreadable files are skipped
+pub fn list_subagents(root: &Path) -> Vec<Subagent> {
+    let mut agents: Vec<Subagent> = std::fs::read_dir(root.join(AGENTS_DIR))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "md"))
+        .filter_map(|entry| std::fs::read_to_string(entry.path()).ok())
+        .filter_map(|text| parse_subagent(&text))
+        .collect();
+    agents.sort_by(|a, b| a.name.cmp(&b.name));
+    agents
+}
+
+/// Write a subagent; `previous_name` is the name of the agent being edited,
+/// whose file is removed when it was renamed
+pub fn save_subagent(root: &Path, agent: &Subagent, previous_name: Option<&str>) -> Result<(), AppError> {
+    if !is_valid_agent_name(&agent.name) {
+        return Err(AppError::invalid_input(format!(
+            "Invalid agent name '{}': use lowercase letters, digits and hyphens",
+            agent.name
+        )));
+    }
+    if agent.description.trim().is_empty() {
+        return Err(AppError::invalid_input("A subagent needs a description"));
+    }
+    let path = agent_path(root, &agent.name);
+    // New agents and renames mustn't replace another agent's file
+    let renamed = previous_name.filter(|previous| *previous != agent.name);
+    if previous_name != Some(agent.name.as_str()) && path.exists() {
+        return Err(AppError::conflict(format!("A subagent named '{}' already exists", agent.name)));
+    }
+
+    std::fs::create_dir_all(root.join(AGENTS_DIR)).map_err(AppError::context("Failed to create .claude/agents"))?;
+    std::fs::write(&path, render_subagent(agent)).map_err(AppError::context("Failed to write subagent"))?;
+    if let Some(previous) = renamed.filter(|previous| is_valid_agent_name(previous)) {
+        let _ = std::fs::remove_file(agent_path(root, previous));
+    }
+    Ok(())
+}
+
+fn settings_path(root: &Path, local: bool) -> PathBuf {
+    root.join(if local { LOCAL_SETTINGS_FILE } else { SETTINGS_FILE })
+}
+
+fn read_settings(path: &Path) -> Result<Map<String, Value>, AppError> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Map::new()),
+        Err(e) => return Err(AppError::from(e).wrap("Failed to read Claude settings")),
+    };
+    match serde_json::from_str(&text) {
+        Ok(Value::Object(settings)) => Ok(settings),
+        Ok(_) => Err(AppError::invalid_input(format!("{} is not a JSON object", path.display()))),
+        Err(e) => Err(AppError::from(e).wrap(format!("Failed to parse {}", path.display()))),
+    }
+}
+
+/// The hooks in a settings object, flattened to one entry per command
+pub fn parse_hooks(settings: &Map<String, Value>) -> Vec<ClaudeHook> {
+    let Some(Value::Object(events)) = settings.get("hooks") else {
+        return Vec::new();
+    };
+    let mut hooks = Vec::new();
+    for (event, groups) in events {
+        for group in groups.as_array().into_iter().flatten() {
+            let matcher = group.get("matcher").and_then(Value::as_str).filter(|m| !m.is_empty());
+            for hook in group.get("hooks").and_then(Value::as_array).into_iter().flatten() {
+                let Some(command) = hook.get("command").and_then(Value::as_str) else { continue };
+                hooks.push(ClaudeHook {
+                    event: event.clone(),
+                    matcher: matcher.map(String::from),
+                    command: command.to_string(),
+                    timeout: hook.get("timeout").and_then(Value::as_u64),
+                });
+            }
+        }
+    }
+    hooks
+}
+
+/// Hooks in Claude Code's settings layout, grouped by event then matcher in
+/// the order they first appear
+pub fn render_hooks(hooks: &[ClaudeHook]) -> Value {
+    let mut events = Map::new();
+    for hook in hooks {
+        let groups = events.entry(hook.event.clone()).or_insert_with(|| json!([]));
+        let groups = groups.as_array_mut().expect("hook groups are arrays");
+        let matcher = hook.matcher.clone().unwrap_or_default();
+        let index = match groups.iter().position(|g| g["matcher"] == matcher.as_str()) {
+            Some(index) => index,
+            None => {
+                groups.push(json!({ "matcher": matcher, "hooks": [] }));
+                groups.len() - 1
+            }
+        };
+        let mut entry = json!({ "type": "command", "command": hook.command });
+        if let Some(timeout) = hook.timeout {
+            entry["timeout"] = json!(timeout);
+        }
+        groups[index]["hooks"].as_array_mut().expect("hook lists are arrays").push(entry);
+    }
+    Value::Object(events)
+}
+
+pub fn list_hooks(root: &Path, local: bool) -> Result<Vec<ClaudeHook>, AppError> {
+    Ok(parse_hooks(&read_settings(&settings_path(root, local))?))
+}
+
+/// Replace the hooks in a settings file, keeping its other settings
+pub fn save_hooks(root: &Path, local: bool, hooks: &[ClaudeHook]) -> Result<(), AppError> {
+    for hook in hooks {
+        if !HOOK_EVENTS.contains(&hook.event.as_str()) {
+            return Err(AppError::invalid_input(format!("Unknown hook event: {}", hook.event)));
+        }
+        if hook.command.trim().is_empty() {
+            return Err(AppError::invalid_input(format!("A {} hook has no command", hook.event)));
+        }
+    }
+
+    let path = settings_path(root, local);
+    let mut settings = read_settings(&path)?;
+    if hooks.is_empty() {
+        settings.remove("hooks");
+    } else {
+        settings.insert("hooks".to_string(), render_hooks(hooks));
+    }
+    std::fs::create_dir_all(root.join(".claude")).map_err(AppError::context("Failed to create .claude"))?;
+    let text = serde_json::to_string_pretty(&settings)?;
+    std::fs::write(&path, text + "\n").map_err(AppError::context("Failed to write Claude settings"))?;
+    Ok(())
+}
+
+pub fn templates() -> ClaudeConfigTemplates {
+    let agent = |name: &str, description: &str, tools: &[&str], prompt: &str| Subagent {
+        name: name.to_string(),
+        description: description.to_string(),
+        tools: tools.iter().map(|t| t.to_string()).collect(),
+        model: None,
+        prompt: prompt.to_string(),
+    };
+    let hook = |event: &str, matcher: Option<&str>, command: &str| ClaudeHook {
+        event: event.to_string(),
+        matcher: matcher.map(String::from),
+        command: command.to_string(),
+        timeout: None,
+    };
+
+    ClaudeConfigTemplates {
+        subagents: vec![
+            SubagentTemplate {
+                id: "code-reviewer",
+                label: "Code reviewer",
+                agent: agent(
+                    "code-reviewer",
+                    "Reviews changes for bugs, security issues and readability. Use right after writing or modifying code.",
+                    &["Read", "Grep", "Glob", "Bash"],
+                    "You are a senior engineer reviewing a change.\n\n\
+                     Run `git diff` to see what changed, then read the surrounding code. Report, most severe first:\n\
+                     - Bugs and unhandled edge cases\n\
+                     - Security problems such as injection or leaked secrets\n\
+                     - Code that is hard to follow or duplicates existing helpers\n\n\
+                     Quote the file and line for each finding and suggest a fix. Don't edit files.",
+                ),
+            },
+            SubagentTemplate {
+                id: "test-writer",
+                label: "Test writer",
+                agent: agent(
+                    "test-writer",
+                    "Writes and runs tests for new or changed code. Use when a feature or fix lacks tests.",
+                    &["Read", "Grep", "Glob", "Edit", "Write", "Bash"],
+                    "You write tests that match the project's existing test style.\n\n\
+                     Find how and where the project keeps its tests, cover the changed behaviour including edge \
+                     cases and failure paths, then run the tests and fix the tests (not the code) until they pass. \
+                     Report anything that looks like a real bug instead of papering over it.",
+                ),
+            },
+            SubagentTemplate {
+                id: "debugger",
+                label: "Debugger",
+                agent: agent(
+                    "debugger",
+                    "Finds the root cause of errors, failing tests and unexpected behaviour.",
+                    &["Read", "Grep", "Glob", "Edit", "Bash"],
+                    "You debug by evidence, not guesswork.\n\n\
+                     Reproduce the failure, read the error and stack trace, form a hypothesis and check it with logs \
+                     or a minimal test. Fix the root cause with the smallest change and explain what was wrong.",
+                ),
+            },
+        ],
+        hooks: vec![
+            HookTemplate {
+                id: "format-on-edit",
+                label: "Format files after edits (Prettier)",
+                hook: hook(
+                    "PostToolUse",
+                    Some("Edit|MultiEdit|Write"),
+                    "jq -r '.tool_input.file_path // empty' | xargs -r npx --no-install prettier --write --ignore-unknown",
+                ),
+            },
+            HookTemplate {
+                id: "protect-env",
+                label: "Block edits to .env files",
+                hook: hook(
+                    "PreToolUse",
+                    Some("Edit|MultiEdit|Write"),
+                    "jq -e '.tool_input.file_path | test(\"(^|/)\\\\.env\")' >/dev/null && { echo 'Editing .env files is blocked' >&2; exit 2; } || exit 0",
+                ),
+            },
+            HookTemplate {
+                id: "test-on-stop",
+                label: "Run tests when the agent finishes",
+                hook: hook("Stop", None, "npm test --silent"),
+            },
+        ],
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+use crate::db::Database;
+use tauri::State;
+
+async fn project_root(db: &Database, project_id: &str) -> Result<PathBuf, AppError> {
+    let root_path: String = sqlx::query_scalar("SELECT root_path FROM projects WHERE id = ?")
+        .bind(project_id)
+        .fetch_optional(db.pool())
+        .await
+        .map_err(AppError::context("Failed to load project"))?
+        .ok_or_else(|| AppError::not_found(format!("Project not found: {}", project_id)))?;
+    Ok(PathBuf::from(root_path))
+}
+
+/// The project's subagents from .claude/agents
+#[tauri::command]
+pub async fn list_claude_subagents(db: State<'_, Database>, project_id: String) -> Result<Vec<Subagent>, AppError> {
+    Ok(list_subagents(&project_root(&db, &project_id).await?))
+}
+
+/// Create or update a subagent; pass `previous_name` when renaming one
+#[tauri::command]
+pub async fn save_claude_subagent(
+    db: State<'_, Database>,
+    project_id: String,
+    agent: Subagent,
+    previous_name: Option<String>,
+) -> Result<Subagent, AppError> {
+    let root = project_root(&db, &project_id).await?;
+    save_subagent(&root, &agent, previous_name.as_deref())?;
+    Ok(agent)
+}
+
+#[tauri::command]
+pub async fn delete_claude_subagent(db: State<'_, Database>, project_id: String, name: String) -> Result<(), AppError> {
+    if !is_valid_agent_name(&name) {
+        return Err(AppError::invalid_input(format!("Invalid agent name: {}", name)));
+    }
+    let path = agent_path(&project_root(&db, &project_id).await?, &name);
+    match std::fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            Err(AppError::not_found(format!("Subagent not found: {}", name)))
+        }
+        Err(e) => Err(AppError::from(e).wrap("Failed to delete subagent")),
+    }
+}
+
+/// The project's hooks; `local` reads .claude/settings.local.json instead of
+/// the shared settings
+#[tauri::command]
+pub async fn list_claude_hooks(
+    db: State<'_, Database>,
+    project_id: String,
+    local: Option<bool>,
+) -> Result<Vec<ClaudeHook>, AppError> {
+    list_hooks(&project_root(&db, &project_id).await?, local.unwrap_or(false))
+}
+
+/// Replace the project's hooks with `hooks`
+#[tauri::command]
+pub async fn save_claude_hooks(
+    db: State<'_, Database>,
+    project_id: String,
+    hooks: Vec<ClaudeHook>,
+    local: Option<bool>,
+) -> Result<Vec<ClaudeHook>, AppError> {
+    let root = project_root(&db, &project_id).await?;
+    let local = local.unwrap_or(false);
+    save_hooks(&root, local, &hooks)?;
+    list_hooks(&root, local)
+}
+
+#[tauri::command]
+pub fn get_claude_config_templates() -> ClaudeConfigTemplates {
+    templates()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subagent_round_trip() {
+        let agent = Subagent {
+            name: "reviewer".to_string(),
+            description: "Reviews code: bugs \\ \"first\"".to_string(),
+            tools: vec!["Read".to_string(), "Grep".to_string()],
+            model: Some("sonnet".to_string()),
+            prompt: "Review the diff.\n\nBe brief.".to_string(),
+        };
+        let text = render_subagent(&agent);
+        assert!(text.contains("description: \"Reviews code:"));
+        assert_eq!(parse_subagent(&text), Some(agent));
+
+        let minimal = parse_subagent("---\nname: helper\ndescription: Helps\n---\nDo things\n").unwrap();
+        assert!(minimal.tools.is_empty());
+        assert_eq!((minimal.model, minimal.prompt.as_str()), (None, "Do things"));
+        assert!(parse_subagent("no frontmatter").is_none());
+        assert!(!is_valid_agent_name("../escape"));
+    }
+
+    #[test]
+    fn test_save_hooks_keeps_other_settings() {
+        let root = std::env::temp_dir().join(format!("ateliercode-claude-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(root.join(".claude")).unwrap();
+        std::fs::write(root.join(SETTINGS_FILE), r#"{"permissions":{"allow":["Bash(npm test)"]}}"#).unwrap();
+
+        let hooks = vec![
+            ClaudeHook { event: "PostToolUse".into(), matcher: Some("Edit".into()), command: "fmt".into(), timeout: None },
+            ClaudeHook { event: "PostToolUse".into(), matcher: Some("Edit".into()), command: "lint".into(), timeout: Some(30) },
+            ClaudeHook { event: "Stop".into(), matcher: None, command: "npm test".into(), timeout: None },
+        ];
+        save_hooks(&root, false, &hooks).unwrap();
+        assert_eq!(list_hooks(&root, false).unwrap(), hooks);
+
+        let settings = read_settings(&root.join(SETTINGS_FILE)).unwrap();
+        assert_eq!(settings["permissions"]["allow"][0], "Bash(npm test)");
+        assert_eq!(settings["hooks"]["PostToolUse"].as_array().unwrap().len(), 1);
+        assert!(list_hooks(&root, true).unwrap().is_empty());
+
+        let bad = ClaudeHook { event: "OnSave".into(), matcher: None, command: "x".into(), timeout: None };
+        assert!(save_hooks(&root, false, &[bad]).is_err());
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
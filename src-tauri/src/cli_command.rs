@@ -0,0 +1,185 @@
+// CLI Commands
+// Builds the Commands used to spawn agent CLIs and git. On Windows, npm .cmd
+// shims are run through node directly and no console window is opened
+
+use std::ffi::{OsStr, OsString};
+
+#[cfg(any(windows, test))]
+use regex::Regex;
+#[cfg(any(windows, test))]
+use std::sync::OnceLock;
+
+/// Keeps a console window from flashing up for every spawned process
+#[cfg(windows)]
+const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+/// Characters cmd.exe gives a meaning to, which must be escaped with ^
+#[cfg(any(windows, test))]
+const CMD_META: &[char] = &['(', ')', '[', ']', '%', '!', '^', '"', '`', '<', '>', '&', '|', ';', ',', ' ', '*', '?'];
+
+/// A tokio Command running `program` with `args`
+pub fn command<I, S>(program: impl AsRef<OsStr>, args: I) -> tokio::process::Command
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    std_command(program, args).into()
+}
+
+/// A std Command running `program` with `args`, for synchronous callers
+pub fn std_command<I, S>(program: impl AsRef<OsStr>, args: I) -> std::process::Command
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    let args: Vec<OsString> = args.into_iter().map(|a| a.as_ref().to_os_string()).collect();
+    build(program.as_ref(), args)
+}
+
+#[cfg(not(windows))]
+fn build(program: &OsStr, args: Vec<OsString>) -> std::process::Command {
+    let mut cmd = std::process::Command::new(program);
+    cmd.args(args);
+    cmd
+}
+
+/// Batch files can't be spawned directly. npm shims are swapped for the node
+/// script they run; anything else goes through cmd.exe with every argument
+/// escaped, since cmd would otherwise act on quotes, carets and % in prompts
+#[cfg(windows)]
+fn build(program: &OsStr, args: Vec<OsString>) -> std::process::Command {
+    use std::os::windows::process::CommandExt;
+    use std::process::Command;
+
+    let mut cmd = match which::which(program) {
+        Ok(path) if is_batch_file(&path) => match npm_shim_target(&path) {
+            Some((node, script)) => {
+                let mut cmd = Command::new(node);
+                cmd.arg(script).args(&args);
+                cmd
+            }
+            None => {
+                // cmd.exe ends the command at a newline, so multi-line arguments can't survive this path
+                let mut line = escape_cmd_meta(&path.to_string_lossy());
+                for arg in &args {
+                    line.push(' ');
+                    line.push_str(&escape_cmd_arg(&arg.to_string_lossy()));
+                }
+                let mut cmd = Command::new(std::env::var_os("COMSPEC").unwrap_or_else(|| "cmd.exe".into()));
+                cmd.raw_arg(format!("/D /S /C \"{}\"", line));
+                cmd
+            }
+        },
+        Ok(path) => {
+            let mut cmd = Command::new(path);
+            cmd.args(&args);
+            cmd
+        }
+        // Let spawn report the missing program
+        Err(_) => {
+            let mut cmd = Command::new(program);
+            cmd.args(&args);
+            cmd
+        }
+    };
+    cmd.creation_flags(CREATE_NO_WINDOW);
+    cmd
+}
+
+#[cfg(windows)]
+fn is_batch_file(path: &std::path::Path) -> bool {
+    path.extension()
+        .and_then(OsStr::to_str)
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("cmd") || ext.eq_ignore_ascii_case("bat"))
+}
+
+/// The node binary and script an npm .cmd shim runs, preferring a node.exe
+/// installed next to the shim as the shim itself does
+#[cfg(windows)]
+fn npm_shim_target(shim: &std::path::Path) -> Option<(std::path::PathBuf, std::path::PathBuf)> {
+    let dir = shim.parent()?;
+    let script = dir.join(shim_script(&std::fs::read_to_string(shim).ok()?)?);
+    if !script.is_file() {
+        return None;
+    }
+    let local_node = dir.join("node.exe");
+    let node = if local_node.is_file() { local_node } else { which::which("node").ok()? };
+    Some((node, script))
+}
+
+/// The script an npm .cmd shim passes to node, relative to the shim's directory
+///
+/// Shims end with a line like `"%_prog%"  "%dp0%\node_modules\pkg\cli.js" %*`;
+/// older ones write `%~dp0` instead of `%dp0%`.
+#[cfg(any(windows, test))]
+fn shim_script(contents: &str) -> Option<String> {
+    static SCRIPT: OnceLock<Regex> = OnceLock::new();
+    let script = SCRIPT.get_or_init(|| Regex::new(r#""%~?dp0%?\\([^"]+\.[cm]?js)"\s+%\*"#).expect("valid regex"));
+    script.captures_iter(contents).last().map(|captures| captures[1].to_string())
+}
+
+#[cfg(any(windows, test))]
+fn escape_cmd_meta(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if CMD_META.contains(&c) {
+            escaped.push('^');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Quote `arg` the way programs split their command line, then escape the
+/// result so cmd.exe passes it through untouched
+#[cfg(any(windows, test))]
+fn escape_cmd_arg(arg: &str) -> String {
+    let mut quoted = String::from("\"");
+    let mut backslashes = 0;
+    for c in arg.chars() {
+        if c == '\\' {
+            backslashes += 1;
+            continue;
+        }
+        // Backslashes are only special right before a quote
+        let count = if c == '"' { backslashes * 2 + 1 } else { backslashes };
+        quoted.extend(std::iter::repeat_n('\\', count));
+        quoted.push(c);
+        backslashes = 0;
+    }
+    quoted.extend(std::iter::repeat_n('\\', backslashes * 2));
+    quoted.push('"');
+    escape_cmd_meta(&quoted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shim_script() {
+        let current = "endLocal & goto #_undefined_# 2>NUL || title %COMSPEC% & \"%_prog%\"  \"%dp0%\\node_modules\\@anthropic-ai\\claude-code\\cli.js\" %*\r\n";
+        assert_eq!(shim_script(current).as_deref(), Some("node_modules\\@anthropic-ai\\claude-code\\cli.js"));
+        let legacy = "\"%~dp0\\node.exe\"  \"%~dp0\\node_modules\\aider\\bin\\aider.mjs\" %*";
+        assert_eq!(shim_script(legacy).as_deref(), Some("node_modules\\aider\\bin\\aider.mjs"));
+        assert_eq!(shim_script("@echo off\r\npython -m aider %*"), None);
+    }
+
+    #[test]
+    fn test_escape_cmd_arg() {
+        assert_eq!(escape_cmd_arg("plain"), "^\"plain^\"");
+        assert_eq!(escape_cmd_arg("100% & \"done\""), "^\"100^%^ ^&^ \\^\"done\\^\"^\"");
+        assert_eq!(escape_cmd_arg("C:\\dir\\"), "^\"C:\\dir\\\\^\"");
+        assert_eq!(escape_cmd_arg("a\\\"b"), "^\"a\\\\\\^\"b^\"");
+    }
+
+    #[test]
+    fn test_command_passes_args_verbatim() {
+        let cmd = std_command("git", ["log", "--format=%H \"quoted\""]);
+        let args: Vec<_> = cmd.get_args().collect();
+        #[cfg(not(windows))]
+        assert_eq!(args, ["log", "--format=%H \"quoted\""]);
+        #[cfg(windows)]
+        assert_eq!(args.len(), 2);
+    }
+}
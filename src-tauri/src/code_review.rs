@@ -0,0 +1,406 @@
+// Code Review
+// Turns review comments on a file change into prompts for the agent
+
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::collections::BTreeMap;
+
+use crate::ai_service::{AIService, ChatMessage};
+use crate::models::{FileChange, ReviewComment};
+
+/// Largest diff included in a prompt, in characters
+const MAX_PROMPT_DIFF_CHARS: usize = 20_000;
+
+/// Author recorded on comments left by the AI review pass
+pub const AI_REVIEW_AUTHOR: &str = "ai";
+
+/// Most comments kept from a single AI review
+const MAX_AI_FINDINGS: usize = 20;
+
+/// Build a prompt asking the agent to address unresolved review comments
+pub fn build_fix_prompt(change: &FileChange, comments: &[ReviewComment]) -> String {
+    let mut prompt = format!(
+        "Please address the following review comments on your change to `{}`.\n\n",
+        change.file_path
+    );
+
+    for (index, comment) in comments.iter().enumerate() {
+        let location = match comment.line_number {
+            Some(line) => format!("Line {}", line),
+            None => "General".to_string(),
+        };
+        prompt.push_str(&format!(
+            "{}. {} ({}): {}\n",
+            index + 1,
+            location,
+            comment.author,
+            comment.comment.trim()
+        ));
+    }
+
+    if let Some(diff) = change.diff.as_deref().filter(|d| !d.trim().is_empty()) {
+        prompt.push_str("\nThe change under review:\n\n```diff\n");
+        prompt.push_str(&truncate_diff(diff));
+        prompt.push_str("\n```\n");
+    }
+
+    prompt.push_str("\nUpdate the file to resolve each comment, then summarize what you changed.");
+    prompt
+}
+
+/// Build a prompt asking for a review of a change, answered as JSON findings
+pub fn build_review_prompt(change: &FileChange) -> String {
+    let diff = change.diff.as_deref().unwrap_or_default();
+    format!(
+        "Review this change to `{}` as a careful senior engineer. Look for bugs, \
+         missing error handling, security problems and unclear code; ignore style nits.\n\n\
+         ```diff\n{}\n```\n\n\
+         Reply with only a JSON array of findings, each {{\"line\": <line number in the new file, or null>, \
+         \"comment\": \"<what is wrong and how to fix it>\"}}. Reply with [] if the change looks good.",
+        change.file_path,
+        truncate_diff(diff)
+    )
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct ReviewFinding {
+    line: Option<i64>,
+    comment: String,
+}
+
+/// Pull the JSON findings out of a review reply, tolerating surrounding prose or fences
+fn parse_review_findings(reply: &str) -> Result<Vec<ReviewFinding>, String> {
+    let (start, end) = match (reply.find('['), reply.rfind(']')) {
+        (Some(start), Some(end)) if start < end => (start, end),
+        _ => return Err("AI review reply contained no findings list".to_string()),
+    };
+    let findings: Vec<ReviewFinding> = serde_json::from_str(&reply[start..=end])
+        .map_err(|e| format!("Failed to parse AI review findings: {}", e))?;
+
+    Ok(findings
+        .into_iter()
+        .filter(|f| !f.comment.trim().is_empty())
+        .take(MAX_AI_FINDINGS)
+        .collect())
+}
+
+/// Review a file change with the AI service and store the findings as review comments
+pub async fn ai_review(pool: &SqlitePool, change_id: &str) -> Result<Vec<ReviewComment>, String> {
+    let change = crate::commands::fetch_file_change(pool, change_id).await.map_err(String::from)?;
+
+    if change.diff.as_deref().is_none_or(|d| d.trim().is_empty()) {
+        return Err("File change has no diff to review".to_string());
+    }
+
+    let ai_service = AIService::from_env();
+    if !ai_service.is_available() {
+        return Err(format!("{} CLI not found", ai_service.provider_name()));
+    }
+
+    let reply = ai_service
+        .chat_completion(vec![ChatMessage {
+            role: "user".to_string(),
+            content: build_review_prompt(&change),
+        }])
+        .await?;
+
+    let mut comments = Vec::new();
+    for finding in parse_review_findings(&reply)? {
+        let comment = ReviewComment::new(
+            change.id.clone(),
+            AI_REVIEW_AUTHOR.to_string(),
+            finding.comment.trim().to_string(),
+            finding.line.filter(|line| *line > 0),
+        );
+
+        sqlx::query(
+            r#"
+            INSERT INTO review_comments (id, file_change_id, line_number, author, comment, timestamp, resolved)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#
+        )
+        .bind(&comment.id)
+        .bind(&comment.file_change_id)
+        .bind(comment.line_number)
+        .bind(&comment.author)
+        .bind(&comment.comment)
+        .bind(comment.timestamp)
+        .bind(comment.resolved)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to add review comment: {}", e))?;
+
+        comments.push(comment);
+    }
+
+    log::info!("AI review of {} left {} comments", change.file_path, comments.len());
+    Ok(comments)
+}
+
+/// Replace lines `start..=end` (1-based) of `content` with `replacement`
+///
+/// Keeps the file's line endings and trailing newline. An empty replacement
+/// deletes the lines.
+pub fn apply_line_replacement(content: &str, start: usize, end: usize, replacement: &str) -> Result<String, String> {
+    let newline = if content.contains("\r\n") { "\r\n" } else { "\n" };
+    let lines: Vec<&str> = content.lines().collect();
+    if start < 1 || end < start || end > lines.len() {
+        return Err(format!(
+            "Suggestion range {}-{} is outside the file ({} lines)",
+            start,
+            end,
+            lines.len()
+        ));
+    }
+
+    let mut patched: Vec<&str> = lines[..start - 1].to_vec();
+    if !replacement.is_empty() {
+        patched.extend(replacement.lines());
+    }
+    patched.extend(&lines[end..]);
+
+    let mut out = patched.join(newline);
+    if content.ends_with('\n') && !out.is_empty() {
+        out.push_str(newline);
+    }
+    Ok(out)
+}
+
+/// Per-file totals in a review report
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct ReportFile {
+    pub file_path: String,
+    pub changes: usize,
+    pub approved: usize,
+    pub rejected: usize,
+    pub pending: usize,
+    /// Reviewed changes whose file was edited again after the review
+    pub changed_after_review: usize,
+}
+
+/// An unresolved comment listed in a review report
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportComment {
+    pub file_path: String,
+    pub line_number: Option<i64>,
+    pub author: String,
+    pub comment: String,
+    pub timestamp: i64,
+}
+
+/// Summary of review activity in a project since a point in time
+#[derive(Debug, Clone, Serialize)]
+pub struct ReviewReport {
+    pub project_id: String,
+    pub since: i64,
+    pub generated_at: i64,
+    /// Changes recorded since `since`, reviewed or not
+    pub changes_since: usize,
+    pub approved: usize,
+    pub rejected: usize,
+    pub pending: usize,
+    /// Reviewed changes whose file was edited again after the review
+    pub changed_after_review: usize,
+    pub files: Vec<ReportFile>,
+    pub unresolved_comments: Vec<ReportComment>,
+    /// The same report rendered as Markdown
+    pub markdown: String,
+}
+
+/// Tally changes and comments into a report
+pub fn build_review_report(
+    project_id: &str,
+    project_name: &str,
+    since: i64,
+    changes: &[FileChange],
+    unresolved_comments: Vec<ReportComment>,
+) -> ReviewReport {
+    let mut files: BTreeMap<&str, ReportFile> = BTreeMap::new();
+    for change in changes {
+        let file = files.entry(&change.file_path).or_insert_with(|| ReportFile {
+            file_path: change.file_path.clone(),
+            ..Default::default()
+        });
+        file.changes += 1;
+        match (change.reviewed, change.approved) {
+            (true, Some(true)) => file.approved += 1,
+            (true, Some(false)) => file.rejected += 1,
+            _ => file.pending += 1,
+        }
+        if change.reviewed && change.stale {
+            file.changed_after_review += 1;
+        }
+    }
+    let files: Vec<ReportFile> = files.into_values().collect();
+
+    let mut report = ReviewReport {
+        project_id: project_id.to_string(),
+        since,
+        generated_at: chrono::Utc::now().timestamp(),
+        changes_since: changes.len(),
+        approved: files.iter().map(|f| f.approved).sum(),
+        rejected: files.iter().map(|f| f.rejected).sum(),
+        pending: files.iter().map(|f| f.pending).sum(),
+        changed_after_review: files.iter().map(|f| f.changed_after_review).sum(),
+        files,
+        unresolved_comments,
+        markdown: String::new(),
+    };
+    report.markdown = render_report_markdown(project_name, &report);
+    report
+}
+
+fn render_report_markdown(project_name: &str, report: &ReviewReport) -> String {
+    let date = |ts: i64| {
+        chrono::DateTime::from_timestamp(ts, 0)
+            .map(|d| d.format("%Y-%m-%d %H:%M UTC").to_string())
+            .unwrap_or_default()
+    };
+
+    let mut md = format!("# Review report: {}\n\n", project_name);
+    md.push_str(&format!("Changes from {} to {}\n\n", date(report.since), date(report.generated_at)));
+
+    md.push_str("## Summary\n\n");
+    md.push_str(&format!(
+        "- Changes recorded: {} across {} files\n",
+        report.changes_since,
+        report.files.len()
+    ));
+    md.push_str(&format!(
+        "- Approved: {}, rejected: {}, pending review: {}\n",
+        report.approved, report.rejected, report.pending
+    ));
+    md.push_str(&format!("- Edited again after review: {}\n", report.changed_after_review));
+    md.push_str(&format!("- Unresolved comments: {}\n", report.unresolved_comments.len()));
+
+    if !report.files.is_empty() {
+        md.push_str("\n## Files\n\n| File | Changes | Approved | Rejected | Pending | Edited after review |\n");
+        md.push_str("|---|---:|---:|---:|---:|---:|\n");
+        for file in &report.files {
+            md.push_str(&format!(
+                "| `{}` | {} | {} | {} | {} | {} |\n",
+                file.file_path, file.changes, file.approved, file.rejected, file.pending, file.changed_after_review
+            ));
+        }
+    }
+
+    if !report.unresolved_comments.is_empty() {
+        md.push_str("\n## Unresolved comments\n\n");
+        for comment in &report.unresolved_comments {
+            let location = match comment.line_number {
+                Some(line) => format!("{}:{}", comment.file_path, line),
+                None => comment.file_path.clone(),
+            };
+            md.push_str(&format!(
+                "- `{}` ({}): {}\n",
+                location,
+                comment.author,
+                comment.comment.trim()
+            ));
+        }
+    }
+
+    md
+}
+
+/// Cut a diff to the prompt budget at a line boundary
+fn truncate_diff(diff: &str) -> String {
+    let diff = diff.trim_end();
+    if diff.len() <= MAX_PROMPT_DIFF_CHARS {
+        return diff.to_string();
+    }
+    let mut end = MAX_PROMPT_DIFF_CHARS;
+    while !diff.is_char_boundary(end) {
+        end -= 1;
+    }
+    let cut = diff[..end].rfind('\n').unwrap_or(0);
+    format!("{}\n... (diff truncated)", &diff[..cut])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(diff: Option<&str>) -> FileChange {
+        let mut change = FileChange::new(
+            "p1".to_string(),
+            "s1".to_string(),
+            "src/lib.rs".to_string(),
+            "modified".to_string(),
+        );
+        change.id = "c1".to_string();
+        change.diff = diff.map(str::to_string);
+        change
+    }
+
+    #[test]
+    fn test_build_fix_prompt() {
+        let comments = vec![
+            ReviewComment::new("c1".to_string(), "user".to_string(), "Handle the error".to_string(), Some(12)),
+            ReviewComment::new("c1".to_string(), "ai".to_string(), " Add a test ".to_string(), None),
+        ];
+        let prompt = build_fix_prompt(&change(Some("+let x = y.unwrap();\n")), &comments);
+
+        assert!(prompt.contains("change to `src/lib.rs`"));
+        assert!(prompt.contains("1. Line 12 (user): Handle the error\n"));
+        assert!(prompt.contains("2. General (ai): Add a test\n"));
+        assert!(prompt.contains("```diff\n+let x = y.unwrap();\n```"));
+    }
+
+    #[test]
+    fn test_build_fix_prompt_without_diff() {
+        let comments = vec![ReviewComment::new("c1".to_string(), "user".to_string(), "Rename".to_string(), None)];
+        assert!(!build_fix_prompt(&change(None), &comments).contains("```diff"));
+    }
+
+    #[test]
+    fn test_build_review_report() {
+        let mut approved = change(None);
+        approved.reviewed = true;
+        approved.approved = Some(true);
+        approved.stale = true;
+        let mut stale_pending = change(None);
+        stale_pending.stale = true;
+        let mut other = change(None);
+        other.file_path = "src/main.rs".to_string();
+
+        let comments = vec![ReportComment {
+            file_path: "src/main.rs".to_string(),
+            line_number: Some(3),
+            author: "user".to_string(),
+            comment: "Missing docs".to_string(),
+            timestamp: 0,
+        }];
+        let report = build_review_report("p1", "Atelier", 0, &[approved, stale_pending, other], comments);
+
+        assert_eq!((report.changes_since, report.approved, report.rejected, report.pending), (3, 1, 0, 2));
+        // Only reviewed changes count as edited after review
+        assert_eq!(report.changed_after_review, 1);
+        assert_eq!(report.files.len(), 2);
+        assert_eq!(report.files[0].file_path, "src/lib.rs");
+        assert_eq!(report.files[0].changes, 2);
+        assert!(report.markdown.starts_with("# Review report: Atelier\n"));
+        assert!(report.markdown.contains("| `src/lib.rs` | 2 | 1 | 0 | 1 | 1 |"));
+        assert!(report.markdown.contains("- `src/main.rs:3` (user): Missing docs"));
+    }
+
+    #[test]
+    fn test_apply_line_replacement() {
+        let content = "a\nb\nc\n";
+        assert_eq!(apply_line_replacement(content, 2, 2, "B1\nB2").unwrap(), "a\nB1\nB2\nc\n");
+        assert_eq!(apply_line_replacement(content, 1, 2, "").unwrap(), "c\n");
+        assert_eq!(apply_line_replacement("a\r\nb", 2, 2, "x").unwrap(), "a\r\nx");
+        assert!(apply_line_replacement(content, 3, 4, "x").is_err());
+    }
+
+    #[test]
+    fn test_parse_review_findings() {
+        let reply = "Here is my review:\n```json\n[{\"line\": 4, \"comment\": \"unwrap can panic\"}, {\"line\": null, \"comment\": \" \"}]\n```";
+        assert_eq!(
+            parse_review_findings(reply).unwrap(),
+            vec![ReviewFinding { line: Some(4), comment: "unwrap can panic".to_string() }]
+        );
+        assert!(parse_review_findings("[]").unwrap().is_empty());
+        assert!(parse_review_findings("Looks good to me").is_err());
+    }
+}
@@ -0,0 +1,454 @@
+// Command Approval Gate
+// Holds agent shell commands that match a project's deny list until the user approves them.
+// Claude sessions get a PreToolUse hook that runs this executable with HOOK_ARG; the hook
+// asks the app over a loopback socket and blocks the Bash call unless it's allowed.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt};
+use tokio::sync::oneshot;
+
+use crate::agent_manager::CommandHook;
+use crate::db::Database;
+use crate::models::{CommandGateSettings, ProjectSettings};
+
+/// First argument that makes the app run as the hook instead of starting
+pub const HOOK_ARG: &str = "--command-gate-hook";
+
+/// Emitted with a [`PendingCommand`] when a command waits for approval
+pub const APPROVAL_REQUESTED_EVENT: &str = "command-approval-requested";
+/// Emitted with the request id once a command is approved, denied or times out
+pub const APPROVAL_RESOLVED_EVENT: &str = "command-approval-resolved";
+
+/// Unanswered requests are denied after this long
+const APPROVAL_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+const ADDR_ENV: &str = "ATELIERCODE_GATE_ADDR";
+const TOKEN_ENV: &str = "ATELIERCODE_GATE_TOKEN";
+const PROJECT_ENV: &str = "ATELIERCODE_PROJECT_ID";
+const SESSION_ENV: &str = "ATELIERCODE_SESSION_ID";
+
+/// Whether `pattern` matches `command`
+///
+/// `*` matches any text, and a pattern also matches longer commands that
+/// start with it, so `git push` covers `git push origin main`.
+pub fn pattern_matches(pattern: &str, command: &str) -> bool {
+    let pattern = normalize(pattern);
+    if pattern.is_empty() {
+        return false;
+    }
+    let glob = pattern.split('*').map(regex::escape).collect::<Vec<_>>().join(".*");
+    Regex::new(&format!(r"^{}(\s.*)?$", glob)).is_ok_and(|re| re.is_match(&normalize(command)))
+}
+
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Words that run the rest of the line as a command
+const WRAPPERS: &[&str] = &["env", "sudo", "command", "exec", "nohup", "time", "nice"];
+/// Shells whose `-c` argument is a command line of its own
+const SHELLS: &[&str] = &["sh", "bash", "zsh", "dash"];
+
+/// Whether a command line runs more than one command
+fn is_compound(command: &str) -> bool {
+    ["&&", "||", ";", "|", "$(", "`", "\n"].iter().any(|op| command.contains(op))
+}
+
+/// The parts of a shell command line run as separate commands
+fn split_commands(command: &str) -> Vec<&str> {
+    command
+        .split(['\n', ';', '|', '&', '(', ')', '`'])
+        .map(|part| part.trim().trim_matches(['\'', '"', '$']).trim())
+        .filter(|part| !part.is_empty())
+        .collect()
+}
+
+/// `part` followed by the commands it wraps, innermost last
+///
+/// `env FOO=1 git push` and `bash -c 'git push'` both end in `git push`.
+fn unwrap_command(part: &str) -> Vec<&str> {
+    let mut forms = Vec::new();
+    let mut current = part;
+    loop {
+        current = current.trim().trim_matches(['\'', '"']).trim();
+        if current.is_empty() {
+            break;
+        }
+        forms.push(current);
+        let word = current.split_whitespace().next().unwrap_or_default();
+        let program = word.rsplit('/').next().unwrap_or(word);
+        current = if WRAPPERS.contains(&program) || is_assignment(word) {
+            let mut next = rest(current);
+            while next.starts_with('-') || next.split_whitespace().next().is_some_and(is_assignment) {
+                next = rest(next);
+            }
+            next
+        } else if SHELLS.contains(&program) {
+            let next = rest(current);
+            match next.split_whitespace().next() {
+                Some(flag) if flag.starts_with('-') && flag.ends_with('c') => rest(next),
+                _ => break,
+            }
+        } else {
+            break;
+        };
+    }
+    forms
+}
+
+/// `text` without its first word
+fn rest(text: &str) -> &str {
+    text.split_once(char::is_whitespace).map_or("", |(_, rest)| rest).trim_start()
+}
+
+fn is_assignment(word: &str) -> bool {
+    word.split_once('=').is_some_and(|(name, _)| {
+        !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    })
+}
+
+/// The deny pattern that makes `command` need approval, if any
+///
+/// Each command on the line is checked on its own, with wrappers like `env`
+/// or `bash -c` stripped. An allow pattern only lets through the single
+/// command it matches, never a whole line of several.
+pub fn needs_approval(settings: &CommandGateSettings, command: &str) -> Option<String> {
+    let allowed = |part: &str| settings.allow.iter().any(|p| pattern_matches(p, part));
+    let denied_by = |part: &str| settings.deny.iter().find(|p| pattern_matches(p, part)).cloned();
+
+    if is_compound(command) {
+        if let Some(pattern) = denied_by(command) {
+            return Some(pattern);
+        }
+    }
+    split_commands(command).into_iter().find_map(|part| {
+        let forms = unwrap_command(part);
+        let innermost = *forms.last()?;
+        let pattern = forms.into_iter().find_map(denied_by)?;
+        (!allowed(innermost)).then_some(pattern)
+    })
+}
+
+/// A command waiting for the user
+#[derive(Debug, Clone, Serialize)]
+pub struct PendingCommand {
+    pub id: String,
+    pub project_id: String,
+    pub session_id: String,
+    pub command: String,
+    /// Deny pattern the command matched
+    pub pattern: String,
+    pub requested_at: i64,
+}
+
+/// What the hook sends for each Bash call
+#[derive(Debug, Serialize, Deserialize)]
+struct HookRequest {
+    token: String,
+    project_id: String,
+    session_id: String,
+    command: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct HookResponse {
+    allow: bool,
+    reason: Option<String>,
+}
+
+struct Endpoint {
+    addr: String,
+    token: String,
+}
+
+/// Set once the gate's socket is listening
+static ENDPOINT: OnceLock<Endpoint> = OnceLock::new();
+
+/// Commands waiting for approval and how to answer them
+#[derive(Default)]
+pub struct CommandGate(Mutex<HashMap<String, (PendingCommand, oneshot::Sender<bool>)>>);
+
+/// Listen for hook requests on a loopback port
+pub fn init(app: &AppHandle) {
+    app.manage(CommandGate::default());
+
+    let listener = match std::net::TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.set_nonblocking(true).map(|_| listener))
+    {
+        Ok(listener) => listener,
+        Err(e) => {
+            log::error!("Failed to start the command approval gate: {}", e);
+            return;
+        }
+    };
+    let addr = match listener.local_addr() {
+        Ok(addr) => addr.to_string(),
+        Err(e) => {
+            log::error!("Failed to start the command approval gate: {}", e);
+            return;
+        }
+    };
+    let token = uuid::Uuid::new_v4().simple().to_string();
+    let _ = ENDPOINT.set(Endpoint { addr, token: token.clone() });
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let listener = match tokio::net::TcpListener::from_std(listener) {
+            Ok(listener) => listener,
+            Err(e) => {
+                log::error!("Failed to start the command approval gate: {}", e);
+                return;
+            }
+        };
+        while let Ok((stream, _)) = listener.accept().await {
+            let app = app.clone();
+            let token = token.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = handle_connection(app, stream, &token).await {
+                    log::warn!("Command approval request failed: {}", e);
+                }
+            });
+        }
+    });
+}
+
+/// The hook to give a Claude session of `project_id`, if its gate is enabled
+pub fn hook_for(settings: &ProjectSettings, project_id: &str, session_id: &str) -> Option<CommandHook> {
+    if !settings.command_gate.enabled {
+        return None;
+    }
+    let Some(endpoint) = ENDPOINT.get() else {
+        log::warn!("Command approval gate isn't running; session {} runs ungated", session_id);
+        return None;
+    };
+    let exe = std::env::current_exe().ok()?;
+
+    let command = format!("\"{}\" {}", exe.display(), HOOK_ARG);
+    let hook_settings = serde_json::json!({
+        "hooks": {
+            "PreToolUse": [{
+                "matcher": "Bash",
+                "hooks": [{ "type": "command", "command": command, "timeout": APPROVAL_TIMEOUT.as_secs() + 30 }]
+            }]
+        }
+    });
+    Some(CommandHook {
+        settings: hook_settings.to_string(),
+        env: vec![
+            (ADDR_ENV.to_string(), endpoint.addr.clone()),
+            (TOKEN_ENV.to_string(), endpoint.token.clone()),
+            (PROJECT_ENV.to_string(), project_id.to_string()),
+            (SESSION_ENV.to_string(), session_id.to_string()),
+        ],
+    })
+}
+
+async fn handle_connection(app: AppHandle, stream: tokio::net::TcpStream, token: &str) -> Result<(), String> {
+    let (reader, mut writer) = stream.into_split();
+    let mut line = String::new();
+    tokio::io::BufReader::new(reader)
+        .read_line(&mut line)
+        .await
+        .map_err(|e| e.to_string())?;
+    let request: HookRequest = serde_json::from_str(&line).map_err(|e| e.to_string())?;
+
+    let response = if request.token != token {
+        HookResponse { allow: false, reason: Some("Invalid approval gate token".to_string()) }
+    } else {
+        decide(&app, request).await
+    };
+
+    let mut out = serde_json::to_string(&response).map_err(|e| e.to_string())?;
+    out.push('\n');
+    writer.write_all(out.as_bytes()).await.map_err(|e| e.to_string())
+}
+
+/// Allow the command right away, or ask the user and wait
+async fn decide(app: &AppHandle, request: HookRequest) -> HookResponse {
+    let db = app.state::<Database>();
+    let settings = sqlx::query_scalar::<_, Option<String>>("SELECT settings FROM projects WHERE id = ?")
+        .bind(&request.project_id)
+        .fetch_optional(db.pool())
+        .await
+        .ok()
+        .flatten()
+        .flatten();
+    let settings = ProjectSettings::from_json(settings.as_deref()).command_gate;
+    if !settings.enabled {
+        return HookResponse { allow: true, reason: None };
+    }
+    let Some(pattern) = needs_approval(&settings, &request.command) else {
+        return HookResponse { allow: true, reason: None };
+    };
+
+    let pending = PendingCommand {
+        id: uuid::Uuid::new_v4().to_string(),
+        project_id: request.project_id,
+        session_id: request.session_id,
+        command: request.command,
+        pattern,
+        requested_at: chrono::Utc::now().timestamp(),
+    };
+    log::info!("Command needs approval ({}): {}", pending.pattern, pending.command);
+
+    let (tx, rx) = oneshot::channel();
+    let gate = app.state::<CommandGate>();
+    gate.0.lock().unwrap().insert(pending.id.clone(), (pending.clone(), tx));
+    if let Err(e) = app.emit(APPROVAL_REQUESTED_EVENT, &pending) {
+        log::warn!("Failed to emit {}: {}", APPROVAL_REQUESTED_EVENT, e);
+    }
+
+    let approved = matches!(tokio::time::timeout(APPROVAL_TIMEOUT, rx).await, Ok(Ok(true)));
+    gate.0.lock().unwrap().remove(&pending.id);
+    let _ = app.emit(APPROVAL_RESOLVED_EVENT, &pending.id);
+
+    if approved {
+        HookResponse { allow: true, reason: None }
+    } else {
+        HookResponse {
+            allow: false,
+            reason: Some(format!(
+                "The user did not approve running `{}` (matches the project's deny pattern `{}`). Do not retry it; ask the user how to proceed.",
+                pending.command, pending.pattern
+            )),
+        }
+    }
+}
+
+/// Run as a Claude PreToolUse hook and return the process exit code
+///
+/// Exit code 2 blocks the tool call and shows stderr to the agent. Fails
+/// closed: if the app can't be asked, the command is blocked.
+pub fn run_hook() -> i32 {
+    match ask_app() {
+        Ok(HookResponse { allow: true, .. }) => 0,
+        Ok(HookResponse { reason, .. }) => {
+            eprintln!("{}", reason.unwrap_or_else(|| "Command blocked by AtelierCode".to_string()));
+            2
+        }
+        Err(e) => {
+            eprintln!("Command blocked: the AtelierCode approval gate is unavailable ({})", e);
+            2
+        }
+    }
+}
+
+fn ask_app() -> Result<HookResponse, String> {
+    let mut input = String::new();
+    std::io::stdin().read_to_string(&mut input).map_err(|e| e.to_string())?;
+    let input: serde_json::Value = serde_json::from_str(&input).map_err(|e| e.to_string())?;
+    let Some(command) = input.pointer("/tool_input/command").and_then(|c| c.as_str()) else {
+        // Not a shell command
+        return Ok(HookResponse { allow: true, reason: None });
+    };
+
+    let env = |key: &str| std::env::var(key).map_err(|_| format!("{} is not set", key));
+    let request = HookRequest {
+        token: env(TOKEN_ENV)?,
+        project_id: env(PROJECT_ENV)?,
+        session_id: env(SESSION_ENV)?,
+        command: command.to_string(),
+    };
+
+    let mut stream = std::net::TcpStream::connect(env(ADDR_ENV)?).map_err(|e| e.to_string())?;
+    let mut line = serde_json::to_string(&request).map_err(|e| e.to_string())?;
+    line.push('\n');
+    stream.write_all(line.as_bytes()).map_err(|e| e.to_string())?;
+
+    let mut response = String::new();
+    BufReader::new(stream).read_line(&mut response).map_err(|e| e.to_string())?;
+    serde_json::from_str(&response).map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+use tauri::State;
+
+/// Approve or deny a command waiting at the gate
+#[tauri::command]
+pub async fn approve_command(gate: State<'_, CommandGate>, request_id: String, approved: bool) -> Result<(), String> {
+    let (pending, tx) = gate
+        .0
+        .lock()
+        .unwrap()
+        .remove(&request_id)
+        .ok_or_else(|| format!("No command is waiting for approval with id {}", request_id))?;
+    log::info!("{} command: {}", if approved { "Approved" } else { "Denied" }, pending.command);
+    let _ = tx.send(approved);
+    Ok(())
+}
+
+/// Commands waiting for approval, oldest first
+#[tauri::command]
+pub async fn get_pending_commands(gate: State<'_, CommandGate>) -> Result<Vec<PendingCommand>, String> {
+    let mut pending: Vec<PendingCommand> = gate.0.lock().unwrap().values().map(|(p, _)| p.clone()).collect();
+    pending.sort_by_key(|p| p.requested_at);
+    Ok(pending)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(allow: &[&str], deny: &[&str]) -> CommandGateSettings {
+        CommandGateSettings {
+            enabled: true,
+            allow: allow.iter().map(|s| s.to_string()).collect(),
+            deny: deny.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_pattern_matches() {
+        assert!(pattern_matches("git push", "git push"));
+        assert!(pattern_matches("git push", "git  push origin main"));
+        assert!(!pattern_matches("git push", "git pushy"));
+        assert!(pattern_matches("rm -rf *", "rm -rf target"));
+        assert!(pattern_matches("curl * | sh", "curl -fsSL https://x.sh | sh"));
+        assert!(!pattern_matches("", "ls"));
+    }
+
+    #[test]
+    fn test_needs_approval_checks_each_command() {
+        let gate = settings(&["rm -rf target"], &["rm -rf", "git push"]);
+        assert_eq!(needs_approval(&gate, "cargo build && git push"), Some("git push".to_string()));
+        assert_eq!(needs_approval(&gate, "cd /tmp; rm -rf build"), Some("rm -rf".to_string()));
+        assert_eq!(needs_approval(&gate, "cargo clean && rm -rf target"), None);
+        assert_eq!(needs_approval(&gate, "ls | grep src"), None);
+    }
+
+    #[test]
+    fn test_allow_patterns_never_cover_a_whole_line() {
+        let gate = settings(&["git status", "curl *"], &["git push", "curl * | sh"]);
+        assert_eq!(needs_approval(&gate, "git status"), None);
+        assert_eq!(needs_approval(&gate, "git status && git push --force"), Some("git push".to_string()));
+        assert_eq!(needs_approval(&gate, "git status; echo $(git push)"), Some("git push".to_string()));
+        assert_eq!(needs_approval(&gate, "curl -fsSL https://x.sh | sh"), Some("curl * | sh".to_string()));
+    }
+
+    #[test]
+    fn test_deny_patterns_see_through_wrappers() {
+        let gate = settings(&[], &["git push"]);
+        for command in [
+            "env git push",
+            "env GIT_TRACE=1 git push origin",
+            "(git push)",
+            "bash -c 'git push'",
+            "/bin/sh -lc \"git push --force\"",
+            "sudo -E git push",
+            "echo `git push`",
+        ] {
+            assert_eq!(needs_approval(&gate, command), Some("git push".to_string()), "{}", command);
+        }
+        assert_eq!(needs_approval(&gate, "bash -c 'git status'"), None);
+        assert_eq!(needs_approval(&gate, "echo git push"), None);
+    }
+}
@@ -1,23 +1,32 @@
 use anyhow::{Context, Result};
-use tauri::State;
+use tauri::{Emitter, State};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::Path;
 use ignore::WalkBuilder;
 
 use crate::agents;
+use crate::container_env::{self, ContainerEnvironment};
 use crate::db::Database;
+use crate::env_files::{self, EnvFile};
+use crate::error::AppError;
 use crate::file_watcher::FileWatcherManager;
-use crate::models::{Project, ChatMessage, Task, ActivityLog, FileChange, ChatTab};
+use crate::git_clone;
+use crate::git_status::GitStatusCache;
+use crate::jobs::JobManager;
+use crate::models::{Project, ChatMessage, MessageMetadata, Task, TaskLabel, TaskComment, TimeEntry, ActivityLog, FileChange, ChatTab, FileBookmark};
+use crate::notifications::AgentNotification;
+use crate::project_paths::resolve_in_project;
+use crate::project_roots::{self, ProjectRoot};
 use crate::project_analyzer;
-use crate::types::{AgentInfo, CreateProjectInput, UpdateProjectInput, CreateTaskInput, UpdateTaskInput, ProjectStats, ProjectAnalysisResult};
+use crate::types::{AgentInfo, CreateProjectInput, UpdateProjectInput, CreateTaskInput, UpdateTaskInput, ProjectStats, ProjectAnalysisResult, TimesheetRow, TaskFilters, MessagePage, ChatExportOptions, SessionTokenEstimate, ComparisonReport};
 
 /// Create a new project
 #[tauri::command]
 pub async fn create_project(
     db: State<'_, Database>,
     input: CreateProjectInput,
-) -> Result<Project, String> {
+) -> Result<Project, AppError> {
     log::info!("Creating project: {}", input.name);
 
     // Create project instance
@@ -36,7 +45,47 @@ pub async fn create_project(
         }
     }
 
-    // Insert into database
+    insert_project(db.pool(), &project).await?;
+
+    log::info!("Project created successfully: {}", project.id);
+    Ok(project)
+}
+
+/// Clone a git repository into `dest` and create a project for it
+///
+/// Runs as a job that reports git's progress. The project's name and
+/// description come from analyzing the cloned files.
+#[tauri::command]
+pub async fn create_project_from_git(
+    db: State<'_, Database>,
+    jobs: State<'_, JobManager>,
+    url: String,
+    dest: String,
+    agent_type: String,
+) -> Result<Project, AppError> {
+    log::info!("Creating project from {} at {}", url, dest);
+
+    let pool = db.pool().clone();
+    let title = format!("Clone {}", url);
+    let project = jobs
+        .run("git_clone", None, title, |ctx| async move {
+            git_clone::clone(&url, Path::new(&dest), &ctx).await?;
+
+            ctx.progress(Some(0.9), "Analyzing project").await;
+            let analysis = analyze_project_directory(dest.clone()).await?;
+            let mut project = Project::new(analysis.suggested_name, dest, agent_type);
+            project.prd_content = Some(analysis.suggested_description);
+
+            insert_project(&pool, &project).await?;
+            Ok(project)
+        })
+        .await?;
+
+    log::info!("Project created from git: {}", project.id);
+    Ok(project)
+}
+
+pub(crate) async fn insert_project(pool: &sqlx::SqlitePool, project: &Project) -> Result<(), AppError> {
     sqlx::query(
         r#"
         INSERT INTO projects (id, name, root_path, agent_type, status, prd_content, created_at, last_activity, settings)
@@ -52,17 +101,21 @@ pub async fn create_project(
     .bind(project.created_at)
     .bind(project.last_activity)
     .bind(&project.settings)
-    .execute(db.pool())
+    .execute(pool)
     .await
-    .map_err(|e| format!("Failed to create project: {}", e))?;
+    .map_err(AppError::context("Failed to create project"))?;
 
-    log::info!("Project created successfully: {}", project.id);
-    Ok(project)
+    if let Some(prd_content) = &project.prd_content {
+        if let Err(e) = crate::prd_history::record_revision(pool, &project.id, prd_content, "user").await {
+            log::warn!("Failed to record PRD revision of {}: {}", project.id, e);
+        }
+    }
+    Ok(())
 }
 
 /// Get all projects
 #[tauri::command]
-pub async fn get_projects(db: State<'_, Database>) -> Result<Vec<Project>, String> {
+pub async fn get_projects(db: State<'_, Database>) -> Result<Vec<Project>, AppError> {
     log::info!("Fetching all projects");
 
     let projects = sqlx::query_as::<_, Project>(
@@ -74,7 +127,7 @@ pub async fn get_projects(db: State<'_, Database>) -> Result<Vec<Project>, Strin
     )
     .fetch_all(db.pool())
     .await
-    .map_err(|e| format!("Failed to fetch projects: {}", e))?;
+    .map_err(AppError::context("Failed to fetch projects"))?;
 
     log::info!("Fetched {} projects", projects.len());
     Ok(projects)
@@ -82,7 +135,7 @@ pub async fn get_projects(db: State<'_, Database>) -> Result<Vec<Project>, Strin
 
 /// Get a single project by ID
 #[tauri::command]
-pub async fn get_project(db: State<'_, Database>, id: String) -> Result<Option<Project>, String> {
+pub async fn get_project(db: State<'_, Database>, id: String) -> Result<Option<Project>, AppError> {
     log::info!("Fetching project: {}", id);
 
     let project = sqlx::query_as::<_, Project>(
@@ -95,7 +148,7 @@ pub async fn get_project(db: State<'_, Database>, id: String) -> Result<Option<P
     .bind(&id)
     .fetch_optional(db.pool())
     .await
-    .map_err(|e| format!("Failed to fetch project: {}", e))?;
+    .map_err(AppError::context("Failed to fetch project"))?;
 
     if project.is_some() {
         log::info!("Project found: {}", id);
@@ -108,7 +161,7 @@ pub async fn get_project(db: State<'_, Database>, id: String) -> Result<Option<P
 
 /// Check if a project has recent activity (within last 30 seconds)
 #[tauri::command]
-pub async fn has_recent_activity(db: State<'_, Database>, project_id: String) -> Result<bool, String> {
+pub async fn has_recent_activity(db: State<'_, Database>, project_id: String) -> Result<bool, AppError> {
     let project = sqlx::query_as::<_, Project>(
         "SELECT id, name, root_path, agent_type, status, prd_content, created_at, last_activity, settings, icon, color
          FROM projects
@@ -117,7 +170,7 @@ pub async fn has_recent_activity(db: State<'_, Database>, project_id: String) ->
     .bind(&project_id)
     .fetch_optional(db.pool())
     .await
-    .map_err(|e| e.to_string())?;
+    ?;
 
     if let Some(project) = project {
         let now = chrono::Utc::now().timestamp();
@@ -146,13 +199,13 @@ pub async fn update_project(
     db: State<'_, Database>,
     id: String,
     updates: UpdateProjectInput,
-) -> Result<Project, String> {
+) -> Result<Project, AppError> {
     log::info!("Updating project: {}", id);
 
     // First, fetch the existing project
     let mut project = get_project(db.clone(), id.clone())
         .await?
-        .ok_or_else(|| format!("Project not found: {}", id))?;
+        .ok_or_else(|| AppError::not_found(format!("Project not found: {}", id)))?;
 
     // Apply updates
     if let Some(name) = updates.name {
@@ -167,6 +220,11 @@ pub async fn update_project(
     if let Some(status) = updates.status {
         project.status = status;
     }
+    let prd_author = updates.prd_author.unwrap_or_else(|| "user".to_string());
+    if !crate::prd_history::AUTHORS.contains(&prd_author.as_str()) {
+        return Err(AppError::invalid_input(format!("Unknown PRD author '{}'; use user or ai", prd_author)));
+    }
+    let prd_changed = updates.prd_content.is_some();
     if let Some(prd_content) = updates.prd_content {
         project.prd_content = Some(prd_content);
     }
@@ -203,7 +261,14 @@ pub async fn update_project(
     .bind(&id)
     .execute(db.pool())
     .await
-    .map_err(|e| format!("Failed to update project: {}", e))?;
+    .map_err(AppError::context("Failed to update project"))?;
+
+    if prd_changed {
+        let content = project.prd_content.as_deref().unwrap_or_default();
+        if let Err(e) = crate::prd_history::record_revision(db.pool(), &id, content, &prd_author).await {
+            log::warn!("Failed to record PRD revision of {}: {}", id, e);
+        }
+    }
 
     log::info!("Project updated successfully: {}", id);
     Ok(project)
@@ -211,14 +276,14 @@ pub async fn update_project(
 
 /// Delete a project
 #[tauri::command]
-pub async fn delete_project(db: State<'_, Database>, id: String) -> Result<bool, String> {
+pub async fn delete_project(db: State<'_, Database>, id: String) -> Result<bool, AppError> {
     log::info!("Deleting project: {}", id);
 
     let result = sqlx::query("DELETE FROM projects WHERE id = ?")
         .bind(&id)
         .execute(db.pool())
         .await
-        .map_err(|e| format!("Failed to delete project: {}", e))?;
+        .map_err(AppError::context("Failed to delete project"))?;
 
     let deleted = result.rows_affected() > 0;
 
@@ -235,14 +300,20 @@ pub async fn delete_project(db: State<'_, Database>, id: String) -> Result<bool,
 #[tauri::command]
 pub async fn detect_agents(
     plugin_manager: tauri::State<'_, crate::plugin::PluginManager>,
-) -> Result<Vec<AgentInfo>, String> {
+) -> Result<Vec<AgentInfo>, AppError> {
     log::info!("Detecting installed agents from plugin system");
 
-    // Get all loaded plugins
-    let plugins = plugin_manager.list_plugins();
+    let agents = installed_agents(&plugin_manager);
 
-    // Convert PluginInfo to AgentInfo
-    let agents: Vec<AgentInfo> = plugins
+    log::info!("Detected {} agents from {} loaded plugins", agents.len(), agents.len());
+
+    Ok(agents)
+}
+
+/// The loaded plugins as agents
+fn installed_agents(plugin_manager: &crate::plugin::PluginManager) -> Vec<AgentInfo> {
+    plugin_manager
+        .list_plugins()
         .into_iter()
         .map(|plugin| AgentInfo {
             name: plugin.name.clone(),
@@ -254,25 +325,21 @@ pub async fn detect_agents(
             icon: plugin.icon,
             color: plugin.color,
         })
-        .collect();
-
-    log::info!("Detected {} agents from {} loaded plugins", agents.len(), agents.len());
-
-    Ok(agents)
+        .collect()
 }
 
 /// List all loaded plugins with their flags
 #[tauri::command]
 pub async fn list_plugins(
     plugin_manager: tauri::State<'_, crate::plugin::PluginManager>,
-) -> Result<Vec<crate::plugin::PluginInfo>, String> {
+) -> Result<Vec<crate::plugin::PluginInfo>, AppError> {
     log::info!("Listing plugins");
     Ok(plugin_manager.list_plugins())
 }
 
 /// Open native folder picker dialog
 #[tauri::command]
-pub async fn select_folder(app_handle: tauri::AppHandle) -> Result<Option<String>, String> {
+pub async fn select_folder(app_handle: tauri::AppHandle) -> Result<Option<String>, AppError> {
     log::info!("Opening folder picker dialog");
 
     use tauri_plugin_dialog::DialogExt;
@@ -297,12 +364,9 @@ pub async fn select_folder(app_handle: tauri::AppHandle) -> Result<Option<String
 
 /// Initialize a git repository in the given path
 async fn initialize_git_repo(path: &str) -> Result<()> {
-    use std::process::Command;
-
     log::info!("Initializing git repository at: {}", path);
 
-    let output = Command::new("git")
-        .args(&["init"])
+    let output = crate::cli_command::std_command("git", ["init"])
         .current_dir(path)
         .output()
         .context("Failed to execute git init")?;
@@ -325,7 +389,7 @@ async fn initialize_git_repo(path: &str) -> Result<()> {
 pub async fn create_task(
     db: State<'_, Database>,
     input: CreateTaskInput,
-) -> Result<Task, String> {
+) -> Result<Task, AppError> {
     log::info!("Creating task: {}", input.title);
 
     // Create task instance
@@ -336,14 +400,34 @@ pub async fn create_task(
         task.description = Some(description);
     }
 
-    // Insert into database
-    sqlx::query(
-        r#"
-        INSERT INTO tasks (id, project_id, title, description, priority, status, estimated_hours,
-                          actual_hours, files_affected, depends_on, created_at, started_at, completed_at)
-        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
-        "#
-    )
+    // New tasks go to the bottom of their column
+    task.position = next_task_position(&db, &task.project_id, &task.status).await?;
+
+    insert_task(db.pool(), &task).await?;
+
+    log::info!("Task created successfully: {}", task.id);
+    Ok(task)
+}
+
+/// Columns a `Task` is read from, in the order `insert_task` binds them
+const TASK_COLUMNS: &str = "id, project_id, title, description, priority, status, estimated_hours, \
+     actual_hours, files_affected, depends_on, created_at, started_at, completed_at, position, metadata";
+
+/// Fetch a task, failing if it doesn't exist
+async fn fetch_task(pool: &sqlx::SqlitePool, task_id: &str) -> Result<Task, AppError> {
+    sqlx::query_as::<_, Task>(&format!("SELECT {} FROM tasks WHERE id = ?", TASK_COLUMNS))
+        .bind(task_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(AppError::context("Failed to fetch task"))?
+        .ok_or_else(|| AppError::not_found(format!("Task not found: {}", task_id)))
+}
+
+pub(crate) async fn insert_task(pool: &sqlx::SqlitePool, task: &Task) -> Result<(), AppError> {
+    sqlx::query(&format!(
+        "INSERT INTO tasks ({}) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        TASK_COLUMNS
+    ))
     .bind(&task.id)
     .bind(&task.project_id)
     .bind(&task.title)
@@ -357,32 +441,58 @@ pub async fn create_task(
     .bind(task.created_at)
     .bind(task.started_at)
     .bind(task.completed_at)
-    .execute(db.pool())
+    .bind(task.position)
+    .bind(&task.metadata)
+    .execute(pool)
     .await
-    .map_err(|e| format!("Failed to create task: {}", e))?;
-
-    log::info!("Task created successfully: {}", task.id);
-    Ok(task)
+    .map_err(AppError::context("Failed to create task"))?;
+    Ok(())
 }
 
-/// Get all tasks for a project
+/// Get tasks for a project, optionally filtered by status, label, priority or text
 #[tauri::command]
-pub async fn get_tasks(db: State<'_, Database>, project_id: String) -> Result<Vec<Task>, String> {
+pub async fn get_tasks(
+    db: State<'_, Database>,
+    project_id: String,
+    status: Option<String>,
+    label_id: Option<String>,
+    priority: Option<String>,
+    search: Option<String>,
+) -> Result<Vec<Task>, AppError> {
     log::info!("Fetching tasks for project: {}", project_id);
 
-    let tasks = sqlx::query_as::<_, Task>(
+    let search_pattern = search
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| format!("%{}%", s));
+
+    let tasks = sqlx::query_as::<_, Task>(&format!(
         r#"
-        SELECT id, project_id, title, description, priority, status, estimated_hours,
-               actual_hours, files_affected, depends_on, created_at, started_at, completed_at
+        SELECT {}
         FROM tasks
         WHERE project_id = ?
-        ORDER BY created_at DESC
-        "#
-    )
+          AND (? IS NULL OR status = ?)
+          AND (? IS NULL OR priority = ?)
+          AND (? IS NULL OR id IN (SELECT task_id FROM task_label_assignments WHERE label_id = ?))
+          AND (? IS NULL OR title LIKE ? OR description LIKE ?)
+        ORDER BY position ASC, created_at DESC
+        "#,
+        TASK_COLUMNS
+    ))
     .bind(&project_id)
+    .bind(&status)
+    .bind(&status)
+    .bind(&priority)
+    .bind(&priority)
+    .bind(&label_id)
+    .bind(&label_id)
+    .bind(&search_pattern)
+    .bind(&search_pattern)
+    .bind(&search_pattern)
     .fetch_all(db.pool())
     .await
-    .map_err(|e| format!("Failed to fetch tasks: {}", e))?;
+    .map_err(AppError::context("Failed to fetch tasks"))?;
 
     log::info!("Fetched {} tasks for project {}", tasks.len(), project_id);
     Ok(tasks)
@@ -394,23 +504,11 @@ pub async fn update_task(
     db: State<'_, Database>,
     task_id: String,
     updates: UpdateTaskInput,
-) -> Result<Task, String> {
+) -> Result<Task, AppError> {
     log::info!("Updating task: {}", task_id);
 
     // First, fetch the existing task
-    let mut task = sqlx::query_as::<_, Task>(
-        r#"
-        SELECT id, project_id, title, description, priority, status, estimated_hours,
-               actual_hours, files_affected, depends_on, created_at, started_at, completed_at
-        FROM tasks
-        WHERE id = ?
-        "#
-    )
-    .bind(&task_id)
-    .fetch_optional(db.pool())
-    .await
-    .map_err(|e| format!("Failed to fetch task: {}", e))?
-    .ok_or_else(|| format!("Task not found: {}", task_id))?;
+    let mut task = fetch_task(db.pool(), &task_id).await?;
 
     // Track if task is being completed for activity logging
     let mut task_completed = false;
@@ -426,6 +524,11 @@ pub async fn update_task(
         task.priority = priority;
     }
     if let Some(status) = updates.status {
+        // Moving to another column places the task at the bottom of it
+        if status != task.status {
+            task.position = next_task_position(&db, &task.project_id, &status).await?;
+        }
+
         // Update status-related timestamps
         if status == "in_progress" && task.started_at.is_none() {
             task.started_at = Some(chrono::Utc::now().timestamp());
@@ -453,7 +556,8 @@ pub async fn update_task(
         r#"
         UPDATE tasks
         SET title = ?, description = ?, priority = ?, status = ?, estimated_hours = ?,
-            actual_hours = ?, files_affected = ?, depends_on = ?, started_at = ?, completed_at = ?
+            actual_hours = ?, files_affected = ?, depends_on = ?, started_at = ?, completed_at = ?,
+            position = ?
         WHERE id = ?
         "#
     )
@@ -467,10 +571,11 @@ pub async fn update_task(
     .bind(&task.depends_on)
     .bind(task.started_at)
     .bind(task.completed_at)
+    .bind(task.position)
     .bind(&task_id)
     .execute(db.pool())
     .await
-    .map_err(|e| format!("Failed to update task: {}", e))?;
+    .map_err(AppError::context("Failed to update task"))?;
 
     log::info!("Task updated successfully: {}", task_id);
 
@@ -493,14 +598,14 @@ pub async fn update_task(
 
 /// Delete a task
 #[tauri::command]
-pub async fn delete_task(db: State<'_, Database>, task_id: String) -> Result<bool, String> {
+pub async fn delete_task(db: State<'_, Database>, task_id: String) -> Result<bool, AppError> {
     log::info!("Deleting task: {}", task_id);
 
     let result = sqlx::query("DELETE FROM tasks WHERE id = ?")
         .bind(&task_id)
         .execute(db.pool())
         .await
-        .map_err(|e| format!("Failed to delete task: {}", e))?;
+        .map_err(AppError::context("Failed to delete task"))?;
 
     let deleted = result.rows_affected() > 0;
 
@@ -519,7 +624,7 @@ pub async fn update_task_status(
     db: State<'_, Database>,
     task_id: String,
     status: String,
-) -> Result<Task, String> {
+) -> Result<Task, AppError> {
     log::info!("Updating task status: {} -> {}", task_id, status);
 
     // Use the generic update_task function with just the status
@@ -537,2447 +642,6214 @@ pub async fn update_task_status(
     update_task(db, task_id, updates).await
 }
 
-// ============================================================================
-// File System Commands
-// ============================================================================
+/// Get the position after the last task in a project's status column
+async fn next_task_position(db: &Database, project_id: &str, status: &str) -> Result<i64, AppError> {
+    let max_position = sqlx::query_scalar::<_, Option<i64>>(
+        "SELECT MAX(position) FROM tasks WHERE project_id = ? AND status = ?"
+    )
+    .bind(project_id)
+    .bind(status)
+    .fetch_one(db.pool())
+    .await
+    .map_err(AppError::context("Failed to fetch task positions"))?;
 
-/// File node structure representing a file or directory
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct FileNode {
-    pub id: String,
-    pub name: String,
-    pub path: String,
-    #[serde(rename = "type")]
-    pub node_type: String, // "file" or "folder"
-    pub size: Option<u64>,
-    pub modified: Option<i64>,
-    pub children: Option<Vec<FileNode>>,
-    /// Indicates if folder has children (for lazy loading UI)
-    #[serde(rename = "hasChildren")]
-    pub has_children: Option<bool>,
+    Ok(max_position.map_or(0, |p| p + 1))
 }
 
-/// Read project files and return file tree structure
+/// Persist the order of tasks within a status column (e.g., after a board drag-and-drop)
+///
+/// `ordered_ids` lists the column's tasks top to bottom. Tasks in the list that
+/// currently have another status are moved into this column; tasks of the
+/// column left out of the list keep their relative order after the listed ones.
 #[tauri::command]
-pub async fn read_project_files(
+pub async fn reorder_tasks(
     db: State<'_, Database>,
-    #[allow(non_snake_case)]
-    projectId: String,
-) -> Result<Vec<FileNode>, String> {
-    log::info!("Reading files for project: {}", projectId);
+    project_id: String,
+    status: String,
+    ordered_ids: Vec<String>,
+) -> Result<Vec<Task>, AppError> {
+    log::info!("Reordering {} tasks in {} for project {}", ordered_ids.len(), status, project_id);
 
-    // Get project from database to get the root path
-    let project = get_project(db.clone(), projectId.clone())
-        .await?
-        .ok_or_else(|| format!("Project not found: {}", projectId))?;
+    write_task_order(db.pool(), &project_id, &status, &ordered_ids).await?;
 
-    let root_path = Path::new(&project.root_path);
+    let tasks = get_tasks(db, project_id, Some(status), None, None, None).await?;
 
-    // Check if path exists
-    if !root_path.exists() {
-        return Err(format!("Project path does not exist: {}", project.root_path));
-    }
+    log::info!("Tasks reordered successfully");
+    Ok(tasks)
+}
 
-    if !root_path.is_dir() {
-        return Err(format!("Project path is not a directory: {}", project.root_path));
+/// Number a status column's tasks as `reorder_tasks` describes
+async fn write_task_order(
+    pool: &sqlx::SqlitePool,
+    project_id: &str,
+    status: &str,
+    ordered_ids: &[String],
+) -> Result<(), AppError> {
+    let now = chrono::Utc::now().timestamp();
+
+    let mut tx = pool.begin().await
+        .map_err(AppError::context("Failed to start transaction"))?;
+
+    for (position, task_id) in ordered_ids.iter().enumerate() {
+        let result = sqlx::query(
+            r#"
+            UPDATE tasks
+            SET position = ?,
+                started_at = CASE WHEN ? = 'in_progress' THEN COALESCE(started_at, ?) ELSE started_at END,
+                completed_at = CASE WHEN ? = 'completed' THEN COALESCE(completed_at, ?) ELSE completed_at END,
+                status = ?
+            WHERE id = ? AND project_id = ?
+            "#
+        )
+        .bind(position as i64)
+        .bind(status)
+        .bind(now)
+        .bind(status)
+        .bind(now)
+        .bind(status)
+        .bind(task_id)
+        .bind(project_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::context("Failed to reorder tasks"))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found(format!("Task not found in project: {}", task_id)));
+        }
     }
 
-    // Build file tree respecting .gitignore
-    let mut root_nodes = Vec::new();
+    // Their old positions could collide with the ones just given out
+    let unlisted = sqlx::query_scalar::<_, String>(
+        r#"
+        SELECT id FROM tasks
+        WHERE project_id = ? AND status = ? AND id NOT IN (SELECT value FROM json_each(?))
+        ORDER BY position ASC, created_at ASC
+        "#
+    )
+    .bind(project_id)
+    .bind(status)
+    .bind(serde_json::json!(ordered_ids).to_string())
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(AppError::context("Failed to fetch unlisted tasks"))?;
 
-    // Use ignore crate's WalkBuilder to respect .gitignore patterns
-    let walker = WalkBuilder::new(root_path)
-        .hidden(false) // Show hidden files
-        .git_ignore(true) // Respect .gitignore
-        .git_exclude(true) // Respect .git/info/exclude
-        .ignore(true) // Respect .ignore files
-        .max_depth(Some(1)) // Only get immediate children for root level
-        .build();
+    for (offset, task_id) in unlisted.iter().enumerate() {
+        sqlx::query("UPDATE tasks SET position = ? WHERE id = ?")
+            .bind((ordered_ids.len() + offset) as i64)
+            .bind(task_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::context("Failed to reorder tasks"))?;
+    }
 
-    for entry in walker {
-        match entry {
-            Ok(entry) => {
-                let path = entry.path();
+    tx.commit().await
+        .map_err(AppError::context("Failed to commit task order"))?;
+    Ok(())
+}
 
-                // Skip the root directory itself
-                if path == root_path {
-                    continue;
-                }
+/// Run a task with an agent: start a session, send the task as a prompt and
+/// track the run until the agent reports completion
+///
+/// Set `auto_complete` to false to keep the task in progress for manual review.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn run_task(
+    app: tauri::AppHandle,
+    db: State<'_, Database>,
+    agent_manager: State<'_, crate::agent_manager::AgentManager>,
+    plugin_settings_manager: State<'_, crate::plugin_settings::PluginSettingsManager>,
+    task_id: String,
+    agent_type: String,
+    plugin_name: Option<String>,
+    auto_complete: Option<bool>,
+) -> Result<crate::agent_manager::AgentSession, AppError> {
+    log::info!("Running task {} with {} agent", task_id, agent_type);
 
-                // Skip .git directory
-                if path.file_name().and_then(|s| s.to_str()) == Some(".git") {
-                    continue;
-                }
+    let task = fetch_task(db.pool(), &task_id).await?;
 
-                if let Some(node) = build_file_node(path, root_path) {
-                    root_nodes.push(node);
-                }
-            }
-            Err(e) => {
-                log::warn!("Error reading file entry: {}", e);
-            }
-        }
+    if task.status == "completed" {
+        return Err(AppError::conflict(format!("Task is already completed: {}", task_id)));
     }
 
-    // Sort: folders first, then files, alphabetically within each group
-    root_nodes.sort_by(|a, b| {
-        match (a.node_type.as_str(), b.node_type.as_str()) {
-            ("folder", "file") => std::cmp::Ordering::Less,
-            ("file", "folder") => std::cmp::Ordering::Greater,
-            _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
-        }
-    });
+    let project = get_project(db.clone(), task.project_id.clone())
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Project not found: {}", task.project_id)))?;
 
-    log::info!("Successfully read {} items from project root", root_nodes.len());
+    let settings = crate::models::ProjectSettings::from_json(project.settings.as_deref());
+    let profile = settings.execution_profile(None)?.cloned();
 
-    // Check if this is the first time reading files for this project
-    // by checking if there are any file_change activity logs
-    let activity_count = sqlx::query_scalar::<_, i64>(
-        "SELECT COUNT(*) FROM activity_log WHERE project_id = ? AND event_type = 'file_change'"
+    let session = agent_manager
+        .start_session(project.id.clone(), agent_type.clone(), project.root_path.clone(), None)
+        .await
+        .map_err(AppError::context("Failed to start agent session"))?;
+    let redactor = settings.redaction.redactor();
+    let _ = agent_manager.set_redactor(&session.session_id, redactor).await;
+    let hook = crate::command_gate::hook_for(&settings, &project.id, &session.session_id);
+    let _ = agent_manager.set_command_hook(&session.session_id, hook).await;
+    let _ = agent_manager.set_execution_profile(&session.session_id, profile).await;
+
+    sqlx::query(
+        r#"
+        INSERT INTO agent_sessions (id, project_id, task_id, agent_type, started_at, ended_at, status, exit_code, claude_session_id)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#
     )
-    .bind(&projectId)
-    .fetch_one(db.pool())
+    .bind(&session.session_id)
+    .bind(&session.project_id)
+    .bind(&task.id)
+    .bind(&session.agent_type)
+    .bind(session.started_at)
+    .bind::<Option<i64>>(None) // ended_at
+    .bind("running")
+    .bind::<Option<i64>>(None) // exit_code
+    .bind(&session.claude_session_id)
+    .execute(db.pool())
     .await
-    .unwrap_or(0);
+    .map_err(AppError::context("Failed to save agent session"))?;
 
-    // Log activity only on first read
-    if activity_count == 0 && !root_nodes.is_empty() {
-        let _ = log_activity(
-            db,
-            projectId,
-            "file_change".to_string(),
-            format!("Project files loaded ({} items)", root_nodes.len()),
-            Some(serde_json::json!({
-                "file_count": root_nodes.len()
-            }).to_string()),
-        ).await;
-    }
+    // Subscribe before sending so no events are missed
+    let events = agent_manager
+        .subscribe_events(&session.session_id)
+        .await
+        .map_err(AppError::context("Failed to subscribe to agent events"))?;
+    crate::notifications::forward_agent_events(app, db.pool().clone(), project.id.clone(), events.resubscribe());
+    crate::session_replay::record_events(db.pool().clone(), session.session_id.clone(), events.resubscribe());
+    crate::notes::append_session_summaries(db.pool().clone(), project.id.clone(), session.agent_type.clone(), events.resubscribe());
+
+    let task = update_task_status(db.clone(), task_id.clone(), "in_progress".to_string()).await?;
+
+    let flag_settings = plugin_name.as_ref().map(|name| {
+        plugin_settings_manager.get_plugin_settings(name).flags
+    });
 
-    Ok(root_nodes)
+    agent_manager
+        .send_message(&session.session_id, crate::task_runner::compose_task_prompt(&task), flag_settings)
+        .await
+        .map_err(AppError::context("Failed to send task to agent"))?;
+
+    tokio::spawn(crate::task_runner::monitor_task_run(
+        db.inner().clone(),
+        task.clone(),
+        session.session_id.clone(),
+        crate::task_rules::TaskRules {
+            auto_complete: auto_complete.unwrap_or(true),
+        },
+        events,
+    ));
+
+    let _ = log_activity(
+        db,
+        task.project_id.clone(),
+        "task_run".to_string(),
+        format!("Started {} agent on task: {}", agent_type, task.title),
+        Some(serde_json::json!({
+            "task_id": task.id,
+            "session_id": session.session_id,
+            "agent_type": agent_type
+        }).to_string()),
+    ).await;
+
+    log::info!("Task {} running in agent session {}", task_id, session.session_id);
+    Ok(session)
 }
 
-/// Build a FileNode from a path (lazy loading - doesn't load children)
-fn build_file_node(path: &Path, root_path: &Path) -> Option<FileNode> {
-    let metadata = match fs::metadata(path) {
-        Ok(m) => m,
-        Err(e) => {
-            log::warn!("Failed to read metadata for {:?}: {}", path, e);
-            return None;
-        }
-    };
+/// Estimate a task's effort with AI, using the task and the files it affects as context
+///
+/// Writes `estimated_hours` and stores the suggested breakdown and raw AI reasoning
+/// under the `estimate` key of the task's metadata for review.
+#[tauri::command]
+pub async fn estimate_task(db: State<'_, Database>, task_id: String) -> Result<Task, AppError> {
+    log::info!("Estimating task: {}", task_id);
 
-    let name = path
-        .file_name()
-        .and_then(|n| n.to_str())
-        .unwrap_or("unknown")
-        .to_string();
+    use crate::ai_service::{AIService, ChatMessage};
+    use crate::task_estimation::{build_estimate_prompt, parse_estimate};
 
-    // Generate a relative path-based ID
-    let relative_path = path.strip_prefix(root_path).unwrap_or(path);
-    let id = relative_path.to_string_lossy().replace('\\', "/");
+    /// Maximum number of affected files sent as context
+    const MAX_CONTEXT_FILES: usize = 5;
 
-    let node_type = if metadata.is_dir() {
-        "folder"
-    } else {
-        "file"
-    };
+    let ai_service = AIService::from_env();
+    if !ai_service.is_available() {
+        return Err(AppError::internal("AI service not available. Please configure an API key."));
+    }
 
-    let size = if metadata.is_file() {
-        Some(metadata.len())
-    } else {
-        None
-    };
+    let task = fetch_task(db.pool(), &task_id).await?;
 
-    let modified = metadata
-        .modified()
-        .ok()
-        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
-        .map(|d| d.as_secs() as i64);
+    let project = get_project(db.clone(), task.project_id.clone())
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Project not found: {}", task.project_id)))?;
+
+    // Read the affected files as code context, skipping any that can't be read
+    let root = Path::new(&project.root_path);
+    let files: Vec<(String, String)> = task
+        .files_affected
+        .as_deref()
+        .map(crate::task_runner::parse_files_affected)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|file| {
+            let contents = fs::read_to_string(resolve_in_project(root, &file).ok()?).ok()?;
+            Some((file, contents))
+        })
+        .take(MAX_CONTEXT_FILES)
+        .collect();
 
-    // For folders, check if they have children (for lazy loading indicator)
-    // Don't actually load children - that will be done on-demand
-    let has_children = if metadata.is_dir() {
-        Some(folder_has_children(path, root_path))
-    } else {
-        None
-    };
+    let messages = vec![ChatMessage {
+        role: "user".to_string(),
+        content: build_estimate_prompt(&task, &files),
+    }];
 
-    Some(FileNode {
-        id,
-        name,
-        path: path.to_string_lossy().to_string(),
-        node_type: node_type.to_string(),
-        size,
-        modified,
-        children: None, // Children loaded lazily via get_folder_children
-        has_children,
-    })
-}
-
-/// Quick check if a folder has any visible children (for lazy loading UI)
-fn folder_has_children(folder_path: &Path, root_path: &Path) -> bool {
-    let walker = WalkBuilder::new(folder_path)
-        .hidden(false)
-        .git_ignore(true)
-        .git_exclude(true)
-        .ignore(true)
-        .max_depth(Some(1))
-        .build();
-
-    for entry in walker {
-        if let Ok(entry) = entry {
-            let path = entry.path();
-            // Skip the folder itself
-            if path == folder_path {
-                continue;
-            }
-            // Skip .git directory
-            if path.file_name().and_then(|s| s.to_str()) == Some(".git") {
-                continue;
-            }
-            // Found at least one child
-            return true;
-        }
-    }
-    false
-}
-
-/// Load children for a folder, respecting .gitignore
-fn load_folder_children(folder_path: &Path, root_path: &Path) -> Vec<FileNode> {
-    let mut children = Vec::new();
-
-    let walker = WalkBuilder::new(folder_path)
-        .hidden(false)
-        .git_ignore(true)
-        .git_exclude(true)
-        .ignore(true)
-        .max_depth(Some(1)) // Only immediate children
-        .build();
-
-    for entry in walker {
-        match entry {
-            Ok(entry) => {
-                let path = entry.path();
-
-                // Skip the folder itself
-                if path == folder_path {
-                    continue;
-                }
-
-                // Skip .git directory
-                if path.file_name().and_then(|s| s.to_str()) == Some(".git") {
-                    continue;
-                }
+    let response = ai_service.chat_completion(messages).await?;
+    let estimate = parse_estimate(&response)
+        .ok_or_else(|| AppError::internal("Failed to parse estimate from AI response"))?;
+
+    // Merge into existing metadata so other keys are preserved
+    let mut metadata = task
+        .metadata
+        .as_deref()
+        .and_then(|m| serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(m).ok())
+        .unwrap_or_default();
+    metadata.insert(
+        "estimate".to_string(),
+        serde_json::json!({
+            "estimated_hours": estimate.estimated_hours,
+            "breakdown": estimate.breakdown,
+            "reasoning": estimate.reasoning,
+            "raw_response": response,
+            "provider": ai_service.provider_name(),
+            "estimated_at": chrono::Utc::now().timestamp()
+        }),
+    );
 
-                if let Some(node) = build_file_node(path, root_path) {
-                    children.push(node);
-                }
-            }
-            Err(e) => {
-                log::warn!("Error reading file entry: {}", e);
-            }
-        }
-    }
+    sqlx::query("UPDATE tasks SET estimated_hours = ?, metadata = ? WHERE id = ?")
+        .bind(estimate.estimated_hours)
+        .bind(serde_json::Value::Object(metadata).to_string())
+        .bind(&task_id)
+        .execute(db.pool())
+        .await
+        .map_err(AppError::context("Failed to save estimate"))?;
 
-    // Sort: folders first, then files
-    children.sort_by(|a, b| {
-        match (a.node_type.as_str(), b.node_type.as_str()) {
-            ("folder", "file") => std::cmp::Ordering::Less,
-            ("file", "folder") => std::cmp::Ordering::Greater,
-            _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
-        }
-    });
+    let task = sqlx::query_as::<_, Task>(&format!("SELECT {} FROM tasks WHERE id = ?", TASK_COLUMNS))
+        .bind(&task_id)
+        .fetch_one(db.pool())
+        .await
+        .map_err(AppError::context("Failed to fetch updated task"))?;
 
-    children
+    log::info!("Task {} estimated at {} hours", task_id, estimate.estimated_hours);
+    Ok(task)
 }
 
-/// Get children of a folder (for lazy loading in UI)
+/// Export a project's tasks as Markdown ("markdown") or CSV ("csv") to the given path
 #[tauri::command]
-pub async fn get_folder_children(
+pub async fn export_tasks(
     db: State<'_, Database>,
-    #[allow(non_snake_case)]
-    projectId: String,
-    #[allow(non_snake_case)]
-    folderPath: String,
-) -> Result<Vec<FileNode>, String> {
-    log::info!("Getting folder children for: {} in project: {}", folderPath, projectId);
+    project_id: String,
+    format: String,
+    filters: Option<TaskFilters>,
+    output_path: String,
+) -> Result<String, AppError> {
+    log::info!("Exporting tasks for project {} as {} to {}", project_id, format, output_path);
 
-    // Get project from database to get the root path
-    let project = get_project(db.clone(), projectId.clone())
+    let project = get_project(db.clone(), project_id.clone())
         .await?
-        .ok_or_else(|| format!("Project not found: {}", projectId))?;
-
-    let root_path = Path::new(&project.root_path);
-    let folder_path = Path::new(&folderPath);
-
-    // Security check: ensure the folder path is within the project root
-    if !folder_path.starts_with(root_path) {
-        return Err(format!("Folder path is outside project root: {}", folderPath));
-    }
+        .ok_or_else(|| AppError::not_found(format!("Project not found: {}", project_id)))?;
 
-    // Check if path exists and is a directory
-    if !folder_path.exists() {
-        return Err(format!("Folder does not exist: {}", folderPath));
-    }
+    let filters = filters.unwrap_or_default();
+    let tasks = get_tasks(
+        db.clone(),
+        project_id,
+        filters.status,
+        filters.label_id,
+        filters.priority,
+        filters.search,
+    )
+    .await?;
+
+    let mut exported = Vec::with_capacity(tasks.len());
+    for task in tasks {
+        let labels = get_task_labels(db.clone(), task.id.clone())
+            .await?
+            .into_iter()
+            .map(|l| l.name)
+            .collect();
+
+        let session_ids = sqlx::query_scalar::<_, String>(
+            "SELECT id FROM agent_sessions WHERE task_id = ? ORDER BY started_at ASC"
+        )
+        .bind(&task.id)
+        .fetch_all(db.pool())
+        .await
+        .map_err(AppError::context("Failed to fetch task sessions"))?;
 
-    if !folder_path.is_dir() {
-        return Err(format!("Path is not a folder: {}", folderPath));
+        exported.push(crate::task_export::ExportedTask { task, labels, session_ids });
     }
 
-    // Load children
-    let children = load_folder_children(folder_path, root_path);
+    let content = match format.to_lowercase().as_str() {
+        "markdown" | "md" => crate::task_export::render_markdown(&project.name, &exported),
+        "csv" => crate::task_export::render_csv(&exported),
+        other => return Err(AppError::invalid_input(format!("Unsupported export format: {}", other))),
+    };
 
-    log::info!("Loaded {} children for folder: {}", children.len(), folderPath);
+    fs::write(&output_path, content)
+        .map_err(AppError::context("Failed to write export file"))?;
 
-    Ok(children)
+    log::info!("Exported {} tasks to {}", exported.len(), output_path);
+    Ok(output_path)
 }
 
-/// Git file status
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct GitFileStatus {
-    pub path: String,
-    pub status: String, // "modified", "added", "deleted", "renamed", "untracked"
-}
+// ============================================================================
+// Task Label Commands
+// ============================================================================
 
-/// Get git status for uncommitted changes in a project
+/// Create a label for a project's tasks
 #[tauri::command]
-pub async fn get_git_status(
+pub async fn create_task_label(
     db: State<'_, Database>,
-    #[allow(non_snake_case)]
-    projectId: String,
-) -> Result<Vec<GitFileStatus>, String> {
-    log::info!("Getting git status for project: {}", projectId);
-
-    // Get project from database to get the root path
-    let project = get_project(db.clone(), projectId.clone())
-        .await?
-        .ok_or_else(|| format!("Project not found: {}", projectId))?;
-
-    let root_path = &project.root_path;
-
-    // Check if .git directory exists
-    let git_dir = Path::new(root_path).join(".git");
-    if !git_dir.exists() {
-        log::info!("Not a git repository: {}", root_path);
-        return Ok(Vec::new());
+    project_id: String,
+    name: String,
+    color: Option<String>,
+) -> Result<TaskLabel, AppError> {
+    log::info!("Creating task label '{}' for project {}", name, project_id);
+
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(AppError::invalid_input("Label name cannot be empty"));
     }
 
-    // Run git status --porcelain to get machine-readable output
-    let output = std::process::Command::new("git")
-        .args(["status", "--porcelain", "-uall"])
-        .current_dir(root_path)
-        .output()
-        .map_err(|e| format!("Failed to run git status: {}", e))?;
+    let label = TaskLabel::new(project_id, name, color);
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        log::warn!("Git status failed: {}", stderr);
-        return Ok(Vec::new());
-    }
+    sqlx::query(
+        r#"
+        INSERT INTO task_labels (id, project_id, name, color, created_at)
+        VALUES (?, ?, ?, ?, ?)
+        "#
+    )
+    .bind(&label.id)
+    .bind(&label.project_id)
+    .bind(&label.name)
+    .bind(&label.color)
+    .bind(label.created_at)
+    .execute(db.pool())
+    .await
+    .map_err(AppError::context("Failed to create task label"))?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let mut statuses = Vec::new();
+    log::info!("Task label created: {}", label.id);
+    Ok(label)
+}
 
-    for line in stdout.lines() {
-        if line.len() < 4 {
-            continue;
-        }
+/// List all task labels for a project
+#[tauri::command]
+pub async fn list_task_labels(
+    db: State<'_, Database>,
+    project_id: String,
+) -> Result<Vec<TaskLabel>, AppError> {
+    log::info!("Fetching task labels for project: {}", project_id);
 
-        // Git porcelain format: XY filename
-        // X = index status, Y = worktree status
-        let index_status = line.chars().next().unwrap_or(' ');
-        let worktree_status = line.chars().nth(1).unwrap_or(' ');
-        let file_path = line[3..].trim();
-
-        // Handle renamed files (format: "R  old -> new")
-        let file_path = if file_path.contains(" -> ") {
-            file_path.split(" -> ").last().unwrap_or(file_path)
-        } else {
-            file_path
-        };
+    let labels = sqlx::query_as::<_, TaskLabel>(
+        r#"
+        SELECT id, project_id, name, color, created_at
+        FROM task_labels
+        WHERE project_id = ?
+        ORDER BY name ASC
+        "#
+    )
+    .bind(&project_id)
+    .fetch_all(db.pool())
+    .await
+    .map_err(AppError::context("Failed to fetch task labels"))?;
 
-        // Determine the status to show
-        let status = match (index_status, worktree_status) {
-            ('?', '?') => "untracked",
-            ('A', _) => "added",
-            ('D', _) | (_, 'D') => "deleted",
-            ('R', _) => "renamed",
-            ('M', _) | (_, 'M') => "modified",
-            ('C', _) => "copied",
-            ('U', _) | (_, 'U') => "conflict",
-            _ => continue, // Skip unknown statuses
-        };
+    Ok(labels)
+}
 
-        // Convert to absolute path
-        let absolute_path = Path::new(root_path).join(file_path);
+/// Rename or recolor a task label
+#[tauri::command]
+pub async fn update_task_label(
+    db: State<'_, Database>,
+    label_id: String,
+    name: Option<String>,
+    color: Option<String>,
+) -> Result<TaskLabel, AppError> {
+    log::info!("Updating task label: {}", label_id);
+
+    let mut label = sqlx::query_as::<_, TaskLabel>(
+        "SELECT id, project_id, name, color, created_at FROM task_labels WHERE id = ?"
+    )
+    .bind(&label_id)
+    .fetch_optional(db.pool())
+    .await
+    .map_err(AppError::context("Failed to fetch task label"))?
+    .ok_or_else(|| AppError::not_found(format!("Task label not found: {}", label_id)))?;
 
-        statuses.push(GitFileStatus {
-            path: absolute_path.to_string_lossy().to_string(),
-            status: status.to_string(),
-        });
+    if let Some(name) = name {
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            return Err(AppError::invalid_input("Label name cannot be empty"));
+        }
+        label.name = name;
+    }
+    if let Some(color) = color {
+        label.color = Some(color);
     }
 
-    log::info!("Found {} uncommitted changes", statuses.len());
+    sqlx::query("UPDATE task_labels SET name = ?, color = ? WHERE id = ?")
+        .bind(&label.name)
+        .bind(&label.color)
+        .bind(&label_id)
+        .execute(db.pool())
+        .await
+        .map_err(AppError::context("Failed to update task label"))?;
 
-    Ok(statuses)
+    Ok(label)
 }
 
-/// Read and return file content
+/// Delete a task label (removing it from every task)
 #[tauri::command]
-pub async fn read_file_content(
-    db: State<'_, Database>,
-    #[allow(non_snake_case)]
-    projectId: String,
-    #[allow(non_snake_case)]
-    filePath: String,
-) -> Result<String, String> {
-    log::info!("Reading file content for project {}: {}", projectId, filePath);
+pub async fn delete_task_label(db: State<'_, Database>, label_id: String) -> Result<bool, AppError> {
+    log::info!("Deleting task label: {}", label_id);
 
-    // Get project from database to verify it exists
-    let project = get_project(db, projectId.clone())
-        .await?
-        .ok_or_else(|| format!("Project not found: {}", projectId))?;
+    let result = sqlx::query("DELETE FROM task_labels WHERE id = ?")
+        .bind(&label_id)
+        .execute(db.pool())
+        .await
+        .map_err(AppError::context("Failed to delete task label"))?;
 
-    let root_path = Path::new(&project.root_path);
-    let target_path = Path::new(&filePath);
+    Ok(result.rows_affected() > 0)
+}
 
-    // Security check: ensure the file is within the project directory
-    let canonical_root = root_path
-        .canonicalize()
-        .map_err(|e| format!("Failed to resolve project path: {}", e))?;
+/// Apply a label to a task
+#[tauri::command]
+pub async fn add_label_to_task(
+    db: State<'_, Database>,
+    task_id: String,
+    label_id: String,
+) -> Result<(), AppError> {
+    log::info!("Adding label {} to task {}", label_id, task_id);
 
-    let canonical_target = target_path
-        .canonicalize()
-        .map_err(|e| format!("Failed to resolve file path: {}", e))?;
+    // Labels can only be applied within the task's own project
+    let same_project = sqlx::query_scalar::<_, i64>(
+        r#"
+        SELECT COUNT(*)
+        FROM tasks t
+        JOIN task_labels l ON l.project_id = t.project_id
+        WHERE t.id = ? AND l.id = ?
+        "#
+    )
+    .bind(&task_id)
+    .bind(&label_id)
+    .fetch_one(db.pool())
+    .await
+    .map_err(AppError::context("Failed to verify task label"))?;
 
-    if !canonical_target.starts_with(&canonical_root) {
-        return Err("Access denied: file is outside project directory".to_string());
+    if same_project == 0 {
+        return Err(AppError::not_found("Task or label not found in the same project"));
     }
 
-    // Check if file exists and is a file
-    if !canonical_target.exists() {
-        return Err(format!("File not found: {}", filePath));
-    }
+    sqlx::query("INSERT OR IGNORE INTO task_label_assignments (task_id, label_id) VALUES (?, ?)")
+        .bind(&task_id)
+        .bind(&label_id)
+        .execute(db.pool())
+        .await
+        .map_err(AppError::context("Failed to add label to task"))?;
 
-    if !canonical_target.is_file() {
-        return Err(format!("Path is not a file: {}", filePath));
-    }
+    Ok(())
+}
 
-    // Read file content
-    let content = fs::read_to_string(&canonical_target)
-        .map_err(|e| format!("Failed to read file: {}", e))?;
+/// Remove a label from a task
+#[tauri::command]
+pub async fn remove_label_from_task(
+    db: State<'_, Database>,
+    task_id: String,
+    label_id: String,
+) -> Result<bool, AppError> {
+    log::info!("Removing label {} from task {}", label_id, task_id);
 
-    // Limit file size to prevent memory issues (e.g., 10MB)
-    if content.len() > 10 * 1024 * 1024 {
-        return Err("File too large to preview (max 10MB)".to_string());
-    }
+    let result = sqlx::query("DELETE FROM task_label_assignments WHERE task_id = ? AND label_id = ?")
+        .bind(&task_id)
+        .bind(&label_id)
+        .execute(db.pool())
+        .await
+        .map_err(AppError::context("Failed to remove label from task"))?;
 
-    log::info!("Successfully read file: {} ({} bytes)", filePath, content.len());
-    Ok(content)
+    Ok(result.rows_affected() > 0)
 }
 
-/// Send a chat message and get AI response
+/// Get the labels applied to a task
 #[tauri::command]
-pub async fn send_message(
+pub async fn get_task_labels(
     db: State<'_, Database>,
-    project_id: String,
+    task_id: String,
+) -> Result<Vec<TaskLabel>, AppError> {
+    let labels = sqlx::query_as::<_, TaskLabel>(
+        r#"
+        SELECT l.id, l.project_id, l.name, l.color, l.created_at
+        FROM task_labels l
+        JOIN task_label_assignments a ON a.label_id = l.id
+        WHERE a.task_id = ?
+        ORDER BY l.name ASC
+        "#
+    )
+    .bind(&task_id)
+    .fetch_all(db.pool())
+    .await
+    .map_err(AppError::context("Failed to fetch labels for task"))?;
+
+    Ok(labels)
+}
+
+// ============================================================================
+// Task Comment Commands
+// ============================================================================
+
+/// Add a comment to a task
+#[tauri::command]
+pub async fn add_task_comment(
+    db: State<'_, Database>,
+    task_id: String,
     content: String,
-    session_id: Option<String>,
-) -> Result<ChatMessage, String> {
-    log::info!("Sending message for project: {} (session: {:?})", project_id, session_id);
+    author: Option<String>,
+) -> Result<TaskComment, AppError> {
+    log::info!("Adding comment to task: {}", task_id);
 
-    // Verify project exists
-    get_project(db.clone(), project_id.clone())
-        .await?
-        .ok_or_else(|| format!("Project not found: {}", project_id))?;
+    if content.trim().is_empty() {
+        return Err(AppError::invalid_input("Comment cannot be empty"));
+    }
 
-    // Create and save user message with session_id
-    let user_message = ChatMessage::new_with_session(
-        project_id.clone(),
-        session_id.clone(),
-        "user".to_string(),
-        content.clone(),
-    );
+    let comment = TaskComment::new(task_id, author.unwrap_or_else(|| "user".to_string()), content);
 
     sqlx::query(
         r#"
-        INSERT INTO chat_messages (id, project_id, session_id, role, content, timestamp, metadata)
-        VALUES (?, ?, ?, ?, ?, ?, ?)
+        INSERT INTO task_comments (id, task_id, author, content, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?)
         "#
     )
-    .bind(&user_message.id)
-    .bind(&user_message.project_id)
-    .bind(&user_message.session_id)
-    .bind(&user_message.role)
-    .bind(&user_message.content)
-    .bind(user_message.timestamp)
-    .bind(&user_message.metadata)
+    .bind(&comment.id)
+    .bind(&comment.task_id)
+    .bind(&comment.author)
+    .bind(&comment.content)
+    .bind(comment.created_at)
+    .bind(comment.updated_at)
     .execute(db.pool())
     .await
-    .map_err(|e| format!("Failed to save user message: {}", e))?;
+    .map_err(AppError::context("Failed to add task comment"))?;
 
-    log::info!("User message saved: {} (session: {:?})", user_message.id, session_id);
-
-    // Track processing time
-    let start_time = std::time::Instant::now();
-
-    // Generate AI response using AI service
-    let response_content = match generate_ai_response(&content).await {
-        Ok(response) => response,
-        Err(e) => {
-            log::error!("AI service error: {}", e);
-            // Fallback to mock response if AI service fails
-            generate_mock_response(&content)
-        }
-    };
-
-    let processing_time = start_time.elapsed().as_millis() as u64;
-
-    // Create AI message with metadata and session_id
-    let mut ai_message = ChatMessage::new_with_session(
-        project_id.clone(),
-        session_id.clone(),
-        "assistant".to_string(),
-        response_content,
-    );
+    log::info!("Task comment added: {}", comment.id);
+    Ok(comment)
+}
 
-    // Add processing time to metadata
-    let metadata = serde_json::json!({
-        "processingTime": processing_time,
-        "model": "claude"
-    });
-    ai_message.metadata = Some(metadata.to_string());
+/// List a task's comments, oldest first
+#[tauri::command]
+pub async fn list_task_comments(
+    db: State<'_, Database>,
+    task_id: String,
+) -> Result<Vec<TaskComment>, AppError> {
+    log::info!("Fetching comments for task: {}", task_id);
 
-    sqlx::query(
+    let comments = sqlx::query_as::<_, TaskComment>(
         r#"
-        INSERT INTO chat_messages (id, project_id, session_id, role, content, timestamp, metadata)
-        VALUES (?, ?, ?, ?, ?, ?, ?)
+        SELECT id, task_id, author, content, created_at, updated_at
+        FROM task_comments
+        WHERE task_id = ?
+        ORDER BY created_at ASC
         "#
     )
-    .bind(&ai_message.id)
-    .bind(&ai_message.project_id)
-    .bind(&ai_message.session_id)
-    .bind(&ai_message.role)
-    .bind(&ai_message.content)
-    .bind(ai_message.timestamp)
-    .bind(&ai_message.metadata)
-    .execute(db.pool())
+    .bind(&task_id)
+    .fetch_all(db.pool())
     .await
-    .map_err(|e| format!("Failed to save AI message: {}", e))?;
+    .map_err(AppError::context("Failed to fetch task comments"))?;
 
-    log::info!("AI message saved: {} (session: {:?})", ai_message.id, session_id);
+    Ok(comments)
+}
 
-    // Update project last activity
-    sqlx::query("UPDATE projects SET last_activity = ? WHERE id = ?")
+/// Edit the content of a task comment
+#[tauri::command]
+pub async fn edit_task_comment(
+    db: State<'_, Database>,
+    comment_id: String,
+    content: String,
+) -> Result<TaskComment, AppError> {
+    log::info!("Editing task comment: {}", comment_id);
+
+    if content.trim().is_empty() {
+        return Err(AppError::invalid_input("Comment cannot be empty"));
+    }
+
+    let result = sqlx::query("UPDATE task_comments SET content = ?, updated_at = ? WHERE id = ?")
+        .bind(&content)
         .bind(chrono::Utc::now().timestamp())
-        .bind(&project_id)
+        .bind(&comment_id)
         .execute(db.pool())
         .await
-        .map_err(|e| format!("Failed to update project activity: {}", e))?;
+        .map_err(AppError::context("Failed to edit task comment"))?;
 
-    // Log activity for the message
-    let _ = log_activity(
-        db,
-        project_id,
-        "message".to_string(),
-        "New message sent".to_string(),
-        Some(serde_json::json!({
-            "preview": if content.len() > 50 {
-                format!("{}...", &content[..50])
-            } else {
-                content.clone()
-            }
-        }).to_string()),
-    ).await;
+    if result.rows_affected() == 0 {
+        return Err(AppError::not_found(format!("Task comment not found: {}", comment_id)));
+    }
 
-    Ok(ai_message)
+    let comment = sqlx::query_as::<_, TaskComment>(
+        "SELECT id, task_id, author, content, created_at, updated_at FROM task_comments WHERE id = ?"
+    )
+    .bind(&comment_id)
+    .fetch_one(db.pool())
+    .await
+    .map_err(AppError::context("Failed to fetch task comment"))?;
+
+    Ok(comment)
 }
 
-/// Get all chat messages for a project (limited to most recent 20)
+/// Delete a task comment
 #[tauri::command]
-pub async fn get_messages(
-    db: State<'_, Database>,
-    project_id: String,
-) -> Result<Vec<ChatMessage>, String> {
-    log::info!("Fetching messages for project: {}", project_id);
+pub async fn delete_task_comment(db: State<'_, Database>, comment_id: String) -> Result<bool, AppError> {
+    log::info!("Deleting task comment: {}", comment_id);
 
-    // Get last 20 messages
-    let mut messages = sqlx::query_as::<_, ChatMessage>(
+    let result = sqlx::query("DELETE FROM task_comments WHERE id = ?")
+        .bind(&comment_id)
+        .execute(db.pool())
+        .await
+        .map_err(AppError::context("Failed to delete task comment"))?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+// ============================================================================
+// Time Tracking Commands
+// ============================================================================
+
+/// Start a timer on a task
+#[tauri::command]
+pub async fn start_task_timer(db: State<'_, Database>, task_id: String) -> Result<TimeEntry, AppError> {
+    log::info!("Starting timer for task: {}", task_id);
+
+    let project_id = sqlx::query_scalar::<_, String>("SELECT project_id FROM tasks WHERE id = ?")
+        .bind(&task_id)
+        .fetch_optional(db.pool())
+        .await
+        .map_err(AppError::context("Failed to fetch task"))?
+        .ok_or_else(|| AppError::not_found(format!("Task not found: {}", task_id)))?;
+
+    let running = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM time_entries WHERE task_id = ? AND ended_at IS NULL"
+    )
+    .bind(&task_id)
+    .fetch_one(db.pool())
+    .await
+    .map_err(AppError::context("Failed to check running timers"))?;
+
+    if running > 0 {
+        return Err(AppError::conflict(format!("A timer is already running for task: {}", task_id)));
+    }
+
+    let entry = TimeEntry::new(task_id, project_id, "manual".to_string());
+
+    sqlx::query(
         r#"
-        SELECT id, project_id, session_id, role, content, timestamp, metadata
-        FROM chat_messages
-        WHERE project_id = ?
-        ORDER BY timestamp DESC
-        LIMIT 20
+        INSERT INTO time_entries (id, task_id, project_id, source, started_at, ended_at, duration_seconds)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
         "#
     )
-    .bind(&project_id)
-    .fetch_all(db.pool())
+    .bind(&entry.id)
+    .bind(&entry.task_id)
+    .bind(&entry.project_id)
+    .bind(&entry.source)
+    .bind(entry.started_at)
+    .bind(entry.ended_at)
+    .bind(entry.duration_seconds)
+    .execute(db.pool())
     .await
-    .map_err(|e| format!("Failed to fetch messages: {}", e))?;
+    .map_err(AppError::context("Failed to start task timer"))?;
 
-    messages.reverse();
-    log::info!("Fetched {} messages for project", messages.len());
-    Ok(messages)
+    log::info!("Task timer started: {}", entry.id);
+    Ok(entry)
 }
 
-/// Get chat messages for a specific session (limited to most recent 20)
+/// Stop the running timer on a task and add the elapsed time to its actual_hours
 #[tauri::command]
-pub async fn get_session_messages(
-    db: State<'_, Database>,
-    session_id: String,
-) -> Result<Vec<ChatMessage>, String> {
-    log::info!("Fetching messages for session: {}", session_id);
+pub async fn stop_task_timer(db: State<'_, Database>, task_id: String) -> Result<TimeEntry, AppError> {
+    log::info!("Stopping timer for task: {}", task_id);
 
-    let messages = sqlx::query_as::<_, ChatMessage>(
+    let mut tx = db.pool().begin().await
+        .map_err(AppError::context("Failed to start transaction"))?;
+
+    let mut entry = sqlx::query_as::<_, TimeEntry>(
         r#"
-        SELECT id, project_id, session_id, role, content, timestamp, metadata
-        FROM chat_messages
-        WHERE session_id = ?
-        ORDER BY timestamp DESC
-        LIMIT 20
+        SELECT id, task_id, project_id, source, started_at, ended_at, duration_seconds
+        FROM time_entries
+        WHERE task_id = ? AND ended_at IS NULL
+        ORDER BY started_at DESC
+        LIMIT 1
         "#
     )
-    .bind(&session_id)
-    .fetch_all(db.pool())
+    .bind(&task_id)
+    .fetch_optional(&mut *tx)
     .await
-    .map_err(|e| format!("Failed to fetch session messages: {}", e))?;
+    .map_err(AppError::context("Failed to fetch running timer"))?
+    .ok_or_else(|| AppError::not_found(format!("No timer running for task: {}", task_id)))?;
 
-    // Reverse to get chronological order (oldest first)
-    let mut messages = messages;
-    messages.reverse();
-
-    log::info!("Fetched {} messages for session {} (limited to 20)", messages.len(), session_id);
-    Ok(messages)
-}
-
-/// Get project messages, optionally filtered by session (limited to most recent 20)
-#[tauri::command]
-pub async fn get_project_messages(
-    db: State<'_, Database>,
-    project_id: String,
-    session_id: Option<String>,
-) -> Result<Vec<ChatMessage>, String> {
-    if let Some(sid) = session_id {
-        log::info!("Fetching messages for project {} and session {}", project_id, sid);
+    let now = chrono::Utc::now().timestamp();
+    let duration = (now - entry.started_at).max(0);
+    entry.ended_at = Some(now);
+    entry.duration_seconds = Some(duration);
+
+    // A concurrent stop may have ended the entry first; its time is counted once
+    let stopped = sqlx::query("UPDATE time_entries SET ended_at = ?, duration_seconds = ? WHERE id = ? AND ended_at IS NULL")
+        .bind(entry.ended_at)
+        .bind(entry.duration_seconds)
+        .bind(&entry.id)
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::context("Failed to stop task timer"))?;
+    if stopped.rows_affected() != 1 {
+        return Err(AppError::conflict(format!("Timer for task {} was already stopped", task_id)));
+    }
 
-        let messages = sqlx::query_as::<_, ChatMessage>(
-            r#"
-            SELECT id, project_id, session_id, role, content, timestamp, metadata
-            FROM chat_messages
-            WHERE project_id = ? AND session_id = ?
-            ORDER BY timestamp DESC
-            LIMIT 20
-            "#
-        )
-        .bind(&project_id)
-        .bind(&sid)
-        .fetch_all(db.pool())
+    sqlx::query("UPDATE tasks SET actual_hours = COALESCE(actual_hours, 0) + ? WHERE id = ?")
+        .bind(duration as f64 / 3600.0)
+        .bind(&task_id)
+        .execute(&mut *tx)
         .await
-        .map_err(|e| format!("Failed to fetch messages: {}", e))?;
+        .map_err(AppError::context("Failed to update task hours"))?;
 
-        // Reverse to get chronological order (oldest first)
-        let mut messages = messages;
-        messages.reverse();
+    tx.commit().await
+        .map_err(AppError::context("Failed to commit task timer"))?;
 
-        log::info!("Fetched {} messages for project {} and session {} (limited to 20)", messages.len(), project_id, sid);
-        Ok(messages)
-    } else {
-        log::info!("Fetching all messages for project: {}", project_id);
-        get_messages(db, project_id).await
-    }
+    log::info!("Task timer stopped after {} seconds", duration);
+    Ok(entry)
 }
 
-/// Count total messages for a session (not limited)
+/// Get all time entries for a task, newest first
 #[tauri::command]
-pub async fn count_session_messages(
+pub async fn get_task_time_entries(
     db: State<'_, Database>,
-    session_id: String,
-) -> Result<i64, String> {
-    log::info!("Counting messages for session: {}", session_id);
-
-    let count = sqlx::query_scalar::<_, i64>(
+    task_id: String,
+) -> Result<Vec<TimeEntry>, AppError> {
+    let entries = sqlx::query_as::<_, TimeEntry>(
         r#"
-        SELECT COUNT(*)
-        FROM chat_messages
-        WHERE session_id = ?
+        SELECT id, task_id, project_id, source, started_at, ended_at, duration_seconds
+        FROM time_entries
+        WHERE task_id = ?
+        ORDER BY started_at DESC
         "#
     )
-    .bind(&session_id)
-    .fetch_one(db.pool())
+    .bind(&task_id)
+    .fetch_all(db.pool())
     .await
-    .map_err(|e| format!("Failed to count session messages: {}", e))?;
+    .map_err(AppError::context("Failed to fetch time entries"))?;
 
-    log::info!("Session {} has {} messages", session_id, count);
-    Ok(count)
+    Ok(entries)
 }
 
-/// Save a message with session context
+/// Get a project's timesheet: hours per task per day, optionally limited to a time range
 #[tauri::command]
-pub async fn save_message(
+pub async fn get_project_timesheet(
     db: State<'_, Database>,
     project_id: String,
-    session_id: Option<String>,
-    role: String,
-    content: String,
-    metadata: Option<String>,
-) -> Result<ChatMessage, String> {
-    log::info!("Saving {} message for project: {}, session: {:?}", role, project_id, session_id);
-
-    // Create message with session context
-    let mut message = ChatMessage::new_with_session(
-        project_id.clone(),
-        session_id.clone(),
-        role,
-        content,
-    );
-
-    // Set metadata if provided
-    if let Some(meta) = metadata {
-        message.metadata = Some(meta);
-    }
+    from: Option<i64>,
+    to: Option<i64>,
+) -> Result<Vec<TimesheetRow>, AppError> {
+    log::info!("Fetching timesheet for project: {}", project_id);
 
-    // Insert into database
-    sqlx::query(
+    let rows = sqlx::query_as::<_, TimesheetRow>(
         r#"
-        INSERT INTO chat_messages (id, project_id, session_id, role, content, timestamp, metadata)
-        VALUES (?, ?, ?, ?, ?, ?, ?)
+        SELECT date(e.started_at, 'unixepoch', 'localtime') AS day,
+               e.task_id AS task_id,
+               t.title AS task_title,
+               CAST(SUM(e.duration_seconds) AS REAL) / 3600.0 AS hours
+        FROM time_entries e
+        JOIN tasks t ON t.id = e.task_id
+        WHERE e.project_id = ?
+          AND e.ended_at IS NOT NULL
+          AND (? IS NULL OR e.started_at >= ?)
+          AND (? IS NULL OR e.started_at < ?)
+        GROUP BY day, e.task_id
+        ORDER BY day DESC, hours DESC
         "#
     )
-    .bind(&message.id)
-    .bind(&message.project_id)
-    .bind(&message.session_id)
-    .bind(&message.role)
-    .bind(&message.content)
-    .bind(message.timestamp)
-    .bind(&message.metadata)
-    .execute(db.pool())
+    .bind(&project_id)
+    .bind(from)
+    .bind(from)
+    .bind(to)
+    .bind(to)
+    .fetch_all(db.pool())
     .await
-    .map_err(|e| format!("Failed to save message: {}", e))?;
-
-    // Update project last activity
-    sqlx::query("UPDATE projects SET last_activity = ? WHERE id = ?")
-        .bind(message.timestamp)
-        .bind(&project_id)
-        .execute(db.pool())
-        .await
-        .map_err(|e| format!("Failed to update project activity: {}", e))?;
-
-    log::debug!("Updated last_activity for project {} to {}", project_id, message.timestamp);
+    .map_err(AppError::context("Failed to fetch timesheet"))?;
 
-    log::info!("Message saved: {}", message.id);
-    Ok(message)
+    Ok(rows)
 }
 
-/// Generate AI response using the AI service
-async fn generate_ai_response(user_input: &str) -> Result<String, String> {
-    use crate::ai_service::{AIService, ChatMessage};
-
-    // Create AI service (defaults to Claude from environment)
-    let ai_service = AIService::from_env();
-
-    // Check if the AI provider is available
-    if !ai_service.is_available() {
-        return Err(format!(
-            "{} CLI not found. Falling back to mock response.",
-            ai_service.provider_name()
-        ));
-    }
+// ============================================================================
+// File System Commands
+// ============================================================================
 
-    log::info!("Using {} for AI response", ai_service.provider_name());
+/// File node structure representing a file or directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileNode {
+    pub id: String,
+    pub name: String,
+    pub path: String,
+    #[serde(rename = "type")]
+    pub node_type: String, // "file" or "folder"
+    pub size: Option<u64>,
+    pub modified: Option<i64>,
+    pub children: Option<Vec<FileNode>>,
+    /// Indicates if folder has children (for lazy loading UI)
+    #[serde(rename = "hasChildren")]
+    pub has_children: Option<bool>,
+}
 
-    // Create message for the AI
-    let messages = vec![ChatMessage {
-        role: "user".to_string(),
-        content: user_input.to_string(),
-    }];
+/// Entries per directory page when the caller doesn't pass a limit
+const DEFAULT_DIR_PAGE_SIZE: usize = 1000;
 
-    // Call the AI service
-    ai_service.chat_completion(messages).await
+/// One page of a directory listing, folders first and then by name
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileNodePage {
+    pub nodes: Vec<FileNode>,
+    /// Entries in the whole directory
+    pub total: usize,
+    pub offset: usize,
 }
 
-/// Generate a mock AI response (fallback when AI service is unavailable)
-fn generate_mock_response(user_input: &str) -> String {
-    let input_lower = user_input.to_lowercase();
+/// Read a page of the project's top-level files, led by the folders of its
+/// other roots
+#[tauri::command]
+pub async fn read_project_files(
+    db: State<'_, Database>,
+    #[allow(non_snake_case)]
+    projectId: String,
+    offset: Option<usize>,
+    limit: Option<usize>,
+) -> Result<FileNodePage, AppError> {
+    log::info!("Reading files for project: {}", projectId);
 
-    if input_lower.contains("hello") || input_lower.contains("hi") {
-        return "Hello! I'm your AI coding assistant. I can help you with code, debugging, architecture questions, and more. What would you like to work on?".to_string();
-    }
+    // Get project from database to get the root path
+    let project = get_project(db.clone(), projectId.clone())
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Project not found: {}", projectId)))?;
 
-    if input_lower.contains("help") {
-        return "I can assist you with:\n\n- Writing and refactoring code\n- Debugging issues\n- Code reviews\n- Explaining complex concepts\n- Architecture decisions\n- Best practices\n\nJust let me know what you need!".to_string();
-    }
+    let root_path = Path::new(&project.root_path);
 
-    if input_lower.contains("error") || input_lower.contains("bug") {
-        return "I'd be happy to help debug that issue! To assist you better, could you:\n\n1. Share the error message\n2. Show me the relevant code\n3. Describe what you expected to happen\n\nThis will help me identify the problem quickly.".to_string();
+    // Check if path exists
+    if !root_path.exists() {
+        return Err(AppError::not_found(format!("Project path does not exist: {}", project.root_path)));
     }
 
-    if input_lower.contains("component") || input_lower.contains("react") {
-        return format!(
-            "Great! I can help you build that. Here's a starting point:\n\n```tsx\nimport {{ FC }} from 'react';\n\ninterface Props {{\n  // Define your props here\n}}\n\nexport const MyComponent: FC<Props> = (props) => {{\n  return (\n    <div className=\"p-4\">\n      {{/* Your component content */}}\n    </div>\n  );\n}};\n```\n\nWould you like me to customize this based on your specific needs?"
-        );
+    if !root_path.is_dir() {
+        return Err(AppError::invalid_input(format!("Project path is not a directory: {}", project.root_path)));
     }
 
-    // Default response
-    format!(
-        "I understand you're asking about: \"{}\"\n\nI'm a mock assistant for now, but in the full version, I'll provide detailed, context-aware responses to help with your coding tasks. This integration is working correctly - your messages are being saved to the database!\n\nIs there anything specific you'd like to know about this project?",
-        user_input
-    )
-}
+    let mut extra_roots = project_roots::list(db.pool(), &projectId).await?;
+
+    // Build the root level of the tree, respecting .gitignore, off the async runtime
+    let root = root_path.to_path_buf();
+    let page = tokio::task::spawn_blocking(move || {
+        extra_roots.retain(|extra| Path::new(&extra.path).is_dir());
+        let offset = offset.unwrap_or(0);
+        let (shown, primary_offset, primary_limit) =
+            project_roots::split_page(extra_roots.len(), offset, limit.unwrap_or(DEFAULT_DIR_PAGE_SIZE));
+        let mut page = load_folder_children(&root, &root, primary_offset, primary_limit);
+        page.nodes.splice(0..0, extra_roots[shown].iter().filter_map(extra_root_node));
+        page.total += extra_roots.len();
+        page.offset = offset;
+        page
+    })
+        .await
+        .map_err(AppError::context("Failed to read project files"))?;
 
-// ============================================================================
-// Activity Logging Commands
-// ============================================================================
+    log::info!("Successfully read {} of {} items from project root", page.nodes.len(), page.total);
 
-/// Log an activity event
-#[tauri::command]
-pub async fn log_activity(
-    db: State<'_, Database>,
-    project_id: String,
-    event_type: String,
-    description: String,
-    data: Option<String>,
-) -> Result<ActivityLog, String> {
-    log::info!("Logging activity for project {}: {} - {}", project_id, event_type, description);
+    // Check if this is the first time reading files for this project
+    // by checking if there are any file_change activity logs
+    let activity_count = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM activity_log WHERE project_id = ? AND event_type = 'file_change'"
+    )
+    .bind(&projectId)
+    .fetch_one(db.pool())
+    .await
+    .unwrap_or(0);
 
-    // Verify project exists
-    get_project(db.clone(), project_id.clone())
-        .await?
-        .ok_or_else(|| format!("Project not found: {}", project_id))?;
+    // Log activity only on first read
+    if activity_count == 0 && page.total > 0 {
+        let _ = log_activity(
+            db,
+            projectId,
+            "file_change".to_string(),
+            format!("Project files loaded ({} items)", page.total),
+            Some(serde_json::json!({
+                "file_count": page.total
+            }).to_string()),
+        ).await;
+    }
 
-    // Create activity log entry
-    let mut activity = ActivityLog::new(
-        project_id.clone(),
-        None, // session_id not required for now
-        event_type,
-        description,
-    );
+    Ok(page)
+}
 
-    // Set optional data
-    if let Some(data_value) = data {
-        activity.data = Some(data_value);
+/// Top-level node of one of a project's other roots
+///
+/// Nodes under other roots are keyed by absolute path, so their ids can't
+/// collide with the project's own relative ones.
+fn extra_root_node(root: &ProjectRoot) -> Option<FileNode> {
+    let mut node = build_file_node(Path::new(&root.path), Path::new(""))?;
+    if let Some(label) = &root.label {
+        node.name = label.clone();
     }
+    Some(node)
+}
 
-    // Insert into database
-    sqlx::query(
-        r#"
-        INSERT INTO activity_log (id, project_id, session_id, event_type, description, data, timestamp)
-        VALUES (?, ?, ?, ?, ?, ?, ?)
-        "#
-    )
-    .bind(&activity.id)
-    .bind(&activity.project_id)
-    .bind(&activity.session_id)
-    .bind(&activity.event_type)
-    .bind(&activity.description)
-    .bind(&activity.data)
-    .bind(activity.timestamp)
-    .execute(db.pool())
-    .await
-    .map_err(|e| format!("Failed to log activity: {}", e))?;
-
-    // Update project last_activity timestamp
-    sqlx::query("UPDATE projects SET last_activity = ? WHERE id = ?")
-        .bind(activity.timestamp)
-        .bind(&project_id)
-        .execute(db.pool())
-        .await
-        .map_err(|e| format!("Failed to update project activity: {}", e))?;
+/// Build a FileNode from a path (lazy loading - doesn't load children)
+fn build_file_node(path: &Path, root_path: &Path) -> Option<FileNode> {
+    let metadata = match fs::metadata(path) {
+        Ok(m) => m,
+        Err(e) => {
+            log::warn!("Failed to read metadata for {:?}: {}", path, e);
+            return None;
+        }
+    };
 
-    log::info!("Activity logged successfully: {}", activity.id);
-    Ok(activity)
-}
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("unknown")
+        .to_string();
 
-/// Get recent activities for a project
-#[tauri::command]
-pub async fn get_activities(
-    db: State<'_, Database>,
-    #[allow(non_snake_case)]
-    projectId: String,
-    limit: Option<i64>,
-) -> Result<Vec<ActivityLog>, String> {
-    log::info!("Fetching activities for project: {}", projectId);
+    // Generate a relative path-based ID
+    let relative_path = path.strip_prefix(root_path).unwrap_or(path);
+    let id = relative_path.to_string_lossy().replace('\\', "/");
 
-    let limit_value = limit.unwrap_or(30).min(100); // Default 30, max 100
+    let node_type = if metadata.is_dir() {
+        "folder"
+    } else {
+        "file"
+    };
 
-    let activities = sqlx::query_as::<_, ActivityLog>(
-        r#"
-        SELECT id, project_id, session_id, event_type, description, data, timestamp
-        FROM activity_log
-        WHERE project_id = ?
-        ORDER BY timestamp DESC
-        LIMIT ?
-        "#
-    )
-    .bind(&projectId)
-    .bind(limit_value)
-    .fetch_all(db.pool())
-    .await
-    .map_err(|e| format!("Failed to fetch activities: {}", e))?;
+    let size = if metadata.is_file() {
+        Some(metadata.len())
+    } else {
+        None
+    };
 
-    log::info!("Fetched {} activities for project {}", activities.len(), projectId);
-    Ok(activities)
-}
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64);
 
-/// Analyze an existing project directory
-#[tauri::command]
-pub async fn analyze_project_directory(path: String) -> Result<ProjectAnalysisResult, String> {
-    log::info!("Analyzing project directory: {}", path);
+    // For folders, check if they have children (for lazy loading indicator)
+    // Don't actually load children - that will be done on-demand
+    let has_children = if metadata.is_dir() {
+        Some(folder_has_children(path, root_path))
+    } else {
+        None
+    };
 
-    // Run analysis in a blocking task since it's CPU-intensive
-    let result = tokio::task::spawn_blocking(move || {
-        project_analyzer::analyze_project(&path)
+    Some(FileNode {
+        id,
+        name,
+        path: path.to_string_lossy().to_string(),
+        node_type: node_type.to_string(),
+        size,
+        modified,
+        children: None, // Children loaded lazily via get_folder_children
+        has_children,
     })
-    .await
-    .map_err(|e| format!("Failed to spawn analysis task: {}", e))?;
+}
 
-    match result {
-        Ok(analysis) => {
-            log::info!(
-                "Project analysis complete: {} files, {} languages, {} frameworks",
-                analysis.file_count,
-                analysis.detected_languages.len(),
-                analysis.detected_frameworks.len()
-            );
-            Ok(analysis)
-        }
-        Err(e) => {
-            log::error!("Project analysis failed: {}", e);
-            Err(e)
+/// Quick check if a folder has any visible children (for lazy loading UI)
+fn folder_has_children(folder_path: &Path, root_path: &Path) -> bool {
+    let walker = WalkBuilder::new(folder_path)
+        .hidden(false)
+        .git_ignore(true)
+        .git_exclude(true)
+        .ignore(true)
+        .max_depth(Some(1))
+        .build();
+
+    for entry in walker {
+        if let Ok(entry) = entry {
+            let path = entry.path();
+            // Skip the folder itself
+            if path == folder_path {
+                continue;
+            }
+            // Skip .git directory
+            if path.file_name().and_then(|s| s.to_str()) == Some(".git") {
+                continue;
+            }
+            // Found at least one child
+            return true;
         }
     }
+    false
 }
 
-/// Update existing project details with AI-generated name and description
-#[tauri::command]
-pub async fn update_project_with_ai(
-    db: State<'_, Database>,
-    project_id: String,
-) -> Result<Project, String> {
-    log::info!("Updating project {} with AI", project_id);
+/// Load a page of a folder's children, respecting .gitignore
+///
+/// Every entry is listed and sorted so pages are stable, but only the
+/// requested page is stat-ed and turned into nodes.
+fn load_folder_children(folder_path: &Path, root_path: &Path, offset: usize, limit: usize) -> FileNodePage {
+    let walker = WalkBuilder::new(folder_path)
+        .hidden(false)
+        .git_ignore(true)
+        .git_exclude(true)
+        .ignore(true)
+        .max_depth(Some(1)) // Only immediate children
+        .build();
 
-    // Get the existing project
-    let project = get_project(db.clone(), project_id.clone())
-        .await?
-        .ok_or_else(|| format!("Project not found: {}", project_id))?;
+    let mut entries: Vec<(bool, String, std::path::PathBuf)> = Vec::new();
+    for entry in walker {
+        match entry {
+            Ok(entry) => {
+                let path = entry.path();
 
-    // Run AI analysis on the project path
-    let analysis = analyze_project_with_ai(project.root_path.clone()).await?;
+                // Skip the folder itself
+                if path == folder_path {
+                    continue;
+                }
 
-    // Update the project in the database
-    sqlx::query(
-        r#"
-        UPDATE projects
-        SET name = ?, description = ?
-        WHERE id = ?
-        "#,
-    )
-    .bind(&analysis.suggested_name)
-    .bind(&analysis.suggested_description)
-    .execute(db.pool())
-    .await
-    .map_err(|e| format!("Failed to update project: {}", e))?;
+                // Skip .git directory
+                if path.file_name().and_then(|s| s.to_str()) == Some(".git") {
+                    continue;
+                }
 
-    log::info!(
-        "Updated project {} with AI: name='{}', desc='{}'",
-        project_id,
-        analysis.suggested_name,
-        analysis.suggested_description
-    );
+                // Symlinks are listed as what they point to
+                let is_dir = match entry.file_type() {
+                    Some(t) if t.is_symlink() => path.is_dir(),
+                    Some(t) => t.is_dir(),
+                    None => false,
+                };
+                let name = entry.file_name().to_string_lossy().to_lowercase();
+                entries.push((is_dir, name, entry.into_path()));
+            }
+            Err(e) => {
+                log::warn!("Error reading file entry: {}", e);
+            }
+        }
+    }
 
-    // Return the updated project
-    get_project(db, project_id)
-        .await?
-        .ok_or_else(|| "Failed to fetch updated project".to_string())
+    // Sort: folders first, then files
+    entries.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+
+    FileNodePage {
+        total: entries.len(),
+        nodes: entries
+            .iter()
+            .skip(offset)
+            .take(limit)
+            .filter_map(|(_, _, path)| build_file_node(path, root_path))
+            .collect(),
+        offset,
+    }
 }
 
-/// Analyze project with AI to generate intelligent name and description
+/// Get a page of a folder's children (for lazy loading in UI)
 #[tauri::command]
-pub async fn analyze_project_with_ai(path: String) -> Result<ProjectAnalysisResult, String> {
-    log::info!("Analyzing project with AI: {}", path);
+pub async fn get_folder_children(
+    db: State<'_, Database>,
+    #[allow(non_snake_case)]
+    projectId: String,
+    #[allow(non_snake_case)]
+    folderPath: String,
+    offset: Option<usize>,
+    limit: Option<usize>,
+) -> Result<FileNodePage, AppError> {
+    log::info!("Getting folder children for: {} in project: {}", folderPath, projectId);
 
-    // First, do the basic file-based analysis
-    let mut analysis = analyze_project_directory(path.clone()).await?;
+    // Get project from database to get the root path
+    let project = get_project(db.clone(), projectId.clone())
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Project not found: {}", projectId)))?;
 
-    // Check if AI service is available
-    use crate::ai_service::{AIService, ChatMessage};
-    let ai_service = AIService::from_env();
+    // Folders of the project's other roots are listed below that root
+    let extra_roots = project_roots::list(db.pool(), &projectId).await?;
+    let (root_path, id_base) = match project_roots::containing(&extra_roots, Path::new(&folderPath)) {
+        Some(extra) => (Path::new(&extra.path), Path::new("")),
+        None => (Path::new(&project.root_path), Path::new(&project.root_path)),
+    };
+    let resolved = resolve_in_project(root_path, &folderPath)?;
 
-    if !ai_service.is_available() {
-        log::warn!("AI service not available, using basic analysis only");
-        return Ok(analysis);
-    }
+    // Walk below the root as the project stores it, so returned paths keep its spelling
+    let canonical_root = root_path
+        .canonicalize()
+        .map_err(AppError::context("Failed to resolve project path"))?;
+    let folder_path = root_path.join(resolved.strip_prefix(&canonical_root).unwrap_or(Path::new("")));
 
-    log::info!("AI service available, generating intelligent description");
+    // Check if path exists and is a directory
+    if !folder_path.exists() {
+        return Err(AppError::not_found(format!("Folder does not exist: {}", folderPath)));
+    }
 
-    // Build a context-rich prompt for the AI
-    let prompt = format!(
-        r#"Analyze this project directory and provide a concise name and description.
-
-Project Path: {}
-Files Found: {}
-Detected Languages: {}
-Detected Frameworks: {}
-Has Git: {}
-
-Based on the above information, please provide:
-1. A short, descriptive project name (3-5 words, use kebab-case)
-2. A brief description (1-2 sentences) explaining what this project does
+    if !folder_path.is_dir() {
+        return Err(AppError::invalid_input(format!("Path is not a folder: {}", folderPath)));
+    }
 
-Format your response exactly as:
-NAME: your-project-name
-DESCRIPTION: Your project description here.
+    // Load children off the async runtime; stat-ing large folders blocks
+    let root = id_base.to_path_buf();
+    let page = tokio::task::spawn_blocking(move || {
+        load_folder_children(&folder_path, &root, offset.unwrap_or(0), limit.unwrap_or(DEFAULT_DIR_PAGE_SIZE))
+    })
+    .await
+    .map_err(AppError::context("Failed to load folder children"))?;
 
-Be concise and professional. Focus on what the project appears to do based on its tech stack."#,
-        path,
-        analysis.file_count,
-        analysis.detected_languages.join(", "),
-        analysis.detected_frameworks.join(", "),
-        if analysis.has_git { "Yes" } else { "No" }
-    );
+    log::info!("Loaded {} of {} children for folder: {}", page.nodes.len(), page.total, folderPath);
 
-    // Call AI service
-    let messages = vec![ChatMessage {
-        role: "user".to_string(),
-        content: prompt,
-    }];
+    Ok(page)
+}
 
-    match ai_service.chat_completion(messages).await {
-        Ok(response) => {
-            log::info!("AI analysis complete");
+/// Git file status
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitFileStatus {
+    pub path: String,
+    pub status: String, // "modified", "added", "deleted", "renamed", "untracked"
+}
 
-            // Parse the AI response
-            let lines: Vec<&str> = response.lines().collect();
-            for line in lines {
-                if let Some(name) = line.strip_prefix("NAME:") {
-                    analysis.suggested_name = name.trim().to_string();
-                } else if let Some(desc) = line.strip_prefix("DESCRIPTION:") {
-                    analysis.suggested_description = desc.trim().to_string();
-                }
-            }
+/// Parse one line of `git status --porcelain` into a relative path and status
+fn parse_porcelain_line(line: &str) -> Option<(&str, &'static str)> {
+    if line.len() < 4 {
+        return None;
+    }
 
-            // If parsing failed, use a fallback
-            if analysis.suggested_name.is_empty() {
-                log::warn!("Failed to parse AI name, using fallback");
-                analysis.suggested_name = path
-                    .split(&['/', '\\'][..])
-                    .last()
-                    .unwrap_or("my-project")
-                    .to_string();
-            }
+    // Git porcelain format: XY filename
+    // X = index status, Y = worktree status
+    let index_status = line.chars().next().unwrap_or(' ');
+    let worktree_status = line.chars().nth(1).unwrap_or(' ');
+    let file_path = line[3..].trim();
 
-            if analysis.suggested_description.is_empty() {
-                log::warn!("Failed to parse AI description, using fallback");
-                analysis.suggested_description = format!(
-                    "A {} project using {}",
-                    analysis.detected_languages.first().unwrap_or(&"software".to_string()),
-                    analysis.detected_frameworks.first().unwrap_or(&"custom frameworks".to_string())
-                );
-            }
+    // Handle renamed files (format: "R  old -> new")
+    let file_path = if file_path.contains(" -> ") {
+        file_path.split(" -> ").last().unwrap_or(file_path)
+    } else {
+        file_path
+    };
 
-            log::info!("AI suggested name: {}", analysis.suggested_name);
-            log::info!("AI suggested description: {}", analysis.suggested_description);
+    // Determine the status to show
+    let status = match (index_status, worktree_status) {
+        ('?', '?') => "untracked",
+        ('A', _) => "added",
+        ('D', _) | (_, 'D') => "deleted",
+        ('R', _) => "renamed",
+        ('M', _) | (_, 'M') => "modified",
+        ('C', _) => "copied",
+        ('U', _) | (_, 'U') => "conflict",
+        _ => return None, // Skip unknown statuses
+    };
 
-            Ok(analysis)
-        }
-        Err(e) => {
-            log::error!("AI analysis failed: {}, using basic analysis", e);
-            // Still return the basic analysis on error
-            Ok(analysis)
-        }
-    }
+    Some((file_path, status))
 }
 
-/// Generate project details (name and description) from project files
-/// This is used in the UI modal to preview AI-generated details before applying
+/// Get git status for uncommitted changes in a project
+///
+/// Watched projects reuse the last result until their watcher sees a change.
 #[tauri::command]
-pub async fn generate_project_details(
-    project_path: String,
-) -> Result<crate::types::AIProjectDetails, String> {
-    log::info!("Generating project details for: {}", project_path);
+pub async fn get_git_status(
+    db: State<'_, Database>,
+    git_status: State<'_, GitStatusCache>,
+    #[allow(non_snake_case)]
+    projectId: String,
+) -> Result<Vec<GitFileStatus>, AppError> {
+    if let Some(cached) = git_status.get(&projectId) {
+        return Ok(cached.as_ref().clone());
+    }
+    let generation = git_status.generation(&projectId);
 
-    let path = std::path::Path::new(&project_path);
+    log::info!("Getting git status for project: {}", projectId);
 
-    // Gather context from various files
-    let mut context_parts = Vec::new();
+    // Get project from database to get the root path
+    let project = get_project(db.clone(), projectId.clone())
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Project not found: {}", projectId)))?;
 
-    // Read package.json for name, productName, and description
-    let package_json_path = path.join("package.json");
-    if let Ok(content) = std::fs::read_to_string(&package_json_path) {
-        if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
-            if let Some(product_name) = json.get("productName").and_then(|n| n.as_str()) {
-                context_parts.push(format!("Product name: {}", product_name));
-            }
-            if let Some(name) = json.get("name").and_then(|n| n.as_str()) {
-                context_parts.push(format!("Package name: {}", name));
-            }
-            if let Some(desc) = json.get("description").and_then(|d| d.as_str()) {
-                if !desc.is_empty() {
-                    context_parts.push(format!("Package description: {}", desc));
-                }
-            }
-        }
-    }
+    let root_path = &project.root_path;
 
-    // Read Cargo.toml for Rust projects
-    let cargo_toml_path = path.join("Cargo.toml");
-    if let Ok(content) = std::fs::read_to_string(&cargo_toml_path) {
-        context_parts.push("Rust/Cargo project detected".to_string());
-        // Try to extract description from Cargo.toml
-        for line in content.lines() {
-            if line.trim().starts_with("description") {
-                if let Some(desc) = line.split('=').nth(1) {
-                    let clean_desc = desc.trim().trim_matches('"').trim_matches('\'');
-                    context_parts.push(format!("Cargo description: {}", clean_desc));
-                }
-            }
-        }
+    // Check if .git directory exists
+    let git_dir = Path::new(root_path).join(".git");
+    if !git_dir.exists() {
+        log::info!("Not a git repository: {}", root_path);
+        return Ok(Vec::new());
     }
 
-    // Read README files (try multiple variations)
-    let mut readme_found = false;
-    for readme_name in &["README.md", "readme.md", "Readme.md", "README.txt"] {
-        let readme_path = path.join(readme_name);
-        if let Ok(content) = std::fs::read_to_string(&readme_path) {
-            let preview: String = content.chars().take(1500).collect();
-            context_parts.push(format!("README: {}", preview));
-            readme_found = true;
-            break;
-        }
+    // Run git status --porcelain to get machine-readable output
+    let output = git_output(root_path, &["status", "--porcelain", "-uall"]).await?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        log::warn!("Git status failed: {}", stderr);
+        return Ok(Vec::new());
     }
 
-    // If no standard README found, look for any markdown files that might describe the project
-    if !readme_found {
-        if let Ok(entries) = std::fs::read_dir(path) {
-            let mut doc_files: Vec<_> = entries
-                .filter_map(|e| e.ok())
-                .filter(|e| {
-                    if let Some(name) = e.file_name().to_str() {
-                        let name_upper = name.to_uppercase();
-                        // Look for files that might contain project info
-                        (name_upper.starts_with("README") ||
-                         name_upper.contains("OVERVIEW") ||
-                         name_upper.contains("ABOUT") ||
-                         name_upper.contains("INTRO")) &&
-                        name.ends_with(".md")
-                    } else {
-                        false
-                    }
-                })
-                .collect();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut statuses = Vec::new();
 
-            // Sort by name to get consistent results
-            doc_files.sort_by_key(|e| e.file_name());
+    for line in stdout.lines() {
+        let Some((file_path, status)) = parse_porcelain_line(line) else {
+            continue;
+        };
 
-            // Read the first matching doc file
-            if let Some(doc_file) = doc_files.first() {
-                if let Ok(content) = std::fs::read_to_string(doc_file.path()) {
-                    let preview: String = content.chars().take(1500).collect();
-                    if let Some(name) = doc_file.file_name().to_str() {
-                        context_parts.push(format!("Documentation from {}: {}", name, preview));
-                    }
-                }
-            }
-        }
-    }
+        // Convert to absolute path
+        let absolute_path = Path::new(root_path).join(file_path);
 
-    // Get project analysis for tech stack
-    let project_path_clone = project_path.clone();
-    let analysis = tokio::task::spawn_blocking(move || {
-        project_analyzer::analyze_project(&project_path_clone)
-    })
-    .await
-    .map_err(|e| format!("Failed to analyze project: {}", e))?
-    .map_err(|e| format!("Project analysis failed: {}", e))?;
+        statuses.push(GitFileStatus {
+            path: absolute_path.to_string_lossy().to_string(),
+            status: status.to_string(),
+        });
+    }
 
-    context_parts.push(format!("Languages: {}", analysis.detected_languages.join(", ")));
-    context_parts.push(format!("Frameworks: {}", analysis.detected_frameworks.join(", ")));
+    log::info!("Found {} uncommitted changes", statuses.len());
 
-    // Build AI prompt
-    let context = context_parts.join("\n");
-    let prompt = format!(
-        r#"You are analyzing a software project. Based on the information provided, determine the proper display name and create a clear description.
+    git_status.insert(&projectId, generation, std::sync::Arc::new(statuses.clone()));
+    Ok(statuses)
+}
 
-Project Information:
-{}
+/// How long a git invocation may run before it's killed
+const GIT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
 
-Instructions:
-1. Determine the display name:
-   - If a "Product name" is listed, use that (it's the user-facing name)
-   - Otherwise, use the most meaningful name you can find from the README or documentation
-   - Convert technical names to proper Title Case (e.g., "ateliercode" → "Atelier Code")
-   - Avoid using npm package names like "@scope/package" - extract the meaningful part
+/// Run git in the project without blocking the async runtime, killing it after [`GIT_TIMEOUT`]
+async fn git_output(root_path: &str, args: &[&str]) -> Result<std::process::Output, AppError> {
+    let output = crate::cli_command::command("git", args)
+        .current_dir(root_path)
+        .kill_on_drop(true)
+        .output();
 
-2. Write a concise description (1-2 sentences):
-   - Explain what the application actually does for end users
-   - Focus on the main purpose and value, not technical details
-   - Base this on README content and documentation
+    tokio::time::timeout(GIT_TIMEOUT, output)
+        .await
+        .map_err(|_| AppError::process(format!("git {} timed out after {}s", args.first().unwrap_or(&""), GIT_TIMEOUT.as_secs())))?
+        .map_err(AppError::context("Failed to run git"))
+}
 
-Format your response EXACTLY as shown below (use these exact prefixes):
-NAME: Your Project Name
-DESCRIPTION: Your project description here.
+/// Run git in the project and return its stdout, or stderr as the error
+async fn run_git(root_path: &str, args: &[&str]) -> Result<String, AppError> {
+    let output = git_output(root_path, args).await?;
 
-Example good responses:
-NAME: Atelier Code
-DESCRIPTION: A desktop application for managing AI-assisted software development workflows.
+    if !output.status.success() {
+        return Err(AppError::process(format!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
 
-NAME: Remote Portals
-DESCRIPTION: A secure streaming platform for remote medical imaging and diagnostics."#,
-        context
-    );
+/// Commit changes in a project and return the new commit hash
+///
+/// Commits `files` (relative paths) when given, otherwise everything in the
+/// working tree. When the project requires review before commit, files with
+/// unreviewed changes are refused unless `override_review` is set; overrides
+/// are recorded in the activity log.
+#[tauri::command]
+pub async fn git_commit(
+    db: State<'_, Database>,
+    git_status: State<'_, GitStatusCache>,
+    project_id: String,
+    message: String,
+    files: Option<Vec<String>>,
+    override_review: Option<bool>,
+) -> Result<String, AppError> {
+    log::info!("Committing changes in project: {}", project_id);
 
-    log::info!("========== AI PROMPT ==========");
-    log::info!("{}", prompt);
-    log::info!("==============================");
+    if message.trim().is_empty() {
+        return Err(AppError::invalid_input("Commit message cannot be empty"));
+    }
 
-    // Try to use AI service
-    use crate::ai_service::{AIService, ChatMessage};
-    let ai_service = AIService::from_env();
+    let project = get_project(db.clone(), project_id.clone())
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Project not found: {}", project_id)))?;
+    let root_path = project.root_path.as_str();
+
+    let paths: Vec<String> = match &files {
+        Some(files) => files.iter().map(|f| f.replace('\\', "/")).collect(),
+        None => run_git(root_path, &["status", "--porcelain", "-uall"]).await?
+            .lines()
+            .filter_map(parse_porcelain_line)
+            .map(|(path, _)| path.to_string())
+            .collect(),
+    };
+    if paths.is_empty() {
+        return Err(AppError::invalid_input("Nothing to commit"));
+    }
 
-    let (name, description) = if ai_service.is_available() {
-        log::info!("AI service available, generating intelligent details");
+    let settings = crate::models::ProjectSettings::from_json(project.settings.as_deref());
+    if settings.require_review_before_commit {
+        let pending: std::collections::HashSet<String> = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT DISTINCT file_path
+            FROM file_changes
+            WHERE project_id = ? AND reviewed = FALSE AND stale = FALSE
+            "#
+        )
+        .bind(&project_id)
+        .fetch_all(db.pool())
+        .await
+        .map_err(AppError::context("Failed to fetch pending changes"))?
+        .into_iter()
+        .collect();
 
-        let messages = vec![ChatMessage {
-            role: "user".to_string(),
-            content: prompt,
-        }];
+        let unreviewed: Vec<&String> = paths.iter().filter(|p| pending.contains(*p)).collect();
+        if !unreviewed.is_empty() {
+            if !override_review.unwrap_or(false) {
+                return Err(AppError::conflict(format!(
+                    "Review required before commit; unreviewed changes in: {}",
+                    unreviewed.iter().map(|p| p.as_str()).collect::<Vec<_>>().join(", ")
+                )));
+            }
 
-        match ai_service.chat_completion(messages).await {
-            Ok(response) => {
-                log::info!("========== AI RESPONSE ==========");
-                log::info!("{}", response);
-                log::info!("=================================");
+            log::warn!("Committing {} unreviewed files with review override", unreviewed.len());
+            log_activity(
+                db.clone(),
+                project_id.clone(),
+                "review_override".to_string(),
+                format!("Committed {} files without review", unreviewed.len()),
+                Some(serde_json::json!({ "files": unreviewed, "message": message }).to_string()),
+            )
+            .await?;
+        }
+    }
 
-                // Parse the AI response - try multiple formats
-                let mut parsed_name = String::new();
-                let mut parsed_description = String::new();
+    let (mut add_args, mut commit_args) = (vec!["add", "-A"], vec!["commit", "-m", message.as_str()]);
+    if files.is_some() {
+        for args in [&mut add_args, &mut commit_args] {
+            args.push("--");
+            args.extend(paths.iter().map(String::as_str));
+        }
+    }
+    let committed = match run_git(root_path, &add_args).await {
+        Ok(_) => run_git(root_path, &commit_args).await,
+        Err(e) => Err(e),
+    };
+    // Even a failed commit may have staged files
+    git_status.invalidate(&project_id);
+    committed?;
 
-                for line in response.lines() {
-                    let line_upper = line.to_uppercase();
+    let hash = run_git(root_path, &["rev-parse", "HEAD"]).await?.trim().to_string();
+    log::info!("Created commit {} in {}", hash, root_path);
+    Ok(hash)
+}
 
-                    // Try "NAME:" prefix (case insensitive)
-                    if line_upper.starts_with("NAME:") {
-                        parsed_name = line[5..].trim().to_string();
-                        log::info!("Parsed name from 'NAME:' line: '{}'", parsed_name);
-                    }
-                    // Try "DESCRIPTION:" prefix (case insensitive)
-                    else if line_upper.starts_with("DESCRIPTION:") {
-                        parsed_description = line[12..].trim().to_string();
-                        log::info!("Parsed description from 'DESCRIPTION:' line: '{}'", parsed_description);
-                    }
-                    // Also try to continue multiline descriptions
-                    else if !parsed_description.is_empty() && !line.trim().is_empty() && !line_upper.starts_with("NAME") {
-                        parsed_description.push(' ');
-                        parsed_description.push_str(line.trim());
-                    }
-                }
+/// Read and return file content (transcoded to UTF-8)
+#[tauri::command]
+pub async fn read_file_content(
+    db: State<'_, Database>,
+    #[allow(non_snake_case)]
+    projectId: String,
+    #[allow(non_snake_case)]
+    filePath: String,
+) -> Result<String, AppError> {
+    let decoded = read_file_with_encoding(db, projectId, filePath).await?;
+    Ok(decoded.content)
+}
 
-                log::info!("Parsed results - name: '{}', description: '{}'", parsed_name, parsed_description);
+/// Read file content along with its detected original encoding
+#[tauri::command]
+pub async fn read_file_with_encoding(
+    db: State<'_, Database>,
+    #[allow(non_snake_case)]
+    projectId: String,
+    #[allow(non_snake_case)]
+    filePath: String,
+) -> Result<crate::file_encoding::DecodedText, AppError> {
+    log::info!("Reading file content for project {}: {}", projectId, filePath);
 
-                // Fallback if parsing failed
-                if parsed_name.is_empty() {
-                    log::warn!("Failed to parse NAME from AI response, using fallback");
-                    parsed_name = project_analyzer::extract_project_name(path)
-                        .unwrap_or_else(|| path.file_name()
-                            .and_then(|n| n.to_str())
-                            .unwrap_or("My Project")
-                            .to_string());
-                }
+    // Get project from database to verify it exists
+    let project = get_project(db.clone(), projectId.clone())
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Project not found: {}", projectId)))?;
 
-                if parsed_description.is_empty() {
-                    log::warn!("Failed to parse DESCRIPTION from AI response, using fallback");
-                    parsed_description = analysis.suggested_description;
-                }
+    let (_, canonical_target) = project_roots::resolve(db.pool(), &projectId, &project.root_path, &filePath).await?;
 
-                (parsed_name, parsed_description)
+    // Check if file exists and is a file
+    if !canonical_target.exists() {
+        return Err(AppError::not_found(format!("File not found: {}", filePath)));
+    }
+
+    if !canonical_target.is_file() {
+        return Err(AppError::invalid_input(format!("Path is not a file: {}", filePath)));
+    }
+
+    // Read raw bytes so non-UTF-8 files can be detected and transcoded
+    let bytes = fs::read(&canonical_target)
+        .map_err(AppError::context("Failed to read file"))?;
+
+    // Limit file size to prevent memory issues (e.g., 10MB)
+    if bytes.len() > 10 * 1024 * 1024 {
+        return Err(AppError::invalid_input("File too large to preview (max 10MB)"));
+    }
+
+    let decoded = crate::file_encoding::decode_bytes(&bytes);
+
+    log::info!(
+        "Successfully read file: {} ({} bytes, encoding: {})",
+        filePath,
+        bytes.len(),
+        decoded.encoding
+    );
+    Ok(decoded)
+}
+
+/// Write file content, re-encoding it to the file's original encoding
+#[tauri::command]
+pub async fn write_file_content(
+    db: State<'_, Database>,
+    #[allow(non_snake_case)]
+    projectId: String,
+    #[allow(non_snake_case)]
+    filePath: String,
+    content: String,
+    encoding: Option<String>,
+    #[allow(non_snake_case)]
+    hasBom: Option<bool>,
+) -> Result<(), AppError> {
+    log::info!("Writing file content for project {}: {}", projectId, filePath);
+
+    let project = get_project(db, projectId.clone())
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Project not found: {}", projectId)))?;
+
+    let canonical_target = resolve_in_project(&project.root_path, &filePath)?;
+    if canonical_target.is_dir() {
+        return Err(AppError::invalid_input(format!("Path is a folder: {}", filePath)));
+    }
+
+    let encoding = encoding.unwrap_or_else(|| "UTF-8".to_string());
+    let bytes = crate::file_encoding::encode_text(&content, &encoding, hasBom.unwrap_or(false))
+        .map_err(AppError::context("Failed to encode file"))?;
+
+    fs::write(&canonical_target, &bytes)
+        .map_err(AppError::context("Failed to write file"))?;
+
+    log::info!("Successfully wrote file: {} ({} bytes, encoding: {})", filePath, bytes.len(), encoding);
+    Ok(())
+}
+
+/// Send a chat message and get AI response
+///
+/// The reply is announced by a `chat-stream-start` event carrying its message
+/// id, streamed through `chat-stream-chunk` events while it is being
+/// generated, followed by `chat-stream-done` once it has been saved. With
+/// `auto_label`, an unlabeled tab for the session is titled after its first reply.
+#[tauri::command]
+pub async fn send_message(
+    app: tauri::AppHandle,
+    db: State<'_, Database>,
+    streams: State<'_, crate::chat_stream::ChatStreamManager>,
+    project_id: String,
+    content: String,
+    session_id: Option<String>,
+    auto_label: Option<bool>,
+) -> Result<ChatMessage, AppError> {
+    log::info!("Sending message for project: {} (session: {:?})", project_id, session_id);
+
+    // Verify project exists
+    get_project(db.clone(), project_id.clone())
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Project not found: {}", project_id)))?;
+
+    // Create and save user message with session_id
+    let mut user_message = ChatMessage::new_with_session(
+        project_id.clone(),
+        session_id.clone(),
+        "user".to_string(),
+        content.clone(),
+    );
+
+    insert_chat_message(&db, &mut user_message)
+        .await
+        .map_err(AppError::context("Failed to save user message"))?;
+
+    log::info!("User message saved: {} (session: {:?})", user_message.id, session_id);
+
+    let ai_message = stream_reply(&app, &db, &streams, &project_id, &session_id, &content, serde_json::Map::new()).await?;
+
+    // Title the session's tab once the first reply is in
+    if let (Some(true), Some(sid)) = (auto_label, &session_id) {
+        tokio::spawn(auto_label_after_first_reply(db.inner().clone(), sid.clone()));
+    }
+
+    // Log activity for the message, previewing the saved (redacted) content
+    let saved = &user_message.content;
+    let _ = log_activity(
+        db,
+        project_id,
+        "message".to_string(),
+        "New message sent".to_string(),
+        Some(serde_json::json!({
+            "preview": if saved.chars().count() > 50 {
+                format!("{}...", saved.chars().take(50).collect::<String>())
+            } else {
+                saved.clone()
             }
-            Err(e) => {
-                log::warn!("AI generation failed: {}, using fallback", e);
-                // Fallback to basic extraction
-                let name = project_analyzer::extract_project_name(path)
-                    .unwrap_or_else(|| path.file_name()
-                        .and_then(|n| n.to_str())
-                        .unwrap_or("My Project")
-                        .to_string());
-                (name, analysis.suggested_description)
+        }).to_string()),
+    ).await;
+
+    Ok(ai_message)
+}
+
+/// Redactor configured for a project; the built-in rules if its settings can't be read
+pub(crate) async fn project_redactor(db: &Database, project_id: &str) -> crate::redaction::Redactor {
+    let settings = sqlx::query_scalar::<_, Option<String>>("SELECT settings FROM projects WHERE id = ?")
+        .bind(project_id)
+        .fetch_optional(db.pool())
+        .await
+        .ok()
+        .flatten()
+        .flatten();
+    crate::models::ProjectSettings::from_json(settings.as_deref()).redaction.redactor()
+}
+
+/// Insert a chat message row, masking secrets in its content first
+async fn insert_chat_message(db: &Database, message: &mut ChatMessage) -> Result<(), sqlx::Error> {
+    message.content = project_redactor(db, &message.project_id).await.redact(&message.content).into_owned();
+    insert_message_row(db.pool(), message).await
+}
+
+/// Insert a chat message row as is, copying the usage in its metadata to the
+/// columns stats read
+async fn insert_message_row<'e, E>(executor: E, message: &ChatMessage) -> Result<(), sqlx::Error>
+where
+    E: sqlx::Executor<'e, Database = sqlx::Sqlite>,
+{
+    let usage = message.parsed_metadata();
+    sqlx::query(
+        r#"
+        INSERT INTO chat_messages (id, project_id, session_id, role, content, timestamp, metadata,
+                                   model, input_tokens, output_tokens, cost_usd, duration_ms)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#
+    )
+    .bind(&message.id)
+    .bind(&message.project_id)
+    .bind(&message.session_id)
+    .bind(&message.role)
+    .bind(&message.content)
+    .bind(message.timestamp)
+    .bind(&message.metadata)
+    .bind(&usage.model)
+    .bind(usage.input_tokens.map(|t| t as i64))
+    .bind(usage.output_tokens.map(|t| t as i64))
+    .bind(usage.cost_usd)
+    .bind(usage.processing_time.map(|t| t as i64))
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// Stream an AI reply to `content`, then save it as an assistant message
+///
+/// `extra_metadata` is merged into the reply's metadata.
+#[tracing::instrument(skip_all, fields(project_id = %project_id, session_id = session_id.as_deref()))]
+async fn stream_reply(
+    app: &tauri::AppHandle,
+    db: &Database,
+    streams: &crate::chat_stream::ChatStreamManager,
+    project_id: &str,
+    session_id: &Option<String>,
+    content: &str,
+    extra_metadata: serde_json::Map<String, serde_json::Value>,
+) -> Result<ChatMessage, AppError> {
+    // Create the AI message up front so its id can identify the stream
+    let mut ai_message = ChatMessage::new_with_session(
+        project_id.to_string(),
+        session_id.clone(),
+        "assistant".to_string(),
+        String::new(),
+    );
+
+    // Track processing time
+    let start_time = std::time::Instant::now();
+
+    // Stream the AI response, forwarding chunks to the frontend as they arrive
+    let (chunk_tx, mut chunk_rx) = tokio::sync::mpsc::unbounded_channel();
+    let mut cancel_rx = streams.register(&ai_message.id);
+    if let Err(e) = app.emit(crate::chat_stream::START_EVENT, serde_json::json!({
+        "message_id": &ai_message.id,
+        "project_id": project_id,
+        "session_id": session_id
+    })) {
+        log::warn!("Failed to emit chat stream start: {}", e);
+    }
+    let generation = generate_ai_response_stream(content, chunk_tx);
+    tokio::pin!(generation);
+
+    let emit_chunk = |chunk: &str| {
+        if let Err(e) = app.emit(crate::chat_stream::CHUNK_EVENT, serde_json::json!({
+            "message_id": &ai_message.id,
+            "project_id": project_id,
+            "session_id": session_id,
+            "chunk": chunk
+        })) {
+            log::warn!("Failed to emit chat stream chunk: {}", e);
+        }
+    };
+
+    let mut streamed = String::new();
+    let outcome = loop {
+        tokio::select! {
+            result = &mut generation => break Some(result),
+            Some(chunk) = chunk_rx.recv() => {
+                emit_chunk(&chunk);
+                streamed.push_str(&chunk);
             }
+            _ = &mut cancel_rx => break None,
         }
-    } else {
-        log::info!("AI service not available, using basic extraction");
-        // Fallback to basic extraction
-        let name = project_analyzer::extract_project_name(path)
-            .unwrap_or_else(|| path.file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("My Project")
-                .to_string());
-        (name, analysis.suggested_description)
     };
+    streams.finish(&ai_message.id);
 
-    log::info!("Generated details - name: '{}', description: '{}'", name, description);
+    // Forward anything produced between the last chunk and completion
+    while let Ok(chunk) = chunk_rx.try_recv() {
+        emit_chunk(&chunk);
+        streamed.push_str(&chunk);
+    }
 
-    Ok(crate::types::AIProjectDetails {
-        name,
-        description,
-    })
+    let cancelled = outcome.is_none();
+    let mut error = None;
+    ai_message.content = match outcome {
+        Some(Ok(response)) => response,
+        Some(Err(e)) if streamed.is_empty() => {
+            log::error!("AI service error: {}", e);
+            // Fallback to mock response if AI service fails
+            let mock = generate_mock_response(content);
+            emit_chunk(&mock);
+            mock
+        }
+        Some(Err(e)) => {
+            log::error!("AI service failed mid-stream: {}", e);
+            error = Some(e.message);
+            streamed
+        }
+        None => {
+            log::info!("Streaming cancelled for message {}", ai_message.id);
+            streamed
+        }
+    };
+
+    let processing_time = start_time.elapsed().as_millis() as u64;
+    ai_message.timestamp = chrono::Utc::now().timestamp();
+
+    ai_message.set_metadata(&MessageMetadata {
+        model: Some("claude".to_string()),
+        processing_time: Some(processing_time),
+        cancelled,
+        error: error.clone(),
+        extra: extra_metadata,
+        ..Default::default()
+    });
+
+    insert_chat_message(db, &mut ai_message)
+        .await
+        .map_err(AppError::context("Failed to save AI message"))?;
+
+    log::info!("AI message saved: {} (session: {:?})", ai_message.id, session_id);
+
+    if let Err(e) = app.emit(crate::chat_stream::DONE_EVENT, serde_json::json!({
+        "message_id": &ai_message.id,
+        "project_id": project_id,
+        "session_id": session_id,
+        "message": &ai_message,
+        "cancelled": cancelled
+    })) {
+        log::warn!("Failed to emit chat stream completion: {}", e);
+    }
+
+    match &error {
+        Some(error) => {
+            crate::notifications::notify(app, db.pool(), project_id, AgentNotification::Error, error).await
+        }
+        None if !cancelled => {
+            crate::notifications::notify(app, db.pool(), project_id, AgentNotification::TurnFinished, &ai_message.content)
+                .await
+        }
+        None => {}
+    }
+
+    // Update project last activity
+    sqlx::query("UPDATE projects SET last_activity = ? WHERE id = ?")
+        .bind(chrono::Utc::now().timestamp())
+        .bind(project_id)
+        .execute(db.pool())
+        .await
+        .map_err(AppError::context("Failed to update project activity"))?;
+
+    Ok(ai_message)
 }
 
-// ============================================================================
-// Statistics Commands
-// ============================================================================
+/// Edit a user message and resend it
+///
+/// The original message is replaced by a corrected copy (the original is
+/// soft-deleted and linked from the new message's metadata). With `truncate`
+/// (the default), later messages in the conversation — the stale reply — are
+/// soft-deleted too. Returns the new assistant reply.
+#[tauri::command]
+pub async fn resend_message(
+    app: tauri::AppHandle,
+    db: State<'_, Database>,
+    streams: State<'_, crate::chat_stream::ChatStreamManager>,
+    message_id: String,
+    new_content: String,
+    truncate: Option<bool>,
+) -> Result<ChatMessage, AppError> {
+    log::info!("Resending edited message: {}", message_id);
+
+    if new_content.trim().is_empty() {
+        return Err(AppError::invalid_input("Message content cannot be empty"));
+    }
+
+    let original = fetch_message(db.pool(), &message_id).await?;
+
+    if original.role != "user" {
+        return Err(AppError::invalid_input("Only user messages can be edited"));
+    }
+
+    let now = chrono::Utc::now().timestamp();
+    let mut corrected = ChatMessage::new_with_session(
+        original.project_id.clone(),
+        original.session_id.clone(),
+        "user".to_string(),
+        new_content.clone(),
+    );
+    corrected.metadata = Some(serde_json::json!({ "edited_from": original.id }).to_string());
+    corrected.content = project_redactor(&db, &corrected.project_id).await.redact(&corrected.content).into_owned();
+
+    let mut tx = db.pool().begin().await
+        .map_err(AppError::context("Failed to start transaction"))?;
+
+    sqlx::query("UPDATE chat_messages SET deleted_at = ? WHERE id = ?")
+        .bind(now)
+        .bind(&original.id)
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::context("Failed to replace message"))?;
+
+    if truncate.unwrap_or(true) {
+        let result = sqlx::query(
+            r#"
+            UPDATE chat_messages
+            SET deleted_at = ?
+            WHERE project_id = ?
+              AND session_id IS ?
+              AND deleted_at IS NULL
+              AND (timestamp > ? OR (timestamp = ? AND role = 'assistant'))
+            "#
+        )
+        .bind(now)
+        .bind(&original.project_id)
+        .bind(&original.session_id)
+        .bind(original.timestamp)
+        .bind(original.timestamp)
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::context("Failed to remove stale replies"))?;
+
+        log::info!("Soft-deleted {} stale messages after {}", result.rows_affected(), original.id);
+    }
+
+    insert_message_row(&mut *tx, &corrected)
+        .await
+        .map_err(AppError::context("Failed to save edited message"))?;
+
+    tx.commit().await
+        .map_err(AppError::context("Failed to commit edited message"))?;
+
+    let reply = stream_reply(
+        &app,
+        &db,
+        &streams,
+        &corrected.project_id,
+        &corrected.session_id,
+        &new_content,
+        serde_json::Map::new(),
+    )
+    .await?;
+
+    log::info!("Edited message {} resent as {}", original.id, corrected.id);
+    Ok(reply)
+}
+
+/// Regenerate the reply to the last user message in a session
+///
+/// The previous replies are soft-deleted and kept (id, content, timestamp) in
+/// the `previous_responses` metadata of the new reply, which is tagged with
+/// `regenerated: true`. If the session is a running agent session the prompt is
+/// re-sent to the agent with the plugin's flag settings and `None` is returned;
+/// the new reply then arrives through the agent's output like any other, so
+/// the replaced replies are kept in the prompt's `previous_responses` instead.
+#[tauri::command]
+pub async fn regenerate_response(
+    app: tauri::AppHandle,
+    db: State<'_, Database>,
+    streams: State<'_, crate::chat_stream::ChatStreamManager>,
+    agent_manager: State<'_, crate::agent_manager::AgentManager>,
+    plugin_settings_manager: State<'_, crate::plugin_settings::PluginSettingsManager>,
+    session_id: String,
+    plugin_name: Option<String>,
+) -> Result<Option<ChatMessage>, AppError> {
+    log::info!("Regenerating last response for session: {}", session_id);
+
+    let prompt = sqlx::query_as::<_, ChatMessage>(&format!(
+        r#"
+        SELECT {}
+        FROM chat_messages
+        WHERE session_id = ? AND role = 'user' AND deleted_at IS NULL
+        ORDER BY timestamp DESC, id DESC
+        LIMIT 1
+        "#,
+        MESSAGE_COLUMNS
+    ))
+    .bind(&session_id)
+    .fetch_optional(db.pool())
+    .await
+    .map_err(AppError::context("Failed to fetch last user message"))?
+    .ok_or_else(|| AppError::not_found(format!("No user message to regenerate in session: {}", session_id)))?;
+
+    let previous = sqlx::query_as::<_, ChatMessage>(&format!(
+        r#"
+        SELECT {}
+        FROM chat_messages
+        WHERE session_id = ? AND role = 'assistant' AND deleted_at IS NULL AND timestamp >= ?
+        ORDER BY timestamp ASC, id ASC
+        "#,
+        MESSAGE_COLUMNS
+    ))
+    .bind(&session_id)
+    .bind(prompt.timestamp)
+    .fetch_all(db.pool())
+    .await
+    .map_err(AppError::context("Failed to fetch previous responses"))?;
+
+    let previous_responses: Vec<serde_json::Value> = previous
+        .iter()
+        .map(|m| serde_json::json!({ "id": m.id, "content": m.content, "timestamp": m.timestamp }))
+        .collect();
+    let agent_running = agent_manager.get_session_status(&session_id).await.is_ok();
+
+    let mut tx = db.pool().begin().await
+        .map_err(AppError::context("Failed to start transaction"))?;
+
+    let now = chrono::Utc::now().timestamp();
+    for message in &previous {
+        sqlx::query("UPDATE chat_messages SET deleted_at = ? WHERE id = ?")
+            .bind(now)
+            .bind(&message.id)
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::context("Failed to replace previous response"))?;
+    }
+
+    if agent_running && !previous_responses.is_empty() {
+        let mut metadata = prompt
+            .metadata
+            .as_deref()
+            .and_then(|m| serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(m).ok())
+            .unwrap_or_default();
+        match metadata.get_mut("previous_responses").and_then(|v| v.as_array_mut()) {
+            Some(kept) => kept.extend(previous_responses.iter().cloned()),
+            None => {
+                metadata.insert("previous_responses".to_string(), previous_responses.clone().into());
+            }
+        }
+        sqlx::query("UPDATE chat_messages SET metadata = ? WHERE id = ?")
+            .bind(serde_json::Value::Object(metadata).to_string())
+            .bind(&prompt.id)
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::context("Failed to keep previous responses"))?;
+    }
+
+    tx.commit().await
+        .map_err(AppError::context("Failed to commit replaced responses"))?;
+    log::info!("Replaced {} previous responses", previous.len());
+
+    if agent_running {
+        let flag_settings = plugin_name.as_ref().map(|name| {
+            plugin_settings_manager.get_plugin_settings(name).flags
+        });
+
+        agent_manager
+            .send_message(&session_id, prompt.content.clone(), flag_settings)
+            .await
+            .map_err(AppError::context("Failed to resend prompt to agent"))?;
+
+        log::info!("Prompt {} resent to agent session {}", prompt.id, session_id);
+        return Ok(None);
+    }
+
+    let mut metadata = serde_json::Map::new();
+    metadata.insert("regenerated".to_string(), serde_json::json!(true));
+    metadata.insert("regenerated_from".to_string(), serde_json::json!(prompt.id));
+    metadata.insert("previous_responses".to_string(), previous_responses.into());
+
+    let reply = stream_reply(
+        &app,
+        &db,
+        &streams,
+        &prompt.project_id,
+        &prompt.session_id,
+        &prompt.content,
+        metadata,
+    )
+    .await?;
+
+    log::info!("Regenerated response {} for prompt {}", reply.id, prompt.id);
+    Ok(Some(reply))
+}
+
+/// Cancel a reply that `send_message` is still streaming
+///
+/// The partial reply is kept and saved with `cancelled` set in its metadata.
+/// Returns false if the stream had already finished.
+#[tauri::command]
+pub async fn cancel_message_stream(
+    streams: State<'_, crate::chat_stream::ChatStreamManager>,
+    message_id: String,
+) -> Result<bool, AppError> {
+    log::info!("Cancelling message stream: {}", message_id);
+    Ok(streams.cancel(&message_id))
+}
+
+/// Get a project's most recent chat messages, one default-sized page
+///
+/// Use `get_project_messages` to page further back.
+#[tauri::command]
+pub async fn get_messages(
+    db: State<'_, Database>,
+    project_id: String,
+) -> Result<Vec<ChatMessage>, AppError> {
+    log::info!("Fetching messages for project: {}", project_id);
+
+    let page = fetch_message_page(&db, Some(&project_id), None, None, None, None).await?;
+
+    log::info!("Fetched {} messages for project", page.messages.len());
+    Ok(page.messages)
+}
+
+/// Number of messages returned per page when no limit is given
+const DEFAULT_MESSAGE_PAGE_SIZE: i64 = 20;
+/// Upper bound on the page size a caller can request
+const MAX_MESSAGE_PAGE_SIZE: i64 = 200;
+
+/// Columns a `ChatMessage` is read from
+const MESSAGE_COLUMNS: &str = "id, project_id, session_id, role, content, timestamp, metadata";
+
+/// Fetch a message, failing if it doesn't exist or was deleted
+async fn fetch_message(pool: &sqlx::SqlitePool, message_id: &str) -> Result<ChatMessage, AppError> {
+    sqlx::query_as::<_, ChatMessage>(&format!(
+        "SELECT {} FROM chat_messages WHERE id = ? AND deleted_at IS NULL",
+        MESSAGE_COLUMNS
+    ))
+    .bind(message_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(AppError::context("Failed to fetch message"))?
+    .ok_or_else(|| AppError::not_found(format!("Message not found: {}", message_id)))
+}
+
+/// Fetch a session's messages oldest first, skipping the first `offset`
+///
+/// Without a `limit` the rest of the session is returned.
+async fn fetch_session_messages(
+    pool: &sqlx::SqlitePool,
+    session_id: &str,
+    offset: i64,
+    limit: Option<i64>,
+) -> Result<Vec<ChatMessage>, AppError> {
+    // SQLite reads a negative LIMIT as no limit
+    sqlx::query_as::<_, ChatMessage>(&format!(
+        r#"
+        SELECT {}
+        FROM chat_messages
+        WHERE session_id = ? AND deleted_at IS NULL
+        ORDER BY timestamp ASC, id ASC
+        LIMIT ? OFFSET ?
+        "#,
+        MESSAGE_COLUMNS
+    ))
+    .bind(session_id)
+    .bind(limit.unwrap_or(-1))
+    .bind(offset.max(0))
+    .fetch_all(pool)
+    .await
+    .map_err(AppError::context("Failed to fetch messages"))
+}
+
+/// Fetch one page of messages older than the (before_timestamp, before_id) cursor
+///
+/// Messages are keyed by (timestamp, id) so pages stay stable when several
+/// messages share a timestamp. Omit the cursor to get the most recent page.
+async fn fetch_message_page(
+    db: &Database,
+    project_id: Option<&str>,
+    session_id: Option<&str>,
+    before_timestamp: Option<i64>,
+    before_id: Option<&str>,
+    limit: Option<i64>,
+) -> Result<MessagePage, AppError> {
+    let limit = limit
+        .unwrap_or(DEFAULT_MESSAGE_PAGE_SIZE)
+        .clamp(1, MAX_MESSAGE_PAGE_SIZE);
+
+    // Fetch one extra row to find out whether there are older messages
+    let mut messages = sqlx::query_as::<_, ChatMessage>(&format!(
+        r#"
+        SELECT {}
+        FROM chat_messages
+        WHERE deleted_at IS NULL
+          AND (? IS NULL OR project_id = ?)
+          AND (? IS NULL OR session_id = ?)
+          AND (? IS NULL OR timestamp < ? OR (timestamp = ? AND id < ?))
+        ORDER BY timestamp DESC, id DESC
+        LIMIT ?
+        "#,
+        MESSAGE_COLUMNS
+    ))
+    .bind(project_id)
+    .bind(project_id)
+    .bind(session_id)
+    .bind(session_id)
+    .bind(before_timestamp)
+    .bind(before_timestamp)
+    .bind(before_timestamp)
+    .bind(before_id.unwrap_or_default())
+    .bind(limit + 1)
+    .fetch_all(db.pool())
+    .await
+    .map_err(AppError::context("Failed to fetch messages"))?;
+
+    let has_more = messages.len() as i64 > limit;
+    messages.truncate(limit as usize);
+
+    // Reverse to get chronological order (oldest first)
+    messages.reverse();
+
+    Ok(MessagePage { messages, has_more })
+}
+
+/// Get a page of chat messages for a specific session
+///
+/// Pass the timestamp and id of the oldest loaded message as the cursor to
+/// load the page before it.
+#[tauri::command]
+pub async fn get_session_messages(
+    db: State<'_, Database>,
+    session_id: String,
+    before_timestamp: Option<i64>,
+    before_id: Option<String>,
+    limit: Option<i64>,
+) -> Result<MessagePage, AppError> {
+    log::info!("Fetching messages for session: {}", session_id);
+
+    let page = fetch_message_page(
+        &db,
+        None,
+        Some(&session_id),
+        before_timestamp,
+        before_id.as_deref(),
+        limit,
+    )
+    .await?;
+
+    log::info!("Fetched {} messages for session {} (has_more: {})", page.messages.len(), session_id, page.has_more);
+    Ok(page)
+}
+
+/// Get a page of project messages, optionally filtered by session
+///
+/// Uses the same (before_timestamp, before_id) cursor as `get_session_messages`.
+#[tauri::command]
+pub async fn get_project_messages(
+    db: State<'_, Database>,
+    project_id: String,
+    session_id: Option<String>,
+    before_timestamp: Option<i64>,
+    before_id: Option<String>,
+    limit: Option<i64>,
+) -> Result<MessagePage, AppError> {
+    log::info!("Fetching messages for project {} (session: {:?})", project_id, session_id);
+
+    let page = fetch_message_page(
+        &db,
+        Some(&project_id),
+        session_id.as_deref(),
+        before_timestamp,
+        before_id.as_deref(),
+        limit,
+    )
+    .await?;
+
+    log::info!("Fetched {} messages for project {} (has_more: {})", page.messages.len(), project_id, page.has_more);
+    Ok(page)
+}
+
+/// Get a session's messages by position, oldest first
+///
+/// Returns up to `limit` messages (capped like other pages) starting `offset`
+/// messages into the session; `has_more` tells whether any follow. Pair with
+/// `count_session_messages` to jump anywhere in a long session.
+#[tauri::command]
+pub async fn get_session_messages_range(
+    db: State<'_, Database>,
+    session_id: String,
+    offset: i64,
+    limit: Option<i64>,
+) -> Result<MessagePage, AppError> {
+    log::info!("Fetching messages for session {} from offset {}", session_id, offset);
+
+    let limit = limit
+        .unwrap_or(DEFAULT_MESSAGE_PAGE_SIZE)
+        .clamp(1, MAX_MESSAGE_PAGE_SIZE);
+
+    // Fetch one extra row to find out whether there are newer messages
+    let mut messages = fetch_session_messages(db.pool(), &session_id, offset, Some(limit + 1)).await?;
+    let has_more = messages.len() as i64 > limit;
+    messages.truncate(limit as usize);
+
+    log::info!("Fetched {} messages for session {} (has_more: {})", messages.len(), session_id, has_more);
+    Ok(MessagePage { messages, has_more })
+}
+
+/// Get every message in a session, oldest first
+///
+/// Unlike the paged commands this is not capped, so it is meant for loading
+/// or exporting a whole conversation on request.
+#[tauri::command]
+pub async fn get_all_session_messages(
+    db: State<'_, Database>,
+    session_id: String,
+) -> Result<Vec<ChatMessage>, AppError> {
+    log::info!("Fetching all messages for session: {}", session_id);
+
+    let messages = fetch_session_messages(db.pool(), &session_id, 0, None).await?;
+
+    log::info!("Fetched {} messages for session {}", messages.len(), session_id);
+    Ok(messages)
+}
+
+/// Estimate how much of the agent's context window a session is using
+///
+/// Counts are heuristic (see `token_estimate`), so the UI should treat them as
+/// a warning level rather than an exact figure.
+#[tauri::command]
+pub async fn estimate_session_tokens(
+    db: State<'_, Database>,
+    session_id: String,
+) -> Result<SessionTokenEstimate, AppError> {
+    use crate::token_estimate::{context_window, estimate_conversation_tokens, SUMMARIZE_THRESHOLD};
+
+    log::info!("Estimating tokens for session: {}", session_id);
+
+    let contents = sqlx::query_scalar::<_, String>(
+        "SELECT content FROM chat_messages WHERE session_id = ? AND deleted_at IS NULL"
+    )
+    .bind(&session_id)
+    .fetch_all(db.pool())
+    .await
+    .map_err(AppError::context("Failed to fetch session messages"))?;
+
+    let agent_type = sqlx::query_scalar::<_, String>(
+        "SELECT agent_type FROM agent_sessions WHERE id = ?"
+    )
+    .bind(&session_id)
+    .fetch_optional(db.pool())
+    .await
+    .map_err(AppError::context("Failed to fetch agent session"))?
+    .unwrap_or_else(|| "claude".to_string());
+
+    let estimated_tokens = estimate_conversation_tokens(contents.iter().map(String::as_str));
+    let context_window = context_window(&agent_type);
+    let usage_ratio = estimated_tokens as f64 / context_window as f64;
+
+    log::info!("Session {} uses ~{} of {} tokens", session_id, estimated_tokens, context_window);
+    Ok(SessionTokenEstimate {
+        session_id,
+        message_count: contents.len(),
+        estimated_tokens,
+        context_window,
+        usage_ratio,
+        should_summarize: usage_ratio >= SUMMARIZE_THRESHOLD,
+    })
+}
+
+/// Pin a message so it is listed by `get_pinned_messages`
+#[tauri::command]
+pub async fn pin_message(db: State<'_, Database>, message_id: String) -> Result<(), AppError> {
+    log::info!("Pinning message: {}", message_id);
+
+    let result = sqlx::query(
+        "UPDATE chat_messages SET pinned = 1, pinned_at = ? WHERE id = ? AND deleted_at IS NULL"
+    )
+    .bind(chrono::Utc::now().timestamp())
+    .bind(&message_id)
+    .execute(db.pool())
+    .await
+    .map_err(AppError::context("Failed to pin message"))?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::not_found(format!("Message not found: {}", message_id)));
+    }
+
+    Ok(())
+}
+
+/// Unpin a message
+#[tauri::command]
+pub async fn unpin_message(db: State<'_, Database>, message_id: String) -> Result<(), AppError> {
+    log::info!("Unpinning message: {}", message_id);
+
+    sqlx::query("UPDATE chat_messages SET pinned = 0, pinned_at = NULL WHERE id = ?")
+        .bind(&message_id)
+        .execute(db.pool())
+        .await
+        .map_err(AppError::context("Failed to unpin message"))?;
+
+    Ok(())
+}
+
+/// Get a project's pinned messages, most recently pinned first
+#[tauri::command]
+pub async fn get_pinned_messages(
+    db: State<'_, Database>,
+    project_id: String,
+) -> Result<Vec<ChatMessage>, AppError> {
+    log::info!("Fetching pinned messages for project: {}", project_id);
+
+    let messages = sqlx::query_as::<_, ChatMessage>(&format!(
+        r#"
+        SELECT {}
+        FROM chat_messages
+        WHERE project_id = ? AND pinned = 1 AND deleted_at IS NULL
+        ORDER BY pinned_at DESC
+        "#,
+        MESSAGE_COLUMNS
+    ))
+    .bind(&project_id)
+    .fetch_all(db.pool())
+    .await
+    .map_err(AppError::context("Failed to fetch pinned messages"))?;
+
+    log::info!("Found {} pinned messages", messages.len());
+    Ok(messages)
+}
+
+/// Remove a message from the conversation
+///
+/// The row is soft-deleted rather than removed so pagination cursors and
+/// metadata links (`edited_from`, `regenerated_from`) stay valid. A reply that
+/// is still streaming can't be deleted; cancel it first.
+#[tauri::command]
+pub async fn delete_message(
+    db: State<'_, Database>,
+    streams: State<'_, crate::chat_stream::ChatStreamManager>,
+    message_id: String,
+) -> Result<(), AppError> {
+    log::info!("Deleting message: {}", message_id);
+
+    if streams.is_streaming(&message_id) {
+        return Err(AppError::conflict("Message is still streaming; cancel it before deleting"));
+    }
+
+    let result = sqlx::query(
+        r#"
+        UPDATE chat_messages
+        SET deleted_at = ?, pinned = 0, pinned_at = NULL
+        WHERE id = ? AND deleted_at IS NULL
+        "#
+    )
+    .bind(chrono::Utc::now().timestamp())
+    .bind(&message_id)
+    .execute(db.pool())
+    .await
+    .map_err(AppError::context("Failed to delete message"))?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::not_found(format!("Message not found: {}", message_id)));
+    }
+
+    log::info!("Message deleted: {}", message_id);
+    Ok(())
+}
+
+/// Replace a message's content with a placeholder, e.g. after pasting a secret
+///
+/// The message keeps its place in the conversation; its metadata is marked
+/// `redacted` so the UI can show it as such. Deleted messages can be redacted
+/// too, since they are only soft-deleted, and copies of the content kept in
+/// other messages' `previous_responses` are redacted with them.
+#[tauri::command]
+pub async fn redact_message(
+    db: State<'_, Database>,
+    streams: State<'_, crate::chat_stream::ChatStreamManager>,
+    message_id: String,
+) -> Result<ChatMessage, AppError> {
+    log::info!("Redacting message: {}", message_id);
+
+    if streams.is_streaming(&message_id) {
+        return Err(AppError::conflict("Message is still streaming; cancel it before redacting"));
+    }
+
+    let message = redact_stored_message(db.pool(), &message_id).await?;
+
+    log::info!("Message redacted: {}", message_id);
+    Ok(message)
+}
+
+/// Redact a message, deleted or not, along with the copies other messages keep
+async fn redact_stored_message(pool: &sqlx::SqlitePool, message_id: &str) -> Result<ChatMessage, AppError> {
+    let mut message = sqlx::query_as::<_, ChatMessage>(&format!(
+        "SELECT {} FROM chat_messages WHERE id = ?",
+        MESSAGE_COLUMNS
+    ))
+    .bind(message_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(AppError::context("Failed to fetch message"))?
+    .ok_or_else(|| AppError::not_found(format!("Message not found: {}", message_id)))?;
+
+    // Keep existing metadata (processing time, links) but drop anything that
+    // could echo the redacted content
+    let mut metadata = message
+        .metadata
+        .as_deref()
+        .and_then(|m| serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(m).ok())
+        .unwrap_or_default();
+    metadata.remove("previous_responses");
+    metadata.insert("redacted".to_string(), serde_json::json!(true));
+    metadata.insert("redacted_at".to_string(), serde_json::json!(chrono::Utc::now().timestamp()));
+
+    message.content = "[redacted]".to_string();
+    message.metadata = Some(serde_json::Value::Object(metadata).to_string());
+
+    let mut tx = pool.begin().await
+        .map_err(AppError::context("Failed to start transaction"))?;
+
+    sqlx::query("UPDATE chat_messages SET content = ?, metadata = ? WHERE id = ?")
+        .bind(&message.content)
+        .bind(&message.metadata)
+        .bind(&message.id)
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::context("Failed to redact message"))?;
+
+    // Regenerated replies and agent prompts keep the replies they replaced
+    let holders = sqlx::query_as::<_, (String, String)>(
+        "SELECT id, metadata FROM chat_messages WHERE project_id = ? AND id != ? AND metadata LIKE ?"
+    )
+    .bind(&message.project_id)
+    .bind(&message.id)
+    .bind(format!("%{}%", message.id))
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(AppError::context("Failed to find copies of the message"))?;
+    for (holder_id, holder_metadata) in holders {
+        if let Some(updated) = redact_previous_response(&holder_metadata, &message.id) {
+            sqlx::query("UPDATE chat_messages SET metadata = ? WHERE id = ?")
+                .bind(updated)
+                .bind(&holder_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(AppError::context("Failed to redact copies of the message"))?;
+        }
+    }
+
+    tx.commit().await
+        .map_err(AppError::context("Failed to commit redaction"))?;
+
+    Ok(message)
+}
+
+/// `metadata` with the `previous_responses` entry for `message_id` redacted,
+/// or None if it has no such entry
+fn redact_previous_response(metadata: &str, message_id: &str) -> Option<String> {
+    let mut metadata: serde_json::Map<String, serde_json::Value> = serde_json::from_str(metadata).ok()?;
+    let mut redacted = false;
+    for entry in metadata.get_mut("previous_responses")?.as_array_mut()? {
+        if entry.get("id").and_then(|id| id.as_str()) == Some(message_id) {
+            entry["content"] = serde_json::json!("[redacted]");
+            redacted = true;
+        }
+    }
+    redacted.then(|| serde_json::Value::Object(metadata).to_string())
+}
+
+/// Count total messages for a session (not limited)
+#[tauri::command]
+pub async fn count_session_messages(
+    db: State<'_, Database>,
+    session_id: String,
+) -> Result<i64, AppError> {
+    log::info!("Counting messages for session: {}", session_id);
+
+    let count = sqlx::query_scalar::<_, i64>(
+        r#"
+        SELECT COUNT(*)
+        FROM chat_messages
+        WHERE session_id = ? AND deleted_at IS NULL
+        "#
+    )
+    .bind(&session_id)
+    .fetch_one(db.pool())
+    .await
+    .map_err(AppError::context("Failed to count session messages"))?;
+
+    log::info!("Session {} has {} messages", session_id, count);
+    Ok(count)
+}
+
+/// Export a conversation to Markdown at the given path, marking the
+/// session's bookmarks
+///
+/// Accepts either a chat tab id or a session id.
+#[tauri::command]
+pub async fn export_chat(
+    db: State<'_, Database>,
+    session_id_or_tab_id: String,
+    path: String,
+    options: Option<ChatExportOptions>,
+) -> Result<String, AppError> {
+    log::info!("Exporting chat {} to {}", session_id_or_tab_id, path);
+
+    let options = options.unwrap_or_default();
+
+    let tab = fetch_chat_tab(db.pool(), &session_id_or_tab_id).await?;
+
+    let session_id = match &tab {
+        Some(tab) => tab
+            .session_id
+            .clone()
+            .ok_or_else(|| AppError::not_found(format!("Chat tab has no session: {}", tab.id)))?,
+        None => session_id_or_tab_id.clone(),
+    };
+
+    let messages = fetch_session_messages(db.pool(), &session_id, 0, None).await?;
+
+    let first = messages
+        .first()
+        .ok_or_else(|| AppError::not_found(format!("No messages found for {}", session_id_or_tab_id)))?;
+
+    let title = match (&options.title, tab.as_ref().and_then(|t| t.label.clone())) {
+        (Some(title), _) => title.clone(),
+        (None, Some(label)) => label,
+        (None, None) => get_project(db.clone(), first.project_id.clone())
+            .await?
+            .map(|p| format!("{} — Conversation", p.name))
+            .unwrap_or_else(|| "Conversation".to_string()),
+    };
+
+    let bookmarks = crate::session_replay::list_bookmarks(db.pool(), &session_id).await?;
+    let markdown = crate::chat_export::render_markdown(&title, &messages, &bookmarks, &options);
+    fs::write(&path, markdown)
+        .map_err(AppError::context("Failed to write export file"))?;
+
+    log::info!("Exported {} messages to {}", messages.len(), path);
+    Ok(path)
+}
+
+/// Save a message with session context
+#[tauri::command]
+pub async fn save_message(
+    db: State<'_, Database>,
+    project_id: String,
+    session_id: Option<String>,
+    role: String,
+    content: String,
+    metadata: Option<String>,
+) -> Result<ChatMessage, AppError> {
+    log::info!("Saving {} message for project: {}, session: {:?}", role, project_id, session_id);
+
+    // Create message with session context
+    let mut message = ChatMessage::new_with_session(
+        project_id.clone(),
+        session_id.clone(),
+        role,
+        content,
+    );
+
+    // Store metadata in the same shape send_message writes
+    if let Some(meta) = metadata {
+        let parsed = MessageMetadata::from_json(&meta)
+            .map_err(|e| AppError::invalid_input(format!("Message metadata isn't valid JSON: {}", e)))?;
+        message.set_metadata(&parsed);
+    }
+
+    insert_chat_message(&db, &mut message)
+        .await
+        .map_err(AppError::context("Failed to save message"))?;
+
+    // Update project last activity
+    sqlx::query("UPDATE projects SET last_activity = ? WHERE id = ?")
+        .bind(message.timestamp)
+        .bind(&project_id)
+        .execute(db.pool())
+        .await
+        .map_err(AppError::context("Failed to update project activity"))?;
+
+    log::debug!("Updated last_activity for project {} to {}", project_id, message.timestamp);
+
+    log::info!("Message saved: {}", message.id);
+    Ok(message)
+}
+
+/// Generate an AI response, sending chunks to `chunks` as they are produced
+async fn generate_ai_response_stream(
+    user_input: &str,
+    chunks: tokio::sync::mpsc::UnboundedSender<String>,
+) -> Result<String, AppError> {
+    use crate::ai_service::{AIService, ChatMessage};
+
+    let ai_service = AIService::from_env();
+
+    if !ai_service.is_available() {
+        return Err(AppError::not_found(format!(
+            "{} CLI not found. Falling back to mock response.",
+            ai_service.provider_name()
+        )));
+    }
+
+    log::info!("Streaming response from {}", ai_service.provider_name());
+
+    let messages = vec![ChatMessage {
+        role: "user".to_string(),
+        content: user_input.to_string(),
+    }];
+
+    Ok(ai_service.chat_completion_stream(messages, chunks).await?)
+}
+
+/// Generate a mock AI response (fallback when AI service is unavailable)
+fn generate_mock_response(user_input: &str) -> String {
+    let input_lower = user_input.to_lowercase();
+
+    if input_lower.contains("hello") || input_lower.contains("hi") {
+        return "Hello! I'm your AI coding assistant. I can help you with code, debugging, architecture questions, and more. What would you like to work on?".to_string();
+    }
+
+    if input_lower.contains("help") {
+        return "I can assist you with:\n\n- Writing and refactoring code\n- Debugging issues\n- Code reviews\n- Explaining complex concepts\n- Architecture decisions\n- Best practices\n\nJust let me know what you need!".to_string();
+    }
+
+    if input_lower.contains("error") || input_lower.contains("bug") {
+        return "I'd be happy to help debug that issue! To assist you better, could you:\n\n1. Share the error message\n2. Show me the relevant code\n3. Describe what you expected to happen\n\nThis will help me identify the problem quickly.".to_string();
+    }
+
+    if input_lower.contains("component") || input_lower.contains("react") {
+        return format!(
+            "Great! I can help you build that. Here's a starting point:\n\n```tsx\nimport {{ FC }} from 'react';\n\ninterface Props {{\n  // Define your props here\n}}\n\nexport const MyComponent: FC<Props> = (props) => {{\n  return (\n    <div className=\"p-4\">\n      {{/* Your component content */}}\n    </div>\n  );\n}};\n```\n\nWould you like me to customize this based on your specific needs?"
+        );
+    }
+
+    // Default response
+    format!(
+        "I understand you're asking about: \"{}\"\n\nI'm a mock assistant for now, but in the full version, I'll provide detailed, context-aware responses to help with your coding tasks. This integration is working correctly - your messages are being saved to the database!\n\nIs there anything specific you'd like to know about this project?",
+        user_input
+    )
+}
+
+// ============================================================================
+// Activity Logging Commands
+// ============================================================================
+
+/// Log an activity event
+#[tauri::command]
+pub async fn log_activity(
+    db: State<'_, Database>,
+    project_id: String,
+    event_type: String,
+    description: String,
+    data: Option<String>,
+) -> Result<ActivityLog, AppError> {
+    log::info!("Logging activity for project {}: {} - {}", project_id, event_type, description);
+
+    // Verify project exists
+    get_project(db.clone(), project_id.clone())
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Project not found: {}", project_id)))?;
+
+    // Create activity log entry
+    let mut activity = ActivityLog::new(
+        project_id.clone(),
+        None, // session_id not required for now
+        event_type,
+        description,
+    );
+
+    // Set optional data
+    if let Some(data_value) = data {
+        activity.data = Some(data_value);
+    }
+
+    // Insert into database and notify activity listeners
+    crate::activity::insert_activity(db.pool(), &activity)
+        .await
+        .map_err(AppError::context("Failed to log activity"))?;
+
+    // Update project last_activity timestamp
+    sqlx::query("UPDATE projects SET last_activity = ? WHERE id = ?")
+        .bind(activity.timestamp)
+        .bind(&project_id)
+        .execute(db.pool())
+        .await
+        .map_err(AppError::context("Failed to update project activity"))?;
+
+    log::info!("Activity logged successfully: {}", activity.id);
+    Ok(activity)
+}
+
+/// Get activities for a project, newest first
+///
+/// `event_types` keeps only those types, and `since`/`until` bound the
+/// timestamp (inclusive). For the next page, pass the timestamp and id of the
+/// last activity received as `before_timestamp` and `before_id`.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn get_activities(
+    db: State<'_, Database>,
+    #[allow(non_snake_case)]
+    projectId: String,
+    limit: Option<i64>,
+    event_types: Option<Vec<String>>,
+    since: Option<i64>,
+    until: Option<i64>,
+    before_timestamp: Option<i64>,
+    before_id: Option<String>,
+) -> Result<Vec<ActivityLog>, AppError> {
+    log::info!("Fetching activities for project: {}", projectId);
+
+    let limit_value = limit.unwrap_or(30).min(100); // Default 30, max 100
+    let event_types = event_types
+        .filter(|types| !types.is_empty())
+        .map(|types| serde_json::json!(types).to_string());
+
+    let activities = sqlx::query_as::<_, ActivityLog>(
+        r#"
+        SELECT id, project_id, session_id, event_type, description, data, timestamp
+        FROM activity_log
+        WHERE project_id = ?
+          AND (?2 IS NULL OR event_type IN (SELECT value FROM json_each(?2)))
+          AND (?3 IS NULL OR timestamp >= ?3)
+          AND (?4 IS NULL OR timestamp <= ?4)
+          AND (?5 IS NULL OR timestamp < ?5 OR (timestamp = ?5 AND id < ?6))
+        ORDER BY timestamp DESC, id DESC
+        LIMIT ?7
+        "#
+    )
+    .bind(&projectId)
+    .bind(&event_types)
+    .bind(since)
+    .bind(until)
+    .bind(before_timestamp)
+    .bind(before_id.unwrap_or_default())
+    .bind(limit_value)
+    .fetch_all(db.pool())
+    .await
+    .map_err(AppError::context("Failed to fetch activities"))?;
+
+    log::info!("Fetched {} activities for project {}", activities.len(), projectId);
+    Ok(activities)
+}
+
+/// Filters for the cross-project activity feed
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GlobalActivityFilters {
+    pub event_types: Option<Vec<String>>,
+    pub project_ids: Option<Vec<String>>,
+    /// Unix timestamps, inclusive
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+}
+
+/// An activity with the project it belongs to
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct GlobalActivity {
+    pub id: String,
+    pub project_id: String,
+    pub session_id: Option<String>,
+    pub event_type: String,
+    pub description: String,
+    pub data: Option<String>,
+    pub timestamp: i64,
+    pub project_name: String,
+    pub project_color: Option<String>,
+    pub project_icon: Option<String>,
+}
+
+/// A live agent session with the project it belongs to
+#[derive(Debug, Clone, Serialize)]
+pub struct GlobalSession {
+    #[serde(flatten)]
+    pub session: crate::agent_manager::AgentSession,
+    pub project_name: Option<String>,
+    pub project_color: Option<String>,
+}
+
+/// Recent activity across all projects plus the agent sessions running now
+#[derive(Debug, Clone, Serialize)]
+pub struct GlobalActivityFeed {
+    pub activities: Vec<GlobalActivity>,
+    pub running_sessions: Vec<GlobalSession>,
+}
+
+/// Get recent activity across all projects, newest first, with running sessions
+#[tauri::command]
+pub async fn get_global_activities(
+    db: State<'_, Database>,
+    agent_manager: State<'_, crate::agent_manager::AgentManager>,
+    limit: Option<i64>,
+    filters: Option<GlobalActivityFilters>,
+) -> Result<GlobalActivityFeed, AppError> {
+    log::info!("Fetching global activity feed");
+
+    let filters = filters.unwrap_or_default();
+    let limit_value = limit.unwrap_or(50).clamp(1, 200);
+    let as_json = |values: Option<Vec<String>>| {
+        values
+            .filter(|v| !v.is_empty())
+            .map(|v| serde_json::json!(v).to_string())
+    };
+
+    let activities = sqlx::query_as::<_, GlobalActivity>(
+        r#"
+        SELECT a.id, a.project_id, a.session_id, a.event_type, a.description, a.data, a.timestamp,
+               p.name AS project_name, p.color AS project_color, p.icon AS project_icon
+        FROM activity_log a
+        JOIN projects p ON p.id = a.project_id
+        WHERE (?1 IS NULL OR a.event_type IN (SELECT value FROM json_each(?1)))
+          AND (?2 IS NULL OR a.project_id IN (SELECT value FROM json_each(?2)))
+          AND (?3 IS NULL OR a.timestamp >= ?3)
+          AND (?4 IS NULL OR a.timestamp <= ?4)
+        ORDER BY a.timestamp DESC, a.id DESC
+        LIMIT ?5
+        "#
+    )
+    .bind(as_json(filters.event_types))
+    .bind(as_json(filters.project_ids))
+    .bind(filters.since)
+    .bind(filters.until)
+    .bind(limit_value)
+    .fetch_all(db.pool())
+    .await
+    .map_err(AppError::context("Failed to fetch global activities"))?;
+
+    let mut running_sessions = Vec::new();
+    for session in agent_manager.list_sessions().await {
+        use crate::agent_manager::AgentStatus;
+        if !matches!(session.status, AgentStatus::Starting | AgentStatus::Running | AgentStatus::Paused) {
+            continue;
+        }
+
+        let project = sqlx::query_as::<_, (String, Option<String>)>("SELECT name, color FROM projects WHERE id = ?")
+            .bind(&session.project_id)
+            .fetch_optional(db.pool())
+            .await
+            .map_err(AppError::context("Failed to fetch project"))?;
+        let (project_name, project_color) = project.map_or((None, None), |(name, color)| (Some(name), color));
+
+        running_sessions.push(GlobalSession {
+            session,
+            project_name,
+            project_color,
+        });
+    }
+
+    log::info!(
+        "Fetched {} global activities and {} running sessions",
+        activities.len(),
+        running_sessions.len()
+    );
+    Ok(GlobalActivityFeed {
+        activities,
+        running_sessions,
+    })
+}
+
+/// Number of activities of one type in a time bucket
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityCount {
+    /// Unix timestamp of the start of the bucket (UTC)
+    pub bucket_start: i64,
+    pub event_type: String,
+    pub count: i64,
+}
+
+/// Count a project's activities per event type in hour, day or week buckets
+///
+/// Weeks start on Monday. `since`/`until` bound the timestamps counted.
+#[tauri::command]
+pub async fn get_activity_counts(
+    db: State<'_, Database>,
+    project_id: String,
+    bucket: String,
+    event_types: Option<Vec<String>>,
+    since: Option<i64>,
+    until: Option<i64>,
+) -> Result<Vec<ActivityCount>, AppError> {
+    log::info!("Counting activities for project {} by {}", project_id, bucket);
+
+    let (size, offset) = crate::commands_stats::bucket_bounds(&bucket)?;
+    let event_types = event_types
+        .filter(|types| !types.is_empty())
+        .map(|types| serde_json::json!(types).to_string());
+
+    let counts = sqlx::query_as::<_, (i64, String, i64)>(
+        r#"
+        SELECT ((timestamp - ?2) / ?3) * ?3 + ?2 AS bucket_start, event_type, COUNT(*)
+        FROM activity_log
+        WHERE project_id = ?1
+          AND (?4 IS NULL OR event_type IN (SELECT value FROM json_each(?4)))
+          AND (?5 IS NULL OR timestamp >= ?5)
+          AND (?6 IS NULL OR timestamp <= ?6)
+        GROUP BY bucket_start, event_type
+        ORDER BY bucket_start ASC, event_type ASC
+        "#
+    )
+    .bind(&project_id)
+    .bind(offset)
+    .bind(size)
+    .bind(&event_types)
+    .bind(since)
+    .bind(until)
+    .fetch_all(db.pool())
+    .await
+    .map_err(AppError::context("Failed to count activities"))?
+    .into_iter()
+    .map(|(bucket_start, event_type, count)| ActivityCount {
+        bucket_start,
+        event_type,
+        count,
+    })
+    .collect();
+
+    Ok(counts)
+}
+
+/// A project's dev container and compose setup, if it has one
+#[tauri::command]
+pub async fn get_container_environment(
+    db: State<'_, Database>,
+    project_id: String,
+) -> Result<Option<ContainerEnvironment>, AppError> {
+    let project = get_project(db, project_id.clone())
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Project not found: {}", project_id)))?;
+
+    tokio::task::spawn_blocking(move || container_env::detect(Path::new(&project.root_path)))
+        .await
+        .map_err(AppError::context("Failed to detect container environment"))
+}
+
+/// The env files at the top of the project, e.g. `.env` and `.env.local`
+#[tauri::command]
+pub async fn list_env_files(db: State<'_, Database>, project_id: String) -> Result<Vec<String>, AppError> {
+    let project = get_project(db, project_id.clone())
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Project not found: {}", project_id)))?;
+    Ok(env_files::list(Path::new(&project.root_path)))
+}
+
+/// Check that `path` names an env file at the top of the project, and not a
+/// symlink out of it
+fn env_file_path(root: &str, path: &str) -> Result<(), AppError> {
+    if !env_files::is_env_file(path) {
+        return Err(AppError::invalid_input(format!("Not an env file: {}", path)));
+    }
+    resolve_in_project(root, path)?;
+    Ok(())
+}
+
+/// Read one of the project's env files; values are masked unless `reveal` is set
+#[tauri::command]
+pub async fn read_env_file(
+    db: State<'_, Database>,
+    project_id: String,
+    path: String,
+    reveal: Option<bool>,
+) -> Result<EnvFile, AppError> {
+    let project = get_project(db, project_id.clone())
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Project not found: {}", project_id)))?;
+    env_file_path(&project.root_path, &path)?;
+
+    env_files::read(Path::new(&project.root_path), &path, reveal.unwrap_or(false))
+        .map_err(|e| AppError::from(e).wrap(format!("Failed to read {}", path)))
+}
+
+/// Set one variable of an env file, creating the file if needed, or remove it
+/// when `value` is null. Returns the file with its values masked.
+#[tauri::command]
+pub async fn set_env_var(
+    db: State<'_, Database>,
+    project_id: String,
+    path: String,
+    key: String,
+    value: Option<String>,
+) -> Result<EnvFile, AppError> {
+    let project = get_project(db, project_id.clone())
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Project not found: {}", project_id)))?;
+    env_file_path(&project.root_path, &path)?;
+    if !env_files::is_valid_key(&key) {
+        return Err(AppError::invalid_input(format!("Invalid variable name: {}", key)));
+    }
+
+    let root = Path::new(&project.root_path);
+    let text = match fs::read_to_string(root.join(&path)) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(AppError::from(e).wrap(format!("Failed to read {}", path))),
+    };
+    fs::write(root.join(&path), env_files::set_var(&text, &key, value.as_deref()))
+        .map_err(|e| AppError::from(e).wrap(format!("Failed to write {}", path)))?;
+    log::info!("{} {} in {} of project {}", if value.is_some() { "Set" } else { "Removed" }, key, path, project_id);
+
+    env_files::read(root, &path, false).map_err(|e| AppError::from(e).wrap(format!("Failed to read {}", path)))
+}
+
+/// Analyze an existing project directory
+#[tauri::command]
+pub async fn analyze_project_directory(path: String) -> Result<ProjectAnalysisResult, AppError> {
+    log::info!("Analyzing project directory: {}", path);
+
+    // Run analysis in a blocking task since it's CPU-intensive
+    let result = tokio::task::spawn_blocking(move || {
+        project_analyzer::analyze_project(&path)
+    })
+    .await
+    .map_err(AppError::context("Failed to spawn analysis task"))?;
+
+    match result {
+        Ok(analysis) => {
+            log::info!(
+                "Project analysis complete: {} files, {} languages, {} frameworks",
+                analysis.file_count,
+                analysis.detected_languages.len(),
+                analysis.detected_frameworks.len()
+            );
+            Ok(analysis)
+        }
+        Err(e) => {
+            log::error!("Project analysis failed: {}", e);
+            Err(e.into())
+        }
+    }
+}
+
+/// Update existing project details with AI-generated name and description
+#[tauri::command]
+pub async fn update_project_with_ai(
+    db: State<'_, Database>,
+    jobs: State<'_, JobManager>,
+    project_id: String,
+) -> Result<Project, AppError> {
+    log::info!("Updating project {} with AI", project_id);
+
+    // Get the existing project
+    let project = get_project(db.clone(), project_id.clone())
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Project not found: {}", project_id)))?;
+
+    // Run AI analysis on the project path as a job, so it can be watched and cancelled
+    let root_path = project.root_path.clone();
+    let analysis = jobs
+        .run("project_analysis", Some(project_id.clone()), format!("Analyze {}", project.name), |ctx| async move {
+            ctx.progress(None, "Asking the AI to describe the project").await;
+            analyze_project_with_ai(root_path).await.map_err(String::from)
+        })
+        .await?;
+
+    // Update the project in the database
+    sqlx::query(
+        r#"
+        UPDATE projects
+        SET name = ?, description = ?
+        WHERE id = ?
+        "#,
+    )
+    .bind(&analysis.suggested_name)
+    .bind(&analysis.suggested_description)
+    .execute(db.pool())
+    .await
+    .map_err(AppError::context("Failed to update project"))?;
+
+    log::info!(
+        "Updated project {} with AI: name='{}', desc='{}'",
+        project_id,
+        analysis.suggested_name,
+        analysis.suggested_description
+    );
+
+    // Return the updated project
+    get_project(db, project_id)
+        .await?
+        .ok_or_else(|| AppError::internal("Failed to fetch updated project"))
+}
+
+/// Analyze project with AI to generate intelligent name and description
+#[tauri::command]
+pub async fn analyze_project_with_ai(path: String) -> Result<ProjectAnalysisResult, AppError> {
+    log::info!("Analyzing project with AI: {}", path);
+
+    // First, do the basic file-based analysis
+    let mut analysis = analyze_project_directory(path.clone()).await?;
+
+    // Check if AI service is available
+    use crate::ai_service::{AIService, ChatMessage};
+    let ai_service = AIService::from_env();
+
+    if !ai_service.is_available() {
+        log::warn!("AI service not available, using basic analysis only");
+        return Ok(analysis);
+    }
+
+    log::info!("AI service available, generating intelligent description");
+
+    // Build a context-rich prompt for the AI
+    let prompt = format!(
+        r#"Analyze this project directory and provide a concise name and description.
+
+Project Path: {}
+Files Found: {}
+Detected Languages: {}
+Detected Frameworks: {}
+Has Git: {}
+
+Based on the above information, please provide:
+1. A short, descriptive project name (3-5 words, use kebab-case)
+2. A brief description (1-2 sentences) explaining what this project does
+
+Format your response exactly as:
+NAME: your-project-name
+DESCRIPTION: Your project description here.
+
+Be concise and professional. Focus on what the project appears to do based on its tech stack."#,
+        path,
+        analysis.file_count,
+        analysis.detected_languages.join(", "),
+        analysis.detected_frameworks.join(", "),
+        if analysis.has_git { "Yes" } else { "No" }
+    );
+
+    // Call AI service
+    let messages = vec![ChatMessage {
+        role: "user".to_string(),
+        content: prompt,
+    }];
+
+    match ai_service.chat_completion(messages).await {
+        Ok(response) => {
+            log::info!("AI analysis complete");
+
+            // Parse the AI response
+            let lines: Vec<&str> = response.lines().collect();
+            for line in lines {
+                if let Some(name) = line.strip_prefix("NAME:") {
+                    analysis.suggested_name = name.trim().to_string();
+                } else if let Some(desc) = line.strip_prefix("DESCRIPTION:") {
+                    analysis.suggested_description = desc.trim().to_string();
+                }
+            }
+
+            // If parsing failed, use a fallback
+            if analysis.suggested_name.is_empty() {
+                log::warn!("Failed to parse AI name, using fallback");
+                analysis.suggested_name = path
+                    .split(&['/', '\\'][..])
+                    .last()
+                    .unwrap_or("my-project")
+                    .to_string();
+            }
+
+            if analysis.suggested_description.is_empty() {
+                log::warn!("Failed to parse AI description, using fallback");
+                analysis.suggested_description = format!(
+                    "A {} project using {}",
+                    analysis.detected_languages.first().unwrap_or(&"software".to_string()),
+                    analysis.detected_frameworks.first().unwrap_or(&"custom frameworks".to_string())
+                );
+            }
+
+            log::info!("AI suggested name: {}", analysis.suggested_name);
+            log::info!("AI suggested description: {}", analysis.suggested_description);
+
+            Ok(analysis)
+        }
+        Err(e) => {
+            log::error!("AI analysis failed: {}, using basic analysis", e);
+            // Still return the basic analysis on error
+            Ok(analysis)
+        }
+    }
+}
+
+/// Generate project details (name and description) from project files
+/// This is used in the UI modal to preview AI-generated details before applying
+#[tauri::command]
+pub async fn generate_project_details(
+    project_path: String,
+) -> Result<crate::types::AIProjectDetails, AppError> {
+    log::info!("Generating project details for: {}", project_path);
+
+    let path = std::path::Path::new(&project_path);
+
+    // Gather context from various files
+    let mut context_parts = Vec::new();
+
+    // Read package.json for name, productName, and description
+    let package_json_path = path.join("package.json");
+    if let Ok(content) = std::fs::read_to_string(&package_json_path) {
+        if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
+            if let Some(product_name) = json.get("productName").and_then(|n| n.as_str()) {
+                context_parts.push(format!("Product name: {}", product_name));
+            }
+            if let Some(name) = json.get("name").and_then(|n| n.as_str()) {
+                context_parts.push(format!("Package name: {}", name));
+            }
+            if let Some(desc) = json.get("description").and_then(|d| d.as_str()) {
+                if !desc.is_empty() {
+                    context_parts.push(format!("Package description: {}", desc));
+                }
+            }
+        }
+    }
+
+    // Read Cargo.toml for Rust projects
+    let cargo_toml_path = path.join("Cargo.toml");
+    if let Ok(content) = std::fs::read_to_string(&cargo_toml_path) {
+        context_parts.push("Rust/Cargo project detected".to_string());
+        // Try to extract description from Cargo.toml
+        for line in content.lines() {
+            if line.trim().starts_with("description") {
+                if let Some(desc) = line.split('=').nth(1) {
+                    let clean_desc = desc.trim().trim_matches('"').trim_matches('\'');
+                    context_parts.push(format!("Cargo description: {}", clean_desc));
+                }
+            }
+        }
+    }
+
+    // Read README files (try multiple variations)
+    let mut readme_found = false;
+    for readme_name in &["README.md", "readme.md", "Readme.md", "README.txt"] {
+        let readme_path = path.join(readme_name);
+        if let Ok(content) = std::fs::read_to_string(&readme_path) {
+            let preview: String = content.chars().take(1500).collect();
+            context_parts.push(format!("README: {}", preview));
+            readme_found = true;
+            break;
+        }
+    }
+
+    // If no standard README found, look for any markdown files that might describe the project
+    if !readme_found {
+        if let Ok(entries) = std::fs::read_dir(path) {
+            let mut doc_files: Vec<_> = entries
+                .filter_map(|e| e.ok())
+                .filter(|e| {
+                    if let Some(name) = e.file_name().to_str() {
+                        let name_upper = name.to_uppercase();
+                        // Look for files that might contain project info
+                        (name_upper.starts_with("README") ||
+                         name_upper.contains("OVERVIEW") ||
+                         name_upper.contains("ABOUT") ||
+                         name_upper.contains("INTRO")) &&
+                        name.ends_with(".md")
+                    } else {
+                        false
+                    }
+                })
+                .collect();
+
+            // Sort by name to get consistent results
+            doc_files.sort_by_key(|e| e.file_name());
+
+            // Read the first matching doc file
+            if let Some(doc_file) = doc_files.first() {
+                if let Ok(content) = std::fs::read_to_string(doc_file.path()) {
+                    let preview: String = content.chars().take(1500).collect();
+                    if let Some(name) = doc_file.file_name().to_str() {
+                        context_parts.push(format!("Documentation from {}: {}", name, preview));
+                    }
+                }
+            }
+        }
+    }
+
+    // Get project analysis for tech stack
+    let project_path_clone = project_path.clone();
+    let analysis = tokio::task::spawn_blocking(move || {
+        project_analyzer::analyze_project(&project_path_clone)
+    })
+    .await
+    .map_err(AppError::context("Failed to analyze project"))?
+    .map_err(AppError::context("Project analysis failed"))?;
+
+    context_parts.push(format!("Languages: {}", analysis.detected_languages.join(", ")));
+    context_parts.push(format!("Frameworks: {}", analysis.detected_frameworks.join(", ")));
+
+    // Build AI prompt
+    let context = context_parts.join("\n");
+    let prompt = format!(
+        r#"You are analyzing a software project. Based on the information provided, determine the proper display name and create a clear description.
+
+Project Information:
+{}
+
+Instructions:
+1. Determine the display name:
+   - If a "Product name" is listed, use that (it's the user-facing name)
+   - Otherwise, use the most meaningful name you can find from the README or documentation
+   - Convert technical names to proper Title Case (e.g., "ateliercode" → "Atelier Code")
+   - Avoid using npm package names like "@scope/package" - extract the meaningful part
+
+2. Write a concise description (1-2 sentences):
+   - Explain what the application actually does for end users
+   - Focus on the main purpose and value, not technical details
+   - Base this on README content and documentation
+
+Format your response EXACTLY as shown below (use these exact prefixes):
+NAME: Your Project Name
+DESCRIPTION: Your project description here.
+
+Example good responses:
+NAME: Atelier Code
+DESCRIPTION: A desktop application for managing AI-assisted software development workflows.
+
+NAME: Remote Portals
+DESCRIPTION: A secure streaming platform for remote medical imaging and diagnostics."#,
+        context
+    );
+
+    log::info!("========== AI PROMPT ==========");
+    log::info!("{}", prompt);
+    log::info!("==============================");
+
+    // Try to use AI service
+    use crate::ai_service::{AIService, ChatMessage};
+    let ai_service = AIService::from_env();
+
+    let (name, description) = if ai_service.is_available() {
+        log::info!("AI service available, generating intelligent details");
+
+        let messages = vec![ChatMessage {
+            role: "user".to_string(),
+            content: prompt,
+        }];
+
+        match ai_service.chat_completion(messages).await {
+            Ok(response) => {
+                log::info!("========== AI RESPONSE ==========");
+                log::info!("{}", response);
+                log::info!("=================================");
+
+                // Parse the AI response - try multiple formats
+                let mut parsed_name = String::new();
+                let mut parsed_description = String::new();
+
+                for line in response.lines() {
+                    let line_upper = line.to_uppercase();
+
+                    // Try "NAME:" prefix (case insensitive)
+                    if line_upper.starts_with("NAME:") {
+                        parsed_name = line[5..].trim().to_string();
+                        log::info!("Parsed name from 'NAME:' line: '{}'", parsed_name);
+                    }
+                    // Try "DESCRIPTION:" prefix (case insensitive)
+                    else if line_upper.starts_with("DESCRIPTION:") {
+                        parsed_description = line[12..].trim().to_string();
+                        log::info!("Parsed description from 'DESCRIPTION:' line: '{}'", parsed_description);
+                    }
+                    // Also try to continue multiline descriptions
+                    else if !parsed_description.is_empty() && !line.trim().is_empty() && !line_upper.starts_with("NAME") {
+                        parsed_description.push(' ');
+                        parsed_description.push_str(line.trim());
+                    }
+                }
+
+                log::info!("Parsed results - name: '{}', description: '{}'", parsed_name, parsed_description);
+
+                // Fallback if parsing failed
+                if parsed_name.is_empty() {
+                    log::warn!("Failed to parse NAME from AI response, using fallback");
+                    parsed_name = project_analyzer::extract_project_name(path)
+                        .unwrap_or_else(|| path.file_name()
+                            .and_then(|n| n.to_str())
+                            .unwrap_or("My Project")
+                            .to_string());
+                }
+
+                if parsed_description.is_empty() {
+                    log::warn!("Failed to parse DESCRIPTION from AI response, using fallback");
+                    parsed_description = analysis.suggested_description;
+                }
+
+                (parsed_name, parsed_description)
+            }
+            Err(e) => {
+                log::warn!("AI generation failed: {}, using fallback", e);
+                // Fallback to basic extraction
+                let name = project_analyzer::extract_project_name(path)
+                    .unwrap_or_else(|| path.file_name()
+                        .and_then(|n| n.to_str())
+                        .unwrap_or("My Project")
+                        .to_string());
+                (name, analysis.suggested_description)
+            }
+        }
+    } else {
+        log::info!("AI service not available, using basic extraction");
+        // Fallback to basic extraction
+        let name = project_analyzer::extract_project_name(path)
+            .unwrap_or_else(|| path.file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("My Project")
+                .to_string());
+        (name, analysis.suggested_description)
+    };
+
+    log::info!("Generated details - name: '{}', description: '{}'", name, description);
+
+    Ok(crate::types::AIProjectDetails {
+        name,
+        description,
+    })
+}
+
+// ============================================================================
+// Statistics Commands
+// ============================================================================
+
+/// Get project statistics
+#[tauri::command]
+pub async fn get_project_stats(
+    db: State<'_, Database>,
+    #[allow(non_snake_case)]
+    projectId: String,
+) -> Result<ProjectStats, AppError> {
+    log::info!("Fetching stats for project: {}", projectId);
+
+    // Verify project exists
+    let project = get_project(db.clone(), projectId.clone())
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Project not found: {}", projectId)))?;
+
+    // Count distinct files changed
+    let files_changed_result = sqlx::query_scalar::<_, i64>(
+        r#"
+        SELECT COUNT(DISTINCT file_path)
+        FROM file_changes
+        WHERE project_id = ?
+        "#
+    )
+    .bind(&projectId)
+    .fetch_one(db.pool())
+    .await;
+
+    let files_changed = files_changed_result.unwrap_or(0) as usize;
+
+    // Count commits from git
+    let commits = count_git_commits(&project.root_path).await.unwrap_or(0);
+
+    // Count chat messages
+    let messages_result = sqlx::query_scalar::<_, i64>(
+        r#"
+        SELECT COUNT(*)
+        FROM chat_messages
+        WHERE project_id = ? AND deleted_at IS NULL
+        "#
+    )
+    .bind(&projectId)
+    .fetch_one(db.pool())
+    .await;
+
+    let messages = messages_result.unwrap_or(0) as usize;
+
+    // Count completed tasks
+    let tasks_completed_result = sqlx::query_scalar::<_, i64>(
+        r#"
+        SELECT COUNT(*)
+        FROM tasks
+        WHERE project_id = ? AND status = 'completed'
+        "#
+    )
+    .bind(&projectId)
+    .fetch_one(db.pool())
+    .await;
+
+    let tasks_completed = tasks_completed_result.unwrap_or(0) as usize;
+
+    // Count total tasks
+    let tasks_total_result = sqlx::query_scalar::<_, i64>(
+        r#"
+        SELECT COUNT(*)
+        FROM tasks
+        WHERE project_id = ?
+        "#
+    )
+    .bind(&projectId)
+    .fetch_one(db.pool())
+    .await;
+
+    let tasks_total = tasks_total_result.unwrap_or(0) as usize;
+
+    let stats = ProjectStats {
+        files_changed,
+        commits,
+        messages,
+        tasks_completed,
+        tasks_total,
+    };
+
+    log::info!("Project stats: {:?}", stats);
+    Ok(stats)
+}
+
+/// Count commits in a git repository
+async fn count_git_commits(path: &str) -> Result<usize> {
+    log::debug!("Counting git commits in: {}", path);
+
+    // Check if git is available and the path is a git repository
+    let check_output = git_output(path, &["rev-parse", "--git-dir"]).await;
+
+    match check_output {
+        Ok(output) if output.status.success() => {
+            // It's a git repo, count commits
+            let count_output = git_output(path, &["rev-list", "--count", "HEAD"])
+                .await
+                .map_err(anyhow::Error::msg)
+                .context("Failed to execute git rev-list")?;
+
+            if count_output.status.success() {
+                let count_str = String::from_utf8_lossy(&count_output.stdout);
+                let count = count_str.trim().parse::<usize>().unwrap_or(0);
+                log::debug!("Found {} commits", count);
+                Ok(count)
+            } else {
+                // No commits yet (empty repository)
+                log::debug!("Empty git repository (no commits)");
+                Ok(0)
+            }
+        }
+        _ => {
+            // Not a git repository or git not available
+            log::debug!("Not a git repository or git not available");
+            Ok(0)
+        }
+    }
+}
+
+// ============================================================================
+// File Watcher Commands
+// ============================================================================
+
+/// Start watching a project for file changes
+///
+/// Windows share a project's watcher; it stops once every window using it
+/// has stopped watching or closed.
+#[tauri::command]
+pub async fn start_watching_project(
+    window: tauri::Window,
+    db: State<'_, Database>,
+    watcher: State<'_, FileWatcherManager>,
+    project_id: String,
+) -> Result<String, AppError> {
+    log::info!("Starting file watcher for project: {}", project_id);
+
+    // Get project to verify it exists and get the path
+    let project = get_project(db.clone(), project_id.clone())
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Project not found: {}", project_id)))?;
+
+    let extra_roots = project_roots::list(db.pool(), &project_id).await?
+        .into_iter()
+        .map(|root| root.path)
+        .collect();
+
+    // Start watching
+    let session_id = watcher
+        .start_watching(project_id.clone(), project.root_path, extra_roots, db.pool().clone(), window.label())
+        .await
+        .map_err(AppError::context("Failed to start watching"))?;
+
+    log::info!("File watcher started for project {} with session {}", project_id, session_id);
+    Ok(session_id)
+}
+
+/// Stop watching a project
+#[tauri::command]
+pub async fn stop_watching_project(
+    window: tauri::Window,
+    watcher: State<'_, FileWatcherManager>,
+    project_id: String,
+) -> Result<bool, AppError> {
+    log::info!("Stopping file watcher for project: {}", project_id);
+
+    watcher
+        .stop_watching(&project_id, window.label())
+        .map_err(AppError::context("Failed to stop watching"))?;
+
+    log::info!("File watcher stopped for project {}", project_id);
+    Ok(true)
+}
+
+/// Check if a project is being watched
+#[tauri::command]
+pub async fn is_watching_project(
+    watcher: State<'_, FileWatcherManager>,
+    project_id: String,
+) -> Result<bool, AppError> {
+    Ok(watcher.is_watching(&project_id))
+}
+
+/// Columns a `FileChange` is read from
+pub(crate) const FILE_CHANGE_COLUMNS: &str =
+    "id, project_id, session_id, file_path, change_type, diff, reviewed, approved, timestamp, content_hash, stale";
+
+/// Fetch a file change, failing if it doesn't exist
+pub(crate) async fn fetch_file_change(pool: &sqlx::SqlitePool, change_id: &str) -> Result<FileChange, AppError> {
+    sqlx::query_as::<_, FileChange>(&format!("SELECT {} FROM file_changes WHERE id = ?", FILE_CHANGE_COLUMNS))
+        .bind(change_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(AppError::context("Failed to fetch file change"))?
+        .ok_or_else(|| AppError::not_found(format!("File change not found: {}", change_id)))
+}
+
+/// A project's unreviewed file changes, newest first
+async fn fetch_pending_changes(pool: &sqlx::SqlitePool, project_id: &str) -> Result<Vec<FileChange>, AppError> {
+    sqlx::query_as::<_, FileChange>(&format!(
+        "SELECT {} FROM file_changes WHERE project_id = ? AND reviewed = FALSE ORDER BY timestamp DESC",
+        FILE_CHANGE_COLUMNS
+    ))
+    .bind(project_id)
+    .fetch_all(pool)
+    .await
+    .map_err(AppError::context("Failed to fetch pending changes"))
+}
+
+/// Get pending file changes for a project
+#[tauri::command]
+pub async fn get_pending_changes(
+    db: State<'_, Database>,
+    project_id: String,
+) -> Result<Vec<FileChange>, AppError> {
+    log::info!("Fetching pending changes for project: {}", project_id);
+
+    let changes = fetch_pending_changes(db.pool(), &project_id).await?;
+
+    log::info!("Found {} pending changes for project {}", changes.len(), project_id);
+    Ok(changes)
+}
+
+/// Get all file changes for a project (including reviewed ones)
+#[tauri::command]
+pub async fn get_all_changes(
+    db: State<'_, Database>,
+    project_id: String,
+    limit: Option<i64>,
+) -> Result<Vec<FileChange>, AppError> {
+    log::info!("Fetching all changes for project: {}", project_id);
+
+    let limit_value = limit.unwrap_or(100).min(500);
+
+    let changes = sqlx::query_as::<_, FileChange>(&format!(
+        "SELECT {} FROM file_changes WHERE project_id = ? ORDER BY timestamp DESC LIMIT ?",
+        FILE_CHANGE_COLUMNS
+    ))
+    .bind(&project_id)
+    .bind(limit_value)
+    .fetch_all(db.pool())
+    .await
+    .map_err(AppError::context("Failed to fetch changes"))?;
+
+    log::info!("Found {} changes for project {}", changes.len(), project_id);
+    Ok(changes)
+}
+
+/// Approve a file change
+#[tauri::command]
+pub async fn approve_change(
+    db: State<'_, Database>,
+    change_id: String,
+) -> Result<FileChange, AppError> {
+    log::info!("Approving change: {}", change_id);
+
+    // Update the change
+    sqlx::query(
+        r#"
+        UPDATE file_changes
+        SET reviewed = TRUE, approved = TRUE
+        WHERE id = ?
+        "#
+    )
+    .bind(&change_id)
+    .execute(db.pool())
+    .await
+    .map_err(AppError::context("Failed to approve change"))?;
+
+    // Fetch the updated change
+    let change = fetch_file_change(db.pool(), &change_id).await?;
+
+    log::info!("Change approved: {}", change_id);
+    Ok(change)
+}
+
+/// Reject a file change
+///
+/// Rejecting a deletion puts the file back when the project's
+/// `capture_deleted_files` setting kept a copy of it.
+#[tauri::command]
+pub async fn reject_change(
+    db: State<'_, Database>,
+    change_id: String,
+) -> Result<FileChange, AppError> {
+    log::info!("Rejecting change: {}", change_id);
+
+    restore_deleted_file(&db, &change_id).await?;
+
+    // Update the change
+    sqlx::query(
+        r#"
+        UPDATE file_changes
+        SET reviewed = TRUE, approved = FALSE
+        WHERE id = ?
+        "#
+    )
+    .bind(&change_id)
+    .execute(db.pool())
+    .await
+    .map_err(AppError::context("Failed to reject change"))?;
+
+    // Fetch the updated change
+    let change = fetch_file_change(db.pool(), &change_id).await?;
+
+    log::info!("Change rejected: {}", change_id);
+    Ok(change)
+}
+
+/// Put back a file whose deletion is being rejected, if the watcher kept a
+/// copy of it and it hasn't been recreated since
+async fn restore_deleted_file(db: &Database, change_id: &str) -> Result<(), AppError> {
+    // Project, path, content and blob id
+    type Captured = (String, String, Option<Vec<u8>>, Option<String>);
+    let captured: Option<Captured> = sqlx::query_as(
+        r#"
+        SELECT project_id, file_path, deleted_content, deleted_blob
+        FROM file_changes
+        WHERE id = ? AND change_type = 'deleted' AND (deleted_content IS NOT NULL OR deleted_blob IS NOT NULL)
+        "#
+    )
+    .bind(change_id)
+    .fetch_optional(db.pool())
+    .await
+    .map_err(AppError::context("Failed to fetch deleted file"))?;
+    let Some((project_id, file_path, content, blob)) = captured else {
+        return Ok(());
+    };
+
+    let root_path: Option<String> = sqlx::query_scalar("SELECT root_path FROM projects WHERE id = ?")
+        .bind(&project_id)
+        .fetch_optional(db.pool())
+        .await
+        .map_err(AppError::context("Failed to fetch project"))?;
+    let root_path = root_path.ok_or_else(|| AppError::not_found(format!("Project not found: {}", project_id)))?;
+    let (repo_dir, target) = project_roots::resolve(db.pool(), &project_id, &root_path, &file_path).await?;
+    if target.exists() {
+        log::info!("Not restoring {}: it was recreated", file_path);
+        return Ok(());
+    }
+    crate::file_watcher::restore_deleted_file(&target, Path::new(&repo_dir), content, blob.as_deref())
+        .map_err(|e| AppError::internal(format!("Failed to restore {}: {:#}", file_path, e)))?;
+    log::info!("Restored deleted file {}", file_path);
+    Ok(())
+}
+
+/// Get the diff content for a file change
+#[tauri::command]
+pub async fn get_file_diff(
+    db: State<'_, Database>,
+    change_id: String,
+) -> Result<String, AppError> {
+    log::info!("Fetching diff for change: {}", change_id);
+
+    let change = fetch_file_change(db.pool(), &change_id).await?;
+
+    Ok(change.diff.unwrap_or_default())
+}
+
+/// Flag changes whose file no longer matches the content they recorded and
+/// return the reviewed ones among them, newest first
+async fn find_stale_changes(
+    pool: &sqlx::SqlitePool,
+    project_id: &str,
+    root_path: &str,
+) -> Result<Vec<FileChange>, AppError> {
+    let tracked = sqlx::query_as::<_, (String, String)>(
+        r#"
+        SELECT DISTINCT file_path, content_hash
+        FROM file_changes
+        WHERE project_id = ? AND stale = FALSE AND content_hash IS NOT NULL
+        "#
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await
+    .map_err(AppError::context("Failed to fetch tracked changes"))?;
+
+    for (file_path, content_hash) in tracked {
+        let current = resolve_in_project(root_path, &file_path)
+            .ok()
+            .and_then(|path| crate::file_watcher::file_content_hash(&path));
+        if current.as_deref() == Some(content_hash.as_str()) {
+            continue;
+        }
+
+        sqlx::query(
+            "UPDATE file_changes SET stale = TRUE WHERE project_id = ? AND file_path = ? AND content_hash = ?"
+        )
+        .bind(project_id)
+        .bind(&file_path)
+        .bind(&content_hash)
+        .execute(pool)
+        .await
+        .map_err(AppError::context("Failed to mark change stale"))?;
+    }
+
+    sqlx::query_as::<_, FileChange>(&format!(
+        "SELECT {} FROM file_changes WHERE project_id = ? AND stale = TRUE AND reviewed = TRUE ORDER BY timestamp DESC",
+        FILE_CHANGE_COLUMNS
+    ))
+    .bind(project_id)
+    .fetch_all(pool)
+    .await
+    .map_err(AppError::context("Failed to fetch stale changes"))
+}
+
+/// Get reviewed changes whose file has changed on disk since they were recorded
+///
+/// Re-hashes the files of changes not yet flagged, so edits made while the
+/// project wasn't watched are caught too. Stale changes have a diff that no
+/// longer matches the file and should be refreshed before being trusted.
+#[tauri::command]
+pub async fn get_stale_changes(
+    db: State<'_, Database>,
+    project_id: String,
+) -> Result<Vec<FileChange>, AppError> {
+    log::info!("Checking for stale changes in project: {}", project_id);
+
+    let project = get_project(db.clone(), project_id.clone())
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Project not found: {}", project_id)))?;
+    let changes = find_stale_changes(db.pool(), &project_id, &project.root_path).await?;
+
+    log::info!("Found {} stale reviewed changes", changes.len());
+    Ok(changes)
+}
+
+/// Get a file change's diff as aligned old/new rows with intra-line change
+/// ranges and syntax highlighting
+#[tauri::command]
+pub async fn get_side_by_side_diff(
+    db: State<'_, Database>,
+    change_id: String,
+) -> Result<crate::side_by_side_diff::SideBySideDiff, AppError> {
+    log::info!("Fetching side-by-side diff for change: {}", change_id);
+
+    let change = fetch_file_change(db.pool(), &change_id).await?;
+
+    let diff = change.diff.unwrap_or_default();
+    let mut diff = crate::side_by_side_diff::side_by_side(&change.file_path, &diff);
+    crate::highlight::highlight_diff(&mut diff);
+    Ok(diff)
+}
+
+// ============================================================================
+// Review Comment Commands
+// ============================================================================
+
+/// Add a review comment to a file change, optionally with a suggested replacement
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn add_review_comment(
+    db: State<'_, Database>,
+    file_change_id: String,
+    author: String,
+    comment: String,
+    line_number: Option<i64>,
+    suggestion: Option<String>,
+    suggestion_start_line: Option<i64>,
+    suggestion_end_line: Option<i64>,
+) -> Result<crate::models::ReviewComment, AppError> {
+    log::info!("Adding review comment to file change: {}", file_change_id);
+
+    let mut review_comment = crate::models::ReviewComment::new(
+        file_change_id.clone(),
+        author,
+        comment,
+        line_number,
+    );
+
+    // A suggestion replaces the commented line unless given its own range
+    if let Some(suggestion) = suggestion {
+        let start = suggestion_start_line
+            .or(line_number)
+            .ok_or_else(|| AppError::invalid_input("A suggestion needs a line range"))?;
+        let end = suggestion_end_line.unwrap_or(start);
+        if start < 1 || end < start {
+            return Err(AppError::invalid_input(format!("Invalid suggestion line range: {}-{}", start, end)));
+        }
+        review_comment.suggestion_start_line = Some(start);
+        review_comment.suggestion_end_line = Some(end);
+        review_comment.suggestion = Some(suggestion);
+    }
+
+    sqlx::query(
+        r#"
+        INSERT INTO review_comments (id, file_change_id, line_number, author, comment, timestamp, resolved,
+                                     suggestion_start_line, suggestion_end_line, suggestion)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#
+    )
+    .bind(&review_comment.id)
+    .bind(&review_comment.file_change_id)
+    .bind(&review_comment.line_number)
+    .bind(&review_comment.author)
+    .bind(&review_comment.comment)
+    .bind(review_comment.timestamp)
+    .bind(review_comment.resolved)
+    .bind(review_comment.suggestion_start_line)
+    .bind(review_comment.suggestion_end_line)
+    .bind(&review_comment.suggestion)
+    .execute(db.pool())
+    .await
+    .map_err(AppError::context("Failed to add review comment"))?;
+
+    log::info!("Review comment added successfully: {}", review_comment.id);
+    Ok(review_comment)
+}
+
+/// Get all review comments for a file change
+#[tauri::command]
+pub async fn get_review_comments(
+    db: State<'_, Database>,
+    file_change_id: String,
+) -> Result<Vec<crate::models::ReviewComment>, AppError> {
+    log::info!("Fetching review comments for file change: {}", file_change_id);
+
+    let comments = sqlx::query_as::<_, crate::models::ReviewComment>(
+        r#"
+        SELECT id, file_change_id, line_number, author, comment, timestamp, resolved,
+               suggestion_start_line, suggestion_end_line, suggestion
+        FROM review_comments
+        WHERE file_change_id = ?
+        ORDER BY timestamp ASC
+        "#
+    )
+    .bind(&file_change_id)
+    .fetch_all(db.pool())
+    .await
+    .map_err(AppError::context("Failed to fetch review comments"))?;
+
+    log::info!("Fetched {} review comments", comments.len());
+    Ok(comments)
+}
+
+/// Resolve a review comment
+#[tauri::command]
+pub async fn resolve_review_comment(
+    db: State<'_, Database>,
+    comment_id: String,
+) -> Result<crate::models::ReviewComment, AppError> {
+    log::info!("Resolving review comment: {}", comment_id);
+
+    sqlx::query(
+        r#"
+        UPDATE review_comments
+        SET resolved = TRUE
+        WHERE id = ?
+        "#
+    )
+    .bind(&comment_id)
+    .execute(db.pool())
+    .await
+    .map_err(AppError::context("Failed to resolve comment"))?;
+
+    // Fetch and return the updated comment
+    let comment = sqlx::query_as::<_, crate::models::ReviewComment>(
+        r#"
+        SELECT id, file_change_id, line_number, author, comment, timestamp, resolved,
+               suggestion_start_line, suggestion_end_line, suggestion
+        FROM review_comments
+        WHERE id = ?
+        "#
+    )
+    .bind(&comment_id)
+    .fetch_one(db.pool())
+    .await
+    .map_err(AppError::context("Failed to fetch updated comment"))?;
+
+    log::info!("Review comment resolved successfully");
+    Ok(comment)
+}
+
+/// Fail unless the file at `path` still holds what `change` recorded
+fn ensure_unchanged_since(change: &FileChange, path: &Path) -> Result<(), AppError> {
+    let unchanged = !change.stale
+        && change.content_hash.is_some()
+        && crate::file_watcher::file_content_hash(path) == change.content_hash;
+    if unchanged {
+        return Ok(());
+    }
+    Err(AppError::conflict(format!(
+        "{} has changed since it was reviewed; review it again before applying suggestions",
+        change.file_path
+    )))
+}
+
+/// Apply a review comment's suggested replacement to the file
+///
+/// Patches the file on disk, records the edit as a new file change and
+/// resolves the comment. Returns the new change. Refused when the file has
+/// changed since the reviewed change, as the suggestion's lines may have moved.
+#[tauri::command]
+pub async fn apply_suggestion(
+    db: State<'_, Database>,
+    watchers: State<'_, FileWatcherManager>,
+    comment_id: String,
+) -> Result<FileChange, AppError> {
+    log::info!("Applying suggestion from review comment: {}", comment_id);
+
+    let pool = db.pool();
+
+    let comment = sqlx::query_as::<_, crate::models::ReviewComment>(
+        r#"
+        SELECT id, file_change_id, line_number, author, comment, timestamp, resolved,
+               suggestion_start_line, suggestion_end_line, suggestion
+        FROM review_comments
+        WHERE id = ?
+        "#
+    )
+    .bind(&comment_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(AppError::context("Failed to fetch review comment"))?
+    .ok_or_else(|| AppError::not_found(format!("Review comment not found: {}", comment_id)))?;
+
+    let (start, end, suggestion) = match (comment.suggestion_start_line, comment.suggestion_end_line, comment.suggestion) {
+        (Some(start), Some(end), Some(suggestion)) => (start as usize, end as usize, suggestion),
+        _ => return Err(AppError::invalid_input("Review comment has no suggestion")),
+    };
+
+    let change = fetch_file_change(pool, &comment.file_change_id).await?;
+
+    let project = get_project(db.clone(), change.project_id.clone())
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Project not found: {}", change.project_id)))?;
+
+    let file_path = resolve_in_project(&project.root_path, &change.file_path)?;
+    let content = fs::read_to_string(&file_path)
+        .map_err(|e| AppError::from(e).wrap(format!("Failed to read {}", change.file_path)))?;
+    ensure_unchanged_since(&change, &file_path)?;
+    let patched = crate::code_review::apply_line_replacement(&content, start, end, &suggestion)?;
+
+    // Recorded under the project's own root spelling so it relativizes
+    let watched_path = Path::new(&project.root_path).join(&change.file_path);
+    watchers.expect_write(&watched_path, Some(patched.as_bytes()));
+    fs::write(&file_path, patched)
+        .map_err(|e| AppError::from(e).wrap(format!("Failed to write {}", change.file_path)))?;
+
+    let new_change_id = crate::file_watcher::record_file_change(
+        pool,
+        &change.project_id,
+        &change.session_id,
+        &project.root_path,
+        &watched_path,
+        "modified",
+    )
+    .await
+    .map_err(AppError::context("Failed to record change"))?;
+
+    sqlx::query("UPDATE review_comments SET resolved = TRUE WHERE id = ?")
+        .bind(&comment_id)
+        .execute(pool)
+        .await
+        .map_err(AppError::context("Failed to resolve comment"))?;
+
+    let new_change = fetch_file_change(pool, &new_change_id).await?;
+
+    log::info!("Suggestion applied to {}", change.file_path);
+    Ok(new_change)
+}
+
+/// Review a file change with the AI service, adding its findings as review comments
+#[tauri::command]
+pub async fn ai_review_change(
+    db: State<'_, Database>,
+    change_id: String,
+) -> Result<Vec<crate::models::ReviewComment>, AppError> {
+    log::info!("Running AI review for change: {}", change_id);
+    Ok(crate::code_review::ai_review(db.pool(), &change_id).await?)
+}
+
+/// Summarize review activity in a project since a point in time
+///
+/// `since` is a Unix timestamp and defaults to 24 hours ago. The report has
+/// approval counts, per-file totals and unresolved comments, with a Markdown
+/// rendering for standups and audits.
+#[tauri::command]
+pub async fn generate_review_report(
+    db: State<'_, Database>,
+    project_id: String,
+    since: Option<i64>,
+) -> Result<crate::code_review::ReviewReport, AppError> {
+    log::info!("Generating review report for project: {}", project_id);
+
+    let project = get_project(db.clone(), project_id.clone())
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Project not found: {}", project_id)))?;
+    let since = since.unwrap_or_else(|| chrono::Utc::now().timestamp() - 24 * 60 * 60);
+    let pool = db.pool();
+
+    // Flag files edited after their review, even while the project wasn't watched
+    find_stale_changes(pool, &project_id, &project.root_path).await?;
+
+    let changes = sqlx::query_as::<_, FileChange>(&format!(
+        "SELECT {} FROM file_changes WHERE project_id = ? AND timestamp >= ? ORDER BY timestamp ASC",
+        FILE_CHANGE_COLUMNS
+    ))
+    .bind(&project_id)
+    .bind(since)
+    .fetch_all(pool)
+    .await
+    .map_err(AppError::context("Failed to fetch changes"))?;
+
+    let comments = sqlx::query_as::<_, (String, Option<i64>, String, String, i64)>(
+        r#"
+        SELECT fc.file_path, rc.line_number, rc.author, rc.comment, rc.timestamp
+        FROM review_comments rc
+        JOIN file_changes fc ON fc.id = rc.file_change_id
+        WHERE fc.project_id = ? AND fc.timestamp >= ? AND rc.resolved = FALSE
+        ORDER BY fc.file_path ASC, rc.line_number ASC, rc.timestamp ASC
+        "#
+    )
+    .bind(&project_id)
+    .bind(since)
+    .fetch_all(pool)
+    .await
+    .map_err(AppError::context("Failed to fetch review comments"))?
+    .into_iter()
+    .map(|(file_path, line_number, author, comment, timestamp)| crate::code_review::ReportComment {
+        file_path,
+        line_number,
+        author,
+        comment,
+        timestamp,
+    })
+    .collect();
+
+    Ok(crate::code_review::build_review_report(&project_id, &project.name, since, &changes, comments))
+}
+
+/// A change and its unresolved review comments, in line order; fails when
+/// there are none to send
+async fn fix_request(
+    pool: &sqlx::SqlitePool,
+    file_change_id: &str,
+) -> Result<(FileChange, Vec<crate::models::ReviewComment>), AppError> {
+    let change = fetch_file_change(pool, file_change_id).await?;
+
+    let comments = sqlx::query_as::<_, crate::models::ReviewComment>(
+        r#"
+        SELECT id, file_change_id, line_number, author, comment, timestamp, resolved,
+               suggestion_start_line, suggestion_end_line, suggestion
+        FROM review_comments
+        WHERE file_change_id = ? AND resolved = FALSE
+        ORDER BY line_number IS NULL, line_number ASC, timestamp ASC
+        "#
+    )
+    .bind(file_change_id)
+    .fetch_all(pool)
+    .await
+    .map_err(AppError::context("Failed to fetch review comments"))?;
+
+    if comments.is_empty() {
+        return Err(AppError::invalid_input("There are no unresolved review comments to send"));
+    }
+    Ok((change, comments))
+}
+
+/// Send a change's unresolved review comments to the agent as a fix request
+///
+/// Goes to `session_id` when given, otherwise to the agent session that was
+/// running when the change was made, falling back to the project's active
+/// chat tab. Returns the session that received the request.
+#[tauri::command]
+pub async fn request_changes(
+    db: State<'_, Database>,
+    agent_manager: State<'_, crate::agent_manager::AgentManager>,
+    plugin_manager: State<'_, crate::plugin::PluginManager>,
+    plugin_settings_manager: State<'_, crate::plugin_settings::PluginSettingsManager>,
+    file_change_id: String,
+    session_id: Option<String>,
+) -> Result<String, AppError> {
+    log::info!("Requesting changes for file change: {}", file_change_id);
+
+    let pool = db.pool();
+
+    let (change, comments) = fix_request(pool, &file_change_id).await?;
+    let prompt = crate::code_review::build_fix_prompt(&change, &comments);
+
+    // Agent sessions that were running when the change was made, newest first
+    let candidates: Vec<(String, String)> = match session_id {
+        Some(id) => {
+            let agent_type = sqlx::query_scalar::<_, String>("SELECT agent_type FROM agent_sessions WHERE id = ?")
+                .bind(&id)
+                .fetch_optional(pool)
+                .await
+                .map_err(AppError::context("Failed to look up agent session"))?
+                .ok_or_else(|| AppError::not_found(format!("Agent session not found: {}", id)))?;
+            vec![(id, agent_type)]
+        }
+        None => sqlx::query_as::<_, (String, String)>(
+            r#"
+            SELECT id, agent_type
+            FROM agent_sessions
+            WHERE project_id = ? AND agent_type != 'file_watcher'
+              AND started_at <= ? AND (ended_at IS NULL OR ended_at >= ?)
+            ORDER BY started_at DESC
+            "#
+        )
+        .bind(&change.project_id)
+        .bind(change.timestamp)
+        .bind(change.timestamp)
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::context("Failed to look up agent sessions"))?,
+    };
+
+    let mut target = None;
+    for (id, agent_type) in candidates {
+        if agent_manager.get_session_status(&id).await.is_ok() {
+            target = Some((id, agent_type));
+            break;
+        }
+    }
+
+    let used_session = if let Some((target_id, agent_type)) = target {
+        let tab_overrides = sqlx::query_scalar::<_, Option<String>>(
+            "SELECT flag_overrides FROM chat_tabs WHERE session_id = ? AND flag_overrides IS NOT NULL LIMIT 1"
+        )
+        .bind(&target_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(AppError::context("Failed to look up tab flag overrides"))?
+        .flatten();
+
+        let flags = apply_flag_overrides(
+            plugin_settings_manager.get_plugin_settings(&plugin_name_for(&agent_type)).flags,
+            tab_overrides.as_deref(),
+        );
+        agent_manager
+            .send_message(&target_id, prompt, Some(flags))
+            .await
+            .map_err(AppError::context("Failed to send fix request"))?;
+        target_id
+    } else {
+        let tab_id = sqlx::query_scalar::<_, String>(
+            "SELECT id FROM chat_tabs WHERE project_id = ? ORDER BY is_active DESC, last_activity DESC LIMIT 1"
+        )
+        .bind(&change.project_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(AppError::context("Failed to find chat tab"))?
+        .ok_or_else(|| AppError::not_found("No running agent session to send the fix request to"))?;
+
+        send_to_tab_session(&db, &agent_manager, &plugin_manager, &plugin_settings_manager, &tab_id, &prompt).await?
+    };
+
+    log::info!("Sent {} review comments to session {}", comments.len(), used_session);
+    Ok(used_session)
+}
+
+/// Unresolve a review comment
+#[tauri::command]
+pub async fn unresolve_review_comment(
+    db: State<'_, Database>,
+    comment_id: String,
+) -> Result<crate::models::ReviewComment, AppError> {
+    log::info!("Unresolving review comment: {}", comment_id);
+
+    sqlx::query(
+        r#"
+        UPDATE review_comments
+        SET resolved = FALSE
+        WHERE id = ?
+        "#
+    )
+    .bind(&comment_id)
+    .execute(db.pool())
+    .await
+    .map_err(AppError::context("Failed to unresolve comment"))?;
+
+    // Fetch and return the updated comment
+    let comment = sqlx::query_as::<_, crate::models::ReviewComment>(
+        r#"
+        SELECT id, file_change_id, line_number, author, comment, timestamp, resolved,
+               suggestion_start_line, suggestion_end_line, suggestion
+        FROM review_comments
+        WHERE id = ?
+        "#
+    )
+    .bind(&comment_id)
+    .fetch_one(db.pool())
+    .await
+    .map_err(AppError::context("Failed to fetch updated comment"))?;
+
+    log::info!("Review comment unresolved successfully");
+    Ok(comment)
+}
+
+/// Delete a review comment
+#[tauri::command]
+pub async fn delete_review_comment(
+    db: State<'_, Database>,
+    comment_id: String,
+) -> Result<(), AppError> {
+    log::info!("Deleting review comment: {}", comment_id);
+
+    sqlx::query(
+        r#"
+        DELETE FROM review_comments
+        WHERE id = ?
+        "#
+    )
+    .bind(&comment_id)
+    .execute(db.pool())
+    .await
+    .map_err(AppError::context("Failed to delete comment"))?;
+
+    log::info!("Review comment deleted successfully");
+    Ok(())
+}
+
+// ============================================================================
+// Agent Session Commands
+// ============================================================================
+
+/// Start an agent session for a project
+///
+/// Without an `agent_type`, the project's default agent is started. When
+/// `tab_id` is a duplicated tab that hasn't run yet, the session forks the
+/// CLI session it was copied from instead of resuming it.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn start_agent_session(
+    app: tauri::AppHandle,
+    db: State<'_, Database>,
+    agent_manager: State<'_, crate::agent_manager::AgentManager>,
+    project_id: String,
+    agent_type: Option<String>,
+    resume_session_id: Option<String>,
+    execution_profile: Option<String>,
+    tab_id: Option<String>,
+) -> Result<crate::agent_manager::AgentSession, AppError> {
+    // Get project to verify it exists and get the root path
+    let project = get_project(db.clone(), project_id.clone())
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Project not found: {}", project_id)))?;
+    let agent_type = crate::agent_defaults::resolve_for_project(&app, &project, agent_type.as_deref())?;
+
+    log::info!("Starting {} agent session for project: {}", agent_type, project_id);
+
+    let fork_source = match &tab_id {
+        Some(tab_id) => tab_fork_source(db.pool(), tab_id).await?,
+        None => None,
+    }
+    .filter(|source| resume_session_id.as_ref().is_none_or(|id| id == source));
+    let resume_session_id = if fork_source.is_some() { None } else { resume_session_id };
+
+    if let Some(ref session_id) = resume_session_id {
+        log::info!("Resuming Claude session: {}", session_id);
+    }
+
+    let settings = crate::models::ProjectSettings::from_json(project.settings.as_deref());
+    let profile = settings.execution_profile(execution_profile.as_deref())?.cloned();
+
+    // Start the agent session
+    let session = agent_manager
+        .start_session(project_id.clone(), agent_type.clone(), project.root_path.clone(), resume_session_id.clone())
+        .await
+        .map_err(AppError::context("Failed to start agent session"))?;
+    if let (Some(source), Some(tab_id)) = (fork_source, &tab_id) {
+        log::info!("Forking Claude session {} for tab {}", source, tab_id);
+        agent_manager
+            .fork_session_from(&session.session_id, source)
+            .await
+            .map_err(AppError::context("Failed to fork session"))?;
+        // The tab picks up the fork's CLI session ID when it is synced
+        sqlx::query("UPDATE chat_tabs SET session_id = ? WHERE id = ?")
+            .bind(&session.session_id)
+            .bind(tab_id)
+            .execute(db.pool())
+            .await
+            .map_err(AppError::context("Failed to link chat tab to session"))?;
+    }
+    let redactor = settings.redaction.redactor();
+    let _ = agent_manager.set_redactor(&session.session_id, redactor).await;
+    let hook = crate::command_gate::hook_for(&settings, &project.id, &session.session_id);
+    let _ = agent_manager.set_command_hook(&session.session_id, hook).await;
+    let _ = agent_manager.set_execution_profile(&session.session_id, profile).await;
+
+    // Save the session to the database
+    sqlx::query(
+        r#"
+        INSERT INTO agent_sessions (id, project_id, task_id, agent_type, started_at, ended_at, status, exit_code, claude_session_id)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#
+    )
+    .bind(&session.session_id)
+    .bind(&session.project_id)
+    .bind::<Option<String>>(None) // task_id
+    .bind(&session.agent_type)
+    .bind(session.started_at)
+    .bind::<Option<i64>>(None) // ended_at
+    .bind("running")
+    .bind::<Option<i64>>(None) // exit_code
+    .bind(&session.claude_session_id)
+    .execute(db.pool())
+    .await
+    .map_err(AppError::context("Failed to save agent session"))?;
+
+    let events = agent_manager
+        .subscribe_events(&session.session_id)
+        .await
+        .map_err(AppError::context("Failed to subscribe to agent events"))?;
+    crate::session_replay::record_events(db.pool().clone(), session.session_id.clone(), events.resubscribe());
+    crate::notes::append_session_summaries(db.pool().clone(), project_id.clone(), session.agent_type.clone(), events.resubscribe());
+    crate::notifications::forward_agent_events(app, db.pool().clone(), project_id.clone(), events);
+
+    // Log activity
+    let _ = log_activity(
+        db,
+        project_id,
+        "agent_start".to_string(),
+        format!("Started {} agent session", agent_type),
+        Some(serde_json::json!({
+            "session_id": session.session_id,
+            "agent_type": agent_type
+        }).to_string()),
+    ).await;
+
+    log::info!("Agent session started: {}", session.session_id);
+    Ok(session)
+}
+
+/// Send the same prompt to several agents and compare what they change
+///
+/// Each agent runs in its own git worktree of the project's HEAD, so the
+/// project must be a git repository. Waits for every agent to finish, up to
+/// `timeout_secs` (default 30 minutes), stops them, then returns the linked
+/// sessions and a combined diff report. If an agent can't be started, the
+/// ones already running are stopped and the comparison is removed.
+#[tauri::command]
+pub async fn run_comparison(
+    db: State<'_, Database>,
+    agent_manager: State<'_, crate::agent_manager::AgentManager>,
+    project_id: String,
+    prompt: String,
+    agents: Vec<String>,
+    timeout_secs: Option<u64>,
+) -> Result<ComparisonReport, AppError> {
+    use crate::agent_comparison::{add_worktree, require_git_repo, worktree_dir};
+    use crate::models::{AgentComparison, ComparisonRun};
+
+    log::info!("Comparing agents {:?} on project {}", agents, project_id);
+
+    let mut agent_types: Vec<String> = Vec::new();
+    for agent in agents {
+        if !agent_types.contains(&agent) {
+            agent_types.push(agent);
+        }
+    }
+    if agent_types.len() < 2 {
+        return Err(AppError::invalid_input("Select at least two different agents to compare"));
+    }
+    if prompt.trim().is_empty() {
+        return Err(AppError::invalid_input("Prompt cannot be empty"));
+    }
+
+    let project = get_project(db.clone(), project_id.clone())
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Project not found: {}", project_id)))?;
+    require_git_repo(&project.root_path)
+        .await
+        .map_err(|e| AppError::invalid_input(e.to_string()))?;
+
+    let comparison = AgentComparison::new(project_id.clone(), prompt.clone());
+    sqlx::query("INSERT INTO agent_comparisons (id, project_id, prompt, created_at) VALUES (?, ?, ?, ?)")
+        .bind(&comparison.id)
+        .bind(&comparison.project_id)
+        .bind(&comparison.prompt)
+        .bind(comparison.created_at)
+        .execute(db.pool())
+        .await
+        .map_err(AppError::context("Failed to save comparison"))?;
+
+    let settings = crate::models::ProjectSettings::from_json(project.settings.as_deref());
+    let profile = settings.execution_profile(None)?.cloned();
+
+    // Runs are tracked from the moment their worktree exists so a failure
+    // part way through can clean up everything started so far
+    let mut runs: Vec<ComparisonRun> = Vec::with_capacity(agent_types.len());
+    let mut waiters = Vec::with_capacity(agent_types.len());
+    let started: Result<(), AppError> = async {
+        for (index, agent_type) in agent_types.iter().enumerate() {
+            let dir = worktree_dir(&comparison.id, index, agent_type);
+            add_worktree(&project.root_path, &dir)
+                .await
+                .map_err(|e| AppError::from(e).wrap(format!("Failed to create worktree for {}", agent_type)))?;
+            runs.push(ComparisonRun {
+                comparison_id: comparison.id.clone(),
+                session_id: String::new(),
+                agent_type: agent_type.clone(),
+                worktree_path: dir.to_string_lossy().to_string(),
+                isolated: true,
+            });
+            let run = runs.last_mut().expect("run was just pushed");
+
+            let session = agent_manager
+                .start_session(project.id.clone(), agent_type.clone(), run.worktree_path.clone(), None)
+                .await
+                .map_err(|e| AppError::from(e).wrap(format!("Failed to start {} session", agent_type)))?;
+            run.session_id = session.session_id.clone();
+            let redactor = settings.redaction.redactor();
+            let _ = agent_manager.set_redactor(&session.session_id, redactor).await;
+            let hook = crate::command_gate::hook_for(&settings, &project.id, &session.session_id);
+            let _ = agent_manager.set_command_hook(&session.session_id, hook).await;
+            let _ = agent_manager.set_execution_profile(&session.session_id, profile.clone()).await;
+
+            sqlx::query(
+                r#"
+                INSERT INTO agent_sessions (id, project_id, agent_type, started_at, ended_at, status, exit_code, claude_session_id)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+                "#
+            )
+            .bind(&session.session_id)
+            .bind(&session.project_id)
+            .bind(&session.agent_type)
+            .bind(session.started_at)
+            .bind::<Option<i64>>(None) // ended_at
+            .bind("running")
+            .bind::<Option<i64>>(None) // exit_code
+            .bind(&session.claude_session_id)
+            .execute(db.pool())
+            .await
+            .map_err(AppError::context("Failed to save agent session"))?;
+
+            sqlx::query(
+                r#"
+                INSERT INTO agent_comparison_runs (comparison_id, session_id, agent_type, worktree_path, isolated)
+                VALUES (?, ?, ?, ?, ?)
+                "#
+            )
+            .bind(&run.comparison_id)
+            .bind(&run.session_id)
+            .bind(&run.agent_type)
+            .bind(&run.worktree_path)
+            .bind(run.isolated)
+            .execute(db.pool())
+            .await
+            .map_err(AppError::context("Failed to save comparison run"))?;
+
+            // Subscribe before sending so the exit event isn't missed
+            let mut events = agent_manager
+                .subscribe_events(&session.session_id)
+                .await
+                .map_err(AppError::context("Failed to subscribe to agent events"))?;
+            crate::session_replay::record_events(db.pool().clone(), session.session_id.clone(), events.resubscribe());
+
+            agent_manager
+                .send_message(&session.session_id, prompt.clone(), None)
+                .await
+                .map_err(|e| AppError::from(e).wrap(format!("Failed to send prompt to {}", agent_type)))?;
+
+            waiters.push(async move {
+                use tokio::sync::broadcast::error::RecvError;
+                loop {
+                    match events.recv().await {
+                        Ok(crate::output_parser::AgentEvent::ProcessExited { .. })
+                        | Ok(crate::output_parser::AgentEvent::TurnCompleted { .. })
+                        | Err(RecvError::Closed) => break,
+                        _ => {}
+                    }
+                }
+            });
+        }
+        Ok(())
+    }
+    .await;
+
+    if let Err(e) = started {
+        drop(waiters);
+        end_comparison_sessions(&db, &agent_manager, &runs).await;
+        if let Err(cleanup) = crate::agent_comparison::remove_worktrees(&project.root_path, &runs).await {
+            log::warn!("Failed to remove worktrees of comparison {}: {}", comparison.id, cleanup);
+        }
+        if let Err(cleanup) = delete_comparison_rows(db.pool(), &comparison.id).await {
+            log::warn!("Failed to delete comparison {}: {}", comparison.id, cleanup);
+        }
+        return Err(e);
+    }
+
+    let timeout = std::time::Duration::from_secs(timeout_secs.unwrap_or(30 * 60));
+    if tokio::time::timeout(timeout, futures::future::join_all(waiters)).await.is_err() {
+        log::warn!("Comparison {} timed out; stopping its agents and reporting changes so far", comparison.id);
+    }
+    // The worktrees stay for the report and are removed by discard_comparison
+    end_comparison_sessions(&db, &agent_manager, &runs).await;
+
+    let report = comparison_report(comparison, runs).await?;
+    let _ = log_activity(
+        db,
+        project_id,
+        "agent_comparison".to_string(),
+        format!("Compared {} on the same prompt", agent_types.join(", ")),
+        Some(serde_json::json!({
+            "comparison_id": report.comparison.id,
+            "sessions": report.runs.iter().map(|r| &r.session_id).collect::<Vec<_>>()
+        }).to_string()),
+    ).await;
+
+    log::info!("Comparison {} complete", report.comparison.id);
+    Ok(report)
+}
 
-/// Get project statistics
+/// Rebuild the diff report for an earlier comparison
 #[tauri::command]
-pub async fn get_project_stats(
+pub async fn get_comparison_report(
     db: State<'_, Database>,
-    #[allow(non_snake_case)]
-    projectId: String,
-) -> Result<ProjectStats, String> {
-    log::info!("Fetching stats for project: {}", projectId);
+    comparison_id: String,
+) -> Result<ComparisonReport, AppError> {
+    log::info!("Building report for comparison {}", comparison_id);
 
-    // Verify project exists
-    let project = get_project(db.clone(), projectId.clone())
+    let (comparison, runs) = fetch_comparison(db.pool(), &comparison_id).await?;
+    comparison_report(comparison, runs).await
+}
+
+/// Delete a comparison and the worktrees its agents worked in
+#[tauri::command]
+pub async fn discard_comparison(db: State<'_, Database>, comparison_id: String) -> Result<(), AppError> {
+    log::info!("Discarding comparison {}", comparison_id);
+
+    let (comparison, runs) = fetch_comparison(db.pool(), &comparison_id).await?;
+    let project = get_project(db.clone(), comparison.project_id.clone())
         .await?
-        .ok_or_else(|| format!("Project not found: {}", projectId))?;
+        .ok_or_else(|| AppError::not_found(format!("Project not found: {}", comparison.project_id)))?;
+    crate::agent_comparison::remove_worktrees(&project.root_path, &runs)
+        .await
+        .map_err(AppError::context("Failed to remove comparison worktrees"))?;
 
-    // Count distinct files changed
-    let files_changed_result = sqlx::query_scalar::<_, i64>(
-        r#"
-        SELECT COUNT(DISTINCT file_path)
-        FROM file_changes
-        WHERE project_id = ?
-        "#
-    )
-    .bind(&projectId)
-    .fetch_one(db.pool())
-    .await;
+    delete_comparison_rows(db.pool(), &comparison_id).await
+}
 
-    let files_changed = files_changed_result.unwrap_or(0) as usize;
+/// Delete a comparison and its runs from the database
+async fn delete_comparison_rows(pool: &sqlx::SqlitePool, comparison_id: &str) -> Result<(), AppError> {
+    let mut tx = pool.begin().await
+        .map_err(AppError::context("Failed to start transaction"))?;
+    sqlx::query("DELETE FROM agent_comparison_runs WHERE comparison_id = ?")
+        .bind(comparison_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::context("Failed to delete comparison runs"))?;
+    sqlx::query("DELETE FROM agent_comparisons WHERE id = ?")
+        .bind(comparison_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::context("Failed to delete comparison"))?;
+    tx.commit().await
+        .map_err(AppError::context("Failed to commit comparison removal"))?;
 
-    // Count commits from git
-    let commits = count_git_commits(&project.root_path).await.unwrap_or(0);
+    Ok(())
+}
 
-    // Count chat messages
-    let messages_result = sqlx::query_scalar::<_, i64>(
-        r#"
-        SELECT COUNT(*)
-        FROM chat_messages
-        WHERE project_id = ?
-        "#
-    )
-    .bind(&projectId)
-    .fetch_one(db.pool())
-    .await;
+/// Stop the agents of a comparison and mark their sessions stopped; agents
+/// that already exited are only marked
+async fn end_comparison_sessions(
+    db: &Database,
+    agent_manager: &crate::agent_manager::AgentManager,
+    runs: &[crate::models::ComparisonRun],
+) {
+    for run in runs.iter().filter(|run| !run.session_id.is_empty()) {
+        let _ = agent_manager.stop_session(&run.session_id).await;
+        if let Err(e) = mark_session_stopped(db.pool(), &run.session_id).await {
+            log::warn!("Failed to mark comparison session {} stopped: {}", run.session_id, e);
+        }
+    }
+}
 
-    let messages = messages_result.unwrap_or(0) as usize;
+/// A comparison and its runs
+async fn fetch_comparison(
+    pool: &sqlx::SqlitePool,
+    comparison_id: &str,
+) -> Result<(crate::models::AgentComparison, Vec<crate::models::ComparisonRun>), AppError> {
+    use crate::models::{AgentComparison, ComparisonRun};
 
-    // Count completed tasks
-    let tasks_completed_result = sqlx::query_scalar::<_, i64>(
-        r#"
-        SELECT COUNT(*)
-        FROM tasks
-        WHERE project_id = ? AND status = 'completed'
-        "#
+    let comparison = sqlx::query_as::<_, AgentComparison>(
+        "SELECT id, project_id, prompt, created_at FROM agent_comparisons WHERE id = ?"
     )
-    .bind(&projectId)
-    .fetch_one(db.pool())
-    .await;
-
-    let tasks_completed = tasks_completed_result.unwrap_or(0) as usize;
+    .bind(comparison_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(AppError::context("Failed to fetch comparison"))?
+    .ok_or_else(|| AppError::not_found(format!("Comparison not found: {}", comparison_id)))?;
 
-    // Count total tasks
-    let tasks_total_result = sqlx::query_scalar::<_, i64>(
+    let runs = sqlx::query_as::<_, ComparisonRun>(
         r#"
-        SELECT COUNT(*)
-        FROM tasks
-        WHERE project_id = ?
+        SELECT comparison_id, session_id, agent_type, worktree_path, isolated
+        FROM agent_comparison_runs
+        WHERE comparison_id = ?
+        ORDER BY worktree_path ASC
         "#
     )
-    .bind(&projectId)
-    .fetch_one(db.pool())
-    .await;
+    .bind(comparison_id)
+    .fetch_all(pool)
+    .await
+    .map_err(AppError::context("Failed to fetch comparison runs"))?;
 
-    let tasks_total = tasks_total_result.unwrap_or(0) as usize;
+    Ok((comparison, runs))
+}
 
-    let stats = ProjectStats {
-        files_changed,
-        commits,
-        messages,
-        tasks_completed,
-        tasks_total,
-    };
+/// Collect each run's diff and render the combined report
+async fn comparison_report(
+    comparison: crate::models::AgentComparison,
+    runs: Vec<crate::models::ComparisonRun>,
+) -> Result<ComparisonReport, AppError> {
+    let mut diffs = Vec::with_capacity(runs.len());
+    for run in &runs {
+        let diff = crate::agent_comparison::collect_diff(run)
+            .await
+            .map_err(AppError::context("Failed to collect changes"))?;
+        diffs.push(diff);
+    }
+    let report = crate::agent_comparison::render_report(&comparison.prompt, &diffs);
 
-    log::info!("Project stats: {:?}", stats);
-    Ok(stats)
+    Ok(ComparisonReport { comparison, runs, report })
 }
 
-/// Count commits in a git repository
-async fn count_git_commits(path: &str) -> Result<usize> {
-    use std::process::Command;
+/// Send a message to an agent session
+///
+/// Flags come from the plugin's settings, overridden by those of the chat tab
+/// that owns the session, if any.
+#[tauri::command]
+pub async fn send_to_agent(
+    db: State<'_, Database>,
+    agent_manager: State<'_, crate::agent_manager::AgentManager>,
+    plugin_settings_manager: State<'_, crate::plugin_settings::PluginSettingsManager>,
+    session_id: String,
+    message: String,
+    plugin_name: Option<String>,
+) -> Result<(), AppError> {
+    log::info!("Sending message to agent session {}: {}", session_id, message);
 
-    log::debug!("Counting git commits in: {}", path);
+    // Get flag settings for the plugin if plugin_name is provided
+    let flag_settings = plugin_name.as_ref().map(|name| {
+        plugin_settings_manager.get_plugin_settings(name).flags
+    });
 
-    // Check if git is available and the path is a git repository
-    let check_output = Command::new("git")
-        .args(&["rev-parse", "--git-dir"])
-        .current_dir(path)
-        .output();
+    // Apply the owning tab's overrides on top
+    let tab_overrides = sqlx::query_scalar::<_, Option<String>>(
+        "SELECT flag_overrides FROM chat_tabs WHERE session_id = ? AND flag_overrides IS NOT NULL LIMIT 1"
+    )
+    .bind(&session_id)
+    .fetch_optional(db.pool())
+    .await
+    .map_err(AppError::context("Failed to look up tab flag overrides"))?
+    .flatten();
 
-    match check_output {
-        Ok(output) if output.status.success() => {
-            // It's a git repo, count commits
-            let count_output = Command::new("git")
-                .args(&["rev-list", "--count", "HEAD"])
-                .current_dir(path)
-                .output()
-                .context("Failed to execute git rev-list")?;
+    let flag_settings = match tab_overrides {
+        Some(overrides) => Some(apply_flag_overrides(flag_settings.unwrap_or_default(), Some(&overrides))),
+        None => flag_settings,
+    };
 
-            if count_output.status.success() {
-                let count_str = String::from_utf8_lossy(&count_output.stdout);
-                let count = count_str.trim().parse::<usize>().unwrap_or(0);
-                log::debug!("Found {} commits", count);
-                Ok(count)
-            } else {
-                // No commits yet (empty repository)
-                log::debug!("Empty git repository (no commits)");
-                Ok(0)
-            }
-        }
-        _ => {
-            // Not a git repository or git not available
-            log::debug!("Not a git repository or git not available");
-            Ok(0)
-        }
+    agent_manager
+        .send_message(&session_id, message, flag_settings)
+        .await
+        .map_err(AppError::context("Failed to send message"))?;
+
+    Ok(())
+}
+
+/// Read output from an agent session
+#[tauri::command]
+pub async fn read_agent_output(
+    agent_manager: State<'_, crate::agent_manager::AgentManager>,
+    session_id: String,
+    timeout_ms: Option<u64>,
+) -> Result<Vec<String>, AppError> {
+    log::info!("Reading output from agent session: {}", session_id);
+
+    let output = if let Some(timeout) = timeout_ms {
+        agent_manager
+            .read_output_with_timeout(&session_id, timeout)
+            .await
+    } else {
+        agent_manager
+            .read_output(&session_id)
+            .await
     }
+    .map_err(AppError::context("Failed to read output"))?;
+
+    // Strip ANSI escape codes from each line for clean UI display
+    let cleaned_output: Vec<String> = output
+        .into_iter()
+        .map(|line| {
+            let cleaned_bytes = strip_ansi_escapes::strip(&line);
+            String::from_utf8_lossy(&cleaned_bytes).to_string()
+        })
+        .collect();
+
+    Ok(cleaned_output)
 }
 
-// ============================================================================
-// File Watcher Commands
-// ============================================================================
+/// Read parsed events from an agent session
+#[tauri::command]
+pub async fn read_agent_events(
+    agent_manager: State<'_, crate::agent_manager::AgentManager>,
+    session_id: String,
+) -> Result<Vec<crate::output_parser::AgentEvent>, AppError> {
+    log::info!("Reading events from agent session: {}", session_id);
 
-/// Start watching a project for file changes
+    let events = agent_manager
+        .read_events(&session_id)
+        .await
+        .map_err(AppError::context("Failed to read events"))?;
+
+    log::info!("Retrieved {} events from session {}", events.len(), session_id);
+    Ok(events)
+}
+
+/// Read both raw output and parsed events from an agent session
 #[tauri::command]
-pub async fn start_watching_project(
+pub async fn read_agent_output_and_events(
+    agent_manager: State<'_, crate::agent_manager::AgentManager>,
+    session_id: String,
+) -> Result<(Vec<String>, Vec<crate::output_parser::AgentEvent>), AppError> {
+    log::info!("Reading output and events from agent session: {}", session_id);
+
+    let (output, events) = agent_manager
+        .read_output_and_events(&session_id)
+        .await
+        .map_err(AppError::context("Failed to read output and events"))?;
+
+    // Strip ANSI escape codes from output lines
+    let cleaned_output: Vec<String> = output
+        .into_iter()
+        .map(|line| {
+            let cleaned_bytes = strip_ansi_escapes::strip(&line);
+            String::from_utf8_lossy(&cleaned_bytes).to_string()
+        })
+        .collect();
+
+    log::info!(
+        "Retrieved {} output lines and {} events from session {}",
+        cleaned_output.len(),
+        events.len(),
+        session_id
+    );
+
+    Ok((cleaned_output, events))
+}
+
+/// Stop an agent session
+#[tauri::command]
+pub async fn stop_agent_session(
     db: State<'_, Database>,
-    watcher: State<'_, FileWatcherManager>,
-    project_id: String,
-) -> Result<String, String> {
-    log::info!("Starting file watcher for project: {}", project_id);
+    agent_manager: State<'_, crate::agent_manager::AgentManager>,
+    session_id: String,
+) -> Result<(), AppError> {
+    log::info!("Stopping agent session: {}", session_id);
 
-    // Get project to verify it exists and get the path
-    let project = get_project(db.clone(), project_id.clone())
-        .await?
-        .ok_or_else(|| format!("Project not found: {}", project_id))?;
+    end_agent_session(&db, &agent_manager, &session_id).await?;
 
-    // Start watching
-    let session_id = watcher
-        .start_watching(project_id.clone(), project.root_path, db.pool().clone())
+    log::info!("Agent session stopped: {}", session_id);
+    Ok(())
+}
+
+/// Stop every running agent session; returns how many were stopped
+#[tauri::command]
+pub async fn stop_all_agent_sessions(
+    db: State<'_, Database>,
+    agent_manager: State<'_, crate::agent_manager::AgentManager>,
+) -> Result<usize, AppError> {
+    log::info!("Stopping all agent sessions");
+    stop_running_sessions(&db, &agent_manager).await
+}
+
+/// Stop every running agent session and record when each ended
+pub(crate) async fn stop_running_sessions(
+    db: &Database,
+    agent_manager: &crate::agent_manager::AgentManager,
+) -> Result<usize, AppError> {
+    let mut stopped = 0;
+    for session in agent_manager.list_sessions().await {
+        end_agent_session(db, agent_manager, &session.session_id).await?;
+        stopped += 1;
+    }
+
+    log::info!("Stopped {} agent sessions", stopped);
+    Ok(stopped)
+}
+
+/// Stop an agent session and mark it stopped in the database
+async fn end_agent_session(
+    db: &Database,
+    agent_manager: &crate::agent_manager::AgentManager,
+    session_id: &str,
+) -> Result<(), AppError> {
+    agent_manager
+        .stop_session(session_id)
         .await
-        .map_err(|e| format!("Failed to start watching: {}", e))?;
+        .map_err(AppError::context("Failed to stop session"))?;
 
-    log::info!("File watcher started for project {} with session {}", project_id, session_id);
-    Ok(session_id)
+    mark_session_stopped(db.pool(), session_id).await
+}
+
+/// Record that an agent session was stopped
+async fn mark_session_stopped(pool: &sqlx::SqlitePool, session_id: &str) -> Result<(), AppError> {
+    let now = chrono::Utc::now().timestamp();
+    sqlx::query(
+        r#"
+        UPDATE agent_sessions
+        SET ended_at = ?, status = 'stopped'
+        WHERE id = ?
+        "#
+    )
+    .bind(now)
+    .bind(session_id)
+    .execute(pool)
+    .await
+    .map_err(AppError::context("Failed to update agent session"))?;
+    Ok(())
 }
 
-/// Stop watching a project
+/// Sync the claude_session_id from runtime session to database
 #[tauri::command]
-pub async fn stop_watching_project(
-    watcher: State<'_, FileWatcherManager>,
-    project_id: String,
-) -> Result<bool, String> {
-    log::info!("Stopping file watcher for project: {}", project_id);
+pub async fn sync_claude_session_id(
+    db: State<'_, Database>,
+    agent_manager: State<'_, crate::agent_manager::AgentManager>,
+    session_id: String,
+) -> Result<Option<String>, AppError> {
+    log::info!("Syncing Claude session ID for session: {}", session_id);
 
-    watcher
-        .stop_watching(&project_id)
-        .map_err(|e| format!("Failed to stop watching: {}", e))?;
+    // Get the session from agent manager to see if it has a claude_session_id
+    let session = agent_manager
+        .get_session_status(&session_id)
+        .await
+        .map_err(AppError::context("Failed to get session"))?;
 
-    log::info!("File watcher stopped for project {}", project_id);
-    Ok(true)
-}
+    // If the session has a claude_session_id, update it in the database
+    if let Some(claude_session_id) = session.claude_session_id {
+        log::info!("Updating database with Claude session ID: {}", claude_session_id);
 
-/// Check if a project is being watched
-#[tauri::command]
-pub async fn is_watching_project(
-    watcher: State<'_, FileWatcherManager>,
-    project_id: String,
-) -> Result<bool, String> {
-    Ok(watcher.is_watching(&project_id))
+        sqlx::query(
+            r#"
+            UPDATE agent_sessions
+            SET claude_session_id = ?
+            WHERE id = ?
+            "#
+        )
+        .bind(&claude_session_id)
+        .bind(&session_id)
+        .execute(db.pool())
+        .await
+        .map_err(AppError::context("Failed to update claude_session_id"))?;
+        record_forked_session(db.pool(), &session_id, &claude_session_id).await?;
+
+        log::info!("Claude session ID synced successfully");
+        Ok(Some(claude_session_id))
+    } else {
+        Ok(None)
+    }
 }
 
-/// Get pending file changes for a project
+/// Get the status of an agent session
 #[tauri::command]
-pub async fn get_pending_changes(
-    db: State<'_, Database>,
-    project_id: String,
-) -> Result<Vec<FileChange>, String> {
-    log::info!("Fetching pending changes for project: {}", project_id);
+pub async fn get_agent_status(
+    agent_manager: State<'_, crate::agent_manager::AgentManager>,
+    session_id: String,
+) -> Result<crate::agent_manager::AgentSession, AppError> {
+    log::info!("Getting status for agent session: {}", session_id);
 
-    let changes = sqlx::query_as::<_, FileChange>(
-        r#"
-        SELECT id, project_id, session_id, file_path, change_type, diff, reviewed, approved, timestamp
-        FROM file_changes
-        WHERE project_id = ? AND reviewed = FALSE
-        ORDER BY timestamp DESC
-        "#
-    )
-    .bind(&project_id)
-    .fetch_all(db.pool())
-    .await
-    .map_err(|e| format!("Failed to fetch pending changes: {}", e))?;
+    let session = agent_manager
+        .get_session_status(&session_id)
+        .await
+        .map_err(AppError::context("Failed to get session status"))?;
 
-    log::info!("Found {} pending changes for project {}", changes.len(), project_id);
-    Ok(changes)
+    Ok(session)
 }
 
-/// Get all file changes for a project (including reviewed ones)
+/// List all active agent sessions
 #[tauri::command]
-pub async fn get_all_changes(
-    db: State<'_, Database>,
-    project_id: String,
-    limit: Option<i64>,
-) -> Result<Vec<FileChange>, String> {
-    log::info!("Fetching all changes for project: {}", project_id);
-
-    let limit_value = limit.unwrap_or(100).min(500);
+pub async fn list_agent_sessions(
+    agent_manager: State<'_, crate::agent_manager::AgentManager>,
+) -> Result<Vec<crate::agent_manager::AgentSession>, AppError> {
+    log::info!("Listing all agent sessions");
 
-    let changes = sqlx::query_as::<_, FileChange>(
-        r#"
-        SELECT id, project_id, session_id, file_path, change_type, diff, reviewed, approved, timestamp
-        FROM file_changes
-        WHERE project_id = ?
-        ORDER BY timestamp DESC
-        LIMIT ?
-        "#
-    )
-    .bind(&project_id)
-    .bind(limit_value)
-    .fetch_all(db.pool())
-    .await
-    .map_err(|e| format!("Failed to fetch changes: {}", e))?;
+    let sessions = agent_manager.list_sessions().await;
 
-    log::info!("Found {} changes for project {}", changes.len(), project_id);
-    Ok(changes)
+    Ok(sessions)
 }
 
-/// Approve a file change
+/// Check if an agent session is healthy
 #[tauri::command]
-pub async fn approve_change(
-    db: State<'_, Database>,
-    change_id: String,
-) -> Result<FileChange, String> {
-    log::info!("Approving change: {}", change_id);
-
-    // Update the change
-    sqlx::query(
-        r#"
-        UPDATE file_changes
-        SET reviewed = TRUE, approved = TRUE
-        WHERE id = ?
-        "#
-    )
-    .bind(&change_id)
-    .execute(db.pool())
-    .await
-    .map_err(|e| format!("Failed to approve change: {}", e))?;
+pub async fn check_agent_health(
+    agent_manager: State<'_, crate::agent_manager::AgentManager>,
+    session_id: String,
+) -> Result<bool, AppError> {
+    log::info!("Checking health for agent session: {}", session_id);
 
-    // Fetch the updated change
-    let change = sqlx::query_as::<_, FileChange>(
-        r#"
-        SELECT id, project_id, session_id, file_path, change_type, diff, reviewed, approved, timestamp
-        FROM file_changes
-        WHERE id = ?
-        "#
-    )
-    .bind(&change_id)
-    .fetch_one(db.pool())
-    .await
-    .map_err(|e| format!("Failed to fetch updated change: {}", e))?;
+    let is_healthy = agent_manager
+        .health_check(&session_id)
+        .await
+        .map_err(AppError::context("Failed to check health"))?;
 
-    log::info!("Change approved: {}", change_id);
-    Ok(change)
+    Ok(is_healthy)
 }
 
-/// Reject a file change
+/// Get agent sessions history for a project from database
 #[tauri::command]
-pub async fn reject_change(
+pub async fn get_project_sessions(
     db: State<'_, Database>,
-    change_id: String,
-) -> Result<FileChange, String> {
-    log::info!("Rejecting change: {}", change_id);
-
-    // Update the change
-    sqlx::query(
-        r#"
-        UPDATE file_changes
-        SET reviewed = TRUE, approved = FALSE
-        WHERE id = ?
-        "#
-    )
-    .bind(&change_id)
-    .execute(db.pool())
-    .await
-    .map_err(|e| format!("Failed to reject change: {}", e))?;
+    #[allow(non_snake_case)] projectId: String,
+) -> Result<Vec<crate::models::AgentSession>, AppError> {
+    log::info!("Getting agent sessions for project: {}", projectId);
 
-    // Fetch the updated change
-    let change = sqlx::query_as::<_, FileChange>(
+    let sessions = sqlx::query_as::<_, crate::models::AgentSession>(
         r#"
-        SELECT id, project_id, session_id, file_path, change_type, diff, reviewed, approved, timestamp
-        FROM file_changes
-        WHERE id = ?
+        SELECT id, project_id, task_id, agent_type, started_at, ended_at, status, exit_code, claude_session_id
+        FROM agent_sessions
+        WHERE project_id = ?
+        ORDER BY started_at DESC
+        LIMIT 50
         "#
     )
-    .bind(&change_id)
-    .fetch_one(db.pool())
+    .bind(&projectId)
+    .fetch_all(db.pool())
     .await
-    .map_err(|e| format!("Failed to fetch updated change: {}", e))?;
+    .map_err(AppError::context("Failed to get project sessions"))?;
 
-    log::info!("Change rejected: {}", change_id);
-    Ok(change)
+    Ok(sessions)
 }
 
-/// Get the diff content for a file change
+/// Parse agent output into structured events
 #[tauri::command]
-pub async fn get_file_diff(
-    db: State<'_, Database>,
-    change_id: String,
-) -> Result<String, String> {
-    log::info!("Fetching diff for change: {}", change_id);
+pub async fn parse_agent_output(
+    session_id: String,
+    agent_type: String,
+    output: Vec<String>,
+) -> Result<crate::agent_adapter::ParsedOutput, AppError> {
+    log::info!("Parsing output from {} agent session: {}", agent_type, session_id);
 
-    let change = sqlx::query_as::<_, FileChange>(
-        r#"
-        SELECT id, project_id, session_id, file_path, change_type, diff, reviewed, approved, timestamp
-        FROM file_changes
-        WHERE id = ?
-        "#
-    )
-    .bind(&change_id)
-    .fetch_one(db.pool())
-    .await
-    .map_err(|e| format!("Failed to fetch change: {}", e))?;
+    use crate::agent_adapter::AgentAdapter;
 
-    Ok(change.diff.unwrap_or_default())
+    // Create the appropriate adapter based on agent type
+    let adapter: Box<dyn AgentAdapter> = match agent_type.to_lowercase().as_str() {
+        "claude" | "claude-code" => {
+            Box::new(crate::adapters::claude_adapter::ClaudeCodeAdapter::new(session_id.clone()))
+        }
+        "aider" => {
+            Box::new(crate::agent_adapter::AiderAdapter::new(session_id.clone()))
+        }
+        _ => {
+            return Err(AppError::invalid_input(format!("Unsupported agent type: {}", agent_type)));
+        }
+    };
+
+    // Parse the output
+    let parsed = adapter.parse_output(&session_id, &output);
+
+    Ok(parsed)
 }
 
 // ============================================================================
-// Review Comment Commands
+// Chat Tab Commands
 // ============================================================================
 
-/// Add a review comment to a file change
-#[tauri::command]
-pub async fn add_review_comment(
-    db: State<'_, Database>,
-    file_change_id: String,
-    author: String,
-    comment: String,
-    line_number: Option<i64>,
-) -> Result<crate::models::ReviewComment, String> {
-    log::info!("Adding review comment to file change: {}", file_change_id);
-
-    let review_comment = crate::models::ReviewComment::new(
-        file_change_id.clone(),
-        author,
-        comment,
-        line_number,
-    );
+/// Columns a `ChatTab` is read from, in the order `insert_chat_tab` binds them
+const CHAT_TAB_COLUMNS: &str =
+    "id, project_id, agent_type, session_id, cli_session_id, label, tab_order, is_active, created_at, last_activity, \
+     flag_overrides, forked_from_cli_session_id";
 
-    sqlx::query(
-        r#"
-        INSERT INTO review_comments (id, file_change_id, line_number, author, comment, timestamp, resolved)
-        VALUES (?, ?, ?, ?, ?, ?, ?)
-        "#
-    )
-    .bind(&review_comment.id)
-    .bind(&review_comment.file_change_id)
-    .bind(&review_comment.line_number)
-    .bind(&review_comment.author)
-    .bind(&review_comment.comment)
-    .bind(review_comment.timestamp)
-    .bind(review_comment.resolved)
-    .execute(db.pool())
-    .await
-    .map_err(|e| format!("Failed to add review comment: {}", e))?;
+/// Fetch a chat tab by id
+async fn fetch_chat_tab(pool: &sqlx::SqlitePool, tab_id: &str) -> Result<Option<ChatTab>, AppError> {
+    sqlx::query_as::<_, ChatTab>(&format!("SELECT {} FROM chat_tabs WHERE id = ?", CHAT_TAB_COLUMNS))
+        .bind(tab_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(AppError::context("Failed to fetch chat tab"))
+}
 
-    log::info!("Review comment added successfully: {}", review_comment.id);
-    Ok(review_comment)
+/// Insert a chat tab row
+pub(crate) async fn insert_chat_tab<'e, E>(executor: E, tab: &ChatTab) -> Result<(), sqlx::Error>
+where
+    E: sqlx::Executor<'e, Database = sqlx::Sqlite>,
+{
+    sqlx::query(&format!(
+        "INSERT INTO chat_tabs ({}) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        CHAT_TAB_COLUMNS
+    ))
+    .bind(&tab.id)
+    .bind(&tab.project_id)
+    .bind(&tab.agent_type)
+    .bind(&tab.session_id)
+    .bind(&tab.cli_session_id)
+    .bind(&tab.label)
+    .bind(tab.tab_order)
+    .bind(tab.is_active)
+    .bind(tab.created_at)
+    .bind(tab.last_activity)
+    .bind(&tab.flag_overrides)
+    .bind(&tab.forked_from_cli_session_id)
+    .execute(executor)
+    .await?;
+    Ok(())
 }
 
-/// Get all review comments for a file change
+/// Get all chat tabs for a project
 #[tauri::command]
-pub async fn get_review_comments(
+pub async fn get_chat_tabs(
+    project_id: String,
     db: State<'_, Database>,
-    file_change_id: String,
-) -> Result<Vec<crate::models::ReviewComment>, String> {
-    log::info!("Fetching review comments for file change: {}", file_change_id);
+) -> Result<Vec<ChatTab>, AppError> {
+    let pool = db.pool();
 
-    let comments = sqlx::query_as::<_, crate::models::ReviewComment>(
-        r#"
-        SELECT id, file_change_id, line_number, author, comment, timestamp, resolved
-        FROM review_comments
-        WHERE file_change_id = ?
-        ORDER BY timestamp ASC
-        "#
-    )
-    .bind(&file_change_id)
-    .fetch_all(db.pool())
+    sqlx::query_as::<_, ChatTab>(&format!(
+        "SELECT {} FROM chat_tabs WHERE project_id = ? ORDER BY tab_order ASC",
+        CHAT_TAB_COLUMNS
+    ))
+    .bind(&project_id)
+    .fetch_all(pool)
     .await
-    .map_err(|e| format!("Failed to fetch review comments: {}", e))?;
-
-    log::info!("Fetched {} review comments", comments.len());
-    Ok(comments)
+    .map_err(AppError::context("Failed to get chat tabs"))
 }
 
-/// Resolve a review comment
+/// Create a new chat tab
+///
+/// Without an `agent_type`, the project's default agent is used.
 #[tauri::command]
-pub async fn resolve_review_comment(
+pub async fn create_chat_tab(
+    app: tauri::AppHandle,
+    project_id: String,
+    agent_type: Option<String>,
+    label: Option<String>,
     db: State<'_, Database>,
-    comment_id: String,
-) -> Result<crate::models::ReviewComment, String> {
-    log::info!("Resolving review comment: {}", comment_id);
+) -> Result<ChatTab, AppError> {
+    let project = get_project(db.clone(), project_id.clone())
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Project not found: {}", project_id)))?;
+    let agent_type = crate::agent_defaults::resolve_for_project(&app, &project, agent_type.as_deref())?;
+    let pool = db.pool();
 
-    sqlx::query(
-        r#"
-        UPDATE review_comments
-        SET resolved = TRUE
-        WHERE id = ?
-        "#
+    // Get the max tab_order for this project
+    let max_order: Option<i64> = sqlx::query_scalar(
+        "SELECT MAX(tab_order) FROM chat_tabs WHERE project_id = ?"
     )
-    .bind(&comment_id)
-    .execute(db.pool())
+    .bind(&project_id)
+    .fetch_one(pool)
     .await
-    .map_err(|e| format!("Failed to resolve comment: {}", e))?;
+    .map_err(AppError::context("Failed to get max tab order"))?;
 
-    // Fetch and return the updated comment
-    let comment = sqlx::query_as::<_, crate::models::ReviewComment>(
-        r#"
-        SELECT id, file_change_id, line_number, author, comment, timestamp, resolved
-        FROM review_comments
-        WHERE id = ?
-        "#
-    )
-    .bind(&comment_id)
-    .fetch_one(db.pool())
-    .await
-    .map_err(|e| format!("Failed to fetch updated comment: {}", e))?;
+    let new_order = max_order.unwrap_or(-1) + 1;
+    let mut tab = ChatTab::new(project_id.clone(), agent_type, new_order);
+    tab.label = label;
 
-    log::info!("Review comment resolved successfully");
-    Ok(comment)
+    // If this is the first tab, make it active
+    if new_order == 0 {
+        tab.is_active = true;
+    }
+
+    insert_chat_tab(pool, &tab)
+        .await
+        .map_err(AppError::context("Failed to create chat tab"))?;
+
+    log::info!("Created chat tab {} for project {}", tab.id, project_id);
+    Ok(tab)
 }
 
-/// Unresolve a review comment
+/// Update a chat tab
 #[tauri::command]
-pub async fn unresolve_review_comment(
+pub async fn update_chat_tab(
+    tab_id: String,
+    label: Option<String>,
+    session_id: Option<String>,
+    cli_session_id: Option<String>,
     db: State<'_, Database>,
-    comment_id: String,
-) -> Result<crate::models::ReviewComment, String> {
-    log::info!("Unresolving review comment: {}", comment_id);
+) -> Result<ChatTab, AppError> {
+    let pool = db.pool();
+    let now = chrono::Utc::now().timestamp();
 
+    // Build update query dynamically based on what's provided
     sqlx::query(
-        r#"
-        UPDATE review_comments
-        SET resolved = FALSE
-        WHERE id = ?
-        "#
-    )
-    .bind(&comment_id)
-    .execute(db.pool())
-    .await
-    .map_err(|e| format!("Failed to unresolve comment: {}", e))?;
-
-    // Fetch and return the updated comment
-    let comment = sqlx::query_as::<_, crate::models::ReviewComment>(
-        r#"
-        SELECT id, file_change_id, line_number, author, comment, timestamp, resolved
-        FROM review_comments
-        WHERE id = ?
-        "#
+        "UPDATE chat_tabs
+         SET label = COALESCE(?, label),
+             session_id = COALESCE(?, session_id),
+             cli_session_id = COALESCE(?, cli_session_id),
+             last_activity = ?
+         WHERE id = ?"
     )
-    .bind(&comment_id)
-    .fetch_one(db.pool())
+    .bind(&label)
+    .bind(&session_id)
+    .bind(&cli_session_id)
+    .bind(now)
+    .bind(&tab_id)
+    .execute(pool)
     .await
-    .map_err(|e| format!("Failed to fetch updated comment: {}", e))?;
+    .map_err(AppError::context("Failed to update chat tab"))?;
 
-    log::info!("Review comment unresolved successfully");
-    Ok(comment)
+    // Fetch and return the updated tab
+    fetch_chat_tab(pool, &tab_id)
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Chat tab not found: {}", tab_id)))
 }
 
-/// Delete a review comment
+/// Set the active tab for a project
 #[tauri::command]
-pub async fn delete_review_comment(
+pub async fn set_active_tab(
+    project_id: String,
+    tab_id: String,
     db: State<'_, Database>,
-    comment_id: String,
-) -> Result<(), String> {
-    log::info!("Deleting review comment: {}", comment_id);
+) -> Result<(), AppError> {
+    let pool = db.pool();
 
-    sqlx::query(
-        r#"
-        DELETE FROM review_comments
-        WHERE id = ?
-        "#
-    )
-    .bind(&comment_id)
-    .execute(db.pool())
-    .await
-    .map_err(|e| format!("Failed to delete comment: {}", e))?;
+    // Deactivate all tabs for this project
+    sqlx::query("UPDATE chat_tabs SET is_active = FALSE WHERE project_id = ?")
+        .bind(&project_id)
+        .execute(pool)
+        .await
+        .map_err(AppError::context("Failed to deactivate tabs"))?;
+
+    // Activate the selected tab
+    sqlx::query("UPDATE chat_tabs SET is_active = TRUE, last_activity = ? WHERE id = ?")
+        .bind(chrono::Utc::now().timestamp())
+        .bind(&tab_id)
+        .execute(pool)
+        .await
+        .map_err(AppError::context("Failed to activate tab"))?;
 
-    log::info!("Review comment deleted successfully");
     Ok(())
 }
 
-// ============================================================================
-// Agent Session Commands
-// ============================================================================
-
-/// Start an agent session for a project
+/// Close/delete a chat tab
 #[tauri::command]
-pub async fn start_agent_session(
+pub async fn close_chat_tab(
+    tab_id: String,
     db: State<'_, Database>,
-    agent_manager: State<'_, crate::agent_manager::AgentManager>,
-    project_id: String,
-    agent_type: String,
-    resume_session_id: Option<String>,
-) -> Result<crate::agent_manager::AgentSession, String> {
-    log::info!("Starting {} agent session for project: {}", agent_type, project_id);
-
-    if let Some(ref session_id) = resume_session_id {
-        log::info!("Resuming Claude session: {}", session_id);
-    }
+) -> Result<(), AppError> {
+    let pool = db.pool();
 
-    // Get project to verify it exists and get the root path
-    let project = get_project(db.clone(), project_id.clone())
+    // Get the tab info before deleting (for reordering)
+    let tab = fetch_chat_tab(pool, &tab_id)
         .await?
-        .ok_or_else(|| format!("Project not found: {}", project_id))?;
+        .ok_or_else(|| AppError::not_found(format!("Chat tab not found: {}", tab_id)))?;
 
-    // Start the agent session
-    let session = agent_manager
-        .start_session(project_id.clone(), agent_type.clone(), project.root_path.clone(), resume_session_id.clone())
+    // Delete the tab
+    sqlx::query("DELETE FROM chat_tabs WHERE id = ?")
+        .bind(&tab_id)
+        .execute(pool)
         .await
-        .map_err(|e| format!("Failed to start agent session: {}", e))?;
+        .map_err(AppError::context("Failed to delete chat tab"))?;
 
-    // Save the session to the database
+    // Reorder remaining tabs
     sqlx::query(
-        r#"
-        INSERT INTO agent_sessions (id, project_id, task_id, agent_type, started_at, ended_at, status, exit_code, claude_session_id)
-        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
-        "#
+        "UPDATE chat_tabs SET tab_order = tab_order - 1
+         WHERE project_id = ? AND tab_order > ?"
     )
-    .bind(&session.session_id)
-    .bind(&session.project_id)
-    .bind::<Option<String>>(None) // task_id
-    .bind(&session.agent_type)
-    .bind(session.started_at)
-    .bind::<Option<i64>>(None) // ended_at
-    .bind("running")
-    .bind::<Option<i64>>(None) // exit_code
-    .bind(&session.claude_session_id)
-    .execute(db.pool())
+    .bind(&tab.project_id)
+    .bind(&tab.tab_order)
+    .execute(pool)
     .await
-    .map_err(|e| format!("Failed to save agent session: {}", e))?;
-
-    // Log activity
-    let _ = log_activity(
-        db,
-        project_id,
-        "agent_start".to_string(),
-        format!("Started {} agent session", agent_type),
-        Some(serde_json::json!({
-            "session_id": session.session_id,
-            "agent_type": agent_type
-        }).to_string()),
-    ).await;
-
-    log::info!("Agent session started: {}", session.session_id);
-    Ok(session)
-}
-
-/// Send a message to an agent session
-#[tauri::command]
-pub async fn send_to_agent(
-    agent_manager: State<'_, crate::agent_manager::AgentManager>,
-    plugin_settings_manager: State<'_, crate::plugin_settings::PluginSettingsManager>,
-    session_id: String,
-    message: String,
-    plugin_name: Option<String>,
-) -> Result<(), String> {
-    log::info!("Sending message to agent session {}: {}", session_id, message);
-
-    // Get flag settings for the plugin if plugin_name is provided
-    let flag_settings = plugin_name.as_ref().map(|name| {
-        plugin_settings_manager.get_plugin_settings(name).flags
-    });
+    .map_err(AppError::context("Failed to reorder tabs"))?;
 
-    agent_manager
-        .send_message(&session_id, message, flag_settings)
+    // If the closed tab was active, activate the first remaining tab
+    if tab.is_active {
+        sqlx::query(
+            "UPDATE chat_tabs SET is_active = TRUE
+             WHERE project_id = ? AND tab_order = 0"
+        )
+        .bind(&tab.project_id)
+        .execute(pool)
         .await
-        .map_err(|e| format!("Failed to send message: {}", e))?;
+        .ok(); // Ignore errors if no tabs remain
+    }
 
+    log::info!("Closed chat tab {}", tab_id);
     Ok(())
 }
 
-/// Read output from an agent session
+/// Reorder chat tabs
 #[tauri::command]
-pub async fn read_agent_output(
-    agent_manager: State<'_, crate::agent_manager::AgentManager>,
-    session_id: String,
-    timeout_ms: Option<u64>,
-) -> Result<Vec<String>, String> {
-    log::info!("Reading output from agent session: {}", session_id);
+pub async fn reorder_chat_tabs(
+    project_id: String,
+    tab_ids: Vec<String>,
+    db: State<'_, Database>,
+) -> Result<(), AppError> {
+    let pool = db.pool();
 
-    let output = if let Some(timeout) = timeout_ms {
-        agent_manager
-            .read_output_with_timeout(&session_id, timeout)
-            .await
-    } else {
-        agent_manager
-            .read_output(&session_id)
+    for (index, tab_id) in tab_ids.iter().enumerate() {
+        sqlx::query("UPDATE chat_tabs SET tab_order = ? WHERE id = ? AND project_id = ?")
+            .bind(index as i64)
+            .bind(tab_id)
+            .bind(&project_id)
+            .execute(pool)
             .await
+            .map_err(|e| AppError::from(e).wrap(format!("Failed to reorder tab {}", tab_id)))?;
     }
-    .map_err(|e| format!("Failed to read output: {}", e))?;
-
-    // Strip ANSI escape codes from each line for clean UI display
-    let cleaned_output: Vec<String> = output
-        .into_iter()
-        .map(|line| {
-            let cleaned_bytes = strip_ansi_escapes::strip(&line);
-            String::from_utf8_lossy(&cleaned_bytes).to_string()
-        })
-        .collect();
 
-    Ok(cleaned_output)
+    Ok(())
 }
 
-/// Read parsed events from an agent session
+/// Duplicate a chat tab to branch the conversation
+///
+/// The copy is placed right after the original. If the original has a CLI
+/// session the copy forks it when first resumed, getting a CLI session of its
+/// own; otherwise, if the original has messages, the copy gets a new session
+/// seeded with a summary of the conversation so far.
 #[tauri::command]
-pub async fn read_agent_events(
-    agent_manager: State<'_, crate::agent_manager::AgentManager>,
-    session_id: String,
-) -> Result<Vec<crate::output_parser::AgentEvent>, String> {
-    log::info!("Reading events from agent session: {}", session_id);
+pub async fn duplicate_chat_tab(tab_id: String, db: State<'_, Database>) -> Result<ChatTab, AppError> {
+    log::info!("Duplicating chat tab {}", tab_id);
+    duplicate_tab(db.pool(), &tab_id).await
+}
 
-    let events = agent_manager
-        .read_events(&session_id)
-        .await
-        .map_err(|e| format!("Failed to read events: {}", e))?;
+/// Insert a copy of a tab right after it
+async fn duplicate_tab(pool: &sqlx::SqlitePool, tab_id: &str) -> Result<ChatTab, AppError> {
+    let original = fetch_chat_tab(pool, tab_id)
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Chat tab not found: {}", tab_id)))?;
+
+    let mut tab = ChatTab::new(original.project_id.clone(), original.agent_type.clone(), original.tab_order + 1);
+    tab.label = original.label.as_ref().map(|label| format!("{} (branch)", label));
+    tab.flag_overrides = original.flag_overrides.clone();
+
+    let mut seed = None;
+    let cli_session_id = original.cli_session_id.as_ref().or(original.forked_from_cli_session_id.as_ref());
+    if let Some(cli_session_id) = cli_session_id {
+        tab.forked_from_cli_session_id = Some(cli_session_id.clone());
+    } else if let Some(session_id) = &original.session_id {
+        let messages = fetch_session_messages(pool, session_id, 0, None).await?;
+
+        if !messages.is_empty() {
+            let new_session_id = uuid::Uuid::new_v4().to_string();
+            let summary = summarize_conversation(&messages).await;
+            let mut message = ChatMessage::new_with_session(
+                original.project_id.clone(),
+                Some(new_session_id.clone()),
+                "system".to_string(),
+                format!("Branched from an earlier conversation. Summary so far:\n\n{}", summary),
+            );
+            message.metadata = Some(serde_json::json!({
+                "seeded_from_tab": original.id,
+                "seeded_from_session": session_id
+            }).to_string());
 
-    log::info!("Retrieved {} events from session {}", events.len(), session_id);
-    Ok(events)
-}
+            tab.session_id = Some(new_session_id);
+            seed = Some(message);
+        }
+    }
 
-/// Read both raw output and parsed events from an agent session
-#[tauri::command]
-pub async fn read_agent_output_and_events(
-    agent_manager: State<'_, crate::agent_manager::AgentManager>,
-    session_id: String,
-) -> Result<(Vec<String>, Vec<crate::output_parser::AgentEvent>), String> {
-    log::info!("Reading output and events from agent session: {}", session_id);
+    let mut tx = pool.begin().await
+        .map_err(AppError::context("Failed to start transaction"))?;
 
-    let (output, events) = agent_manager
-        .read_output_and_events(&session_id)
+    // Make room for the copy right after the original
+    sqlx::query("UPDATE chat_tabs SET tab_order = tab_order + 1 WHERE project_id = ? AND tab_order > ?")
+        .bind(&original.project_id)
+        .bind(original.tab_order)
+        .execute(&mut *tx)
         .await
-        .map_err(|e| format!("Failed to read output and events: {}", e))?;
+        .map_err(AppError::context("Failed to reorder tabs"))?;
 
-    // Strip ANSI escape codes from output lines
-    let cleaned_output: Vec<String> = output
-        .into_iter()
-        .map(|line| {
-            let cleaned_bytes = strip_ansi_escapes::strip(&line);
-            String::from_utf8_lossy(&cleaned_bytes).to_string()
-        })
-        .collect();
+    insert_chat_tab(&mut *tx, &tab)
+        .await
+        .map_err(AppError::context("Failed to create chat tab"))?;
 
-    log::info!(
-        "Retrieved {} output lines and {} events from session {}",
-        cleaned_output.len(),
-        events.len(),
-        session_id
-    );
+    if let Some(message) = &seed {
+        insert_message_row(&mut *tx, message)
+            .await
+            .map_err(AppError::context("Failed to seed duplicated tab"))?;
+    }
 
-    Ok((cleaned_output, events))
-}
+    tx.commit().await
+        .map_err(AppError::context("Failed to commit duplicated tab"))?;
 
-/// Stop an agent session
-#[tauri::command]
-pub async fn stop_agent_session(
-    db: State<'_, Database>,
-    agent_manager: State<'_, crate::agent_manager::AgentManager>,
-    session_id: String,
-) -> Result<(), String> {
-    log::info!("Stopping agent session: {}", session_id);
+    log::info!("Duplicated chat tab {} as {}", tab_id, tab.id);
+    Ok(tab)
+}
 
-    // Stop the session
-    agent_manager
-        .stop_session(&session_id)
-        .await
-        .map_err(|e| format!("Failed to stop session: {}", e))?;
+/// The CLI session a duplicated tab should fork on its first run; `None` once
+/// it has a CLI session of its own
+async fn tab_fork_source(pool: &sqlx::SqlitePool, tab_id: &str) -> Result<Option<String>, AppError> {
+    let tab = fetch_chat_tab(pool, tab_id)
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Chat tab not found: {}", tab_id)))?;
+    Ok(tab.forked_from_cli_session_id.filter(|_| tab.cli_session_id.is_none()))
+}
 
-    // Update the session in the database
-    let now = chrono::Utc::now().timestamp();
+/// Give a forking tab the CLI session ID its agent session reported
+async fn record_forked_session(
+    pool: &sqlx::SqlitePool,
+    agent_session_id: &str,
+    cli_session_id: &str,
+) -> Result<(), AppError> {
     sqlx::query(
         r#"
-        UPDATE agent_sessions
-        SET ended_at = ?, status = 'stopped'
-        WHERE id = ?
+        UPDATE chat_tabs
+        SET cli_session_id = ?, forked_from_cli_session_id = NULL
+        WHERE session_id = ? AND cli_session_id IS NULL
+          AND forked_from_cli_session_id IS NOT NULL AND forked_from_cli_session_id != ?
         "#
     )
-    .bind(now)
-    .bind(&session_id)
-    .execute(db.pool())
+    .bind(cli_session_id)
+    .bind(agent_session_id)
+    .bind(cli_session_id)
+    .execute(pool)
     .await
-    .map_err(|e| format!("Failed to update agent session: {}", e))?;
-
-    log::info!("Agent session stopped: {}", session_id);
+    .map_err(AppError::context("Failed to record forked session"))?;
     Ok(())
 }
 
-/// Sync the claude_session_id from runtime session to database
+/// Summarize a conversation for seeding a branch, falling back to the last
+/// few messages when the AI service isn't available
+async fn summarize_conversation(messages: &[ChatMessage]) -> String {
+    use crate::ai_service::{AIService, ChatMessage as AIMessage};
+
+    let transcript: String = messages
+        .iter()
+        .map(|m| format!("{}: {}\n", m.role, m.content))
+        .collect();
+
+    let ai_service = AIService::from_env();
+    if ai_service.is_available() {
+        let prompt = format!(
+            "Summarize this conversation in a few short paragraphs so it can be continued elsewhere. Keep decisions, open questions and file names.\n\n{}",
+            transcript
+        );
+        match ai_service
+            .chat_completion(vec![AIMessage { role: "user".to_string(), content: prompt }])
+            .await
+        {
+            Ok(summary) => return summary,
+            Err(e) => log::warn!("Failed to summarize conversation: {}", e),
+        }
+    }
+
+    let start = messages.len().saturating_sub(4);
+    messages[start..]
+        .iter()
+        .map(|m| format!("{}: {}", m.role, m.content.chars().take(500).collect::<String>()))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Set a tab's CLI flag overrides (flag_id -> value)
+///
+/// Overrides take precedence over the plugin's flag settings when messages are
+/// sent to the tab's agent session. Empty values are dropped; an empty map
+/// clears the overrides.
 #[tauri::command]
-pub async fn sync_claude_session_id(
+pub async fn set_tab_flag_overrides(
+    tab_id: String,
+    overrides: std::collections::HashMap<String, String>,
     db: State<'_, Database>,
-    agent_manager: State<'_, crate::agent_manager::AgentManager>,
-    session_id: String,
-) -> Result<Option<String>, String> {
-    log::info!("Syncing Claude session ID for session: {}", session_id);
+) -> Result<ChatTab, AppError> {
+    log::info!("Setting flag overrides for chat tab {}: {:?}", tab_id, overrides);
 
-    // Get the session from agent manager to see if it has a claude_session_id
-    let session = agent_manager
-        .get_session_status(&session_id)
-        .await
-        .map_err(|e| format!("Failed to get session: {}", e))?;
+    let pool = db.pool();
 
-    // If the session has a claude_session_id, update it in the database
-    if let Some(claude_session_id) = session.claude_session_id {
-        log::info!("Updating database with Claude session ID: {}", claude_session_id);
+    let overrides: std::collections::BTreeMap<String, String> = overrides
+        .into_iter()
+        .filter(|(_, value)| !value.trim().is_empty())
+        .collect();
+    let stored = if overrides.is_empty() {
+        None
+    } else {
+        Some(serde_json::to_string(&overrides).map_err(AppError::context("Failed to serialize flag overrides"))?)
+    };
 
-        sqlx::query(
-            r#"
-            UPDATE agent_sessions
-            SET claude_session_id = ?
-            WHERE id = ?
-            "#
-        )
-        .bind(&claude_session_id)
-        .bind(&session_id)
-        .execute(db.pool())
+    let result = sqlx::query("UPDATE chat_tabs SET flag_overrides = ? WHERE id = ?")
+        .bind(&stored)
+        .bind(&tab_id)
+        .execute(pool)
         .await
-        .map_err(|e| format!("Failed to update claude_session_id: {}", e))?;
+        .map_err(AppError::context("Failed to update chat tab"))?;
 
-        log::info!("Claude session ID synced successfully");
-        Ok(Some(claude_session_id))
-    } else {
-        Ok(None)
+    if result.rows_affected() == 0 {
+        return Err(AppError::not_found(format!("Chat tab not found: {}", tab_id)));
     }
+
+    fetch_chat_tab(pool, &tab_id)
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Chat tab not found: {}", tab_id)))
 }
 
-/// Get the status of an agent session
+/// Get the flags that apply to a tab: plugin settings with the tab's overrides on top
 #[tauri::command]
-pub async fn get_agent_status(
-    agent_manager: State<'_, crate::agent_manager::AgentManager>,
-    session_id: String,
-) -> Result<crate::agent_manager::AgentSession, String> {
-    log::info!("Getting status for agent session: {}", session_id);
+pub async fn get_tab_effective_flags(
+    tab_id: String,
+    plugin_name: String,
+    db: State<'_, Database>,
+    plugin_settings_manager: State<'_, crate::plugin_settings::PluginSettingsManager>,
+) -> Result<std::collections::HashMap<String, String>, AppError> {
+    let overrides: Option<String> = sqlx::query_scalar::<_, Option<String>>(
+        "SELECT flag_overrides FROM chat_tabs WHERE id = ?"
+    )
+    .bind(&tab_id)
+    .fetch_optional(db.pool())
+    .await
+    .map_err(AppError::context("Failed to find chat tab"))?
+    .ok_or_else(|| AppError::not_found(format!("Chat tab not found: {}", tab_id)))?;
 
-    let session = agent_manager
-        .get_session_status(&session_id)
-        .await
-        .map_err(|e| format!("Failed to get session status: {}", e))?;
+    let defaults = plugin_settings_manager.get_plugin_settings(&plugin_name).flags;
+    Ok(apply_flag_overrides(defaults, overrides.as_deref()))
+}
 
-    Ok(session)
+/// Layer a tab's JSON flag overrides over a set of flags
+fn apply_flag_overrides(
+    mut flags: std::collections::HashMap<String, String>,
+    overrides: Option<&str>,
+) -> std::collections::HashMap<String, String> {
+    let overrides = overrides
+        .and_then(|o| serde_json::from_str::<std::collections::HashMap<String, String>>(o).ok())
+        .unwrap_or_default();
+    flags.extend(overrides);
+    flags
 }
 
-/// List all active agent sessions
-#[tauri::command]
-pub async fn list_agent_sessions(
-    agent_manager: State<'_, crate::agent_manager::AgentManager>,
-) -> Result<Vec<crate::agent_manager::AgentSession>, String> {
-    log::info!("Listing all agent sessions");
+/// Plugin name for an agent type, normalized the way the frontend does
+fn plugin_name_for(agent_type: &str) -> String {
+    agent_type.to_lowercase().split_whitespace().collect::<Vec<_>>().join("-")
+}
 
-    let sessions = agent_manager.list_sessions().await;
+/// Send a prompt to the session a chat tab is attached to
+///
+/// A running agent process gets the prompt with the tab's effective flags;
+/// otherwise it goes through the plugin to resume the tab's CLI session.
+/// Returns the id of the session that received it.
+pub(crate) async fn send_to_tab_session(
+    db: &Database,
+    agent_manager: &crate::agent_manager::AgentManager,
+    plugin_manager: &crate::plugin::PluginManager,
+    plugin_settings_manager: &crate::plugin_settings::PluginSettingsManager,
+    tab_id: &str,
+    message: &str,
+) -> Result<String, AppError> {
+    let pool = db.pool();
 
-    Ok(sessions)
-}
+    let tab = fetch_chat_tab(pool, tab_id)
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Chat tab not found: {}", tab_id)))?;
 
-/// Check if an agent session is healthy
-#[tauri::command]
-pub async fn check_agent_health(
-    agent_manager: State<'_, crate::agent_manager::AgentManager>,
-    session_id: String,
-) -> Result<bool, String> {
-    log::info!("Checking health for agent session: {}", session_id);
+    let plugin_name = plugin_name_for(&tab.agent_type);
 
-    let is_healthy = agent_manager
-        .health_check(&session_id)
+    let live_session = match &tab.session_id {
+        Some(session_id) if agent_manager.get_session_status(session_id).await.is_ok() => Some(session_id.clone()),
+        _ => None,
+    };
+
+    let session_id = if let Some(session_id) = live_session {
+        let flags = apply_flag_overrides(
+            plugin_settings_manager.get_plugin_settings(&plugin_name).flags,
+            tab.flag_overrides.as_deref(),
+        );
+        agent_manager
+            .send_message(&session_id, message.to_string(), Some(flags))
+            .await
+            .map_err(AppError::context("Failed to send message"))?;
+        session_id
+    } else if let Some(cli_session_id) = &tab.cli_session_id {
+        let plugin = plugin_manager
+            .get(&plugin_name)
+            .ok_or_else(|| AppError::not_found(format!("Plugin not found: {}", plugin_name)))?;
+        let session_id = tab.session_id.clone().unwrap_or_else(|| tab.id.clone());
+        let handle = crate::plugin::SessionHandle {
+            session_id: session_id.clone(),
+            cli_session_id: Some(cli_session_id.clone()),
+            process_id: None,
+            plugin_name: plugin_name.clone(),
+            started_at: chrono::Utc::now().timestamp(),
+        };
+        plugin
+            .send_message(&handle, message)
+            .await
+            .map_err(AppError::context("Failed to send message"))?;
+        session_id
+    } else {
+        return Err(AppError::not_found(format!("Chat tab {} has no session to send to", tab_id)));
+    };
+
+    sqlx::query("UPDATE chat_tabs SET last_activity = ? WHERE id = ?")
+        .bind(chrono::Utc::now().timestamp())
+        .bind(tab_id)
+        .execute(pool)
         .await
-        .map_err(|e| format!("Failed to check health: {}", e))?;
+        .map_err(AppError::context("Failed to update chat tab"))?;
 
-    Ok(is_healthy)
+    Ok(session_id)
 }
 
-/// Get agent sessions history for a project from database
+/// Send a prompt to a chat tab's session; returns the session id
 #[tauri::command]
-pub async fn get_project_sessions(
+pub async fn send_to_tab(
+    tab_id: String,
+    message: String,
     db: State<'_, Database>,
-    #[allow(non_snake_case)] projectId: String,
-) -> Result<Vec<crate::models::AgentSession>, String> {
-    log::info!("Getting agent sessions for project: {}", projectId);
+    agent_manager: State<'_, crate::agent_manager::AgentManager>,
+    plugin_manager: State<'_, crate::plugin::PluginManager>,
+    plugin_settings_manager: State<'_, crate::plugin_settings::PluginSettingsManager>,
+) -> Result<String, AppError> {
+    log::info!("Sending message to chat tab {}", tab_id);
+    send_to_tab_session(&db, &agent_manager, &plugin_manager, &plugin_settings_manager, &tab_id, &message).await
+}
 
-    let sessions = sqlx::query_as::<_, crate::models::AgentSession>(
-        r#"
-        SELECT id, project_id, task_id, agent_type, started_at, ended_at, status, exit_code, claude_session_id
-        FROM agent_sessions
-        WHERE project_id = ?
-        ORDER BY started_at DESC
-        LIMIT 50
-        "#
-    )
-    .bind(&projectId)
-    .fetch_all(db.pool())
-    .await
-    .map_err(|e| format!("Failed to get project sessions: {}", e))?;
+/// Send lines `start_line` to `end_line` (1-based, inclusive) of a project
+/// file to a chat tab's session, with `instruction` saying what to do with
+/// them, e.g. "Explain this"; returns the session id
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn send_selection_to_agent(
+    tab_id: String,
+    file_path: String,
+    start_line: usize,
+    end_line: usize,
+    instruction: String,
+    db: State<'_, Database>,
+    agent_manager: State<'_, crate::agent_manager::AgentManager>,
+    plugin_manager: State<'_, crate::plugin::PluginManager>,
+    plugin_settings_manager: State<'_, crate::plugin_settings::PluginSettingsManager>,
+) -> Result<String, AppError> {
+    let project_id: String = sqlx::query_scalar("SELECT project_id FROM chat_tabs WHERE id = ?")
+        .bind(&tab_id)
+        .fetch_optional(db.pool())
+        .await
+        .map_err(AppError::context("Failed to find chat tab"))?
+        .ok_or_else(|| AppError::not_found(format!("Chat tab not found: {}", tab_id)))?;
+    let project = get_project(db.clone(), project_id.clone())
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Project not found: {}", project_id)))?;
 
-    Ok(sessions)
+    let file = read_file_with_encoding(db.clone(), project_id, file_path.clone()).await?;
+    // Absolute paths from the file tree are shown relative to the project
+    let shown_path = Path::new(&file_path)
+        .strip_prefix(&project.root_path)
+        .map_or(file_path.clone(), |path| path.to_string_lossy().replace('\\', "/"));
+    let message = format_selection(&shown_path, &file.content, start_line, end_line, &instruction)?;
+
+    log::info!("Sending {} lines {}-{} to chat tab {}", shown_path, start_line, end_line, tab_id);
+    send_to_tab_session(&db, &agent_manager, &plugin_manager, &plugin_settings_manager, &tab_id, &message).await
 }
 
-/// Parse agent output into structured events
+/// A prompt with `instruction` followed by the selected lines of `text`,
+/// fenced and labeled with the file's path and line range
+fn format_selection(path: &str, text: &str, start_line: usize, end_line: usize, instruction: &str) -> Result<String, AppError> {
+    let lines: Vec<&str> = text.lines().collect();
+    if start_line == 0 || start_line > end_line || start_line > lines.len() {
+        return Err(AppError::invalid_input(format!("Invalid line range {}-{} for {} ({} lines)", start_line, end_line, path, lines.len())));
+    }
+    let end_line = end_line.min(lines.len());
+    let selection = lines[start_line - 1..end_line].join("\n");
+    let language = Path::new(path).extension().and_then(|ext| ext.to_str()).unwrap_or("");
+    let range = if start_line == end_line { format!("line {}", start_line) } else { format!("lines {}-{}", start_line, end_line) };
+
+    Ok(format!(
+        "{}\n\n`{}` {}:\n\n{}",
+        instruction.trim(),
+        path,
+        range,
+        crate::chat_export::fenced(language, &selection)
+    ))
+}
+
+/// Number of messages from the start of a conversation used to title its tab
+const TAB_LABEL_CONTEXT_MESSAGES: i64 = 6;
+
+/// Ask the AI service to title a chat tab from its first exchanges
 #[tauri::command]
-pub async fn parse_agent_output(
-    session_id: String,
-    agent_type: String,
-    output: Vec<String>,
-) -> Result<crate::agent_adapter::ParsedOutput, String> {
-    log::info!("Parsing output from {} agent session: {}", agent_type, session_id);
+pub async fn auto_label_tab(tab_id: String, db: State<'_, Database>) -> Result<ChatTab, AppError> {
+    log::info!("Auto-labeling chat tab {}", tab_id);
+    label_tab_from_conversation(&db, &tab_id).await
+}
 
-    use crate::agent_adapter::AgentAdapter;
+/// Generate and store a label for a tab from its session's first messages
+async fn label_tab_from_conversation(db: &Database, tab_id: &str) -> Result<ChatTab, AppError> {
+    use crate::ai_service::{AIService, ChatMessage as AIMessage};
 
-    // Create the appropriate adapter based on agent type
-    let adapter: Box<dyn AgentAdapter> = match agent_type.to_lowercase().as_str() {
-        "claude" | "claude-code" => {
-            Box::new(crate::adapters::claude_adapter::ClaudeCodeAdapter::new(session_id.clone()))
-        }
-        "aider" => {
-            Box::new(crate::agent_adapter::AiderAdapter::new(session_id.clone()))
-        }
-        _ => {
-            return Err(format!("Unsupported agent type: {}", agent_type));
+    let pool = db.pool();
+
+    let tab = fetch_chat_tab(pool, tab_id)
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Chat tab not found: {}", tab_id)))?;
+
+    let session_id = tab
+        .session_id
+        .clone()
+        .ok_or_else(|| AppError::not_found(format!("Chat tab has no session: {}", tab_id)))?;
+
+    let messages = fetch_session_messages(pool, &session_id, 0, Some(TAB_LABEL_CONTEXT_MESSAGES)).await?;
+
+    if messages.is_empty() {
+        return Err(AppError::invalid_input("Conversation is empty; nothing to label yet"));
+    }
+
+    let ai_service = AIService::from_env();
+    if !ai_service.is_available() {
+        return Err(AppError::not_found(format!("{} CLI not found", ai_service.provider_name())));
+    }
+
+    let transcript: String = messages
+        .iter()
+        .map(|m| format!("{}: {}\n", m.role, m.content.chars().take(1000).collect::<String>()))
+        .collect();
+    let prompt = format!(
+        "Give a 3-5 word title for this conversation. Reply with only the title, no quotes or punctuation.\n\n{}",
+        transcript
+    );
+
+    let response = ai_service
+        .chat_completion(vec![AIMessage { role: "user".to_string(), content: prompt }])
+        .await?;
+    let label = clean_tab_label(&response)
+        .ok_or_else(|| AppError::internal("AI returned an empty title"))?;
+
+    sqlx::query("UPDATE chat_tabs SET label = ? WHERE id = ?")
+        .bind(&label)
+        .bind(tab_id)
+        .execute(pool)
+        .await
+        .map_err(AppError::context("Failed to update chat tab"))?;
+
+    log::info!("Labeled chat tab {} as '{}'", tab_id, label);
+    Ok(ChatTab { label: Some(label), ..tab })
+}
+
+/// Title an unlabeled tab once its session has received its first reply
+async fn auto_label_after_first_reply(db: Database, session_id: String) {
+    let tab_id = sqlx::query_scalar::<_, String>(
+        r#"
+        SELECT t.id
+        FROM chat_tabs t
+        WHERE t.session_id = ? AND t.label IS NULL
+          AND (SELECT COUNT(*) FROM chat_messages m
+               WHERE m.session_id = t.session_id AND m.role = 'assistant' AND m.deleted_at IS NULL) = 1
+        "#
+    )
+    .bind(&session_id)
+    .fetch_optional(db.pool())
+    .await;
+
+    match tab_id {
+        Ok(Some(tab_id)) => {
+            if let Err(e) = label_tab_from_conversation(&db, &tab_id).await {
+                log::warn!("Failed to auto-label chat tab {}: {}", tab_id, e);
+            }
         }
-    };
+        Ok(None) => {}
+        Err(e) => log::warn!("Failed to look up tab for session {}: {}", session_id, e),
+    }
+}
 
-    // Parse the output
-    let parsed = adapter.parse_output(&session_id, &output);
+/// Reduce an AI title response to a short single-line tab label
+fn clean_tab_label(response: &str) -> Option<String> {
+    let line = response.lines().map(str::trim).find(|l| !l.is_empty())?;
+    let line = line.trim_start_matches("Title:").trim();
+    let words: Vec<&str> = line
+        .split_whitespace()
+        .map(|w| w.trim_matches(|c: char| c == '"' || c == '\'' || c == '*' || c == '.' || c == '`'))
+        .filter(|w| !w.is_empty())
+        .take(5)
+        .collect();
 
-    Ok(parsed)
+    if words.is_empty() {
+        None
+    } else {
+        Some(words.join(" "))
+    }
 }
 
 // ============================================================================
-// Database Cleanup Commands
+// File Bookmark Commands
 // ============================================================================
 
-/// Cleanup result containing counts of deleted items
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct CleanupResult {
-    pub sessions_deleted: i64,
-    pub messages_deleted: i64,
+/// Convert a file path into a project-relative, forward-slash path
+fn to_project_relative_path(root_path: &str, file_path: &str) -> String {
+    Path::new(file_path)
+        .strip_prefix(root_path)
+        .unwrap_or(Path::new(file_path))
+        .to_string_lossy()
+        .replace('\\', "/")
+        .trim_start_matches('/')
+        .to_string()
 }
 
-/// Delete orphaned agent sessions and their associated messages
-/// An orphaned session is one where claude_session_id IS NULL
+/// Bookmark a file in a project (updates label/auto-attach if already bookmarked)
 #[tauri::command]
-pub async fn cleanup_orphaned_sessions(
+pub async fn add_file_bookmark(
     db: State<'_, Database>,
-) -> Result<CleanupResult, String> {
-    log::info!("Starting cleanup of orphaned agent sessions");
+    project_id: String,
+    file_path: String,
+    label: Option<String>,
+    auto_attach: Option<bool>,
+) -> Result<FileBookmark, AppError> {
+    log::info!("Adding file bookmark for project {}: {}", project_id, file_path);
 
-    // First, get the count of sessions that will be deleted
-    let sessions_to_delete = sqlx::query_scalar::<_, i64>(
-        r#"
-        SELECT COUNT(*)
-        FROM agent_sessions
-        WHERE claude_session_id IS NULL
-        "#
-    )
-    .fetch_one(db.pool())
-    .await
-    .map_err(|e| format!("Failed to count orphaned sessions: {}", e))?;
+    let project = get_project(db.clone(), project_id.clone())
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Project not found: {}", project_id)))?;
+
+    let bookmark = save_file_bookmark(db.pool(), &project, &file_path, label, auto_attach.unwrap_or(false)).await?;
 
-    log::info!("Found {} orphaned sessions to delete", sessions_to_delete);
+    log::info!("File bookmark saved: {}", bookmark.id);
+    Ok(bookmark)
+}
+
+/// Insert or update a bookmark for a file inside the project; returns the stored row
+async fn save_file_bookmark(
+    pool: &sqlx::SqlitePool,
+    project: &Project,
+    file_path: &str,
+    label: Option<String>,
+    auto_attach: bool,
+) -> Result<FileBookmark, AppError> {
+    resolve_in_project(&project.root_path, file_path)?;
+    let mut bookmark = FileBookmark::new(
+        project.id.clone(),
+        to_project_relative_path(&project.root_path, file_path),
+    );
+    bookmark.label = label;
+    bookmark.auto_attach = auto_attach;
 
-    // Get the IDs of sessions that will be deleted (for logging)
-    let orphaned_session_ids: Vec<String> = sqlx::query_scalar(
+    sqlx::query(
         r#"
-        SELECT id
-        FROM agent_sessions
-        WHERE claude_session_id IS NULL
+        INSERT INTO file_bookmarks (id, project_id, file_path, label, auto_attach, created_at)
+        VALUES (?, ?, ?, ?, ?, ?)
+        ON CONFLICT(project_id, file_path) DO UPDATE SET
+            label = excluded.label,
+            auto_attach = excluded.auto_attach
         "#
     )
-    .fetch_all(db.pool())
+    .bind(&bookmark.id)
+    .bind(&bookmark.project_id)
+    .bind(&bookmark.file_path)
+    .bind(&bookmark.label)
+    .bind(bookmark.auto_attach)
+    .bind(bookmark.created_at)
+    .execute(pool)
     .await
-    .map_err(|e| format!("Failed to fetch orphaned session IDs: {}", e))?;
+    .map_err(AppError::context("Failed to add file bookmark"))?;
 
-    log::debug!("Orphaned session IDs: {:?}", orphaned_session_ids);
-
-    // Delete messages associated with orphaned sessions first (foreign key constraint)
-    let messages_deleted = sqlx::query(
+    // Fetch the stored row (the ID differs if the bookmark already existed)
+    sqlx::query_as::<_, FileBookmark>(
         r#"
-        DELETE FROM chat_messages
-        WHERE session_id IN (
-            SELECT id FROM agent_sessions WHERE claude_session_id IS NULL
-        )
+        SELECT id, project_id, file_path, label, auto_attach, created_at
+        FROM file_bookmarks
+        WHERE project_id = ? AND file_path = ?
         "#
     )
-    .execute(db.pool())
+    .bind(&bookmark.project_id)
+    .bind(&bookmark.file_path)
+    .fetch_one(pool)
     .await
-    .map_err(|e| format!("Failed to delete orphaned session messages: {}", e))?
-    .rows_affected();
+    .map_err(AppError::context("Failed to fetch file bookmark"))
+}
+
+/// List all bookmarked files for a project
+#[tauri::command]
+pub async fn list_file_bookmarks(
+    db: State<'_, Database>,
+    project_id: String,
+) -> Result<Vec<FileBookmark>, AppError> {
+    log::info!("Fetching file bookmarks for project: {}", project_id);
+
+    let bookmarks = fetch_file_bookmarks(db.pool(), &project_id, false).await?;
 
-    log::info!("Deleted {} messages from orphaned sessions", messages_deleted);
+    log::info!("Fetched {} file bookmarks for project {}", bookmarks.len(), project_id);
+    Ok(bookmarks)
+}
 
-    // Then delete the orphaned sessions
-    let sessions_deleted = sqlx::query(
+/// A project's bookmarks, oldest first; only the auto-attached ones with `auto_attach_only`
+async fn fetch_file_bookmarks(
+    pool: &sqlx::SqlitePool,
+    project_id: &str,
+    auto_attach_only: bool,
+) -> Result<Vec<FileBookmark>, AppError> {
+    sqlx::query_as::<_, FileBookmark>(
         r#"
-        DELETE FROM agent_sessions
-        WHERE claude_session_id IS NULL
+        SELECT id, project_id, file_path, label, auto_attach, created_at
+        FROM file_bookmarks
+        WHERE project_id = ? AND (? = FALSE OR auto_attach = TRUE)
+        ORDER BY created_at ASC
         "#
     )
-    .execute(db.pool())
+    .bind(project_id)
+    .bind(auto_attach_only)
+    .fetch_all(pool)
     .await
-    .map_err(|e| format!("Failed to delete orphaned sessions: {}", e))?
-    .rows_affected();
-
-    log::info!(
-        "Cleanup complete: deleted {} sessions and {} messages",
-        sessions_deleted,
-        messages_deleted
-    );
-
-    Ok(CleanupResult {
-        sessions_deleted: sessions_deleted as i64,
-        messages_deleted: messages_deleted as i64,
-    })
+    .map_err(AppError::context("Failed to fetch file bookmarks"))
 }
 
-// ============================================================================
-// Chat Tab Commands
-// ============================================================================
-
-/// Get all chat tabs for a project
+/// Remove a file bookmark
 #[tauri::command]
-pub async fn get_chat_tabs(
-    project_id: String,
+pub async fn remove_file_bookmark(
     db: State<'_, Database>,
-) -> Result<Vec<ChatTab>, String> {
-    let pool = db.pool();
+    bookmark_id: String,
+) -> Result<bool, AppError> {
+    log::info!("Removing file bookmark: {}", bookmark_id);
+    delete_file_bookmark(db.pool(), &bookmark_id).await
+}
 
-    sqlx::query_as::<_, ChatTab>(
-        "SELECT id, project_id, agent_type, session_id, cli_session_id, label, tab_order, is_active, created_at, last_activity
-         FROM chat_tabs
-         WHERE project_id = ?
-         ORDER BY tab_order ASC"
-    )
-    .bind(&project_id)
-    .fetch_all(pool)
-    .await
-    .map_err(|e| format!("Failed to get chat tabs: {}", e))
+/// Delete a bookmark; returns false if it didn't exist
+async fn delete_file_bookmark(pool: &sqlx::SqlitePool, bookmark_id: &str) -> Result<bool, AppError> {
+    let result = sqlx::query("DELETE FROM file_bookmarks WHERE id = ?")
+        .bind(bookmark_id)
+        .execute(pool)
+        .await
+        .map_err(AppError::context("Failed to remove file bookmark"))?;
+
+    Ok(result.rows_affected() > 0)
 }
 
-/// Create a new chat tab
+/// Build a prompt context block from the project's auto-attached bookmarks
 #[tauri::command]
-pub async fn create_chat_tab(
-    project_id: String,
-    agent_type: String,
-    label: Option<String>,
+pub async fn get_bookmark_context(
     db: State<'_, Database>,
-) -> Result<ChatTab, String> {
-    let pool = db.pool();
+    project_id: String,
+) -> Result<String, AppError> {
+    let project = get_project(db.clone(), project_id.clone())
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Project not found: {}", project_id)))?;
 
-    // Get the max tab_order for this project
-    let max_order: Option<i64> = sqlx::query_scalar(
-        "SELECT MAX(tab_order) FROM chat_tabs WHERE project_id = ?"
-    )
-    .bind(&project_id)
-    .fetch_one(pool)
-    .await
-    .map_err(|e| format!("Failed to get max tab order: {}", e))?;
+    bookmark_context(db.pool(), &project).await
+}
 
-    let new_order = max_order.unwrap_or(-1) + 1;
-    let mut tab = ChatTab::new(project_id.clone(), agent_type, new_order);
-    tab.label = label;
+/// The contents of the project's auto-attached bookmarks; files that can't be
+/// read or resolve outside the project are skipped
+async fn bookmark_context(pool: &sqlx::SqlitePool, project: &Project) -> Result<String, AppError> {
+    let bookmarks = fetch_file_bookmarks(pool, &project.id, true).await?;
+
+    let mut context = String::new();
+    for bookmark in bookmarks {
+        match resolve_in_project(&project.root_path, &bookmark.file_path).and_then(|path| fs::read(path).map_err(|e| e.to_string())) {
+            Ok(bytes) => {
+                let decoded = crate::file_encoding::decode_bytes(&bytes);
+                context.push_str(&format!("File: {}\n```\n{}\n```\n\n", bookmark.file_path, decoded.content));
+            }
+            Err(e) => log::warn!("Skipping bookmarked file {}: {}", bookmark.file_path, e),
+        }
+    }
 
-    // If this is the first tab, make it active
-    if new_order == 0 {
-        tab.is_active = true;
+    Ok(context)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_agents() {
+        let mut plugin_manager = crate::plugin::PluginManager::new();
+        assert!(installed_agents(&plugin_manager).is_empty());
+
+        plugin_manager.register(Box::new(crate::plugins::DemoPlugin::default()));
+        let agents = installed_agents(&plugin_manager);
+        assert_eq!(agents.len(), 1);
+        assert_eq!(agents[0].name, "demo");
+        assert_eq!(agents[0].display_name.as_deref(), Some("Demo Agent"));
+        assert!(agents[0].installed);
     }
 
-    sqlx::query(
-        "INSERT INTO chat_tabs (id, project_id, agent_type, session_id, cli_session_id, label, tab_order, is_active, created_at, last_activity)
-         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
-    )
-    .bind(&tab.id)
-    .bind(&tab.project_id)
-    .bind(&tab.agent_type)
-    .bind(&tab.session_id)
-    .bind(&tab.cli_session_id)
-    .bind(&tab.label)
-    .bind(&tab.tab_order)
-    .bind(&tab.is_active)
-    .bind(&tab.created_at)
-    .bind(&tab.last_activity)
-    .execute(pool)
-    .await
-    .map_err(|e| format!("Failed to create chat tab: {}", e))?;
+    #[test]
+    fn test_format_selection() {
+        let text = "fn a() {}\nfn b() {\n    a();\n}\n";
+        let prompt = format_selection("src/lib.rs", text, 2, 9, " Explain this ").unwrap();
+        assert_eq!(prompt, "Explain this\n\n`src/lib.rs` lines 2-4:\n\n```rs\nfn b() {\n    a();\n}\n```");
+        assert!(format_selection("src/lib.rs", text, 1, 1, "x").unwrap().contains("`src/lib.rs` line 1:"));
+        assert!(format_selection("src/lib.rs", text, 3, 2, "x").is_err());
+        assert!(format_selection("src/lib.rs", text, 5, 6, "x").is_err());
+    }
 
-    log::info!("Created chat tab {} for project {}", tab.id, project_id);
-    Ok(tab)
-}
+    #[test]
+    fn test_load_folder_children_pages() {
+        let dir = std::env::temp_dir().join(format!("ateliercode-tree-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        for name in ["b.txt", "A.txt", "c.txt"] {
+            std::fs::write(dir.join(name), "").unwrap();
+        }
 
-/// Update a chat tab
-#[tauri::command]
-pub async fn update_chat_tab(
-    tab_id: String,
-    label: Option<String>,
-    session_id: Option<String>,
-    cli_session_id: Option<String>,
-    db: State<'_, Database>,
-) -> Result<ChatTab, String> {
-    let pool = db.pool();
-    let now = chrono::Utc::now().timestamp();
+        let names = |page: &FileNodePage| page.nodes.iter().map(|n| n.name.clone()).collect::<Vec<_>>();
+        let first = load_folder_children(&dir, &dir, 0, 2);
+        assert_eq!((first.total, names(&first)), (4, vec!["src".to_string(), "A.txt".to_string()]));
+        let rest = load_folder_children(&dir, &dir, 2, 10);
+        assert_eq!((rest.offset, names(&rest)), (2, vec!["b.txt".to_string(), "c.txt".to_string()]));
+        assert!(load_folder_children(&dir, &dir, 10, 10).nodes.is_empty());
 
-    // Build update query dynamically based on what's provided
-    sqlx::query(
-        "UPDATE chat_tabs
-         SET label = COALESCE(?, label),
-             session_id = COALESCE(?, session_id),
-             cli_session_id = COALESCE(?, cli_session_id),
-             last_activity = ?
-         WHERE id = ?"
-    )
-    .bind(&label)
-    .bind(&session_id)
-    .bind(&cli_session_id)
-    .bind(now)
-    .bind(&tab_id)
-    .execute(pool)
-    .await
-    .map_err(|e| format!("Failed to update chat tab: {}", e))?;
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 
-    // Fetch and return the updated tab
-    sqlx::query_as::<_, ChatTab>(
-        "SELECT id, project_id, agent_type, session_id, cli_session_id, label, tab_order, is_active, created_at, last_activity
-         FROM chat_tabs WHERE id = ?"
-    )
-    .bind(&tab_id)
-    .fetch_one(pool)
-    .await
-    .map_err(|e| format!("Failed to fetch updated chat tab: {}", e))
-}
+    #[tokio::test]
+    async fn test_git_helpers_outside_a_repository() {
+        let dir = std::env::temp_dir().join(format!("ateliercode-git-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.to_string_lossy().to_string();
 
-/// Set the active tab for a project
-#[tauri::command]
-pub async fn set_active_tab(
-    project_id: String,
-    tab_id: String,
-    db: State<'_, Database>,
-) -> Result<(), String> {
-    let pool = db.pool();
+        assert_eq!(count_git_commits(&path).await.unwrap(), 0);
+        assert!(run_git(&path, &["rev-parse", "HEAD"]).await.is_err());
 
-    // Deactivate all tabs for this project
-    sqlx::query("UPDATE chat_tabs SET is_active = FALSE WHERE project_id = ?")
-        .bind(&project_id)
-        .execute(pool)
-        .await
-        .map_err(|e| format!("Failed to deactivate tabs: {}", e))?;
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 
-    // Activate the selected tab
-    sqlx::query("UPDATE chat_tabs SET is_active = TRUE, last_activity = ? WHERE id = ?")
-        .bind(chrono::Utc::now().timestamp())
-        .bind(&tab_id)
-        .execute(pool)
-        .await
-        .map_err(|e| format!("Failed to activate tab: {}", e))?;
+    #[test]
+    fn test_apply_flag_overrides() {
+        let defaults = std::collections::HashMap::from([
+            ("model".to_string(), "sonnet".to_string()),
+            ("permission_mode".to_string(), "default".to_string()),
+        ]);
+
+        let flags = apply_flag_overrides(defaults.clone(), Some(r#"{"model":"opus"}"#));
+        assert_eq!(flags["model"], "opus");
+        assert_eq!(flags["permission_mode"], "default");
+        assert_eq!(apply_flag_overrides(defaults.clone(), None), defaults);
+    }
 
-    Ok(())
-}
+    #[test]
+    fn test_clean_tab_label() {
+        assert_eq!(clean_tab_label("\"Fix Login Redirect Bug\"\n").as_deref(), Some("Fix Login Redirect Bug"));
+        assert_eq!(clean_tab_label("Title: Refactor the parser module today now").as_deref(), Some("Refactor the parser module today"));
+        assert_eq!(clean_tab_label("  \n\n"), None);
+    }
 
-/// Close/delete a chat tab
-#[tauri::command]
-pub async fn close_chat_tab(
-    tab_id: String,
-    db: State<'_, Database>,
-) -> Result<(), String> {
-    let pool = db.pool();
+    /// A project rooted in a new temp folder; returns its id and root
+    async fn test_project(pool: &sqlx::SqlitePool) -> (String, std::path::PathBuf) {
+        let root = std::env::temp_dir().join(format!("ateliercode-project-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&root).unwrap();
+        let project = Project::new("Test".to_string(), root.to_string_lossy().to_string(), "claude-code".to_string());
+        insert_project(pool, &project).await.unwrap();
+        (project.id, root)
+    }
 
-    // Get the tab info before deleting (for reordering)
-    let tab: ChatTab = sqlx::query_as(
-        "SELECT id, project_id, agent_type, session_id, cli_session_id, label, tab_order, is_active, created_at, last_activity
-         FROM chat_tabs WHERE id = ?"
-    )
-    .bind(&tab_id)
-    .fetch_one(pool)
-    .await
-    .map_err(|e| format!("Failed to find chat tab: {}", e))?;
+    #[tokio::test]
+    async fn test_reorder_keeps_unlisted_tasks_after_listed_ones() {
+        let (pool, _db) = crate::db::test_pool().await;
+        let (project_id, _) = test_project(&pool).await;
+        for (id, status, position) in [("a", "todo", 0), ("b", "todo", 1), ("c", "todo", 2), ("d", "blocked", 0)] {
+            sqlx::query("INSERT INTO tasks (id, project_id, title, priority, status, created_at, position) VALUES (?, ?, ?, 'medium', ?, 1, ?)")
+                .bind(id)
+                .bind(&project_id)
+                .bind(id)
+                .bind(status)
+                .bind(position)
+                .execute(&pool).await.unwrap();
+        }
 
-    // Delete the tab
-    sqlx::query("DELETE FROM chat_tabs WHERE id = ?")
-        .bind(&tab_id)
-        .execute(pool)
-        .await
-        .map_err(|e| format!("Failed to delete chat tab: {}", e))?;
+        // "a" is left out; "d" moves in from another column
+        let ordered = vec!["c".to_string(), "d".to_string(), "b".to_string()];
+        write_task_order(&pool, &project_id, "todo", &ordered).await.unwrap();
 
-    // Reorder remaining tabs
-    sqlx::query(
-        "UPDATE chat_tabs SET tab_order = tab_order - 1
-         WHERE project_id = ? AND tab_order > ?"
-    )
-    .bind(&tab.project_id)
-    .bind(&tab.tab_order)
-    .execute(pool)
-    .await
-    .map_err(|e| format!("Failed to reorder tabs: {}", e))?;
+        let order: Vec<(String, i64)> =
+            sqlx::query_as("SELECT id, position FROM tasks WHERE status = 'todo' ORDER BY position")
+                .fetch_all(&pool).await.unwrap();
+        let order: Vec<(&str, i64)> = order.iter().map(|(id, p)| (id.as_str(), *p)).collect();
+        assert_eq!(order, vec![("c", 0), ("d", 1), ("b", 2), ("a", 3)]);
 
-    // If the closed tab was active, activate the first remaining tab
-    if tab.is_active {
-        sqlx::query(
-            "UPDATE chat_tabs SET is_active = TRUE
-             WHERE project_id = ? AND tab_order = 0"
+        let missing = write_task_order(&pool, &project_id, "todo", &["zzz".to_string()]).await;
+        assert_eq!(missing.unwrap_err().kind, crate::error::ErrorKind::NotFound);
+    }
+
+    #[tokio::test]
+    async fn test_redact_reaches_replaced_replies() {
+        let (pool, _db) = crate::db::test_pool().await;
+        let (project_id, _) = test_project(&pool).await;
+        let session = Some("chat-1".to_string());
+
+        let mut old_reply = ChatMessage::new_with_session(
+            project_id.clone(), session.clone(), "assistant".to_string(), "the key is sk-123".to_string(),
+        );
+        old_reply.metadata = Some(serde_json::json!({ "duration_ms": 5 }).to_string());
+        insert_message_row(&pool, &old_reply).await.unwrap();
+        sqlx::query("UPDATE chat_messages SET deleted_at = 1 WHERE id = ?")
+            .bind(&old_reply.id)
+            .execute(&pool).await.unwrap();
+
+        let mut new_reply = ChatMessage::new_with_session(
+            project_id.clone(), session, "assistant".to_string(), "Done".to_string(),
+        );
+        new_reply.metadata = Some(serde_json::json!({
+            "regenerated": true,
+            "previous_responses": [{ "id": old_reply.id, "content": old_reply.content, "timestamp": 1 }]
+        }).to_string());
+        insert_message_row(&pool, &new_reply).await.unwrap();
+
+        let redacted = redact_stored_message(&pool, &old_reply.id).await.unwrap();
+        assert_eq!(redacted.content, "[redacted]");
+        assert_eq!(redacted.parsed_metadata().extra.get("redacted"), Some(&serde_json::json!(true)));
+
+        let (content, metadata): (String, String) =
+            sqlx::query_as("SELECT content, metadata FROM chat_messages WHERE id = ?")
+                .bind(&new_reply.id)
+                .fetch_one(&pool).await.unwrap();
+        assert_eq!(content, "Done");
+        assert!(!metadata.contains("sk-123"));
+        assert!(metadata.contains("[redacted]"));
+    }
+
+    #[tokio::test]
+    async fn test_recorded_changes_load() {
+        let (pool, _db) = crate::db::test_pool().await;
+        let (project_id, root) = test_project(&pool).await;
+        std::fs::write(root.join("a.txt"), "a\n").unwrap();
+
+        let change_id = crate::file_watcher::record_file_change(
+            &pool,
+            &project_id,
+            "watch-1",
+            &root.to_string_lossy(),
+            &root.join("a.txt"),
+            "created",
         )
-        .bind(&tab.project_id)
-        .execute(pool)
         .await
-        .ok(); // Ignore errors if no tabs remain
+        .unwrap();
+
+        let change = fetch_file_change(&pool, &change_id).await.unwrap();
+        assert_eq!((change.session_id.as_str(), change.file_path.as_str()), ("watch-1", "a.txt"));
+        assert!(change.content_hash.is_some());
+        let pending = fetch_pending_changes(&pool, &project_id).await.unwrap();
+        assert_eq!(pending.iter().map(|c| c.id.as_str()).collect::<Vec<_>>(), vec![change_id.as_str()]);
+        assert!(fetch_file_change(&pool, "missing").await.is_err());
     }
 
-    log::info!("Closed chat tab {}", tab_id);
-    Ok(())
-}
+    #[tokio::test]
+    async fn test_stale_changes() {
+        let (pool, _db) = crate::db::test_pool().await;
+        let (project_id, root) = test_project(&pool).await;
+        let root_path = root.to_string_lossy().to_string();
+        std::fs::write(root.join("a.txt"), "a\n").unwrap();
+        std::fs::write(root.join("b.txt"), "b\n").unwrap();
+        let mut ids = Vec::new();
+        for name in ["a.txt", "b.txt"] {
+            let path = root.join(name);
+            let id = crate::file_watcher::record_file_change(&pool, &project_id, "w", &root_path, &path, "created").await;
+            ids.push(id.unwrap());
+        }
+        sqlx::query("UPDATE file_changes SET reviewed = TRUE, approved = TRUE").execute(&pool).await.unwrap();
+        assert!(find_stale_changes(&pool, &project_id, &root_path).await.unwrap().is_empty());
+
+        // Edited after review, without the watcher seeing it
+        std::fs::write(root.join("a.txt"), "edited\n").unwrap();
+        let stale = find_stale_changes(&pool, &project_id, &root_path).await.unwrap();
+        assert_eq!(stale.iter().map(|c| c.id.as_str()).collect::<Vec<_>>(), vec![ids[0].as_str()]);
+        assert!(stale[0].stale);
+    }
 
-/// Reorder chat tabs
-#[tauri::command]
-pub async fn reorder_chat_tabs(
-    project_id: String,
-    tab_ids: Vec<String>,
-    db: State<'_, Database>,
-) -> Result<(), String> {
-    let pool = db.pool();
+    #[tokio::test]
+    async fn test_suggestions_need_the_reviewed_content() {
+        let (pool, _db) = crate::db::test_pool().await;
+        let (project_id, root) = test_project(&pool).await;
+        let path = root.join("a.txt");
+        std::fs::write(&path, "one\ntwo\n").unwrap();
+        let change_id =
+            crate::file_watcher::record_file_change(&pool, &project_id, "w", &root.to_string_lossy(), &path, "created")
+                .await
+                .unwrap();
+        let change = fetch_file_change(&pool, &change_id).await.unwrap();
+        assert!(ensure_unchanged_since(&change, &path).is_ok());
+
+        std::fs::write(&path, "zero\none\ntwo\n").unwrap();
+        assert!(ensure_unchanged_since(&change, &path).is_err());
+        let stale = FileChange { stale: true, ..change.clone() };
+        std::fs::write(&path, "one\ntwo\n").unwrap();
+        assert!(ensure_unchanged_since(&stale, &path).is_err());
+    }
 
-    for (index, tab_id) in tab_ids.iter().enumerate() {
-        sqlx::query("UPDATE chat_tabs SET tab_order = ? WHERE id = ? AND project_id = ?")
-            .bind(index as i64)
-            .bind(tab_id)
-            .bind(&project_id)
-            .execute(pool)
+    #[tokio::test]
+    async fn test_fix_request_collects_unresolved_comments() {
+        let (pool, _db) = crate::db::test_pool().await;
+        let (project_id, root) = test_project(&pool).await;
+        let path = root.join("a.txt");
+        std::fs::write(&path, "a\n").unwrap();
+        let change_id =
+            crate::file_watcher::record_file_change(&pool, &project_id, "w", &root.to_string_lossy(), &path, "created")
+                .await
+                .unwrap();
+        assert!(fix_request(&pool, &change_id).await.is_err());
+
+        for (id, line, resolved) in [("c1", Some(3), false), ("c2", None, false), ("c3", Some(1), true)] {
+            sqlx::query(
+                "INSERT INTO review_comments (id, file_change_id, line_number, author, comment, timestamp, resolved)
+                 VALUES (?, ?, ?, 'user', 'Fix this', 0, ?)",
+            )
+            .bind(id)
+            .bind(&change_id)
+            .bind(line)
+            .bind(resolved)
+            .execute(&pool)
             .await
-            .map_err(|e| format!("Failed to reorder tab {}: {}", tab_id, e))?;
+            .unwrap();
+        }
+        let (change, comments) = fix_request(&pool, &change_id).await.unwrap();
+        assert_eq!(change.file_path, "a.txt");
+        assert_eq!(comments.iter().map(|c| c.id.as_str()).collect::<Vec<_>>(), vec!["c1", "c2"]);
+        assert!(fix_request(&pool, "missing").await.is_err());
     }
 
-    Ok(())
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
     #[tokio::test]
-    async fn test_detect_agents() {
-        let result = detect_agents().await;
-        assert!(result.is_ok());
+    async fn test_duplicated_tab_forks_its_cli_session() {
+        let (pool, _db) = crate::db::test_pool().await;
+        let (project_id, _) = test_project(&pool).await;
+        let mut original = ChatTab::new(project_id, "claude-code".to_string(), 0);
+        original.cli_session_id = Some("cli-a".to_string());
+        insert_chat_tab(&pool, &original).await.unwrap();
+
+        let copy = duplicate_tab(&pool, &original.id).await.unwrap();
+        assert_eq!(copy.cli_session_id, None);
+        assert_eq!(tab_fork_source(&pool, &copy.id).await.unwrap().as_deref(), Some("cli-a"));
+        assert_eq!(tab_fork_source(&pool, &original.id).await.unwrap(), None);
+
+        // The copy's first run reports the fork's own session ID
+        sqlx::query("UPDATE chat_tabs SET session_id = 'agent-1' WHERE id = ?")
+            .bind(&copy.id)
+            .execute(&pool).await.unwrap();
+        record_forked_session(&pool, "agent-1", "cli-a").await.unwrap();
+        assert_eq!(tab_fork_source(&pool, &copy.id).await.unwrap().as_deref(), Some("cli-a"));
+        record_forked_session(&pool, "agent-1", "cli-b").await.unwrap();
+
+        let original = fetch_chat_tab(&pool, &original.id).await.unwrap().unwrap();
+        let copy = fetch_chat_tab(&pool, &copy.id).await.unwrap().unwrap();
+        assert_eq!(original.cli_session_id.as_deref(), Some("cli-a"));
+        assert_eq!(copy.cli_session_id.as_deref(), Some("cli-b"));
+        assert_eq!(copy.forked_from_cli_session_id, None);
+        assert_eq!(tab_fork_source(&pool, &copy.id).await.unwrap(), None);
+    }
 
-        let agents = result.unwrap();
-        assert_eq!(agents.len(), 3);
+    #[tokio::test]
+    async fn test_file_bookmarks() {
+        let (pool, _db) = crate::db::test_pool().await;
+        let root = std::env::temp_dir().join(format!("ateliercode-project-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("src/a.rs"), "fn a() {}\n").unwrap();
+        std::fs::write(root.join("b.md"), "# B\n").unwrap();
+        let project = Project::new("Test".to_string(), root.to_string_lossy().to_string(), "claude-code".to_string());
+        insert_project(&pool, &project).await.unwrap();
+
+        let absolute = root.join("src/a.rs").to_string_lossy().to_string();
+        let a = save_file_bookmark(&pool, &project, &absolute, None, true).await.unwrap();
+        assert_eq!(a.file_path, "src/a.rs");
+        let b = save_file_bookmark(&pool, &project, "b.md", Some("Notes".to_string()), false).await.unwrap();
+        assert!(save_file_bookmark(&pool, &project, "../outside.txt", None, true).await.is_err());
+
+        // Bookmarking again updates the existing row
+        let again = save_file_bookmark(&pool, &project, "src/a.rs", Some("A".to_string()), true).await.unwrap();
+        assert_eq!((again.id.as_str(), again.label.as_deref()), (a.id.as_str(), Some("A")));
+        let mut listed: Vec<String> =
+            fetch_file_bookmarks(&pool, &project.id, false).await.unwrap().into_iter().map(|b| b.file_path).collect();
+        listed.sort();
+        assert_eq!(listed, vec!["b.md", "src/a.rs"]);
+
+        // Only auto-attached files inside the project make it into the context
+        sqlx::query("INSERT INTO file_bookmarks (id, project_id, file_path, auto_attach, created_at) VALUES ('x', ?, '../outside.txt', TRUE, 0)")
+            .bind(&project.id)
+            .execute(&pool).await.unwrap();
+        let context = bookmark_context(&pool, &project).await.unwrap();
+        assert_eq!(context, "File: src/a.rs\n```\nfn a() {}\n\n```\n\n");
+
+        assert!(delete_file_bookmark(&pool, &b.id).await.unwrap());
+        assert!(!delete_file_bookmark(&pool, &b.id).await.unwrap());
+        assert_eq!(fetch_file_bookmarks(&pool, &project.id, false).await.unwrap().len(), 2);
+
+        std::fs::remove_dir_all(&root).unwrap();
     }
 }
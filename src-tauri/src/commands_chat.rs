@@ -2,10 +2,15 @@
 // Handles chat sessions, messages, and history
 
 use crate::db::Database;
+use crate::error::AppError;
+use crate::notifications::{self, AgentNotification};
 use crate::plugin::{PluginManager, SessionUpdate, WatchHandle};
+use crate::session_events::SessionEventHub;
+use crate::tool_calls;
+use crate::windows::WindowRegistry;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use tauri::{AppHandle, Emitter, State};
+use tauri::{AppHandle, Manager, State};
 
 /// A message in the conversation history
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,7 +28,7 @@ pub async fn get_chat_history(
     plugin_manager: State<'_, PluginManager>,
     plugin_name: String,
     cli_session_id: String,
-) -> Result<Vec<ChatMessage>, String> {
+) -> Result<Vec<ChatMessage>, AppError> {
     log::info!(
         "Getting chat history for plugin: {}, session: {}",
         plugin_name,
@@ -46,7 +51,7 @@ pub async fn get_chat_history(
     let history = plugin
         .get_conversation_history(&cli_session_id)
         .await
-        .map_err(|e| format!("Failed to get conversation history: {}", e))?;
+        .map_err(AppError::context("Failed to get conversation history"))?;
 
     // Convert HistoryMessage to ChatMessage
     let messages: Vec<ChatMessage> = history
@@ -82,7 +87,7 @@ pub async fn get_chat_history_paginated(
     cli_session_id: String,
     offset: usize,
     limit: usize,
-) -> Result<PaginatedChatHistory, String> {
+) -> Result<PaginatedChatHistory, AppError> {
     log::info!(
         "Getting paginated chat history for plugin: {}, session: {}, offset: {}, limit: {}",
         plugin_name,
@@ -112,7 +117,7 @@ pub async fn get_chat_history_paginated(
     let history = plugin
         .get_conversation_history_paginated(&cli_session_id, offset, limit)
         .await
-        .map_err(|e| format!("Failed to get paginated conversation history: {}", e))?;
+        .map_err(AppError::context("Failed to get paginated conversation history"))?;
 
     // Convert HistoryMessage to ChatMessage
     let messages: Vec<ChatMessage> = history
@@ -148,7 +153,7 @@ pub async fn list_cli_sessions(
     plugin_manager: State<'_, PluginManager>,
     plugin_name: String,
     project_path: String,
-) -> Result<Vec<SessionListItem>, String> {
+) -> Result<Vec<SessionListItem>, AppError> {
     log::info!(
         "Listing CLI sessions for plugin: {} in project: {}",
         plugin_name,
@@ -179,7 +184,7 @@ pub async fn list_cli_sessions(
         .await
         .map_err(|e| {
             eprintln!("[list_cli_sessions] Error calling list_sessions: {}", e);
-            format!("Failed to list sessions: {}", e)
+            AppError::from(e).wrap("Failed to list sessions")
         })?;
     eprintln!("[list_cli_sessions] plugin.list_sessions returned {} sessions", sessions.len());
 
@@ -207,6 +212,121 @@ pub struct SessionListItem {
     pub message_count: usize,
 }
 
+/// Most characters of a session's last message used as its tab label
+const IMPORTED_LABEL_CHARS: usize = 40;
+
+/// CLI sessions one agent already has for a project
+#[derive(Debug, Clone, Serialize)]
+pub struct DetectedCliSessions {
+    pub plugin_name: String,
+    pub display_name: String,
+    /// Most recent first
+    pub sessions: Vec<SessionListItem>,
+}
+
+/// Find the sessions every installed agent CLI has stored for a project path,
+/// e.g. to offer importing them when the project is added
+///
+/// Agents whose session store can't be read are left out.
+#[tauri::command]
+pub async fn find_cli_sessions(
+    plugin_manager: State<'_, PluginManager>,
+    project_path: String,
+) -> Result<Vec<DetectedCliSessions>, AppError> {
+    let mut found = Vec::new();
+    for info in plugin_manager.list_plugins() {
+        let Some(plugin) = plugin_manager.get(&info.name) else { continue };
+        let mut sessions = match plugin.list_sessions(&project_path).await {
+            Ok(sessions) => sessions,
+            Err(e) => {
+                log::debug!("Skipping {} sessions for {}: {}", info.name, project_path, e);
+                continue;
+            }
+        };
+        if sessions.is_empty() {
+            continue;
+        }
+        sessions.sort_by_key(|session| std::cmp::Reverse(session.last_activity));
+        found.push(DetectedCliSessions {
+            plugin_name: info.name,
+            display_name: info.display_name,
+            sessions: sessions
+                .into_iter()
+                .map(|session| SessionListItem {
+                    session_id: session.cli_session_id,
+                    created_at: session.started_at,
+                    last_message_preview: session.metadata.get("last_message").cloned(),
+                    message_count: session.message_count,
+                })
+                .collect(),
+        });
+    }
+    found.sort_by(|a, b| a.display_name.cmp(&b.display_name));
+    Ok(found)
+}
+
+/// Open one chat tab per CLI session, labelled with its last message
+///
+/// Sessions the project already has a tab for are skipped; returns the new tabs.
+#[tauri::command]
+pub async fn import_cli_sessions(
+    app: tauri::AppHandle,
+    db: State<'_, Database>,
+    plugin_manager: State<'_, PluginManager>,
+    project_id: String,
+    plugin_name: String,
+    project_path: String,
+    cli_session_ids: Vec<String>,
+) -> Result<Vec<crate::models::ChatTab>, AppError> {
+    let plugin = plugin_manager
+        .get(&plugin_name)
+        .ok_or_else(|| AppError::not_found(format!("Plugin not found: {}", plugin_name)))?;
+    let sessions = plugin
+        .list_sessions(&project_path)
+        .await
+        .map_err(|e| AppError::from(e).wrap("Failed to list sessions"))?;
+
+    let existing: Vec<String> =
+        sqlx::query_scalar("SELECT cli_session_id FROM chat_tabs WHERE project_id = ? AND cli_session_id IS NOT NULL")
+            .bind(&project_id)
+            .fetch_all(db.pool())
+            .await
+            .map_err(AppError::context("Failed to load chat tabs"))?;
+
+    let mut tabs = Vec::new();
+    for cli_session_id in cli_session_ids {
+        if existing.contains(&cli_session_id) {
+            continue;
+        }
+        let Some(session) = sessions.iter().find(|s| s.cli_session_id == cli_session_id) else {
+            log::warn!("Not importing unknown {} session {}", plugin_name, cli_session_id);
+            continue;
+        };
+        let label = session
+            .metadata
+            .get("last_message")
+            .map(|preview| preview.split_whitespace().collect::<Vec<_>>().join(" "))
+            .filter(|preview| !preview.is_empty())
+            .map(|preview| match preview.char_indices().nth(IMPORTED_LABEL_CHARS) {
+                Some((end, _)) => format!("{}…", preview[..end].trim_end()),
+                None => preview,
+            });
+
+        let mut tab = crate::commands::create_chat_tab(app.clone(), project_id.clone(), Some(plugin_name.clone()), label, db.clone()).await?;
+        sqlx::query("UPDATE chat_tabs SET cli_session_id = ? WHERE id = ?")
+            .bind(&cli_session_id)
+            .bind(&tab.id)
+            .execute(db.pool())
+            .await
+            .map_err(AppError::context("Failed to link chat tab to session"))?;
+        tab.cli_session_id = Some(cli_session_id);
+        tabs.push(tab);
+    }
+
+    log::info!("Imported {} {} sessions into project {}", tabs.len(), plugin_name, project_id);
+    Ok(tabs)
+}
+
 /// Start a new chat session using a plugin
 #[tauri::command]
 pub async fn start_chat_session(
@@ -214,7 +334,7 @@ pub async fn start_chat_session(
     plugin_manager: State<'_, PluginManager>,
     project_id: String,
     plugin_name: String,
-) -> Result<ChatSessionInfo, String> {
+) -> Result<ChatSessionInfo, AppError> {
     log::info!(
         "Starting chat session for project: {} with plugin: {}",
         project_id,
@@ -233,19 +353,19 @@ pub async fn start_chat_session(
     .bind(&project_id)
     .fetch_one(&db.pool)
     .await
-    .map_err(|e| format!("Failed to get project: {}", e))?;
+    .map_err(AppError::context("Failed to get project"))?;
 
     // Get the plugin
     let plugin = plugin_manager
         .get(&plugin_name)
-        .ok_or_else(|| format!("Plugin not found: {}", plugin_name))?;
+        .ok_or_else(|| AppError::not_found(format!("Plugin not found: {}", plugin_name)))?;
 
     // Start the session through the plugin with project path and empty settings
     let settings = std::collections::HashMap::new();
     let handle = plugin
         .start_session(&project.root_path, &settings)
         .await
-        .map_err(|e| format!("Failed to start session: {}", e))?;
+        .map_err(AppError::context("Failed to start session"))?;
 
     log::info!(
         "Started chat session: {} with CLI session: {:?}",
@@ -280,7 +400,7 @@ pub async fn send_chat_message(
     cli_session_id: String,
     _project_path: String,
     message: String,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     log::info!(
         "Sending message in session: {} (CLI: {})",
         session_id,
@@ -290,7 +410,7 @@ pub async fn send_chat_message(
     // Get the plugin
     let plugin = plugin_manager
         .get(&plugin_name)
-        .ok_or_else(|| format!("Plugin not found: {}", plugin_name))?;
+        .ok_or_else(|| AppError::not_found(format!("Plugin not found: {}", plugin_name)))?;
 
     // Create session handle with correct structure
     let handle = crate::plugin::SessionHandle {
@@ -305,7 +425,7 @@ pub async fn send_chat_message(
     plugin
         .send_message(&handle, &message)
         .await
-        .map_err(|e| format!("Failed to send message: {}", e))?;
+        .map_err(AppError::context("Failed to send message"))?;
 
     log::info!("Message sent successfully");
 
@@ -314,13 +434,17 @@ pub async fn send_chat_message(
 
 /// Start watching a session for real-time updates
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn start_watching_session(
     app: AppHandle,
+    window: tauri::Window,
     plugin_manager: State<'_, PluginManager>,
+    window_registry: State<'_, WindowRegistry>,
     plugin_name: String,
     project_path: String,
     cli_session_id: String,
-) -> Result<String, String> {
+    tab_id: String,
+) -> Result<String, AppError> {
     println!(
         "[TAURI] Starting to watch session: {} for plugin: {} in project: {}",
         cli_session_id,
@@ -328,6 +452,13 @@ pub async fn start_watching_session(
         project_path
     );
 
+    // A reloaded webview asks again for the watch it already had; keep that one
+    // and let resume_session_updates replay what it missed
+    if let Some(watch_id) = window_registry.find_session_watch(window.label(), &tab_id, &cli_session_id) {
+        log::info!("Reusing watch {} for session {}", watch_id, cli_session_id);
+        return Ok(watch_id);
+    }
+
     // Get the plugin - if not found, return empty watch_id (agent doesn't support session watching)
     let plugin = match plugin_manager.get(&plugin_name) {
         Some(p) => p,
@@ -342,30 +473,78 @@ pub async fn start_watching_session(
 
     // Clone for use in the closure
     let cli_session_id_for_callback = cli_session_id.clone();
+    let project_path_for_callback = project_path.clone();
 
-    // Create a callback that emits Tauri events
+    // Create a callback that forwards updates to the watching tab
+    let label = window.label().to_string();
+    let tab_id_for_callback = tab_id.clone();
     let callback = Box::new(move |update: SessionUpdate| {
         println!("[TAURI] Session update received: {:?}", update);
 
-        // Emit the event to the frontend
-        if let Err(e) = app.emit("session-update", serde_json::json!({
-            "cli_session_id": &cli_session_id_for_callback,
-            "update": update
-        })) {
-            println!("[TAURI] Failed to emit session-update event: {}", e);
-        } else {
-            println!("[TAURI] Successfully emitted session-update event");
+        let notification = match &update {
+            SessionUpdate::UserPromptRequired { prompt } => Some((
+                AgentNotification::InputRequested,
+                prompt.questions.first().map(|q| q.question.clone()).unwrap_or_default(),
+            )),
+            SessionUpdate::Error { message } => Some((AgentNotification::Error, message.clone())),
+            _ => None,
+        };
+        if let Some((kind, body)) = notification {
+            let app = app.clone();
+            let project_path = project_path_for_callback.clone();
+            tauri::async_runtime::spawn(async move {
+                let db = app.state::<Database>().inner().clone();
+                let project_id = sqlx::query_scalar::<_, String>("SELECT id FROM projects WHERE root_path = ?")
+                    .bind(&project_path)
+                    .fetch_optional(db.pool())
+                    .await;
+                if let Ok(Some(project_id)) = project_id {
+                    notifications::notify(&app, db.pool(), &project_id, kind, &body).await;
+                }
+            });
         }
+
+        if let SessionUpdate::NewMessage { message } = &update {
+            let app = app.clone();
+            let project_path = project_path_for_callback.clone();
+            let cli_session_id = cli_session_id_for_callback.clone();
+            let message = message.clone();
+            tauri::async_runtime::spawn(async move {
+                let db = app.state::<Database>().inner().clone();
+                let project_id = sqlx::query_scalar::<_, String>("SELECT id FROM projects WHERE root_path = ?")
+                    .bind(&project_path)
+                    .fetch_optional(db.pool())
+                    .await;
+                let Ok(Some(project_id)) = project_id else {
+                    return;
+                };
+                let redactor = crate::commands::project_redactor(&db, &project_id).await;
+                if let Err(e) =
+                    tool_calls::record_message(db.pool(), &redactor, &project_id, &cli_session_id, &message).await
+                {
+                    log::warn!("Failed to record tool calls for session {}: {}", cli_session_id, e);
+                }
+            });
+        }
+
+        app.state::<SessionEventHub>().publish(
+            &app,
+            &label,
+            &tab_id_for_callback,
+            &cli_session_id_for_callback,
+            update,
+        );
     });
 
     // Start watching the session
     let watch_handle = plugin
         .start_watching_session(&project_path, &cli_session_id, callback)
         .await
-        .map_err(|e| format!("Failed to start watching session: {}", e))?;
+        .map_err(AppError::context("Failed to start watching session"))?;
 
     let watch_id = watch_handle.id.clone();
     println!("[TAURI] Started watching session with watch_id: {}", watch_id);
+    window_registry.track_session_watch(window.label(), &tab_id, watch_handle);
 
     Ok(watch_id)
 }
@@ -373,11 +552,14 @@ pub async fn start_watching_session(
 /// Stop watching a session
 #[tauri::command]
 pub async fn stop_watching_session(
+    window: tauri::Window,
     plugin_manager: State<'_, PluginManager>,
+    window_registry: State<'_, WindowRegistry>,
+    event_hub: State<'_, SessionEventHub>,
     plugin_name: String,
     watch_id: String,
     cli_session_id: String,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     log::info!(
         "Stopping watch for session: {} (watch_id: {})",
         cli_session_id,
@@ -402,6 +584,10 @@ pub async fn stop_watching_session(
         }
     };
 
+    if let Some(tab_id) = window_registry.untrack_session_watch(window.label(), &watch_id) {
+        event_hub.forget_tab(&tab_id);
+    }
+
     // Create the watch handle
     let watch_handle = WatchHandle {
         id: watch_id.clone(),
@@ -413,7 +599,7 @@ pub async fn stop_watching_session(
     plugin
         .stop_watching_session(watch_handle)
         .await
-        .map_err(|e| format!("Failed to stop watching session: {}", e))?;
+        .map_err(AppError::context("Failed to stop watching session"))?;
 
     log::info!("Successfully stopped watching session");
 
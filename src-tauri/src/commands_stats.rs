@@ -0,0 +1,431 @@
+// Project statistics commands
+// Time series aggregations behind the project dashboard, and per-session summaries
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use tauri::{AppHandle, Emitter, State};
+
+use crate::cost::{self, BudgetAlert, CostSummary, ProjectUsage};
+use crate::db::Database;
+use crate::models::{Project, ProjectSettings};
+use crate::output_parser::AgentEvent;
+
+/// One bucket of a time series
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeseriesPoint {
+    /// Unix timestamp of the start of the bucket (UTC)
+    pub bucket_start: i64,
+    pub value: f64,
+}
+
+/// Bucket size and offset from the epoch, in seconds, for "hour", "day" or "week"
+///
+/// Weeks start on Monday; 1970-01-01 was a Thursday, four days before one.
+pub(crate) fn bucket_bounds(bucket: &str) -> Result<(i64, i64), String> {
+    match bucket {
+        "hour" => Ok((60 * 60, 0)),
+        "day" => Ok((24 * 60 * 60, 0)),
+        "week" => Ok((7 * 24 * 60 * 60, 4 * 24 * 60 * 60)),
+        other => Err(format!("Unknown bucket '{}'; use hour, day or week", other)),
+    }
+}
+
+/// Get a project metric over time
+///
+/// Metrics:
+/// - `messages`: chat messages sent or received
+/// - `file_changes`: file changes recorded
+/// - `tasks_completed`: tasks completed
+/// - `agent_time`: hours of agent sessions, by the bucket they started in
+/// - `tokens`: input and output tokens of chat replies
+/// - `cost`: estimated USD spent on chat replies
+/// - `response_time`: average seconds a chat reply took
+///
+/// Buckets with no data are omitted. `since`/`until` bound the timestamps.
+#[tauri::command]
+pub async fn get_project_timeseries(
+    db: State<'_, Database>,
+    project_id: String,
+    metric: String,
+    bucket: String,
+    since: Option<i64>,
+    until: Option<i64>,
+) -> Result<Vec<TimeseriesPoint>, String> {
+    log::info!("Fetching {} per {} for project {}", metric, bucket, project_id);
+
+    let (size, offset) = bucket_bounds(&bucket)?;
+
+    // Each query binds ?1 project, ?2 offset, ?3 size, ?4 since, ?5 until
+    let query = match metric.as_str() {
+        "messages" => {
+            r#"
+            SELECT ((timestamp - ?2) / ?3) * ?3 + ?2 AS bucket_start, CAST(COUNT(*) AS REAL)
+            FROM chat_messages
+            WHERE project_id = ?1 AND deleted_at IS NULL
+              AND (?4 IS NULL OR timestamp >= ?4)
+              AND (?5 IS NULL OR timestamp <= ?5)
+            GROUP BY bucket_start
+            ORDER BY bucket_start ASC
+            "#
+        }
+        "file_changes" => {
+            r#"
+            SELECT ((timestamp - ?2) / ?3) * ?3 + ?2 AS bucket_start, CAST(COUNT(*) AS REAL)
+            FROM file_changes
+            WHERE project_id = ?1
+              AND (?4 IS NULL OR timestamp >= ?4)
+              AND (?5 IS NULL OR timestamp <= ?5)
+            GROUP BY bucket_start
+            ORDER BY bucket_start ASC
+            "#
+        }
+        "tasks_completed" => {
+            r#"
+            SELECT ((completed_at - ?2) / ?3) * ?3 + ?2 AS bucket_start, CAST(COUNT(*) AS REAL)
+            FROM tasks
+            WHERE project_id = ?1 AND status = 'completed' AND completed_at IS NOT NULL
+              AND (?4 IS NULL OR completed_at >= ?4)
+              AND (?5 IS NULL OR completed_at <= ?5)
+            GROUP BY bucket_start
+            ORDER BY bucket_start ASC
+            "#
+        }
+        "agent_time" => {
+            r#"
+            SELECT ((started_at - ?2) / ?3) * ?3 + ?2 AS bucket_start,
+                   SUM(COALESCE(ended_at, CAST(strftime('%s', 'now') AS INTEGER)) - started_at) / 3600.0
+            FROM agent_sessions
+            WHERE project_id = ?1 AND agent_type != 'file_watcher'
+              AND (?4 IS NULL OR started_at >= ?4)
+              AND (?5 IS NULL OR started_at <= ?5)
+            GROUP BY bucket_start
+            ORDER BY bucket_start ASC
+            "#
+        }
+        "tokens" => {
+            r#"
+            SELECT ((timestamp - ?2) / ?3) * ?3 + ?2 AS bucket_start,
+                   CAST(SUM(COALESCE(input_tokens, 0) + COALESCE(output_tokens, 0)) AS REAL)
+            FROM chat_messages
+            WHERE project_id = ?1 AND deleted_at IS NULL
+              AND (input_tokens IS NOT NULL OR output_tokens IS NOT NULL)
+              AND (?4 IS NULL OR timestamp >= ?4)
+              AND (?5 IS NULL OR timestamp <= ?5)
+            GROUP BY bucket_start
+            ORDER BY bucket_start ASC
+            "#
+        }
+        "cost" => {
+            r#"
+            SELECT ((timestamp - ?2) / ?3) * ?3 + ?2 AS bucket_start, SUM(cost_usd)
+            FROM chat_messages
+            WHERE project_id = ?1 AND deleted_at IS NULL AND cost_usd IS NOT NULL
+              AND (?4 IS NULL OR timestamp >= ?4)
+              AND (?5 IS NULL OR timestamp <= ?5)
+            GROUP BY bucket_start
+            ORDER BY bucket_start ASC
+            "#
+        }
+        "response_time" => {
+            r#"
+            SELECT ((timestamp - ?2) / ?3) * ?3 + ?2 AS bucket_start, AVG(duration_ms) / 1000.0
+            FROM chat_messages
+            WHERE project_id = ?1 AND deleted_at IS NULL AND duration_ms IS NOT NULL
+              AND (?4 IS NULL OR timestamp >= ?4)
+              AND (?5 IS NULL OR timestamp <= ?5)
+            GROUP BY bucket_start
+            ORDER BY bucket_start ASC
+            "#
+        }
+        other => {
+            return Err(format!(
+                "Unknown metric '{}'; use messages, file_changes, tasks_completed, agent_time, tokens, cost or response_time",
+                other
+            ))
+        }
+    };
+
+    let points = sqlx::query_as::<_, (i64, f64)>(query)
+        .bind(&project_id)
+        .bind(offset)
+        .bind(size)
+        .bind(since)
+        .bind(until)
+        .fetch_all(db.pool())
+        .await
+        .map_err(|e| format!("Failed to fetch {} time series: {}", metric, e))?
+        .into_iter()
+        .map(|(bucket_start, value)| TimeseriesPoint { bucket_start, value })
+        .collect();
+
+    Ok(points)
+}
+
+/// Estimate agent spend per project, agent, model and day
+///
+/// Covers one project, or all of them when `project_id` is omitted. `period`
+/// is "day", "week", "month" or "all". Spend comes from the token usage in
+/// Claude Code's session files, priced per model. Projects past their daily or
+/// monthly budget get an alert in the summary and a budget event.
+#[tauri::command]
+pub async fn get_cost_summary(
+    app: AppHandle,
+    db: State<'_, Database>,
+    project_id: Option<String>,
+    period: String,
+) -> Result<CostSummary, String> {
+    log::info!("Summarizing costs for {:?} over {}", project_id, period);
+
+    let now = chrono::Utc::now().timestamp();
+    let since = cost::period_start(&period, now)?;
+    let day_start = cost::period_start("day", now)?;
+    let month_start = cost::month_start(now);
+
+    let projects = sqlx::query_as::<_, Project>(
+        r#"
+        SELECT id, name, root_path, agent_type, status, prd_content, created_at, last_activity, settings, icon, color
+        FROM projects
+        WHERE ? IS NULL OR id = ?
+        "#
+    )
+    .bind(&project_id)
+    .bind(&project_id)
+    .fetch_all(db.pool())
+    .await
+    .map_err(|e| format!("Failed to fetch projects: {}", e))?;
+
+    // Read back to the start of the month too, so monthly budgets can be checked
+    let read_since = since.min(month_start);
+    let root_paths: Vec<String> = projects.iter().map(|p| p.root_path.clone()).collect();
+    let usage = tokio::task::spawn_blocking(move || {
+        root_paths
+            .iter()
+            .map(|root_path| cost::read_project_usage(root_path, read_since))
+            .collect::<Vec<_>>()
+    })
+    .await
+    .map_err(|e| format!("Failed to read session usage: {}", e))?;
+
+    let tagged: Vec<ProjectUsage> = projects
+        .iter()
+        .zip(&usage)
+        .map(|(project, entries)| ProjectUsage {
+            project_name: &project.name,
+            agent_type: &project.agent_type,
+            entries,
+        })
+        .collect();
+    let mut summary = cost::summarize(&period, since, &tagged);
+
+    for (project, entries) in projects.iter().zip(&usage) {
+        let settings = ProjectSettings::from_json(project.settings.as_deref());
+        let budgets = [
+            ("daily", settings.daily_budget_usd, day_start),
+            ("monthly", settings.monthly_budget_usd, month_start),
+        ];
+        for (budget_period, budget, start) in budgets {
+            let Some(budget_usd) = budget else {
+                continue;
+            };
+            let spent_usd = cost::spend_since(entries, start);
+            if spent_usd > budget_usd {
+                summary.budget_alerts.push(BudgetAlert {
+                    project_id: project.id.clone(),
+                    project_name: project.name.clone(),
+                    period: budget_period.to_string(),
+                    spent_usd,
+                    budget_usd,
+                });
+            }
+        }
+    }
+
+    for alert in &summary.budget_alerts {
+        log::warn!(
+            "{} is over its {} budget: ${:.2} of ${:.2}",
+            alert.project_name,
+            alert.period,
+            alert.spent_usd,
+            alert.budget_usd
+        );
+        if let Err(e) = app.emit(cost::BUDGET_EXCEEDED_EVENT, alert) {
+            log::warn!("Failed to emit budget alert: {}", e);
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Lines a session changed in one file
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FileChangeStat {
+    /// Relative to the project root, with `/` separators
+    pub path: String,
+    pub lines_added: u64,
+    pub lines_removed: u64,
+}
+
+/// What an agent session did, for the summary shown when a turn completes
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SessionChangeStats {
+    pub session_id: String,
+    /// Files touched, most lines changed first
+    pub files: Vec<FileChangeStat>,
+    pub lines_added: u64,
+    pub lines_removed: u64,
+    pub commands_run: u32,
+    pub tests_passed: u32,
+    pub tests_failed: u32,
+}
+
+/// Lines added and removed by a unified diff
+fn count_diff_lines(diff: &str) -> (u64, u64) {
+    let mut added = 0;
+    let mut removed = 0;
+    for line in diff.lines() {
+        if line.starts_with("+++") || line.starts_with("---") {
+            continue;
+        }
+        if line.starts_with('+') {
+            added += 1;
+        } else if line.starts_with('-') {
+            removed += 1;
+        }
+    }
+    (added, removed)
+}
+
+/// Tally a session's parsed `events` and the `diffs` recorded while it ran,
+/// oldest first
+///
+/// Stored diffs are against HEAD, so only the last one of each file counts.
+fn session_change_stats(
+    session_id: &str,
+    root_path: &str,
+    events: &[AgentEvent],
+    diffs: &[(String, Option<String>)],
+) -> SessionChangeStats {
+    let mut stats = SessionChangeStats { session_id: session_id.to_string(), ..Default::default() };
+    let mut files: HashMap<String, (u64, u64)> = HashMap::new();
+
+    for event in events {
+        match event {
+            AgentEvent::FileChanged { path, .. } => {
+                let relative = Path::new(path).strip_prefix(root_path).map_or(path.clone(), |p| {
+                    p.to_string_lossy().into_owned()
+                });
+                files.entry(relative.replace('\\', "/")).or_default();
+            }
+            AgentEvent::CommandExecuted { .. } => stats.commands_run += 1,
+            AgentEvent::TestRan { passed: true, .. } => stats.tests_passed += 1,
+            AgentEvent::TestRan { passed: false, .. } => stats.tests_failed += 1,
+            _ => {}
+        }
+    }
+    for (path, diff) in diffs {
+        files.insert(path.clone(), diff.as_deref().map_or((0, 0), count_diff_lines));
+    }
+
+    stats.files = files
+        .into_iter()
+        .map(|(path, (lines_added, lines_removed))| FileChangeStat { path, lines_added, lines_removed })
+        .collect();
+    stats.files.sort_by(|a, b| {
+        (b.lines_added + b.lines_removed)
+            .cmp(&(a.lines_added + a.lines_removed))
+            .then_with(|| a.path.cmp(&b.path))
+    });
+    stats.lines_added = stats.files.iter().map(|f| f.lines_added).sum();
+    stats.lines_removed = stats.files.iter().map(|f| f.lines_removed).sum();
+    stats
+}
+
+/// Summarize an agent session: files touched with lines added and removed,
+/// commands run and tests passed or failed
+///
+/// Line counts come from the diffs the file watcher stored while the session
+/// ran, so a project with several sessions running at once shares them.
+#[tauri::command]
+pub async fn get_session_change_stats(
+    db: State<'_, Database>,
+    session_id: String,
+) -> Result<SessionChangeStats, String> {
+    let (project_id, root_path, started_at, ended_at) = sqlx::query_as::<_, (String, String, i64, Option<i64>)>(
+        r#"
+        SELECT s.project_id, p.root_path, s.started_at, s.ended_at
+        FROM agent_sessions s
+        JOIN projects p ON p.id = s.project_id
+        WHERE s.id = ?
+        "#
+    )
+    .bind(&session_id)
+    .fetch_optional(db.pool())
+    .await
+    .map_err(|e| format!("Failed to load session: {}", e))?
+    .ok_or_else(|| format!("Session not found: {}", session_id))?;
+
+    let events: Vec<AgentEvent> = crate::session_replay::load_events(db.pool(), &session_id)
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|recorded| recorded.event)
+        .collect();
+
+    let diffs = sqlx::query_as::<_, (String, Option<String>)>(
+        r#"
+        SELECT file_path, diff
+        FROM file_changes
+        WHERE project_id = ? AND timestamp >= ? AND (? IS NULL OR timestamp <= ?)
+        ORDER BY timestamp
+        "#
+    )
+    .bind(&project_id)
+    .bind(started_at)
+    .bind(ended_at)
+    .bind(ended_at)
+    .fetch_all(db.pool())
+    .await
+    .map_err(|e| format!("Failed to fetch file changes: {}", e))?;
+
+    Ok(session_change_stats(&session_id, &root_path, &events, &diffs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output_parser::FileChangeType;
+
+    #[test]
+    fn test_session_change_stats() {
+        let events = vec![
+            AgentEvent::FileChanged {
+                path: "/proj/src/lib.rs".to_string(),
+                change_type: FileChangeType::Modified,
+                timestamp: 0,
+            },
+            AgentEvent::FileChanged { path: "README.md".to_string(), change_type: FileChangeType::Created, timestamp: 0 },
+            AgentEvent::CommandExecuted { command: "cargo test".to_string(), exit_code: 0, output: None, timestamp: 0 },
+            AgentEvent::TestRan { name: "a".to_string(), passed: true, details: None, timestamp: 0 },
+            AgentEvent::TestRan { name: "b".to_string(), passed: false, details: None, timestamp: 0 },
+        ];
+        let diffs = vec![
+            ("src/lib.rs".to_string(), Some("--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1 +1 @@\n-a\n+b\n".to_string())),
+            // The later diff of a file replaces the earlier one
+            ("src/lib.rs".to_string(), Some("--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1 +1,2 @@\n-a\n+b\n+c\n".to_string())),
+            ("gone.rs".to_string(), None),
+        ];
+        let stats = session_change_stats("s1", "/proj", &events, &diffs);
+
+        assert_eq!(
+            stats.files,
+            vec![
+                FileChangeStat { path: "src/lib.rs".to_string(), lines_added: 2, lines_removed: 1 },
+                FileChangeStat { path: "README.md".to_string(), lines_added: 0, lines_removed: 0 },
+                FileChangeStat { path: "gone.rs".to_string(), lines_added: 0, lines_removed: 0 },
+            ]
+        );
+        assert_eq!((stats.lines_added, stats.lines_removed), (2, 1));
+        assert_eq!((stats.commands_run, stats.tests_passed, stats.tests_failed), (1, 1, 1));
+    }
+}
@@ -0,0 +1,42 @@
+// Text-to-speech commands
+// Reads agent replies aloud with the operating system's voices
+
+use tauri::State;
+
+use crate::tts::{self, TtsManager};
+
+/// Speak text, stopping anything already playing
+///
+/// Markdown is reduced to plain prose first. With `summarize`, only the
+/// opening sentences of a long reply are read.
+#[tauri::command]
+pub async fn speak_text(
+    tts_manager: State<'_, TtsManager>,
+    text: String,
+    voice: Option<String>,
+    rate: Option<u32>,
+    summarize: Option<bool>,
+) -> Result<(), String> {
+    let max_chars = summarize.unwrap_or(false).then_some(tts::SUMMARY_MAX_CHARS);
+    let speech = tts::speech_text(&text, max_chars);
+    if speech.is_empty() {
+        return Ok(());
+    }
+
+    log::info!("Speaking {} chars", speech.len());
+    tts_manager
+        .speak(&speech, voice.as_deref(), rate.unwrap_or(tts::DEFAULT_RATE_WPM))
+        .map_err(|e| format!("Failed to speak text: {}", e))
+}
+
+/// Stop speaking; returns false if nothing was playing
+#[tauri::command]
+pub async fn stop_speaking(tts_manager: State<'_, TtsManager>) -> Result<bool, String> {
+    Ok(tts_manager.stop())
+}
+
+/// Whether text is currently being spoken
+#[tauri::command]
+pub async fn is_speaking(tts_manager: State<'_, TtsManager>) -> Result<bool, String> {
+    Ok(tts_manager.is_speaking())
+}
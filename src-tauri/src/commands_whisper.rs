@@ -3,9 +3,18 @@
 
 use serde::{Deserialize, Serialize};
 use std::process::Command;
-use tauri::{AppHandle, Manager};
+use tauri::{AppHandle, Emitter, Manager, State};
 use reqwest::multipart;
 
+use crate::agent_manager::AgentManager;
+use crate::db::Database;
+use crate::error::{AppError, ErrorKind};
+use crate::models::Transcription;
+use crate::plugin::PluginManager;
+use crate::plugin_settings::PluginSettingsManager;
+use crate::whisper_native;
+use crate::whisper_stream::{WhisperStream, WhisperStreamManager, WhisperWorker, PARTIAL_EVENT};
+
 /// Result of checking Whisper installation
 #[derive(Debug, Serialize, Deserialize)]
 pub struct WhisperInstallationStatus {
@@ -20,14 +29,56 @@ pub struct WhisperInstallationStatus {
 pub struct TranscriptionResult {
     pub text: String,
     pub language: Option<String>,
+    /// Probability of the detected language; only set when it was auto-detected
+    pub language_confidence: Option<f64>,
     pub duration: Option<f64>,
 }
 
-/// Check if local Whisper (Python) is installed
+/// Result of a voice prompt
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VoiceSendResult {
+    pub transcription: TranscriptionResult,
+    /// History entry for the transcript; None when it was empty
+    pub transcription_id: Option<String>,
+    /// Whether the text was sent; false when it's waiting for confirmation or empty
+    pub sent: bool,
+    pub session_id: Option<String>,
+}
+
+/// Normalize a requested language: empty or "auto" means auto-detect
+fn requested_language(language: Option<String>) -> Option<String> {
+    language
+        .map(|l| l.trim().to_lowercase())
+        .filter(|l| !l.is_empty() && l != "auto")
+}
+
+/// Path of the native whisper.cpp model, if this build supports it and it's downloaded
+fn native_model(app: &AppHandle, model: &str) -> Option<std::path::PathBuf> {
+    if !whisper_native::is_available() {
+        return None;
+    }
+    let app_data_dir = app.path().app_data_dir().ok()?;
+    Some(whisper_native::model_path(&app_data_dir, model)).filter(|path| path.is_file())
+}
+
+/// Check if local Whisper is installed
+///
+/// Native whisper.cpp builds report installed as soon as the model is
+/// downloaded; otherwise this checks the Python package.
 #[tauri::command]
-pub async fn check_whisper_installation() -> Result<WhisperInstallationStatus, String> {
+pub async fn check_whisper_installation(app: AppHandle) -> Result<WhisperInstallationStatus, AppError> {
     log::info!("Checking Whisper installation...");
 
+    if native_model(&app, "base").is_some() {
+        log::info!("Native whisper.cpp model found");
+        return Ok(WhisperInstallationStatus {
+            installed: true,
+            model_downloaded: true,
+            python_version: None,
+            whisper_version: Some("whisper.cpp".to_string()),
+        });
+    }
+
     // Check Python version
     let python_version = Command::new("python")
         .args(["--version"])
@@ -45,7 +96,8 @@ pub async fn check_whisper_installation() -> Result<WhisperInstallationStatus, S
     if python_version.is_none() {
         log::info!("Python not found");
         return Ok(WhisperInstallationStatus {
-            installed: false,
+            // Native builds only need the model downloaded
+            installed: whisper_native::is_available(),
             model_downloaded: false,
             python_version: None,
             whisper_version: None,
@@ -94,20 +146,33 @@ pub async fn check_whisper_installation() -> Result<WhisperInstallationStatus, S
     })
 }
 
-/// Install Whisper using pip
+/// Install Whisper
+///
+/// Native whisper.cpp builds download the ggml model; otherwise the Python
+/// package is installed with pip.
 #[tauri::command]
-pub async fn install_whisper(model: String) -> Result<(), String> {
+pub async fn install_whisper(app: AppHandle, model: String) -> Result<(), AppError> {
     log::info!("Installing Whisper with model: {}", model);
 
+    if whisper_native::is_available() {
+        let app_data_dir = app.path().app_data_dir()
+            .map_err(AppError::context("Failed to get app data dir"))?;
+        let path = whisper_native::download_model(&app_data_dir, &model)
+            .await
+            .map_err(AppError::context("Failed to download model"))?;
+        log::info!("whisper.cpp model ready at {:?}", path);
+        return Ok(());
+    }
+
     // Install whisper package
     let install_result = Command::new("pip")
         .args(["install", "-U", "openai-whisper"])
         .output()
-        .map_err(|e| format!("Failed to run pip: {}", e))?;
+        .map_err(AppError::context("Failed to run pip"))?;
 
     if !install_result.status.success() {
         let stderr = String::from_utf8_lossy(&install_result.stderr);
-        return Err(format!("Failed to install whisper: {}", stderr));
+        return Err(AppError::process(format!("Failed to install whisper: {}", stderr)));
     }
 
     // Download the model
@@ -119,11 +184,11 @@ pub async fn install_whisper(model: String) -> Result<(), String> {
     let download_result = Command::new("python")
         .args(["-c", &download_script])
         .output()
-        .map_err(|e| format!("Failed to download model: {}", e))?;
+        .map_err(AppError::context("Failed to download model"))?;
 
     if !download_result.status.success() {
         let stderr = String::from_utf8_lossy(&download_result.stderr);
-        return Err(format!("Failed to download model: {}", stderr));
+        return Err(AppError::process(format!("Failed to download model: {}", stderr)));
     }
 
     log::info!("Whisper installed successfully");
@@ -136,8 +201,13 @@ pub async fn transcribe_local(
     app: AppHandle,
     audio_data: Vec<u8>,
     model: String,
-) -> Result<TranscriptionResult, String> {
-    println!("[WHISPER] Transcribing audio locally with model: {}, data size: {} bytes", model, audio_data.len());
+    language: Option<String>,
+    translate: Option<bool>,
+) -> Result<TranscriptionResult, AppError> {
+    let language = requested_language(language);
+    let translate = translate.unwrap_or(false);
+    println!("[WHISPER] Transcribing audio locally with model: {}, language: {:?}, translate: {}, data size: {} bytes",
+        model, language, translate, audio_data.len());
 
     // Debug: Log first and last bytes to verify data integrity
     if audio_data.len() >= 20 {
@@ -154,12 +224,12 @@ pub async fn transcribe_local(
 
     // Save audio to temp file (could be webm or wav from frontend)
     let temp_dir = app.path().temp_dir()
-        .map_err(|e| format!("Failed to get temp dir: {}", e))?;
+        .map_err(AppError::context("Failed to get temp dir"))?;
     let input_path = temp_dir.join(format!("whisper_input_{}.webm", uuid::Uuid::new_v4()));
     let wav_path = temp_dir.join(format!("whisper_input_{}.wav", uuid::Uuid::new_v4()));
 
     std::fs::write(&input_path, &audio_data)
-        .map_err(|e| format!("Failed to write temp audio file: {}", e))?;
+        .map_err(AppError::context("Failed to write temp audio file"))?;
 
     // Debug: Verify written file
     let written_size = std::fs::metadata(&input_path)
@@ -179,7 +249,7 @@ pub async fn transcribe_local(
             &wav_path.to_string_lossy().to_string(),
         ])
         .output()
-        .map_err(|e| format!("Failed to run FFmpeg: {}", e))?;
+        .map_err(AppError::context("Failed to run FFmpeg"))?;
 
     // Debug: Log FFmpeg output
     let ffmpeg_stdout = String::from_utf8_lossy(&ffmpeg_result.stdout);
@@ -193,7 +263,7 @@ pub async fn transcribe_local(
     let _ = std::fs::remove_file(&input_path);
 
     if !ffmpeg_result.status.success() {
-        return Err(format!("FFmpeg conversion failed: {}", ffmpeg_stderr));
+        return Err(AppError::process(format!("FFmpeg conversion failed: {}", ffmpeg_stderr)));
     }
 
     // Debug: Verify WAV file
@@ -210,34 +280,69 @@ pub async fn transcribe_local(
         println!("[WHISPER] Estimated audio duration: {:.2} seconds", duration_secs);
     }
 
+    if let Some(model_path) = native_model(&app, &model) {
+        println!("[WHISPER] Audio converted successfully, running whisper.cpp...");
+        let wav = std::fs::read(&wav_path)
+            .map_err(AppError::context("Failed to read converted audio"));
+        match wav {
+            Ok(wav) => match whisper_native::transcribe_wav(&model_path, wav, language.clone(), translate).await {
+                Ok(result) => {
+                    let _ = std::fs::remove_file(&wav_path);
+                    println!("[WHISPER] Native transcription complete: text='{}', language={:?} ({:?})",
+                        result.text, result.language, result.language_confidence);
+                    return Ok(result);
+                }
+                Err(e) => println!("[WHISPER] Native transcription failed, falling back to Python: {}", e),
+            },
+            Err(e) => println!("[WHISPER] {}, falling back to Python", e),
+        }
+    }
+
     println!("[WHISPER] Audio converted successfully, running Whisper...");
 
     // Create Python script for transcription
+    // Language ("" to auto-detect) and task are passed as arguments
     let script = format!(
         r#"
 import whisper
 import json
 import sys
 
+language = sys.argv[1] or None
+task = sys.argv[2]
+audio_path = "{}"
+
 model = whisper.load_model("{}")
-result = model.transcribe("{}")
+
+# Detect the language up front so its probability can be reported
+confidence = None
+if language is None:
+    audio = whisper.pad_or_trim(whisper.load_audio(audio_path))
+    mel = whisper.log_mel_spectrogram(audio, n_mels=model.dims.n_mels).to(model.device)
+    _, probs = model.detect_language(mel)
+    language = max(probs, key=probs.get)
+    confidence = float(probs[language])
+
+result = model.transcribe(audio_path, language=language, task=task)
 
 output = {{
     "text": result["text"].strip(),
     "language": result.get("language"),
+    "language_confidence": confidence,
     "duration": result.get("duration")
 }}
 print(json.dumps(output))
 "#,
-        model,
-        wav_path.to_string_lossy().replace("\\", "\\\\")
+        wav_path.to_string_lossy().replace("\\", "\\\\"),
+        model
     );
 
     // Run transcription
+    let task = if translate { "translate" } else { "transcribe" };
     let output = Command::new("python")
-        .args(["-c", &script])
+        .args(["-c", &script, language.as_deref().unwrap_or(""), task])
         .output()
-        .map_err(|e| format!("Failed to run Whisper: {}", e))?;
+        .map_err(AppError::context("Failed to run Whisper"))?;
 
     // Clean up wav file
     let _ = std::fs::remove_file(&wav_path);
@@ -252,7 +357,7 @@ print(json.dumps(output))
     }
 
     if !output.status.success() {
-        return Err(format!("Whisper transcription failed: {}", stderr));
+        return Err(AppError::process(format!("Whisper transcription failed: {}", stderr)));
     }
 
     let result: TranscriptionResult = serde_json::from_str(&stdout)
@@ -263,53 +368,375 @@ print(json.dumps(output))
     Ok(result)
 }
 
+/// Transcribe a recorded prompt and send it to a chat tab's session
+///
+/// `provider` is "local" or "openai", matching the voice settings. With
+/// `confirm` the transcript is only returned, so the UI can show it for
+/// editing and send it with `send_to_tab`.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn transcribe_and_send(
+    app: AppHandle,
+    db: State<'_, Database>,
+    agent_manager: State<'_, AgentManager>,
+    plugin_manager: State<'_, PluginManager>,
+    plugin_settings_manager: State<'_, PluginSettingsManager>,
+    tab_id: String,
+    audio_data: Vec<u8>,
+    provider: String,
+    model: Option<String>,
+    api_key: Option<String>,
+    language: Option<String>,
+    translate: Option<bool>,
+    confirm: Option<bool>,
+) -> Result<VoiceSendResult, AppError> {
+    log::info!("Transcribing voice prompt for chat tab {} with {}", tab_id, provider);
+
+    let (transcription, model_used) = match provider.as_str() {
+        "openai" => {
+            let api_key = api_key.ok_or_else(|| AppError::invalid_input("An OpenAI API key is required"))?;
+            let result = transcribe_openai(audio_data, api_key, language, translate).await?;
+            (result, "whisper-1".to_string())
+        }
+        "local" => {
+            let model = model.unwrap_or_else(|| "base".to_string());
+            let result = transcribe_local(app, audio_data, model.clone(), language, translate).await?;
+            (result, model)
+        }
+        other => return Err(AppError::invalid_input(format!("Unknown transcription provider: {}", other))),
+    };
+
+    let text = transcription.text.trim().to_string();
+    if text.is_empty() {
+        return Ok(VoiceSendResult {
+            transcription,
+            transcription_id: None,
+            sent: false,
+            session_id: None,
+        });
+    }
+
+    // Keep the transcript before sending so it survives a failed or cancelled send
+    let project_id = sqlx::query_scalar::<_, String>("SELECT project_id FROM chat_tabs WHERE id = ?")
+        .bind(&tab_id)
+        .fetch_optional(db.pool())
+        .await
+        .map_err(AppError::context("Failed to find chat tab"))?
+        .ok_or_else(|| AppError::not_found(format!("Chat tab not found: {}", tab_id)))?;
+
+    let mut record = Transcription::new(project_id, text.clone(), provider.clone());
+    record.duration = transcription.duration;
+    record.model = Some(model_used);
+    record.language = transcription.language.clone();
+    insert_transcription(&db, &record).await?;
+
+    if confirm.unwrap_or(false) {
+        return Ok(VoiceSendResult {
+            transcription,
+            transcription_id: Some(record.id),
+            sent: false,
+            session_id: None,
+        });
+    }
+
+    let session_id = crate::commands::send_to_tab_session(
+        &db,
+        &agent_manager,
+        &plugin_manager,
+        &plugin_settings_manager,
+        &tab_id,
+        &text,
+    )
+    .await?;
+
+    log::info!("Voice prompt sent to session {}", session_id);
+    Ok(VoiceSendResult {
+        transcription,
+        transcription_id: Some(record.id),
+        sent: true,
+        session_id: Some(session_id),
+    })
+}
+
+async fn insert_transcription(db: &Database, record: &Transcription) -> Result<(), AppError> {
+    sqlx::query(
+        r#"
+        INSERT INTO transcriptions (id, project_id, text, duration, provider, model, language, message_id, created_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#
+    )
+    .bind(&record.id)
+    .bind(&record.project_id)
+    .bind(&record.text)
+    .bind(record.duration)
+    .bind(&record.provider)
+    .bind(&record.model)
+    .bind(&record.language)
+    .bind(&record.message_id)
+    .bind(record.created_at)
+    .execute(db.pool())
+    .await
+    .map_err(AppError::context("Failed to save transcription"))?;
+    Ok(())
+}
+
+/// Save a transcript to the project's history
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn save_transcription(
+    db: State<'_, Database>,
+    project_id: String,
+    text: String,
+    provider: String,
+    duration: Option<f64>,
+    model: Option<String>,
+    language: Option<String>,
+    message_id: Option<String>,
+) -> Result<Transcription, AppError> {
+    log::info!("Saving transcription for project {}", project_id);
+
+    let mut record = Transcription::new(project_id, text, provider);
+    record.duration = duration;
+    record.model = model;
+    record.language = language;
+    record.message_id = message_id;
+    insert_transcription(&db, &record).await?;
+
+    Ok(record)
+}
+
+/// List a project's transcriptions, newest first
+#[tauri::command]
+pub async fn list_transcriptions(
+    db: State<'_, Database>,
+    project_id: String,
+    limit: Option<i64>,
+) -> Result<Vec<Transcription>, AppError> {
+    log::info!("Fetching transcriptions for project: {}", project_id);
+
+    let transcriptions = sqlx::query_as::<_, Transcription>(
+        r#"
+        SELECT id, project_id, text, duration, provider, model, language, message_id, created_at
+        FROM transcriptions
+        WHERE project_id = ?
+        ORDER BY created_at DESC
+        LIMIT ?
+        "#
+    )
+    .bind(&project_id)
+    .bind(limit.unwrap_or(50).clamp(1, 500))
+    .fetch_all(db.pool())
+    .await
+    .map_err(AppError::context("Failed to fetch transcriptions"))?;
+
+    log::info!("Fetched {} transcriptions for project {}", transcriptions.len(), project_id);
+    Ok(transcriptions)
+}
+
+/// Record the message a transcript was sent as
+#[tauri::command]
+pub async fn link_transcription_message(
+    db: State<'_, Database>,
+    transcription_id: String,
+    message_id: String,
+) -> Result<bool, AppError> {
+    let result = sqlx::query("UPDATE transcriptions SET message_id = ? WHERE id = ?")
+        .bind(&message_id)
+        .bind(&transcription_id)
+        .execute(db.pool())
+        .await
+        .map_err(AppError::context("Failed to link transcription"))?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Delete a transcript from the history
+#[tauri::command]
+pub async fn delete_transcription(
+    db: State<'_, Database>,
+    transcription_id: String,
+) -> Result<bool, AppError> {
+    log::info!("Deleting transcription: {}", transcription_id);
+
+    let result = sqlx::query("DELETE FROM transcriptions WHERE id = ?")
+        .bind(&transcription_id)
+        .execute(db.pool())
+        .await
+        .map_err(AppError::context("Failed to delete transcription"))?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Start a streaming transcription with local Whisper
+///
+/// Loads the model once and returns a stream id; feed it with
+/// `push_transcription_chunk` and listen for partial transcripts.
+#[tauri::command]
+pub async fn start_transcription_stream(
+    app: AppHandle,
+    streams: State<'_, WhisperStreamManager>,
+    model: String,
+    language: Option<String>,
+    translate: Option<bool>,
+) -> Result<String, AppError> {
+    let language = requested_language(language);
+    log::info!("Starting transcription stream with model: {} (language: {:?})", model, language);
+
+    let temp_dir = app.path().temp_dir()
+        .map_err(AppError::context("Failed to get temp dir"))?;
+    let worker = WhisperWorker::spawn(&model, language.as_deref(), translate.unwrap_or(false))
+        .await
+        .map_err(AppError::context("Failed to start Whisper"))?;
+
+    let stream_id = streams.insert(WhisperStream::new(temp_dir, worker));
+    log::info!("Transcription stream started: {}", stream_id);
+    Ok(stream_id)
+}
+
+/// Append recorded audio to a stream
+///
+/// When Whisper is idle this kicks off a pass over everything recorded so
+/// far and emits the result as a partial transcript; chunks that arrive
+/// mid-pass are picked up by the next one.
+#[tauri::command]
+pub async fn push_transcription_chunk(
+    app: AppHandle,
+    streams: State<'_, WhisperStreamManager>,
+    stream_id: String,
+    chunk: Vec<u8>,
+) -> Result<(), AppError> {
+    let stream = streams
+        .get(&stream_id)
+        .ok_or_else(|| AppError::not_found(format!("Transcription stream not found: {}", stream_id)))?;
+    stream.push(&chunk);
+
+    let Some(mut worker) = stream.try_claim() else {
+        return Ok(());
+    };
+
+    tokio::spawn(async move {
+        match stream.transcribe(&mut worker).await {
+            Ok(result) => {
+                if let Err(e) = app.emit(PARTIAL_EVENT, serde_json::json!({
+                    "stream_id": stream_id,
+                    "text": result.text,
+                    "is_final": false,
+                })) {
+                    log::warn!("Failed to emit partial transcript: {}", e);
+                }
+            }
+            Err(e) => log::warn!("Partial transcription failed for {}: {}", stream_id, e),
+        }
+    });
+
+    Ok(())
+}
+
+/// Finish a stream and return the transcript of the full recording
+#[tauri::command]
+pub async fn finish_transcription_stream(
+    app: AppHandle,
+    streams: State<'_, WhisperStreamManager>,
+    stream_id: String,
+) -> Result<TranscriptionResult, AppError> {
+    log::info!("Finishing transcription stream: {}", stream_id);
+
+    let stream = streams
+        .remove(&stream_id)
+        .ok_or_else(|| AppError::not_found(format!("Transcription stream not found: {}", stream_id)))?;
+
+    let mut worker = stream.claim().await;
+    let result = stream
+        .transcribe(&mut worker)
+        .await
+        .map_err(AppError::context("Failed to transcribe stream"))?;
+
+    if let Err(e) = app.emit(PARTIAL_EVENT, serde_json::json!({
+        "stream_id": stream_id,
+        "text": result.text,
+        "is_final": true,
+    })) {
+        log::warn!("Failed to emit final transcript: {}", e);
+    }
+
+    log::info!("Transcription stream finished: {} chars", result.text.len());
+    Ok(result)
+}
+
+/// Discard a stream without transcribing; returns false if it wasn't open
+#[tauri::command]
+pub async fn cancel_transcription_stream(
+    streams: State<'_, WhisperStreamManager>,
+    stream_id: String,
+) -> Result<bool, AppError> {
+    log::info!("Cancelling transcription stream: {}", stream_id);
+    Ok(streams.remove(&stream_id).is_some())
+}
+
 /// Transcribe audio using OpenAI Whisper API
+///
+/// With `translate` the translations endpoint is used, which always outputs
+/// English and ignores `language`. The API doesn't report a confidence.
 #[tauri::command]
 pub async fn transcribe_openai(
     audio_data: Vec<u8>,
     api_key: String,
-) -> Result<TranscriptionResult, String> {
-    log::info!("Transcribing audio with OpenAI API");
+    language: Option<String>,
+    translate: Option<bool>,
+) -> Result<TranscriptionResult, AppError> {
+    let language = requested_language(language);
+    let translate = translate.unwrap_or(false);
+    log::info!("Transcribing audio with OpenAI API (language: {:?}, translate: {})", language, translate);
 
     // Create multipart form
     let part = multipart::Part::bytes(audio_data)
         .file_name("audio.webm")
         .mime_str("audio/webm")
-        .map_err(|e| format!("Failed to create multipart: {}", e))?;
+        .map_err(AppError::context("Failed to create multipart"))?;
 
-    let form = multipart::Form::new()
+    // verbose_json includes the detected language and duration
+    let mut form = multipart::Form::new()
         .part("file", part)
-        .text("model", "whisper-1");
+        .text("model", "whisper-1")
+        .text("response_format", "verbose_json");
+    if let (Some(language), false) = (&language, translate) {
+        form = form.text("language", language.clone());
+    }
+
+    let endpoint = if translate { "translations" } else { "transcriptions" };
 
     // Send request to OpenAI
     let client = reqwest::Client::new();
     let response = client
-        .post("https://api.openai.com/v1/audio/transcriptions")
+        .post(format!("https://api.openai.com/v1/audio/{}", endpoint))
         .header("Authorization", format!("Bearer {}", api_key))
         .multipart(form)
         .send()
         .await
-        .map_err(|e| format!("Failed to send request to OpenAI: {}", e))?;
+        .map_err(AppError::context("Failed to send request to OpenAI"))?;
 
     if !response.status().is_success() {
         let error_text = response.text().await.unwrap_or_default();
-        return Err(format!("OpenAI API error: {}", error_text));
+        return Err(AppError::new(ErrorKind::Network, format!("OpenAI API error: {}", error_text)));
     }
 
     #[derive(Deserialize)]
     struct OpenAIResponse {
         text: String,
+        language: Option<String>,
+        duration: Option<f64>,
     }
 
     let result: OpenAIResponse = response
         .json()
         .await
-        .map_err(|e| format!("Failed to parse OpenAI response: {}", e))?;
+        .map_err(AppError::context("Failed to parse OpenAI response"))?;
 
     log::info!("OpenAI transcription complete: {} chars", result.text.len());
     Ok(TranscriptionResult {
         text: result.text,
-        language: None,
-        duration: None,
+        language: result.language,
+        language_confidence: None,
+        duration: result.duration,
     })
 }
@@ -0,0 +1,349 @@
+// Container Environments
+// Detects a project's dev container and Docker Compose setup, and runs agent
+// processes inside the project's running container with `docker exec`
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Compose file names Docker looks for, in its order of preference
+const COMPOSE_FILES: &[&str] = &["compose.yaml", "compose.yml", "docker-compose.yaml", "docker-compose.yml"];
+
+/// How long a docker lookup may take before it's abandoned
+const DOCKER_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A project's container setup, as found in its files
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ContainerEnvironment {
+    pub devcontainer: Option<Devcontainer>,
+    /// Compose files at the project root, relative to it
+    pub compose_files: Vec<String>,
+}
+
+/// The parts of a devcontainer.json that matter for finding its container
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Devcontainer {
+    /// The config file, relative to the project root
+    pub config_path: String,
+    pub name: Option<String>,
+    pub image: Option<String>,
+    /// Compose files the container comes from, relative to the project root
+    pub docker_compose_files: Vec<String>,
+    /// Compose service the tools run in
+    pub service: Option<String>,
+    /// Where the project is mounted inside the container
+    pub workspace_folder: Option<String>,
+}
+
+/// Find the dev container config and compose files in `root`, if there are any
+pub fn detect(root: &Path) -> Option<ContainerEnvironment> {
+    let devcontainer = [".devcontainer/devcontainer.json", ".devcontainer.json"]
+        .iter()
+        .find(|path| root.join(path).is_file())
+        .and_then(|path| match read_devcontainer(root, path) {
+            Ok(config) => Some(config),
+            Err(e) => {
+                log::warn!("Failed to read {} in {}: {:#}", path, root.display(), e);
+                None
+            }
+        });
+    let compose_files: Vec<String> = COMPOSE_FILES
+        .iter()
+        .filter(|name| root.join(name).is_file())
+        .map(|name| name.to_string())
+        .collect();
+
+    (devcontainer.is_some() || !compose_files.is_empty()).then_some(ContainerEnvironment { devcontainer, compose_files })
+}
+
+fn read_devcontainer(root: &Path, config_path: &str) -> Result<Devcontainer> {
+    let text = std::fs::read_to_string(root.join(config_path))?;
+    let json: serde_json::Value = serde_json::from_str(&strip_jsonc(&text)).context("Invalid devcontainer.json")?;
+    let string = |key: &str| json.get(key).and_then(|v| v.as_str()).map(str::to_string);
+
+    // Compose files are relative to the config's folder
+    let config_dir = Path::new(config_path).parent().unwrap_or(Path::new(""));
+    let compose_files = match json.get("dockerComposeFile") {
+        Some(serde_json::Value::String(file)) => vec![file.clone()],
+        Some(serde_json::Value::Array(files)) => files.iter().filter_map(|f| f.as_str().map(str::to_string)).collect(),
+        _ => Vec::new(),
+    };
+    Ok(Devcontainer {
+        config_path: config_path.to_string(),
+        name: string("name"),
+        image: string("image"),
+        docker_compose_files: compose_files
+            .iter()
+            .map(|file| normalize(&config_dir.join(file)).to_string_lossy().replace('\\', "/"))
+            .collect(),
+        service: string("service"),
+        workspace_folder: string("workspaceFolder"),
+    })
+}
+
+/// Resolve `.` and `..` without touching the filesystem
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// Turn JSON with comments and trailing commas, as devcontainer.json allows, into plain JSON
+fn strip_jsonc(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            match c {
+                '\\' => out.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+            }
+            ('/', Some('/')) => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        out.push('\n');
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+                out.push(' ');
+            }
+            _ => out.push(c),
+        }
+    }
+    remove_trailing_commas(&out)
+}
+
+fn remove_trailing_commas(json: &str) -> String {
+    let chars: Vec<char> = json.chars().collect();
+    let mut out = String::with_capacity(json.len());
+    let mut in_string = false;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if in_string {
+            match c {
+                '\\' => {
+                    out.push(c);
+                    i += 1;
+                }
+                '"' => in_string = false,
+                _ => {}
+            }
+        } else if c == '"' {
+            in_string = true;
+        } else if c == ',' {
+            let next = chars[i + 1..].iter().find(|c| !c.is_whitespace());
+            if matches!(next, Some('}') | Some(']')) {
+                i += 1;
+                continue;
+            }
+        }
+        if let Some(c) = chars.get(i) {
+            out.push(*c);
+        }
+        i += 1;
+    }
+    out
+}
+
+/// The program and arguments that run `program` in the project's container
+///
+/// Uses `container` when given, otherwise the running container of the
+/// project's dev container or compose setup. `env_names` are passed through
+/// from the `docker` process's environment.
+pub async fn wrap(
+    container: Option<&str>,
+    root: &Path,
+    program: String,
+    args: Vec<String>,
+    env_names: &[String],
+) -> Result<(String, Vec<String>)> {
+    let environment = detect(root);
+    let container = match container {
+        Some(name) => name.to_string(),
+        None => find_container(root, environment.as_ref()).await?,
+    };
+    let workspace_folder = environment.and_then(|env| env.devcontainer).and_then(|config| config.workspace_folder);
+    let workdir = container_workdir(&container, root, workspace_folder).await?;
+    log::info!("Running {} in container {} at {}", program, container, workdir);
+
+    let mut wrapped = vec!["exec".to_string(), "-i".to_string(), "-w".to_string(), workdir];
+    for name in env_names {
+        wrapped.push("-e".to_string());
+        wrapped.push(name.clone());
+    }
+    wrapped.push(container);
+    wrapped.push(program);
+    wrapped.extend(args);
+    Ok(("docker".to_string(), wrapped))
+}
+
+/// Run docker and return its stdout lines
+async fn docker(args: &[&str], dir: &Path) -> Result<Vec<String>> {
+    let output = tokio::process::Command::new("docker")
+        .args(args)
+        .current_dir(dir)
+        .kill_on_drop(true)
+        .output();
+    let output = tokio::time::timeout(DOCKER_TIMEOUT, output)
+        .await
+        .with_context(|| format!("docker {} timed out", args.first().unwrap_or(&"")))?
+        .context("Failed to run docker; is it installed?")?;
+    if !output.status.success() {
+        bail!("docker {} failed: {}", args.first().unwrap_or(&""), String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).lines().map(str::to_string).filter(|l| !l.is_empty()).collect())
+}
+
+/// The id of the running container for the project's dev container or compose setup
+async fn find_container(root: &Path, environment: Option<&ContainerEnvironment>) -> Result<String> {
+    let Some(environment) = environment else {
+        bail!("No dev container or compose file found in {}", root.display());
+    };
+
+    // The dev containers CLI and editors label containers with the folder they serve
+    let label = format!("label=devcontainer.local_folder={}", root.display());
+    if let Some(id) = docker(&["ps", "-q", "--filter", &label], root).await?.into_iter().next() {
+        return Ok(id);
+    }
+
+    let (files, service) = match &environment.devcontainer {
+        Some(config) if !config.docker_compose_files.is_empty() => (&config.docker_compose_files, config.service.as_deref()),
+        _ => (&environment.compose_files, None),
+    };
+    if files.is_empty() {
+        bail!("The project's dev container isn't running; start it first");
+    }
+    let mut args = vec!["compose"];
+    for file in files {
+        args.extend(["-f", file.as_str()]);
+    }
+    args.extend(["ps", "-q"]);
+    args.extend(service);
+    let ids = docker(&args, root).await?;
+    match ids.as_slice() {
+        [] => bail!("The project's containers aren't running; start them first"),
+        [id] => Ok(id.clone()),
+        _ => bail!("Several of the project's containers are running; name one in the execution profile"),
+    }
+}
+
+#[derive(Deserialize)]
+struct Mount {
+    #[serde(rename = "Source")]
+    source: String,
+    #[serde(rename = "Destination")]
+    destination: String,
+}
+
+/// Where `root` is inside the container: through its mounts, or the configured workspace folder
+async fn container_workdir(container: &str, root: &Path, workspace_folder: Option<String>) -> Result<String> {
+    let mounts = docker(&["inspect", "--format", "{{json .Mounts}}", container], root).await?;
+    let mounts: Vec<Mount> = mounts
+        .first()
+        .map(|json| serde_json::from_str(json))
+        .transpose()
+        .context("Unexpected docker inspect output")?
+        .unwrap_or_default();
+    map_to_container(root, &mounts)
+        .or(workspace_folder)
+        .with_context(|| format!("{} isn't mounted in container {}", root.display(), container))
+}
+
+/// The container path of `root`, through the mount closest to it
+fn map_to_container(root: &Path, mounts: &[Mount]) -> Option<String> {
+    let (mount, relative) = mounts
+        .iter()
+        .filter_map(|mount| Some((mount, root.strip_prefix(&mount.source).ok()?)))
+        .max_by_key(|(mount, _)| mount.source.len())?;
+    let mut path = mount.destination.trim_end_matches('/').to_string();
+    for component in relative.components() {
+        path.push('/');
+        path.push_str(&component.as_os_str().to_string_lossy());
+    }
+    Some(if path.is_empty() { "/".to_string() } else { path })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_devcontainer_and_compose() {
+        let root = std::env::temp_dir().join(format!("ateliercode-container-{}", uuid::Uuid::new_v4()));
+        assert_eq!(detect(&root), None);
+
+        std::fs::create_dir_all(root.join(".devcontainer")).unwrap();
+        std::fs::write(root.join("docker-compose.yml"), "services: {}").unwrap();
+        std::fs::write(
+            root.join(".devcontainer/devcontainer.json"),
+            r#"{
+                // Comments and trailing commas are allowed
+                "name": "App // dev",
+                "dockerComposeFile": ["../docker-compose.yml", "compose.extra.yml"],
+                "service": "app", /* the tools live here */
+                "workspaceFolder": "/workspaces/app",
+            }"#,
+        )
+        .unwrap();
+
+        let environment = detect(&root).unwrap();
+        assert_eq!(environment.compose_files, vec!["docker-compose.yml"]);
+        let config = environment.devcontainer.unwrap();
+        assert_eq!(config.config_path, ".devcontainer/devcontainer.json");
+        assert_eq!(config.name.as_deref(), Some("App // dev"));
+        assert_eq!(config.docker_compose_files, vec!["docker-compose.yml", ".devcontainer/compose.extra.yml"]);
+        assert_eq!((config.service.as_deref(), config.workspace_folder.as_deref()), (Some("app"), Some("/workspaces/app")));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_strip_jsonc_keeps_strings() {
+        let json = strip_jsonc(r#"{"url": "http://x/*y*/", "list": [1, 2,], "quote": "a\"//b",}"#);
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["url"], "http://x/*y*/");
+        assert_eq!(value["list"].as_array().unwrap().len(), 2);
+        assert_eq!(value["quote"], "a\"//b");
+    }
+
+    #[test]
+    fn test_map_to_container() {
+        let mount = |source: &str, destination: &str| Mount { source: source.to_string(), destination: destination.to_string() };
+        let mounts = vec![mount("/home/dev", "/home/dev"), mount("/home/dev/src/app", "/workspaces/app")];
+
+        assert_eq!(map_to_container(Path::new("/home/dev/src/app"), &mounts).as_deref(), Some("/workspaces/app"));
+        assert_eq!(map_to_container(Path::new("/home/dev/src/app/web"), &mounts).as_deref(), Some("/workspaces/app/web"));
+        assert_eq!(map_to_container(Path::new("/home/dev/other"), &mounts).as_deref(), Some("/home/dev/other"));
+        assert_eq!(map_to_container(Path::new("/srv/app"), &mounts), None);
+    }
+}
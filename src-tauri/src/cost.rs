@@ -0,0 +1,313 @@
+// Cost Tracking
+// Estimates agent spend from the token usage Claude Code records in its session files
+
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+use std::time::SystemTime;
+
+use crate::plugins::claude_sessions;
+
+/// Event emitted when a project's spend passes one of its budgets
+pub const BUDGET_EXCEEDED_EVENT: &str = "cost-budget-exceeded";
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// Token usage of one model response
+#[derive(Debug, Clone, PartialEq)]
+pub struct UsageEntry {
+    pub timestamp: i64,
+    pub model: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+}
+
+/// USD per million tokens for input and output
+///
+/// Cache writes cost 1.25x input and cache reads 0.1x input. Models are
+/// matched by name fragment, most specific first; unknown models cost nothing.
+const MODEL_PRICES: &[(&str, f64, f64)] = &[
+    ("opus-4-5", 5.0, 25.0),
+    ("opus", 15.0, 75.0),
+    ("sonnet", 3.0, 15.0),
+    ("haiku-4", 1.0, 5.0),
+    ("haiku-3-5", 0.8, 4.0),
+    ("haiku", 0.25, 1.25),
+];
+
+impl UsageEntry {
+    /// Estimated cost in USD
+    pub fn cost_usd(&self) -> f64 {
+        let Some(&(_, input, output)) = MODEL_PRICES.iter().find(|(name, _, _)| self.model.contains(name)) else {
+            return 0.0;
+        };
+        let tokens = self.input_tokens as f64 * input
+            + self.output_tokens as f64 * output
+            + self.cache_creation_tokens as f64 * input * 1.25
+            + self.cache_read_tokens as f64 * input * 0.1;
+        tokens / 1_000_000.0
+    }
+}
+
+/// Parse a session record into its usage, keyed by message id
+///
+/// One response is split across several records that repeat the same usage,
+/// so callers should count each message id once.
+fn parse_usage(line: &str) -> Option<(String, UsageEntry)> {
+    let record: Value = serde_json::from_str(line).ok()?;
+    if record.get("type").and_then(Value::as_str) != Some("assistant") {
+        return None;
+    }
+    let message = record.get("message")?;
+    let usage = message.get("usage")?;
+    let count = |key: &str| usage.get(key).and_then(Value::as_u64).unwrap_or(0);
+
+    let timestamp = record
+        .get("timestamp")
+        .and_then(Value::as_str)
+        .and_then(|ts| chrono::DateTime::parse_from_rfc3339(ts).ok())?
+        .timestamp();
+    let id = message
+        .get("id")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("{}-{}", timestamp, line.len()));
+
+    Some((
+        id,
+        UsageEntry {
+            timestamp,
+            model: message.get("model").and_then(Value::as_str).unwrap_or("unknown").to_string(),
+            input_tokens: count("input_tokens"),
+            output_tokens: count("output_tokens"),
+            cache_creation_tokens: count("cache_creation_input_tokens"),
+            cache_read_tokens: count("cache_read_input_tokens"),
+        },
+    ))
+}
+
+/// Read the usage recorded since `since` in a project's Claude Code sessions
+pub fn read_project_usage(project_path: &str, since: i64) -> Vec<UsageEntry> {
+    let Some(dir) = claude_sessions::projects_dir().map(|d| d.join(claude_sessions::project_dir_name(project_path))) else {
+        return Vec::new();
+    };
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+
+    let mut seen = HashSet::new();
+    let mut usage = Vec::new();
+    for path in entries.flatten().map(|e| e.path()) {
+        if path.extension().is_none_or(|ext| ext != "jsonl") || modified_before(&path, since) {
+            continue;
+        }
+        let Ok(contents) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        for (id, entry) in contents.lines().filter_map(parse_usage) {
+            if entry.timestamp >= since && seen.insert(id) {
+                usage.push(entry);
+            }
+        }
+    }
+    usage
+}
+
+fn modified_before(path: &Path, since: i64) -> bool {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+        .is_some_and(|d| (d.as_secs() as i64) < since)
+}
+
+/// Spend and tokens for one agent, model or project
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct CostBreakdown {
+    pub key: String,
+    pub cost_usd: f64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+/// Spend on one UTC day
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct DailyCost {
+    pub day_start: i64,
+    pub cost_usd: f64,
+}
+
+/// A budget a project has gone over
+#[derive(Debug, Clone, Serialize)]
+pub struct BudgetAlert {
+    pub project_id: String,
+    pub project_name: String,
+    /// "daily" or "monthly"
+    pub period: String,
+    pub spent_usd: f64,
+    pub budget_usd: f64,
+}
+
+/// Estimated spend over a period
+#[derive(Debug, Clone, Serialize)]
+pub struct CostSummary {
+    pub period: String,
+    pub since: i64,
+    pub total_usd: f64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub by_project: Vec<CostBreakdown>,
+    pub by_agent: Vec<CostBreakdown>,
+    pub by_model: Vec<CostBreakdown>,
+    pub by_day: Vec<DailyCost>,
+    pub budget_alerts: Vec<BudgetAlert>,
+}
+
+/// Start of a summary period: "day" (UTC today), "week" (last 7 days),
+/// "month" (UTC calendar month) or "all"
+pub fn period_start(period: &str, now: i64) -> Result<i64, String> {
+    let today = now - now.rem_euclid(SECONDS_PER_DAY);
+    match period {
+        "day" => Ok(today),
+        "week" => Ok(today - 6 * SECONDS_PER_DAY),
+        "month" => Ok(month_start(now)),
+        "all" => Ok(0),
+        other => Err(format!("Unknown period '{}'; use day, week, month or all", other)),
+    }
+}
+
+/// Start of the UTC calendar month containing `now`
+pub fn month_start(now: i64) -> i64 {
+    use chrono::Datelike;
+    chrono::DateTime::from_timestamp(now, 0)
+        .and_then(|dt| dt.date_naive().with_day(1))
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|dt| dt.and_utc().timestamp())
+        .unwrap_or(0)
+}
+
+/// Usage of one project, tagged for aggregation
+pub struct ProjectUsage<'a> {
+    pub project_name: &'a str,
+    pub agent_type: &'a str,
+    pub entries: &'a [UsageEntry],
+}
+
+/// Aggregate usage at or after `since` into a summary, without budget alerts
+pub fn summarize(period: &str, since: i64, projects: &[ProjectUsage]) -> CostSummary {
+    let mut by_project: BTreeMap<&str, CostBreakdown> = BTreeMap::new();
+    let mut by_agent: BTreeMap<&str, CostBreakdown> = BTreeMap::new();
+    let mut by_model: BTreeMap<&str, CostBreakdown> = BTreeMap::new();
+    let mut by_day: BTreeMap<i64, f64> = BTreeMap::new();
+
+    for project in projects {
+        for entry in project.entries.iter().filter(|e| e.timestamp >= since) {
+            let cost = entry.cost_usd();
+            for (map, key) in [
+                (&mut by_project, project.project_name),
+                (&mut by_agent, project.agent_type),
+                (&mut by_model, entry.model.as_str()),
+            ] {
+                let breakdown = map.entry(key).or_insert_with(|| CostBreakdown {
+                    key: key.to_string(),
+                    ..Default::default()
+                });
+                breakdown.cost_usd += cost;
+                breakdown.input_tokens += entry.input_tokens + entry.cache_creation_tokens + entry.cache_read_tokens;
+                breakdown.output_tokens += entry.output_tokens;
+            }
+            *by_day.entry(entry.timestamp - entry.timestamp.rem_euclid(SECONDS_PER_DAY)).or_default() += cost;
+        }
+    }
+
+    let sorted = |map: BTreeMap<&str, CostBreakdown>| {
+        let mut values: Vec<CostBreakdown> = map.into_values().collect();
+        values.sort_by(|a, b| b.cost_usd.total_cmp(&a.cost_usd));
+        values
+    };
+    let by_project = sorted(by_project);
+
+    CostSummary {
+        period: period.to_string(),
+        since,
+        total_usd: by_project.iter().map(|b| b.cost_usd).sum(),
+        input_tokens: by_project.iter().map(|b| b.input_tokens).sum(),
+        output_tokens: by_project.iter().map(|b| b.output_tokens).sum(),
+        by_project,
+        by_agent: sorted(by_agent),
+        by_model: sorted(by_model),
+        by_day: by_day
+            .into_iter()
+            .map(|(day_start, cost_usd)| DailyCost { day_start, cost_usd })
+            .collect(),
+        budget_alerts: Vec::new(),
+    }
+}
+
+/// Total spend of entries at or after `since`
+pub fn spend_since(entries: &[UsageEntry], since: i64) -> f64 {
+    entries.iter().filter(|e| e.timestamp >= since).map(UsageEntry::cost_usd).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(timestamp: i64, model: &str) -> UsageEntry {
+        UsageEntry {
+            timestamp,
+            model: model.to_string(),
+            input_tokens: 1_000_000,
+            output_tokens: 100_000,
+            cache_creation_tokens: 0,
+            cache_read_tokens: 1_000_000,
+        }
+    }
+
+    #[test]
+    fn test_parse_usage() {
+        let line = r#"{"type":"assistant","timestamp":"2025-01-02T03:04:05Z","message":{"id":"msg_1","model":"claude-sonnet-4","usage":{"input_tokens":10,"output_tokens":20,"cache_read_input_tokens":30}}}"#;
+        let (id, usage) = parse_usage(line).unwrap();
+        assert_eq!(id, "msg_1");
+        assert_eq!(usage.model, "claude-sonnet-4");
+        assert_eq!((usage.input_tokens, usage.output_tokens, usage.cache_read_tokens), (10, 20, 30));
+        assert!(parse_usage(r#"{"type":"user","message":{"usage":{}}}"#).is_none());
+    }
+
+    #[test]
+    fn test_cost_usd() {
+        // 1M input at $3, 100k output at $15/M, 1M cache reads at $0.30/M
+        assert!((entry(0, "claude-sonnet-4").cost_usd() - 4.8).abs() < 1e-9);
+        assert_eq!(entry(0, "some-local-model").cost_usd(), 0.0);
+    }
+
+    #[test]
+    fn test_summarize() {
+        let day = SECONDS_PER_DAY;
+        let entries = [entry(day + 5, "claude-sonnet-4"), entry(2 * day, "claude-opus-4"), entry(10, "claude-sonnet-4")];
+        let projects = [ProjectUsage {
+            project_name: "Atelier",
+            agent_type: "claude-code",
+            entries: &entries,
+        }];
+        let summary = summarize("all", day, &projects);
+
+        assert_eq!(summary.by_model.len(), 2);
+        assert_eq!(summary.by_model[0].key, "claude-opus-4");
+        assert_eq!(summary.by_day.iter().map(|d| d.day_start).collect::<Vec<_>>(), [day, 2 * day]);
+        assert!((summary.total_usd - spend_since(&entries, day)).abs() < 1e-9);
+        assert_eq!(summary.by_agent[0].input_tokens, 4_000_000);
+    }
+
+    #[test]
+    fn test_period_start() {
+        // 2025-03-15T12:00:00Z
+        let now = 1_742_040_000;
+        assert_eq!(period_start("day", now).unwrap(), 1_741_996_800);
+        assert_eq!(period_start("month", now).unwrap(), 1_740_787_200);
+        assert!(period_start("year", now).is_err());
+    }
+}
@@ -1,23 +1,42 @@
 use anyhow::{Context, Result};
 use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
-use std::path::PathBuf;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use tauri::{AppHandle, Manager};
 
+use crate::app_settings::AppSettingsManager;
+
 // Embed migrations using Refinery
 mod embedded {
     use refinery::embed_migrations;
     embed_migrations!("migrations");
 }
 
+/// How long a read-only connection is kept before it's reopened, so copies
+/// synced in since show up
+const READ_ONLY_REFRESH: Duration = Duration::from_secs(10);
+
 /// Database connection pool
 #[derive(Clone)]
 pub struct Database {
     pub pool: SqlitePool,
+    /// Set when another machine's database was opened read-only
+    read_only_path: Option<PathBuf>,
 }
 
 impl Database {
-    /// Initialize database connection and run migrations
+    /// Initialize database connection and run migrations, or open the database
+    /// picked in settings read-only, falling back to this machine's one when
+    /// it can't be opened
     pub async fn init(app: &AppHandle) -> Result<Self> {
+        if let Some(path) = app.state::<AppSettingsManager>().get().read_only_database {
+            match Self::open_read_only(Path::new(&path)).await {
+                Ok(db) => return Ok(db),
+                Err(e) => log::error!("Failed to open {} read-only, using this machine's database: {:#}", path, e),
+            }
+        }
+
         let db_path = get_database_path(app)?;
 
         log::info!("Initializing database at: {}", db_path.display());
@@ -32,16 +51,7 @@ impl Database {
         log::info!("Running database migrations with Refinery...");
         let mut conn = rusqlite::Connection::open(&db_path)
             .context("Failed to open database for migrations")?;
-
-        match embedded::migrations::runner().run(&mut conn) {
-            Ok(report) => {
-                log::info!("Migrations applied successfully. Applied migrations: {:?}", report.applied_migrations());
-            }
-            Err(e) => {
-                log::error!("Migration failed: {:?}", e);
-                return Err(anyhow::anyhow!("Failed to run migrations: {}", e));
-            }
-        }
+        run_migrations(&mut conn)?;
 
         // Close rusqlite connection
         drop(conn);
@@ -62,13 +72,84 @@ impl Database {
 
         log::info!("Database initialized successfully");
 
-        Ok(Self { pool })
+        Ok(Self { pool, read_only_path: None })
+    }
+
+    /// Open a database another machine writes to, e.g. one synced here by
+    /// Syncthing or Dropbox, without ever writing to it
+    ///
+    /// The connection is immutable: SQLite takes no locks and creates no
+    /// journal or WAL files next to it, so the sync tool only ever sees the
+    /// other machine's changes. Connections are reopened every few seconds to
+    /// pick up newly synced copies; writes still in the other machine's WAL
+    /// show up once it checkpoints them.
+    pub async fn open_read_only(path: &Path) -> Result<Self> {
+        if !path.is_file() {
+            anyhow::bail!("{} is not a file", path.display());
+        }
+        log::info!("Opening database read-only at: {}", path.display());
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(5)
+            .max_lifetime(READ_ONLY_REFRESH)
+            .idle_timeout(READ_ONLY_REFRESH)
+            .connect_with(SqliteConnectOptions::new().filename(path).read_only(true).immutable(true))
+            .await
+            .context("Failed to connect to database")?;
+
+        let version: Option<i64> = sqlx::query_scalar("SELECT MAX(version) FROM refinery_schema_history")
+            .fetch_one(&pool)
+            .await
+            .context("Not an AtelierCode database")?;
+        let version = version.unwrap_or(0);
+        let latest = latest_migration();
+        if version < latest {
+            log::warn!(
+                "{} is at schema version {} and this app expects {}; some views may fail until it's updated",
+                path.display(),
+                version,
+                latest
+            );
+        } else if version > latest {
+            log::warn!("{} is at schema version {}, newer than this app's {}", path.display(), version, latest);
+        }
+
+        Ok(Self { pool, read_only_path: Some(path.to_path_buf()) })
     }
 
     /// Get a reference to the connection pool
     pub fn pool(&self) -> &SqlitePool {
         &self.pool
     }
+
+    /// The other machine's database, when opened read-only
+    pub fn read_only_path(&self) -> Option<&Path> {
+        self.read_only_path.as_deref()
+    }
+}
+
+/// Apply any pending migrations
+pub(crate) fn run_migrations(conn: &mut rusqlite::Connection) -> Result<()> {
+    match embedded::migrations::runner().run(conn) {
+        Ok(report) => {
+            log::info!("Migrations applied successfully. Applied migrations: {:?}", report.applied_migrations());
+            Ok(())
+        }
+        Err(e) => {
+            log::error!("Migration failed: {:?}", e);
+            Err(anyhow::anyhow!("Failed to run migrations: {}", e))
+        }
+    }
+}
+
+/// Version of the newest embedded migration
+fn latest_migration() -> i64 {
+    embedded::migrations::runner()
+        .get_migrations()
+        .iter()
+        .map(|migration| i64::from(migration.version()))
+        .max()
+        .unwrap_or(0)
 }
 
 /// Get the database file path based on the platform
@@ -86,6 +167,80 @@ fn get_database_path(app: &AppHandle) -> Result<PathBuf> {
     Ok(db_path)
 }
 
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+use tauri::State;
+
+use crate::error::AppError;
+
+/// Which database the app has open
+#[derive(Debug, Clone, Serialize)]
+pub struct DatabaseMode {
+    pub read_only: bool,
+    /// The other machine's database, when read-only
+    pub path: Option<String>,
+}
+
+/// Get whether the app is showing another machine's database read-only
+#[tauri::command]
+pub fn get_database_mode(db: State<'_, Database>) -> DatabaseMode {
+    DatabaseMode {
+        read_only: db.read_only_path().is_some(),
+        path: db.read_only_path().map(|path| path.display().to_string()),
+    }
+}
+
+/// Restart showing another machine's synced database, read-only
+#[tauri::command]
+pub async fn open_read_only_database(
+    app: AppHandle,
+    settings_manager: State<'_, AppSettingsManager>,
+    path: String,
+) -> Result<(), AppError> {
+    // Check it opens now rather than silently falling back after the restart
+    let db = Database::open_read_only(Path::new(&path))
+        .await
+        .map_err(|e| AppError::invalid_input(format!("Can't open {} read-only: {:#}", path, e)))?;
+    db.pool.close().await;
+
+    settings_manager
+        .update(|settings| settings.read_only_database = Some(path))
+        .map_err(AppError::context("Failed to save settings"))?;
+    log::info!("Restarting to open the database read-only");
+    app.restart();
+}
+
+/// Restart back on this machine's own database
+#[tauri::command]
+pub fn close_read_only_database(
+    app: AppHandle,
+    settings_manager: State<'_, AppSettingsManager>,
+) -> Result<(), AppError> {
+    settings_manager
+        .update(|settings| settings.read_only_database = None)
+        .map_err(AppError::context("Failed to save settings"))?;
+    log::info!("Restarting to open this machine's database");
+    app.restart();
+}
+
+/// A migrated database in a temp folder, opened the way `Database::init`
+/// opens the app's; the folder is removed when the returned guard drops
+#[cfg(test)]
+pub(crate) async fn test_pool() -> (SqlitePool, tempfile::TempDir) {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("ateliercode.db");
+    let mut conn = rusqlite::Connection::open(&path).unwrap();
+    run_migrations(&mut conn).unwrap();
+    drop(conn);
+    let pool = SqlitePoolOptions::new()
+        .connect_with(SqliteConnectOptions::new().filename(&path).foreign_keys(true))
+        .await
+        .unwrap();
+    (pool, dir)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -95,4 +250,29 @@ mod tests {
         // Test would require a Tauri app instance
         // This is a placeholder for integration tests
     }
+
+    #[tokio::test]
+    async fn test_open_read_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("read-only.db");
+        let mut conn = rusqlite::Connection::open(&path).unwrap();
+        run_migrations(&mut conn).unwrap();
+        conn.execute("INSERT INTO settings (key, value) VALUES ('theme', 'dark')", []).unwrap();
+        drop(conn);
+
+        let db = Database::open_read_only(&path).await.unwrap();
+        assert_eq!(db.read_only_path(), Some(path.as_path()));
+        let value: String = sqlx::query_scalar("SELECT value FROM settings WHERE key = 'theme'")
+            .fetch_one(db.pool())
+            .await
+            .unwrap();
+        assert_eq!(value, "dark");
+        assert!(sqlx::query("DELETE FROM settings").execute(db.pool()).await.is_err());
+
+        // Files that aren't AtelierCode databases are refused
+        let other = dir.path().join("other.db");
+        rusqlite::Connection::open(&other).unwrap().execute("CREATE TABLE t (x)", []).unwrap();
+        assert!(Database::open_read_only(&other).await.is_err());
+        assert!(Database::open_read_only(&dir.path().join("missing.db")).await.is_err());
+    }
 }
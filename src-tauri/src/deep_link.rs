@@ -0,0 +1,182 @@
+// Deep Links
+// Handles ateliercode:// URLs that open a project, jump to a session or prefill an agent prompt
+
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, Url};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+pub const SCHEME: &str = "ateliercode";
+
+/// Event emitted with a [`DeepLink`] when a link is opened; the frontend
+/// drains them with `take_pending_deep_links`
+pub const DEEP_LINK_EVENT: &str = "deep-link";
+
+/// Where an `ateliercode://` URL points
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum DeepLink {
+    /// `ateliercode://project/<id>`
+    OpenProject { project_id: String },
+    /// `ateliercode://project/<id>/session/<session_id>`
+    OpenSession { project_id: String, session_id: String },
+    /// `ateliercode://project/<id>/new-agent?prompt=...&agent=...`
+    ///
+    /// The prompt is only put in the composer, never sent.
+    NewAgent {
+        project_id: String,
+        prompt: Option<String>,
+        agent_type: Option<String>,
+    },
+}
+
+impl DeepLink {
+    pub fn parse(url: &Url) -> Result<Self, String> {
+        if url.scheme() != SCHEME {
+            return Err(format!("Not an {}:// link: {}", SCHEME, url));
+        }
+        if url.host_str() != Some("project") {
+            return Err(format!("Unknown deep link: {}", url));
+        }
+
+        let segments: Vec<&str> = url
+            .path_segments()
+            .map(|segments| segments.filter(|s| !s.is_empty()).collect())
+            .unwrap_or_default();
+        let query = |key: &str| {
+            url.query_pairs()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.into_owned())
+                .filter(|v| !v.trim().is_empty())
+        };
+
+        match segments.as_slice() {
+            [project_id] => Ok(Self::OpenProject {
+                project_id: project_id.to_string(),
+            }),
+            [project_id, "session", session_id] => Ok(Self::OpenSession {
+                project_id: project_id.to_string(),
+                session_id: session_id.to_string(),
+            }),
+            [project_id, "new-agent"] => Ok(Self::NewAgent {
+                project_id: project_id.to_string(),
+                prompt: query("prompt"),
+                agent_type: query("agent"),
+            }),
+            _ => Err(format!("Unknown deep link: {}", url)),
+        }
+    }
+}
+
+/// Links opened before the frontend picked them up
+#[derive(Default)]
+pub struct PendingDeepLinks(Mutex<Vec<DeepLink>>);
+
+/// Queue the links in `urls`, bring the window forward and tell the frontend
+pub fn handle_urls(app: &AppHandle, urls: Vec<Url>) {
+    for url in urls {
+        let link = match DeepLink::parse(&url) {
+            Ok(link) => link,
+            Err(e) => {
+                log::warn!("Ignoring deep link: {}", e);
+                continue;
+            }
+        };
+        log::info!("Opening deep link {}", url);
+        app.state::<PendingDeepLinks>().0.lock().unwrap().push(link.clone());
+        // Only the main window navigates; project windows stay on their project
+        if let Err(e) = app.emit_to(crate::windows::MAIN_WINDOW, DEEP_LINK_EVENT, &link) {
+            log::warn!("Failed to emit {}: {}", DEEP_LINK_EVENT, e);
+        }
+    }
+    crate::tray::show_main_window(app);
+}
+
+/// Register the URL scheme and handle links opened now or at launch
+pub fn init(app: &AppHandle) {
+    app.manage(PendingDeepLinks::default());
+
+    // macOS registers schemes from the bundle; elsewhere make sure the
+    // current executable handles them, e.g. for dev builds and AppImages
+    #[cfg(any(windows, target_os = "linux"))]
+    if let Err(e) = app.deep_link().register_all() {
+        log::warn!("Failed to register the {}:// scheme: {}", SCHEME, e);
+    }
+
+    let handle = app.clone();
+    app.deep_link().on_open_url(move |event| handle_urls(&handle, event.urls()));
+
+    match app.deep_link().get_current() {
+        Ok(Some(urls)) => handle_urls(app, urls),
+        Ok(None) => {}
+        Err(e) => log::warn!("Failed to read the launch deep link: {}", e),
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+use tauri::State;
+
+/// Take the deep links that haven't been handled yet, oldest first
+#[tauri::command]
+pub async fn take_pending_deep_links(pending: State<'_, PendingDeepLinks>) -> Result<Vec<DeepLink>, String> {
+    Ok(std::mem::take(&mut *pending.0.lock().unwrap()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(url: &str) -> Result<DeepLink, String> {
+        DeepLink::parse(&Url::parse(url).unwrap())
+    }
+
+    #[test]
+    fn test_parse_project_and_session() {
+        assert_eq!(
+            parse("ateliercode://project/abc").unwrap(),
+            DeepLink::OpenProject { project_id: "abc".to_string() }
+        );
+        assert_eq!(
+            parse("ateliercode://project/abc/").unwrap(),
+            DeepLink::OpenProject { project_id: "abc".to_string() }
+        );
+        assert_eq!(
+            parse("ateliercode://project/abc/session/s-1").unwrap(),
+            DeepLink::OpenSession {
+                project_id: "abc".to_string(),
+                session_id: "s-1".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_new_agent() {
+        assert_eq!(
+            parse("ateliercode://project/abc/new-agent?prompt=Fix%20the%20build&agent=claude-code").unwrap(),
+            DeepLink::NewAgent {
+                project_id: "abc".to_string(),
+                prompt: Some("Fix the build".to_string()),
+                agent_type: Some("claude-code".to_string()),
+            }
+        );
+        assert_eq!(
+            parse("ateliercode://project/abc/new-agent?prompt=").unwrap(),
+            DeepLink::NewAgent {
+                project_id: "abc".to_string(),
+                prompt: None,
+                agent_type: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_unknown_links() {
+        assert!(parse("https://project/abc").is_err());
+        assert!(parse("ateliercode://settings").is_err());
+        assert!(parse("ateliercode://project").is_err());
+        assert!(parse("ateliercode://project/abc/tasks/1").is_err());
+    }
+}
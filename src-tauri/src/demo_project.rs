@@ -0,0 +1,262 @@
+// Demo Project
+// Scaffolds a small sample repository with tasks, a PRD, a pending change to
+// review and a chat with the built-in demo agent, for exploring the app
+// before setting up a real agent CLI
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+use crate::models::{ChatTab, Project, Task};
+use crate::plugins::demo;
+
+const PROJECT_NAME: &str = "Tiny Todo (Demo)";
+
+const README: &str = "# Tiny Todo
+
+A todo list for the terminal, kept in `todos.json`.
+
+```sh
+python todo.py add Buy milk
+python todo.py
+```
+
+This is AtelierCode's sample project. Its agent is scripted, so feel free to
+approve, reject or rewrite anything here.
+";
+
+const GITIGNORE: &str = "todos.json\n__pycache__/\n";
+
+const TODO_PY: &str = r#""""A tiny todo list kept in todos.json"""
+import json
+import sys
+from pathlib import Path
+
+STORE = Path(__file__).with_name("todos.json")
+
+
+def load():
+    return json.loads(STORE.read_text()) if STORE.exists() else []
+
+
+def save(todos):
+    STORE.write_text(json.dumps(todos, indent=2))
+
+
+def add(todos, title):
+    todos.append({"title": title, "done": False})
+
+
+def main(argv):
+    todos = load()
+    if argv[:1] == ["add"]:
+        add(todos, " ".join(argv[1:]))
+        save(todos)
+    for number, todo in enumerate(todos, 1):
+        mark = "x" if todo["done"] else " "
+        print(f"{number}. [{mark}] {todo['title']}")
+
+
+if __name__ == "__main__":
+    main(sys.argv[1:])
+"#;
+
+const TEST_TODO_PY: &str = r#"import unittest
+
+import todo
+
+
+class TodoTest(unittest.TestCase):
+    def test_add(self):
+        todos = []
+        todo.add(todos, "Buy milk")
+        self.assertEqual(todos, [{"title": "Buy milk", "done": False}])
+
+    def test_add_keeps_order(self):
+        todos = []
+        todo.add(todos, "First")
+        todo.add(todos, "Second")
+        self.assertEqual([t["title"] for t in todos], ["First", "Second"])
+
+
+if __name__ == "__main__":
+    unittest.main()
+"#;
+
+const PRD: &str = "# Tiny Todo
+
+## Goal
+A fast, dependency-free todo list for the terminal.
+
+## Features
+- Add todos: `todo.py add <title>`
+- List todos with their state: `todo.py`
+- Mark a todo as done: `todo.py done <number>`
+- Delete a todo: `todo.py delete <number>`
+- Show only open todos: `todo.py --open`
+
+## Constraints
+- Python standard library only
+- Every command has a unit test
+";
+
+/// Seeded tasks: title, description, priority, status
+const TASKS: &[(&str, &str, &str, &str)] = &[
+    ("Write the README", "Explain how to add and list todos.", "low", "completed"),
+    (
+        "Mark todos as done",
+        "Add `todo.py done <number>` with a test. The demo agent has made this change; review it in Changes.",
+        "high",
+        "in_progress",
+    ),
+    ("Delete a todo", "Add `todo.py delete <number>`, renumbering the rest.", "medium", "todo"),
+    ("Show only open todos", "Add an `--open` flag that hides finished todos.", "low", "todo"),
+];
+
+/// What the demo agent changed, left uncommitted for review
+fn agent_change() -> [(&'static str, String); 2] {
+    let todo_py = TODO_PY
+        .replace(
+            "\n\ndef main(argv):",
+            "\n\ndef done(todos, number):\n    todos[number - 1][\"done\"] = True\n\n\ndef main(argv):",
+        )
+        .replace(
+            "        save(todos)\n    for",
+            "        save(todos)\n    elif argv[:1] == [\"done\"]:\n        done(todos, int(argv[1]))\n        save(todos)\n    for",
+        );
+    let test_todo_py = TEST_TODO_PY.replace(
+        "\n\nif __name__",
+        "\n\n    def test_done(self):\n        todos = []\n        todo.add(todos, \"Buy milk\")\n        todo.done(todos, 1)\n        self.assertTrue(todos[0][\"done\"])\n\n\nif __name__",
+    );
+    [("todo.py", todo_py), ("test_todo.py", test_todo_py)]
+}
+
+/// A folder for the demo under `parent` that doesn't exist yet
+fn unused_dir(parent: &Path) -> PathBuf {
+    let base = parent.join("AtelierCode Demo");
+    std::iter::once(base.clone())
+        .chain((2..).map(|n| parent.join(format!("AtelierCode Demo {}", n))))
+        .find(|dir| !dir.exists())
+        .unwrap_or(base)
+}
+
+async fn git(root: &Path, args: &[&str]) -> Result<()> {
+    let output = crate::cli_command::command("git", args)
+        .current_dir(root)
+        .output()
+        .await
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        anyhow::bail!("git {} failed: {}", args.join(" "), String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(())
+}
+
+/// Write the sample files and commit them, then apply the agent's change on top
+///
+/// Without git the change is written all the same, it just can't be diffed.
+async fn scaffold(root: &Path) -> Result<()> {
+    std::fs::create_dir_all(root).with_context(|| format!("Failed to create {}", root.display()))?;
+    for (name, contents) in [("README.md", README), (".gitignore", GITIGNORE), ("todo.py", TODO_PY), ("test_todo.py", TEST_TODO_PY)] {
+        std::fs::write(root.join(name), contents).with_context(|| format!("Failed to write {}", name))?;
+    }
+
+    let committed = async {
+        git(root, &["init", "-q"]).await?;
+        git(root, &["add", "-A"]).await?;
+        git(
+            root,
+            &["-c", "user.name=AtelierCode", "-c", "user.email=demo@ateliercode.local", "commit", "-q", "-m", "Add todo list with add and list"],
+        )
+        .await
+    }
+    .await;
+    if let Err(e) = committed {
+        log::warn!("Demo project has no git history: {:#}", e);
+    }
+
+    for (name, contents) in agent_change() {
+        std::fs::write(root.join(name), contents).with_context(|| format!("Failed to write {}", name))?;
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+use crate::db::Database;
+use tauri::State;
+
+/// Create the sample project in the documents folder, or `parent_dir`
+///
+/// The project uses the demo agent, whose chat already holds the conversation
+/// behind the pending change, so review, tasks and chat all have content.
+#[tauri::command]
+pub async fn create_demo_project(db: State<'_, Database>, parent_dir: Option<String>) -> Result<Project, String> {
+    let parent = parent_dir
+        .map(PathBuf::from)
+        .or_else(dirs::document_dir)
+        .or_else(dirs::home_dir)
+        .ok_or("Could not find a folder for the demo project")?;
+    let root = unused_dir(&parent);
+    log::info!("Creating demo project in {}", root.display());
+
+    scaffold(&root).await.map_err(|e| format!("Failed to create demo files: {:#}", e))?;
+    let root_path = root.to_string_lossy().to_string();
+
+    let mut project = Project::new(PROJECT_NAME.to_string(), root_path.clone(), demo::PLUGIN_NAME.to_string());
+    project.prd_content = Some(PRD.to_string());
+    crate::commands::insert_project(db.pool(), &project).await?;
+
+    let now = chrono::Utc::now().timestamp();
+    for (position, (title, description, priority, status)) in TASKS.iter().enumerate() {
+        let mut task = Task::new(project.id.clone(), title.to_string(), priority.to_string());
+        task.description = Some(description.to_string());
+        task.status = status.to_string();
+        task.position = position as i64;
+        if *status != "todo" {
+            task.started_at = Some(now);
+        }
+        if *status == "completed" {
+            task.completed_at = Some(now);
+        }
+        crate::commands::insert_task(db.pool(), &task).await?;
+    }
+
+    // The conversation ends a few minutes ago, when the change was made
+    let cli_session_id = uuid::Uuid::new_v4().to_string();
+    demo::seed_session(&cli_session_id, &root_path, now - 300)
+        .map_err(|e| format!("Failed to write demo conversation: {:#}", e))?;
+    let mut tab = ChatTab::new(project.id.clone(), demo::PLUGIN_NAME.to_string(), 0);
+    tab.cli_session_id = Some(cli_session_id);
+    tab.label = Some("Demo chat".to_string());
+    tab.is_active = true;
+    crate::commands::insert_chat_tab(db.pool(), &tab)
+        .await
+        .map_err(|e| format!("Failed to create demo chat tab: {}", e))?;
+
+    for (name, _) in agent_change() {
+        if let Err(e) =
+            crate::file_watcher::record_file_change(db.pool(), &project.id, "demo", &root_path, &root.join(name), "modified").await
+        {
+            log::warn!("Failed to record demo change to {}: {:#}", name, e);
+        }
+    }
+
+    log::info!("Demo project created: {}", project.id);
+    Ok(project)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_agent_change_applies() {
+        let [(_, todo_py), (_, test_todo_py)] = agent_change();
+        assert!(todo_py.contains("def done(todos, number):"));
+        assert!(todo_py.contains("elif argv[:1] == [\"done\"]:"));
+        assert!(test_todo_py.contains("def test_done(self):"));
+        assert!(test_todo_py.ends_with("unittest.main()\n"));
+    }
+}
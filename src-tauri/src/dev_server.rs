@@ -0,0 +1,395 @@
+// Dev Servers
+// Runs a project's dev server, finds the port it binds from its output, and
+// stops it again when asked or when the window that started it closes
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::{Arc, Mutex, OnceLock};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::sync::oneshot;
+
+/// Event emitted with a server's info whenever its status or URL changes
+pub const DEV_SERVER_UPDATED_EVENT: &str = "dev-server-updated";
+/// Event emitted with each line a dev server prints
+pub const DEV_SERVER_OUTPUT_EVENT: &str = "dev-server-output";
+
+/// Lines of output kept per server
+const LOG_LINES: usize = 500;
+
+/// The command that starts a dev server, e.g. a script from `list_scripts`
+#[derive(Debug, Clone, Deserialize)]
+pub struct RunConfig {
+    pub program: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DevServerInfo {
+    pub project_id: String,
+    pub command: String,
+    pub pid: Option<u32>,
+    /// starting until a port shows up in the output, then running; stopped
+    /// when stopped from here and exited when it ended by itself
+    pub status: String,
+    pub port: Option<u16>,
+    /// Where the running app can be previewed
+    pub url: Option<String>,
+    pub exit_code: Option<i32>,
+    pub started_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DevServerOutput {
+    pub project_id: String,
+    pub line: String,
+}
+
+/// Something the frontend should hear about a server
+pub enum DevServerEvent<'a> {
+    Updated(&'a DevServerInfo),
+    Output(&'a DevServerOutput),
+}
+
+struct DevServer {
+    /// Tells this run apart from a later one of the same project
+    run_id: String,
+    info: DevServerInfo,
+    /// Label of the window that started it
+    holder: Option<String>,
+    stop: Option<oneshot::Sender<()>>,
+    log: VecDeque<String>,
+}
+
+/// The project's dev servers, one per project; ended servers are kept so their
+/// output can still be read
+#[derive(Clone)]
+pub struct DevServerManager {
+    servers: Arc<Mutex<HashMap<String, DevServer>>>,
+    on_event: Arc<dyn Fn(DevServerEvent) + Send + Sync>,
+}
+
+impl DevServerManager {
+    pub fn new(on_event: impl Fn(DevServerEvent) + Send + Sync + 'static) -> Self {
+        Self { servers: Arc::new(Mutex::new(HashMap::new())), on_event: Arc::new(on_event) }
+    }
+
+    /// Start the project's dev server in `root`
+    ///
+    /// `holder` is the label of the window it belongs to, which stops it when
+    /// closed.
+    pub fn start(
+        &self,
+        project_id: &str,
+        root: &Path,
+        config: &RunConfig,
+        holder: Option<String>,
+    ) -> Result<DevServerInfo, String> {
+        let mut servers = self.servers.lock().unwrap();
+        if servers.get(project_id).is_some_and(|server| server.stop.is_some()) {
+            return Err("A dev server is already running for this project".to_string());
+        }
+
+        let program = which::which(&config.program).map_err(|_| format!("{} isn't installed or not on PATH", config.program))?;
+        let mut cmd = tokio::process::Command::new(&program);
+        cmd.args(&config.args)
+            .envs(&config.env)
+            .current_dir(root)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        // Its own process group, so stopping it also stops the watchers and
+        // bundlers it starts
+        #[cfg(unix)]
+        cmd.process_group(0);
+        let mut child = cmd.spawn().map_err(|e| format!("Failed to start dev server: {}", e))?;
+
+        let info = DevServerInfo {
+            project_id: project_id.to_string(),
+            command: std::iter::once(config.program.clone()).chain(config.args.iter().cloned()).collect::<Vec<_>>().join(" "),
+            pid: child.id(),
+            status: "starting".to_string(),
+            port: None,
+            url: None,
+            exit_code: None,
+            started_at: chrono::Utc::now().timestamp(),
+        };
+        let run_id = uuid::Uuid::new_v4().to_string();
+        let (stop, stopped) = oneshot::channel();
+        servers.insert(
+            project_id.to_string(),
+            DevServer { run_id: run_id.clone(), info: info.clone(), holder, stop: Some(stop), log: VecDeque::new() },
+        );
+        drop(servers);
+        log::info!("Started dev server for project {}: {}", project_id, info.command);
+        (self.on_event)(DevServerEvent::Updated(&info));
+
+        let readers = [
+            child.stdout.take().map(|out| self.forward(project_id, &run_id, out)),
+            child.stderr.take().map(|err| self.forward(project_id, &run_id, err)),
+        ];
+        let manager = self.clone();
+        let project_id = project_id.to_string();
+        let pid = child.id();
+        tokio::spawn(async move {
+            let (status, exit_code) = tokio::select! {
+                status = child.wait() => ("exited", status.ok().and_then(|s| s.code())),
+                _ = stopped => {
+                    crate::scripts::stop_process_group(pid);
+                    let _ = child.kill().await;
+                    ("stopped", None)
+                }
+            };
+            for reader in readers.into_iter().flatten() {
+                let _ = reader.await;
+            }
+            log::info!("Dev server for project {} {} with {:?}", project_id, status, exit_code);
+            manager.update(&project_id, &run_id, |info| {
+                info.status = status.to_string();
+                info.exit_code = exit_code;
+                info.pid = None;
+            });
+        });
+
+        Ok(info)
+    }
+
+    /// Stop the project's dev server; returns false if none was running
+    pub fn stop(&self, project_id: &str) -> bool {
+        let stop = self.servers.lock().unwrap().get_mut(project_id).and_then(|server| server.stop.take());
+        match stop {
+            Some(stop) => stop.send(()).is_ok(),
+            None => false,
+        }
+    }
+
+    /// Stop the servers a closed window started
+    pub fn stop_for_holder(&self, holder: &str) {
+        let stops: Vec<_> = self
+            .servers
+            .lock()
+            .unwrap()
+            .values_mut()
+            .filter(|server| server.holder.as_deref() == Some(holder))
+            .filter_map(|server| server.stop.take())
+            .collect();
+        if !stops.is_empty() {
+            log::info!("Stopping {} dev servers of closed window {}", stops.len(), holder);
+        }
+        for stop in stops {
+            let _ = stop.send(());
+        }
+    }
+
+    /// The project's server, running or last ended, with its recent output
+    pub fn get(&self, project_id: &str) -> Option<(DevServerInfo, Vec<String>)> {
+        self.servers
+            .lock()
+            .unwrap()
+            .get(project_id)
+            .map(|server| (server.info.clone(), server.log.iter().cloned().collect()))
+    }
+
+    fn update(&self, project_id: &str, run_id: &str, change: impl FnOnce(&mut DevServerInfo)) {
+        let info = {
+            let mut servers = self.servers.lock().unwrap();
+            let Some(server) = servers.get_mut(project_id).filter(|server| server.run_id == run_id) else { return };
+            change(&mut server.info);
+            if server.info.status != "starting" && server.info.status != "running" {
+                server.stop = None;
+            }
+            server.info.clone()
+        };
+        (self.on_event)(DevServerEvent::Updated(&info));
+    }
+
+    /// Emit and keep each line of `reader`, watching for the server's URL
+    fn forward(
+        &self,
+        project_id: &str,
+        run_id: &str,
+        reader: impl AsyncRead + Unpin + Send + 'static,
+    ) -> tokio::task::JoinHandle<()> {
+        let manager = self.clone();
+        let project_id = project_id.to_string();
+        let run_id = run_id.to_string();
+        tokio::spawn(async move {
+            let mut reader = BufReader::new(reader);
+            let mut buffer = Vec::new();
+            while reader.read_until(b'\n', &mut buffer).await.unwrap_or(0) > 0 {
+                let line = String::from_utf8_lossy(&strip_ansi_escapes::strip(&buffer)).trim_end().to_string();
+                buffer.clear();
+                let found = {
+                    let mut servers = manager.servers.lock().unwrap();
+                    let Some(server) = servers.get_mut(&project_id).filter(|server| server.run_id == run_id) else {
+                        continue;
+                    };
+                    if server.log.len() == LOG_LINES {
+                        server.log.pop_front();
+                    }
+                    server.log.push_back(line.clone());
+                    if server.info.port.is_none() { detect_url(&line) } else { None }
+                };
+                (manager.on_event)(DevServerEvent::Output(&DevServerOutput { project_id: project_id.clone(), line }));
+                if let Some((port, url)) = found {
+                    log::info!("Dev server for project {} is at {}", project_id, url);
+                    manager.update(&project_id, &run_id, |info| {
+                        info.port = Some(port);
+                        info.url = Some(url);
+                        info.status = "running".to_string();
+                    });
+                }
+            }
+        })
+    }
+}
+
+/// The port and preview URL a line of dev server output announces, e.g.
+/// "Local: http://localhost:5173/" or "Listening on port 3000"
+fn detect_url(line: &str) -> Option<(u16, String)> {
+    static URL: OnceLock<Regex> = OnceLock::new();
+    static HOST_PORT: OnceLock<Regex> = OnceLock::new();
+    static PORT: OnceLock<Regex> = OnceLock::new();
+    let url = URL.get_or_init(|| {
+        Regex::new(r"(https?)://(localhost|127\.0\.0\.1|0\.0\.0\.0|\[::1?\]|[A-Za-z0-9.-]+):(\d{2,5})(/[^\s'\x22,)]*)?")
+            .unwrap()
+    });
+    let host_port =
+        HOST_PORT.get_or_init(|| Regex::new(r"\b(?:localhost|127\.0\.0\.1|0\.0\.0\.0):(\d{2,5})\b").unwrap());
+    let port_only = PORT.get_or_init(|| {
+        Regex::new(r"(?i)\b(?:port|listening on|running on|started on)\b[^\d\n]{0,5}(\d{2,5})\b").unwrap()
+    });
+
+    let valid = |port: &str| port.parse::<u16>().ok().filter(|port| *port > 0);
+
+    if let Some(captures) = url.captures(line) {
+        let port = valid(&captures[3])?;
+        // Servers bound to every interface are still previewed locally
+        let host = match &captures[2] {
+            "0.0.0.0" | "[::]" | "[::1]" => "localhost",
+            host => host,
+        };
+        let path = captures.get(4).map_or("/", |path| path.as_str());
+        return Some((port, format!("{}://{}:{}{}", &captures[1], host, port, path)));
+    }
+    let captures = host_port.captures(line).or_else(|| port_only.captures(line))?;
+    let port = valid(&captures[1])?;
+    Some((port, format!("http://localhost:{}/", port)))
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+use crate::db::Database;
+use tauri::State;
+
+/// Start the project's dev server
+///
+/// The server is stopped when the calling window closes. Its output arrives
+/// as `dev-server-output` events, and `dev-server-updated` reports the URL
+/// once found and the server ending.
+#[tauri::command]
+pub async fn start_dev_server(
+    window: tauri::Window,
+    db: State<'_, Database>,
+    servers: State<'_, DevServerManager>,
+    project_id: String,
+    run_config: RunConfig,
+) -> Result<DevServerInfo, String> {
+    let root: String = sqlx::query_scalar("SELECT root_path FROM projects WHERE id = ?")
+        .bind(&project_id)
+        .fetch_optional(db.pool())
+        .await
+        .map_err(|e| format!("Failed to load project: {}", e))?
+        .ok_or_else(|| format!("Project not found: {}", project_id))?;
+    servers.start(&project_id, Path::new(&root), &run_config, Some(window.label().to_string()))
+}
+
+/// Stop the project's dev server; returns false if none was running
+#[tauri::command]
+pub async fn stop_dev_server(servers: State<'_, DevServerManager>, project_id: String) -> Result<bool, String> {
+    log::info!("Stopping dev server for project {}", project_id);
+    Ok(servers.stop(&project_id))
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DevServerState {
+    pub info: DevServerInfo,
+    /// Recent output lines
+    pub log: Vec<String>,
+}
+
+/// The project's dev server, running or last ended, if it has one
+#[tauri::command]
+pub async fn get_dev_server(
+    servers: State<'_, DevServerManager>,
+    project_id: String,
+) -> Result<Option<DevServerState>, String> {
+    Ok(servers.get(&project_id).map(|(info, log)| DevServerState { info, log }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_url() {
+        assert_eq!(
+            detect_url("  ➜  Local:   http://localhost:5173/"),
+            Some((5173, "http://localhost:5173/".to_string()))
+        );
+        assert_eq!(
+            detect_url("Server running at http://0.0.0.0:8000, press Ctrl+C"),
+            Some((8000, "http://localhost:8000/".to_string()))
+        );
+        assert_eq!(detect_url("listening on 127.0.0.1:8080"), Some((8080, "http://localhost:8080/".to_string())));
+        assert_eq!(detect_url("Listening on port 3000"), Some((3000, "http://localhost:3000/".to_string())));
+        assert_eq!(detect_url("Compiled 12 modules in 300ms"), None);
+        assert_eq!(detect_url("port 99999"), None);
+    }
+
+    async fn wait_for(rx: &mut tokio::sync::mpsc::UnboundedReceiver<DevServerInfo>, status: &str) -> DevServerInfo {
+        loop {
+            let info = tokio::time::timeout(std::time::Duration::from_secs(10), rx.recv()).await.unwrap().unwrap();
+            if info.status == status {
+                return info;
+            }
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_dev_server_reports_url_and_stops() {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let manager = DevServerManager::new(move |event| {
+            if let DevServerEvent::Updated(info) = event {
+                let _ = tx.send(info.clone());
+            }
+        });
+        let config = RunConfig {
+            program: "sh".to_string(),
+            args: vec!["-c".to_string(), "echo ready on http://localhost:4321; sleep 30".to_string()],
+            env: HashMap::new(),
+        };
+        let root = std::env::temp_dir();
+        manager.start("p1", &root, &config, Some("project-p1".to_string())).unwrap();
+        assert!(manager.start("p1", &root, &config, None).is_err());
+
+        let running = wait_for(&mut rx, "running").await;
+        assert_eq!(running.url.as_deref(), Some("http://localhost:4321/"));
+
+        manager.stop_for_holder("project-p1");
+        wait_for(&mut rx, "stopped").await;
+        let (info, log) = manager.get("p1").unwrap();
+        assert_eq!(info.pid, None);
+        assert_eq!(log, vec!["ready on http://localhost:4321"]);
+        assert!(!manager.stop("p1"));
+    }
+}
@@ -0,0 +1,364 @@
+// Environment Doctor
+// Checks the tools, keys and directories AtelierCode depends on and suggests fixes
+
+use serde::Serialize;
+use std::path::Path;
+use tokio::process::Command;
+
+/// Node.js releases older than this can't run the agent CLIs
+const MIN_NODE_MAJOR: u32 = 18;
+
+/// Environment variables holding API keys, with the provider they belong to
+const API_KEY_VARS: &[(&str, &str)] = &[
+    ("ANTHROPIC_API_KEY", "Anthropic"),
+    ("OPENAI_API_KEY", "OpenAI"),
+    ("GEMINI_API_KEY", "Google Gemini"),
+    ("GOOGLE_API_KEY", "Google"),
+];
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Ok,
+    /// Works, but something optional is missing or outdated
+    Warning,
+    /// Something AtelierCode needs is missing or broken
+    Error,
+}
+
+/// The result of one check
+#[derive(Debug, Clone, Serialize)]
+pub struct DoctorCheck {
+    /// Stable identifier, e.g. "git" or "plugin:claude-code"
+    pub id: String,
+    pub label: String,
+    pub status: CheckStatus,
+    /// What was found, e.g. a version or an error
+    pub detail: String,
+    /// How to fix a warning or error
+    pub fix: Option<String>,
+}
+
+impl DoctorCheck {
+    fn ok(id: impl Into<String>, label: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            label: label.into(),
+            status: CheckStatus::Ok,
+            detail: detail.into(),
+            fix: None,
+        }
+    }
+
+    fn problem(
+        status: CheckStatus,
+        id: impl Into<String>,
+        label: impl Into<String>,
+        detail: impl Into<String>,
+        fix: impl Into<String>,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            label: label.into(),
+            status,
+            detail: detail.into(),
+            fix: Some(fix.into()),
+        }
+    }
+}
+
+/// Every check, in the order they ran
+#[derive(Debug, Clone, Serialize)]
+pub struct DoctorReport {
+    pub checks: Vec<DoctorCheck>,
+    /// True when no check is an error
+    pub healthy: bool,
+    pub checked_at: i64,
+}
+
+impl DoctorReport {
+    pub fn new(checks: Vec<DoctorCheck>) -> Self {
+        Self {
+            healthy: checks.iter().all(|c| c.status != CheckStatus::Error),
+            checks,
+            checked_at: chrono::Utc::now().timestamp(),
+        }
+    }
+}
+
+/// Run `program args` and return its trimmed output, or None if it can't be run
+async fn tool_version(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().await.ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    stdout.lines().next().map(|line| line.trim().to_string())
+}
+
+pub async fn check_git() -> DoctorCheck {
+    match tool_version("git", &["--version"]).await {
+        Some(version) => DoctorCheck::ok("git", "Git", version),
+        None => DoctorCheck::problem(
+            CheckStatus::Error,
+            "git",
+            "Git",
+            "git was not found on your PATH",
+            "Install Git from https://git-scm.com/downloads and restart AtelierCode",
+        ),
+    }
+}
+
+/// Major version from `node --version` output like "v20.11.1"
+fn parse_node_major(version: &str) -> Option<u32> {
+    version.trim().trim_start_matches('v').split('.').next()?.parse().ok()
+}
+
+pub async fn check_node() -> DoctorCheck {
+    let install = format!("Install Node.js {} or newer from https://nodejs.org", MIN_NODE_MAJOR);
+    match tool_version("node", &["--version"]).await {
+        Some(version) => match parse_node_major(&version) {
+            Some(major) if major < MIN_NODE_MAJOR => DoctorCheck::problem(
+                CheckStatus::Warning,
+                "node",
+                "Node.js",
+                format!("{} is older than the agent CLIs support", version),
+                install,
+            ),
+            _ => DoctorCheck::ok("node", "Node.js", version),
+        },
+        None => DoctorCheck::problem(
+            CheckStatus::Warning,
+            "node",
+            "Node.js",
+            "node was not found; most agent CLIs are installed with npm",
+            install,
+        ),
+    }
+}
+
+/// Check every loaded plugin's CLI
+///
+/// Missing CLIs are warnings, unless none is installed at all.
+pub async fn check_plugins(plugin_manager: &crate::plugin::PluginManager) -> Vec<DoctorCheck> {
+    let plugins = plugin_manager.list_plugins();
+    if plugins.is_empty() {
+        return vec![DoctorCheck::problem(
+            CheckStatus::Error,
+            "plugins",
+            "Agent plugins",
+            "No agent plugins are loaded",
+            format!(
+                "Copy an agent plugin into {}",
+                crate::plugins::get_default_plugin_dir().display()
+            ),
+        )];
+    }
+
+    let mut checks = Vec::with_capacity(plugins.len());
+    for info in &plugins {
+        let Some(plugin) = plugin_manager.get(&info.name) else {
+            continue;
+        };
+        let id = format!("plugin:{}", info.name);
+        let label = format!("{} CLI", info.display_name);
+        let installed = plugin.check_installation().await.unwrap_or(false);
+        checks.push(if installed {
+            let version = plugin
+                .get_cli_version()
+                .await
+                .unwrap_or_else(|_| "installed".to_string());
+            DoctorCheck::ok(id, label, version)
+        } else {
+            DoctorCheck::problem(
+                CheckStatus::Warning,
+                id,
+                label,
+                format!("The {} CLI was not found", info.display_name),
+                format!(
+                    "Install the {} CLI and make sure it is on your PATH",
+                    info.display_name
+                ),
+            )
+        });
+    }
+
+    if checks.iter().all(|c| c.status != CheckStatus::Ok) {
+        for check in &mut checks {
+            check.status = CheckStatus::Error;
+        }
+    }
+    checks
+}
+
+pub async fn check_whisper(app: tauri::AppHandle) -> DoctorCheck {
+    match crate::commands_whisper::check_whisper_installation(app).await {
+        Ok(status) if status.installed && status.model_downloaded => DoctorCheck::ok(
+            "whisper",
+            "Voice transcription",
+            status.whisper_version.unwrap_or_else(|| "installed".to_string()),
+        ),
+        Ok(status) if status.installed => DoctorCheck::problem(
+            CheckStatus::Warning,
+            "whisper",
+            "Voice transcription",
+            "Whisper is installed but no model is downloaded",
+            "Download a model from Settings > Voice Transcription",
+        ),
+        Ok(_) => DoctorCheck::problem(
+            CheckStatus::Warning,
+            "whisper",
+            "Voice transcription",
+            "Local Whisper is not installed",
+            "Install Whisper from Settings > Voice Transcription, or use an OpenAI API key",
+        ),
+        Err(e) => DoctorCheck::problem(
+            CheckStatus::Warning,
+            "whisper",
+            "Voice transcription",
+            e,
+            "Install Whisper from Settings > Voice Transcription",
+        ),
+    }
+}
+
+/// Report which API keys are set, without revealing them
+pub fn check_api_keys() -> DoctorCheck {
+    let found: Vec<_> = API_KEY_VARS
+        .iter()
+        .filter(|(var, _)| std::env::var(var).is_ok_and(|value| !value.trim().is_empty()))
+        .map(|(var, provider)| format!("{} ({})", provider, var))
+        .collect();
+
+    if found.is_empty() {
+        DoctorCheck::problem(
+            CheckStatus::Warning,
+            "api_keys",
+            "API keys",
+            "No API keys are set in the environment",
+            "Agents signed in through their CLI don't need one; otherwise set ANTHROPIC_API_KEY \
+             or another provider's key before starting AtelierCode",
+        )
+    } else {
+        DoctorCheck::ok("api_keys", "API keys", found.join(", "))
+    }
+}
+
+pub async fn check_database(pool: &sqlx::SqlitePool) -> DoctorCheck {
+    let fix = "Quit AtelierCode, back up ateliercode.db from the app data folder and restore it or start fresh";
+    match sqlx::query_scalar::<_, String>("PRAGMA quick_check").fetch_all(pool).await {
+        Ok(rows) if rows.len() == 1 && rows[0] == "ok" => DoctorCheck::ok("database", "Database", "Integrity check passed"),
+        Ok(rows) => DoctorCheck::problem(CheckStatus::Error, "database", "Database", rows.join("; "), fix),
+        Err(e) => DoctorCheck::problem(
+            CheckStatus::Error,
+            "database",
+            "Database",
+            format!("Integrity check failed: {}", e),
+            fix,
+        ),
+    }
+}
+
+/// Check that the plugin directory can be read and written
+pub fn check_plugin_dir(dir: &Path) -> DoctorCheck {
+    let label = "Plugin directory";
+    if !dir.exists() {
+        return DoctorCheck::problem(
+            CheckStatus::Warning,
+            "plugin_dir",
+            label,
+            format!("{} does not exist", dir.display()),
+            format!("Create {} and copy agent plugins into it", dir.display()),
+        );
+    }
+    if let Err(e) = std::fs::read_dir(dir) {
+        return DoctorCheck::problem(
+            CheckStatus::Error,
+            "plugin_dir",
+            label,
+            format!("{} can't be read: {}", dir.display(), e),
+            format!("Give your user read access to {}", dir.display()),
+        );
+    }
+
+    let probe = dir.join(".ateliercode-doctor");
+    match std::fs::write(&probe, b"") {
+        Ok(()) => {
+            let _ = std::fs::remove_file(&probe);
+            DoctorCheck::ok("plugin_dir", label, dir.display().to_string())
+        }
+        Err(e) => DoctorCheck::problem(
+            CheckStatus::Warning,
+            "plugin_dir",
+            label,
+            format!("{} is read-only: {}", dir.display(), e),
+            format!("Give your user write access to {} to install plugins", dir.display()),
+        ),
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+use tauri::State;
+
+/// Check the environment and report problems with suggested fixes
+#[tauri::command]
+pub async fn run_doctor(
+    app: tauri::AppHandle,
+    db: State<'_, crate::db::Database>,
+    plugin_manager: State<'_, crate::plugin::PluginManager>,
+) -> Result<DoctorReport, String> {
+    log::info!("Running environment doctor");
+
+    let mut checks = vec![check_git().await, check_node().await];
+    checks.extend(check_plugins(&plugin_manager).await);
+    checks.push(check_whisper(app).await);
+    checks.push(check_api_keys());
+    checks.push(check_database(db.pool()).await);
+    checks.push(check_plugin_dir(&crate::plugins::get_default_plugin_dir()));
+
+    let report = DoctorReport::new(checks);
+    log::info!(
+        "Doctor finished: {} checks, {} problems",
+        report.checks.len(),
+        report.checks.iter().filter(|c| c.status != CheckStatus::Ok).count()
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_node_major() {
+        assert_eq!(parse_node_major("v20.11.1"), Some(20));
+        assert_eq!(parse_node_major("16.0.0\n"), Some(16));
+        assert_eq!(parse_node_major("node"), None);
+    }
+
+    #[test]
+    fn test_check_plugin_dir() {
+        let dir = std::env::temp_dir().join(format!("ateliercode-doctor-{}", uuid::Uuid::new_v4()));
+        assert_eq!(check_plugin_dir(&dir).status, CheckStatus::Warning);
+
+        std::fs::create_dir_all(&dir).unwrap();
+        let check = check_plugin_dir(&dir);
+        assert_eq!(check.status, CheckStatus::Ok);
+        assert!(check.fix.is_none());
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_report_health() {
+        let ok = DoctorCheck::ok("git", "Git", "git version 2.43.0");
+        let warning = DoctorCheck::problem(CheckStatus::Warning, "node", "Node.js", "missing", "install");
+        assert!(DoctorReport::new(vec![ok.clone(), warning]).healthy);
+
+        let error = DoctorCheck::problem(CheckStatus::Error, "database", "Database", "corrupt", "restore");
+        assert!(!DoctorReport::new(vec![ok, error]).healthy);
+    }
+}
@@ -0,0 +1,237 @@
+// Env Files
+// Reads and edits a project's .env files with values masked unless asked for,
+// and loads them into agent sessions so secrets needn't be pasted into chat
+
+use serde::Serialize;
+use std::path::Path;
+
+/// Shown in place of a value that isn't revealed
+pub const MASK: &str = "••••••••";
+
+/// One variable of an env file
+#[derive(Debug, Clone, Serialize)]
+pub struct EnvEntry {
+    pub key: String,
+    /// The value, or [`MASK`] when masked
+    pub value: String,
+    pub masked: bool,
+    /// 1-based line of the file it's on
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EnvFile {
+    /// Relative to the project root
+    pub path: String,
+    pub entries: Vec<EnvEntry>,
+}
+
+/// Whether `name` is an env file: `.env` or `.env.<suffix>`, e.g. `.env.local`
+pub fn is_env_file(name: &str) -> bool {
+    !name.contains(['/', '\\'])
+        && (name == ".env" || name.strip_prefix(".env.").is_some_and(|suffix| !suffix.is_empty() && suffix != "."))
+}
+
+/// The env files at the top of `root`, sorted by name
+pub fn list(root: &Path) -> Vec<String> {
+    let mut files: Vec<String> = std::fs::read_dir(root)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_file()))
+        .filter_map(|entry| entry.file_name().to_str().map(String::from))
+        .filter(|name| is_env_file(name))
+        .collect();
+    files.sort();
+    files
+}
+
+/// The variables in env file `text` as (key, value, line), in file order
+///
+/// Follows the dotenv format: `#` comments, an optional `export` prefix, and
+/// single-quoted (literal) or double-quoted (with `\n` escapes) values.
+pub fn parse(text: &str) -> Vec<(String, String, usize)> {
+    let mut vars = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let Some((key, raw)) = split_assignment(line) else { continue };
+        vars.push((key.to_string(), parse_value(raw), index + 1));
+    }
+    vars
+}
+
+/// Whether `key` can be a variable name in an env file
+pub fn is_valid_key(key: &str) -> bool {
+    !key.is_empty()
+        && !key.starts_with(|c: char| c.is_ascii_digit())
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'))
+}
+
+/// The key and raw value of an assignment line
+fn split_assignment(line: &str) -> Option<(&str, &str)> {
+    let line = line.trim_start();
+    if line.starts_with('#') {
+        return None;
+    }
+    let line = line.strip_prefix("export ").map_or(line, str::trim_start);
+    let (key, raw) = line.split_once('=')?;
+    let key = key.trim();
+    is_valid_key(key).then_some((key, raw))
+}
+
+fn parse_value(raw: &str) -> String {
+    let raw = raw.trim();
+    if let Some(rest) = raw.strip_prefix('\'') {
+        return rest.split_once('\'').map_or(rest, |(value, _)| value).to_string();
+    }
+    if let Some(rest) = raw.strip_prefix('"') {
+        let mut value = String::new();
+        let mut chars = rest.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' => break,
+                '\\' => match chars.next() {
+                    Some('n') => value.push('\n'),
+                    Some('t') => value.push('\t'),
+                    Some(other) => value.push(other),
+                    None => value.push('\\'),
+                },
+                _ => value.push(c),
+            }
+        }
+        return value;
+    }
+    // Unquoted values end at a comment
+    match raw.find(" #") {
+        Some(end) => raw[..end].trim_end().to_string(),
+        None => raw.to_string(),
+    }
+}
+
+/// `text` with `key` set to `value`, or removed when `value` is `None`
+///
+/// Other lines, comments included, are kept as they are; a new key is appended.
+pub fn set_var(text: &str, key: &str, value: Option<&str>) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    let mut found = false;
+    for line in text.lines() {
+        match split_assignment(line) {
+            Some((existing, _)) if existing == key => {
+                if let (Some(value), false) = (value, found) {
+                    let export = if line.trim_start().starts_with("export ") { "export " } else { "" };
+                    lines.push(format!("{}{}={}", export, key, quote(value)));
+                }
+                found = true;
+            }
+            _ => lines.push(line.to_string()),
+        }
+    }
+    if let (Some(value), false) = (value, found) {
+        lines.push(format!("{}={}", key, quote(value)));
+    }
+
+    let mut text = lines.join("\n");
+    if !text.is_empty() {
+        text.push('\n');
+    }
+    text
+}
+
+/// A value as written to an env file, quoted when it wouldn't read back as is
+fn quote(value: &str) -> String {
+    let needs_quotes =
+        value.starts_with(['\'', '"']) || value.chars().any(|c| c.is_whitespace() || c == '#' || c == '\\');
+    if !needs_quotes {
+        return value.to_string();
+    }
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n").replace('\t', "\\t"))
+}
+
+/// Read an env file, masking its values unless `reveal`
+pub fn read(root: &Path, path: &str, reveal: bool) -> std::io::Result<EnvFile> {
+    let text = std::fs::read_to_string(root.join(path))?;
+    let entries = parse(&text)
+        .into_iter()
+        .map(|(key, value, line)| {
+            // Empty values have nothing to hide
+            let masked = !reveal && !value.is_empty();
+            EnvEntry { key, value: if masked { MASK.to_string() } else { value }, masked, line }
+        })
+        .collect();
+    Ok(EnvFile { path: path.to_string(), entries })
+}
+
+/// The variables of `files`, relative to `root`, with later files overriding
+/// earlier ones; missing files are skipped
+pub fn load(root: &Path, files: &[String]) -> Vec<(String, String)> {
+    let mut vars: Vec<(String, String)> = Vec::new();
+    for file in files {
+        let text = match std::fs::read_to_string(root.join(file)) {
+            Ok(text) => text,
+            Err(e) => {
+                log::warn!("Skipping env file {}: {}", file, e);
+                continue;
+            }
+        };
+        for (key, value, _) in parse(&text) {
+            vars.retain(|(existing, _)| *existing != key);
+            vars.push((key, value));
+        }
+    }
+    vars
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let text = "# db\nexport DB_URL=postgres://localhost/app\nAPI_KEY='a#b c'\nGREETING=\"hi\\nthere\" # note\nDEBUG=true # on\nEMPTY=\n1BAD=x\nnot a var\n";
+        let vars = parse(text);
+        let pairs: Vec<(&str, &str)> = vars.iter().map(|(k, v, _)| (k.as_str(), v.as_str())).collect();
+        assert_eq!(
+            pairs,
+            vec![
+                ("DB_URL", "postgres://localhost/app"),
+                ("API_KEY", "a#b c"),
+                ("GREETING", "hi\nthere"),
+                ("DEBUG", "true"),
+                ("EMPTY", ""),
+            ]
+        );
+        assert_eq!(vars[0].2, 2);
+    }
+
+    #[test]
+    fn test_set_var_keeps_other_lines() {
+        let text = "# keys\nexport A=1\nB=2\n";
+        assert_eq!(set_var(text, "A", Some("x y")), "# keys\nexport A=\"x y\"\nB=2\n");
+        assert_eq!(set_var(text, "B", None), "# keys\nexport A=1\n");
+        assert_eq!(set_var(text, "C", Some("3")), "# keys\nexport A=1\nB=2\nC=3\n");
+        assert_eq!(set_var("", "A", Some("1")), "A=1\n");
+
+        let quoted = set_var("", "Q", Some("say \"hi\"\n"));
+        assert_eq!(parse(&quoted)[0].1, "say \"hi\"\n");
+    }
+
+    #[test]
+    fn test_read_masks_and_load_overrides() {
+        let root = std::env::temp_dir().join(format!("ateliercode-env-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join(".env"), "TOKEN=secret\nEMPTY=\nMODE=dev\n").unwrap();
+        std::fs::write(root.join(".env.local"), "MODE=local\n").unwrap();
+        std::fs::write(root.join(".envrc"), "x").unwrap();
+
+        assert_eq!(list(&root), vec![".env", ".env.local"]);
+        assert!(!is_env_file(".env./../secrets"));
+        let file = read(&root, ".env", false).unwrap();
+        assert_eq!((file.entries[0].value.as_str(), file.entries[0].masked), (MASK, true));
+        assert!(!file.entries[1].masked);
+        assert_eq!(read(&root, ".env", true).unwrap().entries[0].value, "secret");
+
+        let vars = load(&root, &[".env".to_string(), ".env.local".to_string(), ".env.missing".to_string()]);
+        assert!(vars.contains(&("MODE".to_string(), "local".to_string())));
+        assert_eq!(vars.len(), 3);
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,204 @@
+// Application Errors
+// The error returned by Tauri commands. It serializes as { kind, message, details }
+// so the frontend can react to, say, a missing project differently from a busy database
+
+use serde::Serialize;
+
+/// What went wrong, in terms the UI can act on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    /// The project, task, session, file... doesn't exist
+    NotFound,
+    /// The request itself is wrong, e.g. an empty message or a bad path
+    InvalidInput,
+    /// The change clashes with existing state, e.g. a duplicate or a running session
+    Conflict,
+    PermissionDenied,
+    /// Another connection holds the database; retrying usually works
+    DatabaseLocked,
+    Database,
+    Io,
+    Network,
+    /// An external program (git, an agent CLI, whisper) failed
+    Process,
+    Internal,
+}
+
+/// A command error
+///
+/// `message` is the full text to show the user; `details` holds the underlying
+/// error, when there is one, for logs and "show details" views.
+#[derive(Debug, Clone, Serialize, thiserror::Error)]
+#[error("{message}")]
+pub struct AppError {
+    pub kind: ErrorKind,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+}
+
+impl AppError {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        Self { kind, message: message.into(), details: None }
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::NotFound, message)
+    }
+
+    pub fn invalid_input(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::InvalidInput, message)
+    }
+
+    pub fn conflict(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Conflict, message)
+    }
+
+    pub fn process(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Process, message)
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Internal, message)
+    }
+
+    /// Prefix the message with `context`, keeping the kind and the original error as details
+    pub fn wrap(self, context: impl std::fmt::Display) -> Self {
+        Self {
+            kind: self.kind,
+            message: format!("{}: {}", context, self.message),
+            details: Some(self.details.unwrap_or(self.message)),
+        }
+    }
+
+    /// Convert an error and `wrap` it; meant for `map_err`
+    ///
+    /// `.map_err(AppError::context("Failed to fetch tasks"))`
+    pub fn context<E: Into<AppError>>(context: &str) -> impl FnOnce(E) -> AppError + '_ {
+        move |error| error.into().wrap(context)
+    }
+}
+
+/// Errors from helpers that still report plain strings
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        Self::internal(message)
+    }
+}
+
+impl From<&str> for AppError {
+    fn from(message: &str) -> Self {
+        Self::internal(message)
+    }
+}
+
+/// Lets modules that report plain strings call commands with `?`
+impl From<AppError> for String {
+    fn from(error: AppError) -> Self {
+        error.message
+    }
+}
+
+impl From<sqlx::Error> for AppError {
+    fn from(error: sqlx::Error) -> Self {
+        Self::new(sqlx_kind(&error), error.to_string())
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(error: std::io::Error) -> Self {
+        Self::new(io_kind(&error), error.to_string())
+    }
+}
+
+impl From<reqwest::Error> for AppError {
+    fn from(error: reqwest::Error) -> Self {
+        Self::new(ErrorKind::Network, error.to_string())
+    }
+}
+
+impl From<serde_json::Error> for AppError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::internal(error.to_string())
+    }
+}
+
+impl From<tokio::task::JoinError> for AppError {
+    fn from(error: tokio::task::JoinError) -> Self {
+        Self::internal(error.to_string())
+    }
+}
+
+impl From<tauri::Error> for AppError {
+    fn from(error: tauri::Error) -> Self {
+        Self::internal(error.to_string())
+    }
+}
+
+/// anyhow errors keep the kind of the error they wrap, when it is one we know
+impl From<anyhow::Error> for AppError {
+    fn from(error: anyhow::Error) -> Self {
+        let kind = error
+            .chain()
+            .find_map(|cause| {
+                cause
+                    .downcast_ref::<AppError>()
+                    .map(|e| e.kind)
+                    .or_else(|| cause.downcast_ref::<std::io::Error>().map(io_kind))
+                    .or_else(|| cause.downcast_ref::<sqlx::Error>().map(sqlx_kind))
+            })
+            .unwrap_or(ErrorKind::Internal);
+        Self::new(kind, format!("{:#}", error))
+    }
+}
+
+fn io_kind(error: &std::io::Error) -> ErrorKind {
+    match error.kind() {
+        std::io::ErrorKind::NotFound => ErrorKind::NotFound,
+        std::io::ErrorKind::PermissionDenied => ErrorKind::PermissionDenied,
+        std::io::ErrorKind::AlreadyExists => ErrorKind::Conflict,
+        std::io::ErrorKind::InvalidInput => ErrorKind::InvalidInput,
+        _ => ErrorKind::Io,
+    }
+}
+
+fn sqlx_kind(error: &sqlx::Error) -> ErrorKind {
+    // SQLITE_BUSY and SQLITE_LOCKED only show up in the message text
+    let is_locked = |message: &str| message.contains("database is locked") || message.contains("database table is locked");
+
+    match error {
+        sqlx::Error::RowNotFound => ErrorKind::NotFound,
+        sqlx::Error::PoolTimedOut => ErrorKind::DatabaseLocked,
+        sqlx::Error::Database(e) if e.is_unique_violation() => ErrorKind::Conflict,
+        sqlx::Error::Database(e) if is_locked(e.message()) => ErrorKind::DatabaseLocked,
+        _ => ErrorKind::Database,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_context_keeps_kind_and_details() {
+        let error = Err::<(), _>(std::io::Error::from(std::io::ErrorKind::NotFound))
+            .map_err(AppError::context("Failed to read README.md"))
+            .unwrap_err();
+        assert_eq!(error.kind, ErrorKind::NotFound);
+        assert!(error.message.starts_with("Failed to read README.md: "));
+        assert_eq!(error.details.as_deref(), Some(&error.message["Failed to read README.md: ".len()..]));
+    }
+
+    #[test]
+    fn test_serializes_for_the_frontend() {
+        let json = serde_json::to_value(AppError::not_found("Project not found: p1")).unwrap();
+        assert_eq!(json, serde_json::json!({ "kind": "not_found", "message": "Project not found: p1" }));
+    }
+
+    #[test]
+    fn test_anyhow_finds_wrapped_kind() {
+        let error = anyhow::Error::from(sqlx::Error::RowNotFound).context("Failed to load task");
+        assert_eq!(AppError::from(error).kind, ErrorKind::NotFound);
+    }
+}
@@ -0,0 +1,150 @@
+// File Encoding Detection
+// Detects the encoding of text files and transcodes them to/from UTF-8
+
+use anyhow::Result;
+use chardetng::EncodingDetector;
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
+use serde::{Deserialize, Serialize};
+
+/// Text decoded from raw file bytes along with its original encoding
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecodedText {
+    /// File content transcoded to UTF-8
+    pub content: String,
+    /// WHATWG name of the original encoding (e.g., "UTF-8", "Shift_JIS", "windows-1252")
+    pub encoding: String,
+    /// Whether the file started with a byte order mark
+    pub has_bom: bool,
+    /// Whether any bytes could not be decoded and were replaced
+    pub had_errors: bool,
+}
+
+/// Detect the encoding of raw bytes and transcode them to UTF-8
+pub fn decode_bytes(bytes: &[u8]) -> DecodedText {
+    // A BOM is authoritative when present
+    if let Some((encoding, bom_len)) = Encoding::for_bom(bytes) {
+        let (content, had_errors) = encoding.decode_without_bom_handling(&bytes[bom_len..]);
+        return DecodedText {
+            content: content.into_owned(),
+            encoding: encoding.name().to_string(),
+            has_bom: true,
+            had_errors,
+        };
+    }
+
+    // Fast path: valid UTF-8 needs no detection
+    if let Ok(content) = std::str::from_utf8(bytes) {
+        return DecodedText {
+            content: content.to_string(),
+            encoding: UTF_8.name().to_string(),
+            has_bom: false,
+            had_errors: false,
+        };
+    }
+
+    let mut detector = EncodingDetector::new();
+    detector.feed(bytes, true);
+    let encoding = detector.guess(None, true);
+
+    let (content, had_errors) = encoding.decode_without_bom_handling(bytes);
+    log::debug!("Detected file encoding: {} (errors: {})", encoding.name(), had_errors);
+
+    DecodedText {
+        content: content.into_owned(),
+        encoding: encoding.name().to_string(),
+        has_bom: false,
+        had_errors,
+    }
+}
+
+/// Encode UTF-8 text back into the given encoding so writes round-trip the original
+pub fn encode_text(content: &str, encoding_label: &str, with_bom: bool) -> Result<Vec<u8>> {
+    let encoding = Encoding::for_label(encoding_label.as_bytes())
+        .ok_or_else(|| anyhow::anyhow!("Unknown encoding: {}", encoding_label))?;
+
+    // encoding_rs only encodes to UTF-16 as UTF-8 (per the WHATWG spec), so handle it by hand
+    let mut bytes = if encoding == UTF_16LE {
+        content.encode_utf16().flat_map(|u| u.to_le_bytes()).collect()
+    } else if encoding == UTF_16BE {
+        content.encode_utf16().flat_map(|u| u.to_be_bytes()).collect()
+    } else {
+        let (encoded, _, had_errors) = encoding.encode(content);
+        if had_errors {
+            anyhow::bail!("Content contains characters that cannot be represented in {}", encoding.name());
+        }
+        encoded.into_owned()
+    };
+
+    if with_bom {
+        let bom: &[u8] = if encoding == UTF_8 {
+            &[0xEF, 0xBB, 0xBF]
+        } else if encoding == UTF_16LE {
+            &[0xFF, 0xFE]
+        } else if encoding == UTF_16BE {
+            &[0xFE, 0xFF]
+        } else {
+            &[]
+        };
+        bytes.splice(0..0, bom.iter().copied());
+    }
+
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_utf8() {
+        let decoded = decode_bytes("héllo wörld".as_bytes());
+        assert_eq!(decoded.content, "héllo wörld");
+        assert_eq!(decoded.encoding, "UTF-8");
+        assert!(!decoded.has_bom);
+    }
+
+    #[test]
+    fn test_decode_utf8_with_bom() {
+        let decoded = decode_bytes(&[0xEF, 0xBB, 0xBF, b'h', b'i']);
+        assert_eq!(decoded.content, "hi");
+        assert_eq!(decoded.encoding, "UTF-8");
+        assert!(decoded.has_bom);
+    }
+
+    #[test]
+    fn test_decode_windows_1252() {
+        // "café naïve résumé" in Windows-1252
+        let bytes = b"caf\xe9 na\xefve r\xe9sum\xe9, the d\xe9j\xe0 vu of a na\xefve caf\xe9";
+        let decoded = decode_bytes(bytes);
+        assert_eq!(decoded.encoding, "windows-1252");
+        assert!(decoded.content.starts_with("café naïve résumé"));
+    }
+
+    #[test]
+    fn test_decode_shift_jis() {
+        let (bytes, _, _) = encoding_rs::SHIFT_JIS.encode("これは日本語のテキストファイルです。文字コードを判定します。");
+        let decoded = decode_bytes(&bytes);
+        assert_eq!(decoded.encoding, "Shift_JIS");
+        assert_eq!(decoded.content, "これは日本語のテキストファイルです。文字コードを判定します。");
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let (original, _, _) = encoding_rs::SHIFT_JIS.encode("日本語のテキスト");
+        let decoded = decode_bytes(&original);
+        let encoded = encode_text(&decoded.content, &decoded.encoding, decoded.has_bom).unwrap();
+        assert_eq!(encoded, original.into_owned());
+
+        let utf16 = [0xFF, 0xFE, b'h', 0x00, b'i', 0x00];
+        let decoded = decode_bytes(&utf16);
+        assert_eq!(decoded.encoding, "UTF-16LE");
+        let encoded = encode_text(&decoded.content, &decoded.encoding, decoded.has_bom).unwrap();
+        assert_eq!(encoded, utf16.to_vec());
+    }
+
+    #[test]
+    fn test_encode_unrepresentable() {
+        assert!(encode_text("日本語", "windows-1252", false).is_err());
+        assert!(encode_text("hi", "not-an-encoding", false).is_err());
+    }
+}
@@ -1,43 +1,150 @@
 use anyhow::{Context, Result};
 use ignore::gitignore::{Gitignore, GitignoreBuilder};
 use notify::{Config, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use sha2::{Digest, Sha256};
 use sqlx::SqlitePool;
-use std::collections::HashMap;
-use std::path::Path;
-use std::process::Command;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 
+use crate::cli_command;
+use crate::git_status::{self, GitStatusCache};
+use crate::models::{ActivityLog, ProjectSettings};
+
+/// Largest file whose content is kept for restoring it after a deletion
+const MAX_SNAPSHOT_BYTES: u64 = 1024 * 1024;
+/// Content kept per watched project, across all its files
+const MAX_SNAPSHOT_TOTAL: usize = 64 * 1024 * 1024;
+/// How long after the app writes a file itself the watcher leaves the write
+/// for the app to record
+const OWN_WRITE_WINDOW: Duration = Duration::from_secs(5);
+
 /// Manages file system watchers for projects
 pub struct FileWatcherManager {
     watchers: Arc<Mutex<HashMap<String, ProjectWatcher>>>,
+    /// Invalidated by every watcher's events
+    git_status: GitStatusCache,
+    own_writes: OwnWrites,
 }
 
 struct ProjectWatcher {
-    _watcher: RecommendedWatcher,
+    watcher: RecommendedWatcher,
+    /// Every folder watched for the project, its root path first
+    roots: Arc<Mutex<Vec<WatchedRoot>>>,
     session_id: String,
+    /// Labels of the windows using this watcher; it stops when the last one lets go
+    holders: HashSet<String>,
+}
+
+/// A watched folder and the ignore rules that apply in it
+struct WatchedRoot {
+    path: String,
+    gitignore: Gitignore,
+}
+
+impl WatchedRoot {
+    fn load(path: String) -> Result<Self> {
+        let gitignore = load_gitignore(&path)?;
+        Ok(Self { path, gitignore })
+    }
+}
+
+/// Last seen content of the files changed while a project is watched, for
+/// restoring deleted ones git has no copy of
+#[derive(Default)]
+struct Snapshots {
+    files: HashMap<PathBuf, Vec<u8>>,
+    bytes: usize,
+}
+
+impl Snapshots {
+    /// Re-read a changed file, dropping it when it's gone or too big to keep
+    fn update(&mut self, path: &Path) {
+        self.take(path);
+        let small = std::fs::metadata(path).is_ok_and(|m| m.is_file() && m.len() <= MAX_SNAPSHOT_BYTES);
+        if !small {
+            return;
+        }
+        if let Ok(content) = std::fs::read(path) {
+            if self.bytes + content.len() <= MAX_SNAPSHOT_TOTAL {
+                self.bytes += content.len();
+                self.files.insert(path.to_path_buf(), content);
+            }
+        }
+    }
+
+    fn take(&mut self, path: &Path) -> Option<Vec<u8>> {
+        let content = self.files.remove(path)?;
+        self.bytes -= content.len();
+        Some(content)
+    }
+}
+
+/// Files the app is writing and recording itself, with the content hash each
+/// write leaves (`None` for deletions), so the watcher doesn't record them twice
+#[derive(Clone, Default)]
+struct OwnWrites(Arc<Mutex<HashMap<PathBuf, OwnWrite>>>);
+
+/// Content hash a write leaves and when it was made
+type OwnWrite = (Option<String>, Instant);
+
+impl OwnWrites {
+    fn insert(&self, path: &Path, content_hash: Option<String>) {
+        let mut writes = self.0.lock().unwrap();
+        writes.retain(|_, (_, at)| at.elapsed() < OWN_WRITE_WINDOW);
+        writes.insert(path.to_path_buf(), (content_hash, Instant::now()));
+    }
+
+    /// Whether an event on `path` comes from one of these writes: made moments
+    /// ago and leaving the file as it is now
+    fn contains(&self, path: &Path) -> bool {
+        let writes = self.0.lock().unwrap();
+        matches!(
+            writes.get(path),
+            Some((hash, at)) if at.elapsed() < OWN_WRITE_WINDOW && *hash == file_content_hash(path)
+        )
+    }
+}
+
+/// The root a path is in; the deepest one when roots nest
+fn root_of<'a>(roots: &'a [WatchedRoot], path: &Path) -> Option<&'a WatchedRoot> {
+    roots
+        .iter()
+        .filter(|root| path.starts_with(&root.path))
+        .max_by_key(|root| root.path.len())
 }
 
 impl FileWatcherManager {
-    /// Create a new FileWatcherManager
-    pub fn new() -> Self {
+    /// Create a new FileWatcherManager that keeps `git_status` current for watched projects
+    pub fn new(git_status: GitStatusCache) -> Self {
         Self {
             watchers: Arc::new(Mutex::new(HashMap::new())),
+            git_status,
+            own_writes: OwnWrites::default(),
         }
     }
 
-    /// Start watching a project directory
+    /// Start watching a project directory and its `extra_roots` on behalf of
+    /// `holder` (a window label)
+    ///
+    /// If the project is already watched, `holder` shares the existing watcher
+    /// and its session ID is returned.
     pub async fn start_watching(
         &self,
         project_id: String,
         project_path: String,
+        extra_roots: Vec<String>,
         db_pool: SqlitePool,
+        holder: &str,
     ) -> Result<String> {
-        // Check if already watching
+        // Share the watcher if one is already running
         {
-            let watchers = self.watchers.lock().unwrap();
-            if watchers.contains_key(&project_id) {
-                return Err(anyhow::anyhow!("Project is already being watched"));
+            let mut watchers = self.watchers.lock().unwrap();
+            if let Some(watcher) = watchers.get_mut(&project_id) {
+                watcher.holders.insert(holder.to_string());
+                return Ok(watcher.session_id.clone());
             }
         }
 
@@ -65,15 +172,21 @@ impl FileWatcherManager {
 
         log::info!("Starting file watcher for project: {} at path: {}", project_id, project_path);
 
-        // Load gitignore patterns
-        let gitignore = load_gitignore(&project_path)?;
+        // Load gitignore patterns of every root
+        let mut roots = vec![WatchedRoot::load(project_path.clone())?];
+        for path in extra_roots {
+            roots.push(WatchedRoot::load(path)?);
+        }
+        let roots = Arc::new(Mutex::new(roots));
 
         // Create channel for file system events
         let (tx, mut rx) = mpsc::channel(100);
 
         // Clone for the watcher callback
         let project_path_clone = project_path.clone();
-        let gitignore_clone = Arc::new(gitignore);
+        let roots_clone = roots.clone();
+        let git_status = self.git_status.clone();
+        let git_status_project_id = project_id.clone();
 
         // Create the watcher
         let mut watcher = RecommendedWatcher::new(
@@ -83,9 +196,18 @@ impl FileWatcherManager {
                         // Filter events based on kind
                         match event.kind {
                             EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_) => {
-                                // Check each path against gitignore
+                                let root = Path::new(&project_path_clone);
+                                if event.paths.iter().any(|path| git_status::affects_status(path, root)) {
+                                    git_status.invalidate(&git_status_project_id);
+                                }
+
+                                // Check each path against the gitignore of its root
+                                let roots = roots_clone.lock().unwrap();
                                 for path in &event.paths {
-                                    if should_ignore_path(path, &project_path_clone, &gitignore_clone) {
+                                    let Some(root) = root_of(&roots, path) else {
+                                        continue;
+                                    };
+                                    if should_ignore_path(path, &root.path, &root.gitignore) {
                                         continue;
                                     }
 
@@ -104,35 +226,46 @@ impl FileWatcherManager {
         )
         .context("Failed to create file watcher")?;
 
-        // Start watching the project directory
-        watcher
-            .watch(Path::new(&project_path), RecursiveMode::Recursive)
-            .context("Failed to watch directory")?;
+        // Start watching the project directories
+        for root in roots.lock().unwrap().iter() {
+            watcher
+                .watch(Path::new(&root.path), RecursiveMode::Recursive)
+                .with_context(|| format!("Failed to watch directory {}", root.path))?;
+        }
 
         // Store the watcher
+        let event_roots = roots.clone();
         {
             let mut watchers = self.watchers.lock().unwrap();
             watchers.insert(
                 project_id.clone(),
                 ProjectWatcher {
-                    _watcher: watcher,
+                    watcher,
+                    roots,
                     session_id: session_id.clone(),
+                    holders: HashSet::from([holder.to_string()]),
                 },
             );
         }
+        self.git_status.watch(&project_id);
 
         // Spawn a task to handle file events
         let project_id_clone = project_id.clone();
         let session_id_clone = session_id.clone();
         let project_path_clone = project_path.clone();
+        let own_writes = self.own_writes.clone();
 
         tokio::spawn(async move {
+            let mut snapshots = Snapshots::default();
             while let Some(event) = rx.recv().await {
                 if let Err(e) = handle_file_event(
                     &event,
                     &project_id_clone,
                     &session_id_clone,
                     &project_path_clone,
+                    &event_roots,
+                    &mut snapshots,
+                    &own_writes,
                     &db_pool,
                 )
                 .await
@@ -146,16 +279,64 @@ impl FileWatcherManager {
         Ok(session_id)
     }
 
-    /// Stop watching a project
-    pub fn stop_watching(&self, project_id: &str) -> Result<()> {
+    /// Watch another root of a project that is being watched; a no-op if it isn't
+    pub fn add_root(&self, project_id: &str, path: &str) -> Result<()> {
+        let mut watchers = self.watchers.lock().unwrap();
+        let Some(project) = watchers.get_mut(project_id) else {
+            return Ok(());
+        };
+        let root = WatchedRoot::load(path.to_string())?;
+        project
+            .watcher
+            .watch(Path::new(path), RecursiveMode::Recursive)
+            .with_context(|| format!("Failed to watch directory {}", path))?;
+        project.roots.lock().unwrap().push(root);
+        log::info!("Watching root {} of project {}", path, project_id);
+        Ok(())
+    }
+
+    /// Stop watching a root removed from a project
+    pub fn remove_root(&self, project_id: &str, path: &str) -> Result<()> {
+        let mut watchers = self.watchers.lock().unwrap();
+        let Some(project) = watchers.get_mut(project_id) else {
+            return Ok(());
+        };
+        project.roots.lock().unwrap().retain(|root| root.path != path);
+        project
+            .watcher
+            .unwatch(Path::new(path))
+            .with_context(|| format!("Failed to unwatch directory {}", path))?;
+        Ok(())
+    }
+
+    /// Stop watching a project for `holder`; the watcher keeps running while
+    /// other windows still use it
+    pub fn stop_watching(&self, project_id: &str, holder: &str) -> Result<()> {
         let mut watchers = self.watchers.lock().unwrap();
 
-        if let Some(_watcher) = watchers.remove(project_id) {
+        let watcher = watchers
+            .get_mut(project_id)
+            .ok_or_else(|| anyhow::anyhow!("Project is not being watched"))?;
+        watcher.holders.remove(holder);
+        if watcher.holders.is_empty() {
+            watchers.remove(project_id);
+            self.git_status.unwatch(project_id);
             log::info!("Stopped watching project: {}", project_id);
-            Ok(())
-        } else {
-            Err(anyhow::anyhow!("Project is not being watched"))
         }
+        Ok(())
+    }
+
+    /// Drop `holder` from every watcher, stopping those no other window uses
+    pub fn release_holder(&self, holder: &str) {
+        let mut watchers = self.watchers.lock().unwrap();
+        watchers.retain(|project_id, watcher| {
+            watcher.holders.remove(holder);
+            if watcher.holders.is_empty() {
+                self.git_status.unwatch(project_id);
+                log::info!("Stopped watching project {} after its window closed", project_id);
+            }
+            !watcher.holders.is_empty()
+        });
     }
 
     /// Check if a project is being watched
@@ -164,21 +345,39 @@ impl FileWatcherManager {
         watchers.contains_key(project_id)
     }
 
+    /// Leave the upcoming write of `content` to `path` (`None` to delete it) to
+    /// the caller to record, rather than the watcher; `path` is spelled from
+    /// the project root as watched
+    pub fn expect_write(&self, path: &Path, content: Option<&[u8]>) {
+        self.own_writes.insert(path, content.map(content_hash));
+    }
+
     /// Get the session ID for a watched project
     pub fn get_session_id(&self, project_id: &str) -> Option<String> {
         let watchers = self.watchers.lock().unwrap();
         watchers.get(project_id).map(|w| w.session_id.clone())
     }
+
+    /// Session IDs of every running watcher
+    pub fn session_ids(&self) -> Vec<String> {
+        let watchers = self.watchers.lock().unwrap();
+        watchers.values().map(|w| w.session_id.clone()).collect()
+    }
 }
 
 /// Handle a file system event
+#[allow(clippy::too_many_arguments)]
 async fn handle_file_event(
     event: &Event,
     project_id: &str,
     session_id: &str,
     project_path: &str,
+    roots: &Mutex<Vec<WatchedRoot>>,
+    snapshots: &mut Snapshots,
+    own_writes: &OwnWrites,
     db_pool: &SqlitePool,
 ) -> Result<()> {
+    let capture_deleted = project_settings(db_pool, project_id).await.capture_deleted_files;
     for path in &event.paths {
         // Determine change type
         let change_type = match event.kind {
@@ -195,6 +394,14 @@ async fn handle_file_event(
             .to_string_lossy()
             .replace('\\', "/");
 
+        if own_writes.contains(path) {
+            log::debug!("Leaving {} of {} to the app, which made it", change_type, relative_path);
+            if change_type != "deleted" {
+                snapshots.update(path);
+            }
+            continue;
+        }
+
         log::info!(
             "File {} detected: {} in project {}",
             change_type,
@@ -202,59 +409,214 @@ async fn handle_file_event(
             project_id
         );
 
-        // Calculate diff for modified files
-        let diff = if change_type == "modified" || change_type == "created" {
-            calculate_git_diff(path, project_path).ok()
+        let change_id = record_file_change(db_pool, project_id, session_id, project_path, path, change_type).await?;
+        if !capture_deleted {
+            continue;
+        }
+        if change_type == "deleted" {
+            let repo_dir = root_of(&roots.lock().unwrap(), path).map_or(project_path.to_string(), |root| root.path.clone());
+            capture_deleted_file(db_pool, &change_id, path, &repo_dir, snapshots.take(path)).await?;
         } else {
-            None
-        };
+            snapshots.update(path);
+        }
+    }
 
-        // Create file change record
-        let file_change_id = uuid::Uuid::new_v4().to_string();
-        let now = chrono::Utc::now().timestamp();
+    Ok(())
+}
 
-        sqlx::query(
-            r#"
-            INSERT INTO file_changes (id, project_id, session_id, file_path, change_type, diff, reviewed, approved, timestamp)
-            VALUES (?, ?, ?, ?, ?, ?, FALSE, NULL, ?)
-            "#
-        )
-        .bind(&file_change_id)
-        .bind(project_id)
-        .bind(session_id)
-        .bind(&relative_path)
-        .bind(change_type)
-        .bind(&diff)
-        .bind(now)
+/// Store what a deleted file held on its change: the watcher's last snapshot
+/// of it, or else the blob staged for it in the git repository at `repo_dir`
+async fn capture_deleted_file(
+    db_pool: &SqlitePool,
+    change_id: &str,
+    path: &Path,
+    repo_dir: &str,
+    snapshot: Option<Vec<u8>>,
+) -> Result<()> {
+    let blob = if snapshot.is_none() { staged_blob(path, repo_dir) } else { None };
+    if snapshot.is_none() && blob.is_none() {
+        log::debug!("No copy of deleted file {} to keep", path.display());
+        return Ok(());
+    }
+    sqlx::query("UPDATE file_changes SET deleted_content = ?, deleted_blob = ? WHERE id = ?")
+        .bind(&snapshot)
+        .bind(&blob)
+        .bind(change_id)
         .execute(db_pool)
         .await
-        .context("Failed to insert file change")?;
+        .context("Failed to store deleted file content")?;
+    Ok(())
+}
 
-        // Log activity
-        sqlx::query(
-            r#"
-            INSERT INTO activity_log (id, project_id, session_id, event_type, description, data, timestamp)
-            VALUES (?, ?, ?, 'file_change', ?, ?, ?)
-            "#
-        )
-        .bind(uuid::Uuid::new_v4().to_string())
-        .bind(project_id)
-        .bind(session_id)
-        .bind(format!("File {}: {}", change_type, relative_path))
-        .bind(serde_json::json!({
-            "file_path": relative_path,
-            "change_type": change_type,
-            "change_id": file_change_id
-        }).to_string())
-        .bind(now)
-        .execute(db_pool)
+/// Object id of the version of `path` staged in the git index
+fn staged_blob(path: &Path, repo_dir: &str) -> Option<String> {
+    let relative = path.strip_prefix(repo_dir).ok()?.to_string_lossy().replace('\\', "/");
+    let output = cli_command::std_command("git", ["rev-parse", "--verify", "--quiet", &format!(":./{}", relative)])
+        .current_dir(repo_dir)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Write a deleted file back from what its change captured, taking a blob
+/// from the git repository at `repo_dir`; false when nothing was captured
+pub fn restore_deleted_file(target: &Path, repo_dir: &Path, content: Option<Vec<u8>>, blob: Option<&str>) -> Result<bool> {
+    let content = match (content, blob) {
+        (Some(content), _) => content,
+        (None, Some(blob)) => {
+            let output = cli_command::std_command("git", ["cat-file", "blob", blob])
+                .current_dir(repo_dir)
+                .output()
+                .context("Failed to execute git cat-file")?;
+            if !output.status.success() {
+                anyhow::bail!("Git object {} is gone: {}", blob, String::from_utf8_lossy(&output.stderr).trim());
+            }
+            output.stdout
+        }
+        (None, None) => return Ok(false),
+    };
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent).context("Failed to recreate folder")?;
+    }
+    std::fs::write(target, content).with_context(|| format!("Failed to restore {}", target.display()))?;
+    Ok(true)
+}
+
+/// Record a change to `path` with its diff and content hash, flag earlier
+/// changes to the file as stale and log the activity; returns the change id
+#[tracing::instrument(skip_all, fields(project_id = %project_id, session_id = %session_id))]
+pub async fn record_file_change(
+    db_pool: &SqlitePool,
+    project_id: &str,
+    session_id: &str,
+    project_path: &str,
+    path: &Path,
+    change_type: &str,
+) -> Result<String> {
+    let relative_path = path
+        .strip_prefix(project_path)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/");
+
+    // Calculate diff for modified files; files in the project's other roots
+    // are diffed in the repository around them
+    let diff_root = match path.parent() {
+        Some(parent) if !path.starts_with(project_path) => parent.to_string_lossy().to_string(),
+        _ => project_path.to_string(),
+    };
+    let diff = if change_type == "modified" || change_type == "created" {
+        calculate_git_diff(path, &diff_root).ok()
+    } else {
+        None
+    };
+
+    let content_hash = if change_type == "deleted" {
+        None
+    } else {
+        file_content_hash(path)
+    };
+
+    // Earlier changes to this file no longer match what is on disk
+    sqlx::query(
+        r#"
+        UPDATE file_changes
+        SET stale = TRUE
+        WHERE project_id = ? AND file_path = ? AND stale = FALSE
+          AND content_hash IS NOT ?
+        "#
+    )
+    .bind(project_id)
+    .bind(&relative_path)
+    .bind(&content_hash)
+    .execute(db_pool)
+    .await
+    .context("Failed to mark earlier changes stale")?;
+
+    // Create file change record
+    let file_change_id = uuid::Uuid::new_v4().to_string();
+    let now = chrono::Utc::now().timestamp();
+
+    sqlx::query(
+        r#"
+        INSERT INTO file_changes
+            (id, project_id, session_id, file_path, change_type, diff, reviewed, approved, timestamp, content_hash)
+        VALUES (?, ?, ?, ?, ?, ?, FALSE, NULL, ?, ?)
+        "#
+    )
+    .bind(&file_change_id)
+    .bind(project_id)
+    .bind(session_id)
+    .bind(&relative_path)
+    .bind(change_type)
+    .bind(&diff)
+    .bind(now)
+    .bind(&content_hash)
+    .execute(db_pool)
+    .await
+    .context("Failed to insert file change")?;
+
+    // Log activity
+    let mut activity = ActivityLog::new(
+        project_id.to_string(),
+        Some(session_id.to_string()),
+        "file_change".to_string(),
+        format!("File {}: {}", change_type, relative_path),
+    );
+    activity.data = Some(serde_json::json!({
+        "file_path": relative_path,
+        "change_type": change_type,
+        "change_id": file_change_id
+    }).to_string());
+    activity.timestamp = now;
+    crate::activity::insert_activity(db_pool, &activity)
         .await
         .context("Failed to log activity")?;
 
-        log::info!("File change recorded: {} ({})", relative_path, change_type);
+    log::info!("File change recorded: {} ({})", relative_path, change_type);
+
+    if diff.is_some() && ai_review_enabled(db_pool, project_id).await {
+        let pool = db_pool.clone();
+        let change_id = file_change_id.clone();
+        tokio::spawn(async move {
+            if let Err(e) = crate::code_review::ai_review(&pool, &change_id).await {
+                log::warn!("AI review of change {} failed: {}", change_id, e);
+            }
+        });
     }
 
-    Ok(())
+    Ok(file_change_id)
+}
+
+/// SHA-256 of a file's content, or `None` if it can't be read
+pub fn file_content_hash(path: &Path) -> Option<String> {
+    let content = std::fs::read(path).ok()?;
+    Some(content_hash(&content))
+}
+
+/// SHA-256 of `content`, as stored on file changes
+fn content_hash(content: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(content))
+}
+
+/// The project's settings, or the defaults if it can't be read
+async fn project_settings(db_pool: &SqlitePool, project_id: &str) -> ProjectSettings {
+    let settings = sqlx::query_scalar::<_, Option<String>>("SELECT settings FROM projects WHERE id = ?")
+        .bind(project_id)
+        .fetch_optional(db_pool)
+        .await
+        .ok()
+        .flatten()
+        .flatten();
+    ProjectSettings::from_json(settings.as_deref())
+}
+
+/// Whether the project has opted in to AI review of every change
+async fn ai_review_enabled(db_pool: &SqlitePool, project_id: &str) -> bool {
+    project_settings(db_pool, project_id).await.ai_review
 }
 
 /// Load gitignore patterns for a project
@@ -313,8 +675,7 @@ fn calculate_git_diff(file_path: &Path, project_path: &str) -> Result<String> {
         .replace('\\', "/");
 
     // Try to get git diff
-    let output = Command::new("git")
-        .args(&["diff", "HEAD", "--", &relative_path])
+    let output = cli_command::std_command("git", ["diff", "HEAD", "--", &relative_path])
         .current_dir(project_path)
         .output()
         .context("Failed to execute git diff")?;
@@ -325,8 +686,7 @@ fn calculate_git_diff(file_path: &Path, project_path: &str) -> Result<String> {
         // If no diff from HEAD, it might be a new untracked file
         if diff.trim().is_empty() {
             // Try to show the entire file as an addition
-            let output = Command::new("git")
-                .args(&["diff", "--no-index", "/dev/null", &relative_path])
+            let output = cli_command::std_command("git", ["diff", "--no-index", "/dev/null", &relative_path])
                 .current_dir(project_path)
                 .output();
 
@@ -369,4 +729,62 @@ mod tests {
         let result = load_gitignore("/tmp/nonexistent");
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_root_of_picks_deepest_root() {
+        let roots: Vec<WatchedRoot> = ["/work/app", "/work/shared", "/work/shared/vendor"]
+            .into_iter()
+            .map(|path| WatchedRoot::load(path.to_string()).unwrap())
+            .collect();
+        let root = |path: &str| root_of(&roots, Path::new(path)).map(|root| root.path.as_str());
+        assert_eq!(root("/work/app/src/main.rs"), Some("/work/app"));
+        assert_eq!(root("/work/shared/lib.rs"), Some("/work/shared"));
+        assert_eq!(root("/work/shared/vendor/x.rs"), Some("/work/shared/vendor"));
+        assert_eq!(root("/work/application/main.rs"), None);
+    }
+
+    #[test]
+    fn test_snapshot_restores_deleted_file() {
+        let dir = std::env::temp_dir().join(format!("ateliercode-snapshots-{}", uuid::Uuid::new_v4()));
+        let file = dir.join("src/notes.txt");
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+        std::fs::write(&file, "first").unwrap();
+
+        let mut snapshots = Snapshots::default();
+        snapshots.update(&file);
+        std::fs::write(&file, "second").unwrap();
+        snapshots.update(&file);
+        assert_eq!(snapshots.bytes, 6);
+
+        std::fs::remove_dir_all(dir.join("src")).unwrap();
+        let content = snapshots.take(&file);
+        assert_eq!(snapshots.bytes, 0);
+        assert!(restore_deleted_file(&file, &dir, content, None).unwrap());
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "second");
+        assert!(!restore_deleted_file(&file, &dir, None, None).unwrap());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_own_writes_match_the_content_they_leave() {
+        let dir = std::env::temp_dir().join(format!("ateliercode-own-writes-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("a.txt");
+        let own_writes = OwnWrites::default();
+
+        own_writes.insert(&file, Some(content_hash(b"patched")));
+        std::fs::write(&file, "patched").unwrap();
+        assert!(own_writes.contains(&file));
+        // Edited again by someone else: the watcher records it
+        std::fs::write(&file, "edited").unwrap();
+        assert!(!own_writes.contains(&file));
+
+        own_writes.insert(&file, None);
+        std::fs::remove_file(&file).unwrap();
+        assert!(own_writes.contains(&file));
+        assert!(!own_writes.contains(&dir.join("b.txt")));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
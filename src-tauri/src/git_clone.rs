@@ -0,0 +1,130 @@
+// Git Clone
+// Clones a repository for a new project, reporting git's progress to the job
+
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio::io::AsyncReadExt;
+
+use crate::jobs::JobContext;
+
+/// Share of the job's progress taken by the clone; analysis gets the rest
+const CLONE_SHARE: f64 = 0.9;
+
+/// Where each git progress phase starts and ends within the clone
+const PHASES: &[(&str, f64, f64)] = &[
+    ("Receiving objects", 0.0, 0.7),
+    ("Resolving deltas", 0.7, 0.9),
+    ("Updating files", 0.9, 1.0),
+];
+
+/// Parse a `git clone --progress` line into its phase and the clone's overall fraction done
+///
+/// Lines look like `Receiving objects:  45% (450/1000), 1.20 MiB | 2.00 MiB/s`.
+/// Server-side phases (`remote: ...`) and other output return None.
+pub fn parse_progress(line: &str) -> Option<(&str, f64)> {
+    let (phase, rest) = line.split_once(':')?;
+    let (start, end) = PHASES.iter().find(|(name, _, _)| *name == phase).map(|(_, start, end)| (*start, *end))?;
+    let percent: f64 = rest.trim_start().split_once('%')?.0.parse().ok()?;
+    Some((phase, start + (end - start) * percent.clamp(0.0, 100.0) / 100.0))
+}
+
+/// Removes a partial clone unless the clone finished, including when the job is cancelled
+struct PartialClone {
+    path: PathBuf,
+    done: bool,
+}
+
+impl Drop for PartialClone {
+    fn drop(&mut self) {
+        if !self.done {
+            if let Err(e) = std::fs::remove_dir_all(&self.path) {
+                log::warn!("Failed to remove partial clone {}: {}", self.path.display(), e);
+            }
+        }
+    }
+}
+
+/// Clone `url` into `dest`, which must not exist or be an empty directory
+pub async fn clone(url: &str, dest: &Path, ctx: &JobContext) -> Result<(), String> {
+    if url.trim().is_empty() {
+        return Err("Repository URL is required".to_string());
+    }
+    if dest.exists() {
+        let empty = dest
+            .read_dir()
+            .map(|mut entries| entries.next().is_none())
+            .map_err(|e| format!("Failed to read {}: {}", dest.display(), e))?;
+        if !empty {
+            return Err(format!("{} already exists and isn't empty", dest.display()));
+        }
+    }
+
+    log::info!("Cloning {} into {}", url, dest.display());
+    ctx.progress(Some(0.0), format!("Cloning {}", url)).await;
+
+    let mut child = crate::cli_command::command("git", ["clone", "--progress", "--", url])
+        .arg(dest)
+        // Fail instead of waiting on a credential prompt nobody can answer
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+    let mut partial = PartialClone { path: dest.to_path_buf(), done: false };
+
+    // git redraws progress lines with \r, so split on both line endings
+    let mut stderr = child.stderr.take().ok_or("Failed to read git output")?;
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let mut messages: Vec<String> = Vec::new();
+    let mut last_percent = None;
+    loop {
+        let read = stderr.read(&mut chunk).await.map_err(|e| format!("Failed to read git output: {}", e))?;
+        if read == 0 {
+            break;
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+        while let Some(end) = buffer.iter().position(|b| *b == b'\r' || *b == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line).trim().to_string();
+            match parse_progress(&line) {
+                Some((phase, fraction)) => {
+                    // git redraws far more often than the percentage changes
+                    let percent = (fraction * 100.0) as u32;
+                    if last_percent != Some(percent) {
+                        last_percent = Some(percent);
+                        ctx.progress(Some(fraction * CLONE_SHARE), phase).await;
+                    }
+                }
+                None if !line.is_empty() && !line.starts_with("remote:") => messages.push(line),
+                None => {}
+            }
+        }
+    }
+
+    let status = child.wait().await.map_err(|e| format!("Failed to run git: {}", e))?;
+    if !status.success() {
+        let reason = messages.iter().rev().find(|line| line.starts_with("fatal:")).or(messages.last());
+        return Err(format!("git clone failed: {}", reason.map_or("unknown error", |line| line.as_str())));
+    }
+    partial.done = true;
+    log::info!("Cloned {} into {}", url, dest.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_progress() {
+        assert_eq!(parse_progress("Receiving objects:  50% (5/10), 1.20 MiB | 2.00 MiB/s"), Some(("Receiving objects", 0.35)));
+        assert_eq!(parse_progress("Resolving deltas: 100% (10/10), done."), Some(("Resolving deltas", 0.9)));
+        assert_eq!(parse_progress("Updating files:   0% (0/4)"), Some(("Updating files", 0.9)));
+        assert_eq!(parse_progress("remote: Counting objects:  50% (5/10)"), None);
+        assert_eq!(parse_progress("Cloning into 'app'..."), None);
+        assert_eq!(parse_progress("fatal: repository 'x' not found"), None);
+    }
+}
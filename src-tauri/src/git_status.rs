@@ -0,0 +1,132 @@
+// Git Status Cache
+// Keeps each watched project's parsed `git status` until its file watcher or
+// a git operation reports a change
+
+use std::collections::HashMap;
+use std::path::{Component, Path};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::commands::GitFileStatus;
+
+/// Recompute even without events, in case the OS dropped some
+const MAX_AGE: Duration = Duration::from_secs(60);
+
+#[derive(Default)]
+struct ProjectEntry {
+    /// Only watched projects are cached, since nothing else invalidates them
+    watched: bool,
+    /// Bumped on every invalidation so a status read before it isn't stored after it
+    generation: u64,
+    status: Option<(Instant, Arc<Vec<GitFileStatus>>)>,
+}
+
+/// Parsed git status per project, shared with the file watchers
+#[derive(Clone, Default)]
+pub struct GitStatusCache(Arc<Mutex<HashMap<String, ProjectEntry>>>);
+
+impl GitStatusCache {
+    /// The cached status, if it's still current
+    pub fn get(&self, project_id: &str) -> Option<Arc<Vec<GitFileStatus>>> {
+        let entries = self.0.lock().unwrap();
+        let (cached_at, status) = entries.get(project_id)?.status.as_ref()?;
+        (cached_at.elapsed() < MAX_AGE).then(|| status.clone())
+    }
+
+    /// Token to pass to [`insert`](Self::insert) for a status read after this call
+    pub fn generation(&self, project_id: &str) -> u64 {
+        self.0.lock().unwrap().get(project_id).map_or(0, |entry| entry.generation)
+    }
+
+    /// Cache `status`, unless the project isn't watched or changed since `generation`
+    pub fn insert(&self, project_id: &str, generation: u64, status: Arc<Vec<GitFileStatus>>) {
+        let mut entries = self.0.lock().unwrap();
+        if let Some(entry) = entries.get_mut(project_id) {
+            if entry.watched && entry.generation == generation {
+                entry.status = Some((Instant::now(), status));
+            }
+        }
+    }
+
+    /// Drop the project's cached status
+    pub fn invalidate(&self, project_id: &str) {
+        if let Some(entry) = self.0.lock().unwrap().get_mut(project_id) {
+            entry.generation += 1;
+            entry.status = None;
+        }
+    }
+
+    /// Start caching a project whose watcher now reports its changes
+    pub fn watch(&self, project_id: &str) {
+        self.0.lock().unwrap().entry(project_id.to_string()).or_default().watched = true;
+    }
+
+    /// Stop caching a project once nothing watches it
+    pub fn unwatch(&self, project_id: &str) {
+        if let Some(entry) = self.0.lock().unwrap().get_mut(project_id) {
+            entry.watched = false;
+            entry.generation += 1;
+            entry.status = None;
+        }
+    }
+}
+
+/// Whether a change to `path` can affect `git status`
+///
+/// Ignored files still count, since tracked files can match ignore patterns.
+/// Only writes to git's object store are skipped; they always come with an
+/// index or ref update.
+pub fn affects_status(path: &Path, project_path: &Path) -> bool {
+    let mut components = path.strip_prefix(project_path).unwrap_or(path).components();
+    !matches!(
+        (components.next(), components.next()),
+        (Some(Component::Normal(git)), Some(Component::Normal(objects))) if git == ".git" && objects == "objects"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(path: &str) -> Arc<Vec<GitFileStatus>> {
+        Arc::new(vec![GitFileStatus { path: path.to_string(), status: "modified".to_string() }])
+    }
+
+    #[test]
+    fn test_only_watched_projects_are_cached() {
+        let cache = GitStatusCache::default();
+        cache.insert("p1", cache.generation("p1"), status("a.rs"));
+        assert!(cache.get("p1").is_none());
+
+        cache.watch("p1");
+        cache.insert("p1", cache.generation("p1"), status("a.rs"));
+        assert_eq!(cache.get("p1").unwrap()[0].path, "a.rs");
+
+        cache.unwatch("p1");
+        assert!(cache.get("p1").is_none());
+    }
+
+    #[test]
+    fn test_invalidation_discards_in_flight_reads() {
+        let cache = GitStatusCache::default();
+        cache.watch("p1");
+
+        let generation = cache.generation("p1");
+        cache.invalidate("p1");
+        cache.insert("p1", generation, status("stale.rs"));
+        assert!(cache.get("p1").is_none());
+
+        cache.insert("p1", cache.generation("p1"), status("fresh.rs"));
+        cache.invalidate("p1");
+        assert!(cache.get("p1").is_none());
+    }
+
+    #[test]
+    fn test_affects_status() {
+        let root = Path::new("/work/app");
+        assert!(affects_status(Path::new("/work/app/src/main.rs"), root));
+        assert!(affects_status(Path::new("/work/app/.git/index"), root));
+        assert!(affects_status(Path::new("/work/app/node_modules/x/index.js"), root));
+        assert!(!affects_status(Path::new("/work/app/.git/objects/ab/cdef"), root));
+    }
+}
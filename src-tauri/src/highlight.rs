@@ -0,0 +1,251 @@
+// Syntax Highlighting
+// Token spans for files and diffs computed with syntect, so the webview needn't
+// highlight large files itself
+
+use crate::side_by_side_diff::SideBySideDiff;
+use serde::Serialize;
+use std::sync::OnceLock;
+use syntect::parsing::{ParseState, Scope, ScopeStack, SyntaxReference, SyntaxSet};
+
+/// Lines past this many are left unhighlighted
+const MAX_LINES: usize = 50_000;
+
+/// Longer lines (usually minified code) get no tokens
+const MAX_LINE_CHARS: usize = 5_000;
+
+/// Scope prefixes and the token kind they map to, most specific first
+const KINDS: &[(&str, &str)] = &[
+    ("comment", "comment"),
+    ("string", "string"),
+    ("constant.character.escape", "string"),
+    ("constant.numeric", "number"),
+    ("constant", "constant"),
+    ("keyword.operator", "operator"),
+    ("keyword", "keyword"),
+    // Grammars put `fn`, `let`, `class` and `function` under storage.type
+    ("storage", "keyword"),
+    ("entity.name.function", "function"),
+    ("support.function", "function"),
+    ("variable.function", "function"),
+    ("entity.name.tag", "tag"),
+    ("entity.other.attribute-name", "attribute"),
+    ("entity.name", "type"),
+    ("support.type", "type"),
+    ("support.class", "type"),
+    ("variable.parameter", "parameter"),
+    ("variable", "variable"),
+];
+
+/// A highlighted `[start, end)` range of a line, in UTF-16 code units so it
+/// slices JS strings directly
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TokenSpan {
+    pub start: usize,
+    pub end: usize,
+    /// `comment`, `string`, `number`, `constant`, `operator`, `keyword`, `type`,
+    /// `function`, `tag`, `attribute`, `parameter` or `variable`
+    pub kind: &'static str,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HighlightTokens {
+    /// Name of the syntax used, e.g. "Rust"; "Plain Text" when none fit
+    pub syntax: String,
+    /// The spans of each line, in order; unhighlighted text has none
+    pub lines: Vec<Vec<TokenSpan>>,
+    /// Whether lines past [`MAX_LINES`] were left out
+    pub truncated: bool,
+}
+
+fn syntax_set() -> &'static SyntaxSet {
+    static SYNTAXES: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAXES.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+fn kinds() -> &'static [(Scope, &'static str)] {
+    static SCOPES: OnceLock<Vec<(Scope, &'static str)>> = OnceLock::new();
+    SCOPES.get_or_init(|| {
+        KINDS
+            .iter()
+            .map(|(prefix, kind)| (Scope::new(prefix).expect("valid scope"), *kind))
+            .collect()
+    })
+}
+
+/// The syntax for `file_path`, by file name, extension, then first line
+fn find_syntax(file_path: &str, first_line: &str) -> &'static SyntaxReference {
+    let syntaxes = syntax_set();
+    let name = file_path.rsplit(['/', '\\']).next().unwrap_or(file_path);
+    let ext = name.rsplit_once('.').map_or("", |(_, ext)| ext);
+    // The bundled syntaxes have no TypeScript; JavaScript's covers most of it
+    let ext = match ext {
+        "ts" | "tsx" | "mts" | "cts" => "js",
+        other => other,
+    };
+    syntaxes
+        .find_syntax_by_extension(name)
+        .or_else(|| syntaxes.find_syntax_by_extension(ext))
+        .or_else(|| syntaxes.find_syntax_by_first_line(first_line))
+        .unwrap_or_else(|| syntaxes.find_syntax_plain_text())
+}
+
+/// The token kind of the innermost scope on `stack` that has one
+fn kind_of(stack: &ScopeStack) -> Option<&'static str> {
+    stack.as_slice().iter().rev().find_map(|scope| {
+        kinds().iter().find(|(prefix, _)| prefix.is_prefix_of(*scope)).map(|(_, kind)| *kind)
+    })
+}
+
+/// Highlight consecutive lines of one file, parsing them as a whole so
+/// multi-line constructs like block comments carry over
+fn highlight_lines<'a>(syntax: &SyntaxReference, lines: impl IntoIterator<Item = &'a str>) -> Vec<Vec<TokenSpan>> {
+    let syntaxes = syntax_set();
+    let mut state = ParseState::new(syntax);
+    let mut stack = ScopeStack::new();
+    let mut highlighted = Vec::new();
+
+    for line in lines {
+        if line.len() > MAX_LINE_CHARS {
+            highlighted.push(Vec::new());
+            continue;
+        }
+        // The "newlines" syntaxes expect each line to end with one
+        let with_newline = format!("{}\n", line);
+        let Ok(ops) = state.parse_line(&with_newline, syntaxes) else {
+            highlighted.push(Vec::new());
+            continue;
+        };
+
+        let mut spans: Vec<TokenSpan> = Vec::new();
+        let (mut byte_pos, mut utf16_pos) = (0, 0);
+        let mut push = |stack: &ScopeStack, until: usize, byte_pos: &mut usize, utf16_pos: &mut usize| {
+            let until = until.min(line.len());
+            if until <= *byte_pos {
+                return;
+            }
+            let end = *utf16_pos + line[*byte_pos..until].encode_utf16().count();
+            if let Some(kind) = kind_of(stack) {
+                match spans.last_mut() {
+                    Some(last) if last.kind == kind && last.end == *utf16_pos => last.end = end,
+                    _ => spans.push(TokenSpan { start: *utf16_pos, end, kind }),
+                }
+            }
+            *byte_pos = until;
+            *utf16_pos = end;
+        };
+        for (offset, op) in ops {
+            push(&stack, offset, &mut byte_pos, &mut utf16_pos);
+            if stack.apply(&op).is_err() {
+                break;
+            }
+        }
+        push(&stack, line.len(), &mut byte_pos, &mut utf16_pos);
+        highlighted.push(spans);
+    }
+    highlighted
+}
+
+/// Token spans for each line of `text`, a file at `file_path`
+pub fn highlight(file_path: &str, text: &str) -> HighlightTokens {
+    let syntax = find_syntax(file_path, text.lines().next().unwrap_or_default());
+    let truncated = text.lines().nth(MAX_LINES).is_some();
+    HighlightTokens {
+        syntax: syntax.name.clone(),
+        lines: highlight_lines(syntax, text.lines().take(MAX_LINES)),
+        truncated,
+    }
+}
+
+/// Fill in the tokens of each row of `diff`
+///
+/// Each side of a hunk is highlighted as one run of lines, which reads a
+/// hunk's context right unless it starts inside a multi-line construct.
+pub fn highlight_diff(diff: &mut SideBySideDiff) {
+    for hunk in &mut diff.hunks {
+        let first_line = hunk
+            .rows
+            .iter()
+            .find_map(|row| row.new.as_ref().or(row.old.as_ref()))
+            .map_or("", |line| line.content.as_str());
+        let syntax = find_syntax(&diff.file_path, first_line);
+
+        let old = highlight_lines(syntax, hunk.rows.iter().filter_map(|row| row.old.as_ref()).map(|l| l.content.as_str()));
+        let new = highlight_lines(syntax, hunk.rows.iter().filter_map(|row| row.new.as_ref()).map(|l| l.content.as_str()));
+        let mut old = old.into_iter();
+        let mut new = new.into_iter();
+        for row in &mut hunk.rows {
+            if let Some(line) = row.old.as_mut() {
+                line.tokens = old.next().unwrap_or_default();
+            }
+            if let Some(line) = row.new.as_mut() {
+                line.tokens = new.next().unwrap_or_default();
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+use crate::db::Database;
+use tauri::State;
+
+/// Token spans for each line of a project file, so large files can be shown
+/// highlighted without a tokenizer running in the webview
+#[tauri::command]
+pub async fn get_highlight_tokens(
+    db: State<'_, Database>,
+    project_id: String,
+    file_path: String,
+) -> Result<HighlightTokens, String> {
+    let decoded = crate::commands::read_file_with_encoding(db, project_id, file_path.clone()).await?;
+    tokio::task::spawn_blocking(move || highlight(&file_path, &decoded.content))
+        .await
+        .map_err(|e| format!("Failed to highlight file: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds_of(tokens: &[TokenSpan], line: &str) -> Vec<(String, &'static str)> {
+        let units: Vec<u16> = line.encode_utf16().collect();
+        tokens
+            .iter()
+            .map(|t| (String::from_utf16(&units[t.start..t.end]).unwrap(), t.kind))
+            .collect()
+    }
+
+    #[test]
+    fn test_highlight_rust() {
+        let text = "/* a\n   b */\nfn main() {\n    let s = \"é\"; // x\n}";
+        let tokens = highlight("src/main.rs", text);
+        assert_eq!(tokens.syntax, "Rust");
+        assert_eq!(tokens.lines.len(), 5);
+        assert!(!tokens.truncated);
+
+        // The block comment carries over to the second line
+        assert_eq!(kinds_of(&tokens.lines[1], "   b */"), vec![("   b */".to_string(), "comment")]);
+        let fn_line = kinds_of(&tokens.lines[2], "fn main() {");
+        assert!(fn_line.contains(&("fn".to_string(), "keyword")));
+        assert!(fn_line.contains(&("main".to_string(), "function")));
+        let let_line = kinds_of(&tokens.lines[3], "    let s = \"é\"; // x");
+        assert!(let_line.contains(&("\"é\"".to_string(), "string")));
+        assert!(let_line.contains(&("// x".to_string(), "comment")));
+    }
+
+    #[test]
+    fn test_highlight_diff_fills_both_sides() {
+        let diff = "@@ -1,2 +1,2 @@\n // note\n-let a = 1;\n+let a = \"one\";\n";
+        let mut diff = crate::side_by_side_diff::side_by_side("app.ts", diff);
+        highlight_diff(&mut diff);
+
+        let rows = &diff.hunks[0].rows;
+        assert_eq!(rows[0].old.as_ref().unwrap().tokens[0].kind, "comment");
+        let new = rows[1].new.as_ref().unwrap();
+        assert!(kinds_of(&new.tokens, &new.content).contains(&("\"one\"".to_string(), "string")));
+        let old = rows[1].old.as_ref().unwrap();
+        assert!(kinds_of(&old.tokens, &old.content).contains(&("1".to_string(), "number")));
+    }
+}
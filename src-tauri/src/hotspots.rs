@@ -0,0 +1,214 @@
+// Code Hotspots
+// Ranks a project's source files by how often they change (git history) times
+// how complex they are, pointing at the riskiest code to look at first
+
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::cli_command;
+
+/// Days of history counted as churn
+pub const HISTORY_DAYS: i64 = 180;
+
+/// Files returned, highest score first
+pub const MAX_HOTSPOTS: usize = 50;
+
+/// Bigger files are generated or vendored more often than not
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+
+const SOURCE_EXTENSIONS: &[&str] = &[
+    "rs", "ts", "tsx", "js", "jsx", "mjs", "cjs", "py", "go", "java", "kt", "kts", "swift", "c", "h", "cc", "cpp",
+    "hpp", "cs", "rb", "php", "scala", "dart", "vue", "svelte", "lua", "sh",
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Hotspot {
+    /// Relative to the project root, with `/` separators
+    pub path: String,
+    /// Commits that touched the file in the last [`HISTORY_DAYS`]
+    pub commits: u32,
+    /// Lines added plus removed by those commits
+    pub lines_changed: u64,
+    /// Unix timestamp of the latest of them
+    pub last_changed: Option<i64>,
+    /// Non-blank lines
+    pub lines: usize,
+    /// 1 plus the branches, loops and boolean operators in the file, a rough
+    /// cyclomatic complexity summed over its functions
+    pub complexity: usize,
+    /// 0 to 1: churn times complexity, each relative to the project's highest
+    pub score: f64,
+}
+
+/// A file's history in the window
+#[derive(Debug, Default, Clone, PartialEq)]
+struct Churn {
+    commits: u32,
+    lines_changed: u64,
+    last_changed: Option<i64>,
+}
+
+/// Parse `git log --numstat --format=@%ct` output into churn per path
+fn parse_numstat(log: &str) -> HashMap<String, Churn> {
+    let mut churn: HashMap<String, Churn> = HashMap::new();
+    let mut time = None;
+    for line in log.lines() {
+        if let Some(timestamp) = line.strip_prefix('@') {
+            time = timestamp.trim().parse::<i64>().ok();
+            continue;
+        }
+        let mut fields = line.splitn(3, '\t');
+        let (Some(added), Some(removed), Some(path)) = (fields.next(), fields.next(), fields.next()) else {
+            continue;
+        };
+        // Binary files show "-" for both counts
+        let lines = added.parse::<u64>().unwrap_or(0) + removed.parse::<u64>().unwrap_or(0);
+        let entry = churn.entry(path.to_string()).or_default();
+        entry.commits += 1;
+        entry.lines_changed += lines;
+        // Commits come newest first
+        entry.last_changed = entry.last_changed.or(time);
+    }
+    churn
+}
+
+fn churn(root: &Path, since_days: i64) -> Result<HashMap<String, Churn>> {
+    let output = cli_command::std_command("git", ["log", "--no-merges", "--no-renames", "--numstat", "--format=@%ct"])
+        .arg(format!("--since={}.days", since_days))
+        .current_dir(root)
+        .output()
+        .context("Failed to execute git log")?;
+    if !output.status.success() {
+        anyhow::bail!("git log failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(parse_numstat(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Non-blank lines and estimated complexity of source `text`
+fn measure(text: &str) -> (usize, usize) {
+    static DECISIONS: OnceLock<Regex> = OnceLock::new();
+    let decisions = DECISIONS.get_or_init(|| {
+        Regex::new(r"\b(if|elif|for|foreach|while|case|catch|except|match|when|guard)\b|&&|\|\|").expect("valid regex")
+    });
+
+    let mut lines = 0;
+    let mut complexity = 1;
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() {
+            continue;
+        }
+        lines += 1;
+        let is_comment = ["//", "/*", "*", "# ", "--"].iter().any(|prefix| line.starts_with(prefix)) || line == "#";
+        if !is_comment {
+            complexity += decisions.find_iter(line).count();
+        }
+    }
+    (lines, complexity)
+}
+
+/// The project's hotspots; without git history, files rank by complexity alone
+pub fn hotspots(root: &Path, since_days: i64, limit: usize) -> Result<Vec<Hotspot>> {
+    let history = if crate::agent_comparison::is_git_repo(&root.to_string_lossy()) {
+        // A repo without commits yet has no history to read
+        churn(root, since_days).unwrap_or_else(|e| {
+            log::warn!("No churn for {}: {}", root.display(), e);
+            HashMap::new()
+        })
+    } else {
+        HashMap::new()
+    };
+
+    let mut files = Vec::new();
+    // Honours .gitignore, so build output and dependencies stay out
+    for entry in ignore::WalkBuilder::new(root).build().flatten() {
+        let path = entry.path();
+        let is_source = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| SOURCE_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()));
+        let size = entry.metadata().map(|m| m.len()).unwrap_or(u64::MAX);
+        if !is_source || !entry.file_type().is_some_and(|t| t.is_file()) || size > MAX_FILE_BYTES {
+            continue;
+        }
+        let Ok(text) = std::fs::read_to_string(path) else {
+            continue;
+        };
+        let Ok(relative) = path.strip_prefix(root) else {
+            continue;
+        };
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        let (lines, complexity) = measure(&text);
+        let churn = history.get(&relative).cloned().unwrap_or_default();
+        files.push(Hotspot {
+            path: relative,
+            commits: churn.commits,
+            lines_changed: churn.lines_changed,
+            last_changed: churn.last_changed,
+            lines,
+            complexity,
+            score: 0.0,
+        });
+    }
+
+    let max_commits = files.iter().map(|f| f.commits).max().unwrap_or(0);
+    let max_complexity = files.iter().map(|f| f.complexity).max().unwrap_or(0).max(1);
+    for file in &mut files {
+        let churn = if max_commits == 0 { 1.0 } else { file.commits as f64 / max_commits as f64 };
+        file.score = churn * file.complexity as f64 / max_complexity as f64;
+    }
+    files.sort_by(|a, b| b.score.total_cmp(&a.score).then(b.complexity.cmp(&a.complexity)));
+    files.truncate(limit);
+    Ok(files)
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+use crate::db::Database;
+use tauri::State;
+
+/// The project's riskiest files: those changed most in the last 180 days,
+/// weighted by their complexity
+#[tauri::command]
+pub async fn get_code_hotspots(db: State<'_, Database>, project_id: String) -> Result<Vec<Hotspot>, String> {
+    let root: String = sqlx::query_scalar("SELECT root_path FROM projects WHERE id = ?")
+        .bind(&project_id)
+        .fetch_optional(db.pool())
+        .await
+        .map_err(|e| format!("Failed to load project: {}", e))?
+        .ok_or_else(|| format!("Project not found: {}", project_id))?;
+
+    tokio::task::spawn_blocking(move || hotspots(Path::new(&root), HISTORY_DAYS, MAX_HOTSPOTS))
+        .await
+        .map_err(|e| format!("Failed to analyze hotspots: {}", e))?
+        .map_err(|e| format!("Failed to analyze hotspots: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_numstat() {
+        let log = "@1700000200\n\n3\t1\tsrc/a.rs\n-\t-\tlogo.png\n@1700000100\n\n10\t0\tsrc/a.rs\n2\t2\tsrc/b.rs\n";
+        let churn = parse_numstat(log);
+        assert_eq!(
+            churn["src/a.rs"],
+            Churn { commits: 2, lines_changed: 14, last_changed: Some(1700000200) }
+        );
+        assert_eq!(churn["logo.png"].lines_changed, 0);
+        assert_eq!(churn["src/b.rs"].last_changed, Some(1700000100));
+    }
+
+    #[test]
+    fn test_measure() {
+        let text = "fn f(a: bool, b: bool) {\n    // if this were a branch\n\n    if a && b {\n        for _ in 0..3 {}\n    }\n    let verify = 1;\n}\n";
+        // if, &&, for; "verify" and the comment don't count
+        assert_eq!(measure(text), (7, 4));
+    }
+}
@@ -0,0 +1,326 @@
+// Background Jobs
+// Runs long operations (analysis, exports, clones) as jobs with persistent
+// rows, progress events and cancellation
+
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{oneshot, watch, Semaphore};
+
+use crate::models::Job;
+
+/// Event emitted with the job whenever its status or progress changes
+pub const JOB_UPDATED_EVENT: &str = "job-updated";
+/// Error a cancelled job finishes with
+pub const CANCELLED: &str = "Cancelled";
+/// Jobs running at once; the rest wait in the queue
+const MAX_CONCURRENT_JOBS: usize = 3;
+/// Finished jobs are forgotten after this long
+const JOB_RETENTION_SECS: i64 = 30 * 24 * 60 * 60;
+
+/// Runs jobs and keeps their rows current
+#[derive(Clone)]
+pub struct JobManager {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    pool: SqlitePool,
+    /// Called with the job after every change, e.g. to emit an event
+    on_update: Box<dyn Fn(&Job) + Send + Sync>,
+    slots: Semaphore,
+    /// Cancellation switches of queued and running jobs
+    cancels: Mutex<HashMap<String, watch::Sender<bool>>>,
+}
+
+/// Handed to a job to report its progress
+///
+/// A cancelled job is dropped at its next `.await`.
+#[derive(Clone)]
+pub struct JobContext {
+    id: String,
+    manager: JobManager,
+    cancelled: watch::Receiver<bool>,
+}
+
+impl JobContext {
+    /// Record a progress message, and the fraction done (0.0 to 1.0) when known
+    pub async fn progress(&self, fraction: Option<f64>, message: impl Into<String>) {
+        let result = sqlx::query("UPDATE jobs SET progress = COALESCE(?, progress), message = ? WHERE id = ?")
+            .bind(fraction.map(|f| f.clamp(0.0, 1.0)))
+            .bind(message.into())
+            .bind(&self.id)
+            .execute(&self.manager.inner.pool)
+            .await;
+        if let Err(e) = result {
+            log::warn!("Failed to record progress of job {}: {}", self.id, e);
+        }
+        self.manager.notify(&self.id).await;
+    }
+
+}
+
+impl JobManager {
+    /// Create a manager that calls `on_update` with each job as it changes
+    pub fn new(pool: SqlitePool, on_update: impl Fn(&Job) + Send + Sync + 'static) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                pool,
+                on_update: Box::new(on_update),
+                slots: Semaphore::new(MAX_CONCURRENT_JOBS),
+                cancels: Mutex::new(HashMap::new()),
+            }),
+        }
+    }
+
+    /// Fail jobs a previous run left unfinished and forget old finished ones
+    pub async fn recover(&self) -> Result<(), sqlx::Error> {
+        let now = chrono::Utc::now().timestamp();
+        let interrupted = sqlx::query(
+            "UPDATE jobs SET status = 'failed', error = 'Interrupted when the app closed', finished_at = ?
+             WHERE status IN ('queued', 'running')",
+        )
+        .bind(now)
+        .execute(&self.inner.pool)
+        .await?
+        .rows_affected();
+        if interrupted > 0 {
+            log::warn!("Marked {} interrupted jobs as failed", interrupted);
+        }
+
+        sqlx::query("DELETE FROM jobs WHERE finished_at < ?")
+            .bind(now - JOB_RETENTION_SECS)
+            .execute(&self.inner.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Run a job and wait for its result
+    ///
+    /// The caller still gets progress events and can be cancelled with
+    /// `cancel_job`, in which case this returns [`CANCELLED`].
+    pub async fn run<T, F, Fut>(&self, kind: &str, project_id: Option<String>, title: String, job: F) -> Result<T, String>
+    where
+        T: Serialize + Send + 'static,
+        F: FnOnce(JobContext) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<T, String>> + Send + 'static,
+    {
+        let (_, outcome) = self.start(kind, project_id, title, job).await?;
+        outcome.await.unwrap_or_else(|_| Err("Job stopped unexpectedly".to_string()))
+    }
+
+    /// Cancel a queued or running job; returns false if it already finished
+    pub fn cancel(&self, job_id: &str) -> bool {
+        match self.inner.cancels.lock().unwrap().get(job_id) {
+            Some(cancel) => cancel.send(true).is_ok(),
+            None => false,
+        }
+    }
+
+    /// Recent jobs, newest first, optionally only a project's
+    pub async fn list(&self, project_id: Option<&str>, limit: i64) -> Result<Vec<Job>, sqlx::Error> {
+        sqlx::query_as::<_, Job>(
+            "SELECT * FROM jobs WHERE ?1 IS NULL OR project_id = ?1 ORDER BY created_at DESC, rowid DESC LIMIT ?2",
+        )
+        .bind(project_id)
+        .bind(limit)
+        .fetch_all(&self.inner.pool)
+        .await
+    }
+
+    async fn start<T, F, Fut>(
+        &self,
+        kind: &str,
+        project_id: Option<String>,
+        title: String,
+        job: F,
+    ) -> Result<(Job, oneshot::Receiver<Result<T, String>>), String>
+    where
+        T: Serialize + Send + 'static,
+        F: FnOnce(JobContext) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<T, String>> + Send + 'static,
+    {
+        let record = Job::new(kind, project_id, title);
+        sqlx::query("INSERT INTO jobs (id, kind, project_id, title, status, created_at) VALUES (?, ?, ?, ?, ?, ?)")
+            .bind(&record.id)
+            .bind(&record.kind)
+            .bind(&record.project_id)
+            .bind(&record.title)
+            .bind(&record.status)
+            .bind(record.created_at)
+            .execute(&self.inner.pool)
+            .await
+            .map_err(|e| format!("Failed to create job: {}", e))?;
+        log::info!("Queued {} job {}: {}", record.kind, record.id, record.title);
+
+        let (cancel, cancelled) = watch::channel(false);
+        self.inner.cancels.lock().unwrap().insert(record.id.clone(), cancel);
+        (self.inner.on_update)(&record);
+
+        let (outcome_tx, outcome_rx) = oneshot::channel();
+        let manager = self.clone();
+        let context = JobContext { id: record.id.clone(), manager: self.clone(), cancelled };
+        tokio::spawn(async move {
+            let id = context.id.clone();
+            let outcome = manager.execute(context, job).await;
+            manager.inner.cancels.lock().unwrap().remove(&id);
+            let _ = outcome_tx.send(outcome);
+        });
+        Ok((record, outcome_rx))
+    }
+
+    async fn execute<T, F, Fut>(&self, context: JobContext, job: F) -> Result<T, String>
+    where
+        T: Serialize,
+        F: FnOnce(JobContext) -> Fut,
+        Fut: Future<Output = Result<T, String>>,
+    {
+        let id = context.id.clone();
+        let mut cancelled = context.cancelled.clone();
+        let work = async {
+            let _slot = self.inner.slots.acquire().await.map_err(|e| e.to_string())?;
+            self.set_running(&id).await;
+            job(context).await
+        };
+        let outcome = tokio::select! {
+            outcome = work => outcome,
+            _ = cancelled.wait_for(|cancelled| *cancelled) => Err(CANCELLED.to_string()),
+        };
+
+        let (status, result, error) = match &outcome {
+            Ok(value) => ("completed", serde_json::to_string(value).ok(), None),
+            Err(e) if e == CANCELLED => ("cancelled", None, None),
+            Err(e) => ("failed", None, Some(e.as_str())),
+        };
+        log::info!("Job {} {}", id, status);
+        let update = sqlx::query(
+            "UPDATE jobs SET status = ?, progress = COALESCE(?, progress), result = ?, error = ?, finished_at = ?
+             WHERE id = ?",
+        )
+        .bind(status)
+        .bind(outcome.is_ok().then_some(1.0))
+        .bind(result)
+        .bind(error)
+        .bind(chrono::Utc::now().timestamp())
+        .bind(&id)
+        .execute(&self.inner.pool)
+        .await;
+        if let Err(e) = update {
+            log::error!("Failed to record outcome of job {}: {}", id, e);
+        }
+        self.notify(&id).await;
+        outcome
+    }
+
+    async fn set_running(&self, id: &str) {
+        let result = sqlx::query("UPDATE jobs SET status = 'running', started_at = ? WHERE id = ?")
+            .bind(chrono::Utc::now().timestamp())
+            .bind(id)
+            .execute(&self.inner.pool)
+            .await;
+        if let Err(e) = result {
+            log::warn!("Failed to mark job {} running: {}", id, e);
+        }
+        self.notify(id).await;
+    }
+
+    /// Tell the listener about the job's current row
+    async fn notify(&self, id: &str) {
+        match sqlx::query_as::<_, Job>("SELECT * FROM jobs WHERE id = ?").bind(id).fetch_optional(&self.inner.pool).await {
+            Ok(Some(job)) => (self.inner.on_update)(&job),
+            Ok(None) => {}
+            Err(e) => log::warn!("Failed to load job {}: {}", id, e),
+        }
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+use tauri::State;
+
+/// Recent jobs, newest first; all projects' unless `project_id` is given
+#[tauri::command]
+pub async fn list_jobs(
+    jobs: State<'_, JobManager>,
+    project_id: Option<String>,
+    limit: Option<i64>,
+) -> Result<Vec<Job>, String> {
+    jobs.list(project_id.as_deref(), limit.unwrap_or(50))
+        .await
+        .map_err(|e| format!("Failed to list jobs: {}", e))
+}
+
+/// Cancel a queued or running job; returns false if it had already finished
+#[tauri::command]
+pub async fn cancel_job(jobs: State<'_, JobManager>, job_id: String) -> Result<bool, String> {
+    log::info!("Cancelling job {}", job_id);
+    Ok(jobs.cancel(&job_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    type Updates = Arc<Mutex<Vec<(String, String)>>>;
+
+    async fn test_manager() -> (JobManager, Updates, tempfile::TempDir) {
+        let (pool, db) = crate::db::test_pool().await;
+
+        let updates = Arc::new(Mutex::new(Vec::new()));
+        let seen = updates.clone();
+        let manager = JobManager::new(pool, move |job| seen.lock().unwrap().push((job.id.clone(), job.status.clone())));
+        (manager, updates, db)
+    }
+
+    #[tokio::test]
+    async fn test_run_records_progress_and_result() {
+        let (manager, updates, _db) = test_manager().await;
+
+        let value = manager
+            .run("test", None, "Add".to_string(), |ctx| async move {
+                ctx.progress(Some(0.5), "Halfway").await;
+                Ok(2 + 2)
+            })
+            .await
+            .unwrap();
+        assert_eq!(value, 4);
+
+        let job = &manager.list(None, 10).await.unwrap()[0];
+        assert_eq!((job.status.as_str(), job.progress, job.result.as_deref()), ("completed", Some(1.0), Some("4")));
+        assert_eq!(job.message.as_deref(), Some("Halfway"));
+        let statuses: Vec<String> = updates.lock().unwrap().iter().map(|(_, s)| s.clone()).collect();
+        assert_eq!(statuses.first().map(String::as_str), Some("queued"));
+        assert_eq!(statuses.last().map(String::as_str), Some("completed"));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_and_failure() {
+        let (manager, _, _db) = test_manager().await;
+
+        let sleeper = manager.clone();
+        let sleeping = tokio::spawn(async move {
+            sleeper
+                .run("test", None, "Sleep".to_string(), |_| async {
+                    tokio::time::sleep(Duration::from_secs(60)).await;
+                    Ok(())
+                })
+                .await
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let job = manager.list(None, 10).await.unwrap().remove(0);
+        assert!(manager.cancel(&job.id));
+        let failed: Result<(), String> = manager.run("test", None, "Fail".to_string(), |_| async { Err("boom".to_string()) }).await;
+        assert_eq!(failed.unwrap_err(), "boom");
+        assert_eq!(sleeping.await.unwrap().unwrap_err(), CANCELLED);
+        let jobs = manager.list(None, 10).await.unwrap();
+        let status = |title: &str| jobs.iter().find(|j| j.title == title).map(|j| j.status.clone()).unwrap();
+        assert_eq!(status("Sleep"), "cancelled");
+        assert_eq!(status("Fail"), "failed");
+        assert!(!manager.cancel(&job.id));
+    }
+}
@@ -0,0 +1,507 @@
+// Linting and Formatting
+// Runs the linters and formatters a project is set up for (eslint, clippy,
+// ruff, prettier, rustfmt), with findings parsed into one shape and formatter
+// changes previewed as diffs
+
+use serde::Serialize;
+use similar::TextDiff;
+use std::path::Path;
+use std::process::{Output, Stdio};
+use tokio::io::AsyncWriteExt;
+
+use crate::jobs::JobContext;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Linter {
+    Eslint,
+    Clippy,
+    Ruff,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Formatter {
+    Prettier,
+    Rustfmt,
+    Ruff,
+}
+
+/// A problem a linter reported
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct LintFinding {
+    pub tool: Linter,
+    /// Relative to the project root
+    pub file: String,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+    /// error, warning or info
+    pub severity: String,
+    pub rule: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LintReport {
+    pub tools: Vec<Linter>,
+    pub findings: Vec<LintFinding>,
+    /// Tools that couldn't run, e.g. because they aren't installed
+    pub errors: Vec<String>,
+}
+
+/// A file a formatter changes, or would change
+#[derive(Debug, Clone, Serialize)]
+pub struct FormattedFile {
+    pub tool: Formatter,
+    /// Relative to the project root
+    pub path: String,
+    /// Unified diff of the change; empty once applied
+    pub diff: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FormatReport {
+    pub tools: Vec<Formatter>,
+    pub applied: bool,
+    pub files: Vec<FormattedFile>,
+    pub errors: Vec<String>,
+}
+
+const ESLINT_CONFIGS: &[&str] = &[
+    "eslint.config.js",
+    "eslint.config.mjs",
+    "eslint.config.cjs",
+    "eslint.config.ts",
+    ".eslintrc",
+    ".eslintrc.js",
+    ".eslintrc.cjs",
+    ".eslintrc.json",
+    ".eslintrc.yml",
+    ".eslintrc.yaml",
+];
+
+const PRETTIER_CONFIGS: &[&str] = &[
+    ".prettierrc",
+    ".prettierrc.json",
+    ".prettierrc.yml",
+    ".prettierrc.yaml",
+    ".prettierrc.js",
+    ".prettierrc.cjs",
+    ".prettierrc.mjs",
+    "prettier.config.js",
+    "prettier.config.cjs",
+    "prettier.config.mjs",
+];
+
+/// The linters the project at `root` is set up for
+pub fn detect_linters(root: &Path) -> Vec<Linter> {
+    let mut linters = Vec::new();
+    if has_node_tool(root, "eslint", ESLINT_CONFIGS) {
+        linters.push(Linter::Eslint);
+    }
+    if root.join("Cargo.toml").is_file() {
+        linters.push(Linter::Clippy);
+    }
+    if uses_ruff(root) {
+        linters.push(Linter::Ruff);
+    }
+    linters
+}
+
+/// The formatters the project at `root` is set up for
+pub fn detect_formatters(root: &Path) -> Vec<Formatter> {
+    let mut formatters = Vec::new();
+    if has_node_tool(root, "prettier", PRETTIER_CONFIGS) {
+        formatters.push(Formatter::Prettier);
+    }
+    if root.join("Cargo.toml").is_file() {
+        formatters.push(Formatter::Rustfmt);
+    }
+    if uses_ruff(root) {
+        formatters.push(Formatter::Ruff);
+    }
+    formatters
+}
+
+/// Whether package.json depends on `package` or one of its config files exists
+fn has_node_tool(root: &Path, package: &str, configs: &[&str]) -> bool {
+    let in_package_json = std::fs::read_to_string(root.join("package.json"))
+        .ok()
+        .and_then(|text| serde_json::from_str::<serde_json::Value>(&text).ok())
+        .is_some_and(|json| {
+            ["dependencies", "devDependencies"]
+                .iter()
+                .any(|section| json.get(section).and_then(|deps| deps.get(package)).is_some())
+        });
+    in_package_json || configs.iter().any(|config| root.join(config).is_file())
+}
+
+fn uses_ruff(root: &Path) -> bool {
+    root.join("ruff.toml").is_file()
+        || root.join(".ruff.toml").is_file()
+        || std::fs::read_to_string(root.join("pyproject.toml")).is_ok_and(|text| text.contains("[tool.ruff"))
+}
+
+/// Run each linter the project uses and collect what they report
+pub async fn run_linters(root: &Path, ctx: &JobContext) -> LintReport {
+    let tools = detect_linters(root);
+    let mut report = LintReport { tools: tools.clone(), findings: Vec::new(), errors: Vec::new() };
+
+    for (index, tool) in tools.iter().enumerate() {
+        ctx.progress(Some(index as f64 / tools.len() as f64), format!("Running {:?}", tool)).await;
+        let output = match tool {
+            Linter::Eslint => run(root, "npx", &["--no-install", "eslint", "--format", "json", "."]).await,
+            Linter::Clippy => run(root, "cargo", &["clippy", "--quiet", "--message-format=json"]).await,
+            Linter::Ruff => run(root, "ruff", &["check", "--output-format=json", "."]).await,
+        };
+        let parsed = output.and_then(|output| {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            match tool {
+                Linter::Eslint => parse_eslint(root, &stdout),
+                Linter::Clippy => Ok(parse_clippy(&stdout)),
+                Linter::Ruff => parse_ruff(root, &stdout),
+            }
+            .map_err(|e| format!("{}: {}", e, stderr_summary(&output)))
+        });
+        match parsed {
+            Ok(findings) => report.findings.extend(findings),
+            Err(e) => report.errors.push(format!("{:?}: {}", tool, e)),
+        }
+    }
+
+    report
+}
+
+/// Preview or apply each formatter the project uses
+pub async fn run_formatters(root: &Path, apply: bool, ctx: &JobContext) -> FormatReport {
+    let tools = detect_formatters(root);
+    let mut report = FormatReport { tools: tools.clone(), applied: apply, files: Vec::new(), errors: Vec::new() };
+
+    for (index, tool) in tools.iter().enumerate() {
+        ctx.progress(Some(index as f64 / tools.len() as f64), format!("Running {:?}", tool)).await;
+        match format_with(root, *tool, apply).await {
+            Ok(files) => report.files.extend(files),
+            Err(e) => report.errors.push(format!("{:?}: {}", tool, e)),
+        }
+    }
+
+    report
+}
+
+async fn format_with(root: &Path, tool: Formatter, apply: bool) -> Result<Vec<FormattedFile>, String> {
+    // Listing needs no changes made, and tells apply mode which files it touched
+    let output = match tool {
+        Formatter::Prettier => run(root, "npx", &["--no-install", "prettier", "--list-different", "."]).await?,
+        Formatter::Rustfmt => run(root, "cargo", &["fmt", "--all", "--", "--check", "-l"]).await?,
+        Formatter::Ruff => run(root, "ruff", &["format", "--check", "."]).await?,
+    };
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let paths: Vec<String> = stdout
+        .lines()
+        .filter_map(|line| match tool {
+            Formatter::Ruff => line.strip_prefix("Would reformat: "),
+            _ => Some(line),
+        })
+        .map(|path| relative_path(root, path.trim()))
+        .filter(|path| !path.is_empty())
+        .collect();
+    // Each tool exits non-zero when files need formatting, so only an error
+    // without any listed files means it failed
+    if paths.is_empty() && !output.status.success() {
+        return Err(stderr_summary(&output));
+    }
+
+    if apply {
+        let output = match tool {
+            Formatter::Prettier => run(root, "npx", &["--no-install", "prettier", "--write", "."]).await?,
+            Formatter::Rustfmt => run(root, "cargo", &["fmt", "--all"]).await?,
+            Formatter::Ruff => run(root, "ruff", &["format", "."]).await?,
+        };
+        if !output.status.success() {
+            return Err(stderr_summary(&output));
+        }
+        return Ok(paths.into_iter().map(|path| FormattedFile { tool, path, diff: String::new() }).collect());
+    }
+
+    let edition = rust_edition(root);
+    let mut files = Vec::new();
+    for path in paths {
+        let original = std::fs::read_to_string(root.join(&path)).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+        let formatted = match tool {
+            Formatter::Prettier => {
+                pipe(root, "npx", &["--no-install", "prettier", "--stdin-filepath", &path], &original).await?
+            }
+            Formatter::Rustfmt => pipe(root, "rustfmt", &["--emit", "stdout", "--edition", &edition], &original).await?,
+            Formatter::Ruff => pipe(root, "ruff", &["format", "--stdin-filename", &path, "-"], &original).await?,
+        };
+        let diff = unified_diff(&path, &original, &formatted);
+        if !diff.is_empty() {
+            files.push(FormattedFile { tool, path, diff });
+        }
+    }
+    Ok(files)
+}
+
+/// Run `program` in `root` and wait for its output
+async fn run(root: &Path, program: &str, args: &[&str]) -> Result<Output, String> {
+    let program = which::which(program).map_err(|_| format!("{} isn't installed or not on PATH", program))?;
+    tokio::process::Command::new(program)
+        .args(args)
+        .current_dir(root)
+        .stdin(Stdio::null())
+        .kill_on_drop(true)
+        .output()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Run `program` with `input` on stdin and return what it prints
+async fn pipe(root: &Path, program: &str, args: &[&str], input: &str) -> Result<String, String> {
+    let program = which::which(program).map_err(|_| format!("{} isn't installed or not on PATH", program))?;
+    let mut child = tokio::process::Command::new(program)
+        .args(args)
+        .current_dir(root)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| e.to_string())?;
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let input = input.to_string();
+    // Written alongside reading, so a large file can't fill both pipes
+    let writer = tokio::spawn(async move { stdin.write_all(input.as_bytes()).await });
+    let output = child.wait_with_output().await.map_err(|e| e.to_string())?;
+    let _ = writer.await;
+    if !output.status.success() {
+        return Err(stderr_summary(&output));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+fn stderr_summary(output: &Output) -> String {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let summary = stderr.trim().lines().last().unwrap_or("").to_string();
+    if summary.is_empty() {
+        format!("exited with {}", output.status)
+    } else {
+        summary
+    }
+}
+
+/// The crate's edition, which rustfmt needs when reading stdin
+fn rust_edition(root: &Path) -> String {
+    let manifest = std::fs::read_to_string(root.join("Cargo.toml"))
+        .ok()
+        .and_then(|text| text.parse::<toml::Table>().ok());
+    manifest
+        .as_ref()
+        .and_then(|manifest| {
+            manifest
+                .get("package")
+                .or_else(|| manifest.get("workspace").and_then(|w| w.get("package")))
+                .and_then(|package| package.get("edition"))
+                .and_then(|edition| edition.as_str())
+        })
+        .unwrap_or("2021")
+        .to_string()
+}
+
+fn relative_path(root: &Path, path: &str) -> String {
+    let path = Path::new(path);
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .trim_start_matches("./")
+        .replace('\\', "/")
+}
+
+fn unified_diff(path: &str, original: &str, formatted: &str) -> String {
+    if original == formatted {
+        return String::new();
+    }
+    TextDiff::from_lines(original, formatted)
+        .unified_diff()
+        .context_radius(3)
+        .header(&format!("a/{}", path), &format!("b/{}", path))
+        .to_string()
+}
+
+fn parse_eslint(root: &Path, stdout: &str) -> Result<Vec<LintFinding>, String> {
+    let files: Vec<serde_json::Value> =
+        serde_json::from_str(stdout).map_err(|e| format!("Unexpected eslint output: {}", e))?;
+    let mut findings = Vec::new();
+    for file in &files {
+        let path = relative_path(root, file["filePath"].as_str().unwrap_or_default());
+        for message in file["messages"].as_array().into_iter().flatten() {
+            findings.push(LintFinding {
+                tool: Linter::Eslint,
+                file: path.clone(),
+                line: message["line"].as_u64().map(|n| n as u32),
+                column: message["column"].as_u64().map(|n| n as u32),
+                severity: if message["severity"].as_u64() == Some(2) { "error" } else { "warning" }.to_string(),
+                rule: message["ruleId"].as_str().map(String::from),
+                message: message["message"].as_str().unwrap_or_default().to_string(),
+            });
+        }
+    }
+    Ok(findings)
+}
+
+/// Clippy prints one JSON message per line; the same warning shows up once per
+/// target that compiles the file, so repeats are dropped
+fn parse_clippy(stdout: &str) -> Vec<LintFinding> {
+    let mut findings: Vec<LintFinding> = Vec::new();
+    for line in stdout.lines() {
+        let Ok(json) = serde_json::from_str::<serde_json::Value>(line) else { continue };
+        if json["reason"] != "compiler-message" {
+            continue;
+        }
+        let message = &json["message"];
+        let severity = match message["level"].as_str() {
+            Some("error") => "error",
+            Some("warning") => "warning",
+            _ => continue,
+        };
+        let Some(span) = message["spans"]
+            .as_array()
+            .and_then(|spans| spans.iter().find(|span| span["is_primary"] == true))
+        else {
+            // Summaries like "3 warnings emitted" have no location
+            continue;
+        };
+        let finding = LintFinding {
+            tool: Linter::Clippy,
+            file: span["file_name"].as_str().unwrap_or_default().replace('\\', "/"),
+            line: span["line_start"].as_u64().map(|n| n as u32),
+            column: span["column_start"].as_u64().map(|n| n as u32),
+            severity: severity.to_string(),
+            rule: message["code"]["code"].as_str().map(String::from),
+            message: message["message"].as_str().unwrap_or_default().to_string(),
+        };
+        if !findings.contains(&finding) {
+            findings.push(finding);
+        }
+    }
+    findings
+}
+
+fn parse_ruff(root: &Path, stdout: &str) -> Result<Vec<LintFinding>, String> {
+    let diagnostics: Vec<serde_json::Value> =
+        serde_json::from_str(stdout).map_err(|e| format!("Unexpected ruff output: {}", e))?;
+    Ok(diagnostics
+        .iter()
+        .map(|diagnostic| LintFinding {
+            tool: Linter::Ruff,
+            file: relative_path(root, diagnostic["filename"].as_str().unwrap_or_default()),
+            line: diagnostic["location"]["row"].as_u64().map(|n| n as u32),
+            column: diagnostic["location"]["column"].as_u64().map(|n| n as u32),
+            // Ruff doesn't grade its rules; syntax errors have no code
+            severity: if diagnostic["code"].is_null() { "error" } else { "warning" }.to_string(),
+            rule: diagnostic["code"].as_str().map(String::from),
+            message: diagnostic["message"].as_str().unwrap_or_default().to_string(),
+        })
+        .collect())
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+use crate::db::Database;
+use crate::jobs::JobManager;
+use tauri::State;
+
+async fn project_root(db: &Database, project_id: &str) -> Result<String, String> {
+    sqlx::query_scalar("SELECT root_path FROM projects WHERE id = ?")
+        .bind(project_id)
+        .fetch_optional(db.pool())
+        .await
+        .map_err(|e| format!("Failed to load project: {}", e))?
+        .ok_or_else(|| format!("Project not found: {}", project_id))
+}
+
+/// Run the project's linters as a job
+#[tauri::command]
+pub async fn run_linter(
+    db: State<'_, Database>,
+    jobs: State<'_, JobManager>,
+    project_id: String,
+) -> Result<LintReport, String> {
+    let root = project_root(&db, &project_id).await?;
+    jobs.run("lint", Some(project_id), "Lint project".to_string(), |ctx| async move {
+        Ok(run_linters(Path::new(&root), &ctx).await)
+    })
+    .await
+}
+
+/// Run the project's formatters as a job
+///
+/// Without `apply` nothing is changed and each file comes with the diff that
+/// formatting would apply.
+#[tauri::command]
+pub async fn run_formatter(
+    db: State<'_, Database>,
+    jobs: State<'_, JobManager>,
+    project_id: String,
+    apply: bool,
+) -> Result<FormatReport, String> {
+    let root = project_root(&db, &project_id).await?;
+    let title = if apply { "Format project" } else { "Preview formatting" };
+    jobs.run("format", Some(project_id), title.to_string(), move |ctx| async move {
+        Ok(run_formatters(Path::new(&root), apply, &ctx).await)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_tools() {
+        let dir = std::env::temp_dir().join(format!("lint-detect-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("package.json"), r#"{"devDependencies": {"eslint": "^9.0.0"}}"#).unwrap();
+        std::fs::write(dir.join(".prettierrc"), "{}").unwrap();
+        std::fs::write(dir.join("pyproject.toml"), "[tool.ruff]\nline-length = 100\n").unwrap();
+
+        assert_eq!(detect_linters(&dir), vec![Linter::Eslint, Linter::Ruff]);
+        assert_eq!(detect_formatters(&dir), vec![Formatter::Prettier, Formatter::Ruff]);
+
+        std::fs::write(dir.join("Cargo.toml"), "[package]\nname = \"x\"\nedition = \"2018\"\n").unwrap();
+        assert!(detect_linters(&dir).contains(&Linter::Clippy));
+        assert_eq!(rust_edition(&dir), "2018");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_parse_findings() {
+        let root = Path::new("/work/app");
+        let eslint = r#"[{"filePath": "/work/app/src/a.ts", "messages": [
+            {"ruleId": "no-unused-vars", "severity": 2, "message": "'x' is unused", "line": 3, "column": 7}]}]"#;
+        let findings = parse_eslint(root, eslint).unwrap();
+        assert_eq!(findings[0].file, "src/a.ts");
+        assert_eq!((findings[0].severity.as_str(), findings[0].line), ("error", Some(3)));
+
+        let message = serde_json::json!({"reason": "compiler-message", "message": {
+            "level": "warning", "message": "redundant clone", "code": {"code": "clippy::redundant_clone"},
+            "spans": [{"file_name": "src/main.rs", "line_start": 5, "column_start": 9, "is_primary": true}]}});
+        let clippy = format!("{}\n{}\n{{\"reason\": \"build-finished\"}}\n", message, message);
+        let findings = parse_clippy(&clippy);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].rule.as_deref(), Some("clippy::redundant_clone"));
+
+        let ruff = r#"[{"code": "F401", "message": "unused import", "filename": "/work/app/m.py",
+            "location": {"row": 1, "column": 8}}]"#;
+        assert_eq!(parse_ruff(root, ruff).unwrap()[0].file, "m.py");
+    }
+
+    #[test]
+    fn test_unified_diff() {
+        assert_eq!(unified_diff("a.rs", "x\n", "x\n"), "");
+        let diff = unified_diff("a.rs", "fn a(){}\n", "fn a() {}\n");
+        assert!(diff.starts_with("--- a/a.rs\n+++ b/a.rs\n"), "{}", diff);
+        assert!(diff.contains("-fn a(){}\n+fn a() {}\n"));
+    }
+}
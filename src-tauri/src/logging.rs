@@ -0,0 +1,279 @@
+// Application Logging
+// Writes JSON log lines, with secrets masked, to daily rotated files under the app data dir and reads them back for bug reports
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, EnvFilter};
+
+use crate::redaction::Redactor;
+
+const LOG_FILE_PREFIX: &str = "ateliercode";
+const LOG_FILE_SUFFIX: &str = "log";
+/// Days of log files kept
+const MAX_LOG_FILES: usize = 7;
+/// Filter used when RUST_LOG isn't set
+const DEFAULT_FILTER: &str = "info,sqlx=warn";
+
+/// Keeps the background log writer flushing until the app exits
+static GUARD: OnceLock<WorkerGuard> = OnceLock::new();
+
+/// Directory the log files are written to
+pub fn log_dir(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join("logs")
+}
+
+/// Send `log` and `tracing` records to stderr and to the rotating log files
+///
+/// Levels follow RUST_LOG, defaulting to info.
+pub fn init(app_data_dir: &Path) -> Result<()> {
+    let dir = log_dir(app_data_dir);
+    std::fs::create_dir_all(&dir).context("Failed to create log directory")?;
+
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix(LOG_FILE_SUFFIX)
+        .max_log_files(MAX_LOG_FILES)
+        .build(&dir)
+        .context("Failed to create log file")?;
+    let (writer, guard) = tracing_appender::non_blocking(appender);
+    let _ = GUARD.set(guard);
+
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(DEFAULT_FILTER));
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer().with_writer(|| RedactingWriter(std::io::stderr())))
+        .with(
+            fmt::layer()
+                .json()
+                .with_current_span(true)
+                .with_span_list(false)
+                .with_writer(move || RedactingWriter(writer.clone())),
+        )
+        .try_init()
+        .context("Failed to install logger")?;
+    Ok(())
+}
+
+/// Masks secrets in formatted log records; each write is one whole record
+struct RedactingWriter<W>(W);
+
+impl<W: Write> Write for RedactingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match std::str::from_utf8(buf) {
+            Ok(text) => self.0.write_all(Redactor::builtin().redact(text).as_bytes())?,
+            Err(_) => self.0.write_all(buf)?,
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.0.flush()
+    }
+}
+
+/// One line of the application log
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct LogEntry {
+    pub timestamp: String,
+    /// "ERROR", "WARN", "INFO", "DEBUG" or "TRACE"
+    pub level: String,
+    pub target: String,
+    pub message: String,
+    /// Structured fields of the event and its span, e.g. `session_id` and `project_id`
+    pub fields: Map<String, Value>,
+}
+
+fn level_rank(level: &str) -> u8 {
+    match level.to_ascii_uppercase().as_str() {
+        "ERROR" => 0,
+        "WARN" => 1,
+        "INFO" => 2,
+        "DEBUG" => 3,
+        _ => 4,
+    }
+}
+
+/// Parse a JSON log line written by the file layer
+fn parse_log_line(line: &str) -> Option<LogEntry> {
+    let mut record: Map<String, Value> = serde_json::from_str(line).ok()?;
+    let text = |record: &mut Map<String, Value>, key: &str| match record.remove(key) {
+        Some(Value::String(s)) => s,
+        _ => String::new(),
+    };
+
+    let timestamp = text(&mut record, "timestamp");
+    let level = text(&mut record, "level");
+    let target = text(&mut record, "target");
+
+    let mut fields = match record.remove("fields") {
+        Some(Value::Object(fields)) => fields,
+        _ => Map::new(),
+    };
+    let message = match fields.remove("message") {
+        Some(Value::String(s)) => s,
+        Some(other) => other.to_string(),
+        None => String::new(),
+    };
+    // Metadata added when bridging from the `log` crate
+    fields.retain(|key, _| !key.starts_with("log."));
+    if let Some(Value::Object(span)) = record.remove("span") {
+        for (key, value) in span {
+            if key != "name" {
+                fields.entry(key).or_insert(value);
+            }
+        }
+    }
+
+    Some(LogEntry {
+        timestamp,
+        level,
+        target,
+        message,
+        fields,
+    })
+}
+
+/// Log files, newest first
+fn log_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with(LOG_FILE_PREFIX) && n.ends_with(LOG_FILE_SUFFIX))
+        })
+        .collect();
+    // Rotated names end in the date, so they sort by age
+    files.sort_unstable_by(|a, b| b.cmp(a));
+    files
+}
+
+/// The last `lines` entries at `level` or more severe, oldest first
+pub fn read_recent(dir: &Path, level: &str, lines: usize) -> Vec<LogEntry> {
+    let max_rank = level_rank(level);
+    let mut recent = Vec::new();
+
+    for file in log_files(dir) {
+        let Ok(contents) = std::fs::read_to_string(&file) else {
+            continue;
+        };
+        let matching = contents
+            .lines()
+            .rev()
+            .filter_map(parse_log_line)
+            .filter(|entry| level_rank(&entry.level) <= max_rank);
+        recent.extend(matching.take(lines - recent.len()));
+        if recent.len() == lines {
+            break;
+        }
+    }
+
+    recent.reverse();
+    recent
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+use tauri::{AppHandle, Manager};
+
+fn app_log_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| log_dir(&dir))
+        .map_err(|e| format!("Failed to get app data dir: {}", e))
+}
+
+/// Get the most recent log entries
+///
+/// `level` is the least severe level included (default "info"); `lines`
+/// caps how many entries are returned (default 500).
+#[tauri::command]
+pub async fn get_app_logs(
+    app: AppHandle,
+    level: Option<String>,
+    lines: Option<usize>,
+) -> Result<Vec<LogEntry>, String> {
+    let dir = app_log_dir(&app)?;
+    let level = level.unwrap_or_else(|| "info".to_string());
+    let lines = lines.unwrap_or(500);
+
+    tokio::task::spawn_blocking(move || read_recent(&dir, &level, lines))
+        .await
+        .map_err(|e| format!("Failed to read logs: {}", e))
+}
+
+/// Open the log folder in the system file manager
+#[tauri::command]
+pub async fn open_log_folder(app: AppHandle) -> Result<String, String> {
+    let dir = app_log_dir(&app)?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create log folder: {}", e))?;
+
+    let opener = if cfg!(target_os = "windows") {
+        "explorer"
+    } else if cfg!(target_os = "macos") {
+        "open"
+    } else {
+        "xdg-open"
+    };
+    std::process::Command::new(opener)
+        .arg(&dir)
+        .spawn()
+        .map_err(|e| format!("Failed to open log folder: {}", e))?;
+
+    Ok(dir.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LINE: &str = r#"{"timestamp":"2026-01-02T03:04:05.000Z","level":"WARN","fields":{"message":"Agent exited","log.target":"ateliercode::agent_manager","code":2},"target":"ateliercode::agent_manager","span":{"session_id":"s1","project_id":"p1","name":"agent_session"}}"#;
+
+    #[test]
+    fn test_parse_log_line() {
+        let entry = parse_log_line(LINE).unwrap();
+        assert_eq!(entry.level, "WARN");
+        assert_eq!(entry.target, "ateliercode::agent_manager");
+        assert_eq!(entry.message, "Agent exited");
+        assert_eq!(entry.fields.get("session_id"), Some(&Value::from("s1")));
+        assert_eq!(entry.fields.get("code"), Some(&Value::from(2)));
+        assert!(!entry.fields.contains_key("log.target"));
+        assert!(!entry.fields.contains_key("name"));
+        assert!(parse_log_line("not json").is_none());
+    }
+
+    #[test]
+    fn test_read_recent_filters_and_orders() {
+        let dir = std::env::temp_dir().join(format!("ateliercode-logs-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let line = |level: &str, message: &str| {
+            format!(r#"{{"timestamp":"t","level":"{}","fields":{{"message":"{}"}},"target":"app"}}"#, level, message)
+        };
+        let older = [line("ERROR", "old error"), line("INFO", "old info")].join("\n");
+        let newer = [line("INFO", "a"), line("DEBUG", "b"), line("WARN", "c")].join("\n");
+        std::fs::write(dir.join("ateliercode.2026-01-01.log"), older).unwrap();
+        std::fs::write(dir.join("ateliercode.2026-01-02.log"), newer).unwrap();
+
+        let messages = |entries: Vec<LogEntry>| entries.into_iter().map(|e| e.message).collect::<Vec<_>>();
+        assert_eq!(messages(read_recent(&dir, "info", 3)), ["old info", "a", "c"]);
+        assert_eq!(messages(read_recent(&dir, "warn", 10)), ["old error", "c"]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,775 @@
+// Language Servers
+// Runs the project's language servers (rust-analyzer, typescript-language-server)
+// over stdio and keeps their diagnostics, so errors an agent introduces show up
+// without a build
+
+use crate::models::LanguageServerConfig;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, Command};
+use tokio::sync::{mpsc, oneshot, Notify};
+use tokio::time::Instant;
+
+/// How long a server gets to answer `initialize`
+const INIT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long to wait for diagnostics before returning what's known
+const DIAGNOSTICS_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Quiet time after which a server with no work in progress counts as done
+const SETTLE: Duration = Duration::from_millis(750);
+
+/// A problem a language server reported in a file
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Diagnostic {
+    /// Relative to the project root, with `/` separators
+    pub file: String,
+    /// 1-based
+    pub line: u32,
+    /// 1-based, in UTF-16 code units as LSP counts them
+    pub column: u32,
+    pub end_line: u32,
+    pub end_column: u32,
+    /// `error`, `warning`, `info` or `hint`
+    pub severity: String,
+    pub message: String,
+    /// What produced it, e.g. `rustc` or `typescript`
+    pub source: Option<String>,
+    pub code: Option<String>,
+}
+
+/// The servers that suit the project's files and are installed
+pub fn default_servers(root: &Path) -> Vec<LanguageServerConfig> {
+    let mut configs = Vec::new();
+    if root.join("Cargo.toml").is_file() && which::which("rust-analyzer").is_ok() {
+        configs.push(LanguageServerConfig {
+            language: "rust".to_string(),
+            command: "rust-analyzer".to_string(),
+            args: Vec::new(),
+            file_extensions: vec!["rs".to_string()],
+        });
+    }
+    let is_js = root.join("tsconfig.json").is_file() || root.join("package.json").is_file();
+    if is_js && which::which("typescript-language-server").is_ok() {
+        configs.push(LanguageServerConfig {
+            language: "typescript".to_string(),
+            command: "typescript-language-server".to_string(),
+            args: vec!["--stdio".to_string()],
+            file_extensions: ["ts", "tsx", "js", "jsx", "mjs", "cjs"].map(String::from).to_vec(),
+        });
+    }
+    configs
+}
+
+/// The servers running for one project
+struct ProjectServers {
+    configs: Vec<LanguageServerConfig>,
+    servers: Vec<Arc<LanguageServer>>,
+    /// Labels of the windows that asked for diagnostics; the servers stop
+    /// when the last one closes
+    holders: HashSet<String>,
+}
+
+/// Starts language servers per project on first use and answers for their diagnostics
+#[derive(Default)]
+pub struct LspManager {
+    projects: Mutex<HashMap<String, ProjectServers>>,
+}
+
+impl LspManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Diagnostics for `file`, or for everything the servers have checked when
+    /// `None`, starting the project's servers on behalf of `holder` (a window label)
+    ///
+    /// A file is sent to its server when it's new to it or has changed, and the
+    /// server gets until it goes quiet, or [`DIAGNOSTICS_TIMEOUT`], to report.
+    pub async fn diagnostics(
+        &self,
+        project_id: &str,
+        root: &Path,
+        configs: &[LanguageServerConfig],
+        file: Option<&Path>,
+        holder: &str,
+    ) -> Result<Vec<Diagnostic>, String> {
+        if configs.is_empty() {
+            return Err("No language server is configured or installed for this project".to_string());
+        }
+        let servers = self.ensure(project_id, root, configs, holder).await?;
+
+        let mut diagnostics = Vec::new();
+        match file {
+            Some(file) => {
+                for server in servers.iter().filter(|server| server.handles(file)) {
+                    diagnostics.extend(server.check(file).await?);
+                }
+            }
+            None => {
+                for server in &servers {
+                    diagnostics.extend(server.check_all().await);
+                }
+            }
+        }
+        diagnostics.sort_by(|a, b| (&a.file, a.line, a.column).cmp(&(&b.file, b.line, b.column)));
+        Ok(diagnostics)
+    }
+
+    /// The project's running servers, started if missing, exited or configured differently
+    async fn ensure(
+        &self,
+        project_id: &str,
+        root: &Path,
+        configs: &[LanguageServerConfig],
+        holder: &str,
+    ) -> Result<Vec<Arc<LanguageServer>>, String> {
+        {
+            let mut projects = self.projects.lock().unwrap();
+            if let Some(project) = projects.get_mut(project_id) {
+                if project.configs == configs && project.servers.iter().all(|server| server.is_running()) {
+                    project.holders.insert(holder.to_string());
+                    return Ok(project.servers.clone());
+                }
+                projects.remove(project_id);
+            }
+        }
+
+        let mut servers = Vec::new();
+        let mut errors = Vec::new();
+        for config in configs {
+            match LanguageServer::start(config, root).await {
+                Ok(server) => servers.push(server),
+                Err(e) => {
+                    log::warn!("Language server {} failed for project {}: {}", config.command, project_id, e);
+                    errors.push(e);
+                }
+            }
+        }
+        if servers.is_empty() {
+            return Err(errors.join("; "));
+        }
+        log::info!("Started {} language server(s) for project {}", servers.len(), project_id);
+
+        // A concurrent call may have started servers meanwhile; the newer set wins
+        // and the older one is killed as it drops
+        self.projects.lock().unwrap().insert(
+            project_id.to_string(),
+            ProjectServers {
+                configs: configs.to_vec(),
+                servers: servers.clone(),
+                holders: HashSet::from([holder.to_string()]),
+            },
+        );
+        Ok(servers)
+    }
+
+    /// Shut down the project's servers; returns false if none were running
+    pub async fn stop(&self, project_id: &str) -> bool {
+        let Some(project) = self.projects.lock().unwrap().remove(project_id) else {
+            return false;
+        };
+        for server in project.servers {
+            server.shutdown().await;
+        }
+        log::info!("Stopped language servers for project {}", project_id);
+        true
+    }
+
+    /// Drop `holder` from every project, killing the servers no other window uses
+    pub fn release_holder(&self, holder: &str) {
+        self.projects.lock().unwrap().retain(|project_id, project| {
+            project.holders.remove(holder);
+            if project.holders.is_empty() {
+                log::info!("Stopping language servers for project {} after its window closed", project_id);
+            }
+            !project.holders.is_empty()
+        });
+    }
+}
+
+/// State the reader task shares with the server handle
+#[derive(Default)]
+struct Shared {
+    root: PathBuf,
+    pending: Mutex<HashMap<i64, oneshot::Sender<Result<Value, String>>>>,
+    /// Latest diagnostics per document URI, with the sequence number they arrived at
+    published: Mutex<HashMap<String, (u64, Vec<Diagnostic>)>>,
+    seq: AtomicU64,
+    /// `$/progress` tokens that have begun and not ended
+    progress: Mutex<HashSet<String>>,
+    /// Woken on new diagnostics and progress changes
+    changed: Notify,
+}
+
+impl Shared {
+    /// Act on a message from the server, returning the reply it needs, if any
+    fn handle(&self, message: Value) -> Option<Value> {
+        let method = message["method"].as_str();
+        match (message.get("id"), method) {
+            // Requests from the server; we don't take part beyond not blocking it
+            (Some(id), Some(method)) => {
+                let result = match method {
+                    "workspace/configuration" => {
+                        let items = message["params"]["items"].as_array().map_or(0, Vec::len);
+                        Value::Array(vec![Value::Null; items])
+                    }
+                    _ => Value::Null,
+                };
+                Some(json!({ "jsonrpc": "2.0", "id": id, "result": result }))
+            }
+            (Some(id), None) => {
+                let sender = id.as_i64().and_then(|id| self.pending.lock().unwrap().remove(&id));
+                if let Some(sender) = sender {
+                    let result = match message.get("error") {
+                        Some(error) => Err(error["message"].as_str().unwrap_or("Request failed").to_string()),
+                        None => Ok(message.get("result").cloned().unwrap_or(Value::Null)),
+                    };
+                    let _ = sender.send(result);
+                }
+                None
+            }
+            (None, Some("textDocument/publishDiagnostics")) => {
+                let params = &message["params"];
+                let uri = params["uri"].as_str()?;
+                let file = relative_file(&self.root, uri);
+                let diagnostics = params["diagnostics"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|diagnostic| to_diagnostic(&file, diagnostic))
+                    .collect();
+                let seq = self.seq.fetch_add(1, Ordering::SeqCst) + 1;
+                self.published.lock().unwrap().insert(uri.to_string(), (seq, diagnostics));
+                self.changed.notify_waiters();
+                None
+            }
+            (None, Some("$/progress")) => {
+                let token = message["params"]["token"].to_string();
+                let mut progress = self.progress.lock().unwrap();
+                match message["params"]["value"]["kind"].as_str() {
+                    Some("begin") => progress.insert(token),
+                    Some("end") => progress.remove(&token),
+                    _ => return None,
+                };
+                self.changed.notify_waiters();
+                None
+            }
+            (None, Some("window/logMessage")) => {
+                log::debug!("Language server: {}", message["params"]["message"].as_str().unwrap_or_default());
+                None
+            }
+            _ => None,
+        }
+    }
+
+    fn seq_of(&self, uri: &str) -> u64 {
+        self.published.lock().unwrap().get(uri).map_or(0, |(seq, _)| *seq)
+    }
+
+    fn is_busy(&self) -> bool {
+        !self.progress.lock().unwrap().is_empty()
+    }
+}
+
+/// A document the server has open: its version and the text it was last sent
+struct OpenDocument {
+    version: i64,
+    text: String,
+}
+
+/// One running language server
+struct LanguageServer {
+    config: LanguageServerConfig,
+    child: Mutex<Child>,
+    outgoing: mpsc::UnboundedSender<Value>,
+    next_id: AtomicI64,
+    shared: Arc<Shared>,
+    documents: tokio::sync::Mutex<HashMap<String, OpenDocument>>,
+}
+
+impl LanguageServer {
+    /// Spawn the server and go through the `initialize` handshake
+    async fn start(config: &LanguageServerConfig, root: &Path) -> Result<Arc<Self>, String> {
+        // Resolving first finds `.cmd` shims on Windows
+        let program = which::which(&config.command).map_err(|e| format!("{} not found: {}", config.command, e))?;
+        let mut child = Command::new(program)
+            .args(&config.args)
+            .current_dir(root)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| format!("Failed to start {}: {}", config.command, e))?;
+        let mut stdin = child.stdin.take().ok_or("Language server has no stdin")?;
+        let stdout = child.stdout.take().ok_or("Language server has no stdout")?;
+        let stderr = child.stderr.take().ok_or("Language server has no stderr")?;
+
+        let shared = Arc::new(Shared { root: root.to_path_buf(), ..Default::default() });
+        let (outgoing, mut queue) = mpsc::unbounded_channel::<Value>();
+
+        tokio::spawn(async move {
+            while let Some(message) = queue.recv().await {
+                if stdin.write_all(&encode(&message)).await.is_err() || stdin.flush().await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        // The last line of stderr says why a server that fails to start did
+        let (last_line_tx, last_line) = oneshot::channel();
+        let name = config.command.clone();
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(stderr).lines();
+            let mut last = None;
+            while let Ok(Some(line)) = lines.next_line().await {
+                log::debug!("{}: {}", name, line);
+                last = Some(line);
+            }
+            let _ = last_line_tx.send(last);
+        });
+
+        let reader_shared = shared.clone();
+        let replies = outgoing.clone();
+        let name = config.command.clone();
+        tokio::spawn(async move {
+            let mut reader = BufReader::new(stdout);
+            loop {
+                match read_message(&mut reader).await {
+                    Ok(Some(message)) => {
+                        if let Some(reply) = reader_shared.handle(message) {
+                            let _ = replies.send(reply);
+                        }
+                    }
+                    Ok(None) => break,
+                    Err(e) => {
+                        log::warn!("Unreadable message from {}: {}", name, e);
+                        break;
+                    }
+                }
+            }
+            log::info!("Language server {} exited", name);
+            for (_, sender) in reader_shared.pending.lock().unwrap().drain() {
+                let _ = sender.send(Err("Language server exited".to_string()));
+            }
+            reader_shared.changed.notify_waiters();
+        });
+
+        let server = Arc::new(Self {
+            config: config.clone(),
+            child: Mutex::new(child),
+            outgoing,
+            next_id: AtomicI64::new(1),
+            shared,
+            documents: tokio::sync::Mutex::new(HashMap::new()),
+        });
+
+        let root_uri = path_to_uri(root);
+        let name = root.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        let params = json!({
+            "processId": std::process::id(),
+            "clientInfo": { "name": "AtelierCode" },
+            "rootUri": root_uri,
+            "rootPath": root,
+            "workspaceFolders": [{ "uri": root_uri, "name": name }],
+            "capabilities": {
+                "textDocument": {
+                    "synchronization": { "didSave": true },
+                    "publishDiagnostics": {},
+                },
+                "window": { "workDoneProgress": true },
+                "workspace": { "configuration": true, "workspaceFolders": true },
+            },
+        });
+        if let Err(e) = server.request("initialize", params, INIT_TIMEOUT).await {
+            server.child.lock().unwrap().start_kill().ok();
+            let last_line = tokio::time::timeout(Duration::from_secs(1), last_line).await;
+            return Err(match last_line {
+                Ok(Ok(Some(line))) => format!("{}: {}", e, line.trim()),
+                _ => e,
+            });
+        }
+        server.notify("initialized", json!({}));
+        Ok(server)
+    }
+
+    fn is_running(&self) -> bool {
+        matches!(self.child.lock().unwrap().try_wait(), Ok(None))
+    }
+
+    fn handles(&self, file: &Path) -> bool {
+        file.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| self.config.file_extensions.iter().any(|known| known.eq_ignore_ascii_case(ext)))
+    }
+
+    async fn request(&self, method: &str, params: Value, timeout: Duration) -> Result<Value, String> {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let (sender, receiver) = oneshot::channel();
+        self.shared.pending.lock().unwrap().insert(id, sender);
+        self.outgoing
+            .send(json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }))
+            .map_err(|_| "Language server exited".to_string())?;
+        match tokio::time::timeout(timeout, receiver).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err("Language server exited".to_string()),
+            Err(_) => {
+                self.shared.pending.lock().unwrap().remove(&id);
+                Err(format!("{} didn't answer {}", self.config.command, method))
+            }
+        }
+    }
+
+    fn notify(&self, method: &str, params: Value) {
+        let _ = self.outgoing.send(json!({ "jsonrpc": "2.0", "method": method, "params": params }));
+    }
+
+    /// Send `file` to the server if it's new to it or changed on disk since;
+    /// returns whether anything was sent
+    ///
+    /// Each send is followed by `didSave`, which is what makes rust-analyzer
+    /// run `cargo check`.
+    async fn sync(&self, file: &Path) -> Result<bool, String> {
+        let uri = path_to_uri(file);
+        let read = tokio::fs::read_to_string(file).await;
+        let mut documents = self.documents.lock().await;
+        let text = match read {
+            Ok(text) => text,
+            Err(e) => {
+                if documents.remove(&uri).is_some() {
+                    self.notify("textDocument/didClose", json!({ "textDocument": { "uri": uri } }));
+                }
+                return Err(format!("Failed to read {}: {}", file.display(), e));
+            }
+        };
+
+        match documents.get_mut(&uri) {
+            Some(document) if document.text == text => return Ok(false),
+            Some(document) => {
+                document.version += 1;
+                self.notify(
+                    "textDocument/didChange",
+                    json!({
+                        "textDocument": { "uri": uri, "version": document.version },
+                        "contentChanges": [{ "text": text }],
+                    }),
+                );
+                document.text = text;
+            }
+            None => {
+                let ext = file.extension().and_then(|ext| ext.to_str()).unwrap_or_default();
+                self.notify(
+                    "textDocument/didOpen",
+                    json!({
+                        "textDocument": {
+                            "uri": uri,
+                            "languageId": language_id(&self.config, ext),
+                            "version": 1,
+                            "text": text,
+                        },
+                    }),
+                );
+                documents.insert(uri.clone(), OpenDocument { version: 1, text });
+            }
+        }
+        self.notify("textDocument/didSave", json!({ "textDocument": { "uri": uri } }));
+        Ok(true)
+    }
+
+    /// Diagnostics for `file`, waiting for fresh ones if it had to be sent
+    async fn check(&self, file: &Path) -> Result<Vec<Diagnostic>, String> {
+        let uri = path_to_uri(file);
+        let before = self.shared.seq_of(&uri);
+        if self.sync(file).await? || before == 0 {
+            self.settle(|shared| shared.seq_of(&uri) > before).await;
+        }
+        Ok(self.shared.published.lock().unwrap().get(&uri).map(|(_, d)| d.clone()).unwrap_or_default())
+    }
+
+    /// Every diagnostic reported, after re-sending open files that changed
+    async fn check_all(&self) -> Vec<Diagnostic> {
+        let files: Vec<PathBuf> = self.documents.lock().await.keys().filter_map(|uri| uri_to_path(uri)).collect();
+        for file in files {
+            if let Err(e) = self.sync(&file).await {
+                log::debug!("Closed {}: {}", file.display(), e);
+            }
+        }
+        self.settle(|_| true).await;
+        self.shared.published.lock().unwrap().values().flat_map(|(_, d)| d.iter().cloned()).collect()
+    }
+
+    /// Wait until `ready` holds and the server has been idle for [`SETTLE`],
+    /// or until [`DIAGNOSTICS_TIMEOUT`]
+    async fn settle(&self, ready: impl Fn(&Shared) -> bool) {
+        let deadline = Instant::now() + DIAGNOSTICS_TIMEOUT;
+        loop {
+            let changed = self.shared.changed.notified();
+            let idle = ready(&self.shared) && !self.shared.is_busy();
+            let until = if idle { (Instant::now() + SETTLE).min(deadline) } else { deadline };
+            if tokio::time::timeout_at(until, changed).await.is_err() || !self.is_running() {
+                return;
+            }
+        }
+    }
+
+    /// Ask the server to exit, killing it if it doesn't
+    async fn shutdown(&self) {
+        if self.request("shutdown", Value::Null, Duration::from_secs(2)).await.is_ok() {
+            self.notify("exit", Value::Null);
+            let deadline = Instant::now() + Duration::from_secs(2);
+            while self.is_running() && Instant::now() < deadline {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        }
+        if let Err(e) = self.child.lock().unwrap().start_kill() {
+            log::debug!("Language server {} already gone: {}", self.config.command, e);
+        }
+    }
+}
+
+/// The LSP language id of a file, which differs for JSX and plain JavaScript
+fn language_id<'a>(config: &'a LanguageServerConfig, ext: &str) -> &'a str {
+    match ext {
+        "tsx" => "typescriptreact",
+        "jsx" => "javascriptreact",
+        "js" | "mjs" | "cjs" => "javascript",
+        _ => &config.language,
+    }
+}
+
+fn to_diagnostic(file: &str, value: &Value) -> Option<Diagnostic> {
+    let position = |key: &str, field: &str| value["range"][key][field].as_u64().map(|n| n as u32 + 1);
+    let severity = match value["severity"].as_u64() {
+        Some(2) => "warning",
+        Some(3) => "info",
+        Some(4) => "hint",
+        _ => "error",
+    };
+    Some(Diagnostic {
+        file: file.to_string(),
+        line: position("start", "line")?,
+        column: position("start", "character")?,
+        end_line: position("end", "line")?,
+        end_column: position("end", "character")?,
+        severity: severity.to_string(),
+        message: value["message"].as_str()?.to_string(),
+        source: value["source"].as_str().map(String::from),
+        code: match &value["code"] {
+            Value::String(code) => Some(code.clone()),
+            Value::Number(code) => Some(code.to_string()),
+            _ => None,
+        },
+    })
+}
+
+/// The path of `uri` relative to `root`, or the URI itself when it's outside
+fn relative_file(root: &Path, uri: &str) -> String {
+    uri_to_path(uri)
+        .and_then(|path| path.strip_prefix(root).ok().map(Path::to_path_buf))
+        .map(|path| path.to_string_lossy().replace('\\', "/"))
+        .unwrap_or_else(|| uri.to_string())
+}
+
+/// A `file://` URI for an absolute path
+fn path_to_uri(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let mut uri = String::from("file://");
+    if !path.starts_with('/') {
+        uri.push('/');
+    }
+    for byte in path.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~/:".contains(&byte) {
+            uri.push(byte as char);
+        } else {
+            uri.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    uri
+}
+
+fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    let encoded = uri.strip_prefix("file://")?.as_bytes();
+    let mut bytes = Vec::with_capacity(encoded.len());
+    let mut i = 0;
+    while i < encoded.len() {
+        let escaped = (encoded[i] == b'%')
+            .then(|| encoded.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match escaped {
+            Some(byte) => {
+                bytes.push(byte);
+                i += 3;
+            }
+            None => {
+                bytes.push(encoded[i]);
+                i += 1;
+            }
+        }
+    }
+    let path = String::from_utf8(bytes).ok()?;
+    // `/C:/src` on Windows
+    let path = match path.as_bytes() {
+        [b'/', drive, b':', ..] if cfg!(windows) && drive.is_ascii_alphabetic() => path[1..].to_string(),
+        _ => path,
+    };
+    Some(PathBuf::from(path))
+}
+
+/// A message framed with the `Content-Length` header LSP uses over stdio
+fn encode(message: &Value) -> Vec<u8> {
+    let body = message.to_string();
+    let mut framed = format!("Content-Length: {}\r\n\r\n", body.len()).into_bytes();
+    framed.extend_from_slice(body.as_bytes());
+    framed
+}
+
+/// The next framed message, or `None` at end of stream
+async fn read_message<R: AsyncBufRead + Unpin>(reader: &mut R) -> std::io::Result<Option<Value>> {
+    let invalid = |e: String| std::io::Error::new(std::io::ErrorKind::InvalidData, e);
+    let mut length = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await? == 0 {
+            return Ok(None);
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                length = Some(value.trim().parse::<usize>().map_err(|e| invalid(e.to_string()))?);
+            }
+        }
+    }
+    let length = length.ok_or_else(|| invalid("Missing Content-Length header".to_string()))?;
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).await?;
+    serde_json::from_slice(&body).map(Some).map_err(|e| invalid(e.to_string()))
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+use crate::db::Database;
+use crate::models::ProjectSettings;
+use tauri::State;
+
+/// Diagnostics from the project's language servers for `file` (relative to
+/// the project root), or for every file they've checked
+///
+/// Servers come from the project's `language_servers` setting, or from its
+/// files when that's empty. They start on first use and stop when the last
+/// window that asked closes.
+#[tauri::command]
+pub async fn get_diagnostics(
+    window: tauri::Window,
+    db: State<'_, Database>,
+    lsp: State<'_, LspManager>,
+    project_id: String,
+    file: Option<String>,
+) -> Result<Vec<Diagnostic>, String> {
+    let (root, settings): (String, Option<String>) =
+        sqlx::query_as("SELECT root_path, settings FROM projects WHERE id = ?")
+            .bind(&project_id)
+            .fetch_optional(db.pool())
+            .await
+            .map_err(|e| format!("Failed to load project: {}", e))?
+            .ok_or_else(|| format!("Project not found: {}", project_id))?;
+    let root = Path::new(&root)
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve project path: {}", e))?;
+
+    let mut configs = ProjectSettings::from_json(settings.as_deref()).language_servers;
+    if configs.is_empty() {
+        configs = default_servers(&root);
+    }
+    let file = file.map(|file| crate::project_paths::resolve_in_project(&root, file)).transpose()?;
+    lsp.diagnostics(&project_id, &root, &configs, file.as_deref(), window.label()).await
+}
+
+/// Shut down the project's language servers; returns false if none were running
+#[tauri::command]
+pub async fn stop_language_servers(lsp: State<'_, LspManager>, project_id: String) -> Result<bool, String> {
+    Ok(lsp.stop(&project_id).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_framing_round_trip() {
+        let first = json!({ "jsonrpc": "2.0", "id": 1, "result": { "ok": "é" } });
+        let second = json!({ "jsonrpc": "2.0", "method": "initialized", "params": {} });
+        let mut stream = encode(&first);
+        stream.extend(encode(&second));
+
+        let mut reader = BufReader::new(stream.as_slice());
+        assert_eq!(read_message(&mut reader).await.unwrap(), Some(first));
+        assert_eq!(read_message(&mut reader).await.unwrap(), Some(second));
+        assert_eq!(read_message(&mut reader).await.unwrap(), None);
+    }
+
+    #[test]
+    fn test_published_diagnostics_and_server_requests() {
+        let root = if cfg!(windows) { PathBuf::from(r"C:\work\my app") } else { PathBuf::from("/work/my app") };
+        let shared = Shared { root: root.clone(), ..Default::default() };
+        let uri = path_to_uri(&root.join("src").join("main.rs"));
+        assert!(uri.ends_with("/work/my%20app/src/main.rs"));
+
+        shared.handle(json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": {
+                "uri": uri,
+                "diagnostics": [{
+                    "range": { "start": { "line": 2, "character": 4 }, "end": { "line": 2, "character": 9 } },
+                    "severity": 1,
+                    "code": "E0308",
+                    "source": "rustc",
+                    "message": "mismatched types",
+                }],
+            },
+        }));
+        let (seq, diagnostics) = shared.published.lock().unwrap()[&uri].clone();
+        assert_eq!(seq, 1);
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic {
+                file: "src/main.rs".to_string(),
+                line: 3,
+                column: 5,
+                end_line: 3,
+                end_column: 10,
+                severity: "error".to_string(),
+                message: "mismatched types".to_string(),
+                source: Some("rustc".to_string()),
+                code: Some("E0308".to_string()),
+            }]
+        );
+
+        let reply = shared.handle(json!({
+            "jsonrpc": "2.0",
+            "id": 7,
+            "method": "workspace/configuration",
+            "params": { "items": [{ "section": "rust-analyzer" }, {}] },
+        }));
+        assert_eq!(reply, Some(json!({ "jsonrpc": "2.0", "id": 7, "result": [null, null] })));
+
+        shared.handle(json!({ "method": "$/progress", "params": { "token": "check", "value": { "kind": "begin" } } }));
+        assert!(shared.is_busy());
+        shared.handle(json!({ "method": "$/progress", "params": { "token": "check", "value": { "kind": "end" } } }));
+        assert!(!shared.is_busy());
+    }
+}
@@ -1,25 +1,90 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod activity;
 mod adapters;
 mod agent_adapter;
+mod agent_auth;
+mod agent_comparison;
+mod agent_defaults;
+mod agent_instructions;
 mod agent_manager;
 mod agents;
 mod ai_service;
+mod api_server;
+mod app_settings;
+mod chat_export;
+mod chat_stream;
+mod ci;
+mod claude_config;
+mod cli_command;
+mod code_review;
+mod command_gate;
 mod commands;
 mod commands_chat;
+mod commands_stats;
+mod commands_tts;
 mod commands_whisper;
+mod container_env;
+mod cost;
 mod db;
+mod deep_link;
+mod demo_project;
+mod dev_server;
+mod doctor;
+mod env_files;
+mod error;
+mod file_encoding;
 mod file_watcher;
+mod git_clone;
+mod git_status;
+mod highlight;
+mod hotspots;
+mod jobs;
+mod lint;
+mod logging;
+mod lsp;
+mod maintenance;
 mod models;
+mod notes;
+mod notifications;
+mod onboarding;
+mod open_files;
 mod output_parser;
+mod palette;
+mod patches;
 mod plugin;
 mod plugin_settings;
 mod plugins;
+mod prd_history;
 mod project_analyzer;
+mod project_bundle;
+mod project_paths;
+mod project_roots;
+mod recent_projects;
+mod redaction;
+mod sandbox;
+mod scripts;
+mod session_events;
+mod session_health;
+mod session_replay;
+mod side_by_side_diff;
+mod task_estimation;
+mod task_export;
+mod task_rules;
+mod task_runner;
+mod terminal;
+mod token_estimate;
+mod tool_calls;
+mod tray;
+mod tts;
 mod types;
+mod updater;
+mod whisper_native;
+mod whisper_stream;
+mod windows;
 
-use tauri::Manager;
+use tauri::{Emitter, Manager};
 use db::Database;
 use file_watcher::FileWatcherManager;
 use agent_manager::AgentManager;
@@ -44,17 +109,28 @@ fn get_platform() -> String {
 }
 
 fn main() {
-    // Initialize logger
-    env_logger::init();
+    // Claude runs this executable as its command approval hook
+    if std::env::args().nth(1).as_deref() == Some(command_gate::HOOK_ARG) {
+        std::process::exit(command_gate::run_hook());
+    }
 
     tauri::Builder::default()
+        // Must come first: a second launch (e.g. from a deep link) hands its
+        // arguments to the running instance and exits
+        .plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
+            tray::show_main_window(app);
+        }))
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_updater::Builder::new().build())
         .invoke_handler(tauri::generate_handler![
             greet,
             get_hostname,
             get_platform,
             commands::create_project,
+            commands::create_project_from_git,
             commands::get_projects,
             commands::get_project,
             commands::has_recent_activity,
@@ -64,80 +140,262 @@ fn main() {
             commands::list_plugins,
             commands::select_folder,
             commands::analyze_project_directory,
+            commands::get_container_environment,
             commands::analyze_project_with_ai,
             commands::update_project_with_ai,
             commands::generate_project_details,
+            agent_instructions::get_agent_instructions,
+            agent_instructions::set_agent_instructions,
+            agent_instructions::generate_agent_instructions,
             commands::create_task,
             commands::get_tasks,
             commands::update_task,
             commands::delete_task,
             commands::update_task_status,
+            commands::reorder_tasks,
+            commands::run_task,
+            commands::estimate_task,
+            commands::export_tasks,
+            commands::create_task_label,
+            commands::list_task_labels,
+            commands::update_task_label,
+            commands::delete_task_label,
+            commands::add_label_to_task,
+            commands::remove_label_from_task,
+            commands::get_task_labels,
+            commands::add_task_comment,
+            commands::list_task_comments,
+            commands::edit_task_comment,
+            commands::delete_task_comment,
+            commands::start_task_timer,
+            commands::stop_task_timer,
+            commands::get_task_time_entries,
+            commands::get_project_timesheet,
             commands::read_project_files,
             commands::get_folder_children,
             commands::get_git_status,
+            commands::git_commit,
             commands::read_file_content,
+            commands::read_file_with_encoding,
+            commands::write_file_content,
             commands::send_message,
+            commands::cancel_message_stream,
+            commands::resend_message,
+            commands::regenerate_response,
             commands::get_messages,
             commands::get_session_messages,
             commands::get_project_messages,
             commands::count_session_messages,
+            commands::get_session_messages_range,
+            commands::get_all_session_messages,
+            commands::estimate_session_tokens,
+            commands::pin_message,
+            commands::unpin_message,
+            commands::get_pinned_messages,
+            commands::delete_message,
+            commands::redact_message,
+            commands::export_chat,
             commands::save_message,
             commands::log_activity,
             commands::get_activities,
+            commands::get_activity_counts,
+            commands::get_global_activities,
             commands::get_project_stats,
+            commands_stats::get_project_timeseries,
+            commands_stats::get_cost_summary,
+            commands_stats::get_session_change_stats,
             commands::start_watching_project,
             commands::stop_watching_project,
             commands::is_watching_project,
+            project_roots::list_project_roots,
+            project_roots::add_project_root,
+            project_roots::remove_project_root,
+            open_files::watch_open_file,
+            open_files::unwatch_open_file,
+            recent_projects::record_project_opened,
+            recent_projects::record_project_closed,
+            recent_projects::get_recent_projects,
+            recent_projects::get_resumable_sessions,
+            onboarding::get_onboarding_state,
+            onboarding::complete_onboarding_step,
+            patches::export_patch,
+            patches::import_patch,
             commands::get_pending_changes,
             commands::get_all_changes,
             commands::approve_change,
             commands::reject_change,
             commands::get_file_diff,
+            commands::get_stale_changes,
+            commands::get_side_by_side_diff,
+            highlight::get_highlight_tokens,
+            commands::ai_review_change,
+            commands::generate_review_report,
             commands::add_review_comment,
             commands::get_review_comments,
             commands::resolve_review_comment,
             commands::unresolve_review_comment,
+            commands::apply_suggestion,
+            commands::request_changes,
             commands::delete_review_comment,
             commands::start_agent_session,
+            commands::run_comparison,
+            commands::get_comparison_report,
+            commands::discard_comparison,
             commands::send_to_agent,
             commands::read_agent_output,
             commands::read_agent_events,
             commands::read_agent_output_and_events,
             commands::stop_agent_session,
+            commands::stop_all_agent_sessions,
             commands::sync_claude_session_id,
             commands::get_agent_status,
             commands::list_agent_sessions,
             commands::check_agent_health,
             commands::get_project_sessions,
-            commands::cleanup_orphaned_sessions,
+            maintenance::cleanup_orphaned_sessions,
+            maintenance::preview_cleanup,
+            session_health::reconcile_sessions,
             // Chat tab commands
             commands::get_chat_tabs,
             commands::create_chat_tab,
             commands::update_chat_tab,
+            commands::auto_label_tab,
+            commands::duplicate_chat_tab,
+            commands::set_tab_flag_overrides,
+            commands::get_tab_effective_flags,
+            commands::send_to_tab,
+            commands::send_selection_to_agent,
             commands::set_active_tab,
             commands::close_chat_tab,
             commands::reorder_chat_tabs,
+            // File bookmark commands
+            commands::add_file_bookmark,
+            commands::list_file_bookmarks,
+            commands::remove_file_bookmark,
+            commands::get_bookmark_context,
             // Chat commands (plugin-based)
             commands_chat::get_chat_history,
             commands_chat::get_chat_history_paginated,
             commands_chat::list_cli_sessions,
+            commands_chat::find_cli_sessions,
+            commands_chat::import_cli_sessions,
             commands_chat::start_chat_session,
             commands_chat::send_chat_message,
             commands_chat::start_watching_session,
             commands_chat::stop_watching_session,
+            session_events::resume_session_updates,
             // Whisper transcription commands
             commands_whisper::check_whisper_installation,
             commands_whisper::install_whisper,
             commands_whisper::transcribe_local,
+            commands_whisper::start_transcription_stream,
+            commands_whisper::push_transcription_chunk,
+            commands_whisper::finish_transcription_stream,
+            commands_whisper::cancel_transcription_stream,
             commands_whisper::transcribe_openai,
+            commands_whisper::transcribe_and_send,
+            commands_whisper::save_transcription,
+            commands_whisper::list_transcriptions,
+            commands_whisper::link_transcription_message,
+            commands_whisper::delete_transcription,
+            // Text-to-speech commands
+            commands_tts::speak_text,
+            commands_tts::stop_speaking,
+            commands_tts::is_speaking,
             // Plugin settings commands
             plugin_settings::get_plugin_settings,
             plugin_settings::get_plugin_flag_value,
             plugin_settings::set_plugin_flag_value,
             plugin_settings::set_plugin_settings,
             plugin_settings::get_all_plugin_settings,
+            app_settings::get_app_settings,
+            app_settings::set_app_settings,
+            doctor::run_doctor,
+            logging::get_app_logs,
+            logging::open_log_folder,
+            updater::check_for_updates,
+            updater::install_update,
+            deep_link::take_pending_deep_links,
+            api_server::get_api_server_status,
+            api_server::regenerate_api_token,
+            windows::open_project_window,
+            windows::get_window_context,
+            project_bundle::export_project_bundle,
+            project_bundle::read_project_bundle,
+            project_bundle::import_project_bundle,
+            palette::palette_search,
+            command_gate::approve_command,
+            command_gate::get_pending_commands,
+            jobs::list_jobs,
+            jobs::cancel_job,
+            terminal::open_terminal,
+            terminal::write_terminal,
+            terminal::resize_terminal,
+            terminal::close_terminal,
+            terminal::list_terminals,
+            scripts::list_scripts,
+            scripts::run_script,
+            scripts::cancel_script,
+            scripts::get_script_runs,
+            lint::run_linter,
+            lint::run_formatter,
+            dev_server::start_dev_server,
+            dev_server::stop_dev_server,
+            dev_server::get_dev_server,
+            lsp::get_diagnostics,
+            lsp::stop_language_servers,
+            hotspots::get_code_hotspots,
+            session_replay::get_session_events,
+            session_replay::replay_session,
+            session_replay::stop_replay,
+            session_replay::bookmark_session_moment,
+            session_replay::list_session_bookmarks,
+            session_replay::delete_session_bookmark,
+            prd_history::get_prd_history,
+            prd_history::restore_prd_revision,
+            prd_history::diff_prd_revisions,
+            notes::get_notes,
+            notes::get_note,
+            notes::create_note,
+            notes::update_note,
+            notes::delete_note,
+            tool_calls::get_tool_calls,
+            tool_calls::get_tool_call_counts,
+            agent_auth::get_agent_auth_status,
+            agent_auth::start_agent_login,
+            ci::get_ci_status,
+            claude_config::list_claude_subagents,
+            claude_config::save_claude_subagent,
+            claude_config::delete_claude_subagent,
+            claude_config::list_claude_hooks,
+            claude_config::save_claude_hooks,
+            claude_config::get_claude_config_templates,
+            demo_project::create_demo_project,
+            commands::list_env_files,
+            commands::read_env_file,
+            commands::set_env_var,
+            db::get_database_mode,
+            db::open_read_only_database,
+            db::close_read_only_database,
         ])
         .setup(|app| {
+            // Log to stderr and to rotating files under the app data dir
+            match app.path().app_data_dir() {
+                Ok(dir) => {
+                    if let Err(e) = logging::init(&dir) {
+                        eprintln!("Failed to initialize logging: {:#}", e);
+                    }
+                }
+                Err(e) => eprintln!("Failed to get app data dir for logs: {}", e),
+            }
+
+            // Initialize app settings manager, before the database since they pick which one opens
+            let app_settings_manager = app_settings::AppSettingsManager::new(
+                app.path().app_data_dir().expect("Failed to get app data directory"),
+            )
+            .expect("Failed to initialize app settings manager");
+            app.manage(app_settings_manager);
+
             // Initialize database
             let app_handle = app.handle();
             tauri::async_runtime::block_on(async move {
@@ -154,17 +412,101 @@ fn main() {
                     }
                 }
             })?;
+            // Another machine's database: leave its jobs and sessions to that machine
+            let read_only = app.state::<Database>().read_only_path().is_some();
+
+            // Push new activity log entries to the frontend
+            activity::init_events(app.handle().clone());
 
-            // Initialize file watcher manager (for project changes)
-            let watcher_manager = FileWatcherManager::new();
+            // Initialize job manager, failing jobs the last run left unfinished
+            let pool = app.state::<Database>().pool().clone();
+            let event_handle = app.handle().clone();
+            let job_manager = jobs::JobManager::new(pool, move |job| {
+                if let Err(e) = event_handle.emit(jobs::JOB_UPDATED_EVENT, job) {
+                    log::warn!("Failed to emit job update: {}", e);
+                }
+            });
+            if !read_only {
+                if let Err(e) = tauri::async_runtime::block_on(job_manager.recover()) {
+                    log::error!("Failed to recover jobs: {}", e);
+                }
+            }
+            app.manage(job_manager);
+
+            // Initialize script runner, streaming each run's output to the frontend
+            let pool = app.state::<Database>().pool().clone();
+            let event_handle = app.handle().clone();
+            let script_runner = scripts::ScriptRunner::new(pool, move |event| {
+                let emitted = match event {
+                    scripts::ScriptEvent::Output(output) => event_handle.emit(scripts::SCRIPT_OUTPUT_EVENT, output),
+                    scripts::ScriptEvent::Finished(run) => event_handle.emit(scripts::SCRIPT_FINISHED_EVENT, run),
+                };
+                if let Err(e) = emitted {
+                    log::warn!("Failed to emit script event: {}", e);
+                }
+            });
+            if !read_only {
+                if let Err(e) = tauri::async_runtime::block_on(script_runner.recover()) {
+                    log::error!("Failed to recover script runs: {}", e);
+                }
+            }
+            app.manage(script_runner);
+
+            // Initialize dev server manager, reporting each server's URL and output
+            let event_handle = app.handle().clone();
+            app.manage(dev_server::DevServerManager::new(move |event| {
+                let emitted = match event {
+                    dev_server::DevServerEvent::Updated(info) => event_handle.emit(dev_server::DEV_SERVER_UPDATED_EVENT, info),
+                    dev_server::DevServerEvent::Output(output) => event_handle.emit(dev_server::DEV_SERVER_OUTPUT_EVENT, output),
+                };
+                if let Err(e) = emitted {
+                    log::warn!("Failed to emit dev server event: {}", e);
+                }
+            }));
+
+            // Language servers start per project when diagnostics are first asked for
+            app.manage(lsp::LspManager::new());
+
+            // Replays of recorded agent sessions, kept so they can be stopped
+            app.manage(session_replay::ReplayManager::new());
+
+            // Initialize file watcher manager (for project changes), which keeps
+            // the git status cache of watched projects current
+            let git_status_cache = git_status::GitStatusCache::default();
+            let watcher_manager = FileWatcherManager::new(git_status_cache.clone());
+            app.manage(git_status_cache);
             app.manage(watcher_manager);
             log::info!("File watcher manager initialized");
 
+            // Track what each window holds open
+            app.manage(windows::WindowRegistry::default());
+
+            // Per-tab session updates, buffered while a webview reloads
+            app.manage(session_events::SessionEventHub::default());
+
+            // Cache project file lists for the command palette
+            app.manage(palette::FileIndex::default());
+
+            // Watch files open in the viewer for changes on disk
+            app.manage(open_files::OpenFileWatcher::default());
+
             // Initialize agent manager
             let agent_manager = AgentManager::new();
             app.manage(agent_manager);
             log::info!("Agent manager initialized");
 
+            // Initialize chat stream manager (for cancelling streamed replies)
+            app.manage(chat_stream::ChatStreamManager::new());
+
+            // Initialize whisper stream manager (for live dictation)
+            app.manage(whisper_stream::WhisperStreamManager::new());
+
+            // Initialize text-to-speech manager (for reading replies aloud)
+            app.manage(tts::TtsManager::new());
+
+            // Track embedded terminal sessions
+            app.manage(terminal::TerminalManager::default());
+
             // Initialize plugin manager
             let mut plugin_manager = PluginManager::new();
 
@@ -179,6 +521,9 @@ fn main() {
                 }
             }
 
+            // Built in, for the sample project
+            plugin_manager.register(Box::new(plugins::DemoPlugin::default()));
+
             app.manage(plugin_manager);
             log::info!("Plugin manager initialized");
 
@@ -190,6 +535,33 @@ fn main() {
             app.manage(plugin_settings_manager);
             log::info!("Plugin settings manager initialized");
 
+            // Check for updates in the background on the selected channel
+            app.manage(updater::PendingUpdate::default());
+            updater::spawn_background_checks(app.handle().clone());
+
+            if !read_only {
+                // Keep recorded session statuses in line with what's running
+                session_health::spawn_watchdog(app.handle().clone());
+
+                // Clear orphaned and stale sessions now and then
+                maintenance::spawn_scheduled_cleanup(app.handle().clone());
+            }
+
+            // Local HTTP/WebSocket API, when enabled in settings
+            app.manage(api_server::ApiServer::default());
+            api_server::apply(app.handle());
+
+            // Tray icon with running session status
+            if let Err(e) = tray::init(app.handle()) {
+                log::error!("Failed to create tray icon: {}", e);
+            }
+
+            // Open ateliercode:// links, including the one the app was launched with
+            deep_link::init(app.handle());
+
+            // Hold denied agent commands until the user approves them
+            command_gate::init(app.handle());
+
             #[cfg(debug_assertions)]
             {
                 let window = app.get_webview_window("main").unwrap();
@@ -197,6 +569,23 @@ fn main() {
             }
             Ok(())
         })
+        .on_page_load(|webview, payload| {
+            if payload.event() == tauri::webview::PageLoadEvent::Started {
+                webview.state::<session_events::SessionEventHub>().page_loading(webview.label());
+            }
+        })
+        .on_window_event(|window, event| match event {
+            // In background mode, closing the main window hides it to the tray
+            tauri::WindowEvent::CloseRequested { api, .. } => {
+                let background_mode = window.state::<app_settings::AppSettingsManager>().get().background_mode;
+                if window.label() == windows::MAIN_WINDOW && background_mode {
+                    api.prevent_close();
+                    let _ = window.hide();
+                }
+            }
+            tauri::WindowEvent::Destroyed => windows::window_destroyed(window.app_handle(), window.label()),
+            _ => {}
+        })
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
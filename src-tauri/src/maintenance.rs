@@ -0,0 +1,259 @@
+// Maintenance
+// Clears what crashed or closed sessions leave behind: sessions that never got
+// a CLI id, sessions still marked running after their process went away,
+// session watches whose tab was closed and file changes of deleted projects
+
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::collections::HashSet;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::plugin::PluginManager;
+use crate::session_events::SessionEventHub;
+use crate::session_health;
+use crate::windows::WindowRegistry;
+
+/// Wait before the first scheduled cleanup, so it doesn't compete with startup
+const FIRST_RUN_DELAY: Duration = Duration::from_secs(5 * 60);
+const RUN_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// What a cleanup removed, or would remove when `dry_run` is set
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CleanupResult {
+    /// Sessions that never got a CLI session id
+    pub sessions_deleted: i64,
+    pub messages_deleted: i64,
+    /// Sessions marked running whose process is gone, now marked stopped
+    /// (or errored, when the agent failed)
+    pub stale_sessions_stopped: i64,
+    /// Session watches whose chat tab no longer exists
+    pub watches_stopped: i64,
+    /// File changes left over from deleted projects
+    pub file_changes_deleted: i64,
+    pub dry_run: bool,
+}
+
+impl CleanupResult {
+    fn is_empty(&self) -> bool {
+        self.sessions_deleted == 0
+            && self.messages_deleted == 0
+            && self.stale_sessions_stopped == 0
+            && self.watches_stopped == 0
+            && self.file_changes_deleted == 0
+    }
+}
+
+/// Delete sessions without a CLI id, skipping any the app holds or that are
+/// still running; `stale` are the running ones about to be marked stopped
+async fn clean_sessions(
+    pool: &SqlitePool,
+    held_by_app: &HashSet<String>,
+    stale: &HashSet<String>,
+    dry_run: bool,
+    result: &mut CleanupResult,
+) -> Result<(), AppError> {
+    let candidates: Vec<(String, String)> =
+        sqlx::query_as("SELECT id, status FROM agent_sessions WHERE claude_session_id IS NULL")
+            .fetch_all(pool)
+            .await
+            .map_err(AppError::context("Failed to fetch orphaned session IDs"))?;
+    let orphaned: Vec<String> = candidates
+        .into_iter()
+        .filter(|(id, status)| {
+            let running = matches!(status.as_str(), "running" | "starting") && !stale.contains(id);
+            !held_by_app.contains(id) && !running
+        })
+        .map(|(id, _)| id)
+        .collect();
+    log::debug!("Orphaned session IDs: {:?}", orphaned);
+
+    for id in &orphaned {
+        let messages = if dry_run {
+            sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM chat_messages WHERE session_id = ?")
+                .bind(id)
+                .fetch_one(pool)
+                .await
+                .map_err(AppError::context("Failed to count orphaned session messages"))?
+        } else {
+            // Messages first, for the foreign key
+            let deleted = sqlx::query("DELETE FROM chat_messages WHERE session_id = ?")
+                .bind(id)
+                .execute(pool)
+                .await
+                .map_err(AppError::context("Failed to delete orphaned session messages"))?
+                .rows_affected();
+            sqlx::query("DELETE FROM agent_sessions WHERE id = ?")
+                .bind(id)
+                .execute(pool)
+                .await
+                .map_err(AppError::context("Failed to delete orphaned sessions"))?;
+            deleted as i64
+        };
+        result.messages_deleted += messages;
+    }
+    result.sessions_deleted = orphaned.len() as i64;
+    Ok(())
+}
+
+/// File changes whose project is gone, which only happens when rows were
+/// deleted with foreign keys off (older builds, manual edits)
+async fn clean_file_changes(pool: &SqlitePool, dry_run: bool, result: &mut CleanupResult) -> Result<(), AppError> {
+    const WHERE_ORPHANED: &str = "WHERE project_id NOT IN (SELECT id FROM projects)";
+    result.file_changes_deleted = if dry_run {
+        sqlx::query_scalar::<_, i64>(&format!("SELECT COUNT(*) FROM file_changes {}", WHERE_ORPHANED))
+            .fetch_one(pool)
+            .await
+            .map_err(AppError::context("Failed to count orphaned file changes"))?
+    } else {
+        // Review comments go with them by cascade
+        sqlx::query(&format!("DELETE FROM file_changes {}", WHERE_ORPHANED))
+            .execute(pool)
+            .await
+            .map_err(AppError::context("Failed to delete orphaned file changes"))?
+            .rows_affected() as i64
+    };
+    Ok(())
+}
+
+/// Stop session watches whose chat tab was deleted
+async fn clean_watches(app: &AppHandle, pool: &SqlitePool, dry_run: bool, result: &mut CleanupResult) -> Result<(), AppError> {
+    let tab_ids: HashSet<String> = sqlx::query_scalar::<_, String>("SELECT id FROM chat_tabs")
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::context("Failed to list chat tabs"))?
+        .into_iter()
+        .collect();
+    let watches = app.state::<WindowRegistry>().watches_without_tab(&tab_ids);
+    result.watches_stopped = watches.len() as i64;
+    if dry_run {
+        return Ok(());
+    }
+
+    let plugin_manager = app.state::<PluginManager>();
+    for (label, handle) in watches {
+        if let Some(tab_id) = app.state::<WindowRegistry>().untrack_session_watch(&label, &handle.id) {
+            app.state::<SessionEventHub>().forget_tab(&tab_id);
+        }
+        let Some(plugin) = plugin_manager.get(&handle.plugin_name) else {
+            continue;
+        };
+        let cli_session_id = handle.cli_session_id.clone();
+        if let Err(e) = plugin.stop_watching_session(handle).await {
+            log::warn!("Failed to stop watching session {}: {}", cli_session_id, e);
+        }
+    }
+    Ok(())
+}
+
+/// Run every cleanup, or with `dry_run` only count what it would do
+pub async fn run(app: &AppHandle, dry_run: bool) -> Result<CleanupResult, AppError> {
+    let pool = app.state::<Database>().pool().clone();
+    let app_sessions = session_health::app_sessions(app).await;
+    let (_, fixes) = session_health::find_mismatches(&pool, &app_sessions).await?;
+    let mut result = CleanupResult { dry_run, stale_sessions_stopped: fixes.len() as i64, ..Default::default() };
+    if !dry_run {
+        session_health::apply(app, &pool, &fixes).await?;
+    }
+
+    let held_by_app: HashSet<String> = app_sessions.into_keys().collect();
+    let stale: HashSet<String> = fixes.into_iter().map(|fix| fix.session_id).collect();
+    clean_sessions(&pool, &held_by_app, &stale, dry_run, &mut result).await?;
+    clean_watches(app, &pool, dry_run, &mut result).await?;
+    clean_file_changes(&pool, dry_run, &mut result).await?;
+    Ok(result)
+}
+
+/// Clean up in the background every few hours
+pub fn spawn_scheduled_cleanup(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(FIRST_RUN_DELAY).await;
+        loop {
+            match run(&app, false).await {
+                Ok(result) if !result.is_empty() => log::info!("Scheduled cleanup: {:?}", result),
+                Ok(_) => {}
+                Err(e) => log::warn!("Scheduled cleanup failed: {}", e),
+            }
+            tokio::time::sleep(RUN_INTERVAL).await;
+        }
+    });
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Delete orphaned sessions and their messages, stop stale sessions and
+/// session watches without a tab, and drop file changes of deleted projects
+#[tauri::command]
+pub async fn cleanup_orphaned_sessions(app: AppHandle) -> Result<CleanupResult, AppError> {
+    log::info!("Starting cleanup of orphaned agent sessions");
+    let result = run(&app, false).await?;
+    log::info!("Cleanup complete: {:?}", result);
+    Ok(result)
+}
+
+/// What `cleanup_orphaned_sessions` would do, without changing anything
+#[tauri::command]
+pub async fn preview_cleanup(app: AppHandle) -> Result<CleanupResult, AppError> {
+    run(&app, true).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_pool;
+
+    async fn insert_session(pool: &SqlitePool, id: &str, status: &str, cli_session_id: Option<&str>) {
+        sqlx::query(
+            "INSERT INTO agent_sessions (id, project_id, agent_type, started_at, status, claude_session_id)
+             VALUES (?, 'p1', 'claude-code', 0, ?, ?)",
+        )
+        .bind(id)
+        .bind(status)
+        .bind(cli_session_id)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    async fn status(pool: &SqlitePool, id: &str) -> Option<String> {
+        sqlx::query_scalar("SELECT status FROM agent_sessions WHERE id = ?")
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_clean_sessions() {
+        let (pool, _db) = test_pool().await;
+        sqlx::query("INSERT INTO projects (id, name, root_path, created_at, last_activity) VALUES ('p1', 'App', '/tmp/app', 0, 0)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        insert_session(&pool, "live", "running", None).await;
+        insert_session(&pool, "cli-run", "running", None).await;
+        insert_session(&pool, "crashed", "running", None).await;
+        insert_session(&pool, "orphan", "stopped", None).await;
+        insert_session(&pool, "done", "stopped", Some("cli-1")).await;
+        let held_by_app = HashSet::from(["live".to_string()]);
+        let stale = HashSet::from(["crashed".to_string()]);
+
+        let mut preview = CleanupResult { dry_run: true, ..Default::default() };
+        clean_sessions(&pool, &held_by_app, &stale, true, &mut preview).await.unwrap();
+        assert_eq!(preview.sessions_deleted, 2);
+        assert_eq!(status(&pool, "orphan").await.as_deref(), Some("stopped"));
+
+        let mut result = CleanupResult::default();
+        clean_sessions(&pool, &held_by_app, &stale, false, &mut result).await.unwrap();
+        assert_eq!(result.sessions_deleted, 2);
+        assert_eq!(status(&pool, "live").await.as_deref(), Some("running"));
+        assert_eq!(status(&pool, "cli-run").await.as_deref(), Some("running"));
+        assert_eq!(status(&pool, "crashed").await, None);
+        assert_eq!(status(&pool, "orphan").await, None);
+        assert_eq!(status(&pool, "done").await.as_deref(), Some("stopped"));
+    }
+}
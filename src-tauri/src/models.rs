@@ -39,6 +39,179 @@ impl Project {
     }
 }
 
+/// Project-level settings stored as JSON in `projects.settings`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProjectSettings {
+    /// Run an AI review pass on every recorded file change
+    pub ai_review: bool,
+    /// Refuse to commit files whose recorded changes haven't been reviewed
+    pub require_review_before_commit: bool,
+    /// Estimated agent spend per UTC day, in USD, that raises an alert
+    pub daily_budget_usd: Option<f64>,
+    /// Estimated agent spend per UTC calendar month, in USD, that raises an alert
+    pub monthly_budget_usd: Option<f64>,
+    /// Which agent events raise an OS notification while the app is in the background
+    pub notifications: NotificationPrefs,
+    /// Masking of secrets in agent output and chat messages before they're stored
+    pub redaction: RedactionSettings,
+    /// Approval of agent shell commands that match a deny list
+    pub command_gate: CommandGateSettings,
+    /// Named restrictions for agent processes, chosen when a session starts
+    pub execution_profiles: Vec<ExecutionProfile>,
+    /// Profile used when a session doesn't pick one
+    pub default_execution_profile: Option<String>,
+    /// Language servers for diagnostics; empty picks them from the project's
+    /// files (rust-analyzer, typescript-language-server) when installed
+    pub language_servers: Vec<LanguageServerConfig>,
+    /// Append the agent's closing message of every finished turn to the
+    /// "Session summaries" note
+    pub session_summaries_to_notes: bool,
+    /// Keep what files deleted while watched looked like, so rejecting the
+    /// deletion puts them back
+    pub capture_deleted_files: bool,
+    /// Agent for new chat tabs and sessions that don't pick one, instead of
+    /// the one the project was created with
+    pub default_agent: Option<String>,
+}
+
+impl ProjectSettings {
+    /// Parse settings JSON, falling back to defaults
+    pub fn from_json(json: Option<&str>) -> Self {
+        json.and_then(|s| serde_json::from_str(s).ok()).unwrap_or_default()
+    }
+
+    /// The profile named `name`, or the default profile when `name` is `None`
+    pub fn execution_profile(&self, name: Option<&str>) -> Result<Option<&ExecutionProfile>, String> {
+        let Some(name) = name.or(self.default_execution_profile.as_deref()) else {
+            return Ok(None);
+        };
+        self.execution_profiles
+            .iter()
+            .find(|profile| profile.name == name)
+            .map(Some)
+            .ok_or_else(|| format!("Execution profile not found: {}", name))
+    }
+}
+
+/// Per-project OS notification preferences; everything is on by default
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationPrefs {
+    /// An agent finished its turn
+    pub turn_finished: bool,
+    /// An agent failed or exited with an error
+    pub errors: bool,
+    /// An agent is waiting on an answer
+    pub input_requested: bool,
+}
+
+impl Default for NotificationPrefs {
+    fn default() -> Self {
+        Self {
+            turn_finished: true,
+            errors: true,
+            input_requested: true,
+        }
+    }
+}
+
+/// Per-project secrets redaction; on by default
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RedactionSettings {
+    pub enabled: bool,
+    /// Extra regex patterns to mask on top of the built-in key formats
+    pub patterns: Vec<String>,
+}
+
+impl Default for RedactionSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            patterns: Vec::new(),
+        }
+    }
+}
+
+impl RedactionSettings {
+    pub fn redactor(&self) -> crate::redaction::Redactor {
+        if self.enabled {
+            crate::redaction::Redactor::with_patterns(&self.patterns)
+        } else {
+            crate::redaction::Redactor::disabled()
+        }
+    }
+}
+
+/// Per-project approval gate for shell commands run by Claude sessions
+///
+/// Patterns match a command and anything after it, with `*` matching any text.
+/// Applies to sessions started after it's enabled.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CommandGateSettings {
+    pub enabled: bool,
+    /// Commands that never need approval, even if a deny pattern matches
+    pub allow: Vec<String>,
+    /// Commands that wait for the user's approval
+    pub deny: Vec<String>,
+}
+
+impl Default for CommandGateSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            allow: Vec::new(),
+            deny: ["rm -rf", "sudo", "git push", "git reset --hard", "git clean", "curl * | sh", "curl * | bash"]
+                .iter()
+                .map(|p| p.to_string())
+                .collect(),
+        }
+    }
+}
+
+/// Restrictions applied to the processes an agent session spawns
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExecutionProfile {
+    pub name: String,
+    /// Environment variables passed through besides the basics shells need; `None` passes everything
+    pub env_allowlist: Option<Vec<String>>,
+    /// Directories making up the agent's PATH; `None` keeps the app's PATH
+    pub path: Option<Vec<String>>,
+    /// Run without network access (Linux and macOS). This cuts off hosted
+    /// model APIs too, so it suits agents backed by a local model.
+    pub network_disabled: bool,
+    /// CPU seconds an agent process may use before the OS stops it (Unix)
+    pub max_cpu_seconds: Option<u64>,
+    /// Wall-clock seconds a turn may run before the agent is killed
+    pub max_runtime_seconds: Option<u64>,
+    /// Run the agent with `docker exec` in the project's running dev container
+    /// or compose service, so it uses the project's toolchain. The container's
+    /// own environment applies instead of the restrictions above, except the
+    /// runtime limit.
+    pub run_in_container: bool,
+    /// Container to run in, when it can't be found from the project's config
+    pub container_name: Option<String>,
+    /// Project env files (e.g. `.env`) whose variables are set for the agent,
+    /// later files overriding earlier ones
+    pub env_files: Vec<String>,
+}
+
+/// A language server started per project to report diagnostics
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LanguageServerConfig {
+    /// LSP language id sent for opened files, e.g. `rust` or `typescript`
+    pub language: String,
+    /// Program speaking LSP over stdio
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Extensions (without the dot) of the files this server checks
+    pub file_extensions: Vec<String>,
+}
+
 /// Task model
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Task {
@@ -55,6 +228,10 @@ pub struct Task {
     pub created_at: i64,
     pub started_at: Option<i64>,
     pub completed_at: Option<i64>,
+    /// Position within the task's status column on the board
+    pub position: i64,
+    /// JSON object with extra task data (e.g., AI estimate reasoning)
+    pub metadata: Option<String>,
 }
 
 impl Task {
@@ -74,6 +251,8 @@ impl Task {
             created_at: chrono::Utc::now().timestamp(),
             started_at: None,
             completed_at: None,
+            position: 0,
+            metadata: None,
         }
     }
 }
@@ -121,6 +300,77 @@ impl ChatMessage {
             metadata: None,
         }
     }
+
+    /// The message's metadata, empty when it has none or it isn't JSON
+    pub fn parsed_metadata(&self) -> MessageMetadata {
+        self.metadata.as_deref().and_then(|json| MessageMetadata::from_json(json).ok()).unwrap_or_default()
+    }
+
+    pub fn set_metadata(&mut self, metadata: &MessageMetadata) {
+        self.metadata = Some(metadata.to_json());
+    }
+}
+
+/// What's stored in `chat_messages.metadata`
+///
+/// The typed fields are also written to their own columns so stats can query
+/// them; anything else (edit and regeneration links, tool message flags) is
+/// kept as is in `extra`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct MessageMetadata {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input_tokens: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_tokens: Option<u64>,
+    /// Estimated from the model and tokens when not given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cost_usd: Option<f64>,
+    /// Ids of the tool calls the reply made (see the tool_calls table)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tool_call_ids: Vec<String>,
+    /// Milliseconds the reply took
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub processing_time: Option<u64>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub cancelled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl MessageMetadata {
+    /// Parse metadata JSON, filling in the cost when the model and tokens are known
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        let mut metadata: Self = serde_json::from_str(json)?;
+        if metadata.cost_usd.is_none() {
+            metadata.cost_usd = metadata.estimated_cost();
+        }
+        Ok(metadata)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|_| "{}".to_string())
+    }
+
+    fn estimated_cost(&self) -> Option<f64> {
+        let model = self.model.as_ref()?;
+        if self.input_tokens.is_none() && self.output_tokens.is_none() {
+            return None;
+        }
+        let usage = crate::cost::UsageEntry {
+            timestamp: 0,
+            model: model.clone(),
+            input_tokens: self.input_tokens.unwrap_or(0),
+            output_tokens: self.output_tokens.unwrap_or(0),
+            cache_creation_tokens: 0,
+            cache_read_tokens: 0,
+        };
+        Some(usage.cost_usd())
+    }
 }
 
 /// Agent session model
@@ -198,6 +448,10 @@ pub struct FileChange {
     pub reviewed: bool,
     pub approved: Option<bool>,
     pub timestamp: i64,
+    /// SHA-256 of the file content this change recorded
+    pub content_hash: Option<String>,
+    /// The file has changed on disk since this change was recorded
+    pub stale: bool,
 }
 
 impl FileChange {
@@ -218,6 +472,8 @@ impl FileChange {
             reviewed: false,
             approved: None,
             timestamp: chrono::Utc::now().timestamp(),
+            content_hash: None,
+            stale: false,
         }
     }
 }
@@ -232,6 +488,12 @@ pub struct ReviewComment {
     pub comment: String,
     pub timestamp: i64,
     pub resolved: bool,
+    /// First line (1-based) replaced by `suggestion`
+    pub suggestion_start_line: Option<i64>,
+    /// Last line (inclusive) replaced by `suggestion`
+    pub suggestion_end_line: Option<i64>,
+    /// Suggested replacement text for the line range
+    pub suggestion: Option<String>,
 }
 
 impl ReviewComment {
@@ -250,6 +512,9 @@ impl ReviewComment {
             comment,
             timestamp: chrono::Utc::now().timestamp(),
             resolved: false,
+            suggestion_start_line: None,
+            suggestion_end_line: None,
+            suggestion: None,
         }
     }
 }
@@ -281,6 +546,11 @@ pub struct ChatTab {
     pub is_active: bool,
     pub created_at: i64,
     pub last_activity: i64,
+    /// JSON object of CLI flag overrides (flag_id -> value) for this tab
+    pub flag_overrides: Option<String>,
+    /// CLI session a duplicated tab branches from, until its first resume
+    /// forks it into a session of its own
+    pub forked_from_cli_session_id: Option<String>,
 }
 
 impl ChatTab {
@@ -298,6 +568,281 @@ impl ChatTab {
             is_active: false,
             created_at: now,
             last_activity: now,
+            flag_overrides: None,
+            forked_from_cli_session_id: None,
+        }
+    }
+}
+
+/// File bookmark model - a pinned file within a project
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct FileBookmark {
+    pub id: String,
+    pub project_id: String,
+    pub file_path: String,
+    pub label: Option<String>,
+    pub auto_attach: bool,
+    pub created_at: i64,
+}
+
+impl FileBookmark {
+    /// Create a new file bookmark
+    pub fn new(project_id: String, file_path: String) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            project_id,
+            file_path,
+            label: None,
+            auto_attach: false,
+            created_at: chrono::Utc::now().timestamp(),
+        }
+    }
+}
+
+/// Transcription model - a dictated prompt kept in the project's history
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Transcription {
+    pub id: String,
+    pub project_id: String,
+    pub text: String,
+    pub duration: Option<f64>,
+    pub provider: String,
+    pub model: Option<String>,
+    pub language: Option<String>,
+    pub message_id: Option<String>,
+    pub created_at: i64,
+}
+
+impl Transcription {
+    /// Create a new transcription record
+    pub fn new(project_id: String, text: String, provider: String) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            project_id,
+            text,
+            duration: None,
+            provider,
+            model: None,
+            language: None,
+            message_id: None,
+            created_at: chrono::Utc::now().timestamp(),
         }
     }
 }
+
+/// Note model - a markdown page in a project's wiki
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Note {
+    pub id: String,
+    pub project_id: String,
+    /// Unique within the project
+    pub title: String,
+    pub content: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl Note {
+    /// Create a new note
+    pub fn new(project_id: String, title: String, content: String) -> Self {
+        let now = chrono::Utc::now().timestamp();
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            project_id,
+            title,
+            content,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+/// Task label model - a per-project tag such as "bug" or "feature"
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TaskLabel {
+    pub id: String,
+    pub project_id: String,
+    pub name: String,
+    pub color: Option<String>,
+    pub created_at: i64,
+}
+
+impl TaskLabel {
+    /// Create a new task label
+    pub fn new(project_id: String, name: String, color: Option<String>) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            project_id,
+            name,
+            color,
+            created_at: chrono::Utc::now().timestamp(),
+        }
+    }
+}
+
+/// Task comment model - an entry in a task's discussion thread
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TaskComment {
+    pub id: String,
+    pub task_id: String,
+    pub author: String,
+    pub content: String,
+    pub created_at: i64,
+    pub updated_at: Option<i64>,
+}
+
+impl TaskComment {
+    /// Create a new task comment
+    pub fn new(task_id: String, author: String, content: String) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            task_id,
+            author,
+            content,
+            created_at: chrono::Utc::now().timestamp(),
+            updated_at: None,
+        }
+    }
+}
+
+/// Time entry model - a span of time spent on a task
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TimeEntry {
+    pub id: String,
+    pub task_id: String,
+    pub project_id: String,
+    pub source: String,
+    pub started_at: i64,
+    pub ended_at: Option<i64>,
+    pub duration_seconds: Option<i64>,
+}
+
+impl TimeEntry {
+    /// Start a new time entry
+    pub fn new(task_id: String, project_id: String, source: String) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            task_id,
+            project_id,
+            source,
+            started_at: chrono::Utc::now().timestamp(),
+            ended_at: None,
+            duration_seconds: None,
+        }
+    }
+}
+
+/// Agent comparison model - one prompt sent to several agents
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AgentComparison {
+    pub id: String,
+    pub project_id: String,
+    pub prompt: String,
+    pub created_at: i64,
+}
+
+impl AgentComparison {
+    /// Create a new comparison
+    pub fn new(project_id: String, prompt: String) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            project_id,
+            prompt,
+            created_at: chrono::Utc::now().timestamp(),
+        }
+    }
+}
+
+/// One agent's run within a comparison
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ComparisonRun {
+    pub comparison_id: String,
+    pub session_id: String,
+    pub agent_type: String,
+    /// Directory the agent worked in (a git worktree when isolated)
+    pub worktree_path: String,
+    /// False only for runs from before comparisons required git, which
+    /// shared the project root
+    pub isolated: bool,
+}
+
+/// A background job and its latest progress
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Job {
+    pub id: String,
+    /// What the job does, e.g. "project_export"
+    pub kind: String,
+    pub project_id: Option<String>,
+    pub title: String,
+    /// queued, running, completed, failed or cancelled
+    pub status: String,
+    /// Fraction done, from 0.0 to 1.0, when the job reports it
+    pub progress: Option<f64>,
+    pub message: Option<String>,
+    /// JSON result of a completed job
+    pub result: Option<String>,
+    pub error: Option<String>,
+    pub created_at: i64,
+    pub started_at: Option<i64>,
+    pub finished_at: Option<i64>,
+}
+
+impl Job {
+    /// Create a queued job
+    pub fn new(kind: &str, project_id: Option<String>, title: String) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            kind: kind.to_string(),
+            project_id,
+            title,
+            status: "queued".to_string(),
+            progress: None,
+            message: None,
+            result: None,
+            error: None,
+            created_at: chrono::Utc::now().timestamp(),
+            started_at: None,
+            finished_at: None,
+        }
+    }
+}
+
+/// A run of a project script and its outcome
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ScriptRun {
+    pub id: String,
+    pub project_id: String,
+    /// The script as listed, e.g. "npm run build"
+    pub name: String,
+    /// Full command line that ran
+    pub command: String,
+    /// running, succeeded, failed or cancelled
+    pub status: String,
+    pub exit_code: Option<i64>,
+    /// Last lines of stdout and stderr
+    pub output: Option<String>,
+    pub started_at: i64,
+    pub finished_at: Option<i64>,
+    pub duration_ms: Option<i64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_metadata_round_trip() {
+        let metadata = MessageMetadata::from_json(
+            r#"{"model":"claude-sonnet-4","inputTokens":1000000,"outputTokens":0,"processingTime":1200,"edited_from":"m1"}"#,
+        )
+        .unwrap();
+        assert_eq!(metadata.cost_usd, Some(3.0));
+        assert_eq!(metadata.processing_time, Some(1200));
+        assert_eq!(metadata.extra.get("edited_from"), Some(&serde_json::json!("m1")));
+
+        let json: serde_json::Value = serde_json::from_str(&metadata.to_json()).unwrap();
+        assert_eq!(json["costUsd"], 3.0);
+        assert_eq!(json["edited_from"], "m1");
+        assert!(json.get("cancelled").is_none() && json.get("toolCallIds").is_none());
+    }
+}
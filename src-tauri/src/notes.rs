@@ -0,0 +1,253 @@
+// Project Notes
+// Markdown pages per project for decisions that belong neither in chat nor in
+// the PRD, optionally collecting the agent's summary of every finished turn
+
+use anyhow::{Context, Result};
+use sqlx::SqlitePool;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use crate::models::{Note, ProjectSettings};
+use crate::output_parser::AgentEvent;
+
+/// Note that agent turn summaries are appended to
+pub const SUMMARIES_NOTE_TITLE: &str = "Session summaries";
+
+/// Longer closing messages are cut to this many characters
+const MAX_SUMMARY_CHARS: usize = 4000;
+
+fn validate_title(title: &str) -> Result<&str, String> {
+    let title = title.trim();
+    if title.is_empty() {
+        return Err("Note title cannot be empty".to_string());
+    }
+    Ok(title)
+}
+
+async fn load_note(pool: &SqlitePool, note_id: &str) -> Result<Note, String> {
+    sqlx::query_as::<_, Note>(
+        "SELECT id, project_id, title, content, created_at, updated_at FROM notes WHERE id = ?",
+    )
+    .bind(note_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| format!("Failed to fetch note: {}", e))?
+    .ok_or_else(|| format!("Note not found: {}", note_id))
+}
+
+/// Append `text` as a new paragraph of the project's note titled `title`,
+/// creating the note if there isn't one
+pub async fn append_to_note(pool: &SqlitePool, project_id: &str, title: &str, text: &str) -> Result<()> {
+    let note = Note::new(project_id.to_string(), title.to_string(), text.to_string());
+    sqlx::query(
+        r#"
+        INSERT INTO notes (id, project_id, title, content, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?)
+        ON CONFLICT(project_id, title) DO UPDATE
+        SET content = notes.content || char(10) || char(10) || excluded.content,
+            updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(&note.id)
+    .bind(&note.project_id)
+    .bind(&note.title)
+    .bind(&note.content)
+    .bind(note.created_at)
+    .bind(note.updated_at)
+    .execute(pool)
+    .await
+    .with_context(|| format!("Failed to append to note '{}'", title))?;
+    Ok(())
+}
+
+/// The section added to the summaries note for a turn that ended with `message`
+fn summary_section(agent_type: &str, ended_at: chrono::DateTime<chrono::Utc>, message: &str) -> String {
+    let mut summary: String = message.trim().chars().take(MAX_SUMMARY_CHARS).collect();
+    if message.trim().chars().count() > MAX_SUMMARY_CHARS {
+        summary.push('…');
+    }
+    format!("## {} · {}\n\n{}", ended_at.format("%Y-%m-%d %H:%M UTC"), agent_type, summary)
+}
+
+/// Append the agent's last message of each successful turn to the project's
+/// [`SUMMARIES_NOTE_TITLE`] note while `session_summaries_to_notes` is on
+pub fn append_session_summaries(
+    pool: SqlitePool,
+    project_id: String,
+    agent_type: String,
+    mut events: broadcast::Receiver<AgentEvent>,
+) {
+    tokio::spawn(async move {
+        let mut last_message: Option<String> = None;
+        loop {
+            let event = match events.recv().await {
+                Ok(event) => event,
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            };
+            match event {
+                AgentEvent::MessageReceived { content, .. } if !content.trim().is_empty() => {
+                    last_message = Some(content);
+                }
+                AgentEvent::ProcessExited { success, .. } | AgentEvent::TurnCompleted { success, .. } => {
+                    let Some(message) = last_message.take().filter(|_| success) else {
+                        continue;
+                    };
+                    let settings: Option<Option<String>> =
+                        sqlx::query_scalar("SELECT settings FROM projects WHERE id = ?")
+                            .bind(&project_id)
+                            .fetch_optional(&pool)
+                            .await
+                            .unwrap_or_default();
+                    let Some(settings) = settings else {
+                        break;
+                    };
+                    if !ProjectSettings::from_json(settings.as_deref()).session_summaries_to_notes {
+                        continue;
+                    }
+                    let section = summary_section(&agent_type, chrono::Utc::now(), &message);
+                    if let Err(e) = append_to_note(&pool, &project_id, SUMMARIES_NOTE_TITLE, &section).await {
+                        log::warn!("Failed to save session summary for {}: {}", project_id, e);
+                    }
+                }
+                _ => {}
+            }
+        }
+    });
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+use crate::db::Database;
+use tauri::State;
+
+/// A project's notes, most recently edited first
+///
+/// `search` keeps notes whose title or content contains it, ignoring case.
+#[tauri::command]
+pub async fn get_notes(
+    db: State<'_, Database>,
+    project_id: String,
+    search: Option<String>,
+) -> Result<Vec<Note>, String> {
+    let pattern = search
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .map(|s| format!("%{}%", s));
+    sqlx::query_as::<_, Note>(
+        r#"
+        SELECT id, project_id, title, content, created_at, updated_at
+        FROM notes
+        WHERE project_id = ?1 AND (?2 IS NULL OR title LIKE ?2 OR content LIKE ?2)
+        ORDER BY updated_at DESC
+        "#,
+    )
+    .bind(&project_id)
+    .bind(&pattern)
+    .fetch_all(db.pool())
+    .await
+    .map_err(|e| format!("Failed to fetch notes: {}", e))
+}
+
+/// Get a note by id
+#[tauri::command]
+pub async fn get_note(db: State<'_, Database>, note_id: String) -> Result<Note, String> {
+    load_note(db.pool(), &note_id).await
+}
+
+/// Create a note; titles are unique within a project
+#[tauri::command]
+pub async fn create_note(
+    db: State<'_, Database>,
+    project_id: String,
+    title: String,
+    content: Option<String>,
+) -> Result<Note, String> {
+    let title = validate_title(&title)?;
+    let note = Note::new(project_id, title.to_string(), content.unwrap_or_default());
+    sqlx::query(
+        r#"
+        INSERT INTO notes (id, project_id, title, content, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&note.id)
+    .bind(&note.project_id)
+    .bind(&note.title)
+    .bind(&note.content)
+    .bind(note.created_at)
+    .bind(note.updated_at)
+    .execute(db.pool())
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::Database(ref db_err) if db_err.is_unique_violation() => {
+            format!("A note titled '{}' already exists", note.title)
+        }
+        e => format!("Failed to create note: {}", e),
+    })?;
+
+    log::info!("Created note '{}' in project {}", note.title, note.project_id);
+    Ok(note)
+}
+
+/// Rename a note or replace its content
+#[tauri::command]
+pub async fn update_note(
+    db: State<'_, Database>,
+    note_id: String,
+    title: Option<String>,
+    content: Option<String>,
+) -> Result<Note, String> {
+    let mut note = load_note(db.pool(), &note_id).await?;
+    if let Some(title) = title {
+        note.title = validate_title(&title)?.to_string();
+    }
+    if let Some(content) = content {
+        note.content = content;
+    }
+    note.updated_at = chrono::Utc::now().timestamp();
+
+    sqlx::query("UPDATE notes SET title = ?, content = ?, updated_at = ? WHERE id = ?")
+        .bind(&note.title)
+        .bind(&note.content)
+        .bind(note.updated_at)
+        .bind(&note.id)
+        .execute(db.pool())
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db_err) if db_err.is_unique_violation() => {
+                format!("A note titled '{}' already exists", note.title)
+            }
+            e => format!("Failed to update note: {}", e),
+        })?;
+    Ok(note)
+}
+
+/// Delete a note
+#[tauri::command]
+pub async fn delete_note(db: State<'_, Database>, note_id: String) -> Result<bool, String> {
+    let result = sqlx::query("DELETE FROM notes WHERE id = ?")
+        .bind(&note_id)
+        .execute(db.pool())
+        .await
+        .map_err(|e| format!("Failed to delete note: {}", e))?;
+    Ok(result.rows_affected() > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_summary_section() {
+        let ended_at = chrono::Utc.with_ymd_and_hms(2026, 10, 17, 9, 5, 0).unwrap();
+        assert_eq!(
+            summary_section("claude", ended_at, "  Renamed the config loader.\n"),
+            "## 2026-10-17 09:05 UTC · claude\n\nRenamed the config loader."
+        );
+        let long = "x".repeat(MAX_SUMMARY_CHARS + 10);
+        assert!(summary_section("claude", ended_at, &long).ends_with("x…"));
+    }
+}
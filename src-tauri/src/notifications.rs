@@ -0,0 +1,146 @@
+// Agent Notifications
+// Native OS notifications for agent turns, errors and questions while the app is in the background
+
+use sqlx::SqlitePool;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::Instrument;
+
+use crate::models::{NotificationPrefs, ProjectSettings};
+use crate::output_parser::{AgentEvent, ErrorSeverity};
+
+/// Longest notification body, in characters
+const MAX_BODY_CHARS: usize = 200;
+
+/// Agent lifecycle events that can raise a notification
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AgentNotification {
+    TurnFinished,
+    Error,
+    InputRequested,
+}
+
+impl AgentNotification {
+    /// The notification for an agent output event, with its body, if it warrants one
+    ///
+    /// Only fatal errors notify; plain error lines are too common in agent output.
+    pub fn from_agent_event(event: &AgentEvent) -> Option<(Self, String)> {
+        match event {
+            AgentEvent::ProcessExited { success: true, .. } | AgentEvent::TurnCompleted { success: true, .. } => {
+                Some((Self::TurnFinished, "The agent finished its turn".to_string()))
+            }
+            // No exit code means the process was killed, usually by a newer message
+            AgentEvent::ProcessExited { exit_code: Some(code), .. } => {
+                Some((Self::Error, format!("The agent exited with code {}", code)))
+            }
+            AgentEvent::Error { message, severity: ErrorSeverity::Fatal, .. } => {
+                Some((Self::Error, message.clone()))
+            }
+            AgentEvent::InputRequired { prompt, .. } => Some((Self::InputRequested, prompt.clone())),
+            _ => None,
+        }
+    }
+
+    fn enabled(self, prefs: &NotificationPrefs) -> bool {
+        match self {
+            Self::TurnFinished => prefs.turn_finished,
+            Self::Error => prefs.errors,
+            Self::InputRequested => prefs.input_requested,
+        }
+    }
+
+    fn title(self, project_name: &str) -> String {
+        match self {
+            Self::TurnFinished => format!("{} is ready", project_name),
+            Self::Error => format!("{} hit an error", project_name),
+            Self::InputRequested => format!("{} needs your input", project_name),
+        }
+    }
+}
+
+/// Whether any of the app's windows has focus
+fn app_focused(app: &AppHandle) -> bool {
+    app.webview_windows()
+        .values()
+        .any(|window| window.is_focused().unwrap_or(false))
+}
+
+/// Show a notification for a project, unless the app has focus or the
+/// project has turned this kind off
+pub async fn notify(app: &AppHandle, pool: &SqlitePool, project_id: &str, kind: AgentNotification, body: &str) {
+    if app_focused(app) {
+        return;
+    }
+
+    let project = sqlx::query_as::<_, (String, Option<String>)>("SELECT name, settings FROM projects WHERE id = ?")
+        .bind(project_id)
+        .fetch_optional(pool)
+        .await;
+    let (name, settings) = match project {
+        Ok(Some(project)) => project,
+        Ok(None) => return,
+        Err(e) => {
+            log::warn!("Failed to load notification settings for {}: {}", project_id, e);
+            return;
+        }
+    };
+    if !kind.enabled(&ProjectSettings::from_json(settings.as_deref()).notifications) {
+        return;
+    }
+
+    let body: String = body.chars().take(MAX_BODY_CHARS).collect();
+    if let Err(e) = app.notification().builder().title(kind.title(&name)).body(body).show() {
+        log::warn!("Failed to show {:?} notification: {}", kind, e);
+    }
+}
+
+/// Notify about an agent session's output events until the session is stopped
+pub fn forward_agent_events(
+    app: AppHandle,
+    pool: SqlitePool,
+    project_id: String,
+    mut events: broadcast::Receiver<AgentEvent>,
+) {
+    let span = tracing::info_span!("agent_notifications", project_id = %project_id);
+    tokio::spawn(async move {
+        loop {
+            match events.recv().await {
+                Ok(event) => {
+                    if let Some((kind, body)) = AgentNotification::from_agent_event(&event) {
+                        notify(&app, &pool, &project_id, kind, &body).await;
+                    }
+                }
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            }
+        }
+    }
+    .instrument(span));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_agent_event() {
+        let exited = |success, exit_code| AgentEvent::ProcessExited { success, exit_code, timestamp: 0 };
+        assert_eq!(
+            AgentNotification::from_agent_event(&exited(true, Some(0))).map(|(kind, _)| kind),
+            Some(AgentNotification::TurnFinished)
+        );
+        assert_eq!(
+            AgentNotification::from_agent_event(&exited(false, Some(2))),
+            Some((AgentNotification::Error, "The agent exited with code 2".to_string()))
+        );
+        assert!(AgentNotification::from_agent_event(&exited(false, None)).is_none());
+
+        let error = |severity| AgentEvent::Error { message: "boom".to_string(), severity, timestamp: 0 };
+        assert!(AgentNotification::from_agent_event(&error(ErrorSeverity::Error)).is_none());
+        assert_eq!(
+            AgentNotification::from_agent_event(&error(ErrorSeverity::Fatal)).map(|(kind, _)| kind),
+            Some(AgentNotification::Error)
+        );
+    }
+}
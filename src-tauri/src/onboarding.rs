@@ -0,0 +1,226 @@
+// Onboarding
+// Tracks the first-run setup steps in the settings table so the UI can guide
+// new users through them, one at a time and in order
+
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+
+use crate::error::AppError;
+use crate::plugins::demo;
+
+/// A setup step, in the order users go through them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OnboardingStep {
+    /// An agent CLI or plugin was found on this machine
+    PluginsDetected,
+    /// A project other than the sample one was created
+    FirstProjectCreated,
+    /// An agent session ran in a real project
+    FirstSessionRun,
+}
+
+impl OnboardingStep {
+    pub const ALL: [OnboardingStep; 3] = [
+        OnboardingStep::PluginsDetected,
+        OnboardingStep::FirstProjectCreated,
+        OnboardingStep::FirstSessionRun,
+    ];
+
+    /// Key of the step's row in the settings table
+    fn key(self) -> &'static str {
+        match self {
+            OnboardingStep::PluginsDetected => "onboarding.plugins_detected",
+            OnboardingStep::FirstProjectCreated => "onboarding.first_project_created",
+            OnboardingStep::FirstSessionRun => "onboarding.first_session_run",
+        }
+    }
+}
+
+/// A finished step and when it was finished (Unix seconds)
+#[derive(Debug, Clone, Serialize)]
+pub struct CompletedStep {
+    pub step: OnboardingStep,
+    pub completed_at: i64,
+}
+
+/// Where the user is in onboarding
+#[derive(Debug, Clone, Serialize)]
+pub struct OnboardingState {
+    pub completed: Vec<CompletedStep>,
+    /// The step to guide the user through; `None` once all are done
+    pub current_step: Option<OnboardingStep>,
+    pub finished: bool,
+}
+
+impl OnboardingState {
+    fn from_completed(completed_at: &HashMap<OnboardingStep, i64>) -> Self {
+        let completed: Vec<CompletedStep> = OnboardingStep::ALL
+            .into_iter()
+            .filter_map(|step| Some(CompletedStep { step, completed_at: *completed_at.get(&step)? }))
+            .collect();
+        let current_step = OnboardingStep::ALL.into_iter().find(|step| !completed_at.contains_key(step));
+        OnboardingState { completed, current_step, finished: current_step.is_none() }
+    }
+}
+
+/// When each finished step was finished
+async fn completed_steps(pool: &SqlitePool) -> Result<HashMap<OnboardingStep, i64>, AppError> {
+    let rows: Vec<(String, String)> = sqlx::query_as("SELECT key, value FROM settings WHERE key LIKE 'onboarding.%'")
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::context("Failed to load onboarding state"))?;
+    Ok(OnboardingStep::ALL
+        .into_iter()
+        .filter_map(|step| {
+            let (_, value) = rows.iter().find(|(key, _)| key == step.key())?;
+            Some((step, value.parse().ok()?))
+        })
+        .collect())
+}
+
+/// Mark `step` finished, along with the steps before it; steps keep the time
+/// they were first finished
+pub async fn complete_step(pool: &SqlitePool, step: OnboardingStep) -> Result<(), AppError> {
+    let now = chrono::Utc::now().timestamp().to_string();
+    for earlier in OnboardingStep::ALL.into_iter().take_while(|s| *s != step).chain([step]) {
+        sqlx::query("INSERT OR IGNORE INTO settings (key, value) VALUES (?, ?)")
+            .bind(earlier.key())
+            .bind(&now)
+            .execute(pool)
+            .await
+            .map_err(AppError::context("Failed to save onboarding step"))?;
+    }
+    Ok(())
+}
+
+/// The furthest step the user's data shows they're past, for users who set
+/// things up before onboarding was tracked; the sample project doesn't count
+async fn furthest_done(pool: &SqlitePool) -> Result<Option<OnboardingStep>, AppError> {
+    // File watcher rows aren't agent runs
+    let ran_session: bool = sqlx::query_scalar(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM agent_sessions s
+            JOIN projects p ON p.id = s.project_id
+            WHERE s.agent_type NOT IN (?, 'file_watcher') AND p.agent_type != ?
+        )
+        "#,
+    )
+    .bind(demo::PLUGIN_NAME)
+    .bind(demo::PLUGIN_NAME)
+    .fetch_one(pool)
+    .await
+    .map_err(AppError::context("Failed to check agent sessions"))?;
+    if ran_session {
+        return Ok(Some(OnboardingStep::FirstSessionRun));
+    }
+    let has_project: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM projects WHERE agent_type != ?)")
+        .bind(demo::PLUGIN_NAME)
+        .fetch_one(pool)
+        .await
+        .map_err(AppError::context("Failed to check projects"))?;
+    Ok(has_project.then_some(OnboardingStep::FirstProjectCreated))
+}
+
+/// The onboarding state, catching up on steps already done
+pub async fn onboarding_state(pool: &SqlitePool) -> Result<OnboardingState, AppError> {
+    let completed = completed_steps(pool).await?;
+    if completed.len() < OnboardingStep::ALL.len() {
+        if let Some(step) = furthest_done(pool).await?.filter(|step| !completed.contains_key(step)) {
+            complete_step(pool, step).await?;
+            return Ok(OnboardingState::from_completed(&completed_steps(pool).await?));
+        }
+    }
+    Ok(OnboardingState::from_completed(&completed))
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+use tauri::State;
+
+use crate::db::Database;
+
+/// Get the onboarding steps done so far and the one to show next
+#[tauri::command]
+pub async fn get_onboarding_state(db: State<'_, Database>) -> Result<OnboardingState, AppError> {
+    onboarding_state(db.pool()).await
+}
+
+/// Mark an onboarding step, and the ones before it, done
+#[tauri::command]
+pub async fn complete_onboarding_step(db: State<'_, Database>, step: OnboardingStep) -> Result<OnboardingState, AppError> {
+    complete_step(db.pool(), step).await?;
+    onboarding_state(db.pool()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_pool;
+
+    fn steps(state: &OnboardingState) -> Vec<OnboardingStep> {
+        state.completed.iter().map(|c| c.step).collect()
+    }
+
+    #[tokio::test]
+    async fn test_steps_complete_in_order() {
+        let (pool, _db) = test_pool().await;
+        let state = onboarding_state(&pool).await.unwrap();
+        assert!(state.completed.is_empty());
+        assert_eq!(state.current_step, Some(OnboardingStep::PluginsDetected));
+
+        complete_step(&pool, OnboardingStep::PluginsDetected).await.unwrap();
+        let state = onboarding_state(&pool).await.unwrap();
+        assert_eq!(steps(&state), vec![OnboardingStep::PluginsDetected]);
+        assert_eq!(state.current_step, Some(OnboardingStep::FirstProjectCreated));
+
+        // Finishing a later step finishes the ones before it
+        complete_step(&pool, OnboardingStep::FirstSessionRun).await.unwrap();
+        let state = onboarding_state(&pool).await.unwrap();
+        assert_eq!(steps(&state), OnboardingStep::ALL.to_vec());
+        assert_eq!(state.current_step, None);
+        assert!(state.finished);
+    }
+
+    #[tokio::test]
+    async fn test_catches_up_from_existing_data() {
+        let (pool, _db) = test_pool().await;
+        let insert_project = |id: &'static str, agent_type: &'static str| {
+            sqlx::query("INSERT INTO projects (id, name, root_path, agent_type, created_at, last_activity) VALUES (?, ?, ?, ?, 0, 0)")
+                .bind(id)
+                .bind(id)
+                .bind(format!("/tmp/{}", id))
+                .bind(agent_type)
+        };
+
+        // The sample project doesn't count
+        insert_project("sample", demo::PLUGIN_NAME).execute(&pool).await.unwrap();
+        assert_eq!(onboarding_state(&pool).await.unwrap().current_step, Some(OnboardingStep::PluginsDetected));
+
+        insert_project("app", "claude-code").execute(&pool).await.unwrap();
+        let state = onboarding_state(&pool).await.unwrap();
+        assert_eq!(steps(&state), vec![OnboardingStep::PluginsDetected, OnboardingStep::FirstProjectCreated]);
+        assert_eq!(state.current_step, Some(OnboardingStep::FirstSessionRun));
+
+        let insert_session = |id: &'static str, project_id: &'static str, agent_type: &'static str| {
+            sqlx::query("INSERT INTO agent_sessions (id, project_id, agent_type, started_at, status) VALUES (?, ?, ?, 0, 'completed')")
+                .bind(id)
+                .bind(project_id)
+                .bind(agent_type)
+        };
+
+        // Neither a file watcher nor a session in the sample project counts as a run
+        insert_session("watcher", "app", "file_watcher").execute(&pool).await.unwrap();
+        insert_session("sample-run", "sample", "claude-code").execute(&pool).await.unwrap();
+        assert_eq!(onboarding_state(&pool).await.unwrap().current_step, Some(OnboardingStep::FirstSessionRun));
+
+        insert_session("run", "app", "claude-code").execute(&pool).await.unwrap();
+        let state = onboarding_state(&pool).await.unwrap();
+        assert_eq!(steps(&state), OnboardingStep::ALL.to_vec());
+        assert!(state.finished);
+    }
+}
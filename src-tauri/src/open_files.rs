@@ -0,0 +1,239 @@
+// Open Files
+// Tells a window when a file shown in its viewer changes on disk, whether an
+// agent or anything else wrote it, so it can offer to reload the file instead
+// of showing stale content
+
+use notify::{Config, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::collections::hash_map::{Entry, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter};
+
+use crate::error::AppError;
+use crate::file_watcher::file_content_hash;
+
+/// Emitted to the window that watches the file
+pub const OPEN_FILE_CHANGED_EVENT: &str = "open-file-changed";
+
+/// Payload of [`OPEN_FILE_CHANGED_EVENT`]
+#[derive(Debug, Clone, Serialize)]
+pub struct OpenFileChanged {
+    pub project_id: String,
+    /// As passed to `watch_open_file`
+    pub file_path: String,
+    /// SHA-256 of the new content; `None` once the file is gone
+    pub content_hash: Option<String>,
+}
+
+struct OpenFile {
+    project_id: String,
+    file_path: String,
+    last_hash: Option<String>,
+}
+
+/// A window's watcher and the files it has open, keyed by canonical path
+struct WindowFiles {
+    watcher: RecommendedWatcher,
+    files: Arc<Mutex<HashMap<PathBuf, OpenFile>>>,
+}
+
+/// Open-file watches of every window, keyed by window label
+///
+/// Files are watched through their folder, so editors that save by writing a
+/// new file and renaming it over the old one are caught too.
+#[derive(Default)]
+pub struct OpenFileWatcher(Mutex<HashMap<String, WindowFiles>>);
+
+/// The update to emit for an event on an open file, if its content changed
+fn changed(file: &mut OpenFile, path: &Path) -> Option<OpenFileChanged> {
+    let hash = file_content_hash(path);
+    if hash == file.last_hash {
+        return None;
+    }
+    file.last_hash = hash.clone();
+    Some(OpenFileChanged {
+        project_id: file.project_id.clone(),
+        file_path: file.file_path.clone(),
+        content_hash: hash,
+    })
+}
+
+impl OpenFileWatcher {
+    fn window_files(app: &AppHandle, label: &str) -> Result<WindowFiles, AppError> {
+        let files: Arc<Mutex<HashMap<PathBuf, OpenFile>>> = Arc::default();
+        let event_files = files.clone();
+        let app = app.clone();
+        let window = label.to_string();
+        let watcher = RecommendedWatcher::new(
+            move |result: Result<Event, notify::Error>| {
+                let event = match result {
+                    Ok(event) => event,
+                    Err(e) => {
+                        log::warn!("Open file watch error: {:?}", e);
+                        return;
+                    }
+                };
+                if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)) {
+                    return;
+                }
+                let updates: Vec<OpenFileChanged> = {
+                    let mut files = event_files.lock().unwrap();
+                    event
+                        .paths
+                        .iter()
+                        .filter_map(|path| changed(files.get_mut(path)?, path))
+                        .collect()
+                };
+                for update in updates {
+                    if let Err(e) = app.emit_to(window.as_str(), OPEN_FILE_CHANGED_EVENT, &update) {
+                        log::warn!("Failed to emit {}: {}", OPEN_FILE_CHANGED_EVENT, e);
+                    }
+                }
+            },
+            Config::default(),
+        )
+        .map_err(|e| AppError::internal(format!("Failed to create file watcher: {}", e)))?;
+        Ok(WindowFiles { watcher, files })
+    }
+
+    /// Watch `target` (canonical) for a window; returns its current hash
+    fn watch(
+        &self,
+        app: &AppHandle,
+        label: &str,
+        target: PathBuf,
+        project_id: String,
+        file_path: String,
+    ) -> Result<Option<String>, AppError> {
+        let folder = target
+            .parent()
+            .ok_or_else(|| AppError::invalid_input(format!("Not a file: {}", file_path)))?
+            .to_path_buf();
+        let mut windows = self.0.lock().unwrap();
+        let window = match windows.entry(label.to_string()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(Self::window_files(app, label)?),
+        };
+
+        // Not holding the files while (un)watching: the event handler takes them
+        let folder_watched = window.files.lock().unwrap().keys().any(|open| open.parent() == Some(folder.as_path()));
+        if !folder_watched {
+            window
+                .watcher
+                .watch(&folder, RecursiveMode::NonRecursive)
+                .map_err(|e| AppError::internal(format!("Failed to watch {}: {}", folder.display(), e)))?;
+        }
+        let last_hash = file_content_hash(&target);
+        let file = OpenFile { project_id, file_path, last_hash: last_hash.clone() };
+        window.files.lock().unwrap().insert(target, file);
+        Ok(last_hash)
+    }
+
+    /// Stop watching `target` for a window
+    fn unwatch(&self, label: &str, target: &Path) {
+        let mut windows = self.0.lock().unwrap();
+        let Some(window) = windows.get_mut(label) else {
+            return;
+        };
+        let Some(folder) = target.parent() else {
+            return;
+        };
+        let folder_in_use = {
+            let mut files = window.files.lock().unwrap();
+            if files.remove(target).is_none() {
+                return;
+            }
+            files.keys().any(|open| open.parent() == Some(folder))
+        };
+        if !folder_in_use {
+            if let Err(e) = window.watcher.unwatch(folder) {
+                log::debug!("Failed to unwatch {}: {}", folder.display(), e);
+            }
+        }
+    }
+
+    /// Drop the watches of a closed window
+    pub fn release_window(&self, label: &str) {
+        self.0.lock().unwrap().remove(label);
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+use tauri::State;
+
+use crate::commands::get_project;
+use crate::db::Database;
+use crate::project_roots;
+
+/// Resolve a file of a project to the canonical path that is watched
+async fn open_file_target(db: State<'_, Database>, project_id: &str, file_path: &str) -> Result<PathBuf, AppError> {
+    let project = get_project(db.clone(), project_id.to_string())
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Project not found: {}", project_id)))?;
+    let (_, target) = project_roots::resolve(db.pool(), project_id, &project.root_path, file_path).await?;
+    Ok(target)
+}
+
+/// Watch a file open in the viewer, emitting `open-file-changed` to the calling
+/// window whenever its content changes on disk
+///
+/// Returns the file's current content hash, to compare with the one in events.
+#[tauri::command]
+pub async fn watch_open_file(
+    app: AppHandle,
+    window: tauri::Window,
+    db: State<'_, Database>,
+    open_files: State<'_, OpenFileWatcher>,
+    project_id: String,
+    file_path: String,
+) -> Result<Option<String>, AppError> {
+    let target = open_file_target(db, &project_id, &file_path).await?;
+    open_files.watch(&app, window.label(), target, project_id, file_path)
+}
+
+/// Stop watching a file closed in the viewer
+#[tauri::command]
+pub async fn unwatch_open_file(
+    window: tauri::Window,
+    db: State<'_, Database>,
+    open_files: State<'_, OpenFileWatcher>,
+    project_id: String,
+    file_path: String,
+) -> Result<(), AppError> {
+    let target = open_file_target(db, &project_id, &file_path).await?;
+    open_files.unwatch(window.label(), &target);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changed_only_when_content_differs() {
+        let path = std::env::temp_dir().join(format!("ateliercode-open-file-{}.txt", uuid::Uuid::new_v4()));
+        std::fs::write(&path, "one").unwrap();
+        let mut file = OpenFile {
+            project_id: "p1".to_string(),
+            file_path: "notes.txt".to_string(),
+            last_hash: file_content_hash(&path),
+        };
+
+        // Touching the file or rewriting the same content isn't a change
+        std::fs::write(&path, "one").unwrap();
+        assert!(changed(&mut file, &path).is_none());
+
+        std::fs::write(&path, "two").unwrap();
+        let update = changed(&mut file, &path).unwrap();
+        assert_eq!(update.file_path, "notes.txt");
+        assert_eq!(update.content_hash, file_content_hash(&path));
+        assert!(changed(&mut file, &path).is_none());
+
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(changed(&mut file, &path).unwrap().content_hash, None);
+    }
+}
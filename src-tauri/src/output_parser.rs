@@ -1,5 +1,6 @@
 use regex::Regex;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 /// Events that can be parsed from agent output
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -76,6 +77,19 @@ pub enum AgentEvent {
         line: String,
         timestamp: i64,
     },
+
+    /// Agent process exited
+    ProcessExited {
+        success: bool,
+        exit_code: Option<i32>,
+        timestamp: i64,
+    },
+
+    /// Agent finished answering a message but its process keeps running
+    TurnCompleted {
+        success: bool,
+        timestamp: i64,
+    },
 }
 
 /// Type of file change
@@ -483,10 +497,151 @@ impl Default for OutputParser {
     }
 }
 
+/// What one line of Claude's `--output-format stream-json` output holds
+#[derive(Debug, Default)]
+pub struct StreamLine {
+    /// Claude's session id, announced when the process starts
+    pub session_id: Option<String>,
+    pub events: Vec<AgentEvent>,
+}
+
+/// Parser for Claude's stream-json output, where every line is a JSON event
+///
+/// Tool calls arrive as structured blocks, so file edits and commands are read
+/// from them; the assistant's text still goes through [`OutputParser`].
+#[derive(Default)]
+pub struct ClaudeStreamParser {
+    /// Bash commands waiting for their result, by tool use id
+    pending_commands: std::collections::HashMap<String, String>,
+}
+
+impl ClaudeStreamParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse one output line; lines that aren't JSON are parsed as text
+    pub fn parse_line(&mut self, text_parser: &OutputParser, line: &str) -> StreamLine {
+        let Ok(record) = serde_json::from_str::<Value>(line) else {
+            return StreamLine { session_id: None, events: text_parser.parse_line(line) };
+        };
+        let now = chrono::Utc::now().timestamp();
+        let blocks = record.pointer("/message/content").and_then(Value::as_array);
+        let mut parsed = StreamLine::default();
+
+        match str_field(&record, "type").as_deref() {
+            Some("system") if str_field(&record, "subtype").as_deref() == Some("init") => {
+                parsed.session_id = str_field(&record, "session_id");
+            }
+            Some("assistant") => {
+                for block in blocks.into_iter().flatten() {
+                    self.parse_assistant_block(text_parser, block, now, &mut parsed.events);
+                }
+            }
+            // Tool results come back as user messages
+            Some("user") => {
+                for block in blocks.into_iter().flatten() {
+                    parsed.events.extend(self.parse_tool_result(block, now));
+                }
+            }
+            Some("result") => {
+                let failed = record.get("is_error").and_then(Value::as_bool).unwrap_or(false)
+                    || str_field(&record, "subtype").is_some_and(|subtype| subtype != "success");
+                if failed {
+                    let message = str_field(&record, "result")
+                        .unwrap_or_else(|| "Claude couldn't finish the turn".to_string());
+                    parsed.events.push(AgentEvent::Error { message, severity: ErrorSeverity::Error, timestamp: now });
+                }
+                parsed.events.push(AgentEvent::TurnCompleted { success: !failed, timestamp: now });
+            }
+            _ => {}
+        }
+        parsed
+    }
+
+    fn parse_assistant_block(
+        &mut self,
+        text_parser: &OutputParser,
+        block: &Value,
+        now: i64,
+        events: &mut Vec<AgentEvent>,
+    ) {
+        let input = block.get("input").cloned().unwrap_or_default();
+        match (str_field(block, "type").as_deref(), str_field(block, "name").as_deref()) {
+            (Some("text"), _) => {
+                let text = str_field(block, "text").unwrap_or_default();
+                let lines: Vec<String> = text.lines().map(str::to_string).collect();
+                events.extend(text_parser.parse_lines(&lines));
+                events.push(AgentEvent::MessageReceived { content: text, timestamp: now });
+            }
+            (Some("tool_use"), Some("Write" | "Edit" | "MultiEdit" | "NotebookEdit")) => {
+                if let Some(path) = str_field(&input, "file_path").or_else(|| str_field(&input, "notebook_path")) {
+                    events.push(AgentEvent::FileChanged { path, change_type: FileChangeType::Modified, timestamp: now });
+                }
+            }
+            (Some("tool_use"), Some("Bash")) => {
+                if let (Some(id), Some(command)) = (str_field(block, "id"), str_field(&input, "command")) {
+                    self.pending_commands.insert(id, command);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// The command a tool_result block finishes, if it answers a Bash call
+    fn parse_tool_result(&mut self, block: &Value, now: i64) -> Option<AgentEvent> {
+        let command = self.pending_commands.remove(&str_field(block, "tool_use_id")?)?;
+        let output = match block.get("content") {
+            Some(Value::String(text)) => Some(text.clone()),
+            Some(Value::Array(parts)) => {
+                Some(parts.iter().filter_map(|p| p.get("text").and_then(Value::as_str)).collect::<Vec<_>>().join("\n"))
+            }
+            _ => None,
+        };
+        let failed = block.get("is_error").and_then(Value::as_bool).unwrap_or(false);
+        Some(AgentEvent::CommandExecuted { command, exit_code: if failed { 1 } else { 0 }, output, timestamp: now })
+    }
+}
+
+fn str_field(value: &Value, key: &str) -> Option<String> {
+    value.get(key).and_then(Value::as_str).map(str::to_string)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_claude_stream_parser() {
+        let text = OutputParser::new();
+        let mut parser = ClaudeStreamParser::new();
+
+        let init = parser.parse_line(&text, r#"{"type":"system","subtype":"init","session_id":"s-1","tools":[]}"#);
+        assert_eq!(init.session_id.as_deref(), Some("s-1"));
+
+        let call = parser.parse_line(
+            &text,
+            r#"{"type":"assistant","message":{"content":[{"type":"text","text":"Running the tests"},{"type":"tool_use","id":"t1","name":"Bash","input":{"command":"cargo test"}},{"type":"tool_use","id":"t2","name":"Edit","input":{"file_path":"src/lib.rs"}}]}}"#,
+        );
+        assert!(call.events.iter().any(|e| matches!(e, AgentEvent::MessageReceived { content, .. } if content == "Running the tests")));
+        assert!(call.events.iter().any(|e| matches!(e, AgentEvent::FileChanged { path, .. } if path == "src/lib.rs")));
+
+        let result = parser.parse_line(
+            &text,
+            r#"{"type":"user","message":{"content":[{"type":"tool_result","tool_use_id":"t1","content":"ok","is_error":true}]}}"#,
+        );
+        assert!(matches!(
+            &result.events[..],
+            [AgentEvent::CommandExecuted { command, exit_code: 1, .. }] if command == "cargo test"
+        ));
+
+        let done = parser.parse_line(&text, r#"{"type":"result","subtype":"success","is_error":false,"result":"Done"}"#);
+        assert!(matches!(&done.events[..], [AgentEvent::TurnCompleted { success: true, .. }]));
+
+        let plain = parser.parse_line(&text, "Error: not json");
+        assert!(plain.events.iter().any(|e| matches!(e, AgentEvent::Error { .. })));
+    }
+
     #[test]
     fn test_parse_file_created() {
         let parser = OutputParser::new();
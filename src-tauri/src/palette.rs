@@ -0,0 +1,310 @@
+// Command Palette
+// Fuzzy search across projects, files, tasks, notes, chat tabs and recent sessions
+// for the UI's quick-open palette
+
+use ignore::WalkBuilder;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How long a project's file list is reused before walking the tree again
+const FILE_INDEX_TTL: Duration = Duration::from_secs(30);
+/// Stop indexing very large trees after this many files
+const MAX_INDEXED_FILES: usize = 20_000;
+const DEFAULT_LIMIT: usize = 50;
+/// Sessions older than the most recent ones aren't worth offering
+const RECENT_SESSION_LIMIT: i64 = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PaletteItemKind {
+    Project,
+    File,
+    Task,
+    Note,
+    ChatTab,
+    Session,
+}
+
+/// One palette result
+#[derive(Debug, Clone, Serialize)]
+pub struct PaletteItem {
+    pub kind: PaletteItemKind,
+    /// Id of the project, task, note, tab or session; the relative path for files
+    pub id: String,
+    pub title: String,
+    pub subtitle: Option<String>,
+    /// Project the item belongs to (the project itself for projects)
+    pub project_id: String,
+    /// Higher is a better match
+    pub score: i64,
+}
+
+/// Score `candidate` against `query` as a case-insensitive subsequence match
+///
+/// Returns `None` when not every query character appears in order. Runs of
+/// consecutive characters, matches at word starts and short candidates score higher.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    let query: Vec<char> = query.chars().filter(|c| !c.is_whitespace()).flat_map(char::to_lowercase).collect();
+    if query.is_empty() {
+        return Some(0);
+    }
+    let chars: Vec<char> = candidate.chars().collect();
+
+    let mut score = 0;
+    let mut qi = 0;
+    let mut previous_match: Option<usize> = None;
+    for (i, c) in chars.iter().enumerate() {
+        if qi == query.len() {
+            break;
+        }
+        if !c.to_lowercase().eq(std::iter::once(query[qi])) {
+            continue;
+        }
+
+        score += 1;
+        if previous_match == Some(i.wrapping_sub(1)) {
+            score += 5;
+        }
+        let at_word_start = i == 0
+            || matches!(chars[i - 1], '/' | '\\' | '_' | '-' | '.' | ' ')
+            || (chars[i - 1].is_lowercase() && c.is_uppercase());
+        if at_word_start {
+            score += 8;
+        }
+        previous_match = Some(i);
+        qi += 1;
+    }
+
+    if qi < query.len() {
+        return None;
+    }
+    Some(score * 10 - chars.len() as i64)
+}
+
+struct IndexedFiles {
+    built_at: Instant,
+    files: Arc<Vec<String>>,
+}
+
+/// Score a note by its title, or weakly by its text containing `query`
+fn score_note(query: &str, title: &str, content: &str) -> Option<i64> {
+    let by_title = fuzzy_score(query, title);
+    let query = query.to_lowercase();
+    let in_content = (!query.is_empty() && content.to_lowercase().contains(&query)).then_some(0);
+    by_title.max(in_content)
+}
+
+/// Files of each project relative to its root, cached for a short while
+#[derive(Default)]
+pub struct FileIndex(Mutex<HashMap<String, IndexedFiles>>);
+
+impl FileIndex {
+    async fn files(&self, project_id: &str, root_path: &str) -> Arc<Vec<String>> {
+        if let Some(indexed) = self.0.lock().unwrap().get(project_id) {
+            if indexed.built_at.elapsed() < FILE_INDEX_TTL {
+                return indexed.files.clone();
+            }
+        }
+
+        let root = root_path.to_string();
+        let files = tokio::task::spawn_blocking(move || list_files(Path::new(&root)))
+            .await
+            .unwrap_or_default();
+        let files = Arc::new(files);
+        self.0
+            .lock()
+            .unwrap()
+            .insert(project_id.to_string(), IndexedFiles { built_at: Instant::now(), files: files.clone() });
+        files
+    }
+}
+
+/// Files under `root` that aren't gitignored, with `/` separators
+fn list_files(root: &Path) -> Vec<String> {
+    WalkBuilder::new(root)
+        .hidden(false)
+        .git_ignore(true)
+        .git_exclude(true)
+        .ignore(true)
+        .filter_entry(|e| e.file_name() != ".git")
+        .build()
+        .filter_map(Result::ok)
+        .filter(|e| e.file_type().is_some_and(|t| t.is_file()))
+        .filter_map(|e| {
+            e.path()
+                .strip_prefix(root)
+                .ok()
+                .map(|p| p.to_string_lossy().replace('\\', "/"))
+        })
+        .take(MAX_INDEXED_FILES)
+        .collect()
+}
+
+/// Score a file by its name, falling back to the whole path so `src/main` still finds `src/main.rs`
+fn score_file(query: &str, path: &str) -> Option<i64> {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    let by_name = fuzzy_score(query, name).map(|s| s + 50);
+    by_name.max(fuzzy_score(query, path))
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+use crate::db::Database;
+use tauri::State;
+
+/// Search everything the palette can open in one call, best matches first
+///
+/// Files are only searched in `project_id`; tasks, notes, tabs and sessions are
+/// limited to it when given. Notes also match on their text. An empty query lists recent items without files.
+#[tauri::command]
+pub async fn palette_search(
+    db: State<'_, Database>,
+    file_index: State<'_, FileIndex>,
+    query: String,
+    project_id: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<PaletteItem>, String> {
+    let query = query.trim();
+    let mut items = Vec::new();
+    let mut push = |kind, id: String, title: String, subtitle: Option<String>, project_id: String, score| {
+        if let Some(score) = score {
+            items.push(PaletteItem { kind, id, title, subtitle, project_id, score });
+        }
+    };
+
+    let projects = sqlx::query_as::<_, (String, String, String)>(
+        "SELECT id, name, root_path FROM projects ORDER BY last_activity DESC",
+    )
+    .fetch_all(db.pool())
+    .await
+    .map_err(|e| format!("Failed to search projects: {}", e))?;
+    let project_names: HashMap<&str, &str> = projects.iter().map(|(id, name, _)| (id.as_str(), name.as_str())).collect();
+    let project_name = |id: &str| project_names.get(id).map(|name| name.to_string());
+
+    for (id, name, root_path) in &projects {
+        let score = fuzzy_score(query, name);
+        push(PaletteItemKind::Project, id.clone(), name.clone(), Some(root_path.clone()), id.clone(), score);
+    }
+
+    let tasks = sqlx::query_as::<_, (String, String, String, String)>(
+        "SELECT id, project_id, title, status FROM tasks WHERE ?1 IS NULL OR project_id = ?1 ORDER BY created_at DESC",
+    )
+    .bind(&project_id)
+    .fetch_all(db.pool())
+    .await
+    .map_err(|e| format!("Failed to search tasks: {}", e))?;
+    for (id, task_project_id, title, status) in tasks {
+        let score = fuzzy_score(query, &title);
+        let subtitle = Some(match project_name(&task_project_id) {
+            Some(name) => format!("{} · {}", name, status),
+            None => status,
+        });
+        push(PaletteItemKind::Task, id, title, subtitle, task_project_id, score);
+    }
+
+    let notes = sqlx::query_as::<_, (String, String, String, String)>(
+        "SELECT id, project_id, title, content FROM notes WHERE ?1 IS NULL OR project_id = ?1 ORDER BY updated_at DESC",
+    )
+    .bind(&project_id)
+    .fetch_all(db.pool())
+    .await
+    .map_err(|e| format!("Failed to search notes: {}", e))?;
+    for (id, note_project_id, title, content) in notes {
+        let score = score_note(query, &title, &content);
+        let subtitle = project_name(&note_project_id);
+        push(PaletteItemKind::Note, id, title, subtitle, note_project_id, score);
+    }
+
+    let tabs = sqlx::query_as::<_, (String, String, String, Option<String>)>(
+        "SELECT id, project_id, agent_type, label FROM chat_tabs WHERE ?1 IS NULL OR project_id = ?1 ORDER BY last_activity DESC",
+    )
+    .bind(&project_id)
+    .fetch_all(db.pool())
+    .await
+    .map_err(|e| format!("Failed to search chat tabs: {}", e))?;
+    for (id, tab_project_id, agent_type, label) in tabs {
+        let title = label.unwrap_or_else(|| agent_type.clone());
+        let score = fuzzy_score(query, &title).max(fuzzy_score(query, &agent_type));
+        let subtitle = project_name(&tab_project_id);
+        push(PaletteItemKind::ChatTab, id, title, subtitle, tab_project_id, score);
+    }
+
+    let sessions = sqlx::query_as::<_, (String, String, String, String, i64)>(
+        "SELECT id, project_id, agent_type, status, started_at FROM agent_sessions
+         WHERE ?1 IS NULL OR project_id = ?1 ORDER BY started_at DESC LIMIT ?2",
+    )
+    .bind(&project_id)
+    .bind(RECENT_SESSION_LIMIT)
+    .fetch_all(db.pool())
+    .await
+    .map_err(|e| format!("Failed to search sessions: {}", e))?;
+    for (id, session_project_id, agent_type, status, started_at) in sessions {
+        let title = format!("{} session", agent_type);
+        let started = chrono::DateTime::from_timestamp(started_at, 0)
+            .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_default();
+        let subtitle = Some(match project_name(&session_project_id) {
+            Some(name) => format!("{} · {} · {}", name, started, status),
+            None => format!("{} · {}", started, status),
+        });
+        let score = fuzzy_score(query, &title);
+        push(PaletteItemKind::Session, id, title, subtitle, session_project_id, score);
+    }
+
+    if let Some(project_id) = project_id.filter(|_| !query.is_empty()) {
+        if let Some((_, _, root_path)) = projects.iter().find(|(id, _, _)| *id == project_id) {
+            let files = file_index.files(&project_id, root_path).await;
+            for path in files.iter() {
+                let score = score_file(query, path);
+                let title = path.rsplit('/').next().unwrap_or(path).to_string();
+                push(PaletteItemKind::File, path.clone(), title, Some(path.clone()), project_id.clone(), score);
+            }
+        }
+    }
+
+    // Stable, so equal scores keep the recency order they were queried in
+    items.sort_by_key(|item| std::cmp::Reverse(item.score));
+    items.truncate(limit.unwrap_or(DEFAULT_LIMIT));
+    Ok(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_score_matches_subsequences() {
+        assert!(fuzzy_score("fwt", "file_watcher.rs").is_some());
+        assert!(fuzzy_score("FILE", "file_watcher.rs").is_some());
+        assert_eq!(fuzzy_score("", "anything"), Some(0));
+        assert_eq!(fuzzy_score("xyz", "file_watcher.rs"), None);
+        assert_eq!(fuzzy_score("wf", "file_watcher.rs"), None);
+    }
+
+    #[test]
+    fn test_fuzzy_score_ranking() {
+        // Consecutive and word-start matches beat scattered ones
+        assert!(fuzzy_score("main", "main.rs") > fuzzy_score("main", "my_anim_inner.rs"));
+        assert!(fuzzy_score("cT", "ChatTab.tsx") > fuzzy_score("cT", "scatter.ts"));
+        // Shorter candidates win ties
+        assert!(fuzzy_score("app", "App.tsx") > fuzzy_score("app", "App.test.tsx"));
+    }
+
+    #[test]
+    fn test_score_note_matches_text() {
+        assert!(score_note("deploy", "Deploy checklist", "") > score_note("deploy", "Decisions", "We deploy on Fridays"));
+        assert_eq!(score_note("DEPLOY", "Decisions", "we deploy on fridays"), Some(0));
+        assert_eq!(score_note("rollback", "Decisions", "We deploy on Fridays"), None);
+    }
+
+    #[test]
+    fn test_score_file_prefers_file_name() {
+        assert!(score_file("main", "src/main.rs") > score_file("main", "main/src/lib.rs"));
+        assert!(score_file("src/main", "src/main.rs").is_some());
+    }
+}
@@ -0,0 +1,835 @@
+// Patches
+// Writes recorded file changes out as a standard .patch file, so agent work
+// can be applied to another checkout or attached to an email or PR, and
+// applies patches from elsewhere as changes waiting for review
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::cli_command;
+use crate::commands::FILE_CHANGE_COLUMNS;
+use crate::error::AppError;
+use crate::file_watcher::FileWatcherManager;
+use crate::models::FileChange;
+
+/// A change with what its file looked like if it was deleted
+#[derive(Debug, sqlx::FromRow)]
+struct ExportedChange {
+    #[sqlx(flatten)]
+    change: FileChange,
+    deleted_content: Option<Vec<u8>>,
+}
+
+/// A written patch file
+#[derive(Debug, Clone, Serialize)]
+pub struct PatchExport {
+    pub path: String,
+    /// Project-relative paths of the files in the patch
+    pub files: Vec<String>,
+    /// Files left out: outside the project folder, or with nothing to diff
+    pub skipped: Vec<String>,
+}
+
+/// A file changed by an imported patch
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportedFile {
+    pub file_path: String,
+    /// "created", "modified" or "deleted"
+    pub change_type: String,
+    pub applied_hunks: usize,
+    /// The change recorded for review; `None` on a dry run
+    pub change_id: Option<String>,
+}
+
+/// A hunk, or a whole file, an imported patch couldn't apply
+#[derive(Debug, Clone, Serialize)]
+pub struct RejectedHunk {
+    pub file_path: String,
+    /// The hunk's `@@` line; `None` when the whole file was rejected
+    pub header: Option<String>,
+    pub reason: String,
+}
+
+/// What importing a patch did, or would do on a dry run
+#[derive(Debug, Clone, Serialize)]
+pub struct PatchImport {
+    pub dry_run: bool,
+    pub files: Vec<ImportedFile>,
+    pub rejected: Vec<RejectedHunk>,
+}
+
+/// Whether a stored diff has the headers `git apply` and `patch -p1` expect
+fn is_git_diff(diff: &str) -> bool {
+    diff.starts_with("diff --git ")
+}
+
+/// A diff creating `path` with `content`, or deleting it when `!added`
+fn whole_file_diff(path: &str, content: &str, added: bool) -> String {
+    let lines: Vec<&str> = content.lines().collect();
+    let (mode, old, new, range, sign) = if added {
+        ("new", "/dev/null".to_string(), format!("b/{}", path), format!("-0,0 +1,{}", lines.len()), '+')
+    } else {
+        ("deleted", format!("a/{}", path), "/dev/null".to_string(), format!("-1,{} +0,0", lines.len()), '-')
+    };
+    let mut diff = format!(
+        "diff --git a/{path} b/{path}\n{mode} file mode 100644\n--- {old}\n+++ {new}\n@@ {range} @@\n"
+    );
+    for line in lines {
+        diff.push(sign);
+        diff.push_str(line);
+        diff.push('\n');
+    }
+    if !content.is_empty() && !content.ends_with('\n') {
+        diff.push_str("\\ No newline at end of file\n");
+    }
+    diff
+}
+
+/// The diff of a file against HEAD, if git tracks it and it differs
+fn git_diff_head(root: &Path, path: &str) -> Option<String> {
+    let output = cli_command::std_command("git", ["diff", "HEAD", "--", path])
+        .current_dir(root)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    let diff = String::from_utf8_lossy(&output.stdout).to_string();
+    (!diff.trim().is_empty()).then_some(diff)
+}
+
+/// The patch section of a change; stored diffs are used when they're complete
+/// git diffs, otherwise the file is diffed again
+fn change_diff(root: &Path, exported: &ExportedChange) -> Option<String> {
+    let change = &exported.change;
+    if let Some(diff) = change.diff.as_deref().filter(|diff| is_git_diff(diff)) {
+        return Some(diff.to_string());
+    }
+    if let Some(diff) = git_diff_head(root, &change.file_path) {
+        return Some(diff);
+    }
+    // Not tracked: the whole file was created, or deleted as captured
+    match std::fs::read_to_string(root.join(&change.file_path)) {
+        Ok(content) => Some(whole_file_diff(&change.file_path, &content, true)),
+        Err(_) if change.change_type == "deleted" => {
+            let content = String::from_utf8(exported.deleted_content.clone()?).ok()?;
+            Some(whole_file_diff(&change.file_path, &content, false))
+        }
+        Err(_) => None,
+    }
+}
+
+/// Build a patch from changes, oldest first; a file's latest change stands for
+/// it, since every recorded diff is against HEAD
+fn build_patch(root: &Path, changes: &[ExportedChange]) -> (String, Vec<String>, Vec<String>) {
+    let mut latest: BTreeMap<&str, &ExportedChange> = BTreeMap::new();
+    for exported in changes {
+        latest.insert(&exported.change.file_path, exported);
+    }
+
+    let mut patch = String::new();
+    let mut files = Vec::new();
+    let mut skipped = Vec::new();
+    for (path, exported) in latest {
+        // Files in a project's other roots have absolute paths
+        let diff = if Path::new(path).is_absolute() { None } else { change_diff(root, exported) };
+        match diff {
+            Some(diff) => {
+                patch.push_str(&diff);
+                if !diff.ends_with('\n') {
+                    patch.push('\n');
+                }
+                files.push(path.to_string());
+            }
+            None => skipped.push(path.to_string()),
+        }
+    }
+    (patch, files, skipped)
+}
+
+/// One hunk of a unified diff, lines without their prefix
+#[derive(Debug, Clone, PartialEq)]
+struct Hunk {
+    header: String,
+    old_start: usize,
+    old: Vec<String>,
+    new: Vec<String>,
+    /// The new side's last line has no newline after it
+    new_no_newline: bool,
+}
+
+/// A file's part of a patch; `None` paths are /dev/null
+#[derive(Debug, Clone, PartialEq)]
+struct FilePatch {
+    old_path: Option<String>,
+    new_path: Option<String>,
+    hunks: Vec<Hunk>,
+    /// Git binary patches and "Binary files differ" can't be applied
+    binary: bool,
+}
+
+impl FilePatch {
+    /// Where the result goes, relative to the project
+    fn path(&self) -> &str {
+        self.new_path.as_deref().or(self.old_path.as_deref()).unwrap_or_default()
+    }
+}
+
+/// A path from a ---/+++ line with its a/ or b/ prefix and any timestamp dropped
+fn patch_path(value: &str, prefix: &str) -> Option<String> {
+    let value = value.split('\t').next().unwrap_or(value).trim_end();
+    if value == "/dev/null" {
+        return None;
+    }
+    Some(value.strip_prefix(prefix).unwrap_or(value).to_string())
+}
+
+/// Old start, old line count and new line count of a header like `@@ -12,7 +12,8 @@`
+fn parse_hunk_header(header: &str) -> Option<(usize, usize, usize)> {
+    let mut ranges = header.strip_prefix("@@ -")?.split(' ');
+    let range = |value: &str| -> Option<(usize, usize)> {
+        match value.split_once(',') {
+            Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+            None => Some((value.parse().ok()?, 1)),
+        }
+    };
+    let (old_start, old_count) = range(ranges.next()?)?;
+    let (_, new_count) = range(ranges.next()?.strip_prefix('+')?)?;
+    Some((old_start, old_count, new_count))
+}
+
+/// Split a unified diff, from git or plain `diff -u`, into its files
+fn parse_patch(patch: &str) -> Vec<FilePatch> {
+    let mut files: Vec<FilePatch> = Vec::new();
+    // The current file's ---/+++ lines were read, so another --- starts a file
+    let mut paths_read = false;
+    // Old and new lines left in the current hunk
+    let mut remaining = (0usize, 0usize);
+    // Whether a `\ No newline` marker follows a line of the new side
+    let mut last_in_new = false;
+
+    for line in patch.lines() {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        if remaining != (0, 0) {
+            if let Some(hunk) = files.last_mut().and_then(|file| file.hunks.last_mut()) {
+                let text = line.get(1..).unwrap_or_default().to_string();
+                match line.chars().next() {
+                    Some(' ') | None => {
+                        hunk.old.push(text.clone());
+                        hunk.new.push(text);
+                        remaining = (remaining.0.saturating_sub(1), remaining.1.saturating_sub(1));
+                        last_in_new = true;
+                        continue;
+                    }
+                    Some('-') => {
+                        hunk.old.push(text);
+                        remaining.0 = remaining.0.saturating_sub(1);
+                        last_in_new = false;
+                        continue;
+                    }
+                    Some('+') => {
+                        hunk.new.push(text);
+                        remaining.1 = remaining.1.saturating_sub(1);
+                        last_in_new = true;
+                        continue;
+                    }
+                    Some('\\') => {}
+                    // A hunk shorter than its header says
+                    _ => remaining = (0, 0),
+                }
+            }
+        }
+
+        if line.starts_with('\\') {
+            if let Some(hunk) = files.last_mut().and_then(|file| file.hunks.last_mut()) {
+                hunk.new_no_newline |= last_in_new;
+            }
+        } else if let Some(rest) = line.strip_prefix("diff --git ") {
+            let (old, new) = rest.split_once(" b/").unwrap_or((rest, rest));
+            files.push(FilePatch {
+                old_path: patch_path(old, "a/"),
+                new_path: Some(new.to_string()),
+                hunks: Vec::new(),
+                binary: false,
+            });
+            paths_read = false;
+        } else if let Some(rest) = line.strip_prefix("--- ") {
+            if paths_read || files.last().is_none_or(|file| !file.hunks.is_empty()) {
+                files.push(FilePatch { old_path: None, new_path: None, hunks: Vec::new(), binary: false });
+            }
+            files.last_mut().unwrap().old_path = patch_path(rest, "a/");
+        } else if let Some(rest) = line.strip_prefix("+++ ") {
+            if let Some(file) = files.last_mut() {
+                file.new_path = patch_path(rest, "b/");
+                paths_read = true;
+            }
+        } else if let Some(file) = files.last_mut() {
+            if line.starts_with("new file mode") {
+                file.old_path = None;
+            } else if line.starts_with("deleted file mode") {
+                file.new_path = None;
+            } else if let Some(path) = line.strip_prefix("rename from ") {
+                file.old_path = Some(path.to_string());
+            } else if let Some(path) = line.strip_prefix("rename to ") {
+                file.new_path = Some(path.to_string());
+            } else if line.starts_with("GIT binary patch") || line.starts_with("Binary files ") {
+                file.binary = true;
+            } else if let Some((old_start, old_count, new_count)) = parse_hunk_header(line) {
+                file.hunks.push(Hunk {
+                    header: line.to_string(),
+                    old_start,
+                    old: Vec::new(),
+                    new: Vec::new(),
+                    new_no_newline: false,
+                });
+                remaining = (old_count, new_count);
+            }
+        }
+    }
+    files
+}
+
+/// A file's lines, without line endings
+#[derive(Debug, Clone, PartialEq)]
+struct FileLines {
+    lines: Vec<String>,
+    crlf: bool,
+    trailing_newline: bool,
+}
+
+impl FileLines {
+    fn parse(content: &str) -> Self {
+        let crlf = content.contains("\r\n");
+        let trailing_newline = content.is_empty() || content.ends_with('\n');
+        let lines = content
+            .lines()
+            .map(|line| line.strip_suffix('\r').unwrap_or(line).to_string())
+            .collect();
+        FileLines { lines, crlf, trailing_newline }
+    }
+
+    fn render(&self) -> String {
+        let mut content = self.lines.join(if self.crlf { "\r\n" } else { "\n" });
+        if self.trailing_newline && !self.lines.is_empty() {
+            content.push_str(if self.crlf { "\r\n" } else { "\n" });
+        }
+        content
+    }
+}
+
+/// Where a hunk's old lines are, searching outwards from where its header
+/// says, but not before `from`
+fn find_hunk(lines: &[String], hunk: &Hunk, expected: usize, from: usize) -> Option<usize> {
+    let fits = |at: usize| {
+        at >= from && at + hunk.old.len() <= lines.len() && lines[at..at + hunk.old.len()] == hunk.old[..]
+    };
+    let expected = expected.clamp(from, lines.len());
+    (0..=lines.len()).find_map(|distance| {
+        let after = expected + distance;
+        if fits(after) {
+            return Some(after);
+        }
+        let before = expected.checked_sub(distance)?;
+        fits(before).then_some(before)
+    })
+}
+
+/// Apply a file's hunks in order; returns the headers of hunks whose lines
+/// weren't found
+fn apply_hunks(file: &mut FileLines, hunks: &[Hunk]) -> Vec<String> {
+    let mut rejected = Vec::new();
+    // Lines added minus removed by the hunks applied so far
+    let mut offset: isize = 0;
+    let mut from = 0;
+    for hunk in hunks {
+        // `-12,0` inserts after line 12, `-12,3` replaces from line 12
+        let start = if hunk.old.is_empty() { hunk.old_start } else { hunk.old_start.saturating_sub(1) };
+        let expected = start.saturating_add_signed(offset);
+        let Some(at) = find_hunk(&file.lines, hunk, expected, from) else {
+            rejected.push(hunk.header.clone());
+            continue;
+        };
+        let at_end = at + hunk.old.len() == file.lines.len();
+        file.lines.splice(at..at + hunk.old.len(), hunk.new.iter().cloned());
+        if at_end && !hunk.new.is_empty() {
+            file.trailing_newline = !hunk.new_no_newline;
+        }
+        offset += hunk.new.len() as isize - hunk.old.len() as isize;
+        from = at + hunk.new.len();
+    }
+    rejected
+}
+
+/// What applying a file patch does to the file
+#[derive(Debug, Clone, PartialEq)]
+enum Outcome {
+    Write(String),
+    Delete,
+}
+
+/// A file patch applied to the file's content (`None` when it doesn't
+/// exist): the outcome unless every hunk was rejected, how many hunks applied
+/// and the rejected hunks' headers; deletions apply whole or not at all
+fn plan_file(file: &FilePatch, current: Option<&str>) -> Result<(Option<Outcome>, usize, Vec<String>), String> {
+    if file.binary {
+        return Err("Binary patches can't be applied".to_string());
+    }
+    let mut lines = match (&file.old_path, current) {
+        (None, Some(_)) => return Err("The file already exists".to_string()),
+        (None, None) => FileLines::parse(""),
+        (Some(_), Some(content)) => FileLines::parse(content),
+        (Some(_), None) => return Err("The file doesn't exist".to_string()),
+    };
+    let rejected = apply_hunks(&mut lines, &file.hunks);
+    let applied = file.hunks.len() - rejected.len();
+    if applied == 0 && !file.hunks.is_empty() {
+        return Ok((None, 0, rejected));
+    }
+    if file.new_path.is_none() {
+        if !rejected.is_empty() || !lines.lines.is_empty() {
+            return Err("The file doesn't match what the patch deletes".to_string());
+        }
+        return Ok((Some(Outcome::Delete), applied, rejected));
+    }
+    Ok((Some(Outcome::Write(lines.render())), applied, rejected))
+}
+
+/// The project's changes with `ids` oldest first, failing if any isn't
+/// found; without `ids`, its approved changes
+async fn load_exported_changes(
+    pool: &sqlx::SqlitePool,
+    project_id: &str,
+    ids: Option<&[String]>,
+) -> Result<Vec<ExportedChange>, AppError> {
+    let ids_json = ids
+        .map(|ids| serde_json::to_string(ids).map_err(AppError::context("Failed to encode change ids")))
+        .transpose()?;
+
+    let changes = sqlx::query_as::<_, ExportedChange>(&format!(
+        r#"
+        SELECT {}, deleted_content
+        FROM file_changes
+        WHERE project_id = ?1
+          AND ((?2 IS NULL AND approved = 1) OR id IN (SELECT value FROM json_each(?2)))
+        ORDER BY timestamp ASC
+        "#,
+        FILE_CHANGE_COLUMNS
+    ))
+    .bind(project_id)
+    .bind(&ids_json)
+    .fetch_all(pool)
+    .await
+    .map_err(AppError::context("Failed to fetch changes"))?;
+
+    if let Some(ids) = ids {
+        let missing: Vec<&str> = ids
+            .iter()
+            .filter(|id| !changes.iter().any(|c| &c.change.id == *id))
+            .map(String::as_str)
+            .collect();
+        if !missing.is_empty() {
+            return Err(AppError::not_found(format!("Changes not found in this project: {}", missing.join(", "))));
+        }
+    }
+    Ok(changes)
+}
+
+/// A file as it was before an import wrote it; `None` if it didn't exist
+struct Written {
+    watched: PathBuf,
+    target: PathBuf,
+    original: Option<Vec<u8>>,
+}
+
+/// Write every planned file, or none: if one write fails, the files already
+/// written are put back as they were
+///
+/// `file_path`s are relative to `root`, the project root as watched; the
+/// watcher is told to leave the writes to the import to record.
+fn write_all(
+    root: &Path,
+    writes: &[(String, PathBuf, Outcome)],
+    watchers: &FileWatcherManager,
+) -> Result<(), AppError> {
+    let mut written: Vec<Written> = Vec::with_capacity(writes.len());
+    for (file_path, target, outcome) in writes {
+        let result = match std::fs::read(target) {
+            Ok(original) => Ok(Some(original)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+        .and_then(|original| {
+            let watched = root.join(file_path);
+            let content = match outcome {
+                Outcome::Write(content) => Some(content.as_bytes()),
+                Outcome::Delete => None,
+            };
+            watchers.expect_write(&watched, content);
+            written.push(Written { watched, target: target.clone(), original });
+            match content {
+                Some(content) => target
+                    .parent()
+                    .map_or(Ok(()), std::fs::create_dir_all)
+                    .and_then(|_| std::fs::write(target, content)),
+                None => std::fs::remove_file(target),
+            }
+        });
+        if let Err(e) = result {
+            restore(&written, watchers);
+            return Err(AppError::from(e).wrap(format!("Failed to write {}; no files were changed", file_path)));
+        }
+    }
+    Ok(())
+}
+
+/// Put files back as they were before an import, newest write first
+fn restore(written: &[Written], watchers: &FileWatcherManager) {
+    for file in written.iter().rev() {
+        watchers.expect_write(&file.watched, file.original.as_deref());
+        let restored = match &file.original {
+            Some(original) => std::fs::write(&file.target, original),
+            None if file.target.exists() => std::fs::remove_file(&file.target),
+            None => Ok(()),
+        };
+        if let Err(e) = restored {
+            log::error!("Failed to restore {} after a failed import: {}", file.target.display(), e);
+        }
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+use std::collections::HashMap;
+use tauri::State;
+
+use crate::commands::get_project;
+use crate::db::Database;
+use crate::project_paths::resolve_in_project;
+
+/// Write recorded changes to a .patch file that `git apply` or `patch -p1`
+/// can apply in another checkout
+///
+/// Without `change_ids`, every approved change of the project is exported.
+#[tauri::command]
+pub async fn export_patch(
+    db: State<'_, Database>,
+    project_id: String,
+    change_ids: Option<Vec<String>>,
+    path: String,
+) -> Result<PatchExport, AppError> {
+    let project = get_project(db.clone(), project_id.clone())
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Project not found: {}", project_id)))?;
+    let changes = load_exported_changes(db.pool(), &project_id, change_ids.as_deref()).await?;
+
+    let (patch, files, skipped) = build_patch(Path::new(&project.root_path), &changes);
+    if files.is_empty() {
+        return Err(AppError::invalid_input("None of the changes has a diff to export"));
+    }
+    std::fs::write(&path, patch).map_err(AppError::context(&format!("Failed to write {}", path)))?;
+    log::info!("Exported {} files of project {} to {}", files.len(), project_id, path);
+    Ok(PatchExport { path, files, skipped })
+}
+
+/// Recorded as the session of changes made by imported patches
+const IMPORT_SESSION_ID: &str = "patch-import";
+
+/// Apply a unified diff to a project's files, recording what it changed as
+/// changes waiting for review
+///
+/// Hunks whose lines aren't found are reported and skipped, as are files that
+/// can't be patched. A dry run reports the same without touching any file.
+/// If writing any file fails, none of them are changed.
+#[tauri::command]
+pub async fn import_patch(
+    db: State<'_, Database>,
+    watchers: State<'_, FileWatcherManager>,
+    project_id: String,
+    path: String,
+    dry_run: bool,
+) -> Result<PatchImport, AppError> {
+    let project = get_project(db.clone(), project_id.clone())
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Project not found: {}", project_id)))?;
+    let patch = std::fs::read_to_string(&path).map_err(AppError::context(&format!("Failed to read {}", path)))?;
+    let file_patches = parse_patch(&patch);
+    if file_patches.is_empty() {
+        return Err(AppError::invalid_input(format!("No file diffs found in {}", path)));
+    }
+
+    let root = Path::new(&project.root_path);
+    // Contents as earlier parts of the patch left them; `None` once deleted
+    let mut patched: HashMap<PathBuf, Option<String>> = HashMap::new();
+    let mut writes: Vec<(String, PathBuf, Outcome)> = Vec::new();
+    let mut files = Vec::new();
+    let mut rejected = Vec::new();
+    for file_patch in &file_patches {
+        let file_path = file_patch.path().to_string();
+        let reject_file = |reason: String| RejectedHunk { file_path: file_path.clone(), header: None, reason };
+        let resolve = |rel: &Option<String>| -> Result<Option<PathBuf>, String> {
+            rel.as_ref().map(|rel| resolve_in_project(root, rel)).transpose()
+        };
+        let (source, target) = match (resolve(&file_patch.old_path), resolve(&file_patch.new_path)) {
+            (Ok(source), Ok(target)) => (source, target),
+            (Err(e), _) | (_, Err(e)) => {
+                rejected.push(reject_file(e));
+                continue;
+            }
+        };
+        let current = match source.as_ref().or(target.as_ref()) {
+            Some(source) => match patched.get(source) {
+                Some(content) => content.clone(),
+                None if source.exists() => match std::fs::read_to_string(source) {
+                    Ok(content) => Some(content),
+                    Err(e) => {
+                        rejected.push(reject_file(format!("Failed to read the file: {}", e)));
+                        continue;
+                    }
+                },
+                None => None,
+            },
+            None => None,
+        };
+
+        let (outcome, applied_hunks, hunks_rejected) = match plan_file(file_patch, current.as_deref()) {
+            Ok(plan) => plan,
+            Err(reason) => {
+                rejected.push(reject_file(reason));
+                continue;
+            }
+        };
+        rejected.extend(hunks_rejected.into_iter().map(|header| RejectedHunk {
+            file_path: file_path.clone(),
+            header: Some(header),
+            reason: "Its lines weren't found in the file".to_string(),
+        }));
+        let Some(outcome) = outcome else { continue };
+
+        // A rename writes the new file and deletes the old one
+        let renamed_from = source.clone().filter(|source| Some(source) != target.as_ref());
+        let change_type = match (&outcome, &file_patch.old_path) {
+            (Outcome::Delete, _) => "deleted",
+            (_, None) => "created",
+            _ if renamed_from.is_some() => "created",
+            _ => "modified",
+        };
+        if let Some(source) = renamed_from {
+            patched.insert(source.clone(), None);
+            let old_path = file_patch.old_path.clone().unwrap_or_default();
+            files.push(ImportedFile {
+                file_path: old_path.clone(),
+                change_type: "deleted".to_string(),
+                applied_hunks: 0,
+                change_id: None,
+            });
+            writes.push((old_path, source, Outcome::Delete));
+        }
+        let target = target.or(source).unwrap_or_default();
+        patched.insert(target.clone(), match &outcome {
+            Outcome::Write(content) => Some(content.clone()),
+            Outcome::Delete => None,
+        });
+        files.push(ImportedFile {
+            file_path: file_path.clone(),
+            change_type: change_type.to_string(),
+            applied_hunks,
+            change_id: None,
+        });
+        writes.push((file_path, target, outcome));
+    }
+
+    if !dry_run {
+        write_all(root, &writes, &watchers)?;
+        for ((file_path, _, _), imported) in writes.iter().zip(files.iter_mut()) {
+            let change_id = crate::file_watcher::record_file_change(
+                db.pool(),
+                &project_id,
+                IMPORT_SESSION_ID,
+                &project.root_path,
+                // Recorded under the project's own root spelling so it relativizes
+                &root.join(file_path),
+                &imported.change_type,
+            )
+            .await
+            .map_err(AppError::context("Failed to record change"))?;
+            imported.change_id = Some(change_id);
+        }
+        log::info!("Imported {} into project {}: {} files, {} rejected", path, project_id, files.len(), rejected.len());
+    }
+    Ok(PatchImport { dry_run, files, rejected })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exported(path: &str, change_type: &str, diff: Option<&str>, deleted_content: Option<&str>) -> ExportedChange {
+        let mut change = FileChange::new("p1".to_string(), "s1".to_string(), path.to_string(), change_type.to_string());
+        change.diff = diff.map(str::to_string);
+        ExportedChange { change, deleted_content: deleted_content.map(|c| c.as_bytes().to_vec()) }
+    }
+
+    #[test]
+    fn test_write_all_restores_on_failure() {
+        let root = std::env::temp_dir().join(format!("ateliercode-patch-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join("a.txt"), "old\n").unwrap();
+        std::fs::write(root.join("blocker"), "a file, not a folder\n").unwrap();
+        let watchers = FileWatcherManager::new(crate::git_status::GitStatusCache::default());
+
+        let writes = vec![
+            ("a.txt".to_string(), root.join("a.txt"), Outcome::Write("new\n".to_string())),
+            ("b.txt".to_string(), root.join("b.txt"), Outcome::Write("b\n".to_string())),
+            ("blocker/c.txt".to_string(), root.join("blocker/c.txt"), Outcome::Write("c\n".to_string())),
+        ];
+        assert!(write_all(&root, &writes, &watchers).is_err());
+        assert_eq!(std::fs::read_to_string(root.join("a.txt")).unwrap(), "old\n");
+        assert!(!root.join("b.txt").exists());
+
+        write_all(&root, &writes[..2], &watchers).unwrap();
+        assert_eq!(std::fs::read_to_string(root.join("a.txt")).unwrap(), "new\n");
+        assert_eq!(std::fs::read_to_string(root.join("b.txt")).unwrap(), "b\n");
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_whole_file_diff() {
+        assert_eq!(
+            whole_file_diff("src/a.txt", "one\ntwo", true),
+            "diff --git a/src/a.txt b/src/a.txt\nnew file mode 100644\n--- /dev/null\n+++ b/src/a.txt\n\
+             @@ -0,0 +1,2 @@\n+one\n+two\n\\ No newline at end of file\n"
+        );
+        assert_eq!(
+            whole_file_diff("a.txt", "gone\n", false),
+            "diff --git a/a.txt b/a.txt\ndeleted file mode 100644\n--- a/a.txt\n+++ /dev/null\n@@ -1,1 +0,0 @@\n-gone\n"
+        );
+    }
+
+    #[test]
+    fn test_build_patch() {
+        let root = std::env::temp_dir().join(format!("ateliercode-patch-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join("new.txt"), "hello\n").unwrap();
+        let old = "diff --git a/lib.rs b/lib.rs\n--- a/lib.rs\n+++ b/lib.rs\n@@ -1 +1 @@\n-a\n+b\n";
+        let new = "diff --git a/lib.rs b/lib.rs\n--- a/lib.rs\n+++ b/lib.rs\n@@ -1 +1 @@\n-a\n+c";
+        let changes = vec![
+            exported("lib.rs", "modified", Some(old), None),
+            // Not a git diff: the file is diffed again
+            exported("new.txt", "created", Some("--- /dev/null\n+++ new.txt\n"), None),
+            exported("lib.rs", "modified", Some(new), None),
+            exported("old.txt", "deleted", None, Some("bye\n")),
+            exported("/elsewhere/x.txt", "modified", Some(old), None),
+            exported("empty.txt", "deleted", None, None),
+        ];
+
+        let (patch, files, skipped) = build_patch(&root, &changes);
+        assert_eq!(files, vec!["lib.rs", "new.txt", "old.txt"]);
+        assert_eq!(skipped, vec!["/elsewhere/x.txt", "empty.txt"]);
+        assert_eq!(
+            patch,
+            format!(
+                "{}\n{}{}",
+                new,
+                whole_file_diff("new.txt", "hello\n", true),
+                whole_file_diff("old.txt", "bye\n", false)
+            )
+        );
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    fn lines(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_patch() {
+        let patch = "diff --git a/src/db.sql b/src/db.sql\nindex 1..2 100644\n--- a/src/db.sql\n+++ b/src/db.sql\n\
+                     @@ -1,2 +1,2 @@\n -- keep\n--- old comment\n+-- new comment\n\
+                     diff --git a/gone.txt b/gone.txt\ndeleted file mode 100644\n--- a/gone.txt\n+++ /dev/null\n@@ -1 +0,0 @@\n-bye\n\
+                     --- notes.txt\t2026-10-17 10:00:00\n+++ notes.txt\t2026-10-17 10:01:00\n@@ -1 +1 @@\n-a\n\\ No newline at end of file\n+b\n\\ No newline at end of file\n";
+        let files = parse_patch(patch);
+        assert_eq!(files.len(), 3);
+
+        // Removed lines starting with "--" aren't taken for file headers
+        assert_eq!(files[0].path(), "src/db.sql");
+        assert_eq!(files[0].hunks[0].old, lines(&["-- keep", "-- old comment"]));
+        assert_eq!(files[0].hunks[0].new, lines(&["-- keep", "-- new comment"]));
+
+        assert_eq!((files[1].old_path.as_deref(), files[1].new_path.as_deref()), (Some("gone.txt"), None));
+        assert_eq!(files[1].hunks[0].old, lines(&["bye"]));
+
+        assert_eq!((files[2].old_path.as_deref(), files[2].path()), (Some("notes.txt"), "notes.txt"));
+        assert!(files[2].hunks[0].new_no_newline);
+    }
+
+    #[test]
+    fn test_apply_hunks_with_offset_and_rejects() {
+        let mut file = FileLines::parse("zero\na\nb\nc\nd\ne\n");
+        let hunk = |header: &str, old_start: usize, old: &[&str], new: &[&str]| Hunk {
+            header: header.to_string(),
+            old_start,
+            old: lines(old),
+            new: lines(new),
+            new_no_newline: false,
+        };
+        let hunks = [
+            // A line was added above since the patch was made
+            hunk("@@ -1,2 +1,3 @@", 1, &["a", "b"], &["a", "a2", "b"]),
+            hunk("@@ -3,1 +4,1 @@", 3, &["missing"], &["x"]),
+            hunk("@@ -5,1 +6,1 @@", 5, &["e"], &["E"]),
+        ];
+        let rejected = apply_hunks(&mut file, &hunks);
+        assert_eq!(rejected, vec!["@@ -3,1 +4,1 @@"]);
+        assert_eq!(file.render(), "zero\na\na2\nb\nc\nd\nE\n");
+    }
+
+    #[test]
+    fn test_plan_file() {
+        let created = parse_patch(&whole_file_diff("new.txt", "one\ntwo", true)).remove(0);
+        assert_eq!(plan_file(&created, None), Ok((Some(Outcome::Write("one\ntwo".to_string())), 1, vec![])));
+        assert!(plan_file(&created, Some("")).is_err());
+
+        let deleted = parse_patch(&whole_file_diff("old.txt", "bye\n", false)).remove(0);
+        assert_eq!(plan_file(&deleted, Some("bye\n")), Ok((Some(Outcome::Delete), 1, vec![])));
+        assert!(plan_file(&deleted, Some("bye\nand more\n")).is_err());
+
+        let modified = parse_patch("--- a/x.txt\n+++ b/x.txt\n@@ -1 +1 @@\n-a\n+b\n").remove(0);
+        assert_eq!(plan_file(&modified, Some("a\r\n")), Ok((Some(Outcome::Write("b\r\n".to_string())), 1, vec![])));
+        assert_eq!(plan_file(&modified, Some("c\n")), Ok((None, 0, vec!["@@ -1 +1 @@".to_string()])));
+        assert!(plan_file(&modified, None).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_load_exported_changes() {
+        let (pool, _db) = crate::db::test_pool().await;
+
+        let root = std::env::temp_dir().join(format!("ateliercode-patch-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&root).unwrap();
+        let root_path = root.to_string_lossy().to_string();
+        let project = crate::models::Project::new("Test".to_string(), root_path.clone(), "claude-code".to_string());
+        crate::commands::insert_project(&pool, &project).await.unwrap();
+        let mut ids = Vec::new();
+        for name in ["a.txt", "b.txt"] {
+            std::fs::write(root.join(name), "hello\n").unwrap();
+            let path = root.join(name);
+            let record = crate::file_watcher::record_file_change(&pool, &project.id, "w", &root_path, &path, "created");
+            ids.push(record.await.unwrap());
+        }
+        sqlx::query("UPDATE file_changes SET reviewed = TRUE, approved = TRUE WHERE id = ?")
+            .bind(&ids[0])
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let approved = load_exported_changes(&pool, &project.id, None).await.unwrap();
+        assert_eq!(approved.iter().map(|c| c.change.file_path.as_str()).collect::<Vec<_>>(), vec!["a.txt"]);
+        let picked = load_exported_changes(&pool, &project.id, Some(&ids)).await.unwrap();
+        let (patch, files, _) = build_patch(&root, &picked);
+        assert_eq!(files, vec!["a.txt".to_string(), "b.txt".to_string()]);
+        assert!(patch.contains("+++ b/b.txt\n") && patch.contains("+hello\n"));
+
+        let missing = load_exported_changes(&pool, &project.id, Some(&["nope".to_string()])).await;
+        assert_eq!(missing.unwrap_err().kind, crate::error::ErrorKind::NotFound);
+    }
+}
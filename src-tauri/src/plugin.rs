@@ -698,15 +698,50 @@ impl PluginManager {
                 capabilities: p.get_capabilities(),
                 icon: p.icon().map(|s| s.to_string()),
                 color: p.color().map(|s| s.to_string()),
-                flags: p.get_available_flags(),
+                flags: with_app_flags(p.name(), p.get_available_flags()),
             })
             .collect()
     }
 
     /// Get available flags for a specific plugin
     pub fn get_plugin_flags(&self, plugin_name: &str) -> Option<Vec<PluginFlag>> {
-        self.plugins.get(plugin_name).map(|p| p.get_available_flags())
+        self.plugins
+            .get(plugin_name)
+            .map(|p| with_app_flags(p.name(), p.get_available_flags()))
+    }
+}
+
+/// A plugin's flags plus those AtelierCode handles itself for it
+fn with_app_flags(plugin_name: &str, mut flags: Vec<PluginFlag>) -> Vec<PluginFlag> {
+    let option = |value: &str, label: &str, description: &str| FlagOption {
+        value: value.to_string(),
+        label: label.to_string(),
+        description: description.to_string(),
+    };
+    if plugin_name.contains("claude") && !flags.iter().any(|f| f.id == "conversation_mode") {
+        flags.push(PluginFlag {
+            id: "conversation_mode".to_string(),
+            flag: "--input-format".to_string(),
+            label: "Conversation mode".to_string(),
+            description: "How Claude is run for each message".to_string(),
+            flag_type: FlagType::Select,
+            default_value: "per_message".to_string(),
+            options: vec![
+                option(
+                    "per_message",
+                    "Process per message",
+                    "Starts Claude for every message; a new message interrupts the current one",
+                ),
+                option(
+                    "persistent",
+                    "Persistent process",
+                    "Keeps one streaming Claude process per session for faster replies; new messages wait for the current one",
+                ),
+            ],
+            category: "session".to_string(),
+        });
     }
+    flags
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
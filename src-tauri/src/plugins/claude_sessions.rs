@@ -0,0 +1,248 @@
+// Claude Code Session Reader
+// Reads Claude Code's JSONL session files from ~/.claude/projects directly,
+// so history loads without spawning the CLI and works offline
+
+use crate::plugin::{HistoryMessage, SessionInfo};
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Root directory holding one folder per project
+///
+/// Honors `CLAUDE_CONFIG_DIR` like the CLI does.
+pub fn projects_dir() -> Option<PathBuf> {
+    std::env::var_os("CLAUDE_CONFIG_DIR")
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(".claude")))
+        .map(|dir| dir.join("projects"))
+}
+
+/// Folder name Claude Code uses for a project path
+///
+/// Every character that isn't an ASCII letter or digit becomes '-', so
+/// `/home/me/my_app` is stored as `-home-me-my-app`.
+pub fn project_dir_name(project_path: &str) -> String {
+    project_path
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect()
+}
+
+/// Find the JSONL file for a session by scanning every project folder
+pub fn find_session_file(cli_session_id: &str) -> Option<PathBuf> {
+    let file_name = format!("{}.jsonl", cli_session_id);
+    std::fs::read_dir(projects_dir()?)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path().join(&file_name))
+        .find(|path| path.is_file())
+}
+
+/// List the sessions stored for a project, most recently active first
+///
+/// Returns `None` when the project has no session folder.
+pub fn list_sessions(project_path: &str) -> Option<Vec<SessionInfo>> {
+    let dir = projects_dir()?.join(project_dir_name(project_path));
+    let entries = std::fs::read_dir(&dir).ok()?;
+
+    let mut sessions: Vec<SessionInfo> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "jsonl"))
+        .filter_map(|path| match session_info(&path) {
+            Ok(info) => Some(info),
+            Err(e) => {
+                log::warn!("Skipping unreadable Claude session {:?}: {}", path, e);
+                None
+            }
+        })
+        .collect();
+
+    sessions.sort_by_key(|s| std::cmp::Reverse(s.last_activity));
+    Some(sessions)
+}
+
+/// Read a session's conversation in chronological order
+pub fn read_history(path: &Path) -> Result<Vec<HistoryMessage>> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read session file {:?}", path))?;
+    Ok(contents.lines().filter_map(parse_record).collect())
+}
+
+/// Summarize a session file without keeping its messages
+pub fn session_info(path: &Path) -> Result<SessionInfo> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read session file {:?}", path))?;
+
+    let cli_session_id = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let mut metadata = HashMap::new();
+    let mut message_count = 0;
+    let mut started_at = None;
+    let mut last_activity = 0;
+
+    for line in contents.lines() {
+        let Ok(record) = serde_json::from_str::<Value>(line) else {
+            continue;
+        };
+
+        if record.get("type").and_then(Value::as_str) == Some("summary") {
+            if let Some(summary) = record.get("summary").and_then(Value::as_str) {
+                metadata.insert("summary".to_string(), summary.to_string());
+            }
+            continue;
+        }
+
+        if let Some(message) = parse_value(&record) {
+            if message.role == "user" && !metadata.contains_key("first_prompt") {
+                let preview: String = message.content.chars().take(200).collect();
+                metadata.insert("first_prompt".to_string(), preview);
+            }
+            message_count += 1;
+            started_at.get_or_insert(message.timestamp);
+            last_activity = last_activity.max(message.timestamp);
+        }
+    }
+
+    let started_at = started_at.unwrap_or(last_activity);
+    Ok(SessionInfo {
+        cli_session_id,
+        started_at,
+        last_activity,
+        message_count,
+        status: "completed".to_string(),
+        metadata,
+    })
+}
+
+/// Parse one JSONL line into a message; other record types are skipped
+pub fn parse_record(line: &str) -> Option<HistoryMessage> {
+    parse_value(&serde_json::from_str::<Value>(line).ok()?)
+}
+
+fn parse_value(record: &Value) -> Option<HistoryMessage> {
+    let record_type = record.get("type").and_then(Value::as_str)?;
+    if record_type != "user" && record_type != "assistant" {
+        return None;
+    }
+    // Sidechains are subagent conversations; meta records are CLI bookkeeping
+    if record.get("isSidechain").and_then(Value::as_bool) == Some(true)
+        || record.get("isMeta").and_then(Value::as_bool) == Some(true)
+    {
+        return None;
+    }
+
+    let message = record.get("message")?;
+    let role = message
+        .get("role")
+        .and_then(Value::as_str)
+        .unwrap_or(record_type)
+        .to_string();
+
+    let mut metadata = HashMap::new();
+    if let Some(model) = message.get("model").and_then(Value::as_str) {
+        metadata.insert("model".to_string(), model.to_string());
+    }
+
+    let content = match message.get("content")? {
+        Value::String(text) => text.clone(),
+        Value::Array(blocks) => {
+            let block_type = |b: &Value| b.get("type").and_then(Value::as_str).unwrap_or_default().to_string();
+
+            let tools: Vec<&str> = blocks
+                .iter()
+                .filter(|b| block_type(b) == "tool_use")
+                .filter_map(|b| b.get("name").and_then(Value::as_str))
+                .collect();
+            if !tools.is_empty() {
+                metadata.insert("tool_use".to_string(), tools.join(","));
+            }
+            if blocks.iter().any(|b| block_type(b) == "tool_result") {
+                metadata.insert("tool_result".to_string(), "true".to_string());
+            }
+            if blocks.iter().any(|b| block_type(b) == "thinking") {
+                metadata.insert("thinking".to_string(), "true".to_string());
+            }
+
+            // Plain text stays plain; anything with tool blocks keeps its JSON
+            // so tool use (e.g. AskUserQuestion) can still be parsed downstream
+            if blocks.iter().all(|b| matches!(block_type(b).as_str(), "text" | "thinking")) {
+                blocks
+                    .iter()
+                    .filter(|b| block_type(b) == "text")
+                    .filter_map(|b| b.get("text").and_then(Value::as_str))
+                    .collect::<Vec<_>>()
+                    .join("\n\n")
+            } else {
+                serde_json::to_string(blocks).ok()?
+            }
+        }
+        _ => return None,
+    };
+
+    if content.trim().is_empty() {
+        return None;
+    }
+
+    let timestamp = record
+        .get("timestamp")
+        .and_then(Value::as_str)
+        .and_then(|ts| chrono::DateTime::parse_from_rfc3339(ts).ok())
+        .map(|dt| dt.timestamp())
+        .unwrap_or(0);
+
+    let id = record
+        .get("uuid")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("msg-{}-{:x}", timestamp, content.len()));
+
+    Some(HistoryMessage {
+        id,
+        role,
+        content,
+        timestamp,
+        metadata,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_dir_name() {
+        assert_eq!(project_dir_name("/home/me/my_app"), "-home-me-my-app");
+        assert_eq!(project_dir_name("C:\\Users\\me\\app.v2"), "C--Users-me-app-v2");
+    }
+
+    #[test]
+    fn test_parse_text_record() {
+        let line = r#"{"type":"user","uuid":"u1","timestamp":"2025-01-01T00:00:10.000Z","message":{"role":"user","content":"Fix the bug"}}"#;
+        let message = parse_record(line).unwrap();
+        assert_eq!(message.id, "u1");
+        assert_eq!(message.role, "user");
+        assert_eq!(message.content, "Fix the bug");
+        assert_eq!(message.timestamp, 1735689610);
+    }
+
+    #[test]
+    fn test_parse_tool_use_record() {
+        let line = r#"{"type":"assistant","uuid":"a1","timestamp":"2025-01-01T00:00:11Z","message":{"role":"assistant","model":"claude-sonnet","content":[{"type":"text","text":"Reading"},{"type":"tool_use","id":"t1","name":"Read","input":{}}]}}"#;
+        let message = parse_record(line).unwrap();
+        assert_eq!(message.metadata.get("tool_use").map(String::as_str), Some("Read"));
+        assert_eq!(message.metadata.get("model").map(String::as_str), Some("claude-sonnet"));
+        assert!(message.content.starts_with('['));
+    }
+
+    #[test]
+    fn test_skips_non_message_records() {
+        assert!(parse_record(r#"{"type":"summary","summary":"Bug fix"}"#).is_none());
+        assert!(parse_record(r#"{"type":"user","isSidechain":true,"message":{"role":"user","content":"hi"}}"#).is_none());
+        assert!(parse_record("not json").is_none());
+    }
+}
@@ -47,6 +47,9 @@ pub struct PluginCapabilities {
     pub file_context: bool,
     #[serde(default)]
     pub thinking: bool,
+    /// Read history from Claude Code's JSONL session files instead of CLI commands
+    #[serde(default)]
+    pub claude_session_files: bool,
 }
 
 fn default_true() -> bool {
@@ -207,6 +210,7 @@ output_format = "text"
                 multi_turn: true,
                 file_context: false,
                 thinking: false,
+                claude_session_files: false,
             },
             commands: PluginCommands {
                 start_session: vec!["test".to_string(), "--project".to_string(), "{project_path}".to_string()],
@@ -0,0 +1,364 @@
+// Demo Agent Plugin
+// A built-in agent that needs no CLI. Its sessions are scripted transcripts in
+// Claude's JSONL format, so the sample project's chat can be explored offline
+
+use crate::plugin::{
+    AgentPlugin, HistoryMessage, OutputChunk, PaginatedHistory, PluginCapability, SessionHandle,
+    SessionInfo, SessionStatus, SessionUpdate, WatchHandle,
+};
+use crate::plugins::claude_sessions;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+pub const PLUGIN_NAME: &str = "demo";
+
+/// How often watched transcripts are checked for new messages
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Where demo transcripts are kept, next to the plugin directory
+pub fn sessions_dir() -> PathBuf {
+    crate::plugins::get_default_plugin_dir().with_file_name("demo-sessions")
+}
+
+fn transcript_path(cli_session_id: &str) -> PathBuf {
+    sessions_dir().join(format!("{}.jsonl", cli_session_id))
+}
+
+/// One transcript line, as Claude would write it
+fn record(project_path: &str, role: &str, content: Value, timestamp: i64) -> String {
+    let timestamp = chrono::DateTime::from_timestamp(timestamp, 0).unwrap_or_default().to_rfc3339();
+    json!({
+        "type": role,
+        "uuid": uuid::Uuid::new_v4().to_string(),
+        "timestamp": timestamp,
+        "cwd": project_path,
+        "message": { "role": role, "content": content },
+    })
+    .to_string()
+}
+
+fn append(cli_session_id: &str, lines: &[String]) -> Result<()> {
+    let path = transcript_path(cli_session_id);
+    std::fs::create_dir_all(sessions_dir()).context("Failed to create the demo sessions folder")?;
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    for line in lines {
+        writeln!(file, "{}", line)?;
+    }
+    Ok(())
+}
+
+fn text(text: &str) -> Value {
+    json!({ "type": "text", "text": text })
+}
+
+fn tool_use(id: &str, name: &str, input: Value) -> Value {
+    json!({ "type": "tool_use", "id": id, "name": name, "input": input })
+}
+
+fn tool_result(id: &str, output: &str) -> Value {
+    json!({ "type": "tool_result", "tool_use_id": id, "content": output })
+}
+
+/// Test run output shared by the opening script and later replies
+const TEST_OUTPUT: &str = "...\n----------------------------------------------------------------------\nRan 3 tests in 0.002s\n\nOK";
+
+/// Start a session for the sample project with the conversation that made its pending change
+pub fn seed_session(cli_session_id: &str, project_path: &str, started_at: i64) -> Result<()> {
+    let turns: Vec<(&str, Value)> = vec![
+        ("user", json!("Add a `done` command that marks a todo as finished, and cover it with a test.")),
+        (
+            "assistant",
+            json!([
+                text("I'll check how commands are handled first."),
+                tool_use("demo-read", "Read", json!({ "file_path": "todo.py" })),
+            ]),
+        ),
+        ("user", json!([tool_result("demo-read", "def main(argv):\n    todos = load()\n    if argv[:1] == [\"add\"]:\n        ...")])),
+        (
+            "assistant",
+            json!([
+                text("Commands are matched on the first argument. I'll add `done` next to `add`."),
+                tool_use(
+                    "demo-edit",
+                    "Edit",
+                    json!({ "file_path": "todo.py", "old_string": "def main(argv):", "new_string": "def done(todos, number):\n    todos[number - 1][\"done\"] = True\n\n\ndef main(argv):" }),
+                ),
+                tool_use("demo-test-edit", "Edit", json!({ "file_path": "test_todo.py", "new_string": "def test_done(self): ..." })),
+            ]),
+        ),
+        (
+            "user",
+            json!([
+                tool_result("demo-edit", "The file todo.py has been updated."),
+                tool_result("demo-test-edit", "The file test_todo.py has been updated."),
+            ]),
+        ),
+        ("assistant", json!([tool_use("demo-test", "Bash", json!({ "command": "python -m unittest" }))])),
+        ("user", json!([tool_result("demo-test", TEST_OUTPUT)])),
+        (
+            "assistant",
+            json!("`python todo.py done 2` now marks the second todo as finished, and `test_done` covers it. \
+                   All 3 tests pass. The change is waiting for your review in the Changes tab."),
+        ),
+    ];
+    let lines: Vec<String> = turns
+        .into_iter()
+        .enumerate()
+        .map(|(i, (role, content))| record(project_path, role, content, started_at + i as i64 * 20))
+        .collect();
+    append(cli_session_id, &lines)
+}
+
+/// The scripted answer to `message`: content blocks, each sent as its own turn
+fn scripted_reply(message: &str) -> Vec<(&'static str, Value)> {
+    let message = message.to_lowercase();
+    if message.contains("test") {
+        vec![
+            ("assistant", json!([text("Running the tests."), tool_use("demo-test", "Bash", json!({ "command": "python -m unittest" }))])),
+            ("user", json!([tool_result("demo-test", TEST_OUTPUT)])),
+            ("assistant", json!("All 3 tests pass.")),
+        ]
+    } else {
+        vec![(
+            "assistant",
+            json!("I'm AtelierCode's demo agent, so I follow a script rather than work on your request. \
+                   Ask me to run the tests to see tool calls, or pick an agent like Claude Code \
+                   when you create your own project."),
+        )]
+    }
+}
+
+/// Add `message` and the scripted reply to the session's transcript
+///
+/// Returns the reply's text, for callers that report agent output.
+pub fn reply(cli_session_id: &str, project_path: &str, message: &str) -> Result<String> {
+    let now = chrono::Utc::now().timestamp();
+    let turns = scripted_reply(message);
+    let reply_text = turns
+        .iter()
+        .filter(|(role, _)| *role == "assistant")
+        .flat_map(|(_, content)| match content {
+            Value::String(text) => vec![text.clone()],
+            blocks => blocks
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|b| b.get("text").and_then(Value::as_str).map(str::to_string))
+                .collect(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    let mut lines = vec![record(project_path, "user", json!(message), now)];
+    lines.extend(turns.into_iter().map(|(role, content)| record(project_path, role, content, now)));
+    append(cli_session_id, &lines)?;
+    Ok(reply_text)
+}
+
+/// The demo agent, registered with the plugin manager at startup
+#[derive(Default)]
+pub struct DemoPlugin {
+    /// Polling tasks of watched sessions, by watch id
+    watchers: Mutex<HashMap<String, tokio::task::JoinHandle<()>>>,
+}
+
+impl DemoPlugin {
+    fn handle(&self, cli_session_id: String) -> SessionHandle {
+        SessionHandle {
+            session_id: uuid::Uuid::new_v4().to_string(),
+            cli_session_id: Some(cli_session_id),
+            process_id: None,
+            plugin_name: PLUGIN_NAME.to_string(),
+            started_at: chrono::Utc::now().timestamp(),
+        }
+    }
+}
+
+#[async_trait]
+impl AgentPlugin for DemoPlugin {
+    fn name(&self) -> &str {
+        PLUGIN_NAME
+    }
+
+    fn display_name(&self) -> &str {
+        "Demo Agent"
+    }
+
+    fn version(&self) -> &str {
+        env!("CARGO_PKG_VERSION")
+    }
+
+    fn description(&self) -> &str {
+        "Scripted agent for the sample project; no CLI needed"
+    }
+
+    fn icon(&self) -> Option<&str> {
+        Some("🎬")
+    }
+
+    fn color(&self) -> Option<&str> {
+        Some("gray")
+    }
+
+    async fn check_installation(&self) -> Result<bool> {
+        Ok(true)
+    }
+
+    async fn get_cli_version(&self) -> Result<String> {
+        Ok("built-in".to_string())
+    }
+
+    async fn validate_settings(&self, _settings: &HashMap<String, String>) -> Result<()> {
+        Ok(())
+    }
+
+    async fn start_session(&self, _project_path: &str, _settings: &HashMap<String, String>) -> Result<SessionHandle> {
+        Ok(self.handle(uuid::Uuid::new_v4().to_string()))
+    }
+
+    async fn resume_session(
+        &self,
+        cli_session_id: &str,
+        _project_path: &str,
+        _settings: &HashMap<String, String>,
+    ) -> Result<SessionHandle> {
+        Ok(self.handle(cli_session_id.to_string()))
+    }
+
+    async fn stop_session(&self, _handle: &SessionHandle) -> Result<()> {
+        Ok(())
+    }
+
+    async fn get_session_status(&self, _handle: &SessionHandle) -> Result<SessionStatus> {
+        Ok(SessionStatus { is_running: true, is_waiting_for_input: true, error: None, metadata: HashMap::new() })
+    }
+
+    async fn send_message(&self, handle: &SessionHandle, message: &str) -> Result<()> {
+        let cli_session_id = handle.cli_session_id.as_deref().context("Demo session has no ID")?;
+        reply(cli_session_id, "", message).map(drop)
+    }
+
+    /// Replies go straight to the transcript, which watchers pick up
+    async fn read_output(&self, _handle: &SessionHandle) -> Result<Vec<OutputChunk>> {
+        Ok(Vec::new())
+    }
+
+    async fn list_sessions(&self, project_path: &str) -> Result<Vec<SessionInfo>> {
+        let Ok(entries) = std::fs::read_dir(sessions_dir()) else {
+            return Ok(Vec::new());
+        };
+        let mut sessions: Vec<SessionInfo> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| session_project(path).as_deref() == Some(project_path))
+            .filter_map(|path| claude_sessions::session_info(&path).ok())
+            .collect();
+        sessions.sort_by_key(|s| std::cmp::Reverse(s.last_activity));
+        Ok(sessions)
+    }
+
+    async fn get_conversation_history(&self, cli_session_id: &str) -> Result<Vec<HistoryMessage>> {
+        let path = transcript_path(cli_session_id);
+        if !path.is_file() {
+            return Ok(Vec::new());
+        }
+        claude_sessions::read_history(&path)
+    }
+
+    async fn get_conversation_history_paginated(
+        &self,
+        cli_session_id: &str,
+        offset: usize,
+        limit: usize,
+    ) -> Result<PaginatedHistory> {
+        let mut messages = self.get_conversation_history(cli_session_id).await?;
+        let total_count = messages.len();
+        messages.reverse();
+        let page: Vec<HistoryMessage> = messages.into_iter().skip(offset).take(limit).collect();
+        Ok(PaginatedHistory {
+            has_more: offset + page.len() < total_count,
+            messages: page,
+            total_count,
+            offset,
+        })
+    }
+
+    async fn start_watching_session(
+        &self,
+        _project_path: &str,
+        cli_session_id: &str,
+        callback: Box<dyn Fn(SessionUpdate) + Send + Sync>,
+    ) -> Result<WatchHandle> {
+        let path = transcript_path(cli_session_id);
+        let mut seen = if path.is_file() { claude_sessions::read_history(&path)?.len() } else { 0 };
+        let task = tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(POLL_INTERVAL).await;
+                let Ok(messages) = claude_sessions::read_history(&path) else {
+                    continue;
+                };
+                for message in messages.into_iter().skip(seen) {
+                    seen += 1;
+                    callback(SessionUpdate::NewMessage { message });
+                }
+            }
+        });
+
+        let handle = WatchHandle {
+            id: uuid::Uuid::new_v4().to_string(),
+            plugin_name: PLUGIN_NAME.to_string(),
+            cli_session_id: cli_session_id.to_string(),
+        };
+        self.watchers.lock().unwrap().insert(handle.id.clone(), task);
+        Ok(handle)
+    }
+
+    async fn stop_watching_session(&self, handle: WatchHandle) -> Result<()> {
+        if let Some(task) = self.watchers.lock().unwrap().remove(&handle.id) {
+            task.abort();
+        }
+        Ok(())
+    }
+
+    fn get_capabilities(&self) -> Vec<PluginCapability> {
+        vec![PluginCapability::SessionResume, PluginCapability::ToolUse, PluginCapability::MultiTurn]
+    }
+}
+
+/// The project a transcript belongs to, from its first record
+fn session_project(path: &Path) -> Option<String> {
+    let contents = std::fs::read_to_string(path).ok()?;
+    let first: Value = serde_json::from_str(contents.lines().next()?).ok()?;
+    first.get("cwd").and_then(Value::as_str).map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scripted_reply() {
+        let turns = scripted_reply("Please run the tests");
+        assert_eq!(turns.len(), 3);
+        assert!(turns[1].1.to_string().contains("tool_result"));
+        assert_eq!(scripted_reply("hello").len(), 1);
+    }
+
+    #[test]
+    fn test_records_parse_as_claude_history() {
+        let line = record("/demo", "assistant", json!([text("Hi"), tool_use("t1", "Bash", json!({ "command": "ls" }))]), 0);
+        let message = claude_sessions::parse_record(&line).unwrap();
+        assert_eq!(message.role, "assistant");
+        assert_eq!(message.metadata.get("tool_use").map(String::as_str), Some("Bash"));
+    }
+}
@@ -1,11 +1,13 @@
 // Generic CLI Plugin Implementation
 // Works with any CLI tool via configuration
 
+use crate::cli_command;
 use crate::output_parser::{AgentEvent, OutputParser};
 use crate::plugin::{
     AgentPlugin, HistoryMessage, OutputChunk, PaginatedHistory, PluginCapability, SessionHandle,
     SessionInfo, SessionStatus,
 };
+use crate::plugins::claude_sessions;
 use crate::plugins::config::PluginConfig;
 use anyhow::{Context, Result};
 use async_trait::async_trait;
@@ -14,7 +16,6 @@ use std::collections::HashMap;
 use std::process::Stdio;
 use std::sync::Arc;
 use tokio::io::{AsyncBufReadExt, BufReader};
-use tokio::process::Command;
 use tokio::sync::RwLock;
 
 /// Internal session state
@@ -54,6 +55,11 @@ impl GenericCliPlugin {
         Self::new(config)
     }
 
+    /// Whether history comes from Claude Code's session files
+    fn reads_claude_session_files(&self) -> bool {
+        self.config.capabilities.claude_session_files || self.config.plugin.cli_command == "claude"
+    }
+
     /// Extract session ID from output using configured pattern
     fn extract_session_id(&self, line: &str) -> Option<String> {
         if let Some(pattern) = &self.config.output_parsing.session_id_pattern {
@@ -110,6 +116,12 @@ impl GenericCliPlugin {
             AgentEvent::RawOutput { line, .. } => OutputChunk::Text {
                 content: line.clone(),
             },
+            AgentEvent::ProcessExited { exit_code, .. } => OutputChunk::StatusUpdate {
+                message: format!("Process exited (exit code: {:?})", exit_code),
+            },
+            AgentEvent::TurnCompleted { success, .. } => OutputChunk::StatusUpdate {
+                message: format!("Turn {}", if *success { "completed" } else { "failed" }),
+            },
         }
     }
 
@@ -124,25 +136,11 @@ impl GenericCliPlugin {
 
         log::info!("Executing: {} {:?} in {}", self.config.plugin.cli_command, args, project_path);
 
-        let mut cmd = Command::new(&self.config.plugin.cli_command);
-        cmd.args(&args)
-            .current_dir(project_path)
+        let mut cmd = cli_command::command(&self.config.plugin.cli_command, &args);
+        cmd.current_dir(project_path)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
 
-        #[cfg(target_os = "windows")]
-        {
-            // On Windows, wrap with cmd.exe for .cmd files
-            let original_args = args.clone();
-            cmd = Command::new("cmd");
-            cmd.arg("/C")
-                .arg(&self.config.plugin.cli_command)
-                .args(&original_args)
-                .current_dir(project_path)
-                .stdout(Stdio::piped())
-                .stderr(Stdio::piped());
-        }
-
         let child = cmd.spawn().context("Failed to spawn CLI command")?;
         Ok(child)
     }
@@ -188,8 +186,7 @@ impl AgentPlugin for GenericCliPlugin {
 
     async fn get_cli_version(&self) -> Result<String> {
         if let Some(version_cmd) = &self.config.commands.get_version {
-            let output = Command::new(&self.config.plugin.cli_command)
-                .args(version_cmd)
+            let output = cli_command::command(&self.config.plugin.cli_command, version_cmd)
                 .output()
                 .await
                 .context("Failed to execute version command")?;
@@ -202,8 +199,7 @@ impl AgentPlugin for GenericCliPlugin {
         } else {
             // Try common version flags
             for flag in &["--version", "-v", "-V", "version"] {
-                if let Ok(output) = Command::new(&self.config.plugin.cli_command)
-                    .arg(flag)
+                if let Ok(output) = cli_command::command(&self.config.plugin.cli_command, [flag])
                     .output()
                     .await
                 {
@@ -383,10 +379,9 @@ impl AgentPlugin for GenericCliPlugin {
         }
 
         // Choose the appropriate command
-        let command_template = if cli_session_id.is_some() && self.config.commands.resume_session.is_some() {
-            self.config.commands.resume_session.as_ref().unwrap()
-        } else {
-            &self.config.commands.send_message
+        let command_template = match &self.config.commands.resume_session {
+            Some(resume_session) if cli_session_id.is_some() => resume_session,
+            _ => &self.config.commands.send_message,
         };
 
         // Execute command
@@ -529,6 +524,13 @@ impl AgentPlugin for GenericCliPlugin {
     // ========================================================================
 
     async fn list_sessions(&self, project_path: &str) -> Result<Vec<SessionInfo>> {
+        if self.reads_claude_session_files() {
+            if let Some(sessions) = claude_sessions::list_sessions(project_path) {
+                log::info!("Found {} Claude sessions on disk for project: {}", sessions.len(), project_path);
+                return Ok(sessions);
+            }
+        }
+
         if let Some(list_cmd) = &self.config.commands.list_sessions {
             log::info!("Listing {} sessions for project: {}", self.name(), project_path);
 
@@ -537,8 +539,7 @@ impl AgentPlugin for GenericCliPlugin {
 
             let args = self.config.replace_variables(list_cmd, &vars);
 
-            let output = Command::new(&self.config.plugin.cli_command)
-                .args(&args)
+            let output = cli_command::command(&self.config.plugin.cli_command, &args)
                 .current_dir(project_path)
                 .output()
                 .await
@@ -588,6 +589,13 @@ impl AgentPlugin for GenericCliPlugin {
         &self,
         cli_session_id: &str,
     ) -> Result<Vec<HistoryMessage>> {
+        if self.reads_claude_session_files() {
+            if let Some(path) = claude_sessions::find_session_file(cli_session_id) {
+                log::info!("Reading conversation history from {:?}", path);
+                return claude_sessions::read_history(&path);
+            }
+        }
+
         if let Some(history_cmd) = &self.config.commands.get_history {
             log::info!("Getting conversation history for session: {}", cli_session_id);
 
@@ -596,8 +604,7 @@ impl AgentPlugin for GenericCliPlugin {
 
             let args = self.config.replace_variables(history_cmd, &vars);
 
-            let output = Command::new(&self.config.plugin.cli_command)
-                .args(&args)
+            let output = cli_command::command(&self.config.plugin.cli_command, &args)
                 .output()
                 .await
                 .context("Failed to get conversation history")?;
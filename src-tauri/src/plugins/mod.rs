@@ -1,8 +1,11 @@
 // Plugin implementations module
+pub mod claude_sessions;
 pub mod config;
+pub mod demo;
 pub mod generic_cli;
 pub mod loader;
 
 pub use config::PluginConfig;
+pub use demo::DemoPlugin;
 pub use generic_cli::GenericCliPlugin;
 pub use loader::{discover_plugins, get_default_plugin_dir, DynamicPlugin, PluginManifest};
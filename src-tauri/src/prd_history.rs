@@ -0,0 +1,177 @@
+// PRD History
+// Every version of a project's PRD with who wrote it, so edits can be compared
+// and an earlier version restored
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use similar::TextDiff;
+use sqlx::{FromRow, SqlitePool};
+
+/// Who wrote a revision
+pub const AUTHORS: &[&str] = &["user", "ai"];
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PrdRevision {
+    pub id: String,
+    pub project_id: String,
+    pub content: String,
+    /// "user" or "ai"
+    pub author: String,
+    pub created_at: i64,
+}
+
+/// Store `content` as the project's newest revision
+///
+/// Returns None without storing anything if it matches the newest one, so
+/// saving a project without touching its PRD adds no revision.
+pub async fn record_revision(
+    pool: &SqlitePool,
+    project_id: &str,
+    content: &str,
+    author: &str,
+) -> Result<Option<PrdRevision>> {
+    if !AUTHORS.contains(&author) {
+        anyhow::bail!("Unknown PRD author '{}'; use user or ai", author);
+    }
+    let latest: Option<String> = sqlx::query_scalar(
+        "SELECT content FROM prd_revisions WHERE project_id = ? ORDER BY created_at DESC, rowid DESC LIMIT 1",
+    )
+    .bind(project_id)
+    .fetch_optional(pool)
+    .await
+    .context("Failed to load the latest PRD revision")?;
+    if latest.as_deref() == Some(content) || (latest.is_none() && content.is_empty()) {
+        return Ok(None);
+    }
+
+    let revision = PrdRevision {
+        id: uuid::Uuid::new_v4().to_string(),
+        project_id: project_id.to_string(),
+        content: content.to_string(),
+        author: author.to_string(),
+        created_at: chrono::Utc::now().timestamp(),
+    };
+    sqlx::query("INSERT INTO prd_revisions (id, project_id, content, author, created_at) VALUES (?, ?, ?, ?, ?)")
+        .bind(&revision.id)
+        .bind(&revision.project_id)
+        .bind(&revision.content)
+        .bind(&revision.author)
+        .bind(revision.created_at)
+        .execute(pool)
+        .await
+        .context("Failed to save PRD revision")?;
+    Ok(Some(revision))
+}
+
+async fn load_revision(pool: &SqlitePool, revision_id: &str) -> Result<PrdRevision, String> {
+    sqlx::query_as::<_, PrdRevision>(
+        "SELECT id, project_id, content, author, created_at FROM prd_revisions WHERE id = ?",
+    )
+    .bind(revision_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| format!("Failed to load PRD revision: {}", e))?
+    .ok_or_else(|| format!("PRD revision not found: {}", revision_id))
+}
+
+/// Unified diff from `old` to `new`; empty when they match
+fn diff(old: &str, new: &str, old_label: &str, new_label: &str) -> String {
+    if old == new {
+        return String::new();
+    }
+    TextDiff::from_lines(old, new)
+        .unified_diff()
+        .context_radius(3)
+        .header(old_label, new_label)
+        .to_string()
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+use crate::db::Database;
+use crate::models::Project;
+use tauri::State;
+
+/// A project's PRD revisions, newest first
+#[tauri::command]
+pub async fn get_prd_history(db: State<'_, Database>, project_id: String) -> Result<Vec<PrdRevision>, String> {
+    sqlx::query_as::<_, PrdRevision>(
+        r#"
+        SELECT id, project_id, content, author, created_at
+        FROM prd_revisions
+        WHERE project_id = ?
+        ORDER BY created_at DESC, rowid DESC
+        "#,
+    )
+    .bind(&project_id)
+    .fetch_all(db.pool())
+    .await
+    .map_err(|e| format!("Failed to fetch PRD history: {}", e))
+}
+
+/// Make an earlier revision the project's PRD again, as a new revision by the user
+#[tauri::command]
+pub async fn restore_prd_revision(db: State<'_, Database>, revision_id: String) -> Result<Project, String> {
+    let revision = load_revision(db.pool(), &revision_id).await?;
+    log::info!("Restoring PRD revision {} of project {}", revision.id, revision.project_id);
+
+    sqlx::query("UPDATE projects SET prd_content = ?, last_activity = ? WHERE id = ?")
+        .bind(&revision.content)
+        .bind(chrono::Utc::now().timestamp())
+        .bind(&revision.project_id)
+        .execute(db.pool())
+        .await
+        .map_err(|e| format!("Failed to restore PRD: {}", e))?;
+    record_revision(db.pool(), &revision.project_id, &revision.content, "user")
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::commands::get_project(db, revision.project_id.clone())
+        .await?
+        .ok_or_else(|| format!("Project not found: {}", revision.project_id))
+}
+
+/// Unified diff from one PRD revision to another, or to the current PRD when
+/// `to_revision_id` is omitted
+#[tauri::command]
+pub async fn diff_prd_revisions(
+    db: State<'_, Database>,
+    from_revision_id: String,
+    to_revision_id: Option<String>,
+) -> Result<String, String> {
+    let from = load_revision(db.pool(), &from_revision_id).await?;
+    let (to_content, to_label) = match to_revision_id {
+        Some(id) => {
+            let to = load_revision(db.pool(), &id).await?;
+            if to.project_id != from.project_id {
+                return Err("PRD revisions belong to different projects".to_string());
+            }
+            (to.content, format!("revision {}", to.id))
+        }
+        None => {
+            let current: Option<String> = sqlx::query_scalar("SELECT prd_content FROM projects WHERE id = ?")
+                .bind(&from.project_id)
+                .fetch_optional(db.pool())
+                .await
+                .map_err(|e| format!("Failed to load project: {}", e))?
+                .ok_or_else(|| format!("Project not found: {}", from.project_id))?;
+            (current.unwrap_or_default(), "current".to_string())
+        }
+    };
+    Ok(diff(&from.content, &to_content, &format!("revision {}", from.id), &to_label))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff() {
+        assert_eq!(diff("same\n", "same\n", "a", "b"), "");
+        let unified = diff("# App\nOld goal\n", "# App\nNew goal\n", "a", "b");
+        assert!(unified.starts_with("--- a\n+++ b\n"));
+        assert!(unified.contains("-Old goal\n+New goal\n"));
+    }
+}
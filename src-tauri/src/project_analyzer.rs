@@ -4,6 +4,8 @@ use std::path::Path;
 use walkdir::WalkDir;
 use serde_json::Value;
 
+use crate::ci;
+use crate::container_env;
 use crate::types::ProjectAnalysisResult;
 
 /// Analyze a project directory to detect languages, frameworks, and other metadata
@@ -106,6 +108,8 @@ pub fn analyze_project(path: &str) -> Result<ProjectAnalysisResult, String> {
         detected_frameworks,
         file_count,
         has_git,
+        container: container_env::detect(project_path),
+        ci: ci::detect(project_path),
     })
 }
 
@@ -143,8 +147,8 @@ fn detect_languages(extensions: &HashMap<String, usize>) -> Vec<String> {
             "html" | "htm" => Some("HTML"),
             "css" | "scss" | "sass" | "less" => Some("CSS"),
             "sql" => Some("SQL"),
-            "md" | "markdown" => Some("Markdown"),
-            "json" | "yaml" | "yml" | "toml" | "xml" => None, // Config files, not languages
+            // Docs and config files, not languages
+            "md" | "markdown" | "json" | "yaml" | "yml" | "toml" | "xml" => None,
             _ => None,
         };
 
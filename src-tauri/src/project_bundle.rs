@@ -0,0 +1,376 @@
+// Project Bundles
+// Packs a project's AtelierCode state (the project row with its settings, tasks,
+// chats, reviews and tabs) into a zip archive that can be imported on another machine
+
+use anyhow::{anyhow, bail, Context, Result};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Column, Row, SqlitePool, TypeInfo, ValueRef};
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::path::Path;
+
+/// Bumped when the archive layout changes incompatibly
+/// (2: BLOB columns are written as `{"$blob": "<base64>"}`)
+pub const BUNDLE_FORMAT_VERSION: u32 = 2;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const TABLES_DIR: &str = "tables";
+/// Key of the object a BLOB value is stored as
+const BLOB_KEY: &str = "$blob";
+
+/// Tables holding a project's state and the condition selecting its rows,
+/// parents before children so rows can be inserted in this order.
+/// Runtime-only tables like `agent_processes` are left out.
+const PROJECT_TABLES: &[(&str, &str)] = &[
+    ("projects", "id = ?"),
+    ("prd_revisions", "project_id = ?"),
+    ("project_roots", "project_id = ?"),
+    ("project_agents", "project_id = ?"),
+    ("chat_sessions", "project_id = ?"),
+    ("agent_sessions", "project_id = ?"),
+    ("chat_tabs", "project_id = ?"),
+    ("chat_messages", "project_id = ?"),
+    ("tasks", "project_id = ?"),
+    ("task_labels", "project_id = ?"),
+    ("task_label_assignments", "task_id IN (SELECT id FROM tasks WHERE project_id = ?)"),
+    ("task_comments", "task_id IN (SELECT id FROM tasks WHERE project_id = ?)"),
+    ("time_entries", "project_id = ?"),
+    ("file_changes", "project_id = ?"),
+    ("review_comments", "file_change_id IN (SELECT id FROM file_changes WHERE project_id = ?)"),
+    ("file_bookmarks", "project_id = ?"),
+    ("activity_log", "project_id = ?"),
+    ("agent_comparisons", "project_id = ?"),
+    ("agent_comparison_runs", "comparison_id IN (SELECT id FROM agent_comparisons WHERE project_id = ?)"),
+    ("transcriptions", "project_id = ?"),
+    ("notes", "project_id = ?"),
+];
+
+/// Describes a bundle; stored as `manifest.json` at the archive root
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub format_version: u32,
+    pub app_version: String,
+    pub exported_at: i64,
+    pub project_id: String,
+    pub project_name: String,
+    /// Project location on the exporting machine
+    pub root_path: String,
+    /// Extra project roots on the exporting machine; unlike `root_path` they
+    /// can't be overridden on import and are kept as they are
+    #[serde(default)]
+    pub extra_roots: Vec<String>,
+    /// Rows exported per table
+    pub row_counts: BTreeMap<String, usize>,
+}
+
+/// Write the project's rows to a zip archive at `path`
+pub async fn export(pool: &SqlitePool, project_id: &str, path: &Path) -> Result<BundleManifest> {
+    let mut tables = Vec::with_capacity(PROJECT_TABLES.len());
+    for (table, condition) in PROJECT_TABLES {
+        let rows = sqlx::query(&format!("SELECT * FROM {} WHERE {}", table, condition))
+            .bind(project_id)
+            .fetch_all(pool)
+            .await
+            .with_context(|| format!("Failed to read {}", table))?;
+        let rows = rows.iter().map(row_to_json).collect::<Result<Vec<_>>>()?;
+        tables.push((*table, rows));
+    }
+
+    let project = tables[0].1.first().ok_or_else(|| anyhow!("Project not found: {}", project_id))?;
+    let text = |column: &str| project.get(column).and_then(Value::as_str).unwrap_or_default().to_string();
+    let (project_name, root_path) = (text("name"), text("root_path"));
+    let extra_roots = tables
+        .iter()
+        .find(|(table, _)| *table == "project_roots")
+        .map(|(_, rows)| rows.iter().filter_map(|row| row.get("path")?.as_str().map(str::to_string)).collect())
+        .unwrap_or_default();
+
+    let manifest = BundleManifest {
+        format_version: BUNDLE_FORMAT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        exported_at: chrono::Utc::now().timestamp(),
+        project_id: project_id.to_string(),
+        project_name,
+        root_path,
+        extra_roots,
+        row_counts: tables.iter().map(|(t, rows)| (t.to_string(), rows.len())).collect(),
+    };
+
+    let file = std::fs::File::create(path).with_context(|| format!("Failed to create {}", path.display()))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+    zip.start_file(MANIFEST_FILE, options)?;
+    zip.write_all(&serde_json::to_vec_pretty(&manifest)?)?;
+    for (table, rows) in &tables {
+        zip.start_file(format!("{}/{}.json", TABLES_DIR, table), options)?;
+        zip.write_all(&serde_json::to_vec(rows)?)?;
+    }
+    zip.finish().context("Failed to write bundle")?;
+
+    Ok(manifest)
+}
+
+/// Read a bundle's manifest without importing it
+pub fn read_manifest(path: &Path) -> Result<BundleManifest> {
+    let mut archive = open_archive(path)?;
+    read_json(&mut archive, MANIFEST_FILE)
+}
+
+/// Import a bundle as a new project
+///
+/// Row ids are kept, so a project can't be imported into a database that
+/// already has it. `root_path` overrides the exported project location,
+/// which usually differs between machines; the manifest's `extra_roots`
+/// are imported unchanged.
+pub async fn import(pool: &SqlitePool, path: &Path, root_path: Option<&str>) -> Result<BundleManifest> {
+    let mut archive = open_archive(path)?;
+    let manifest: BundleManifest = read_json(&mut archive, MANIFEST_FILE)?;
+    if manifest.format_version > BUNDLE_FORMAT_VERSION {
+        bail!(
+            "Bundle format {} is newer than this version of AtelierCode supports ({})",
+            manifest.format_version,
+            BUNDLE_FORMAT_VERSION
+        );
+    }
+
+    let exists: Option<String> = sqlx::query_scalar("SELECT name FROM projects WHERE id = ?")
+        .bind(&manifest.project_id)
+        .fetch_optional(pool)
+        .await?;
+    if let Some(name) = exists {
+        bail!("Project {:?} is already in AtelierCode; delete it before importing", name);
+    }
+
+    let mut tx = pool.begin().await?;
+    for (table, _) in PROJECT_TABLES {
+        let entry = format!("{}/{}.json", TABLES_DIR, table);
+        // Tables added after the bundle was made are simply absent
+        if archive.index_for_name(&entry).is_none() {
+            continue;
+        }
+        let mut rows: Vec<Map<String, Value>> = read_json(&mut archive, &entry)?;
+        if *table == "projects" {
+            if let (Some(project), Some(root_path)) = (rows.first_mut(), root_path) {
+                project.insert("root_path".to_string(), Value::String(root_path.to_string()));
+            }
+        }
+
+        // Only insert columns this schema has; the names in the file aren't trusted
+        let known: Vec<String> = sqlx::query_scalar(&format!("SELECT name FROM pragma_table_info('{}')", table))
+            .fetch_all(&mut *tx)
+            .await?;
+        for row in rows {
+            let columns: Vec<(&String, &Value)> = row.iter().filter(|(c, _)| known.contains(c)).collect();
+            if columns.is_empty() {
+                continue;
+            }
+            let sql = insert_sql(table, columns.iter().map(|(c, _)| c.as_str()));
+            let mut query = sqlx::query(&sql);
+            for (_, value) in columns {
+                query = match value {
+                    Value::Null => query.bind(None::<String>),
+                    Value::Bool(b) => query.bind(*b),
+                    Value::Number(n) => match n.as_i64() {
+                        Some(i) => query.bind(i),
+                        None => query.bind(n.as_f64()),
+                    },
+                    Value::String(s) => query.bind(s.clone()),
+                    Value::Object(object) if object.contains_key(BLOB_KEY) => query.bind(blob_from_json(object)?),
+                    other => query.bind(other.to_string()),
+                };
+            }
+            query
+                .execute(&mut *tx)
+                .await
+                .with_context(|| format!("Failed to import {}", table))?;
+        }
+    }
+    tx.commit().await?;
+
+    Ok(manifest)
+}
+
+/// Convert a row to a JSON object keyed by column name
+fn row_to_json(row: &SqliteRow) -> Result<Map<String, Value>> {
+    let mut object = Map::new();
+    for (i, column) in row.columns().iter().enumerate() {
+        let raw = row.try_get_raw(i)?;
+        let value = if raw.is_null() {
+            Value::Null
+        } else if raw.type_info().name() == "BLOB" {
+            let bytes: Vec<u8> = row.try_get(i)?;
+            Value::Object(Map::from_iter([(BLOB_KEY.to_string(), Value::from(BASE64.encode(bytes)))]))
+        } else if let Ok(v) = row.try_get::<i64, _>(i) {
+            Value::from(v)
+        } else if let Ok(v) = row.try_get::<f64, _>(i) {
+            Value::from(v)
+        } else {
+            Value::from(row.try_get::<String, _>(i)?)
+        };
+        object.insert(column.name().to_string(), value);
+    }
+    Ok(object)
+}
+
+/// Decode a BLOB value written by [`row_to_json`]
+fn blob_from_json(object: &Map<String, Value>) -> Result<Vec<u8>> {
+    let encoded = object.get(BLOB_KEY).and_then(Value::as_str).ok_or_else(|| anyhow!("Malformed BLOB value"))?;
+    BASE64.decode(encoded).context("Malformed BLOB value")
+}
+
+fn insert_sql<'a>(table: &str, columns: impl Iterator<Item = &'a str>) -> String {
+    let columns: Vec<String> = columns.map(|c| format!("\"{}\"", c)).collect();
+    format!(
+        "INSERT INTO {} ({}) VALUES ({})",
+        table,
+        columns.join(", "),
+        vec!["?"; columns.len()].join(", ")
+    )
+}
+
+fn open_archive(path: &Path) -> Result<zip::ZipArchive<std::fs::File>> {
+    let file = std::fs::File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    zip::ZipArchive::new(file).context("Not an AtelierCode project bundle")
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(archive: &mut zip::ZipArchive<std::fs::File>, name: &str) -> Result<T> {
+    let mut content = String::new();
+    archive
+        .by_name(name)
+        .with_context(|| format!("Bundle is missing {}", name))?
+        .read_to_string(&mut content)?;
+    serde_json::from_str(&content).with_context(|| format!("Failed to parse {}", name))
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+use crate::db::Database;
+use crate::jobs::JobManager;
+use tauri::State;
+
+/// Export a project's AtelierCode state to a bundle file
+#[tauri::command]
+pub async fn export_project_bundle(
+    db: State<'_, Database>,
+    jobs: State<'_, JobManager>,
+    project_id: String,
+    path: String,
+) -> Result<BundleManifest, String> {
+    log::info!("Exporting project {} bundle to {}", project_id, path);
+    let pool = db.pool().clone();
+    let title = format!("Export to {}", path);
+    jobs.run("project_export", Some(project_id.clone()), title, |_| async move {
+        export(&pool, &project_id, Path::new(&path))
+            .await
+            .map_err(|e| format!("Failed to export project bundle: {:#}", e))
+    })
+    .await
+}
+
+/// Read a bundle's manifest, e.g. to confirm an import
+#[tauri::command]
+pub async fn read_project_bundle(path: String) -> Result<BundleManifest, String> {
+    read_manifest(Path::new(&path)).map_err(|e| format!("Failed to read project bundle: {:#}", e))
+}
+
+/// Import a project bundle, optionally at a different project location
+///
+/// Returns the imported project's id.
+#[tauri::command]
+pub async fn import_project_bundle(
+    db: State<'_, Database>,
+    jobs: State<'_, JobManager>,
+    path: String,
+    root_path: Option<String>,
+) -> Result<String, String> {
+    log::info!("Importing project bundle {}", path);
+    let pool = db.pool().clone();
+    let title = format!("Import {}", path);
+    jobs.run("project_import", None, title, |_| async move {
+        let manifest = import(&pool, Path::new(&path), root_path.as_deref())
+            .await
+            .map_err(|e| format!("Failed to import project bundle: {:#}", e))?;
+        Ok(manifest.project_id)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_pool;
+
+    #[tokio::test]
+    async fn test_export_import_round_trip() {
+        let (pool, _source_db) = test_pool().await;
+
+        sqlx::query("INSERT INTO projects (id, name, root_path, created_at, last_activity, settings) VALUES ('p1', 'Demo', '/old/demo', 1, 2, '{\"theme\":\"dark\"}')")
+            .execute(&pool).await.unwrap();
+        sqlx::query("INSERT INTO tasks (id, project_id, title, priority, status, estimated_hours, created_at) VALUES ('t1', 'p1', 'Ship it', 'high', 'todo', 1.5, 3)")
+            .execute(&pool).await.unwrap();
+        sqlx::query("INSERT INTO task_labels (id, project_id, name, created_at) VALUES ('l1', 'p1', 'bug', 4)")
+            .execute(&pool).await.unwrap();
+        sqlx::query("INSERT INTO task_label_assignments (task_id, label_id) VALUES ('t1', 'l1')")
+            .execute(&pool).await.unwrap();
+        sqlx::query("INSERT INTO notes (id, project_id, title, content, created_at, updated_at) VALUES ('n1', 'p1', 'Plan', '# Plan', 6, 6)")
+            .execute(&pool).await.unwrap();
+        sqlx::query("INSERT INTO prd_revisions (id, project_id, content, author, created_at) VALUES ('v1', 'p1', '# PRD', 'user', 8)")
+            .execute(&pool).await.unwrap();
+        sqlx::query("INSERT INTO project_roots (id, project_id, path, created_at) VALUES ('r1', 'p1', '/old/shared', 7)")
+            .execute(&pool).await.unwrap();
+        let deleted: &[u8] = b"fn main() {}\n\0\xff";
+        sqlx::query("INSERT INTO file_changes (id, project_id, file_path, change_type, timestamp, deleted_content) VALUES ('c1', 'p1', 'main.rs', 'deleted', 5, ?)")
+            .bind(deleted)
+            .execute(&pool).await.unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let bundle = dir.path().join("demo.zip");
+        let manifest = export(&pool, "p1", &bundle).await.unwrap();
+        assert_eq!(manifest.project_name, "Demo");
+        assert_eq!(manifest.root_path, "/old/demo");
+        assert_eq!(manifest.extra_roots, vec!["/old/shared".to_string()]);
+        assert_eq!(manifest.row_counts["task_label_assignments"], 1);
+        assert!(import(&pool, &bundle, None).await.is_err());
+
+        let (target, _target_db) = test_pool().await;
+        import(&target, &bundle, Some("/new/demo")).await.unwrap();
+
+        let (root_path, settings): (String, String) =
+            sqlx::query_as("SELECT root_path, settings FROM projects WHERE id = 'p1'")
+                .fetch_one(&target).await.unwrap();
+        assert_eq!(root_path, "/new/demo");
+        assert_eq!(settings, "{\"theme\":\"dark\"}");
+        let (position, hours): (i64, f64) = sqlx::query_as("SELECT position, estimated_hours FROM tasks WHERE id = 't1'")
+            .fetch_one(&target).await.unwrap();
+        assert_eq!((position, hours), (0, 1.5));
+        let assigned: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM task_label_assignments WHERE label_id = 'l1'")
+            .fetch_one(&target).await.unwrap();
+        assert_eq!(assigned, 1);
+        let content: Vec<u8> = sqlx::query_scalar("SELECT deleted_content FROM file_changes WHERE id = 'c1'")
+            .fetch_one(&target).await.unwrap();
+        assert_eq!(content, deleted);
+        let note: String = sqlx::query_scalar("SELECT content FROM notes WHERE id = 'n1'")
+            .fetch_one(&target).await.unwrap();
+        assert_eq!(note, "# Plan");
+        let extra_root: String = sqlx::query_scalar("SELECT path FROM project_roots WHERE id = 'r1'")
+            .fetch_one(&target).await.unwrap();
+        assert_eq!(extra_root, "/old/shared");
+        let revisions: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM prd_revisions WHERE project_id = 'p1'")
+            .fetch_one(&target).await.unwrap();
+        assert_eq!(revisions, 1);
+    }
+
+    #[test]
+    fn test_insert_sql_quotes_columns() {
+        assert_eq!(
+            insert_sql("tasks", ["id", "title"].into_iter()),
+            "INSERT INTO tasks (\"id\", \"title\") VALUES (?, ?)"
+        );
+    }
+}
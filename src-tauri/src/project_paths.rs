@@ -0,0 +1,111 @@
+// Project Paths
+// Resolves paths handed to file commands and keeps them inside the project root
+
+use std::ffi::OsString;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+/// Resolve `candidate` to a canonical path inside `project_root`
+///
+/// `candidate` may be absolute or relative to the root, and need not exist
+/// yet (for writes). Symlinks are followed, so a link pointing outside the
+/// project is rejected just like `..` segments that climb out of it.
+pub fn resolve_in_project(project_root: impl AsRef<Path>, candidate: impl AsRef<Path>) -> Result<PathBuf, String> {
+    let candidate = candidate.as_ref();
+    let root = project_root
+        .as_ref()
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve project path: {}", e))?;
+
+    // Canonicalize the deepest ancestor that exists; what's left can't hold symlinks
+    let mut existing = project_root.as_ref().join(candidate);
+    let mut missing: Vec<OsString> = Vec::new();
+    let base = loop {
+        match existing.canonicalize() {
+            Ok(path) => break path,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                // A dangling symlink would be created through on write
+                if existing.symlink_metadata().is_ok() {
+                    return Err(format!("Access denied: {} is a broken symlink", candidate.display()));
+                }
+                match existing.components().next_back() {
+                    Some(Component::Normal(name)) => missing.push(name.to_os_string()),
+                    Some(Component::CurDir) => {}
+                    _ => return Err(format!("Invalid path: {}", candidate.display())),
+                }
+                existing.pop();
+            }
+            Err(e) => return Err(format!("Failed to resolve path {}: {}", candidate.display(), e)),
+        }
+    };
+
+    let resolved = missing.iter().rev().fold(base, |path, name| path.join(name));
+    if !resolved.starts_with(&root) {
+        return Err(format!("Access denied: {} is outside the project directory", candidate.display()));
+    }
+    Ok(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project() -> PathBuf {
+        let root = std::env::temp_dir().join(format!("ateliercode-paths-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("src/main.rs"), "fn main() {}").unwrap();
+        root
+    }
+
+    #[test]
+    fn test_resolves_paths_inside_project() {
+        let root = project();
+        let canonical = root.canonicalize().unwrap();
+
+        assert_eq!(resolve_in_project(&root, "src/main.rs").unwrap(), canonical.join("src/main.rs"));
+        assert_eq!(resolve_in_project(&root, root.join("src/main.rs")).unwrap(), canonical.join("src/main.rs"));
+        assert_eq!(resolve_in_project(&root, "src/../src/main.rs").unwrap(), canonical.join("src/main.rs"));
+        // Files that don't exist yet, for writes
+        assert_eq!(resolve_in_project(&root, "src/new/lib.rs").unwrap(), canonical.join("src/new/lib.rs"));
+        assert_eq!(resolve_in_project(&root, "").unwrap(), canonical);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_rejects_traversal() {
+        let root = project();
+        let outside = std::env::temp_dir();
+
+        assert!(resolve_in_project(&root, "../outside.txt").is_err());
+        assert!(resolve_in_project(&root, "src/../../outside.txt").is_err());
+        assert!(resolve_in_project(&root, "src/missing/../../../outside.txt").is_err());
+        assert!(resolve_in_project(&root, outside.join("outside.txt")).is_err());
+        // A sibling whose name merely starts with the root's
+        assert!(resolve_in_project(&root, format!("{}-evil/file", root.display())).is_err());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_rejects_symlink_escapes() {
+        let root = project();
+        let outside = std::env::temp_dir().join(format!("ateliercode-outside-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&outside).unwrap();
+        std::fs::write(outside.join("secret.txt"), "secret").unwrap();
+
+        std::os::unix::fs::symlink(&outside, root.join("escape")).unwrap();
+        std::os::unix::fs::symlink(outside.join("missing.txt"), root.join("dangling")).unwrap();
+        std::os::unix::fs::symlink(root.join("src"), root.join("inner")).unwrap();
+
+        assert!(resolve_in_project(&root, "escape/secret.txt").is_err());
+        assert!(resolve_in_project(&root, "escape/new.txt").is_err());
+        assert!(resolve_in_project(&root, "dangling").is_err());
+        // Links that stay inside the project are fine
+        assert!(resolve_in_project(&root, "inner/main.rs").is_ok());
+
+        std::fs::remove_dir_all(&root).unwrap();
+        std::fs::remove_dir_all(&outside).unwrap();
+    }
+}
@@ -0,0 +1,219 @@
+// Project Roots
+// Extra folders a project spans besides its root path, such as a shared
+// library kept next to the app. They are watched with the project and shown
+// as top-level folders of its file tree
+
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use crate::error::AppError;
+use crate::project_paths::resolve_in_project;
+
+/// A folder registered with a project in addition to its root path
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ProjectRoot {
+    pub id: String,
+    pub project_id: String,
+    /// Canonical absolute path
+    pub path: String,
+    /// Shown instead of the folder name when set
+    pub label: Option<String>,
+    pub created_at: i64,
+}
+
+/// A project's extra roots, oldest first
+pub async fn list(pool: &SqlitePool, project_id: &str) -> Result<Vec<ProjectRoot>, AppError> {
+    sqlx::query_as::<_, ProjectRoot>(
+        "SELECT id, project_id, path, label, created_at FROM project_roots WHERE project_id = ? ORDER BY created_at, path",
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await
+    .map_err(AppError::context("Failed to list project roots"))
+}
+
+/// The extra root an absolute path is in, if any
+pub fn containing<'a>(roots: &'a [ProjectRoot], path: &Path) -> Option<&'a ProjectRoot> {
+    if !path.is_absolute() {
+        return None;
+    }
+    roots.iter().find(|root| path.starts_with(&root.path))
+}
+
+/// Resolve a path handed to a file command inside the root it belongs to: the
+/// extra root holding it for absolute paths, or else the project's root path
+///
+/// Returns the root with the resolved path.
+pub async fn resolve(
+    pool: &SqlitePool,
+    project_id: &str,
+    root_path: &str,
+    candidate: &str,
+) -> Result<(String, PathBuf), AppError> {
+    let extra_roots = list(pool, project_id).await?;
+    let root = containing(&extra_roots, Path::new(candidate)).map_or(root_path, |extra| extra.path.as_str());
+    let resolved = resolve_in_project(root, candidate)?;
+    Ok((root.to_string(), resolved))
+}
+
+/// Which of the `extra` root folders a page of the top level shows, and the
+/// offset and limit left for the project root's own entries
+///
+/// The root folders come first, so they take the first `extra` positions.
+pub fn split_page(extra: usize, offset: usize, limit: usize) -> (Range<usize>, usize, usize) {
+    let shown = offset.min(extra)..offset.saturating_add(limit).min(extra);
+    let primary_limit = limit - shown.len();
+    (shown, offset.saturating_sub(extra), primary_limit)
+}
+
+/// Reject a root that is, holds or sits inside one the project already has;
+/// all paths canonical
+fn check_overlap(new: &Path, primary: &Path, extra: &[PathBuf]) -> Result<(), AppError> {
+    if new == primary || new.starts_with(primary) || primary.starts_with(new) {
+        return Err(AppError::invalid_input(format!(
+            "{} overlaps the project directory",
+            new.display()
+        )));
+    }
+    if let Some(other) = extra.iter().find(|other| new.starts_with(other) || other.starts_with(new)) {
+        return Err(AppError::conflict(format!("{} overlaps the root {}", new.display(), other.display())));
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+use tauri::State;
+
+use crate::commands::get_project;
+use crate::db::Database;
+use crate::file_watcher::FileWatcherManager;
+
+/// List the folders a project spans besides its root path
+#[tauri::command]
+pub async fn list_project_roots(db: State<'_, Database>, project_id: String) -> Result<Vec<ProjectRoot>, AppError> {
+    list(db.pool(), &project_id).await
+}
+
+/// Add a folder to a project, watching it right away if the project is watched
+#[tauri::command]
+pub async fn add_project_root(
+    db: State<'_, Database>,
+    watcher: State<'_, FileWatcherManager>,
+    project_id: String,
+    path: String,
+    label: Option<String>,
+) -> Result<ProjectRoot, AppError> {
+    let project = get_project(db.clone(), project_id.clone())
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Project not found: {}", project_id)))?;
+
+    let new = Path::new(path.trim());
+    if !new.is_dir() {
+        return Err(AppError::invalid_input(format!("Not a folder: {}", path)));
+    }
+    let new = new.canonicalize().map_err(AppError::context("Failed to resolve folder"))?;
+    let primary = Path::new(&project.root_path)
+        .canonicalize()
+        .map_err(AppError::context("Failed to resolve project path"))?;
+    let existing = list(db.pool(), &project_id).await?;
+    let extra: Vec<PathBuf> = existing.iter().map(|root| PathBuf::from(&root.path)).collect();
+    check_overlap(&new, &primary, &extra)?;
+
+    let root = ProjectRoot {
+        id: uuid::Uuid::new_v4().to_string(),
+        project_id,
+        path: new.to_string_lossy().to_string(),
+        label: label.map(|l| l.trim().to_string()).filter(|l| !l.is_empty()),
+        created_at: chrono::Utc::now().timestamp(),
+    };
+    sqlx::query("INSERT INTO project_roots (id, project_id, path, label, created_at) VALUES (?, ?, ?, ?, ?)")
+        .bind(&root.id)
+        .bind(&root.project_id)
+        .bind(&root.path)
+        .bind(&root.label)
+        .bind(root.created_at)
+        .execute(db.pool())
+        .await
+        .map_err(AppError::context("Failed to add project root"))?;
+
+    if let Err(e) = watcher.add_root(&root.project_id, &root.path) {
+        log::warn!("Failed to watch root {}: {}", root.path, e);
+    }
+    log::info!("Added root {} to project {}", root.path, root.project_id);
+    Ok(root)
+}
+
+/// Remove a folder from a project; nothing on disk is touched
+#[tauri::command]
+pub async fn remove_project_root(
+    db: State<'_, Database>,
+    watcher: State<'_, FileWatcherManager>,
+    root_id: String,
+) -> Result<(), AppError> {
+    let root = sqlx::query_as::<_, ProjectRoot>(
+        "SELECT id, project_id, path, label, created_at FROM project_roots WHERE id = ?",
+    )
+    .bind(&root_id)
+    .fetch_optional(db.pool())
+    .await
+    .map_err(AppError::context("Failed to fetch project root"))?
+    .ok_or_else(|| AppError::not_found(format!("Project root not found: {}", root_id)))?;
+
+    sqlx::query("DELETE FROM project_roots WHERE id = ?")
+        .bind(&root_id)
+        .execute(db.pool())
+        .await
+        .map_err(AppError::context("Failed to remove project root"))?;
+
+    if let Err(e) = watcher.remove_root(&root.project_id, &root.path) {
+        log::warn!("Failed to stop watching root {}: {}", root.path, e);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_page() {
+        // Two root folders ahead of the project's own entries
+        assert_eq!(split_page(2, 0, 10), (0..2, 0, 8));
+        assert_eq!(split_page(2, 1, 10), (1..2, 0, 9));
+        assert_eq!(split_page(2, 10, 10), (2..2, 8, 10));
+        assert_eq!(split_page(2, 0, 1), (0..1, 0, 0));
+        assert_eq!(split_page(0, 5, 10), (0..0, 5, 10));
+    }
+
+    #[test]
+    fn test_check_overlap() {
+        let primary = Path::new("/work/app");
+        let extra = vec![PathBuf::from("/work/shared")];
+        assert!(check_overlap(Path::new("/work/docs"), primary, &extra).is_ok());
+        assert!(check_overlap(Path::new("/work/app-tools"), primary, &extra).is_ok());
+        assert!(check_overlap(Path::new("/work/app"), primary, &extra).is_err());
+        assert!(check_overlap(Path::new("/work/app/src"), primary, &extra).is_err());
+        assert!(check_overlap(Path::new("/work"), primary, &extra).is_err());
+        assert!(check_overlap(Path::new("/work/shared/vendor"), primary, &extra).is_err());
+    }
+
+    #[test]
+    fn test_containing() {
+        let root = ProjectRoot {
+            id: "r1".to_string(),
+            project_id: "p1".to_string(),
+            path: "/work/shared".to_string(),
+            label: None,
+            created_at: 0,
+        };
+        let roots = [root];
+        assert_eq!(containing(&roots, Path::new("/work/shared/src/lib.rs")).map(|r| r.id.as_str()), Some("r1"));
+        assert!(containing(&roots, Path::new("/work/app/main.rs")).is_none());
+        assert!(containing(&roots, Path::new("shared/lib.rs")).is_none());
+    }
+}
@@ -0,0 +1,247 @@
+// Recent Projects
+// Records when projects are opened and left, and finds the CLI sessions worth
+// resuming, so the home screen can offer to continue where the user left off
+
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+
+use crate::error::AppError;
+use crate::models::Project;
+use crate::plugin::SessionInfo;
+
+const DEFAULT_RECENT_PROJECTS: usize = 10;
+const DEFAULT_RESUMABLE_SESSIONS: usize = 5;
+
+/// A project with when it was last opened
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct RecentProject {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub project: Project,
+    pub last_opened_at: i64,
+    /// When the latest visit ended; `None` while it's open or if the app
+    /// quit without closing it
+    pub last_closed_at: Option<i64>,
+    pub open_count: i64,
+}
+
+/// A CLI session that can be picked up again
+#[derive(Debug, Clone, Serialize)]
+pub struct ResumableSession {
+    pub plugin_name: String,
+    pub agent_name: String,
+    pub cli_session_id: String,
+    pub started_at: i64,
+    pub last_activity: i64,
+    pub message_count: usize,
+    /// The CLI's summary of the session, or else its first prompt
+    pub summary: Option<String>,
+    /// Chat tab already showing the session, if any
+    pub tab_id: Option<String>,
+}
+
+/// Projects by their latest visit, most recent first
+async fn recent_projects(pool: &SqlitePool, limit: usize) -> Result<Vec<RecentProject>, AppError> {
+    sqlx::query_as::<_, RecentProject>(
+        r#"
+        SELECT p.id, p.name, p.root_path, p.agent_type, p.status, p.prd_content, p.created_at,
+               p.last_activity, p.settings, p.icon, p.color,
+               latest.opened_at AS last_opened_at, latest.closed_at AS last_closed_at,
+               (SELECT COUNT(*) FROM project_visits WHERE project_id = p.id) AS open_count
+        FROM projects p
+        JOIN project_visits latest ON latest.id = (
+            SELECT id FROM project_visits WHERE project_id = p.id ORDER BY opened_at DESC, rowid DESC LIMIT 1
+        )
+        ORDER BY latest.opened_at DESC
+        LIMIT ?
+        "#,
+    )
+    .bind(limit as i64)
+    .fetch_all(pool)
+    .await
+    .map_err(AppError::context("Failed to list recent projects"))
+}
+
+/// The most recently active sessions across agents, with the tab of each
+fn rank_sessions(
+    sessions: Vec<(String, String, SessionInfo)>,
+    tabs: &HashMap<String, String>,
+    limit: usize,
+) -> Vec<ResumableSession> {
+    let mut ranked: Vec<ResumableSession> = sessions
+        .into_iter()
+        .filter(|(_, _, session)| session.message_count > 0)
+        .map(|(plugin_name, agent_name, session)| {
+            let summary = ["summary", "first_prompt", "last_message"]
+                .iter()
+                .find_map(|key| session.metadata.get(*key))
+                .map(|text| text.split_whitespace().collect::<Vec<_>>().join(" "));
+            ResumableSession {
+                plugin_name,
+                agent_name,
+                tab_id: tabs.get(&session.cli_session_id).cloned(),
+                cli_session_id: session.cli_session_id,
+                started_at: session.started_at,
+                last_activity: session.last_activity,
+                message_count: session.message_count,
+                summary,
+            }
+        })
+        .collect();
+    ranked.sort_by_key(|session| std::cmp::Reverse(session.last_activity));
+    ranked.truncate(limit);
+    ranked
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+use tauri::State;
+
+use crate::commands::get_project;
+use crate::db::Database;
+use crate::plugin::PluginManager;
+
+/// Record that a project was opened; returns the visit id to close it with
+#[tauri::command]
+pub async fn record_project_opened(db: State<'_, Database>, project_id: String) -> Result<String, AppError> {
+    let id = uuid::Uuid::new_v4().to_string();
+    sqlx::query("INSERT INTO project_visits (id, project_id, opened_at) VALUES (?, ?, ?)")
+        .bind(&id)
+        .bind(&project_id)
+        .bind(chrono::Utc::now().timestamp())
+        .execute(db.pool())
+        .await
+        .map_err(AppError::context("Failed to record project visit"))?;
+    Ok(id)
+}
+
+/// Record that the user left a project
+#[tauri::command]
+pub async fn record_project_closed(db: State<'_, Database>, visit_id: String) -> Result<(), AppError> {
+    sqlx::query("UPDATE project_visits SET closed_at = ? WHERE id = ? AND closed_at IS NULL")
+        .bind(chrono::Utc::now().timestamp())
+        .bind(&visit_id)
+        .execute(db.pool())
+        .await
+        .map_err(AppError::context("Failed to record project visit"))?;
+    Ok(())
+}
+
+/// Projects the user opened most recently
+#[tauri::command]
+pub async fn get_recent_projects(db: State<'_, Database>, limit: Option<usize>) -> Result<Vec<RecentProject>, AppError> {
+    recent_projects(db.pool(), limit.unwrap_or(DEFAULT_RECENT_PROJECTS)).await
+}
+
+/// A project's most recently active CLI sessions across every installed
+/// agent, for one-click resuming
+///
+/// Agents whose session store can't be read are left out.
+#[tauri::command]
+pub async fn get_resumable_sessions(
+    db: State<'_, Database>,
+    plugin_manager: State<'_, PluginManager>,
+    project_id: String,
+    limit: Option<usize>,
+) -> Result<Vec<ResumableSession>, AppError> {
+    let project = get_project(db.clone(), project_id.clone())
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Project not found: {}", project_id)))?;
+
+    let mut sessions = Vec::new();
+    for info in plugin_manager.list_plugins() {
+        let Some(plugin) = plugin_manager.get(&info.name) else { continue };
+        match plugin.list_sessions(&project.root_path).await {
+            Ok(found) => sessions.extend(found.into_iter().map(|s| (info.name.clone(), info.display_name.clone(), s))),
+            Err(e) => log::debug!("Skipping {} sessions for {}: {}", info.name, project.root_path, e),
+        }
+    }
+
+    let tabs: HashMap<String, String> = sqlx::query_as::<_, (String, String)>(
+        "SELECT cli_session_id, id FROM chat_tabs WHERE project_id = ? AND cli_session_id IS NOT NULL",
+    )
+    .bind(&project_id)
+    .fetch_all(db.pool())
+    .await
+    .map_err(AppError::context("Failed to load chat tabs"))?
+    .into_iter()
+    .collect();
+
+    Ok(rank_sessions(sessions, &tabs, limit.unwrap_or(DEFAULT_RESUMABLE_SESSIONS)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_pool;
+
+    fn session(id: &str, last_activity: i64, message_count: usize, metadata: &[(&str, &str)]) -> SessionInfo {
+        SessionInfo {
+            cli_session_id: id.to_string(),
+            started_at: 0,
+            last_activity,
+            message_count,
+            status: "completed".to_string(),
+            metadata: metadata.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+        }
+    }
+
+    #[test]
+    fn test_rank_sessions() {
+        let claude = |s: SessionInfo| ("claude-code".to_string(), "Claude".to_string(), s);
+        let codex = |s: SessionInfo| ("codex".to_string(), "Codex".to_string(), s);
+        let sessions = vec![
+            claude(session("old", 10, 4, &[("first_prompt", "Add  login")])),
+            claude(session("empty", 50, 0, &[])),
+            codex(session("new", 30, 2, &[("summary", "Fix build"), ("first_prompt", "x")])),
+            codex(session("mid", 20, 1, &[])),
+        ];
+        let tabs = HashMap::from([("old".to_string(), "tab-1".to_string())]);
+
+        let ranked = rank_sessions(sessions, &tabs, 2);
+        assert_eq!(ranked.iter().map(|s| s.cli_session_id.as_str()).collect::<Vec<_>>(), vec!["new", "mid"]);
+        assert_eq!(ranked[0].summary.as_deref(), Some("Fix build"));
+        assert_eq!(ranked[1].summary, None);
+
+        let old = session("old", 10, 4, &[("first_prompt", "Add  login")]);
+        let ranked = rank_sessions(vec![claude(old)], &tabs, 5);
+        assert_eq!(ranked[0].summary.as_deref(), Some("Add login"));
+        assert_eq!(ranked[0].tab_id.as_deref(), Some("tab-1"));
+    }
+
+    #[tokio::test]
+    async fn test_recent_projects_by_latest_visit() {
+        let (pool, _db) = test_pool().await;
+        for id in ["app", "lib", "never"] {
+            sqlx::query("INSERT INTO projects (id, name, root_path, created_at, last_activity) VALUES (?, ?, ?, 0, 0)")
+                .bind(id)
+                .bind(id)
+                .bind(format!("/tmp/{}", id))
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        let visits = [("v1", "app", 100, Some(150)), ("v2", "lib", 200, Some(250)), ("v3", "app", 300, None)];
+        for (id, project, opened_at, closed_at) in visits {
+            sqlx::query("INSERT INTO project_visits (id, project_id, opened_at, closed_at) VALUES (?, ?, ?, ?)")
+                .bind(id)
+                .bind(project)
+                .bind(opened_at)
+                .bind(closed_at)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        let recent = recent_projects(&pool, 10).await.unwrap();
+        let summary: Vec<_> = recent
+            .iter()
+            .map(|r| (r.project.id.as_str(), r.last_opened_at, r.last_closed_at, r.open_count))
+            .collect();
+        assert_eq!(summary, vec![("app", 300, None, 2), ("lib", 200, Some(250), 1)]);
+        assert_eq!(recent_projects(&pool, 1).await.unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,140 @@
+// Sandboxed Agent Execution
+// Applies a project's execution profile (environment, PATH, network and
+// resource limits) to the processes an agent session spawns
+
+use anyhow::{Context, Result};
+use std::ffi::OsString;
+use tokio::process::Command;
+
+use crate::models::ExecutionProfile;
+
+/// Variables kept by every profile; shells and CLIs misbehave without them
+const BASE_ENV: &[&str] = &[
+    "HOME", "USER", "LOGNAME", "SHELL", "TERM", "LANG", "LC_ALL", "TMPDIR",
+    // Windows
+    "USERPROFILE", "APPDATA", "LOCALAPPDATA", "TEMP", "TMP", "SYSTEMROOT", "SYSTEMDRIVE", "COMSPEC", "PATHEXT", "WINDIR",
+];
+
+/// The variables from `vars` a profile with `allowlist` passes through, besides PATH
+pub fn filtered_env(allowlist: &[String], vars: impl IntoIterator<Item = (String, String)>) -> Vec<(String, String)> {
+    // Windows variable names are case-insensitive
+    let allowed = |key: &str| {
+        BASE_ENV.iter().any(|name| name.eq_ignore_ascii_case(key))
+            || allowlist.iter().any(|name| if cfg!(windows) { name.eq_ignore_ascii_case(key) } else { name == key })
+    };
+    vars.into_iter()
+        .filter(|(key, _)| !key.eq_ignore_ascii_case("PATH") && allowed(key))
+        .collect()
+}
+
+/// The program and arguments to spawn so `program` runs under `profile`
+///
+/// The program is resolved against the app's PATH first, since the profile's
+/// PATH may not contain it. Fails when the profile needs network isolation the
+/// OS can't provide, rather than running the agent unrestricted.
+pub fn wrap(profile: Option<&ExecutionProfile>, program: String, args: Vec<String>) -> Result<(String, Vec<String>)> {
+    let Some(profile) = profile else {
+        return Ok((program, args));
+    };
+    let program = which::which(&program)
+        .map(|path| path.to_string_lossy().to_string())
+        .unwrap_or(program);
+    if !profile.network_disabled {
+        return Ok((program, args));
+    }
+
+    let (wrapper, wrapper_args): (&str, &[&str]) = if cfg!(target_os = "linux") {
+        // A fresh network namespace has only a downed loopback device
+        ("unshare", &["--user", "--map-root-user", "--net", "--"])
+    } else if cfg!(target_os = "macos") {
+        ("sandbox-exec", &["-p", "(version 1)(allow default)(deny network*)"])
+    } else {
+        anyhow::bail!("Execution profile {:?} disables network access, which isn't supported on this OS", profile.name);
+    };
+    let wrapper = which::which(wrapper)
+        .with_context(|| format!("Execution profile {:?} disables network access, but {} isn't available", profile.name, wrapper))?;
+
+    let mut wrapped: Vec<String> = wrapper_args.iter().map(|arg| arg.to_string()).collect();
+    wrapped.push(program);
+    wrapped.extend(args);
+    Ok((wrapper.to_string_lossy().to_string(), wrapped))
+}
+
+/// Restrict `cmd`'s environment, PATH and CPU time to `profile`
+///
+/// Call before adding session-specific variables, which filtering would drop.
+pub fn apply(cmd: &mut Command, profile: &ExecutionProfile) -> Result<()> {
+    if let Some(allowlist) = &profile.env_allowlist {
+        cmd.env_clear();
+        cmd.envs(filtered_env(allowlist, std::env::vars()));
+        if profile.path.is_none() {
+            if let Some(path) = std::env::var_os("PATH") {
+                cmd.env("PATH", path);
+            }
+        }
+    }
+
+    if let Some(dirs) = &profile.path {
+        let path: OsString = std::env::join_paths(dirs).context("Invalid PATH directory in execution profile")?;
+        cmd.env("PATH", path);
+    }
+
+    if let Some(seconds) = profile.max_cpu_seconds {
+        limit_cpu(cmd, seconds);
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn limit_cpu(cmd: &mut Command, seconds: u64) {
+    let limit = libc::rlimit {
+        rlim_cur: seconds as libc::rlim_t,
+        // One extra second between SIGXCPU and SIGKILL
+        rlim_max: seconds.saturating_add(1) as libc::rlim_t,
+    };
+    // SAFETY: setrlimit is async-signal-safe and touches no memory of the parent
+    unsafe {
+        cmd.pre_exec(move || {
+            if libc::setrlimit(libc::RLIMIT_CPU, &limit) == 0 {
+                Ok(())
+            } else {
+                Err(std::io::Error::last_os_error())
+            }
+        });
+    }
+}
+
+#[cfg(not(unix))]
+fn limit_cpu(_cmd: &mut Command, _seconds: u64) {
+    log::warn!("CPU limits aren't supported on this OS; only the runtime limit applies");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars() -> Vec<(String, String)> {
+        [("HOME", "/home/dev"), ("PATH", "/usr/bin"), ("AWS_SECRET_ACCESS_KEY", "x"), ("NODE_ENV", "test")]
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_filtered_env_keeps_basics_and_allowlist() {
+        let env = filtered_env(&["NODE_ENV".to_string()], vars());
+        let keys: Vec<&str> = env.iter().map(|(k, _)| k.as_str()).collect();
+        assert_eq!(keys, vec!["HOME", "NODE_ENV"]);
+        assert!(filtered_env(&[], vars()).iter().all(|(k, _)| k == "HOME"));
+    }
+
+    #[test]
+    fn test_wrap_without_restrictions() {
+        let (program, args) = wrap(None, "claude".to_string(), vec!["-p".to_string()]).unwrap();
+        assert_eq!((program.as_str(), args), ("claude", vec!["-p".to_string()]));
+
+        let profile = ExecutionProfile { name: "strict".to_string(), ..Default::default() };
+        let (program, args) = wrap(Some(&profile), "no-such-agent-cli".to_string(), Vec::new()).unwrap();
+        assert_eq!((program.as_str(), args.len()), ("no-such-agent-cli", 0));
+    }
+}
@@ -0,0 +1,452 @@
+// Project Scripts
+// Finds a project's runnable scripts (package.json, Cargo, Make, Go) and runs
+// them with streamed output, recording each run
+
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::sync::oneshot;
+
+use crate::models::ScriptRun;
+
+/// Event emitted with each line a running script prints
+pub const SCRIPT_OUTPUT_EVENT: &str = "script-output";
+/// Event emitted with the run once a script exits
+pub const SCRIPT_FINISHED_EVENT: &str = "script-finished";
+
+/// Lines of output kept with a run
+const OUTPUT_TAIL_LINES: usize = 500;
+/// Make targets listed at most, since generated Makefiles can have hundreds
+const MAX_MAKE_TARGETS: usize = 20;
+
+/// A command the project defines for building, testing or running it
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ScriptConfig {
+    /// How the script is shown and asked for, e.g. "npm run build"
+    pub name: String,
+    pub program: String,
+    pub args: Vec<String>,
+    /// File the script was found in
+    pub source: String,
+}
+
+impl ScriptConfig {
+    fn new(source: &str, program: &str, args: &[&str]) -> Self {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        Self {
+            name: std::iter::once(program.to_string()).chain(args.iter().cloned()).collect::<Vec<_>>().join(" "),
+            program: program.to_string(),
+            args,
+            source: source.to_string(),
+        }
+    }
+}
+
+/// The scripts a project at `root` defines
+pub fn detect(root: &Path) -> Vec<ScriptConfig> {
+    let mut scripts = Vec::new();
+
+    if let Some(package) = read_json(&root.join("package.json")) {
+        let manager = node_package_manager(root);
+        if let Some(defined) = package.get("scripts").and_then(|s| s.as_object()) {
+            for name in defined.keys() {
+                scripts.push(ScriptConfig::new("package.json", manager, &["run", name]));
+            }
+        }
+    }
+
+    if root.join("Cargo.toml").is_file() {
+        for command in ["build", "test", "run", "clippy"] {
+            scripts.push(ScriptConfig::new("Cargo.toml", "cargo", &[command]));
+        }
+    }
+
+    if root.join("go.mod").is_file() {
+        for command in ["build", "test", "vet"] {
+            scripts.push(ScriptConfig::new("go.mod", "go", &[command, "./..."]));
+        }
+    }
+
+    if let Ok(makefile) = std::fs::read_to_string(root.join("Makefile")) {
+        for target in make_targets(&makefile).into_iter().take(MAX_MAKE_TARGETS) {
+            scripts.push(ScriptConfig::new("Makefile", "make", &[target]));
+        }
+    }
+
+    scripts
+}
+
+fn read_json(path: &Path) -> Option<serde_json::Value> {
+    serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok()
+}
+
+/// The package manager whose lockfile the project has
+fn node_package_manager(root: &Path) -> &'static str {
+    [("pnpm-lock.yaml", "pnpm"), ("yarn.lock", "yarn"), ("bun.lockb", "bun"), ("bun.lock", "bun")]
+        .iter()
+        .find(|(lockfile, _)| root.join(lockfile).is_file())
+        .map_or("npm", |(_, manager)| *manager)
+}
+
+/// Targets defined in a Makefile, skipping special (`.PHONY`) and pattern (`%.o`) targets
+fn make_targets(makefile: &str) -> Vec<&str> {
+    let mut targets = Vec::new();
+    for line in makefile.lines() {
+        let Some((target, rest)) = line.split_once(':') else { continue };
+        // `X := y` and `X ::= y` are variable assignments
+        if rest.starts_with('=') || rest.starts_with(":=") || line.starts_with(['\t', ' ', '#']) {
+            continue;
+        }
+        let valid = !target.is_empty()
+            && !target.starts_with('.')
+            && target.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '/'));
+        if valid && !targets.contains(&target) {
+            targets.push(target);
+        }
+    }
+    targets
+}
+
+/// A line a running script printed
+#[derive(Debug, Clone, Serialize)]
+pub struct ScriptOutput {
+    pub run_id: String,
+    /// stdout or stderr
+    pub stream: &'static str,
+    pub line: String,
+}
+
+/// Something the frontend should hear about a run
+pub enum ScriptEvent<'a> {
+    Output(&'a ScriptOutput),
+    Finished(&'a ScriptRun),
+}
+
+/// Runs scripts and keeps their rows current
+#[derive(Clone)]
+pub struct ScriptRunner {
+    pool: SqlitePool,
+    on_event: Arc<dyn Fn(ScriptEvent) + Send + Sync>,
+    /// Cancellation switches of running scripts
+    running: Arc<Mutex<HashMap<String, oneshot::Sender<()>>>>,
+}
+
+impl ScriptRunner {
+    pub fn new(pool: SqlitePool, on_event: impl Fn(ScriptEvent) + Send + Sync + 'static) -> Self {
+        Self { pool, on_event: Arc::new(on_event), running: Arc::new(Mutex::new(HashMap::new())) }
+    }
+
+    /// Fail runs a previous app session left running
+    pub async fn recover(&self) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE script_runs SET status = 'failed', finished_at = ? WHERE status = 'running'")
+            .bind(chrono::Utc::now().timestamp())
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Start `script` in `root` and return its run right away
+    pub async fn start(&self, project_id: &str, root: &Path, script: &ScriptConfig) -> Result<ScriptRun, String> {
+        let program = which::which(&script.program).map_err(|_| format!("{} isn't installed or not on PATH", script.program))?;
+        let command = std::iter::once(program.to_string_lossy().to_string())
+            .chain(script.args.iter().cloned())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let mut cmd = tokio::process::Command::new(program);
+        cmd.args(&script.args)
+            .current_dir(root)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        // Its own process group, so cancelling also stops what the script started
+        #[cfg(unix)]
+        cmd.process_group(0);
+        let mut child = cmd.spawn().map_err(|e| format!("Failed to run {}: {}", script.name, e))?;
+
+        let run = ScriptRun {
+            id: uuid::Uuid::new_v4().to_string(),
+            project_id: project_id.to_string(),
+            name: script.name.clone(),
+            command,
+            status: "running".to_string(),
+            exit_code: None,
+            output: None,
+            started_at: chrono::Utc::now().timestamp(),
+            finished_at: None,
+            duration_ms: None,
+        };
+        sqlx::query(
+            "INSERT INTO script_runs (id, project_id, name, command, status, started_at) VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&run.id)
+        .bind(&run.project_id)
+        .bind(&run.name)
+        .bind(&run.command)
+        .bind(&run.status)
+        .bind(run.started_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| format!("Failed to record script run: {}", e))?;
+        log::info!("Running {} in {} as {}", script.name, root.display(), run.id);
+
+        let (cancel, cancelled) = oneshot::channel();
+        self.running.lock().unwrap().insert(run.id.clone(), cancel);
+
+        let tail = Arc::new(Mutex::new(VecDeque::new()));
+        let readers = [
+            child.stdout.take().map(|out| self.forward(&run.id, "stdout", out, tail.clone())),
+            child.stderr.take().map(|err| self.forward(&run.id, "stderr", err, tail.clone())),
+        ];
+        let runner = self.clone();
+        let mut finished = run.clone();
+        let started = Instant::now();
+        tokio::spawn(async move {
+            let pid = child.id();
+            let status = tokio::select! {
+                status = child.wait() => status.ok(),
+                _ = cancelled => {
+                    stop_process_group(pid);
+                    let _ = child.kill().await;
+                    None
+                }
+            };
+            // Let the readers drain what the script printed before it exited
+            for reader in readers.into_iter().flatten() {
+                let _ = reader.await;
+            }
+
+            finished.exit_code = status.and_then(|s| s.code()).map(i64::from);
+            finished.status = match status {
+                None => "cancelled",
+                Some(s) if s.success() => "succeeded",
+                Some(_) => "failed",
+            }
+            .to_string();
+            finished.output = Some(tail.lock().unwrap().iter().cloned().collect::<Vec<String>>().join("\n"));
+            finished.finished_at = Some(chrono::Utc::now().timestamp());
+            finished.duration_ms = Some(started.elapsed().as_millis() as i64);
+            runner.finish(&finished).await;
+        });
+
+        Ok(run)
+    }
+
+    /// Cancel a running script; returns false if it already finished
+    pub fn cancel(&self, run_id: &str) -> bool {
+        match self.running.lock().unwrap().remove(run_id) {
+            Some(cancel) => cancel.send(()).is_ok(),
+            None => false,
+        }
+    }
+
+    /// A project's recent runs, newest first
+    pub async fn list(&self, project_id: &str, limit: i64) -> Result<Vec<ScriptRun>, sqlx::Error> {
+        sqlx::query_as::<_, ScriptRun>(
+            "SELECT * FROM script_runs WHERE project_id = ? ORDER BY started_at DESC, rowid DESC LIMIT ?",
+        )
+        .bind(project_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Emit each line of `stream` and keep the last ones
+    fn forward(
+        &self,
+        run_id: &str,
+        stream: &'static str,
+        reader: impl AsyncRead + Unpin + Send + 'static,
+        tail: Arc<Mutex<VecDeque<String>>>,
+    ) -> tokio::task::JoinHandle<()> {
+        let on_event = self.on_event.clone();
+        let run_id = run_id.to_string();
+        tokio::spawn(async move {
+            let mut reader = BufReader::new(reader);
+            let mut buffer = Vec::new();
+            // Lines needn't be valid UTF-8
+            while reader.read_until(b'\n', &mut buffer).await.unwrap_or(0) > 0 {
+                let line = String::from_utf8_lossy(&buffer).trim_end_matches(['\n', '\r']).to_string();
+                buffer.clear();
+                {
+                    let mut tail = tail.lock().unwrap();
+                    if tail.len() == OUTPUT_TAIL_LINES {
+                        tail.pop_front();
+                    }
+                    tail.push_back(line.clone());
+                }
+                on_event(ScriptEvent::Output(&ScriptOutput { run_id: run_id.clone(), stream, line }));
+            }
+        })
+    }
+
+    async fn finish(&self, run: &ScriptRun) {
+        self.running.lock().unwrap().remove(&run.id);
+        log::info!("Script run {} {} in {:?}ms", run.id, run.status, run.duration_ms);
+        let result = sqlx::query(
+            "UPDATE script_runs SET status = ?, exit_code = ?, output = ?, finished_at = ?, duration_ms = ? WHERE id = ?",
+        )
+        .bind(&run.status)
+        .bind(run.exit_code)
+        .bind(&run.output)
+        .bind(run.finished_at)
+        .bind(run.duration_ms)
+        .bind(&run.id)
+        .execute(&self.pool)
+        .await;
+        if let Err(e) = result {
+            log::error!("Failed to record outcome of script run {}: {}", run.id, e);
+        }
+        (self.on_event)(ScriptEvent::Finished(run));
+    }
+}
+
+#[cfg(unix)]
+pub(crate) fn stop_process_group(pid: Option<u32>) {
+    if let Some(pid) = pid {
+        // SAFETY: signals the group the child leads; no memory is shared
+        unsafe {
+            libc::killpg(pid as libc::pid_t, libc::SIGTERM);
+        }
+    }
+}
+
+#[cfg(not(unix))]
+pub(crate) fn stop_process_group(_pid: Option<u32>) {}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+use crate::db::Database;
+use tauri::State;
+
+async fn project_root(db: &Database, project_id: &str) -> Result<String, String> {
+    sqlx::query_scalar("SELECT root_path FROM projects WHERE id = ?")
+        .bind(project_id)
+        .fetch_optional(db.pool())
+        .await
+        .map_err(|e| format!("Failed to load project: {}", e))?
+        .ok_or_else(|| format!("Project not found: {}", project_id))
+}
+
+/// The scripts the project defines
+#[tauri::command]
+pub async fn list_scripts(db: State<'_, Database>, project_id: String) -> Result<Vec<ScriptConfig>, String> {
+    let root = project_root(&db, &project_id).await?;
+    tokio::task::spawn_blocking(move || detect(Path::new(&root)))
+        .await
+        .map_err(|e| format!("Failed to detect scripts: {}", e))
+}
+
+/// Start one of the project's scripts by name
+///
+/// Output arrives as `script-output` events and the finished run as
+/// `script-finished`.
+#[tauri::command]
+pub async fn run_script(
+    db: State<'_, Database>,
+    runner: State<'_, ScriptRunner>,
+    project_id: String,
+    name: String,
+) -> Result<ScriptRun, String> {
+    let root = project_root(&db, &project_id).await?;
+    let scripts = {
+        let root = root.clone();
+        tokio::task::spawn_blocking(move || detect(Path::new(&root)))
+            .await
+            .map_err(|e| format!("Failed to detect scripts: {}", e))?
+    };
+    let script = scripts
+        .iter()
+        .find(|script| script.name == name)
+        .ok_or_else(|| format!("Script not found: {}", name))?;
+    runner.start(&project_id, Path::new(&root), script).await
+}
+
+/// Stop a running script; returns false if it had already finished
+#[tauri::command]
+pub async fn cancel_script(runner: State<'_, ScriptRunner>, run_id: String) -> Result<bool, String> {
+    log::info!("Cancelling script run {}", run_id);
+    Ok(runner.cancel(&run_id))
+}
+
+/// The project's recent script runs, newest first
+#[tauri::command]
+pub async fn get_script_runs(
+    runner: State<'_, ScriptRunner>,
+    project_id: String,
+    limit: Option<i64>,
+) -> Result<Vec<ScriptRun>, String> {
+    runner
+        .list(&project_id, limit.unwrap_or(20))
+        .await
+        .map_err(|e| format!("Failed to load script runs: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_scripts() {
+        let root = std::env::temp_dir().join(format!("ateliercode-scripts-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join("package.json"), r#"{"scripts": {"build": "vite build", "test": "vitest"}}"#).unwrap();
+        std::fs::write(root.join("pnpm-lock.yaml"), "").unwrap();
+        std::fs::write(root.join("Cargo.toml"), "[package]").unwrap();
+        std::fs::write(
+            root.join("Makefile"),
+            ".PHONY: all\nCC := gcc\nall: build\n\tcc -o app\nbuild:\n%.o: %.c\ndeploy-prod: all\n",
+        )
+        .unwrap();
+
+        let names: Vec<String> = detect(&root).into_iter().map(|script| script.name).collect();
+        assert_eq!(
+            names,
+            vec![
+                "pnpm run build", "pnpm run test",
+                "cargo build", "cargo test", "cargo run", "cargo clippy",
+                "make all", "make build", "make deploy-prod",
+            ]
+        );
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_records_output_and_exit_code() {
+        let root = std::env::temp_dir().join(format!("ateliercode-scripts-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&root).unwrap();
+        let (pool, _db) = crate::db::test_pool().await;
+        sqlx::query("INSERT INTO projects (id, name, root_path, created_at, last_activity) VALUES ('p1', 'Demo', '/demo', 0, 0)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let (finished_tx, mut finished_rx) = tokio::sync::mpsc::unbounded_channel();
+        let runner = ScriptRunner::new(pool, move |event| {
+            if let ScriptEvent::Finished(run) = event {
+                let _ = finished_tx.send(run.clone());
+            }
+        });
+        let script = ScriptConfig::new("test", "sh", &["-c", "echo out; echo err >&2; exit 3"]);
+        let run = runner.start("p1", &root, &script).await.unwrap();
+        assert_eq!(run.status, "running");
+
+        let finished = finished_rx.recv().await.unwrap();
+        assert_eq!((finished.status.as_str(), finished.exit_code), ("failed", Some(3)));
+        let output = finished.output.unwrap();
+        assert!(output.contains("out") && output.contains("err"));
+        assert_eq!(runner.list("p1", 10).await.unwrap()[0].status, "failed");
+        assert!(!runner.cancel(&run.id));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,176 @@
+// Session Events
+// Forwards session watcher updates to the frontend as `session-update://{tab_id}`
+// events. Events are numbered and the last few kept per tab, so a webview that
+// reloads can ask for what it missed instead of losing prompts and status changes
+
+use serde::Serialize;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, EventTarget, State};
+
+use crate::plugin::SessionUpdate;
+
+/// Every tab's updates, for listeners outside the webviews such as the local API
+pub const ALL_SESSION_EVENT: &str = "session-update://all";
+
+/// Events kept per tab for replay
+const BUFFER_SIZE: usize = 200;
+
+/// Name of the event a tab's session updates are emitted on
+pub fn event_name(tab_id: &str) -> String {
+    format!("session-update://{}", tab_id)
+}
+
+/// Payload of every `session-update://{tab_id}` event
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionEvent {
+    pub tab_id: String,
+    pub cli_session_id: String,
+    /// Counts up from 1 per tab, so the frontend can skip events it already has
+    pub seq: u64,
+    /// Tagged by `type`: NewMessage, UserPromptRequired, StatusChanged,
+    /// SessionEnded or Error
+    pub update: SessionUpdate,
+}
+
+#[derive(Default)]
+struct TabChannel {
+    /// Label of the window the tab lives in
+    window: String,
+    last_seq: u64,
+    buffer: VecDeque<SessionEvent>,
+}
+
+#[derive(Default)]
+struct HubState {
+    tabs: HashMap<String, TabChannel>,
+    /// Windows whose page is loading; their events are buffered, not emitted
+    loading: HashSet<String>,
+}
+
+/// The one place session updates are emitted from
+#[derive(Default)]
+pub struct SessionEventHub(Mutex<HubState>);
+
+impl SessionEventHub {
+    /// Number and keep an update, then emit it unless the tab's window is reloading
+    pub fn publish(&self, app: &AppHandle, window: &str, tab_id: &str, cli_session_id: &str, update: SessionUpdate) {
+        let (event, live) = self.record(window, tab_id, cli_session_id, update);
+        if let Err(e) = app.emit_to(EventTarget::App, ALL_SESSION_EVENT, &event) {
+            log::warn!("Failed to emit {}: {}", ALL_SESSION_EVENT, e);
+        }
+        if !live {
+            return;
+        }
+        if let Err(e) = app.emit_to(window, &event_name(tab_id), &event) {
+            log::warn!("Failed to emit session update for tab {}: {}", tab_id, e);
+        }
+    }
+
+    /// Buffer an event; also returns whether the window is listening right now
+    fn record(&self, window: &str, tab_id: &str, cli_session_id: &str, update: SessionUpdate) -> (SessionEvent, bool) {
+        let mut state = self.0.lock().unwrap();
+        let channel = state.tabs.entry(tab_id.to_string()).or_default();
+        channel.window = window.to_string();
+        channel.last_seq += 1;
+        let event = SessionEvent {
+            tab_id: tab_id.to_string(),
+            cli_session_id: cli_session_id.to_string(),
+            seq: channel.last_seq,
+            update,
+        };
+        channel.buffer.push_back(event.clone());
+        if channel.buffer.len() > BUFFER_SIZE {
+            channel.buffer.pop_front();
+        }
+        let live = !state.loading.contains(window);
+        (event, live)
+    }
+
+    /// A window started (re)loading its page
+    pub fn page_loading(&self, window: &str) {
+        self.0.lock().unwrap().loading.insert(window.to_string());
+    }
+
+    /// A tab is listening again: emit live from now on and return the buffered
+    /// events after `after_seq`, oldest first
+    pub fn resume(&self, window: &str, tab_id: &str, after_seq: u64) -> Vec<SessionEvent> {
+        let mut state = self.0.lock().unwrap();
+        state.loading.remove(window);
+        state
+            .tabs
+            .get(tab_id)
+            .map(|channel| channel.buffer.iter().filter(|e| e.seq > after_seq).cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Label of the window a tab's updates last went to
+    pub fn window_of(&self, tab_id: &str) -> Option<String> {
+        self.0.lock().unwrap().tabs.get(tab_id).map(|channel| channel.window.clone())
+    }
+
+    /// Drop a tab's buffer once nothing watches it
+    pub fn forget_tab(&self, tab_id: &str) {
+        self.0.lock().unwrap().tabs.remove(tab_id);
+    }
+
+    pub fn window_destroyed(&self, window: &str) {
+        let mut state = self.0.lock().unwrap();
+        state.loading.remove(window);
+        state.tabs.retain(|_, channel| channel.window != window);
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Resume a tab's session updates after (re)subscribing
+///
+/// Returns the events after `after_seq` that are still buffered; the frontend
+/// keeps the last seq it handled across reloads.
+#[tauri::command]
+pub fn resume_session_updates(
+    window: tauri::Window,
+    hub: State<'_, SessionEventHub>,
+    tab_id: String,
+    after_seq: Option<u64>,
+) -> Vec<SessionEvent> {
+    hub.resume(window.label(), &tab_id, after_seq.unwrap_or(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(status: &str) -> SessionUpdate {
+        SessionUpdate::StatusChanged { status: status.to_string() }
+    }
+
+    #[test]
+    fn test_buffers_while_loading_and_replays_after_seq() {
+        let hub = SessionEventHub::default();
+        let (event, live) = hub.record("main", "tab", "cli", status("working"));
+        assert_eq!((event.seq, live), (1, true));
+
+        hub.page_loading("main");
+        assert!(!hub.record("main", "tab", "cli", status("idle")).1);
+        assert!(!hub.record("main", "tab", "cli", SessionUpdate::SessionEnded).1);
+        assert!(hub.record("other", "tab2", "cli2", status("idle")).1);
+
+        let missed = hub.resume("main", "tab", 1);
+        assert_eq!(missed.iter().map(|e| e.seq).collect::<Vec<_>>(), vec![2, 3]);
+        assert!(hub.record("main", "tab", "cli", status("working")).1);
+    }
+
+    #[test]
+    fn test_buffer_is_bounded() {
+        let hub = SessionEventHub::default();
+        for _ in 0..BUFFER_SIZE + 5 {
+            hub.record("main", "tab", "cli", status("working"));
+        }
+        let events = hub.resume("main", "tab", 0);
+        assert_eq!(events.len(), BUFFER_SIZE);
+        assert_eq!(events[0].seq, 6);
+    }
+}
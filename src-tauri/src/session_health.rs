@@ -0,0 +1,248 @@
+// Session Health
+// Watchdog that keeps the status of agent_sessions rows in line with what is
+// actually running, so a crash doesn't leave sessions "running" forever
+
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::agent_manager::{AgentManager, AgentStatus};
+use crate::db::Database;
+use crate::error::AppError;
+use crate::file_watcher::FileWatcherManager;
+use crate::plugin::SessionUpdate;
+use crate::session_events::SessionEventHub;
+use crate::windows::MAIN_WINDOW;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+/// Rows are written around the time a session starts, so younger ones may not
+/// be registered with the app yet
+const STARTUP_GRACE_SECS: i64 = 30;
+
+/// A session whose recorded status was corrected
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StatusFix {
+    pub session_id: String,
+    pub project_id: String,
+    pub previous: String,
+    pub status: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ReconcileReport {
+    /// Sessions marked running or starting that were checked
+    pub checked: usize,
+    pub fixed: Vec<StatusFix>,
+}
+
+/// What a session recorded as running should be marked instead, if anything
+///
+/// Sessions without a pid are run by this app and go by what it holds for
+/// them; a pid marks one run by the CLI binary, alive while that process is.
+fn reconciled_status(
+    pid: Option<i64>,
+    in_app: Option<&AgentStatus>,
+    process_alive: impl Fn(u32) -> bool,
+) -> Option<&'static str> {
+    if let Some(pid) = pid.and_then(|pid| u32::try_from(pid).ok()) {
+        if pid != std::process::id() {
+            return (!process_alive(pid)).then_some("stopped");
+        }
+    }
+    match in_app {
+        None | Some(AgentStatus::Stopped) => Some("stopped"),
+        Some(AgentStatus::Error) => Some("error"),
+        Some(_) => None,
+    }
+}
+
+#[cfg(unix)]
+fn process_alive(pid: u32) -> bool {
+    // SAFETY: signal 0 only checks that the process exists; nothing is sent
+    let result = unsafe { libc::kill(pid as libc::pid_t, 0) };
+    result == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+/// No cheap check here, so CLI sessions are left for the CLI to close
+#[cfg(not(unix))]
+fn process_alive(_pid: u32) -> bool {
+    true
+}
+
+/// Status of every session this app runs: agents and project file watchers
+pub(crate) async fn app_sessions(app: &AppHandle) -> HashMap<String, AgentStatus> {
+    let mut sessions: HashMap<String, AgentStatus> = app
+        .state::<AgentManager>()
+        .list_sessions()
+        .await
+        .into_iter()
+        .map(|s| (s.session_id, s.status))
+        .collect();
+    for id in app.state::<FileWatcherManager>().session_ids() {
+        sessions.insert(id, AgentStatus::Running);
+    }
+    sessions
+}
+
+/// Sessions recorded as running or starting whose status is wrong, and how
+/// many were checked
+pub(crate) async fn find_mismatches(
+    pool: &SqlitePool,
+    app_sessions: &HashMap<String, AgentStatus>,
+) -> Result<(usize, Vec<StatusFix>), AppError> {
+    let running: Vec<(String, String, String, Option<i64>)> = sqlx::query_as(
+        "SELECT id, project_id, status, pid FROM agent_sessions
+         WHERE status IN ('running', 'starting') AND started_at < ?",
+    )
+    .bind(chrono::Utc::now().timestamp() - STARTUP_GRACE_SECS)
+    .fetch_all(pool)
+    .await
+    .map_err(AppError::context("Failed to list running sessions"))?;
+
+    let checked = running.len();
+    let fixes = running
+        .into_iter()
+        .filter_map(|(session_id, project_id, previous, pid)| {
+            let status = reconciled_status(pid, app_sessions.get(&session_id), process_alive)?;
+            Some(StatusFix { session_id, project_id, previous, status: status.to_string() })
+        })
+        .collect();
+    Ok((checked, fixes))
+}
+
+/// Write the corrected statuses, skipping rows that changed in the meantime
+async fn mark(pool: &SqlitePool, fixes: &[StatusFix]) -> Result<(), AppError> {
+    let now = chrono::Utc::now().timestamp();
+    for fix in fixes {
+        sqlx::query(
+            "UPDATE agent_sessions SET status = ?, ended_at = COALESCE(ended_at, ?) WHERE id = ? AND status = ?",
+        )
+        .bind(&fix.status)
+        .bind(now)
+        .bind(&fix.session_id)
+        .bind(&fix.previous)
+        .execute(pool)
+        .await
+        .map_err(AppError::context("Failed to update session status"))?;
+    }
+    Ok(())
+}
+
+/// Send StatusChanged to the chat tabs showing the fixed sessions
+async fn notify(app: &AppHandle, pool: &SqlitePool, fixes: &[StatusFix]) {
+    let hub = app.state::<SessionEventHub>();
+    for fix in fixes {
+        let tabs: Vec<(String, Option<String>)> =
+            match sqlx::query_as("SELECT id, cli_session_id FROM chat_tabs WHERE session_id = ?")
+                .bind(&fix.session_id)
+                .fetch_all(pool)
+                .await
+            {
+                Ok(tabs) => tabs,
+                Err(e) => {
+                    log::warn!("Failed to find tabs of session {}: {}", fix.session_id, e);
+                    continue;
+                }
+            };
+        for (tab_id, cli_session_id) in tabs {
+            let window = hub.window_of(&tab_id).unwrap_or_else(|| MAIN_WINDOW.to_string());
+            let update = SessionUpdate::StatusChanged { status: fix.status.clone() };
+            hub.publish(app, &window, &tab_id, cli_session_id.as_deref().unwrap_or_default(), update);
+        }
+    }
+}
+
+/// Record the fixes and tell the UI about them
+pub(crate) async fn apply(app: &AppHandle, pool: &SqlitePool, fixes: &[StatusFix]) -> Result<(), AppError> {
+    if fixes.is_empty() {
+        return Ok(());
+    }
+    mark(pool, fixes).await?;
+    for fix in fixes {
+        log::info!("Session {} was {}, marked {}", fix.session_id, fix.previous, fix.status);
+    }
+    notify(app, pool, fixes).await;
+    Ok(())
+}
+
+/// Cross-check every session recorded as running against what's alive and fix
+/// the ones that aren't
+pub async fn reconcile(app: &AppHandle) -> Result<ReconcileReport, AppError> {
+    let pool = app.state::<Database>().pool().clone();
+    let (checked, fixed) = find_mismatches(&pool, &app_sessions(app).await).await?;
+    apply(app, &pool, &fixed).await?;
+    Ok(ReconcileReport { checked, fixed })
+}
+
+/// Reconcile session statuses every minute
+pub fn spawn_watchdog(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Err(e) = reconcile(&app).await {
+                log::warn!("Session health check failed: {}", e);
+            }
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Fix sessions recorded as running whose process is gone, now rather than
+/// at the watchdog's next check
+#[tauri::command]
+pub async fn reconcile_sessions(app: AppHandle) -> Result<ReconcileReport, AppError> {
+    reconcile(&app).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_pool;
+
+    #[test]
+    fn test_reconciled_status() {
+        assert_eq!(reconciled_status(None, Some(&AgentStatus::Running), |_| false), None);
+        assert_eq!(reconciled_status(None, None, |_| true), Some("stopped"));
+        assert_eq!(reconciled_status(None, Some(&AgentStatus::Error), |_| true), Some("error"));
+        assert_eq!(reconciled_status(Some(4242), None, |pid| pid == 4242), None);
+        assert_eq!(reconciled_status(Some(4242), None, |_| false), Some("stopped"));
+        assert_eq!(reconciled_status(Some(std::process::id() as i64), None, |_| true), Some("stopped"));
+    }
+
+    #[tokio::test]
+    async fn test_find_mismatches_and_mark() {
+        let (pool, _db) = test_pool().await;
+        sqlx::query("INSERT INTO projects (id, name, root_path, created_at, last_activity) VALUES ('p1', 'App', '/tmp/app', 0, 0)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        for (id, status) in [("live", "running"), ("crashed", "starting"), ("done", "stopped")] {
+            sqlx::query("INSERT INTO agent_sessions (id, project_id, agent_type, started_at, status) VALUES (?, 'p1', 'claude-code', 0, ?)")
+                .bind(id)
+                .bind(status)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        let app_sessions = HashMap::from([("live".to_string(), AgentStatus::Running)]);
+        let (checked, fixes) = find_mismatches(&pool, &app_sessions).await.unwrap();
+        assert_eq!(checked, 2);
+        assert_eq!(fixes.iter().map(|f| (f.session_id.as_str(), f.status.as_str())).collect::<Vec<_>>(), vec![("crashed", "stopped")]);
+
+        mark(&pool, &fixes).await.unwrap();
+        let (status, ended_at): (String, Option<i64>) =
+            sqlx::query_as("SELECT status, ended_at FROM agent_sessions WHERE id = 'crashed'")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(status, "stopped");
+        assert!(ended_at.is_some());
+        assert!(find_mismatches(&pool, &app_sessions).await.unwrap().1.is_empty());
+    }
+}
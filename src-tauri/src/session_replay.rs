@@ -0,0 +1,442 @@
+// Session Replay
+// Records the events parsed from agent sessions with their arrival times and
+// plays them back in order, sped up, to review what an unattended run did;
+// bookmarks mark moments in them to come back to
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use sqlx::{FromRow, SqlitePool};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::task::AbortHandle;
+
+use crate::output_parser::AgentEvent;
+
+/// Emitted for each event a replay plays back
+pub const SESSION_REPLAY_EVENT: &str = "session-replay-event";
+
+/// Emitted when a replay has played its last event or was stopped
+pub const SESSION_REPLAY_FINISHED_EVENT: &str = "session-replay-finished";
+
+/// Longest pause between two replayed events, however long the agent idled
+const MAX_GAP: Duration = Duration::from_secs(5);
+
+/// An event as recorded for a session
+#[derive(Debug, Clone, Serialize)]
+pub struct RecordedEvent {
+    /// 1-based order within the session
+    pub seq: i64,
+    /// Unix milliseconds when the event arrived
+    pub recorded_at: i64,
+    pub event: AgentEvent,
+}
+
+/// Store a session's events as they arrive until the session is stopped
+pub fn record_events(pool: SqlitePool, session_id: String, mut events: broadcast::Receiver<AgentEvent>) {
+    tokio::spawn(async move {
+        let mut seq: i64 = sqlx::query_scalar("SELECT COALESCE(MAX(seq), 0) FROM session_events WHERE session_id = ?")
+            .bind(&session_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap_or(0);
+        loop {
+            let event = match events.recv().await {
+                Ok(event) => event,
+                Err(RecvError::Lagged(missed)) => {
+                    log::warn!("Replay of session {} will miss {} events", session_id, missed);
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            };
+            seq += 1;
+            if let Err(e) = insert_event(&pool, &session_id, seq, &event).await {
+                log::warn!("Failed to record event of session {}: {}", session_id, e);
+            }
+        }
+    });
+}
+
+async fn insert_event(pool: &SqlitePool, session_id: &str, seq: i64, event: &AgentEvent) -> Result<()> {
+    let payload = serde_json::to_value(event)?;
+    let event_type = payload["type"].as_str().unwrap_or_default().to_string();
+    sqlx::query(
+        "INSERT INTO session_events (session_id, seq, event_type, payload, recorded_at) VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(session_id)
+    .bind(seq)
+    .bind(event_type)
+    .bind(payload.to_string())
+    .bind(chrono::Utc::now().timestamp_millis())
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// A session's recorded events in order
+pub async fn load_events(pool: &SqlitePool, session_id: &str) -> Result<Vec<RecordedEvent>> {
+    let rows: Vec<(i64, i64, String)> = sqlx::query_as(
+        "SELECT seq, recorded_at, payload FROM session_events WHERE session_id = ? ORDER BY seq",
+    )
+    .bind(session_id)
+    .fetch_all(pool)
+    .await
+    .context("Failed to load session events")?;
+
+    let mut events = Vec::with_capacity(rows.len());
+    for (seq, recorded_at, payload) in rows {
+        match serde_json::from_str(&payload) {
+            Ok(event) => events.push(RecordedEvent { seq, recorded_at, event }),
+            Err(e) => log::warn!("Skipping unreadable event {} of session {}: {}", seq, session_id, e),
+        }
+    }
+    Ok(events)
+}
+
+/// A moment marked in a session's recorded events
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct SessionBookmark {
+    pub id: String,
+    pub session_id: String,
+    /// `seq` of the bookmarked event
+    pub event_seq: i64,
+    pub note: String,
+    /// Unix milliseconds when the bookmarked event arrived
+    pub recorded_at: i64,
+    /// Unix seconds
+    pub created_at: i64,
+}
+
+const BOOKMARK_COLUMNS: &str = "b.id, b.session_id, b.event_seq, b.note, e.recorded_at, b.created_at";
+
+/// Bookmark one of a session's recorded events, replacing the note if it's
+/// already bookmarked; `None` if the session has no such event
+pub async fn add_bookmark(
+    pool: &SqlitePool,
+    session_id: &str,
+    event_seq: i64,
+    note: &str,
+) -> Result<Option<SessionBookmark>> {
+    let recorded: bool =
+        sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM session_events WHERE session_id = ? AND seq = ?)")
+            .bind(session_id)
+            .bind(event_seq)
+            .fetch_one(pool)
+            .await
+            .context("Failed to look up session event")?;
+    if !recorded {
+        return Ok(None);
+    }
+
+    sqlx::query(
+        "INSERT INTO session_bookmarks (id, session_id, event_seq, note, created_at) VALUES (?, ?, ?, ?, ?)
+         ON CONFLICT(session_id, event_seq) DO UPDATE SET note = excluded.note",
+    )
+    .bind(uuid::Uuid::new_v4().to_string())
+    .bind(session_id)
+    .bind(event_seq)
+    .bind(note.trim())
+    .bind(chrono::Utc::now().timestamp())
+    .execute(pool)
+    .await
+    .context("Failed to save bookmark")?;
+
+    let bookmark = sqlx::query_as(&format!(
+        "SELECT {} FROM session_bookmarks b
+         JOIN session_events e ON e.session_id = b.session_id AND e.seq = b.event_seq
+         WHERE b.session_id = ? AND b.event_seq = ?",
+        BOOKMARK_COLUMNS
+    ))
+    .bind(session_id)
+    .bind(event_seq)
+    .fetch_one(pool)
+    .await
+    .context("Failed to load bookmark")?;
+    Ok(Some(bookmark))
+}
+
+/// A session's bookmarks in event order
+pub async fn list_bookmarks(pool: &SqlitePool, session_id: &str) -> Result<Vec<SessionBookmark>> {
+    sqlx::query_as(&format!(
+        "SELECT {} FROM session_bookmarks b
+         JOIN session_events e ON e.session_id = b.session_id AND e.seq = b.event_seq
+         WHERE b.session_id = ? ORDER BY b.event_seq",
+        BOOKMARK_COLUMNS
+    ))
+    .bind(session_id)
+    .fetch_all(pool)
+    .await
+    .context("Failed to load bookmarks")
+}
+
+/// Remove a bookmark; returns false if there was none
+pub async fn delete_bookmark(pool: &SqlitePool, bookmark_id: &str) -> Result<bool> {
+    let result = sqlx::query("DELETE FROM session_bookmarks WHERE id = ?")
+        .bind(bookmark_id)
+        .execute(pool)
+        .await
+        .context("Failed to delete bookmark")?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// How long to wait before each event: the original gap divided by `speed`,
+/// capped at [`MAX_GAP`]
+fn replay_delays(events: &[RecordedEvent], speed: f64) -> Vec<Duration> {
+    let mut previous = events.first().map_or(0, |e| e.recorded_at);
+    events
+        .iter()
+        .map(|event| {
+            let gap_ms = (event.recorded_at - previous).max(0) as f64 / speed;
+            previous = event.recorded_at;
+            Duration::from_secs_f64(gap_ms / 1000.0).min(MAX_GAP)
+        })
+        .collect()
+}
+
+/// Replays in progress, so they can be stopped
+#[derive(Default)]
+pub struct ReplayManager {
+    active: Arc<Mutex<HashMap<String, AbortHandle>>>,
+}
+
+impl ReplayManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Play `events` back at `speed` times their original pace, calling `emit`
+    /// with each, then `finished`
+    pub fn start(
+        &self,
+        replay_id: String,
+        events: Vec<RecordedEvent>,
+        speed: f64,
+        emit: impl Fn(&RecordedEvent) + Send + 'static,
+        finished: impl FnOnce() + Send + 'static,
+    ) {
+        let delays = replay_delays(&events, speed);
+        let id = replay_id.clone();
+        let replays = self.active.clone();
+        // Registered under the lock so a replay that finishes right away can't
+        // unregister before it's registered
+        let mut active = self.active.lock().unwrap();
+        let handle = tokio::spawn(async move {
+            for (event, delay) in events.iter().zip(delays) {
+                tokio::time::sleep(delay).await;
+                emit(event);
+            }
+            replays.lock().unwrap().remove(&id);
+            finished();
+        });
+        active.insert(replay_id, handle.abort_handle());
+    }
+
+    /// Stop a replay; returns false if it wasn't playing
+    pub fn stop(&self, replay_id: &str) -> bool {
+        match self.active.lock().unwrap().remove(replay_id) {
+            Some(handle) => {
+                handle.abort();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+use crate::db::Database;
+use tauri::{AppHandle, Emitter, State};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayInfo {
+    pub replay_id: String,
+    pub session_id: String,
+    pub events: usize,
+    /// How long the replay takes, in milliseconds
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ReplayedEvent<'a> {
+    replay_id: &'a str,
+    session_id: &'a str,
+    #[serde(flatten)]
+    event: &'a RecordedEvent,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ReplayFinished {
+    replay_id: String,
+    session_id: String,
+    stopped: bool,
+}
+
+/// A session's recorded events, for stepping through them
+#[tauri::command]
+pub async fn get_session_events(db: State<'_, Database>, session_id: String) -> Result<Vec<RecordedEvent>, String> {
+    load_events(db.pool(), &session_id).await.map_err(|e| e.to_string())
+}
+
+/// Play a session's recorded events back in order as `session-replay-event`
+/// events, `speed` times faster than they happened (pauses longer than 5
+/// seconds are shortened), ending with `session-replay-finished`; `from_seq`
+/// starts at that event, e.g. a bookmarked one
+#[tauri::command]
+pub async fn replay_session(
+    app: AppHandle,
+    db: State<'_, Database>,
+    replays: State<'_, ReplayManager>,
+    session_id: String,
+    speed: f64,
+    from_seq: Option<i64>,
+) -> Result<ReplayInfo, String> {
+    if !speed.is_finite() || speed <= 0.0 {
+        return Err(format!("Replay speed must be above 0, got {}", speed));
+    }
+    let mut events = load_events(db.pool(), &session_id).await.map_err(|e| e.to_string())?;
+    if let Some(from_seq) = from_seq {
+        events.retain(|event| event.seq >= from_seq);
+    }
+    if events.is_empty() {
+        return Err(format!("No recorded events for session {}", session_id));
+    }
+
+    let replay_id = uuid::Uuid::new_v4().to_string();
+    let info = ReplayInfo {
+        replay_id: replay_id.clone(),
+        session_id: session_id.clone(),
+        events: events.len(),
+        duration_ms: replay_delays(&events, speed).iter().sum::<Duration>().as_millis() as u64,
+    };
+    log::info!("Replaying {} events of session {} at {}x", events.len(), session_id, speed);
+
+    let emit_handle = app.clone();
+    let (emit_replay, emit_session) = (replay_id.clone(), session_id.clone());
+    let finished = ReplayFinished { replay_id: replay_id.clone(), session_id, stopped: false };
+    replays.start(
+        replay_id,
+        events,
+        speed,
+        move |event| {
+            let payload = ReplayedEvent { replay_id: &emit_replay, session_id: &emit_session, event };
+            if let Err(e) = emit_handle.emit(SESSION_REPLAY_EVENT, payload) {
+                log::warn!("Failed to emit replayed event: {}", e);
+            }
+        },
+        move || {
+            if let Err(e) = app.emit(SESSION_REPLAY_FINISHED_EVENT, finished) {
+                log::warn!("Failed to emit replay end: {}", e);
+            }
+        },
+    );
+    Ok(info)
+}
+
+/// Stop a replay; returns false if it had already finished
+#[tauri::command]
+pub async fn stop_replay(
+    app: AppHandle,
+    replays: State<'_, ReplayManager>,
+    replay_id: String,
+    session_id: String,
+) -> Result<bool, String> {
+    if !replays.stop(&replay_id) {
+        return Ok(false);
+    }
+    let finished = ReplayFinished { replay_id, session_id, stopped: true };
+    if let Err(e) = app.emit(SESSION_REPLAY_FINISHED_EVENT, finished) {
+        log::warn!("Failed to emit replay end: {}", e);
+    }
+    Ok(true)
+}
+
+/// Bookmark a moment in a session's recorded events with a note, e.g. where
+/// it went wrong; bookmarking the same event again replaces its note
+#[tauri::command]
+pub async fn bookmark_session_moment(
+    db: State<'_, Database>,
+    session_id: String,
+    event_seq: i64,
+    note: String,
+) -> Result<SessionBookmark, String> {
+    add_bookmark(db.pool(), &session_id, event_seq, &note)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Session {} has no recorded event {}", session_id, event_seq))
+}
+
+/// A session's bookmarks in event order
+#[tauri::command]
+pub async fn list_session_bookmarks(
+    db: State<'_, Database>,
+    session_id: String,
+) -> Result<Vec<SessionBookmark>, String> {
+    list_bookmarks(db.pool(), &session_id).await.map_err(|e| e.to_string())
+}
+
+/// Remove a bookmark; returns false if it was already gone
+#[tauri::command]
+pub async fn delete_session_bookmark(db: State<'_, Database>, bookmark_id: String) -> Result<bool, String> {
+    delete_bookmark(db.pool(), &bookmark_id).await.map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_pool;
+
+    fn recorded(seq: i64, recorded_at: i64) -> RecordedEvent {
+        RecordedEvent { seq, recorded_at, event: AgentEvent::Thinking { message: None, timestamp: 0 } }
+    }
+
+    #[test]
+    fn test_replay_delays_scale_and_cap() {
+        let events = [recorded(1, 1_000), recorded(2, 3_000), recorded(3, 3_500), recorded(4, 3_600_000)];
+        let delays = replay_delays(&events, 2.0);
+        assert_eq!(
+            delays,
+            vec![Duration::ZERO, Duration::from_secs(1), Duration::from_millis(250), MAX_GAP]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_bookmarks() {
+        let (pool, _db) = test_pool().await;
+        sqlx::query("INSERT INTO projects (id, name, root_path, created_at, last_activity) VALUES ('p1', 'p1', '/tmp/p1', 0, 0)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO agent_sessions (id, project_id, agent_type, started_at, status)
+             VALUES ('s1', 'p1', 'claude-code', 0, 'stopped')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let event = AgentEvent::Thinking { message: None, timestamp: 0 };
+        for seq in 1..=3 {
+            insert_event(&pool, "s1", seq, &event).await.unwrap();
+        }
+
+        assert!(add_bookmark(&pool, "s1", 9, "missing").await.unwrap().is_none());
+        let late = add_bookmark(&pool, "s1", 3, " went wrong ").await.unwrap().unwrap();
+        assert_eq!(late.note, "went wrong");
+        add_bookmark(&pool, "s1", 1, "start").await.unwrap().unwrap();
+
+        // Bookmarking an event again replaces its note
+        let again = add_bookmark(&pool, "s1", 3, "really went wrong").await.unwrap().unwrap();
+        assert_eq!(again.id, late.id);
+
+        let bookmarks = list_bookmarks(&pool, "s1").await.unwrap();
+        let listed: Vec<(i64, &str)> = bookmarks.iter().map(|b| (b.event_seq, b.note.as_str())).collect();
+        assert_eq!(listed, vec![(1, "start"), (3, "really went wrong")]);
+
+        assert!(delete_bookmark(&pool, &late.id).await.unwrap());
+        assert!(!delete_bookmark(&pool, &late.id).await.unwrap());
+        assert_eq!(list_bookmarks(&pool, "s1").await.unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,293 @@
+// Side-by-Side Diff
+// Aligns the hunks of a unified diff into old/new line pairs with intra-line change ranges
+
+use serde::Serialize;
+use similar::{Algorithm, DiffTag, TextDiff};
+
+/// Pairs less similar than this are highlighted as whole lines
+const MIN_INLINE_RATIO: f32 = 0.4;
+
+/// Lines longer than this skip the character-level diff
+const MAX_INLINE_LINE_CHARS: usize = 2_000;
+
+/// Changed `[start, end)` ranges in UTF-16 code units, so they slice JS strings directly
+pub type ChangeRanges = Vec<(usize, usize)>;
+
+/// One side of a row
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct DiffLine {
+    /// 1-based line number in its version of the file
+    pub number: usize,
+    pub content: String,
+    pub changes: ChangeRanges,
+    /// Syntax highlighting, filled in by [`crate::highlight::highlight_diff`]
+    pub tokens: Vec<crate::highlight::TokenSpan>,
+}
+
+/// One aligned row; `old` is empty for insertions, `new` for deletions
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct DiffRow {
+    /// "equal", "delete", "insert" or "replace"
+    pub kind: &'static str,
+    pub old: Option<DiffLine>,
+    pub new: Option<DiffLine>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DiffHunk {
+    /// The `@@ -a,b +c,d @@` line
+    pub header: String,
+    pub rows: Vec<DiffRow>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SideBySideDiff {
+    pub file_path: String,
+    pub hunks: Vec<DiffHunk>,
+}
+
+/// Build side-by-side rows from a unified diff
+pub fn side_by_side(file_path: &str, unified_diff: &str) -> SideBySideDiff {
+    let mut hunks = Vec::new();
+    let mut current: Option<RawHunk> = None;
+
+    for line in unified_diff.lines() {
+        if line.starts_with("@@") {
+            if let Some(hunk) = current.take() {
+                hunks.push(hunk.align());
+            }
+            current = RawHunk::parse_header(line);
+            continue;
+        }
+        let Some(hunk) = current.as_mut() else {
+            // File headers before the first hunk
+            continue;
+        };
+        match line.chars().next() {
+            Some('+') => hunk.new_lines.push(line[1..].to_string()),
+            Some('-') => hunk.old_lines.push(line[1..].to_string()),
+            Some(' ') => {
+                hunk.old_lines.push(line[1..].to_string());
+                hunk.new_lines.push(line[1..].to_string());
+            }
+            // Some tools drop the space on empty context lines
+            None => {
+                hunk.old_lines.push(String::new());
+                hunk.new_lines.push(String::new());
+            }
+            // "\ No newline at end of file"
+            _ => {}
+        }
+    }
+    if let Some(hunk) = current {
+        hunks.push(hunk.align());
+    }
+
+    SideBySideDiff {
+        file_path: file_path.to_string(),
+        hunks,
+    }
+}
+
+struct RawHunk {
+    header: String,
+    old_start: usize,
+    new_start: usize,
+    old_lines: Vec<String>,
+    new_lines: Vec<String>,
+}
+
+impl RawHunk {
+    fn parse_header(line: &str) -> Option<Self> {
+        let mut ranges = line.trim_start_matches('@').split_whitespace();
+        let old = ranges.next()?.strip_prefix('-')?;
+        let new = ranges.next()?.strip_prefix('+')?;
+        let start = |range: &str| range.split(',').next().and_then(|n| n.parse::<usize>().ok());
+
+        Some(Self {
+            header: line.to_string(),
+            // An empty side ("-0,0") starts before line 1
+            old_start: start(old)?.max(1),
+            new_start: start(new)?.max(1),
+            old_lines: Vec::new(),
+            new_lines: Vec::new(),
+        })
+    }
+
+    fn align(self) -> DiffHunk {
+        let ops = similar::capture_diff_slices(Algorithm::Myers, &self.old_lines, &self.new_lines);
+        let old_line = |i: usize| DiffLine {
+            number: self.old_start + i,
+            content: self.old_lines[i].clone(),
+            changes: Vec::new(),
+            tokens: Vec::new(),
+        };
+        let new_line = |i: usize| DiffLine {
+            number: self.new_start + i,
+            content: self.new_lines[i].clone(),
+            changes: Vec::new(),
+            tokens: Vec::new(),
+        };
+
+        let mut rows = Vec::new();
+        for op in ops {
+            let (tag, old_range, new_range) = op.as_tag_tuple();
+            match tag {
+                DiffTag::Equal => rows.extend(old_range.zip(new_range).map(|(o, n)| DiffRow {
+                    kind: "equal",
+                    old: Some(old_line(o)),
+                    new: Some(new_line(n)),
+                })),
+                DiffTag::Delete => rows.extend(old_range.map(|o| deleted(old_line(o)))),
+                DiffTag::Insert => rows.extend(new_range.map(|n| inserted(new_line(n)))),
+                DiffTag::Replace => {
+                    let paired = old_range.len().min(new_range.len());
+                    for (o, n) in old_range.clone().zip(new_range.clone()) {
+                        let (old_changes, new_changes) =
+                            inline_changes(&self.old_lines[o], &self.new_lines[n]);
+                        rows.push(DiffRow {
+                            kind: "replace",
+                            old: Some(DiffLine { changes: old_changes, ..old_line(o) }),
+                            new: Some(DiffLine { changes: new_changes, ..new_line(n) }),
+                        });
+                    }
+                    rows.extend(old_range.skip(paired).map(|o| deleted(old_line(o))));
+                    rows.extend(new_range.skip(paired).map(|n| inserted(new_line(n))));
+                }
+            }
+        }
+
+        DiffHunk {
+            header: self.header,
+            rows,
+        }
+    }
+}
+
+fn deleted(mut line: DiffLine) -> DiffRow {
+    line.changes = whole_line(&line.content);
+    DiffRow {
+        kind: "delete",
+        old: Some(line),
+        new: None,
+    }
+}
+
+fn inserted(mut line: DiffLine) -> DiffRow {
+    line.changes = whole_line(&line.content);
+    DiffRow {
+        kind: "insert",
+        old: None,
+        new: Some(line),
+    }
+}
+
+fn whole_line(content: &str) -> ChangeRanges {
+    match content.encode_utf16().count() {
+        0 => Vec::new(),
+        len => vec![(0, len)],
+    }
+}
+
+/// Character-level changed ranges for a replaced pair of lines
+fn inline_changes(old: &str, new: &str) -> (ChangeRanges, ChangeRanges) {
+    if old.chars().count() > MAX_INLINE_LINE_CHARS || new.chars().count() > MAX_INLINE_LINE_CHARS {
+        return (whole_line(old), whole_line(new));
+    }
+
+    let diff = TextDiff::configure().algorithm(Algorithm::Myers).diff_chars(old, new);
+    if diff.ratio() < MIN_INLINE_RATIO {
+        return (whole_line(old), whole_line(new));
+    }
+
+    let old_offsets = utf16_offsets(old);
+    let new_offsets = utf16_offsets(new);
+    let mut old_changes = ChangeRanges::new();
+    let mut new_changes = ChangeRanges::new();
+
+    for op in diff.ops() {
+        let (tag, old_range, new_range) = op.as_tag_tuple();
+        if tag == DiffTag::Equal {
+            continue;
+        }
+        if !old_range.is_empty() {
+            push_range(&mut old_changes, old_offsets[old_range.start], old_offsets[old_range.end]);
+        }
+        if !new_range.is_empty() {
+            push_range(&mut new_changes, new_offsets[new_range.start], new_offsets[new_range.end]);
+        }
+    }
+    (old_changes, new_changes)
+}
+
+/// UTF-16 offset of every char boundary, including the end
+fn utf16_offsets(text: &str) -> Vec<usize> {
+    let mut offsets = Vec::with_capacity(text.len() + 1);
+    let mut offset = 0;
+    offsets.push(0);
+    for c in text.chars() {
+        offset += c.len_utf16();
+        offsets.push(offset);
+    }
+    offsets
+}
+
+/// Append a range, merging it with the previous one when they touch
+fn push_range(ranges: &mut ChangeRanges, start: usize, end: usize) {
+    match ranges.last_mut() {
+        Some(last) if last.1 == start => last.1 = end,
+        _ => ranges.push((start, end)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIFF: &str = concat!(
+        "diff --git a/src/lib.rs b/src/lib.rs\n",
+        "--- a/src/lib.rs\n",
+        "+++ b/src/lib.rs\n",
+        "@@ -10,4 +10,4 @@ fn main() {\n",
+        "     let a = 1;\n",
+        "-    let b = 2;\n",
+        "-    let c = 3;\n",
+        "+    let b = 20;\n",
+        "     println!();\n",
+        "+    done();\n",
+    );
+
+    #[test]
+    fn test_side_by_side_aligns_rows() {
+        let diff = side_by_side("src/lib.rs", DIFF);
+        assert_eq!(diff.hunks.len(), 1);
+
+        let kinds: Vec<_> = diff.hunks[0].rows.iter().map(|r| r.kind).collect();
+        assert_eq!(kinds, ["equal", "replace", "delete", "equal", "insert"]);
+
+        let replace = &diff.hunks[0].rows[1];
+        assert_eq!(replace.old.as_ref().unwrap().number, 11);
+        assert_eq!(replace.new.as_ref().unwrap().number, 11);
+        assert_eq!(replace.new.as_ref().unwrap().changes, vec![(13, 14)]);
+        assert!(replace.old.as_ref().unwrap().changes.is_empty());
+
+        let insert = &diff.hunks[0].rows[4];
+        assert_eq!(insert.new.as_ref().unwrap().number, 13);
+        assert_eq!(insert.new.as_ref().unwrap().changes, vec![(0, 11)]);
+    }
+
+    #[test]
+    fn test_inline_changes_use_utf16_offsets() {
+        let (old, new) = inline_changes("let s = \"😀 a\";", "let s = \"😀 b\";");
+        assert_eq!(old, vec![(12, 13)]);
+        assert_eq!(new, vec![(12, 13)]);
+    }
+
+    #[test]
+    fn test_new_file_starts_at_line_one() {
+        let diff = side_by_side("new.txt", "--- /dev/null\n+++ new.txt\n@@ -0,0 +1,2 @@\n+one\n+two\n");
+        let numbers: Vec<_> = diff.hunks[0].rows.iter().map(|r| r.new.as_ref().unwrap().number).collect();
+        assert_eq!(numbers, [1, 2]);
+        assert!(diff.hunks[0].rows.iter().all(|r| r.old.is_none()));
+    }
+}
@@ -0,0 +1,97 @@
+// Task Estimation
+// Builds the AI prompt for estimating a task and parses the estimate it returns
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::Task;
+
+/// Maximum characters of each file included as context
+const MAX_SNIPPET_CHARS: usize = 8_000;
+
+/// One step of a suggested breakdown
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EstimateStep {
+    pub step: String,
+    pub hours: f64,
+}
+
+/// An effort estimate returned by the AI
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskEstimate {
+    pub estimated_hours: f64,
+    #[serde(default)]
+    pub breakdown: Vec<EstimateStep>,
+    #[serde(default)]
+    pub reasoning: Option<String>,
+}
+
+/// Build the estimation prompt from a task and `(path, contents)` file snippets
+pub fn build_estimate_prompt(task: &Task, files: &[(String, String)]) -> String {
+    let mut prompt = format!(
+        "Estimate the effort needed to complete this software task.\n\nTask: {}\nPriority: {}\n",
+        task.title, task.priority
+    );
+
+    if let Some(description) = task.description.as_deref().map(str::trim).filter(|d| !d.is_empty()) {
+        prompt.push_str(&format!("\nDescription:\n{}\n", description));
+    }
+
+    for (path, contents) in files {
+        let snippet: String = contents.chars().take(MAX_SNIPPET_CHARS).collect();
+        let truncated = if snippet.len() < contents.len() { "\n... (truncated)" } else { "" };
+        prompt.push_str(&format!("\nFile: {}\n```\n{}{}\n```\n", path, snippet, truncated));
+    }
+
+    prompt.push_str(
+        r#"
+Respond with only a JSON object in this format:
+{"estimated_hours": 4.5, "breakdown": [{"step": "short description", "hours": 1.5}], "reasoning": "why this estimate"}"#,
+    );
+    prompt
+}
+
+/// Parse an estimate from the AI response, tolerating code fences or surrounding text
+pub fn parse_estimate(response: &str) -> Option<TaskEstimate> {
+    let start = response.find('{')?;
+    let end = response.rfind('}')?;
+    if end < start {
+        return None;
+    }
+
+    let estimate: TaskEstimate = serde_json::from_str(&response[start..=end]).ok()?;
+    if !estimate.estimated_hours.is_finite() || estimate.estimated_hours < 0.0 {
+        return None;
+    }
+    Some(estimate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_estimate_prompt() {
+        let mut task = Task::new("p1".to_string(), "Add login".to_string(), "high".to_string());
+        task.description = Some("Use OAuth".to_string());
+
+        let files = vec![("src/auth.rs".to_string(), "fn login() {}".to_string())];
+        let prompt = build_estimate_prompt(&task, &files);
+        assert!(prompt.contains("Task: Add login"));
+        assert!(prompt.contains("Description:\nUse OAuth"));
+        assert!(prompt.contains("File: src/auth.rs\n```\nfn login() {}\n```"));
+        assert!(prompt.contains("\"estimated_hours\""));
+    }
+
+    #[test]
+    fn test_parse_estimate() {
+        let response = "Here you go:\n```json\n{\"estimated_hours\": 3, \"breakdown\": [{\"step\": \"Write handler\", \"hours\": 2}, {\"step\": \"Tests\", \"hours\": 1}], \"reasoning\": \"Small change\"}\n```";
+        let estimate = parse_estimate(response).unwrap();
+        assert_eq!(estimate.estimated_hours, 3.0);
+        assert_eq!(estimate.breakdown.len(), 2);
+        assert_eq!(estimate.breakdown[0].step, "Write handler");
+        assert_eq!(estimate.reasoning.as_deref(), Some("Small change"));
+
+        assert!(parse_estimate("no json here").is_none());
+        assert!(parse_estimate("{\"estimated_hours\": -1}").is_none());
+    }
+}
@@ -0,0 +1,164 @@
+// Task Export
+// Renders a project's tasks as a Markdown board or CSV for sharing outside the app
+
+use crate::models::Task;
+
+/// Board columns in display order, with their headings
+const STATUS_COLUMNS: &[(&str, &str)] = &[
+    ("todo", "To Do"),
+    ("in_progress", "In Progress"),
+    ("completed", "Completed"),
+];
+
+/// A task together with the agent sessions that worked on it
+#[derive(Debug, Clone)]
+pub struct ExportedTask {
+    pub task: Task,
+    pub labels: Vec<String>,
+    pub session_ids: Vec<String>,
+}
+
+/// Render tasks as a Markdown board grouped by status
+pub fn render_markdown(project_name: &str, tasks: &[ExportedTask]) -> String {
+    let mut out = format!("# {} — Tasks\n", project_name);
+
+    // Known statuses first, then any custom statuses in the order they appear
+    let mut statuses: Vec<(String, String)> = STATUS_COLUMNS
+        .iter()
+        .map(|(status, heading)| (status.to_string(), heading.to_string()))
+        .collect();
+    for exported in tasks {
+        if !statuses.iter().any(|(s, _)| *s == exported.task.status) {
+            statuses.push((exported.task.status.clone(), exported.task.status.clone()));
+        }
+    }
+
+    for (status, heading) in statuses {
+        let column: Vec<&ExportedTask> = tasks.iter().filter(|t| t.task.status == status).collect();
+        if column.is_empty() {
+            continue;
+        }
+
+        out.push_str(&format!("\n## {} ({})\n\n", heading, column.len()));
+        for exported in column {
+            let task = &exported.task;
+            let checkbox = if task.status == "completed" { "x" } else { " " };
+            out.push_str(&format!("- [{}] **{}** ({})", checkbox, task.title, task.priority));
+            if !exported.labels.is_empty() {
+                out.push_str(&format!(" `{}`", exported.labels.join("` `")));
+            }
+            out.push('\n');
+
+            if let Some(description) = task.description.as_deref().filter(|d| !d.trim().is_empty()) {
+                for line in description.lines() {
+                    out.push_str(&format!("  > {}\n", line));
+                }
+            }
+
+            let hours = format_hours(task.estimated_hours, task.actual_hours);
+            if !hours.is_empty() {
+                out.push_str(&format!("  - Hours: {}\n", hours));
+            }
+            if !exported.session_ids.is_empty() {
+                out.push_str(&format!("  - Sessions: {}\n", exported.session_ids.join(", ")));
+            }
+        }
+    }
+
+    out
+}
+
+/// Render tasks as CSV with a header row
+pub fn render_csv(tasks: &[ExportedTask]) -> String {
+    let mut out = String::from(
+        "id,title,status,priority,labels,estimated_hours,actual_hours,created_at,completed_at,sessions\n",
+    );
+
+    for exported in tasks {
+        let task = &exported.task;
+        let fields = [
+            task.id.clone(),
+            task.title.clone(),
+            task.status.clone(),
+            task.priority.clone(),
+            exported.labels.join(";"),
+            task.estimated_hours.map(|h| h.to_string()).unwrap_or_default(),
+            task.actual_hours.map(|h| format!("{:.2}", h)).unwrap_or_default(),
+            format_timestamp(Some(task.created_at)),
+            format_timestamp(task.completed_at),
+            exported.session_ids.join(";"),
+        ];
+        let row: Vec<String> = fields.iter().map(|f| escape_csv(f)).collect();
+        out.push_str(&row.join(","));
+        out.push('\n');
+    }
+
+    out
+}
+
+/// Quote a CSV field if it contains a delimiter, quote or newline
+fn escape_csv(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn format_hours(estimated: Option<f64>, actual: Option<f64>) -> String {
+    match (estimated, actual) {
+        (Some(e), Some(a)) => format!("{:.1} actual / {:.1} estimated", a, e),
+        (Some(e), None) => format!("{:.1} estimated", e),
+        (None, Some(a)) => format!("{:.1} actual", a),
+        (None, None) => String::new(),
+    }
+}
+
+fn format_timestamp(timestamp: Option<i64>) -> String {
+    timestamp
+        .and_then(|ts| chrono::DateTime::from_timestamp(ts, 0))
+        .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exported(title: &str, status: &str) -> ExportedTask {
+        let mut task = Task::new("p1".to_string(), title.to_string(), "high".to_string());
+        task.status = status.to_string();
+        ExportedTask {
+            task,
+            labels: vec!["bug".to_string()],
+            session_ids: vec!["s1".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_escape_csv() {
+        assert_eq!(escape_csv("plain"), "plain");
+        assert_eq!(escape_csv("a, b"), "\"a, b\"");
+        assert_eq!(escape_csv("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn test_render_markdown_groups_by_status() {
+        let tasks = vec![exported("Fix crash", "completed"), exported("Add login", "todo")];
+        let markdown = render_markdown("Demo", &tasks);
+
+        let todo = markdown.find("## To Do (1)").unwrap();
+        let completed = markdown.find("## Completed (1)").unwrap();
+        assert!(todo < completed);
+        assert!(markdown.contains("- [x] **Fix crash** (high) `bug`"));
+        assert!(markdown.contains("  - Sessions: s1"));
+    }
+
+    #[test]
+    fn test_render_csv() {
+        let csv = render_csv(&[exported("Add, login", "todo")]);
+        let mut lines = csv.lines();
+        assert!(lines.next().unwrap().starts_with("id,title,status"));
+        assert!(lines.next().unwrap().contains(",\"Add, login\",todo,high,bug,"));
+    }
+}
@@ -0,0 +1,140 @@
+// Task Rules
+// Maps agent events from a task's session to updates on the task itself
+
+use std::collections::BTreeSet;
+
+use crate::output_parser::{AgentEvent, FileChangeType};
+
+/// Options controlling how agent events update a task
+#[derive(Debug, Clone, Copy)]
+pub struct TaskRules {
+    /// Mark the task completed when the agent reports completion and exits cleanly
+    pub auto_complete: bool,
+}
+
+impl Default for TaskRules {
+    fn default() -> Self {
+        Self { auto_complete: true }
+    }
+}
+
+/// An update to apply to a task in response to an event
+#[derive(Debug, Clone, PartialEq)]
+pub enum TaskAction {
+    /// A file touched by the agent that wasn't yet in files_affected
+    AddAffectedFile(String),
+    /// A test started failing
+    FlagFailingTest { name: String, details: Option<String> },
+}
+
+/// What has been observed so far during a task's agent run
+#[derive(Debug, Clone, Default)]
+pub struct TaskProgress {
+    /// Files affected by the task, in the order they were added
+    pub files: Vec<String>,
+    /// Tests whose most recent run failed
+    pub failing_tests: BTreeSet<String>,
+    /// Whether the agent reported the task as done
+    pub completion_reported: bool,
+}
+
+impl TaskProgress {
+    /// Start tracking from the task's existing files_affected
+    pub fn new(files: Vec<String>) -> Self {
+        Self {
+            files,
+            ..Default::default()
+        }
+    }
+
+    /// Record an event and return the task updates it calls for
+    pub fn apply(&mut self, event: &AgentEvent) -> Vec<TaskAction> {
+        match event {
+            AgentEvent::FileChanged { path, change_type, .. } => {
+                if matches!(change_type, FileChangeType::Deleted) || self.files.contains(path) {
+                    return Vec::new();
+                }
+                self.files.push(path.clone());
+                vec![TaskAction::AddAffectedFile(path.clone())]
+            }
+            AgentEvent::TestRan { name, passed: true, .. } => {
+                self.failing_tests.remove(name);
+                Vec::new()
+            }
+            AgentEvent::TestRan { name, passed: false, details, .. } => {
+                if self.failing_tests.insert(name.clone()) {
+                    vec![TaskAction::FlagFailingTest {
+                        name: name.clone(),
+                        details: details.clone(),
+                    }]
+                } else {
+                    Vec::new()
+                }
+            }
+            AgentEvent::TaskCompleted { .. } => {
+                self.completion_reported = true;
+                Vec::new()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Whether the task should be marked completed once the agent exits
+    pub fn should_complete(&self, rules: &TaskRules, exited_successfully: bool) -> bool {
+        rules.auto_complete
+            && exited_successfully
+            && self.completion_reported
+            && self.failing_tests.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_ran(name: &str, passed: bool) -> AgentEvent {
+        AgentEvent::TestRan {
+            name: name.to_string(),
+            passed,
+            details: None,
+            timestamp: 0,
+        }
+    }
+
+    #[test]
+    fn test_adds_new_files_once() {
+        let mut progress = TaskProgress::new(vec!["src/main.rs".to_string()]);
+        let changed = |path: &str| AgentEvent::FileChanged {
+            path: path.to_string(),
+            change_type: FileChangeType::Modified,
+            timestamp: 0,
+        };
+
+        assert!(progress.apply(&changed("src/main.rs")).is_empty());
+        assert_eq!(
+            progress.apply(&changed("src/lib.rs")),
+            vec![TaskAction::AddAffectedFile("src/lib.rs".to_string())]
+        );
+        assert!(progress.apply(&changed("src/lib.rs")).is_empty());
+        assert_eq!(progress.files, vec!["src/main.rs", "src/lib.rs"]);
+    }
+
+    #[test]
+    fn test_failing_tests_block_completion() {
+        let rules = TaskRules::default();
+        let mut progress = TaskProgress::default();
+        progress.apply(&AgentEvent::TaskCompleted {
+            description: "done".to_string(),
+            timestamp: 0,
+        });
+
+        assert_eq!(progress.apply(&test_ran("auth", false)).len(), 1);
+        assert!(progress.apply(&test_ran("auth", false)).is_empty());
+        assert!(!progress.should_complete(&rules, true));
+
+        progress.apply(&test_ran("auth", true));
+        assert!(progress.should_complete(&rules, true));
+        assert!(!progress.should_complete(&rules, false));
+        assert!(!progress.should_complete(&TaskRules { auto_complete: false }, true));
+    }
+}
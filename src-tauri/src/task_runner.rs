@@ -0,0 +1,261 @@
+// Task Runner
+// Drives a task through an agent session: builds the prompt, then watches the
+// session's events to record time spent and mark the task completed
+
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::db::Database;
+use crate::models::{ActivityLog, Task, TaskComment, TimeEntry};
+use crate::output_parser::AgentEvent;
+use crate::task_rules::{TaskAction, TaskProgress, TaskRules};
+
+/// Split a task's `files_affected` value into individual paths
+///
+/// Accepts either a JSON array of strings or a comma/newline separated list.
+pub fn parse_files_affected(files_affected: &str) -> Vec<String> {
+    if let Ok(files) = serde_json::from_str::<Vec<String>>(files_affected) {
+        return files
+            .into_iter()
+            .map(|f| f.trim().to_string())
+            .filter(|f| !f.is_empty())
+            .collect();
+    }
+
+    files_affected
+        .split([',', '\n'])
+        .map(|f| f.trim().to_string())
+        .filter(|f| !f.is_empty())
+        .collect()
+}
+
+/// Build the prompt sent to the agent for a task
+pub fn compose_task_prompt(task: &Task) -> String {
+    let mut prompt = format!("Please work on the following task.\n\nTask: {}\n", task.title);
+
+    if let Some(description) = task.description.as_deref().map(str::trim).filter(|d| !d.is_empty()) {
+        prompt.push_str(&format!("\nDescription:\n{}\n", description));
+    }
+
+    let files = task
+        .files_affected
+        .as_deref()
+        .map(parse_files_affected)
+        .unwrap_or_default();
+    if !files.is_empty() {
+        prompt.push_str("\nFiles likely affected:\n");
+        for file in &files {
+            prompt.push_str(&format!("- {}\n", file));
+        }
+    }
+
+    prompt.push_str("\nWhen you have finished, reply with a line starting with \"Task completed:\" followed by a short summary.");
+    prompt
+}
+
+/// Watch a task's agent session until the process exits
+///
+/// Events are fed through the task rules as they arrive: touched files are
+/// added to `files_affected` and failing tests are noted on the task. Time
+/// spent is added to `actual_hours`, and the task is marked completed if the
+/// rules allow it.
+#[tracing::instrument(skip_all, fields(project_id = %task.project_id, task_id = %task.id, session_id = %session_id))]
+pub async fn monitor_task_run(
+    db: Database,
+    task: Task,
+    session_id: String,
+    rules: TaskRules,
+    mut events: broadcast::Receiver<AgentEvent>,
+) {
+    let started = chrono::Utc::now().timestamp();
+    let mut progress = TaskProgress::new(
+        task.files_affected.as_deref().map(parse_files_affected).unwrap_or_default(),
+    );
+
+    let success = loop {
+        match events.recv().await {
+            Ok(AgentEvent::ProcessExited { success, exit_code, .. }) => {
+                log::info!("Agent for task {} exited (code: {:?})", task.id, exit_code);
+                break success;
+            }
+            Ok(AgentEvent::TurnCompleted { success, .. }) => {
+                log::info!("Agent finished its turn for task {}", task.id);
+                break success;
+            }
+            Ok(event) => {
+                for action in progress.apply(&event) {
+                    if let Err(e) = apply_task_action(&db, &task, &progress, action).await {
+                        log::error!("Failed to update task {} from agent event: {}", task.id, e);
+                    }
+                }
+            }
+            Err(RecvError::Lagged(skipped)) => {
+                log::warn!("Task runner for {} skipped {} events", task.id, skipped);
+            }
+            Err(RecvError::Closed) => {
+                log::warn!("Agent session {} closed before task {} finished", session_id, task.id);
+                break false;
+            }
+        }
+    };
+
+    let now = chrono::Utc::now().timestamp();
+    let elapsed_hours = (now - started) as f64 / 3600.0;
+
+    if let Err(e) = record_agent_time(&db, &task, started, now).await {
+        log::error!("Failed to record time for task {}: {}", task.id, e);
+    }
+
+    if !progress.should_complete(&rules, success) {
+        log::info!("Task {} left in progress after agent run", task.id);
+        return;
+    }
+
+    if let Err(e) = sqlx::query(
+        r#"
+        UPDATE tasks
+        SET status = 'completed',
+            completed_at = COALESCE(completed_at, ?),
+            position = (SELECT COALESCE(MAX(position), -1) + 1 FROM tasks
+                        WHERE project_id = ? AND status = 'completed')
+        WHERE id = ?
+        "#
+    )
+    .bind(now)
+    .bind(&task.project_id)
+    .bind(&task.id)
+    .execute(db.pool())
+    .await
+    {
+        log::error!("Failed to complete task {}: {}", task.id, e);
+        return;
+    }
+
+    let mut activity = ActivityLog::new(
+        task.project_id.clone(),
+        Some(session_id.clone()),
+        "task_complete".to_string(),
+        format!("Task completed: {}", task.title),
+    );
+    activity.data = Some(serde_json::json!({
+        "task_id": task.id,
+        "title": task.title,
+        "session_id": session_id,
+        "hours": elapsed_hours
+    }).to_string());
+
+    if let Err(e) = crate::activity::insert_activity(db.pool(), &activity).await {
+        log::error!("Failed to log task completion for {}: {}", task.id, e);
+    }
+
+    log::info!("Task {} completed by agent session {}", task.id, session_id);
+}
+
+/// Persist a single rule-driven update to a task
+async fn apply_task_action(
+    db: &Database,
+    task: &Task,
+    progress: &TaskProgress,
+    action: TaskAction,
+) -> Result<(), sqlx::Error> {
+    match action {
+        TaskAction::AddAffectedFile(path) => {
+            log::info!("Task {} touched {}", task.id, path);
+            let files = serde_json::to_string(&progress.files).unwrap_or_default();
+            sqlx::query("UPDATE tasks SET files_affected = ? WHERE id = ?")
+                .bind(files)
+                .bind(&task.id)
+                .execute(db.pool())
+                .await?;
+        }
+        TaskAction::FlagFailingTest { name, details } => {
+            log::warn!("Test failing during task {}: {}", task.id, name);
+            let mut content = format!("Test failing: {}", name);
+            if let Some(details) = details {
+                content.push_str(&format!("\n\n{}", details));
+            }
+            let comment = TaskComment::new(task.id.clone(), "agent".to_string(), content);
+            sqlx::query(
+                r#"
+                INSERT INTO task_comments (id, task_id, author, content, created_at, updated_at)
+                VALUES (?, ?, ?, ?, ?, ?)
+                "#
+            )
+            .bind(&comment.id)
+            .bind(&comment.task_id)
+            .bind(&comment.author)
+            .bind(&comment.content)
+            .bind(comment.created_at)
+            .bind(comment.updated_at)
+            .execute(db.pool())
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Log an agent run as a time entry and add it to the task's actual_hours
+async fn record_agent_time(db: &Database, task: &Task, started: i64, ended: i64) -> Result<(), sqlx::Error> {
+    let mut entry = TimeEntry::new(task.id.clone(), task.project_id.clone(), "agent".to_string());
+    entry.started_at = started;
+    entry.ended_at = Some(ended);
+    entry.duration_seconds = Some((ended - started).max(0));
+
+    let mut tx = db.pool().begin().await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO time_entries (id, task_id, project_id, source, started_at, ended_at, duration_seconds)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
+        "#
+    )
+    .bind(&entry.id)
+    .bind(&entry.task_id)
+    .bind(&entry.project_id)
+    .bind(&entry.source)
+    .bind(entry.started_at)
+    .bind(entry.ended_at)
+    .bind(entry.duration_seconds)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query("UPDATE tasks SET actual_hours = COALESCE(actual_hours, 0) + ? WHERE id = ?")
+        .bind((ended - started).max(0) as f64 / 3600.0)
+        .bind(&task.id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_files_affected() {
+        assert_eq!(
+            parse_files_affected(r#"["src/main.rs", " src/lib.rs "]"#),
+            vec!["src/main.rs", "src/lib.rs"]
+        );
+        assert_eq!(
+            parse_files_affected("src/main.rs, src/lib.rs\nREADME.md,"),
+            vec!["src/main.rs", "src/lib.rs", "README.md"]
+        );
+        assert!(parse_files_affected("").is_empty());
+    }
+
+    #[test]
+    fn test_compose_task_prompt() {
+        let mut task = Task::new("p1".to_string(), "Add login".to_string(), "high".to_string());
+        task.description = Some("Use OAuth".to_string());
+        task.files_affected = Some("src/auth.rs".to_string());
+
+        let prompt = compose_task_prompt(&task);
+        assert!(prompt.contains("Task: Add login"));
+        assert!(prompt.contains("Description:\nUse OAuth"));
+        assert!(prompt.contains("- src/auth.rs"));
+        assert!(prompt.contains("Task completed:"));
+    }
+}
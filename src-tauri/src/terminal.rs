@@ -0,0 +1,298 @@
+// Terminal Sessions
+// Interactive shells on a pseudo-terminal, started in a project's folder, with
+// their output streamed to the frontend
+
+use anyhow::{Context, Result};
+use portable_pty::{native_pty_system, ChildKiller, CommandBuilder, MasterPty, PtySize};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// Event emitted with each chunk of a terminal's output
+pub const TERMINAL_OUTPUT_EVENT: &str = "terminal-output";
+/// Event emitted once a terminal's shell exits
+pub const TERMINAL_EXIT_EVENT: &str = "terminal-exit";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TerminalInfo {
+    pub terminal_id: String,
+    pub project_id: String,
+    pub cwd: String,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TerminalOutput {
+    pub terminal_id: String,
+    pub data: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TerminalExit {
+    pub terminal_id: String,
+    /// None when the shell was killed
+    pub exit_code: Option<u32>,
+}
+
+struct Terminal {
+    info: TerminalInfo,
+    master: Box<dyn MasterPty + Send>,
+    writer: Box<dyn Write + Send>,
+    killer: Box<dyn ChildKiller + Send + Sync>,
+}
+
+/// The open terminals, by id
+#[derive(Clone, Default)]
+pub struct TerminalManager {
+    terminals: Arc<Mutex<HashMap<String, Terminal>>>,
+}
+
+impl TerminalManager {
+    /// Start `shell` (the user's default shell when `None`) in `cwd`
+    ///
+    /// Output is passed to `on_output` from a reader thread until the shell
+    /// exits, then `on_exit` is called and the terminal is forgotten.
+    pub fn open(
+        &self,
+        project_id: &str,
+        cwd: &Path,
+        shell: Option<&str>,
+        size: PtySize,
+        on_output: impl Fn(TerminalOutput) + Send + 'static,
+        on_exit: impl FnOnce(TerminalExit) + Send + 'static,
+    ) -> Result<TerminalInfo> {
+        let cmd = shell.map_or_else(CommandBuilder::new_default_prog, CommandBuilder::new);
+        self.open_command(project_id, cwd, cmd, size, on_output, on_exit)
+    }
+
+    /// Run `cmd` in a terminal in `cwd` instead of a shell, e.g. a CLI's
+    /// interactive login; the terminal goes away when the command exits
+    pub fn open_command(
+        &self,
+        project_id: &str,
+        cwd: &Path,
+        mut cmd: CommandBuilder,
+        size: PtySize,
+        on_output: impl Fn(TerminalOutput) + Send + 'static,
+        on_exit: impl FnOnce(TerminalExit) + Send + 'static,
+    ) -> Result<TerminalInfo> {
+        let pair = native_pty_system()
+            .openpty(size)
+            .context("Failed to open a pseudo-terminal")?;
+
+        cmd.cwd(cwd);
+        cmd.env("TERM", "xterm-256color");
+        let mut child = pair.slave.spawn_command(cmd).context("Failed to start the command")?;
+        // The reader only sees EOF once no slave handle is left open here
+        drop(pair.slave);
+
+        let mut reader = pair.master.try_clone_reader().context("Failed to read the terminal")?;
+        let writer = pair.master.take_writer().context("Failed to write to the terminal")?;
+        let info = TerminalInfo {
+            terminal_id: uuid::Uuid::new_v4().to_string(),
+            project_id: project_id.to_string(),
+            cwd: cwd.to_string_lossy().to_string(),
+            created_at: chrono::Utc::now().timestamp(),
+        };
+        self.terminals.lock().unwrap().insert(
+            info.terminal_id.clone(),
+            Terminal { info: info.clone(), master: pair.master, writer, killer: child.clone_killer() },
+        );
+        log::info!("Opened terminal {} for project {} in {}", info.terminal_id, project_id, info.cwd);
+
+        let terminals = self.terminals.clone();
+        let terminal_id = info.terminal_id.clone();
+        std::thread::spawn(move || {
+            let mut buffer = [0u8; 8192];
+            // Bytes of a UTF-8 character split across reads
+            let mut pending: Vec<u8> = Vec::new();
+            loop {
+                match reader.read(&mut buffer) {
+                    Ok(0) | Err(_) => break,
+                    Ok(read) => {
+                        pending.extend_from_slice(&buffer[..read]);
+                        let complete = complete_utf8_len(&pending);
+                        let data = String::from_utf8_lossy(&pending[..complete]).to_string();
+                        pending.drain(..complete);
+                        on_output(TerminalOutput { terminal_id: terminal_id.clone(), data });
+                    }
+                }
+            }
+
+            let exit_code = child.wait().ok().map(|status| status.exit_code());
+            terminals.lock().unwrap().remove(&terminal_id);
+            log::info!("Terminal {} exited with {:?}", terminal_id, exit_code);
+            on_exit(TerminalExit { terminal_id, exit_code });
+        });
+
+        Ok(info)
+    }
+
+    /// Send input, e.g. keystrokes, to the terminal's shell
+    pub fn write(&self, terminal_id: &str, data: &[u8]) -> Result<()> {
+        let mut terminals = self.terminals.lock().unwrap();
+        let terminal = terminals.get_mut(terminal_id).context("Terminal not found")?;
+        terminal.writer.write_all(data)?;
+        terminal.writer.flush()?;
+        Ok(())
+    }
+
+    pub fn resize(&self, terminal_id: &str, cols: u16, rows: u16) -> Result<()> {
+        let terminals = self.terminals.lock().unwrap();
+        let terminal = terminals.get(terminal_id).context("Terminal not found")?;
+        terminal.master.resize(PtySize { rows, cols, ..Default::default() })
+    }
+
+    /// Kill the terminal's shell; returns false if it had already exited
+    pub fn close(&self, terminal_id: &str) -> bool {
+        match self.terminals.lock().unwrap().remove(terminal_id) {
+            Some(mut terminal) => {
+                if let Err(e) = terminal.killer.kill() {
+                    log::warn!("Failed to kill terminal {}: {}", terminal_id, e);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The project's open terminals, oldest first
+    pub fn list(&self, project_id: &str) -> Vec<TerminalInfo> {
+        let mut terminals: Vec<TerminalInfo> = self
+            .terminals
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|terminal| terminal.info.project_id == project_id)
+            .map(|terminal| terminal.info.clone())
+            .collect();
+        terminals.sort_by_key(|info| info.created_at);
+        terminals
+    }
+}
+
+/// Length of `bytes` without a trailing, still incomplete UTF-8 character
+fn complete_utf8_len(bytes: &[u8]) -> usize {
+    match std::str::from_utf8(bytes) {
+        Ok(_) => bytes.len(),
+        // Invalid bytes are replaced when decoding; only an unfinished tail waits
+        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+        Err(_) => bytes.len(),
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+use crate::db::Database;
+use tauri::{AppHandle, Emitter, State};
+
+/// Open a terminal in the project's folder, or a folder inside it
+///
+/// Output arrives as `terminal-output` events, and `terminal-exit` follows
+/// when the shell exits.
+#[tauri::command]
+pub async fn open_terminal(
+    app: AppHandle,
+    db: State<'_, Database>,
+    terminals: State<'_, TerminalManager>,
+    project_id: String,
+    cwd: Option<String>,
+    cols: Option<u16>,
+    rows: Option<u16>,
+) -> Result<TerminalInfo, String> {
+    let root_path: String = sqlx::query_scalar("SELECT root_path FROM projects WHERE id = ?")
+        .bind(&project_id)
+        .fetch_optional(db.pool())
+        .await
+        .map_err(|e| format!("Failed to load project: {}", e))?
+        .ok_or_else(|| format!("Project not found: {}", project_id))?;
+    let cwd = crate::project_paths::resolve_in_project(&root_path, cwd.unwrap_or_default())?;
+
+    let output_app = app.clone();
+    terminals
+        .open(
+            &project_id,
+            &cwd,
+            None,
+            PtySize { cols: cols.unwrap_or(80), rows: rows.unwrap_or(24), ..Default::default() },
+            move |output| {
+                let _ = output_app.emit(TERMINAL_OUTPUT_EVENT, output);
+            },
+            move |exit| {
+                let _ = app.emit(TERMINAL_EXIT_EVENT, exit);
+            },
+        )
+        .map_err(|e| format!("Failed to open terminal: {:#}", e))
+}
+
+#[tauri::command]
+pub async fn write_terminal(terminals: State<'_, TerminalManager>, terminal_id: String, data: String) -> Result<(), String> {
+    terminals
+        .write(&terminal_id, data.as_bytes())
+        .map_err(|e| format!("Failed to write to terminal: {:#}", e))
+}
+
+#[tauri::command]
+pub async fn resize_terminal(
+    terminals: State<'_, TerminalManager>,
+    terminal_id: String,
+    cols: u16,
+    rows: u16,
+) -> Result<(), String> {
+    terminals
+        .resize(&terminal_id, cols, rows)
+        .map_err(|e| format!("Failed to resize terminal: {:#}", e))
+}
+
+/// Kill a terminal's shell; returns false if it had already exited
+#[tauri::command]
+pub async fn close_terminal(terminals: State<'_, TerminalManager>, terminal_id: String) -> Result<bool, String> {
+    Ok(terminals.close(&terminal_id))
+}
+
+/// The project's open terminals, e.g. to reattach after a reload
+#[tauri::command]
+pub async fn list_terminals(terminals: State<'_, TerminalManager>, project_id: String) -> Result<Vec<TerminalInfo>, String> {
+    Ok(terminals.list(&project_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    #[test]
+    fn test_complete_utf8_len() {
+        let text = "é€".as_bytes();
+        assert_eq!(complete_utf8_len(text), text.len());
+        assert_eq!(complete_utf8_len(&text[..3]), 2);
+        assert_eq!(complete_utf8_len(&[b'a', 0xff, b'b']), 3);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_terminal_runs_in_cwd_and_exits() {
+        let manager = TerminalManager::default();
+        let cwd = std::env::temp_dir().canonicalize().unwrap();
+        let (output_tx, output_rx) = mpsc::channel();
+        let (exit_tx, exit_rx) = mpsc::channel();
+        let info = manager
+            .open("p1", &cwd, Some("sh"), PtySize::default(), move |out| drop(output_tx.send(out.data)), move |exit| drop(exit_tx.send(exit)))
+            .unwrap();
+        assert_eq!(manager.list("p1").len(), 1);
+
+        manager.resize(&info.terminal_id, 120, 40).unwrap();
+        manager.write(&info.terminal_id, b"pwd; exit 3\n").unwrap();
+        let exit = exit_rx.recv_timeout(Duration::from_secs(10)).unwrap();
+        assert_eq!(exit.exit_code, Some(3));
+        let output: String = output_rx.try_iter().collect();
+        assert!(output.contains(&*cwd.to_string_lossy()), "{}", output);
+        assert!(manager.list("p1").is_empty());
+        assert!(!manager.close(&info.terminal_id));
+    }
+}
@@ -0,0 +1,68 @@
+// Token Estimate
+// Approximates token counts for chat content without a model-specific tokenizer
+
+/// Tokens added per message for role markers and separators
+const MESSAGE_OVERHEAD_TOKENS: usize = 4;
+
+/// Share of the context window at which summarizing is suggested
+pub const SUMMARIZE_THRESHOLD: f64 = 0.8;
+
+/// Estimate the number of tokens in a piece of text
+///
+/// Follows the shape of BPE tokenizers: runs of ASCII letters and digits cost
+/// about one token per four characters, while punctuation, symbols and
+/// non-ASCII characters (CJK, emoji) cost roughly one token each.
+pub fn estimate_tokens(text: &str) -> usize {
+    let mut tokens = 0;
+    let mut run: usize = 0;
+
+    for c in text.chars() {
+        if c.is_ascii_alphanumeric() {
+            run += 1;
+            continue;
+        }
+        tokens += run.div_ceil(4);
+        run = 0;
+        if !c.is_whitespace() {
+            tokens += 1;
+        }
+    }
+
+    tokens + run.div_ceil(4)
+}
+
+/// Estimate the tokens used by a conversation's messages
+pub fn estimate_conversation_tokens<'a>(messages: impl IntoIterator<Item = &'a str>) -> usize {
+    messages
+        .into_iter()
+        .map(|content| estimate_tokens(content) + MESSAGE_OVERHEAD_TOKENS)
+        .sum()
+}
+
+/// Context window size for an agent type
+pub fn context_window(agent_type: &str) -> usize {
+    match agent_type.to_lowercase().as_str() {
+        "claude" | "claude-code" => 200_000,
+        "gemini" => 1_000_000,
+        _ => 128_000,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_tokens() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("hello world"), 4);
+        assert_eq!(estimate_tokens("fn main() {}"), 6);
+        assert_eq!(estimate_tokens("日本語"), 3);
+    }
+
+    #[test]
+    fn test_estimate_conversation_tokens() {
+        let messages = ["hello world", "ok"];
+        assert_eq!(estimate_conversation_tokens(messages), 4 + 1 + 2 * MESSAGE_OVERHEAD_TOKENS);
+    }
+}
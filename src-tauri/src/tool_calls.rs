@@ -0,0 +1,263 @@
+// Tool Calls
+// Pulls the tools agents use out of session messages into their own table, so
+// a session's calls can be listed and filtered without parsing message JSON
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::{FromRow, SqlitePool};
+
+use crate::plugin::HistoryMessage;
+use crate::redaction::Redactor;
+
+/// Longer results are cut to this many characters
+const MAX_RESULT_CHARS: usize = 20_000;
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ToolCall {
+    /// The tool use id from the CLI
+    pub id: String,
+    pub project_id: String,
+    pub cli_session_id: String,
+    /// Message that made the call
+    pub message_id: String,
+    pub tool_name: String,
+    /// JSON
+    pub input: String,
+    /// None while the tool is running
+    pub result: Option<String>,
+    pub is_error: bool,
+    pub started_at: i64,
+    pub completed_at: Option<i64>,
+    /// Seconds from the call to its result
+    pub duration_secs: Option<i64>,
+}
+
+/// A tool call starting or finishing, as found in one message
+#[derive(Debug, Clone, PartialEq)]
+enum ToolEvent {
+    Started { id: String, name: String, input: String },
+    Finished { id: String, result: String, is_error: bool },
+}
+
+/// Text of a tool_result block's content, a string or a list of text blocks
+fn result_text(content: Option<&Value>) -> String {
+    match content {
+        Some(Value::String(text)) => text.clone(),
+        Some(Value::Array(blocks)) => blocks
+            .iter()
+            .filter_map(|b| b.get("text").and_then(Value::as_str))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Some(other) => other.to_string(),
+        None => String::new(),
+    }
+}
+
+/// The tool calls a message starts or finishes
+///
+/// Claude sessions keep tool_use and tool_result blocks as JSON in the
+/// content; other plugins describe one call per message in its metadata.
+fn tool_events(message: &HistoryMessage) -> Vec<ToolEvent> {
+    let meta = |key: &str| message.metadata.get(key).map(String::as_str);
+    if meta("is_tool_message") == Some("true") {
+        let Some(name) = meta("tool_name") else {
+            return Vec::new();
+        };
+        let mut events = vec![ToolEvent::Started {
+            id: message.id.clone(),
+            name: name.to_string(),
+            input: meta("tool_input").unwrap_or("{}").to_string(),
+        }];
+        if meta("is_pending") != Some("true") {
+            events.push(ToolEvent::Finished {
+                id: message.id.clone(),
+                result: meta("tool_result").unwrap_or_default().to_string(),
+                is_error: meta("is_error") == Some("true"),
+            });
+        }
+        return events;
+    }
+
+    let Ok(Value::Array(blocks)) = serde_json::from_str::<Value>(&message.content) else {
+        return Vec::new();
+    };
+    blocks
+        .iter()
+        .filter_map(|block| match block.get("type").and_then(Value::as_str)? {
+            "tool_use" => Some(ToolEvent::Started {
+                id: block.get("id")?.as_str()?.to_string(),
+                name: block.get("name")?.as_str()?.to_string(),
+                input: block.get("input").map_or_else(|| "{}".to_string(), Value::to_string),
+            }),
+            "tool_result" => Some(ToolEvent::Finished {
+                id: block.get("tool_use_id")?.as_str()?.to_string(),
+                result: result_text(block.get("content")),
+                is_error: block.get("is_error").and_then(Value::as_bool).unwrap_or(false),
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Store the tool calls `message` starts or finishes
+///
+/// Safe to call again for the same message, as watchers resend history.
+pub async fn record_message(
+    pool: &SqlitePool,
+    redactor: &Redactor,
+    project_id: &str,
+    cli_session_id: &str,
+    message: &HistoryMessage,
+) -> Result<()> {
+    for event in tool_events(message) {
+        match event {
+            ToolEvent::Started { id, name, input } => {
+                sqlx::query(
+                    r#"
+                    INSERT OR IGNORE INTO tool_calls (id, project_id, cli_session_id, message_id, tool_name, input, started_at)
+                    VALUES (?, ?, ?, ?, ?, ?, ?)
+                    "#,
+                )
+                .bind(&id)
+                .bind(project_id)
+                .bind(cli_session_id)
+                .bind(&message.id)
+                .bind(&name)
+                .bind(redactor.redact(&input).as_ref())
+                .bind(message.timestamp)
+                .execute(pool)
+                .await
+                .context("Failed to save tool call")?;
+            }
+            ToolEvent::Finished { id, result, is_error } => {
+                let mut result = redactor.redact(&result).into_owned();
+                if let Some((cut, _)) = result.char_indices().nth(MAX_RESULT_CHARS) {
+                    result.truncate(cut);
+                    result.push('…');
+                }
+                sqlx::query("UPDATE tool_calls SET result = ?, is_error = ?, completed_at = ? WHERE id = ?")
+                    .bind(&result)
+                    .bind(is_error)
+                    .bind(message.timestamp)
+                    .bind(&id)
+                    .execute(pool)
+                    .await
+                    .context("Failed to save tool result")?;
+            }
+        }
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+use crate::db::Database;
+use tauri::State;
+
+/// Tool calls in call order, from one CLI session or a whole project
+///
+/// `tool_name` keeps only that tool's calls, e.g. "Bash" for every command
+/// a session ran.
+#[tauri::command]
+pub async fn get_tool_calls(
+    db: State<'_, Database>,
+    project_id: String,
+    cli_session_id: Option<String>,
+    tool_name: Option<String>,
+    limit: Option<i64>,
+) -> Result<Vec<ToolCall>, String> {
+    sqlx::query_as::<_, ToolCall>(
+        r#"
+        SELECT id, project_id, cli_session_id, message_id, tool_name, input, result, is_error,
+               started_at, completed_at, completed_at - started_at AS duration_secs
+        FROM tool_calls
+        WHERE project_id = ?1
+          AND (?2 IS NULL OR cli_session_id = ?2)
+          AND (?3 IS NULL OR tool_name = ?3)
+        ORDER BY started_at, rowid
+        LIMIT ?4
+        "#,
+    )
+    .bind(&project_id)
+    .bind(&cli_session_id)
+    .bind(&tool_name)
+    .bind(limit.unwrap_or(-1))
+    .fetch_all(db.pool())
+    .await
+    .map_err(|e| format!("Failed to fetch tool calls: {}", e))
+}
+
+/// Each tool used in a project or CLI session with how often, most used first
+#[tauri::command]
+pub async fn get_tool_call_counts(
+    db: State<'_, Database>,
+    project_id: String,
+    cli_session_id: Option<String>,
+) -> Result<Vec<(String, i64)>, String> {
+    sqlx::query_as::<_, (String, i64)>(
+        r#"
+        SELECT tool_name, COUNT(*)
+        FROM tool_calls
+        WHERE project_id = ?1 AND (?2 IS NULL OR cli_session_id = ?2)
+        GROUP BY tool_name
+        ORDER BY COUNT(*) DESC, tool_name
+        "#,
+    )
+    .bind(&project_id)
+    .bind(&cli_session_id)
+    .fetch_all(db.pool())
+    .await
+    .map_err(|e| format!("Failed to count tool calls: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn message(content: &str, metadata: &[(&str, &str)]) -> HistoryMessage {
+        HistoryMessage {
+            id: "m1".to_string(),
+            role: "assistant".to_string(),
+            content: content.to_string(),
+            timestamp: 0,
+            metadata: metadata.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect::<HashMap<_, _>>(),
+        }
+    }
+
+    #[test]
+    fn test_tool_events_from_claude_blocks() {
+        let call = message(
+            r#"[{"type":"text","text":"Running"},{"type":"tool_use","id":"t1","name":"Bash","input":{"command":"ls"}}]"#,
+            &[],
+        );
+        assert_eq!(
+            tool_events(&call),
+            vec![ToolEvent::Started { id: "t1".into(), name: "Bash".into(), input: r#"{"command":"ls"}"#.into() }]
+        );
+
+        let result = message(
+            r#"[{"type":"tool_result","tool_use_id":"t1","content":[{"type":"text","text":"a.rs"}],"is_error":true}]"#,
+            &[],
+        );
+        assert_eq!(
+            tool_events(&result),
+            vec![ToolEvent::Finished { id: "t1".into(), result: "a.rs".into(), is_error: true }]
+        );
+        assert!(tool_events(&message("Just text", &[])).is_empty());
+    }
+
+    #[test]
+    fn test_tool_events_from_metadata() {
+        let pending = message("", &[("is_tool_message", "true"), ("tool_name", "Read"), ("is_pending", "true")]);
+        assert_eq!(
+            tool_events(&pending),
+            vec![ToolEvent::Started { id: "m1".into(), name: "Read".into(), input: "{}".into() }]
+        );
+        let done = message("", &[("is_tool_message", "true"), ("tool_name", "Read"), ("tool_result", "ok")]);
+        assert_eq!(tool_events(&done).len(), 2);
+    }
+}
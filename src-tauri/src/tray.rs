@@ -0,0 +1,214 @@
+// System Tray
+// Tray icon with the number of running agent sessions and a menu to open projects or stop sessions
+
+use std::time::Duration;
+use tauri::menu::{CheckMenuItem, Menu, MenuEvent, MenuItem, PredefinedMenuItem, Submenu};
+use tauri::tray::TrayIconBuilder;
+use tauri::{AppHandle, Emitter, Manager, Wry};
+
+use crate::agent_manager::{AgentManager, AgentStatus};
+use crate::app_settings::AppSettingsManager;
+use crate::db::Database;
+use crate::windows::MAIN_WINDOW;
+
+/// Event asking the frontend to open a project's workspace; the payload is the project id
+pub const OPEN_PROJECT_EVENT: &str = "tray://open-project";
+
+const TRAY_ID: &str = "main";
+const POLL_INTERVAL: Duration = Duration::from_secs(3);
+/// Most recently active projects listed in the menu
+const MAX_PROJECTS: i64 = 10;
+
+const SHOW_ID: &str = "show";
+const STOP_ALL_ID: &str = "stop_all";
+const BACKGROUND_MODE_ID: &str = "background_mode";
+const QUIT_ID: &str = "quit";
+const PROJECT_ID_PREFIX: &str = "project:";
+
+/// What the tray currently shows
+#[derive(Debug, Clone, Default, PartialEq)]
+struct TrayState {
+    running: usize,
+    projects: Vec<TrayProject>,
+    background_mode: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct TrayProject {
+    id: String,
+    name: String,
+    running: usize,
+}
+
+/// Create the tray icon and keep it in step with the running sessions
+pub fn init(app: &AppHandle) -> tauri::Result<()> {
+    let state = TrayState {
+        background_mode: app.state::<AppSettingsManager>().get().background_mode,
+        ..Default::default()
+    };
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip(tooltip(&state))
+        .menu(&build_menu(app, &state)?)
+        .on_menu_event(handle_menu_event);
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut shown = state;
+        loop {
+            let state = load_state(&app).await;
+            if state != shown {
+                if let Err(e) = update(&app, &state) {
+                    log::warn!("Failed to update tray: {}", e);
+                }
+                shown = state;
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    });
+    Ok(())
+}
+
+/// Bring the main window back, e.g. after it was hidden in background mode
+pub fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(MAIN_WINDOW) {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+async fn load_state(app: &AppHandle) -> TrayState {
+    let sessions: Vec<_> = app
+        .state::<AgentManager>()
+        .list_sessions()
+        .await
+        .into_iter()
+        .filter(|s| s.status == AgentStatus::Running)
+        .collect();
+
+    let db = app.state::<Database>();
+    let projects = sqlx::query_as::<_, (String, String)>(
+        "SELECT id, name FROM projects ORDER BY last_activity DESC LIMIT ?"
+    )
+    .bind(MAX_PROJECTS)
+    .fetch_all(db.pool())
+    .await
+    .unwrap_or_else(|e| {
+        log::warn!("Failed to load projects for the tray: {}", e);
+        Vec::new()
+    });
+
+    TrayState {
+        running: sessions.len(),
+        projects: projects
+            .into_iter()
+            .map(|(id, name)| TrayProject {
+                running: sessions.iter().filter(|s| s.project_id == id).count(),
+                id,
+                name,
+            })
+            .collect(),
+        background_mode: app.state::<AppSettingsManager>().get().background_mode,
+    }
+}
+
+fn update(app: &AppHandle, state: &TrayState) -> tauri::Result<()> {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return Ok(());
+    };
+    tray.set_menu(Some(build_menu(app, state)?))?;
+    tray.set_tooltip(Some(tooltip(state)))?;
+    // Shown next to the icon on macOS and Linux
+    tray.set_title((state.running > 0).then(|| state.running.to_string()))?;
+    Ok(())
+}
+
+fn running_label(running: usize) -> String {
+    match running {
+        0 => "No sessions running".to_string(),
+        1 => "1 session running".to_string(),
+        n => format!("{} sessions running", n),
+    }
+}
+
+fn tooltip(state: &TrayState) -> String {
+    format!("AtelierCode: {}", running_label(state.running))
+}
+
+fn build_menu(app: &AppHandle, state: &TrayState) -> tauri::Result<Menu<Wry>> {
+    let status = MenuItem::with_id(app, "status", running_label(state.running), false, None::<&str>)?;
+    let show = MenuItem::with_id(app, SHOW_ID, "Open AtelierCode", true, None::<&str>)?;
+
+    let projects = Submenu::with_id(app, "projects", "Projects", !state.projects.is_empty())?;
+    for project in &state.projects {
+        let label = match project.running {
+            0 => project.name.clone(),
+            n => format!("{} ({} running)", project.name, n),
+        };
+        let id = format!("{}{}", PROJECT_ID_PREFIX, project.id);
+        projects.append(&MenuItem::with_id(app, id, label, true, None::<&str>)?)?;
+    }
+
+    let stop_all = MenuItem::with_id(app, STOP_ALL_ID, "Stop All Sessions", state.running > 0, None::<&str>)?;
+    let background_mode = CheckMenuItem::with_id(
+        app,
+        BACKGROUND_MODE_ID,
+        "Keep Running When Closed",
+        true,
+        state.background_mode,
+        None::<&str>,
+    )?;
+    let quit = MenuItem::with_id(app, QUIT_ID, "Quit AtelierCode", true, None::<&str>)?;
+
+    Menu::with_items(
+        app,
+        &[
+            &status,
+            &PredefinedMenuItem::separator(app)?,
+            &show,
+            &projects,
+            &stop_all,
+            &PredefinedMenuItem::separator(app)?,
+            &background_mode,
+            &quit,
+        ],
+    )
+}
+
+fn handle_menu_event(app: &AppHandle, event: MenuEvent) {
+    match event.id().as_ref() {
+        SHOW_ID => show_main_window(app),
+        STOP_ALL_ID => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                let db = app.state::<Database>();
+                let agent_manager = app.state::<AgentManager>();
+                if let Err(e) = crate::commands::stop_running_sessions(&db, &agent_manager).await {
+                    log::error!("Failed to stop sessions from the tray: {}", e);
+                }
+            });
+        }
+        BACKGROUND_MODE_ID => {
+            let settings = app.state::<AppSettingsManager>();
+            if let Err(e) = settings.update(|s| s.background_mode = !s.background_mode) {
+                log::error!("Failed to save background mode: {}", e);
+            }
+        }
+        QUIT_ID => app.exit(0),
+        id => {
+            if let Some(project_id) = id.strip_prefix(PROJECT_ID_PREFIX) {
+                if crate::windows::focus_project_window(app, project_id) {
+                    return;
+                }
+                show_main_window(app);
+                if let Err(e) = app.emit_to(MAIN_WINDOW, OPEN_PROJECT_EVENT, project_id) {
+                    log::warn!("Failed to emit {}: {}", OPEN_PROJECT_EVENT, e);
+                }
+            }
+        }
+    }
+}
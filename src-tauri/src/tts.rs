@@ -0,0 +1,203 @@
+// Text-to-Speech
+// Reads text aloud with the operating system's voices
+
+use anyhow::{Context, Result};
+use std::io::Write;
+use std::process::{Child, Command, Stdio};
+
+use crate::cli_command;
+use std::sync::Mutex;
+
+/// Default speaking rate in words per minute
+pub const DEFAULT_RATE_WPM: u32 = 180;
+
+/// Longest text read when summarizing a reply
+pub const SUMMARY_MAX_CHARS: usize = 600;
+
+/// Turn a Markdown reply into plain text worth hearing
+///
+/// Code blocks are dropped, links keep their text and emphasis markers are
+/// removed. With `max_chars`, the text is cut at the last sentence that fits.
+pub fn speech_text(markdown: &str, max_chars: Option<usize>) -> String {
+    let mut prose = Vec::new();
+    let mut in_code = false;
+
+    for line in markdown.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            continue;
+        }
+        let line = line.trim_start_matches(|c: char| c == '#' || c == '>' || c.is_whitespace());
+        let line = line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")).unwrap_or(line);
+        if !line.trim().is_empty() {
+            prose.push(strip_inline_markdown(line));
+        }
+    }
+
+    let text = prose.join(" ").split_whitespace().collect::<Vec<_>>().join(" ");
+    match max_chars {
+        Some(max) if text.chars().count() > max => truncate_at_sentence(&text, max),
+        _ => text,
+    }
+}
+
+/// Remove inline code, emphasis and link targets from one line
+fn strip_inline_markdown(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '*' | '_' | '`' | '~' => {}
+            // [text](url) keeps only the text
+            ']' if chars.peek() == Some(&'(') => {
+                for c in chars.by_ref() {
+                    if c == ')' {
+                        break;
+                    }
+                }
+            }
+            '[' => {}
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn truncate_at_sentence(text: &str, max_chars: usize) -> String {
+    let cut: String = text.chars().take(max_chars).collect();
+    match cut.rfind(['.', '!', '?']) {
+        Some(end) if end > 0 => cut[..=end].to_string(),
+        _ => format!("{}…", cut.trim_end()),
+    }
+}
+
+/// Build the platform's speech command; the text is written to its stdin
+fn speech_command(voice: Option<&str>, rate_wpm: u32) -> Result<Command> {
+    if cfg!(target_os = "macos") {
+        let mut cmd = Command::new("say");
+        cmd.args(["-r", &rate_wpm.to_string()]);
+        if let Some(voice) = voice {
+            cmd.args(["-v", voice]);
+        }
+        return Ok(cmd);
+    }
+
+    if cfg!(target_os = "windows") {
+        // SpeechSynthesizer rates run from -10 to 10, with 0 at roughly 180 wpm
+        let rate = ((rate_wpm as i32 - 180) / 20).clamp(-10, 10);
+        let select_voice = voice
+            .map(|v| format!("$s.SelectVoice('{}');", v.replace('\'', "''")))
+            .unwrap_or_default();
+        let script = format!(
+            "Add-Type -AssemblyName System.Speech; \
+             $s = New-Object System.Speech.Synthesis.SpeechSynthesizer; \
+             $s.Rate = {}; {} $s.Speak([Console]::In.ReadToEnd())",
+            rate, select_voice
+        );
+        // Built through cli_command so no console window opens
+        return Ok(cli_command::std_command(
+            "powershell",
+            ["-NoProfile", "-NonInteractive", "-Command", &script],
+        ));
+    }
+
+    let engine = ["espeak-ng", "espeak"]
+        .into_iter()
+        .find(|engine| which::which(engine).is_ok())
+        .context("No speech engine found; install espeak-ng")?;
+    let mut cmd = Command::new(engine);
+    cmd.args(["--stdin", "-s", &rate_wpm.to_string()]);
+    if let Some(voice) = voice {
+        cmd.args(["-v", voice]);
+    }
+    Ok(cmd)
+}
+
+/// Plays one utterance at a time; starting a new one stops the previous
+pub struct TtsManager {
+    current: Mutex<Option<Child>>,
+}
+
+impl TtsManager {
+    /// Create a new TtsManager
+    pub fn new() -> Self {
+        Self {
+            current: Mutex::new(None),
+        }
+    }
+
+    /// Start speaking without waiting for it to finish
+    pub fn speak(&self, text: &str, voice: Option<&str>, rate_wpm: u32) -> Result<()> {
+        let mut current = self.current.lock().unwrap();
+        if let Some(mut child) = current.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+
+        let mut child = speech_command(voice, rate_wpm)?
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .context("Failed to start speech engine")?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(text.as_bytes())
+                .context("Failed to send text to speech engine")?;
+        }
+
+        *current = Some(child);
+        Ok(())
+    }
+
+    /// Stop speaking; returns false if nothing was playing
+    pub fn stop(&self) -> bool {
+        match self.current.lock().unwrap().take() {
+            Some(mut child) => {
+                let playing = matches!(child.try_wait(), Ok(None));
+                let _ = child.kill();
+                let _ = child.wait();
+                playing
+            }
+            None => false,
+        }
+    }
+
+    /// Whether an utterance is still playing
+    pub fn is_speaking(&self) -> bool {
+        self.current
+            .lock()
+            .unwrap()
+            .as_mut()
+            .is_some_and(|child| matches!(child.try_wait(), Ok(None)))
+    }
+}
+
+impl Default for TtsManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_speech_text_strips_markdown() {
+        let reply = "## Done\n\nI fixed **the bug** in [`parser.rs`](src/parser.rs).\n\n```rust\nfn main() {}\n```\n\n- Added a test";
+        assert_eq!(speech_text(reply, None), "Done I fixed the bug in parser.rs. Added a test");
+    }
+
+    #[test]
+    fn test_speech_text_truncates_at_sentence() {
+        let reply = "First sentence. Second sentence is longer. Third.";
+        assert_eq!(speech_text(reply, Some(30)), "First sentence.");
+        assert_eq!(speech_text("no punctuation here at all", Some(10)), "no punctua…");
+    }
+}
@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
 
+use crate::ci::CiConfig;
+use crate::container_env::ContainerEnvironment;
+
 /// Input for creating a new project
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateProjectInput {
@@ -18,6 +21,8 @@ pub struct UpdateProjectInput {
     pub agent_type: Option<String>,
     pub status: Option<String>,
     pub prd_content: Option<String>,
+    /// Who wrote `prd_content`, "user" (the default) or "ai"; kept in the PRD history
+    pub prd_author: Option<String>,
     pub settings: Option<String>,
     /// Project icon (emoji, icon name, or path to custom icon)
     pub icon: Option<String>,
@@ -124,6 +129,10 @@ pub struct ProjectAnalysisResult {
     pub detected_frameworks: Vec<String>,
     pub file_count: usize,
     pub has_git: bool,
+    /// Dev container and compose files, when the project has them
+    pub container: Option<ContainerEnvironment>,
+    /// GitHub Actions and GitLab CI configs
+    pub ci: Vec<CiConfig>,
 }
 
 /// AI-generated project details (for modal preview)
@@ -132,3 +141,73 @@ pub struct AIProjectDetails {
     pub name: String,
     pub description: String,
 }
+
+/// Time logged against one task on one day
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct TimesheetRow {
+    /// Local date (YYYY-MM-DD)
+    pub day: String,
+    pub task_id: String,
+    pub task_title: String,
+    pub hours: f64,
+}
+
+/// Filters for selecting a project's tasks
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TaskFilters {
+    pub status: Option<String>,
+    pub label_id: Option<String>,
+    pub priority: Option<String>,
+    pub search: Option<String>,
+}
+
+/// A page of chat messages in chronological order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessagePage {
+    pub messages: Vec<crate::models::ChatMessage>,
+    /// Whether older messages exist before the first message in this page
+    pub has_more: bool,
+}
+
+/// Options for exporting a chat conversation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChatExportOptions {
+    /// Title for the document; defaults to the tab label or project name
+    pub title: Option<String>,
+    pub include_timestamps: bool,
+    /// Put tool use and tool results in collapsible `<details>` sections
+    pub collapse_tool_use: bool,
+}
+
+impl Default for ChatExportOptions {
+    fn default() -> Self {
+        Self {
+            title: None,
+            include_timestamps: true,
+            collapse_tool_use: true,
+        }
+    }
+}
+
+/// Estimated context usage of a chat session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionTokenEstimate {
+    pub session_id: String,
+    pub message_count: usize,
+    pub estimated_tokens: usize,
+    pub context_window: usize,
+    /// estimated_tokens / context_window
+    pub usage_ratio: f64,
+    /// True once usage passes the point where summarizing is advisable
+    pub should_summarize: bool,
+}
+
+/// Result of comparing agents on the same prompt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComparisonReport {
+    pub comparison: crate::models::AgentComparison,
+    pub runs: Vec<crate::models::ComparisonRun>,
+    /// Markdown report with each agent's diff
+    pub report: String,
+}
@@ -0,0 +1,167 @@
+// Auto Update
+// Checks the release feed of the selected channel and installs signed updates
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, Url};
+use tauri_plugin_updater::{Update, UpdaterExt};
+
+use crate::app_settings::AppSettingsManager;
+
+/// Event emitted with an [`UpdateInfo`] when a newer version is found
+pub const UPDATE_AVAILABLE_EVENT: &str = "update-available";
+/// Event emitted with `{ downloaded, total }` bytes while an update downloads
+pub const UPDATE_PROGRESS_EVENT: &str = "update-download-progress";
+
+/// Wait before the first background check, so startup isn't slowed down
+const FIRST_CHECK_DELAY: Duration = Duration::from_secs(30);
+const CHECK_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Release feed to follow
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    Beta,
+}
+
+impl UpdateChannel {
+    /// Update manifest for the channel; betas are published under a rolling "beta" release
+    fn endpoint(self) -> &'static str {
+        match self {
+            Self::Stable => "https://github.com/jariahh/ateliercode/releases/latest/download/latest.json",
+            Self::Beta => "https://github.com/jariahh/ateliercode/releases/download/beta/latest.json",
+        }
+    }
+}
+
+/// A newer version than the one running
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateInfo {
+    pub version: String,
+    pub current_version: String,
+    pub channel: UpdateChannel,
+    /// Release notes
+    pub notes: Option<String>,
+    /// Publish date (RFC 3339)
+    pub date: Option<String>,
+}
+
+/// The update found by the last check, kept for `install_update`
+#[derive(Default)]
+pub struct PendingUpdate(Mutex<Option<Update>>);
+
+/// Whether this build was configured with an update signing key
+fn updates_configured(app: &AppHandle) -> bool {
+    app.config()
+        .plugins
+        .0
+        .get("updater")
+        .and_then(|updater| updater.get("pubkey"))
+        .and_then(|key| key.as_str())
+        .is_some_and(|key| !key.trim().is_empty())
+}
+
+/// Check the selected channel for a newer version, emitting an event when there is one
+pub async fn check(app: &AppHandle) -> Result<Option<UpdateInfo>, String> {
+    if !updates_configured(app) {
+        return Err("This build has no update signing key, so updates are disabled".to_string());
+    }
+
+    let channel = app.state::<AppSettingsManager>().get().update_channel;
+    let endpoint = Url::parse(channel.endpoint()).map_err(|e| format!("Invalid update endpoint: {}", e))?;
+    let update = app
+        .updater_builder()
+        .endpoints(vec![endpoint])
+        .and_then(|builder| builder.build())
+        .map_err(|e| format!("Failed to set up updater: {}", e))?
+        .check()
+        .await
+        .map_err(|e| format!("Failed to check for updates: {}", e))?;
+
+    let info = update.as_ref().map(|update| UpdateInfo {
+        version: update.version.clone(),
+        current_version: update.current_version.clone(),
+        channel,
+        notes: update.body.clone(),
+        date: update.date.map(|date| date.to_string()),
+    });
+    *app.state::<PendingUpdate>().0.lock().unwrap() = update;
+
+    if let Some(info) = &info {
+        log::info!("Update {} available on the {:?} channel", info.version, channel);
+        if let Err(e) = app.emit(UPDATE_AVAILABLE_EVENT, info) {
+            log::warn!("Failed to emit {}: {}", UPDATE_AVAILABLE_EVENT, e);
+        }
+    }
+    Ok(info)
+}
+
+/// Check for updates shortly after startup and then periodically
+pub fn spawn_background_checks(app: AppHandle) {
+    if !updates_configured(&app) {
+        log::info!("No update signing key configured; skipping update checks");
+        return;
+    }
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(FIRST_CHECK_DELAY).await;
+        loop {
+            if let Err(e) = check(&app).await {
+                log::warn!("Background update check failed: {}", e);
+            }
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Check the selected update channel for a newer version
+///
+/// Emits `update-available` when one is found.
+#[tauri::command]
+pub async fn check_for_updates(app: AppHandle) -> Result<Option<UpdateInfo>, String> {
+    log::info!("Checking for updates");
+    check(&app).await
+}
+
+/// Download and install the update found by the last check, then restart
+#[tauri::command]
+pub async fn install_update(app: AppHandle) -> Result<(), String> {
+    let pending = app.state::<PendingUpdate>().0.lock().unwrap().take();
+    let update = match pending {
+        Some(update) => update,
+        None => {
+            check(&app).await?;
+            app.state::<PendingUpdate>()
+                .0
+                .lock()
+                .unwrap()
+                .take()
+                .ok_or_else(|| "AtelierCode is up to date".to_string())?
+        }
+    };
+
+    log::info!("Installing update {}", update.version);
+    let mut downloaded = 0;
+    update
+        .download_and_install(
+            |chunk, total| {
+                downloaded += chunk;
+                let _ = app.emit(
+                    UPDATE_PROGRESS_EVENT,
+                    serde_json::json!({ "downloaded": downloaded, "total": total }),
+                );
+            },
+            || log::info!("Update downloaded"),
+        )
+        .await
+        .map_err(|e| format!("Failed to install update: {}", e))?;
+
+    log::info!("Update installed, restarting");
+    app.restart();
+}
@@ -0,0 +1,233 @@
+// Native Whisper
+// In-process transcription with whisper.cpp (whisper-rs), behind the `native-whisper` feature
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+use crate::commands_whisper::TranscriptionResult;
+
+/// Sample rate whisper.cpp expects
+#[cfg_attr(not(feature = "native-whisper"), allow(dead_code))]
+const SAMPLE_RATE: f64 = 16000.0;
+/// Where ggml model files are downloaded from
+const MODEL_BASE_URL: &str = "https://huggingface.co/ggerganov/whisper.cpp/resolve/main";
+
+/// Whether this build can transcribe natively
+pub fn is_available() -> bool {
+    cfg!(feature = "native-whisper")
+}
+
+/// Local path of a ggml model, e.g. `<app data>/whisper-models/ggml-base.bin`
+pub fn model_path(app_data_dir: &Path, model: &str) -> PathBuf {
+    app_data_dir
+        .join("whisper-models")
+        .join(format!("ggml-{}.bin", model))
+}
+
+/// Download a ggml model if it isn't present yet
+pub async fn download_model(app_data_dir: &Path, model: &str) -> Result<PathBuf> {
+    let path = model_path(app_data_dir, model);
+    if path.is_file() {
+        return Ok(path);
+    }
+
+    let url = format!("{}/ggml-{}.bin", MODEL_BASE_URL, model);
+    log::info!("Downloading whisper.cpp model from {}", url);
+
+    let response = reqwest::get(&url)
+        .await
+        .context("Failed to download model")?
+        .error_for_status()
+        .with_context(|| format!("Unknown Whisper model '{}'", model))?;
+    let bytes = response.bytes().await.context("Failed to download model")?;
+
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    // Write to a temp name first so an interrupted download isn't mistaken for a model
+    let partial = path.with_extension("bin.part");
+    tokio::fs::write(&partial, &bytes).await?;
+    tokio::fs::rename(&partial, &path).await?;
+
+    Ok(path)
+}
+
+/// Read a 16-bit PCM WAV file as mono f32 samples
+#[cfg_attr(not(feature = "native-whisper"), allow(dead_code))]
+pub fn read_wav_samples(bytes: &[u8]) -> Result<Vec<f32>> {
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        anyhow::bail!("Not a WAV file");
+    }
+
+    // Walk the chunks to find "data"; FFmpeg may add a LIST chunk before it
+    let mut offset = 12;
+    while offset + 8 <= bytes.len() {
+        let id = &bytes[offset..offset + 4];
+        let size = u32::from_le_bytes([
+            bytes[offset + 4],
+            bytes[offset + 5],
+            bytes[offset + 6],
+            bytes[offset + 7],
+        ]) as usize;
+        let body = offset + 8;
+
+        if id == b"data" {
+            let end = body.saturating_add(size).min(bytes.len());
+            return Ok(bytes[body..end]
+                .chunks_exact(2)
+                .map(|s| i16::from_le_bytes([s[0], s[1]]) as f32 / 32768.0)
+                .collect());
+        }
+        offset = body.saturating_add(size).saturating_add(size % 2);
+    }
+
+    anyhow::bail!("WAV file has no data chunk")
+}
+
+#[cfg(feature = "native-whisper")]
+mod engine {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex, OnceLock};
+    use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
+
+    /// Loaded models, kept so repeated dictations don't reload from disk
+    fn contexts() -> &'static Mutex<HashMap<PathBuf, Arc<WhisperContext>>> {
+        static CONTEXTS: OnceLock<Mutex<HashMap<PathBuf, Arc<WhisperContext>>>> = OnceLock::new();
+        CONTEXTS.get_or_init(|| Mutex::new(HashMap::new()))
+    }
+
+    fn load(model_path: &Path) -> Result<Arc<WhisperContext>> {
+        let mut contexts = contexts().lock().unwrap();
+        if let Some(ctx) = contexts.get(model_path) {
+            return Ok(ctx.clone());
+        }
+
+        let path = model_path.to_str().context("Model path is not valid UTF-8")?;
+        let ctx = WhisperContext::new_with_params(path, WhisperContextParameters::default())
+            .map(Arc::new)
+            .map_err(|e| anyhow::anyhow!("Failed to load model {:?}: {}", model_path, e))?;
+        contexts.insert(model_path.to_path_buf(), ctx.clone());
+        Ok(ctx)
+    }
+
+    pub fn transcribe(
+        model_path: &Path,
+        samples: &[f32],
+        language: Option<&str>,
+        translate: bool,
+    ) -> Result<TranscriptionResult> {
+        let ctx = load(model_path)?;
+        let mut state = ctx
+            .create_state()
+            .map_err(|e| anyhow::anyhow!("Failed to create Whisper state: {}", e))?;
+        let threads = std::thread::available_parallelism()
+            .map(|n| n.get().min(8))
+            .unwrap_or(4);
+
+        let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+        params.set_language(Some(language.unwrap_or("auto")));
+        params.set_translate(translate);
+        params.set_print_progress(false);
+        params.set_print_realtime(false);
+        params.set_print_special(false);
+        params.set_print_timestamps(false);
+        params.set_n_threads(threads as i32);
+
+        state
+            .full(params, samples)
+            .map_err(|e| anyhow::anyhow!("Whisper transcription failed: {}", e))?;
+
+        let segments = state
+            .full_n_segments()
+            .map_err(|e| anyhow::anyhow!("Failed to read segments: {}", e))?;
+        let mut text = String::new();
+        for i in 0..segments {
+            if let Ok(segment) = state.full_get_segment_text_lossy(i) {
+                text.push_str(&segment);
+            }
+        }
+
+        let lang_id = state.full_lang_id_from_state().ok();
+        // The mel of the full pass is still on the state, so detection is cheap
+        let language_confidence = match (language, lang_id) {
+            (None, Some(id)) => state
+                .lang_detect(0, threads)
+                .ok()
+                .and_then(|(_, probs)| probs.get(id as usize).copied())
+                .map(f64::from),
+            _ => None,
+        };
+
+        Ok(TranscriptionResult {
+            text: text.trim().to_string(),
+            language: lang_id.and_then(whisper_rs::get_lang_str).map(str::to_string),
+            language_confidence,
+            duration: Some(samples.len() as f64 / SAMPLE_RATE),
+        })
+    }
+}
+
+/// Transcribe 16kHz mono WAV bytes with a ggml model
+///
+/// Runs on a blocking thread. `language` is an ISO 639-1 code, or `None` to
+/// auto-detect; `translate` outputs English whatever the spoken language.
+#[cfg(feature = "native-whisper")]
+pub async fn transcribe_wav(
+    model_path: &Path,
+    wav: Vec<u8>,
+    language: Option<String>,
+    translate: bool,
+) -> Result<TranscriptionResult> {
+    let model_path = model_path.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let samples = read_wav_samples(&wav)?;
+        engine::transcribe(&model_path, &samples, language.as_deref(), translate)
+    })
+    .await
+    .context("Whisper task panicked")?
+}
+
+/// Transcribe 16kHz mono WAV bytes with a ggml model
+#[cfg(not(feature = "native-whisper"))]
+pub async fn transcribe_wav(
+    _model_path: &Path,
+    _wav: Vec<u8>,
+    _language: Option<String>,
+    _translate: bool,
+) -> Result<TranscriptionResult> {
+    anyhow::bail!("This build was compiled without the native-whisper feature")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wav(chunks: &[(&[u8; 4], &[u8])]) -> Vec<u8> {
+        let mut out = b"RIFF\0\0\0\0WAVE".to_vec();
+        for (id, body) in chunks {
+            out.extend_from_slice(*id);
+            out.extend_from_slice(&(body.len() as u32).to_le_bytes());
+            out.extend_from_slice(body);
+            if body.len() % 2 == 1 {
+                out.push(0);
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn test_read_wav_samples() {
+        let samples = [0i16, 16384, -32768];
+        let data: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+        let bytes = wav(&[(b"fmt ", &[0u8; 16]), (b"LIST", &[0u8; 5]), (b"data", &data)]);
+
+        assert_eq!(read_wav_samples(&bytes).unwrap(), vec![0.0, 0.5, -1.0]);
+    }
+
+    #[test]
+    fn test_read_wav_samples_rejects_other_formats() {
+        assert!(read_wav_samples(b"\x1a\x45\xdf\xa3webm").is_err());
+        assert!(read_wav_samples(&wav(&[(b"fmt ", &[0u8; 16])])).is_err());
+    }
+}
@@ -0,0 +1,245 @@
+// Whisper Stream Manager
+// Keeps a Whisper worker loaded per dictation so partial transcripts arrive while recording
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+
+use crate::commands_whisper::TranscriptionResult;
+
+/// Event emitted with each partial (and the final) transcript of a stream
+pub const PARTIAL_EVENT: &str = "transcription-partial";
+
+/// Bytes per second of the 16kHz mono 16-bit WAV handed to Whisper
+const WAV_BYTES_PER_SEC: f64 = 32000.0;
+/// Size of the WAV header written by FFmpeg
+const WAV_HEADER_BYTES: u64 = 44;
+
+/// Loads the model once, then transcribes one WAV path per stdin line and
+/// answers with one JSON line; takes the model, language ("" to auto-detect)
+/// and task as arguments
+const WORKER_SCRIPT: &str = r#"
+import json
+import sys
+import whisper
+
+model = whisper.load_model(sys.argv[1])
+language = sys.argv[2] or None
+task = sys.argv[3]
+print(json.dumps({"ready": True}), flush=True)
+
+for line in sys.stdin:
+    path = line.strip()
+    if not path:
+        continue
+    try:
+        result = model.transcribe(path, fp16=False, language=language, task=task)
+        output = {"text": result["text"].strip(), "language": result.get("language")}
+    except Exception as e:
+        output = {"error": str(e)}
+    print(json.dumps(output), flush=True)
+"#;
+
+#[derive(Debug, Deserialize)]
+struct WorkerReply {
+    #[serde(default)]
+    text: String,
+    language: Option<String>,
+    error: Option<String>,
+}
+
+/// A running Python Whisper process
+pub struct WhisperWorker {
+    _child: Child,
+    stdin: ChildStdin,
+    stdout: Lines<BufReader<ChildStdout>>,
+}
+
+impl WhisperWorker {
+    /// Spawn a worker and wait until the model has loaded
+    pub async fn spawn(model: &str, language: Option<&str>, translate: bool) -> Result<Self> {
+        let task = if translate { "translate" } else { "transcribe" };
+        let mut child = Command::new("python")
+            .args(["-u", "-c", WORKER_SCRIPT, model, language.unwrap_or(""), task])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+            .context("Failed to start Whisper")?;
+
+        let stdin = child.stdin.take().context("Whisper stdin unavailable")?;
+        let stdout = child.stdout.take().context("Whisper stdout unavailable")?;
+        let mut worker = Self {
+            _child: child,
+            stdin,
+            stdout: BufReader::new(stdout).lines(),
+        };
+
+        match worker.stdout.next_line().await? {
+            Some(line) if line.contains("\"ready\"") => Ok(worker),
+            _ => anyhow::bail!("Whisper exited while loading model '{}'", model),
+        }
+    }
+
+    /// Transcribe a 16kHz mono WAV file
+    async fn transcribe(&mut self, wav_path: &Path) -> Result<(String, Option<String>)> {
+        self.stdin
+            .write_all(format!("{}\n", wav_path.to_string_lossy()).as_bytes())
+            .await
+            .context("Failed to send audio to Whisper")?;
+        self.stdin.flush().await?;
+
+        let line = self
+            .stdout
+            .next_line()
+            .await?
+            .context("Whisper exited during transcription")?;
+        let reply: WorkerReply = serde_json::from_str(&line)
+            .with_context(|| format!("Failed to parse Whisper output: {}", line))?;
+
+        match reply.error {
+            Some(error) => anyhow::bail!("Whisper transcription failed: {}", error),
+            None => Ok((reply.text, reply.language)),
+        }
+    }
+}
+
+/// One dictation in progress
+///
+/// Chunks must be consecutive pieces of a single recording (e.g. MediaRecorder
+/// timeslices), so the buffer is always a decodable file. Each pass transcribes
+/// the whole buffer, which keeps earlier words stable as context grows.
+pub struct WhisperStream {
+    temp_dir: PathBuf,
+    audio: Mutex<Vec<u8>>,
+    worker: Arc<tokio::sync::Mutex<WhisperWorker>>,
+}
+
+impl WhisperStream {
+    pub fn new(temp_dir: PathBuf, worker: WhisperWorker) -> Self {
+        Self {
+            temp_dir,
+            audio: Mutex::new(Vec::new()),
+            worker: Arc::new(tokio::sync::Mutex::new(worker)),
+        }
+    }
+
+    /// Append a chunk of recorded audio
+    pub fn push(&self, chunk: &[u8]) {
+        self.audio.lock().unwrap().extend_from_slice(chunk);
+    }
+
+    /// Claim the worker for a partial pass; `None` while a pass is running
+    pub fn try_claim(&self) -> Option<tokio::sync::OwnedMutexGuard<WhisperWorker>> {
+        self.worker.clone().try_lock_owned().ok()
+    }
+
+    /// Wait for any running pass, then claim the worker
+    pub async fn claim(&self) -> tokio::sync::OwnedMutexGuard<WhisperWorker> {
+        self.worker.clone().lock_owned().await
+    }
+
+    /// Transcribe everything recorded so far
+    pub async fn transcribe(&self, worker: &mut WhisperWorker) -> Result<TranscriptionResult> {
+        let audio = self.audio.lock().unwrap().clone();
+        if audio.is_empty() {
+            return Ok(TranscriptionResult {
+                text: String::new(),
+                language: None,
+                language_confidence: None,
+                duration: None,
+            });
+        }
+
+        let pass_id = uuid::Uuid::new_v4();
+        let input_path = self.temp_dir.join(format!("whisper_stream_{}.webm", pass_id));
+        let wav_path = self.temp_dir.join(format!("whisper_stream_{}.wav", pass_id));
+
+        let result = async {
+            tokio::fs::write(&input_path, &audio)
+                .await
+                .context("Failed to write temp audio file")?;
+            convert_to_wav(&input_path, &wav_path).await?;
+
+            let duration = tokio::fs::metadata(&wav_path)
+                .await
+                .ok()
+                .filter(|m| m.len() > WAV_HEADER_BYTES)
+                .map(|m| (m.len() - WAV_HEADER_BYTES) as f64 / WAV_BYTES_PER_SEC);
+
+            let (text, language) = worker.transcribe(&wav_path).await?;
+            Ok(TranscriptionResult {
+                text,
+                language,
+                language_confidence: None,
+                duration,
+            })
+        }
+        .await;
+
+        let _ = tokio::fs::remove_file(&input_path).await;
+        let _ = tokio::fs::remove_file(&wav_path).await;
+        result
+    }
+}
+
+/// Convert recorded audio to the 16kHz mono WAV Whisper expects
+async fn convert_to_wav(input_path: &Path, wav_path: &Path) -> Result<()> {
+    let output = Command::new("ffmpeg")
+        .arg("-y")
+        .arg("-i")
+        .arg(input_path)
+        .args(["-ar", "16000", "-ac", "1", "-c:a", "pcm_s16le"])
+        .arg(wav_path)
+        .output()
+        .await
+        .context("Failed to run FFmpeg")?;
+
+    if !output.status.success() {
+        anyhow::bail!("FFmpeg conversion failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(())
+}
+
+/// Tracks open transcription streams by id
+pub struct WhisperStreamManager {
+    streams: Arc<Mutex<HashMap<String, Arc<WhisperStream>>>>,
+}
+
+impl WhisperStreamManager {
+    /// Create a new WhisperStreamManager
+    pub fn new() -> Self {
+        Self {
+            streams: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Register a stream and return its id
+    pub fn insert(&self, stream: WhisperStream) -> String {
+        let stream_id = uuid::Uuid::new_v4().to_string();
+        self.streams.lock().unwrap().insert(stream_id.clone(), Arc::new(stream));
+        stream_id
+    }
+
+    /// Look up an open stream
+    pub fn get(&self, stream_id: &str) -> Option<Arc<WhisperStream>> {
+        self.streams.lock().unwrap().get(stream_id).cloned()
+    }
+
+    /// Close a stream; its worker stops once any running pass finishes
+    pub fn remove(&self, stream_id: &str) -> Option<Arc<WhisperStream>> {
+        self.streams.lock().unwrap().remove(stream_id)
+    }
+}
+
+impl Default for WhisperStreamManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
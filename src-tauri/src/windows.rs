@@ -0,0 +1,206 @@
+// Project Windows
+// Opens projects in their own windows and tracks what each window holds open,
+// so watchers started by a window are released when it closes
+
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+
+use crate::dev_server::DevServerManager;
+use crate::file_watcher::FileWatcherManager;
+use crate::lsp::LspManager;
+use crate::open_files::OpenFileWatcher;
+use crate::plugin::{PluginManager, WatchHandle};
+use crate::session_events::SessionEventHub;
+
+/// Label of the window created from tauri.conf.json
+pub const MAIN_WINDOW: &str = "main";
+const PROJECT_WINDOW_PREFIX: &str = "project-";
+
+/// What one window has open
+#[derive(Default)]
+struct WindowState {
+    /// Set for project windows
+    project_id: Option<String>,
+    /// Session watches started by this window
+    session_watches: Vec<SessionWatch>,
+}
+
+/// A session watch and the chat tab its updates go to
+struct SessionWatch {
+    tab_id: String,
+    handle: WatchHandle,
+}
+
+/// Per-window state, keyed by window label
+#[derive(Default)]
+pub struct WindowRegistry(Mutex<HashMap<String, WindowState>>);
+
+impl WindowRegistry {
+    /// Remember a session watch so it's stopped when the window closes
+    pub fn track_session_watch(&self, label: &str, tab_id: &str, handle: WatchHandle) {
+        let mut windows = self.0.lock().unwrap();
+        let watch = SessionWatch { tab_id: tab_id.to_string(), handle };
+        windows.entry(label.to_string()).or_default().session_watches.push(watch);
+    }
+
+    /// Id of the watch a window already has on a tab's session; a reloaded
+    /// webview picks it up again instead of starting a second watcher
+    pub fn find_session_watch(&self, label: &str, tab_id: &str, cli_session_id: &str) -> Option<String> {
+        let windows = self.0.lock().unwrap();
+        windows
+            .get(label)?
+            .session_watches
+            .iter()
+            .find(|w| w.tab_id == tab_id && w.handle.cli_session_id == cli_session_id)
+            .map(|w| w.handle.id.clone())
+    }
+
+    /// Forget a watch, returning the tab it fed
+    pub fn untrack_session_watch(&self, label: &str, watch_id: &str) -> Option<String> {
+        let mut windows = self.0.lock().unwrap();
+        let watches = &mut windows.get_mut(label)?.session_watches;
+        let index = watches.iter().position(|w| w.handle.id == watch_id)?;
+        Some(watches.remove(index).tab_id)
+    }
+
+    /// Session watches whose tab isn't in `tab_ids`, with the window holding each
+    pub fn watches_without_tab(&self, tab_ids: &HashSet<String>) -> Vec<(String, WatchHandle)> {
+        let windows = self.0.lock().unwrap();
+        windows
+            .iter()
+            .flat_map(|(label, state)| {
+                state
+                    .session_watches
+                    .iter()
+                    .filter(|w| !tab_ids.contains(&w.tab_id))
+                    .map(move |w| (label.clone(), w.handle.clone()))
+            })
+            .collect()
+    }
+}
+
+/// Label of a project's window; labels may only hold alphanumerics, `-` and `_`
+fn project_window_label(project_id: &str) -> String {
+    let id: String = project_id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+        .collect();
+    format!("{}{}", PROJECT_WINDOW_PREFIX, id)
+}
+
+/// Release everything a closed window held: its file watchers, dev servers,
+/// open-file watches, session watches and buffered session updates
+pub fn window_destroyed(app: &AppHandle, label: &str) {
+    let state = app.state::<WindowRegistry>().0.lock().unwrap().remove(label);
+    app.state::<FileWatcherManager>().release_holder(label);
+    app.state::<DevServerManager>().stop_for_holder(label);
+    app.state::<LspManager>().release_holder(label);
+    app.state::<OpenFileWatcher>().release_window(label);
+    app.state::<SessionEventHub>().window_destroyed(label);
+
+    let Some(state) = state else {
+        return;
+    };
+    if state.session_watches.is_empty() {
+        return;
+    }
+    log::info!("Stopping {} session watches of closed window {}", state.session_watches.len(), label);
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let plugin_manager = app.state::<PluginManager>();
+        for SessionWatch { handle, .. } in state.session_watches {
+            let Some(plugin) = plugin_manager.get(&handle.plugin_name) else {
+                continue;
+            };
+            let cli_session_id = handle.cli_session_id.clone();
+            if let Err(e) = plugin.stop_watching_session(handle).await {
+                log::warn!("Failed to stop watching session {}: {}", cli_session_id, e);
+            }
+        }
+    });
+}
+
+/// Focus the window of a project if it has one
+pub fn focus_project_window(app: &AppHandle, project_id: &str) -> bool {
+    match app.get_webview_window(&project_window_label(project_id)) {
+        Some(window) => {
+            let _ = window.unminimize();
+            let _ = window.set_focus();
+            true
+        }
+        None => false,
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+use tauri::State;
+
+/// The window a command was called from
+#[derive(Debug, Clone, Serialize)]
+pub struct WindowContext {
+    pub label: String,
+    /// Set when the window was opened for a single project
+    pub project_id: Option<String>,
+}
+
+/// Open a project in its own window, or focus it if it already has one
+///
+/// Returns the window label.
+#[tauri::command]
+pub async fn open_project_window(
+    app: AppHandle,
+    db: State<'_, crate::db::Database>,
+    registry: State<'_, WindowRegistry>,
+    project_id: String,
+) -> Result<String, String> {
+    let label = project_window_label(&project_id);
+    if focus_project_window(&app, &project_id) {
+        return Ok(label);
+    }
+
+    let project = crate::commands::get_project(db, project_id.clone())
+        .await?
+        .ok_or_else(|| format!("Project not found: {}", project_id))?;
+
+    log::info!("Opening window {} for project {}", label, project.name);
+    let url = WebviewUrl::App(format!("workspace/{}", project_id).into());
+    WebviewWindowBuilder::new(&app, &label, url)
+        .title(format!("{} - AtelierCode", project.name))
+        .inner_size(1400.0, 900.0)
+        .min_inner_size(1200.0, 700.0)
+        .build()
+        .map_err(|e| format!("Failed to open project window: {}", e))?;
+
+    registry.0.lock().unwrap().entry(label.clone()).or_default().project_id = Some(project_id);
+    Ok(label)
+}
+
+/// Get the calling window's label and project
+#[tauri::command]
+pub fn get_window_context(
+    window: tauri::Window,
+    registry: State<'_, WindowRegistry>,
+) -> WindowContext {
+    let label = window.label().to_string();
+    let project_id = registry.0.lock().unwrap().get(&label).and_then(|w| w.project_id.clone());
+    WindowContext { label, project_id }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_window_label() {
+        assert_eq!(
+            project_window_label("0b6f5c1e-2f4a-4b8e-9d3c-1a2b3c4d5e6f"),
+            "project-0b6f5c1e-2f4a-4b8e-9d3c-1a2b3c4d5e6f"
+        );
+        assert_eq!(project_window_label("a/b c"), "project-a_b_c");
+    }
+}
@@ -8,6 +8,11 @@ import ThemeSelector from './components/ThemeSelector';
 import ProjectSidebar from './components/ProjectSidebar';
 import ErrorBoundary from './components/ErrorBoundary';
 import AuthDialog from './components/AuthDialog';
+import TrayNavigator from './components/TrayNavigator';
+import DeepLinkNavigator from './components/DeepLinkNavigator';
+import CommandApprovalModal from './components/modals/CommandApprovalModal';
+import JobsIndicator from './components/JobsIndicator';
+import ReadOnlyIndicator from './components/ReadOnlyIndicator';
 import { initServerConnection } from './services/serverConnection';
 import { useAuthStore } from './stores/authStore';
 import { isWeb } from './lib/platform';
@@ -80,13 +85,30 @@ function App() {
               © {currentYear} AtelierCode. All rights reserved.
             </div>
 
-            {/* Theme Selector */}
-            <ThemeSelector />
+            <div className="flex items-center gap-4">
+              {/* Shown while watching another machine's database */}
+              <ReadOnlyIndicator />
+
+              {/* Background jobs */}
+              <JobsIndicator />
+
+              {/* Theme Selector */}
+              <ThemeSelector />
+            </div>
           </div>
         </footer>
 
         {/* Auth Dialog (for web mode) */}
         <AuthDialog />
+
+        {/* Opens projects picked from the system tray */}
+        <TrayNavigator />
+
+        {/* Opens ateliercode:// links */}
+        <DeepLinkNavigator />
+
+        {/* Asks before agents run commands on a project's deny list */}
+        <CommandApprovalModal />
       </BrowserRouter>
     </ErrorBoundary>
   );
@@ -1,6 +1,8 @@
 // Activity API wrapper for type-safe command invocations
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from '../lib/invoke';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
 import type { ActivityLog } from '../types/tauri';
+import type { AgentSession } from './agentSession';
 
 /**
  * Log an activity event for a project
@@ -20,16 +22,115 @@ export async function logActivity(
 }
 
 /**
- * Get recent activities for a project
+ * Filters for getActivities and getActivityCounts
+ */
+export interface ActivityFilter {
+  eventTypes?: string[];
+  /** Unix timestamps in seconds, inclusive */
+  since?: number;
+  until?: number;
+}
+
+/**
+ * An activity with the project it belongs to
+ */
+export interface GlobalActivity extends ActivityLog {
+  project_name: string;
+  project_color: string | null;
+  project_icon: string | null;
+}
+
+/**
+ * Recent activity across all projects plus the agent sessions running now
+ */
+export interface GlobalActivityFeed {
+  activities: GlobalActivity[];
+  running_sessions: (AgentSession & { project_name: string | null; project_color: string | null })[];
+}
+
+/**
+ * Number of activities of one type in a time bucket
+ */
+export interface ActivityCount {
+  bucket_start: number; // Unix timestamp in seconds (UTC)
+  event_type: string;
+  count: number;
+}
+
+/**
+ * Get activities for a project, newest first
  * @param projectId - The project ID
- * @param limit - Maximum number of activities to fetch (default: 50, max: 100)
+ * @param limit - Maximum number of activities to fetch (default: 30, max: 100)
+ * @param filter - Event types and time range to keep
+ * @param after - The last activity of the previous page, to fetch the next one
  */
 export async function getActivities(
   projectId: string,
-  limit?: number
+  limit?: number,
+  filter?: ActivityFilter,
+  after?: Pick<ActivityLog, 'id' | 'timestamp'>
 ): Promise<ActivityLog[]> {
   return await invoke<ActivityLog[]>('get_activities', {
     projectId,
     limit,
+    eventTypes: filter?.eventTypes,
+    since: filter?.since,
+    until: filter?.until,
+    beforeTimestamp: after?.timestamp,
+    beforeId: after?.id,
+  });
+}
+
+/**
+ * Count a project's activities per event type over time
+ * @param projectId - The project ID
+ * @param bucket - Bucket size; weeks start on Monday
+ * @param filter - Event types and time range to count
+ */
+export async function getActivityCounts(
+  projectId: string,
+  bucket: 'hour' | 'day' | 'week',
+  filter?: ActivityFilter
+): Promise<ActivityCount[]> {
+  return await invoke<ActivityCount[]>('get_activity_counts', {
+    projectId,
+    bucket,
+    eventTypes: filter?.eventTypes,
+    since: filter?.since,
+    until: filter?.until,
+  });
+}
+
+/**
+ * Listen for new activity as it is logged
+ * @param projectId - The project to follow, or null for activity across all projects
+ * @param onActivity - Called with each new activity log entry
+ * @returns Function that stops listening
+ */
+export async function listenToActivity(
+  projectId: string | null,
+  onActivity: (activity: ActivityLog) => void
+): Promise<UnlistenFn> {
+  const channel = projectId ? `activity://${projectId}` : 'activity://all';
+  return await listen<ActivityLog>(channel, (e) => onActivity(e.payload));
+}
+
+/**
+ * Get recent activity across all projects, newest first, with running sessions
+ * @param limit - Maximum number of activities (default: 50, max: 200)
+ * @param filter - Event types, projects and time range to keep
+ */
+export async function getGlobalActivities(
+  limit?: number,
+  filter?: ActivityFilter & { projectIds?: string[] }
+): Promise<GlobalActivityFeed> {
+  return await invoke<GlobalActivityFeed>('get_global_activities', {
+    limit,
+    filters: {
+      event_types: filter?.eventTypes ?? null,
+      project_ids: filter?.projectIds ?? null,
+      since: filter?.since ?? null,
+      until: filter?.until ?? null,
+    },
   });
 }
@@ -0,0 +1,36 @@
+// Agent Auth API
+// Whether agent CLIs are signed in, and their login flows
+import { invoke } from '../lib/invoke';
+import { peerConnection } from '../services/peerConnection';
+import type { TerminalInfo } from './terminal';
+
+export interface AgentAuthStatus {
+  plugin_name: string;
+  logged_in: boolean;
+  method: 'cli_login' | 'api_key' | null; // How the agent is signed in, when it is
+  detail: string;
+  can_login: boolean; // Whether startAgentLogin can sign this agent in
+}
+
+/**
+ * Whether the agent's CLI is signed in or has an API key to use
+ */
+export async function getAgentAuthStatus(pluginName: string): Promise<AgentAuthStatus> {
+  if (peerConnection.isClient) {
+    return peerConnection.sendCommand<AgentAuthStatus>('get_agent_auth_status', { pluginName });
+  }
+  return await invoke<AgentAuthStatus>('get_agent_auth_status', { pluginName });
+}
+
+/**
+ * Start the agent CLI's login flow in a terminal
+ *
+ * Its output arrives through listenToTerminalOutput and input, such as a
+ * pasted code, is sent with writeTerminal.
+ */
+export async function startAgentLogin(
+  pluginName: string,
+  options: { cols?: number; rows?: number } = {}
+): Promise<TerminalInfo> {
+  return await invoke<TerminalInfo>('start_agent_login', { pluginName, ...options });
+}
@@ -0,0 +1,36 @@
+// Agent Instructions API
+// The instruction file an agent CLI reads from the project root, e.g. CLAUDE.md
+import { invoke } from '../lib/invoke';
+
+export interface AgentInstructions {
+  agent: string;
+  /** Relative to the project root, e.g. "CLAUDE.md" */
+  path: string;
+  exists: boolean;
+  content: string;
+}
+
+/**
+ * Read the instruction file for an agent; defaults to the project's own agent
+ */
+export async function getAgentInstructions(projectId: string, agent?: string): Promise<AgentInstructions> {
+  return await invoke<AgentInstructions>('get_agent_instructions', { projectId, agent });
+}
+
+/**
+ * Write the instruction file for an agent, creating it if needed
+ */
+export async function setAgentInstructions(
+  projectId: string,
+  content: string,
+  agent?: string
+): Promise<AgentInstructions> {
+  return await invoke<AgentInstructions>('set_agent_instructions', { projectId, content, agent });
+}
+
+/**
+ * Draft instructions from the project analysis and PRD without saving them
+ */
+export async function generateAgentInstructions(projectId: string, agent?: string): Promise<string> {
+  return await invoke<string>('generate_agent_instructions', { projectId, agent });
+}
@@ -1,5 +1,5 @@
 // Agent Session API functions for managing persistent AI agent sessions
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from '../lib/invoke';
 import { peerConnection } from '../services/peerConnection';
 
 // Helper to check if we should use WebRTC
@@ -31,26 +31,34 @@ export interface AgentSession {
 /**
  * Start a new persistent agent session
  * @param projectId - The ID of the project
- * @param agentType - The type of agent to start (e.g., 'Claude Code', 'Aider')
+ * @param agentType - The type of agent to start (e.g., 'claude-code', 'aider'); defaults to the project's default agent
  * @param resumeSessionId - Optional Claude session ID to resume
+ * @param executionProfile - Execution profile to restrict the agent with; defaults to the project's
+ * @param tabId - Chat tab the session runs in; a duplicated tab forks the session it was copied from
  * @returns Promise with the created agent session
  */
 export async function startAgentSession(
   projectId: string,
-  agentType: string,
-  resumeSessionId?: string
+  agentType: string | undefined,
+  resumeSessionId?: string,
+  executionProfile?: string,
+  tabId?: string
 ): Promise<AgentSession> {
   if (useWebRTC()) {
     return peerConnection.sendCommand<AgentSession>('start_agent_session', {
       projectId,
-      agentType,
+      agentType: agentType ?? null,
       resumeSessionId: resumeSessionId || null,
+      executionProfile: executionProfile || null,
+      tabId: tabId || null,
     });
   }
   return await invoke<AgentSession>('start_agent_session', {
     projectId,
-    agentType,
+    agentType: agentType ?? null,
     resumeSessionId: resumeSessionId || null,
+    executionProfile: executionProfile || null,
+    tabId: tabId || null,
   });
 }
 
@@ -147,6 +155,34 @@ export async function syncClaudeSessionId(sessionId: string): Promise<string | n
   return await invoke<string | null>('sync_claude_session_id', { sessionId });
 }
 
+/**
+ * A session whose recorded status the health watchdog corrected
+ */
+export interface StatusFix {
+  session_id: string;
+  project_id: string;
+  previous: string;
+  status: string;
+}
+
+export interface ReconcileReport {
+  /** Sessions recorded as running or starting that were checked */
+  checked: number;
+  fixed: StatusFix[];
+}
+
+/**
+ * Mark sessions recorded as running whose process is gone as stopped, without
+ * waiting for the watchdog's next check
+ * @returns Promise with what was checked and fixed
+ */
+export async function reconcileSessions(): Promise<ReconcileReport> {
+  if (useWebRTC()) {
+    return peerConnection.sendCommand<ReconcileReport>('reconcile_sessions', {});
+  }
+  return await invoke<ReconcileReport>('reconcile_sessions');
+}
+
 /**
  * Database agent session (historical record)
  */
@@ -173,3 +209,63 @@ export async function getProjectSessions(projectId: string): Promise<DbAgentSess
   }
   return await invoke<DbAgentSession[]>('get_project_sessions', { projectId });
 }
+
+/**
+ * One agent's run within a comparison
+ */
+export interface ComparisonRun {
+  comparison_id: string;
+  session_id: string;
+  agent_type: string;
+  worktree_path: string;
+  isolated: boolean;
+}
+
+/**
+ * Linked sessions and combined diff report for an agent comparison
+ */
+export interface ComparisonReport {
+  comparison: {
+    id: string;
+    project_id: string;
+    prompt: string;
+    created_at: number;
+  };
+  runs: ComparisonRun[];
+  /** Markdown report with each agent's diff */
+  report: string;
+}
+
+/**
+ * Send the same prompt to several agents, each in its own worktree, and compare their changes
+ * @param projectId - The ID of the project
+ * @param prompt - The prompt sent to every agent
+ * @param agents - Agent types to compare (at least two)
+ * @param timeoutSecs - How long to wait for the agents to finish (default 30 minutes)
+ * @returns Promise with the linked sessions and diff report
+ */
+export async function runComparison(
+  projectId: string,
+  prompt: string,
+  agents: string[],
+  timeoutSecs?: number
+): Promise<ComparisonReport> {
+  return await invoke<ComparisonReport>('run_comparison', { projectId, prompt, agents, timeoutSecs });
+}
+
+/**
+ * Rebuild the diff report for an earlier comparison
+ * @param comparisonId - The ID of the comparison
+ * @returns Promise with the linked sessions and diff report
+ */
+export async function getComparisonReport(comparisonId: string): Promise<ComparisonReport> {
+  return await invoke<ComparisonReport>('get_comparison_report', { comparisonId });
+}
+
+/**
+ * Delete a comparison and remove the worktrees its agents worked in
+ * @param comparisonId - The ID of the comparison
+ */
+export async function discardComparison(comparisonId: string): Promise<void> {
+  await invoke('discard_comparison', { comparisonId });
+}
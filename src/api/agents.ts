@@ -2,7 +2,7 @@
  * Agents API - detect and manage available AI agents
  */
 
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from '../lib/invoke';
 import { peerConnection } from '../services/peerConnection';
 
 export interface DetectedAgent {
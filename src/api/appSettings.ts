@@ -0,0 +1,124 @@
+// App Settings API
+// Settings for the desktop app as a whole; these stay local to this machine
+import { invoke } from '../lib/invoke';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+import { open } from '@tauri-apps/plugin-dialog';
+import type { UpdateChannel } from './updates';
+
+export interface ApiServerSettings {
+  enabled: boolean;
+  port: number;
+  /** Listen on the local network instead of only this machine */
+  lan_access: boolean;
+  /** Sent as `Authorization: Bearer <token>` or `?token=<token>`; generated when first enabled */
+  token: string;
+}
+
+/** Empty tokens fall back to GITHUB_TOKEN/GH_TOKEN and GITLAB_TOKEN */
+export interface CiSettings {
+  github_token: string;
+  gitlab_token: string;
+}
+
+export interface ApiServerStatus {
+  running: boolean;
+  url: string | null;
+  /** Why the server couldn't start */
+  error: string | null;
+}
+
+export interface AppSettings {
+  /** Closing the window hides it to the tray, keeping watchers and sessions alive */
+  background_mode: boolean;
+  /** Release feed checked for updates */
+  update_channel: UpdateChannel;
+  /** Local HTTP/WebSocket API for other devices */
+  api_server: ApiServerSettings;
+  /** Tokens for fetching CI runs */
+  ci: CiSettings;
+  /** Agent for new chat tabs of projects whose own agent isn't available */
+  default_agent: string | null;
+  /** Another machine's database opened read-only in place of this one */
+  read_only_database: string | null;
+}
+
+/**
+ * Get the app-wide settings
+ */
+export async function getAppSettings(): Promise<AppSettings> {
+  return await invoke<AppSettings>('get_app_settings');
+}
+
+/**
+ * Replace the app-wide settings
+ */
+export async function setAppSettings(settings: AppSettings): Promise<AppSettings> {
+  return await invoke<AppSettings>('set_app_settings', { settings });
+}
+
+export interface DatabaseMode {
+  read_only: boolean;
+  /** The other machine's database, when read-only */
+  path: string | null;
+}
+
+/**
+ * Get whether the app is showing another machine's database read-only
+ */
+export async function getDatabaseMode(): Promise<DatabaseMode> {
+  return await invoke<DatabaseMode>('get_database_mode');
+}
+
+/**
+ * Ask for another machine's synced AtelierCode database
+ * @returns The database path, or null if cancelled
+ */
+export async function pickDatabaseFile(): Promise<string | null> {
+  return await open({ multiple: false, directory: false, filters: [{ name: 'AtelierCode database', extensions: ['db'] }] });
+}
+
+/**
+ * Restart showing another machine's synced database read-only
+ */
+export async function openReadOnlyDatabase(path: string): Promise<void> {
+  await invoke('open_read_only_database', { path });
+}
+
+/**
+ * Restart back on this machine's own database
+ */
+export async function closeReadOnlyDatabase(): Promise<void> {
+  await invoke('close_read_only_database');
+}
+
+/**
+ * Get whether the local API is running and its URL
+ */
+export async function getApiServerStatus(): Promise<ApiServerStatus> {
+  return await invoke<ApiServerStatus>('get_api_server_status');
+}
+
+/**
+ * Replace the local API token, disconnecting clients using the old one
+ */
+export async function regenerateApiToken(): Promise<AppSettings> {
+  return await invoke<AppSettings>('regenerate_api_token');
+}
+
+/**
+ * Stop every running agent session
+ * @returns How many sessions were stopped
+ */
+export async function stopAllAgentSessions(): Promise<number> {
+  return await invoke<number>('stop_all_agent_sessions');
+}
+
+/**
+ * Listen for the tray menu asking to open a project
+ * @returns Unlisten function
+ */
+export async function listenToTrayOpenProject(
+  callback: (projectId: string) => void
+): Promise<UnlistenFn> {
+  return await listen<string>('tray://open-project', (event) => callback(event.payload));
+}
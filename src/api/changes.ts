@@ -1,5 +1,9 @@
 // File changes API for tracking and reviewing code changes
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from '../lib/invoke';
+import { open, save } from '@tauri-apps/plugin-dialog';
+import type { TokenSpan } from './highlight';
+
+const PATCH_FILTERS = [{ name: 'Patch', extensions: ['patch', 'diff'] }];
 
 /**
  * File change structure representing a tracked file modification
@@ -14,6 +18,32 @@ export interface FileChange {
   reviewed: boolean;
   approved: boolean | null;
   timestamp: number; // Unix timestamp in seconds
+  content_hash: string | null; // SHA-256 of the file as recorded
+  stale: boolean; // The file has changed on disk since this change
+}
+
+/**
+ * One side of a side-by-side diff row
+ */
+export interface DiffLine {
+  number: number; // 1-based line number in its version of the file
+  content: string;
+  changes: [number, number][]; // Changed [start, end) ranges, usable with String.slice
+  tokens: TokenSpan[]; // Syntax highlighting
+}
+
+/**
+ * An aligned row; old is null for insertions and new is null for deletions
+ */
+export interface DiffRow {
+  kind: 'equal' | 'delete' | 'insert' | 'replace';
+  old: DiffLine | null;
+  new: DiffLine | null;
+}
+
+export interface SideBySideDiff {
+  file_path: string;
+  hunks: { header: string; rows: DiffRow[] }[];
 }
 
 /**
@@ -131,3 +161,159 @@ export async function getFileDiff(changeId: string): Promise<string> {
     throw error;
   }
 }
+
+/**
+ * Get reviewed changes whose file has changed on disk since they were recorded
+ * @param projectId - The ID of the project
+ * @returns Stale changes, newest first
+ */
+export async function getStaleChanges(projectId: string): Promise<FileChange[]> {
+  try {
+    return await invoke<FileChange[]>('get_stale_changes', { projectId });
+  } catch (error) {
+    console.error('Failed to get stale changes:', error);
+    throw error;
+  }
+}
+
+/**
+ * Get the diff for a file change as aligned side-by-side rows
+ * @param changeId - The ID of the change
+ * @returns Hunks of old/new line pairs with intra-line change ranges
+ */
+export async function getSideBySideDiff(changeId: string): Promise<SideBySideDiff> {
+  try {
+    return await invoke<SideBySideDiff>('get_side_by_side_diff', { changeId });
+  } catch (error) {
+    console.error('Failed to get side-by-side diff:', error);
+    throw error;
+  }
+}
+
+/**
+ * Per-file totals in a review report
+ */
+export interface ReportFile {
+  file_path: string;
+  changes: number;
+  approved: number;
+  rejected: number;
+  pending: number;
+  /** Reviewed changes whose file was edited again after the review */
+  changed_after_review: number;
+}
+
+/**
+ * Review activity in a project since a point in time
+ */
+export interface ReviewReport {
+  project_id: string;
+  since: number; // Unix timestamp in seconds
+  generated_at: number;
+  /** Changes recorded since `since`, reviewed or not */
+  changes_since: number;
+  approved: number;
+  rejected: number;
+  pending: number;
+  /** Reviewed changes whose file was edited again after the review */
+  changed_after_review: number;
+  files: ReportFile[];
+  unresolved_comments: {
+    file_path: string;
+    line_number: number | null;
+    author: string;
+    comment: string;
+    timestamp: number;
+  }[];
+  markdown: string; // The same report rendered as Markdown
+}
+
+/**
+ * Generate a review report for standups and audits
+ * @param projectId - The ID of the project
+ * @param since - Unix timestamp in seconds; defaults to 24 hours ago
+ * @returns The report, including a Markdown rendering
+ */
+export async function generateReviewReport(projectId: string, since?: number): Promise<ReviewReport> {
+  try {
+    return await invoke<ReviewReport>('generate_review_report', { projectId, since });
+  } catch (error) {
+    console.error('Failed to generate review report:', error);
+    throw error;
+  }
+}
+
+/**
+ * A written patch file
+ */
+export interface PatchExport {
+  path: string;
+  files: string[]; // Project-relative paths in the patch
+  skipped: string[]; // Outside the project folder, or nothing to diff
+}
+
+/**
+ * Write recorded changes to a .patch file that `git apply` can apply elsewhere
+ * @param projectId - The ID of the project
+ * @param changeIds - Changes to export; defaults to every approved change
+ * @param path - Where to write the patch
+ */
+export async function exportPatch(projectId: string, changeIds: string[] | null, path: string): Promise<PatchExport> {
+  try {
+    return await invoke<PatchExport>('export_patch', { projectId, changeIds, path });
+  } catch (error) {
+    console.error('Failed to export patch:', error);
+    throw error;
+  }
+}
+
+/**
+ * Ask where to save a patch of recorded changes and write it
+ * @returns The export, or null if cancelled
+ */
+export async function exportPatchWithDialog(projectId: string, changeIds: string[] | null): Promise<PatchExport | null> {
+  const path = await save({ defaultPath: 'changes.patch', filters: PATCH_FILTERS });
+  if (!path) return null;
+  return await exportPatch(projectId, changeIds, path);
+}
+
+/**
+ * What importing a patch did, or would do on a dry run
+ */
+export interface PatchImport {
+  dry_run: boolean;
+  files: {
+    file_path: string;
+    change_type: 'created' | 'modified' | 'deleted';
+    applied_hunks: number;
+    change_id: string | null; // The change recorded for review; null on a dry run
+  }[];
+  rejected: {
+    file_path: string;
+    header: string | null; // The hunk's @@ line; null when the whole file was rejected
+    reason: string;
+  }[];
+}
+
+/**
+ * Apply a unified diff to a project, recording what it changed as pending review
+ * @param projectId - The ID of the project
+ * @param path - The patch file
+ * @param dryRun - Report what would apply without touching any file
+ */
+export async function importPatch(projectId: string, path: string, dryRun: boolean): Promise<PatchImport> {
+  try {
+    return await invoke<PatchImport>('import_patch', { projectId, path, dryRun });
+  } catch (error) {
+    console.error('Failed to import patch:', error);
+    throw error;
+  }
+}
+
+/**
+ * Ask for a patch file to apply to a project
+ * @returns The patch path, or null if cancelled
+ */
+export async function pickPatchFile(): Promise<string | null> {
+  return await open({ multiple: false, directory: false, filters: PATCH_FILTERS });
+}
@@ -1,26 +1,113 @@
 // Chat API functions for communicating with Tauri backend
-import { invoke } from '@tauri-apps/api/core';
-import type { ChatMessage } from '../types/tauri';
+import { invoke } from '../lib/invoke';
+import { listen, UnlistenFn } from '@tauri-apps/api/event';
+import type { ChatMessage, MessageCursor, MessagePage, SessionTokenEstimate } from '../types/tauri';
+
+/** Emitted when an assistant reply starts streaming, before its first chunk */
+export interface ChatStreamStart {
+  message_id: string;
+  project_id: string;
+  session_id: string | null;
+}
+
+/** A chunk of an assistant reply streamed by send_message */
+export interface ChatStreamChunk {
+  message_id: string;
+  project_id: string;
+  session_id: string | null;
+  chunk: string;
+}
+
+/** Emitted once a streamed reply has been saved */
+export interface ChatStreamDone {
+  message_id: string;
+  project_id: string;
+  session_id: string | null;
+  message: ChatMessage;
+  cancelled: boolean;
+}
 
 /**
  * Send a chat message and get AI response
  * @param projectId - The ID of the project
  * @param content - The message content
  * @param sessionId - Optional session ID to associate the message with
+ * @param autoLabel - Title the session's unlabeled tab after the first reply
  * @returns Promise with the AI response message
  */
 export async function sendMessage(
   projectId: string,
   content: string,
-  sessionId?: string
+  sessionId?: string,
+  autoLabel?: boolean
 ): Promise<ChatMessage> {
   return await invoke<ChatMessage>('send_message', {
     projectId,
     content,
-    sessionId
+    sessionId,
+    autoLabel
   });
 }
 
+/**
+ * Listen for streamed replies while sendMessage is pending
+ * @param onChunk - Called for each chunk of the reply
+ * @param onDone - Called once the final message has been saved
+ * @param onStart - Called with the reply's message id before any chunk arrives
+ * @returns Function that removes the listeners
+ */
+export async function listenToMessageStream(
+  onChunk: (event: ChatStreamChunk) => void,
+  onDone: (event: ChatStreamDone) => void,
+  onStart?: (event: ChatStreamStart) => void
+): Promise<UnlistenFn> {
+  const unlistenStart = await listen<ChatStreamStart>('chat-stream-start', (e) => onStart?.(e.payload));
+  const unlistenChunk = await listen<ChatStreamChunk>('chat-stream-chunk', (e) => onChunk(e.payload));
+  const unlistenDone = await listen<ChatStreamDone>('chat-stream-done', (e) => onDone(e.payload));
+  return () => {
+    unlistenStart();
+    unlistenChunk();
+    unlistenDone();
+  };
+}
+
+/**
+ * Cancel a reply that is still streaming; the partial reply is kept
+ * @param messageId - The message_id from the chat-stream-start event
+ * @returns Promise resolving to false if the stream had already finished
+ */
+export async function cancelMessageStream(messageId: string): Promise<boolean> {
+  return await invoke<boolean>('cancel_message_stream', { messageId });
+}
+
+/**
+ * Edit a user message and resend it, streaming a new reply
+ * @param messageId - The ID of the user message to edit
+ * @param newContent - The corrected message content
+ * @param truncate - Remove later messages (the stale reply); defaults to true
+ * @returns Promise with the new AI response message
+ */
+export async function resendMessage(
+  messageId: string,
+  newContent: string,
+  truncate?: boolean
+): Promise<ChatMessage> {
+  return await invoke<ChatMessage>('resend_message', { messageId, newContent, truncate });
+}
+
+/**
+ * Regenerate the reply to the last user message in a session
+ * @param sessionId - The ID of the session
+ * @param pluginName - Plugin whose flag settings apply when the session is a running agent
+ * @returns Promise with the new reply, or null when it will arrive from the agent
+ */
+export async function regenerateResponse(
+  sessionId: string,
+  pluginName?: string
+): Promise<ChatMessage | null> {
+  return await invoke<ChatMessage | null>('regenerate_response', { sessionId, pluginName });
+}
+
 /**
  * Get all chat messages for a project
  * @param projectId - The ID of the project
@@ -31,30 +118,88 @@ export async function getMessages(projectId: string): Promise<ChatMessage[]> {
 }
 
 /**
- * Get chat messages for a specific session
+ * Get a page of chat messages for a specific session
  * @param sessionId - The ID of the session
- * @returns Promise with array of chat messages
+ * @param cursor - Optional cursor to load messages older than a given message
+ * @returns Promise with the page of messages and whether older ones exist
  */
-export async function getSessionMessages(sessionId: string): Promise<ChatMessage[]> {
-  return await invoke<ChatMessage[]>('get_session_messages', { sessionId });
+export async function getSessionMessages(
+  sessionId: string,
+  cursor: MessageCursor = {}
+): Promise<MessagePage> {
+  return await invoke<MessagePage>('get_session_messages', { sessionId, ...cursor });
 }
 
 /**
- * Get project messages, optionally filtered by session
+ * Get a page of project messages, optionally filtered by session
  * @param projectId - The ID of the project
  * @param sessionId - Optional session ID to filter messages
- * @returns Promise with array of chat messages
+ * @param cursor - Optional cursor to load messages older than a given message
+ * @returns Promise with the page of messages and whether older ones exist
  */
 export async function getProjectMessages(
   projectId: string,
-  sessionId?: string
-): Promise<ChatMessage[]> {
-  return await invoke<ChatMessage[]>('get_project_messages', {
+  sessionId?: string,
+  cursor: MessageCursor = {}
+): Promise<MessagePage> {
+  return await invoke<MessagePage>('get_project_messages', {
     projectId,
-    sessionId
+    sessionId,
+    ...cursor
   });
 }
 
+/**
+ * Estimate how much of the agent's context window a session is using
+ * @param sessionId - The ID of the session
+ * @returns Promise with the estimate; should_summarize flags a nearly full context
+ */
+export async function estimateSessionTokens(sessionId: string): Promise<SessionTokenEstimate> {
+  return await invoke<SessionTokenEstimate>('estimate_session_tokens', { sessionId });
+}
+
+/**
+ * Pin a message so it surfaces above the scrollback
+ * @param messageId - The ID of the message
+ */
+export async function pinMessage(messageId: string): Promise<void> {
+  await invoke('pin_message', { messageId });
+}
+
+/**
+ * Unpin a message
+ * @param messageId - The ID of the message
+ */
+export async function unpinMessage(messageId: string): Promise<void> {
+  await invoke('unpin_message', { messageId });
+}
+
+/**
+ * Get a project's pinned messages, most recently pinned first
+ * @param projectId - The ID of the project
+ * @returns Promise with array of pinned messages
+ */
+export async function getPinnedMessages(projectId: string): Promise<ChatMessage[]> {
+  return await invoke<ChatMessage[]>('get_pinned_messages', { projectId });
+}
+
+/**
+ * Remove a message from the conversation (soft delete)
+ * @param messageId - The ID of the message
+ */
+export async function deleteMessage(messageId: string): Promise<void> {
+  await invoke('delete_message', { messageId });
+}
+
+/**
+ * Replace a message's content with a placeholder, e.g. after pasting a secret
+ * @param messageId - The ID of the message
+ * @returns Promise with the redacted message
+ */
+export async function redactMessage(messageId: string): Promise<ChatMessage> {
+  return await invoke<ChatMessage>('redact_message', { messageId });
+}
+
 /**
  * Count messages for a session
  * @param sessionId - The ID of the session
@@ -64,6 +209,26 @@ export async function countSessionMessages(sessionId: string): Promise<number> {
   return await invoke<number>('count_session_messages', { sessionId });
 }
 
+/**
+ * Get a session's messages by position, oldest first
+ * @param sessionId - The ID of the session
+ * @param offset - Number of messages to skip from the start of the session
+ * @param limit - Page size (defaults to 20, capped at 200)
+ * @returns Promise with the page; has_more means newer messages follow it
+ */
+export async function getSessionMessagesRange(sessionId: string, offset: number, limit?: number): Promise<MessagePage> {
+  return await invoke<MessagePage>('get_session_messages_range', { sessionId, offset, limit });
+}
+
+/**
+ * Get every message in a session, oldest first (not paged)
+ * @param sessionId - The ID of the session
+ * @returns Promise with all of the session's messages
+ */
+export async function getAllSessionMessages(sessionId: string): Promise<ChatMessage[]> {
+  return await invoke<ChatMessage[]>('get_all_session_messages', { sessionId });
+}
+
 /**
  * Save a message with session context
  * @param projectId - The ID of the project
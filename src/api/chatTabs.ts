@@ -2,8 +2,9 @@
  * Chat Tabs API - manages chat tabs for multi-agent support
  */
 
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from '../lib/invoke';
 import { peerConnection } from '../services/peerConnection';
+import type { SessionListItem } from '../lib/chat';
 
 export interface ChatTab {
   id: string;
@@ -16,6 +17,18 @@ export interface ChatTab {
   is_active: boolean;
   created_at: number;
   last_activity: number;
+  /** JSON object of CLI flag overrides (flag_id -> value) */
+  flag_overrides: string | null;
+  /** CLI session a duplicated tab forks when it first runs */
+  forked_from_cli_session_id: string | null;
+}
+
+/** Sessions an agent CLI already has stored for a project */
+export interface DetectedCliSessions {
+  plugin_name: string;
+  display_name: string;
+  /** Most recent first */
+  sessions: SessionListItem[];
 }
 
 // Helper to check if we should use WebRTC
@@ -36,16 +49,17 @@ export async function getChatTabs(projectId: string): Promise<ChatTab[]> {
 
 /**
  * Create a new chat tab
+ * @param agentType - Agent for the tab; defaults to the project's default agent
  */
 export async function createChatTab(
   projectId: string,
-  agentType: string,
+  agentType?: string,
   label?: string
 ): Promise<ChatTab> {
   if (useWebRTC()) {
-    return peerConnection.sendCommand<ChatTab>('create_chat_tab', { projectId, agentType, label });
+    return peerConnection.sendCommand<ChatTab>('create_chat_tab', { projectId, agentType: agentType ?? null, label });
   }
-  return invoke<ChatTab>('create_chat_tab', { projectId, agentType, label });
+  return invoke<ChatTab>('create_chat_tab', { projectId, agentType: agentType ?? null, label });
 }
 
 /**
@@ -75,6 +89,83 @@ export async function updateChatTab(
   });
 }
 
+/**
+ * Set a tab's CLI flag overrides (e.g. model, permission_mode); an empty object clears them
+ */
+export async function setTabFlagOverrides(
+  tabId: string,
+  overrides: Record<string, string>
+): Promise<ChatTab> {
+  if (useWebRTC()) {
+    return peerConnection.sendCommand<ChatTab>('set_tab_flag_overrides', { tabId, overrides });
+  }
+  return invoke<ChatTab>('set_tab_flag_overrides', { tabId, overrides });
+}
+
+/**
+ * Get the flags that apply to a tab: plugin settings with the tab's overrides on top
+ */
+export async function getTabEffectiveFlags(
+  tabId: string,
+  pluginName: string
+): Promise<Record<string, string>> {
+  if (useWebRTC()) {
+    return peerConnection.sendCommand<Record<string, string>>('get_tab_effective_flags', { tabId, pluginName });
+  }
+  return invoke<Record<string, string>>('get_tab_effective_flags', { tabId, pluginName });
+}
+
+/**
+ * Send a prompt to the session a tab is attached to
+ * @returns The ID of the session that received it
+ */
+export async function sendToTab(tabId: string, message: string): Promise<string> {
+  if (useWebRTC()) {
+    return peerConnection.sendCommand<string>('send_to_tab', { tabId, message });
+  }
+  return invoke<string>('send_to_tab', { tabId, message });
+}
+
+/**
+ * Send lines of a project file to a tab's session, with an instruction such as "Explain this"
+ * @param startLine - First line, 1-based
+ * @param endLine - Last line, inclusive
+ * @returns The ID of the session that received it
+ */
+export async function sendSelectionToAgent(
+  tabId: string,
+  filePath: string,
+  startLine: number,
+  endLine: number,
+  instruction: string
+): Promise<string> {
+  const params = { tabId, filePath, startLine, endLine, instruction };
+  if (useWebRTC()) {
+    return peerConnection.sendCommand<string>('send_selection_to_agent', params);
+  }
+  return invoke<string>('send_selection_to_agent', params);
+}
+
+/**
+ * Duplicate a chat tab to branch the conversation
+ */
+export async function duplicateChatTab(tabId: string): Promise<ChatTab> {
+  if (useWebRTC()) {
+    return peerConnection.sendCommand<ChatTab>('duplicate_chat_tab', { tabId });
+  }
+  return invoke<ChatTab>('duplicate_chat_tab', { tabId });
+}
+
+/**
+ * Title a chat tab from its first exchanges using the AI service
+ */
+export async function autoLabelTab(tabId: string): Promise<ChatTab> {
+  if (useWebRTC()) {
+    return peerConnection.sendCommand<ChatTab>('auto_label_tab', { tabId });
+  }
+  return invoke<ChatTab>('auto_label_tab', { tabId });
+}
+
 /**
  * Set the active tab for a project
  */
@@ -104,3 +195,30 @@ export async function reorderChatTabs(projectId: string, tabIds: string[]): Prom
   }
   return invoke('reorder_chat_tabs', { projectId, tabIds });
 }
+
+/**
+ * Find the sessions installed agent CLIs have stored for a project folder
+ */
+export async function findCliSessions(projectPath: string): Promise<DetectedCliSessions[]> {
+  if (useWebRTC()) {
+    return peerConnection.sendCommand<DetectedCliSessions[]>('find_cli_sessions', { projectPath });
+  }
+  return invoke<DetectedCliSessions[]>('find_cli_sessions', { projectPath });
+}
+
+/**
+ * Open a chat tab for each CLI session; sessions that already have a tab are skipped
+ * @returns The new tabs
+ */
+export async function importCliSessions(
+  projectId: string,
+  pluginName: string,
+  projectPath: string,
+  cliSessionIds: string[]
+): Promise<ChatTab[]> {
+  const params = { projectId, pluginName, projectPath, cliSessionIds };
+  if (useWebRTC()) {
+    return peerConnection.sendCommand<ChatTab[]>('import_cli_sessions', params);
+  }
+  return invoke<ChatTab[]>('import_cli_sessions', params);
+}
@@ -0,0 +1,49 @@
+// CI API
+// GitHub Actions and GitLab CI runs for the project's remote
+import { invoke } from '../lib/invoke';
+import { peerConnection } from '../services/peerConnection';
+
+export type CiProvider = 'github_actions' | 'gitlab_ci';
+
+export type CiRunStatus = 'queued' | 'running' | 'success' | 'failure' | 'cancelled' | 'skipped';
+
+export interface CiConfig {
+  provider: CiProvider;
+  /** Config files, relative to the project root */
+  files: string[];
+}
+
+export interface CiRun {
+  id: string;
+  /** Workflow name; GitLab pipelines are named after their source, e.g. "push" */
+  name: string;
+  branch: string | null;
+  commit_sha: string;
+  status: CiRunStatus;
+  url: string;
+  /** RFC 3339 */
+  created_at: string;
+  updated_at: string | null;
+}
+
+export interface CiStatus {
+  configs: CiConfig[];
+  provider: CiProvider | null;
+  remote_url: string | null;
+  /** The commit checked out locally */
+  head_sha: string | null;
+  /** The newest run for head_sha, if CI has picked it up */
+  head_run: CiRun | null;
+  /** Recent runs, newest first */
+  runs: CiRun[];
+}
+
+/**
+ * Get the project's recent CI runs and whether the checked out commit passed
+ */
+export async function getCiStatus(projectId: string, limit?: number): Promise<CiStatus> {
+  if (peerConnection.isClient) {
+    return peerConnection.sendCommand<CiStatus>('get_ci_status', { projectId, limit });
+  }
+  return await invoke<CiStatus>('get_ci_status', { projectId, limit });
+}
@@ -0,0 +1,83 @@
+// Claude Config API
+// Managing a project's Claude Code subagents and hooks under .claude/
+import { invoke } from '../lib/invoke';
+
+/** A subagent in .claude/agents/<name>.md */
+export interface Subagent {
+  /** Lowercase letters, digits and hyphens */
+  name: string;
+  /** When Claude should hand work to this agent */
+  description: string;
+  /** Allowed tools; empty inherits all of them */
+  tools: string[];
+  /** e.g. "sonnet", "opus" or "inherit" */
+  model: string | null;
+  /** The agent's system prompt */
+  prompt: string;
+}
+
+/** One hook command from .claude/settings.json */
+export interface ClaudeHook {
+  /** e.g. "PreToolUse", "PostToolUse" or "Stop" */
+  event: string;
+  /** Tool name pattern for tool events, e.g. "Edit|Write" */
+  matcher: string | null;
+  command: string;
+  /** Seconds */
+  timeout: number | null;
+}
+
+export interface ClaudeConfigTemplates {
+  subagents: { id: string; label: string; agent: Subagent }[];
+  hooks: { id: string; label: string; hook: ClaudeHook }[];
+}
+
+/** Events Claude Code runs hooks on */
+export const HOOK_EVENTS = [
+  'PreToolUse',
+  'PostToolUse',
+  'Notification',
+  'UserPromptSubmit',
+  'Stop',
+  'SubagentStop',
+  'PreCompact',
+  'SessionStart',
+  'SessionEnd',
+] as const;
+
+/**
+ * The project's subagents, sorted by name
+ */
+export async function listClaudeSubagents(projectId: string): Promise<Subagent[]> {
+  return await invoke<Subagent[]>('list_claude_subagents', { projectId });
+}
+
+/**
+ * Create a subagent, or update one by passing the name it had when loaded
+ */
+export async function saveClaudeSubagent(projectId: string, agent: Subagent, previousName?: string): Promise<Subagent> {
+  return await invoke<Subagent>('save_claude_subagent', { projectId, agent, previousName });
+}
+
+export async function deleteClaudeSubagent(projectId: string, name: string): Promise<void> {
+  await invoke('delete_claude_subagent', { projectId, name });
+}
+
+/**
+ * The project's hooks; local reads .claude/settings.local.json
+ */
+export async function listClaudeHooks(projectId: string, local = false): Promise<ClaudeHook[]> {
+  return await invoke<ClaudeHook[]>('list_claude_hooks', { projectId, local });
+}
+
+/**
+ * Replace the project's hooks, keeping the rest of the settings file
+ * @returns The hooks as saved
+ */
+export async function saveClaudeHooks(projectId: string, hooks: ClaudeHook[], local = false): Promise<ClaudeHook[]> {
+  return await invoke<ClaudeHook[]>('save_claude_hooks', { projectId, hooks, local });
+}
+
+export async function getClaudeConfigTemplates(): Promise<ClaudeConfigTemplates> {
+  return await invoke<ClaudeConfigTemplates>('get_claude_config_templates');
+}
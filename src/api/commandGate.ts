@@ -0,0 +1,44 @@
+// Command Gate API
+// Approving agent shell commands that match a project's deny list
+import { invoke } from '../lib/invoke';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+
+export interface PendingCommand {
+  id: string;
+  project_id: string;
+  session_id: string;
+  command: string;
+  /** Deny pattern the command matched */
+  pattern: string;
+  requested_at: number;
+}
+
+/**
+ * Let a held command run, or block it
+ */
+export async function approveCommand(requestId: string, approved: boolean): Promise<void> {
+  return await invoke<void>('approve_command', { requestId, approved });
+}
+
+/**
+ * Commands waiting for approval, oldest first
+ */
+export async function getPendingCommands(): Promise<PendingCommand[]> {
+  return await invoke<PendingCommand[]>('get_pending_commands');
+}
+
+/**
+ * Listen for commands that start waiting for approval
+ * @returns Unlisten function
+ */
+export async function listenToCommandApprovals(callback: (command: PendingCommand) => void): Promise<UnlistenFn> {
+  return await listen<PendingCommand>('command-approval-requested', (event) => callback(event.payload));
+}
+
+/**
+ * Listen for commands that were approved, denied or timed out, by request id
+ * @returns Unlisten function
+ */
+export async function listenToCommandApprovalsResolved(callback: (requestId: string) => void): Promise<UnlistenFn> {
+  return await listen<string>('command-approval-resolved', (event) => callback(event.payload));
+}
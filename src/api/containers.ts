@@ -0,0 +1,11 @@
+// Containers API
+// A project's dev container and Docker Compose setup
+import { invoke } from '../lib/invoke';
+import type { ContainerEnvironment } from '../types/tauri';
+
+/**
+ * The project's dev container and compose files, or null when it has none
+ */
+export async function getContainerEnvironment(projectId: string): Promise<ContainerEnvironment | null> {
+  return await invoke<ContainerEnvironment | null>('get_container_environment', { projectId });
+}
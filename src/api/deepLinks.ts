@@ -0,0 +1,27 @@
+// Deep Links API
+// ateliercode:// links opened from terminals, docs or PRs
+import { invoke } from '../lib/invoke';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+
+export type DeepLink =
+  /** ateliercode://project/<id> */
+  | { action: 'open_project'; project_id: string }
+  /** ateliercode://project/<id>/session/<session_id> */
+  | { action: 'open_session'; project_id: string; session_id: string }
+  /** ateliercode://project/<id>/new-agent?prompt=...&agent=... */
+  | { action: 'new_agent'; project_id: string; prompt: string | null; agent_type: string | null };
+
+/**
+ * Take the deep links opened since the last call, including the one the app was launched with
+ */
+export async function takePendingDeepLinks(): Promise<DeepLink[]> {
+  return await invoke<DeepLink[]>('take_pending_deep_links');
+}
+
+/**
+ * Listen for deep links opened while the app is running
+ * @returns Unlisten function
+ */
+export async function listenToDeepLinks(callback: (link: DeepLink) => void): Promise<UnlistenFn> {
+  return await listen<DeepLink>('deep-link', (event) => callback(event.payload));
+}
@@ -0,0 +1,14 @@
+// Demo Project API
+// A sample project for trying the app without an agent CLI
+import { invoke } from '../lib/invoke';
+import type { Project } from '../types/tauri';
+
+/**
+ * Create the sample project, in the documents folder unless `parentDir` is given
+ *
+ * It comes with tasks, a PRD, a pending change to review and a chat with the
+ * scripted demo agent.
+ */
+export async function createDemoProject(parentDir?: string): Promise<Project> {
+  return await invoke<Project>('create_demo_project', { parentDir });
+}
@@ -0,0 +1,79 @@
+// Dev Server API
+// Starting and stopping a project's dev server and finding its preview URL
+import { invoke } from '../lib/invoke';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+
+/** The command that starts a dev server, e.g. a script from listScripts */
+export interface RunConfig {
+  program: string;
+  args?: string[];
+  env?: Record<string, string>;
+}
+
+export type DevServerStatus = 'starting' | 'running' | 'stopped' | 'exited';
+
+export interface DevServerInfo {
+  project_id: string;
+  command: string;
+  pid: number | null;
+  /** starting until the port shows up in the output */
+  status: DevServerStatus;
+  port: number | null;
+  /** Where the running app can be previewed */
+  url: string | null;
+  exit_code: number | null;
+  started_at: number;
+}
+
+export interface DevServerState {
+  info: DevServerInfo;
+  /** Recent output lines */
+  log: string[];
+}
+
+export interface DevServerOutput {
+  project_id: string;
+  line: string;
+}
+
+export function isDevServerActive(info: DevServerInfo): boolean {
+  return info.status === 'starting' || info.status === 'running';
+}
+
+/**
+ * Start the project's dev server; it stops when the calling window closes
+ */
+export async function startDevServer(projectId: string, runConfig: RunConfig): Promise<DevServerInfo> {
+  return await invoke<DevServerInfo>('start_dev_server', { projectId, runConfig });
+}
+
+/**
+ * Stop the project's dev server
+ * @returns false if none was running
+ */
+export async function stopDevServer(projectId: string): Promise<boolean> {
+  return await invoke<boolean>('stop_dev_server', { projectId });
+}
+
+/**
+ * The project's dev server, running or last ended, with its recent output
+ */
+export async function getDevServer(projectId: string): Promise<DevServerState | null> {
+  return await invoke<DevServerState | null>('get_dev_server', { projectId });
+}
+
+/**
+ * Listen for dev servers starting, finding their URL and ending
+ * @returns Unlisten function
+ */
+export async function listenToDevServerUpdates(callback: (info: DevServerInfo) => void): Promise<UnlistenFn> {
+  return await listen<DevServerInfo>('dev-server-updated', (event) => callback(event.payload));
+}
+
+/**
+ * Listen for lines printed by dev servers
+ * @returns Unlisten function
+ */
+export async function listenToDevServerOutput(callback: (output: DevServerOutput) => void): Promise<UnlistenFn> {
+  return await listen<DevServerOutput>('dev-server-output', (event) => callback(event.payload));
+}
@@ -0,0 +1,34 @@
+// Environment Doctor API
+import { invoke } from '../lib/invoke';
+import { peerConnection } from '../services/peerConnection';
+
+export type CheckStatus = 'ok' | 'warning' | 'error';
+
+export interface DoctorCheck {
+  /** Stable identifier, e.g. "git" or "plugin:claude-code" */
+  id: string;
+  label: string;
+  status: CheckStatus;
+  /** What was found, e.g. a version or an error */
+  detail: string;
+  /** How to fix a warning or error */
+  fix: string | null;
+}
+
+export interface DoctorReport {
+  checks: DoctorCheck[];
+  /** True when no check is an error */
+  healthy: boolean;
+  checked_at: number;
+}
+
+/**
+ * Check git, node, agent CLIs, whisper, API keys, the database and the plugin
+ * directory, with suggested fixes for anything missing
+ */
+export async function runDoctor(): Promise<DoctorReport> {
+  if (peerConnection.isClient) {
+    return peerConnection.sendCommand<DoctorReport>('run_doctor', {});
+  }
+  return await invoke<DoctorReport>('run_doctor');
+}
@@ -0,0 +1,40 @@
+// Env Files API
+// Reading and editing a project's .env files without exposing their values
+import { invoke } from '../lib/invoke';
+
+export interface EnvEntry {
+  key: string;
+  /** The value, or a mask when masked */
+  value: string;
+  masked: boolean;
+  /** 1-based line in the file */
+  line: number;
+}
+
+export interface EnvFile {
+  /** Relative to the project root, e.g. ".env.local" */
+  path: string;
+  entries: EnvEntry[];
+}
+
+/**
+ * The env files at the top of the project
+ */
+export async function listEnvFiles(projectId: string): Promise<string[]> {
+  return await invoke<string[]>('list_env_files', { projectId });
+}
+
+/**
+ * Read an env file; values are masked unless reveal is set
+ */
+export async function readEnvFile(projectId: string, path: string, reveal = false): Promise<EnvFile> {
+  return await invoke<EnvFile>('read_env_file', { projectId, path, reveal });
+}
+
+/**
+ * Set one variable, creating the file if needed, or remove it when value is null
+ * @returns The file with its values masked
+ */
+export async function setEnvVar(projectId: string, path: string, key: string, value: string | null): Promise<EnvFile> {
+  return await invoke<EnvFile>('set_env_var', { projectId, path, key, value });
+}
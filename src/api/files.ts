@@ -1,5 +1,6 @@
 // File operations API for interacting with the file system via Tauri
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from '../lib/invoke';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
 
 /**
  * File node structure representing a file or directory
@@ -16,13 +17,37 @@ export interface FileNode {
 }
 
 /**
- * Read project files and return file tree structure
+ * One page of a directory listing, folders first and then by name
+ */
+export interface FileNodePage {
+  nodes: FileNode[];
+  /** Entries in the whole directory */
+  total: number;
+  offset: number;
+}
+
+/**
+ * Options for paging through large directories
+ */
+export interface PageOptions {
+  offset?: number;
+  /** Entries per page; the backend defaults to 1000 */
+  limit?: number;
+}
+
+/**
+ * Read a page of the project's top-level files
  * @param projectId - The ID of the project to read files from
- * @returns Array of FileNode objects representing the project's file structure
+ * @param page - Which entries to return
+ * @returns The page of FileNode objects and the total entry count
  */
-export async function readProjectFiles(projectId: string): Promise<FileNode[]> {
+export async function readProjectFiles(projectId: string, page?: PageOptions): Promise<FileNodePage> {
   try {
-    return await invoke<FileNode[]>('read_project_files', { projectId });
+    return await invoke<FileNodePage>('read_project_files', {
+      projectId,
+      offset: page?.offset,
+      limit: page?.limit,
+    });
   } catch (error) {
     console.error('Failed to read project files:', error);
     throw error;
@@ -30,17 +55,24 @@ export async function readProjectFiles(projectId: string): Promise<FileNode[]> {
 }
 
 /**
- * Get children of a folder (for lazy loading)
+ * Get a page of a folder's children (for lazy loading)
  * @param projectId - The ID of the project
  * @param folderPath - The absolute path to the folder
- * @returns Array of FileNode objects representing the folder's children
+ * @param page - Which entries to return
+ * @returns The page of FileNode objects and the total entry count
  */
 export async function getFolderChildren(
   projectId: string,
-  folderPath: string
-): Promise<FileNode[]> {
+  folderPath: string,
+  page?: PageOptions
+): Promise<FileNodePage> {
   try {
-    return await invoke<FileNode[]>('get_folder_children', { projectId, folderPath });
+    return await invoke<FileNodePage>('get_folder_children', {
+      projectId,
+      folderPath,
+      offset: page?.offset,
+      limit: page?.limit,
+    });
   } catch (error) {
     console.error('Failed to get folder children:', error);
     throw error;
@@ -69,6 +101,32 @@ export async function getGitStatus(projectId: string): Promise<GitFileStatus[]>
   }
 }
 
+/**
+ * Commit changes in a project
+ * @param projectId - The ID of the project
+ * @param message - The commit message
+ * @param options - Files to commit (relative paths; default everything) and
+ *   whether to commit despite the project's review-before-commit policy
+ * @returns The new commit hash
+ */
+export async function gitCommit(
+  projectId: string,
+  message: string,
+  options?: { files?: string[]; overrideReview?: boolean }
+): Promise<string> {
+  try {
+    return await invoke<string>('git_commit', {
+      projectId,
+      message,
+      files: options?.files,
+      overrideReview: options?.overrideReview,
+    });
+  } catch (error) {
+    console.error('Failed to commit:', error);
+    throw error;
+  }
+}
+
 /**
  * Read and return file content
  * @param projectId - The ID of the project the file belongs to
@@ -86,3 +144,36 @@ export async function readFileContent(
     throw error;
   }
 }
+
+/**
+ * Sent to a window when a file it watches changes on disk
+ */
+export interface OpenFileChanged {
+  project_id: string;
+  /** As passed to watchOpenFile */
+  file_path: string;
+  /** SHA-256 of the new content; null once the file is deleted */
+  content_hash: string | null;
+}
+
+/**
+ * Watch a file open in the viewer for changes on disk
+ * @returns The file's current content hash
+ */
+export async function watchOpenFile(projectId: string, filePath: string): Promise<string | null> {
+  return await invoke<string | null>('watch_open_file', { projectId, filePath });
+}
+
+/**
+ * Stop watching a file closed in the viewer
+ */
+export async function unwatchOpenFile(projectId: string, filePath: string): Promise<void> {
+  await invoke('unwatch_open_file', { projectId, filePath });
+}
+
+/**
+ * Listen for changes to the files this window watches
+ */
+export async function onOpenFileChanged(callback: (change: OpenFileChanged) => void): Promise<UnlistenFn> {
+  return await listen<OpenFileChanged>('open-file-changed', (event) => callback(event.payload));
+}
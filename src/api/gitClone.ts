@@ -0,0 +1,22 @@
+// Git Clone API
+// Creating projects by cloning a repository
+import { invoke } from '../lib/invoke';
+import type { Project } from '../types/tauri';
+
+/**
+ * Clone a repository into `dest` and create a project for it
+ *
+ * Runs as a background job; its progress arrives as job updates of kind
+ * "git_clone". Name and description are filled in from analyzing the clone.
+ */
+export async function createProjectFromGit(url: string, dest: string, agentType: string): Promise<Project> {
+  return await invoke<Project>('create_project_from_git', { url, dest, agentType });
+}
+
+/**
+ * The folder name git would clone a URL into, e.g. "app" for "git@host:me/app.git"
+ */
+export function repoName(url: string): string {
+  const last = url.trim().replace(/[/\\]+$/, '').split(/[/\\:]/).pop() ?? '';
+  return last.replace(/\.git$/, '');
+}
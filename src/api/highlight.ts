@@ -0,0 +1,42 @@
+// Highlight API
+// Syntax highlighting computed by the backend, for files too large to tokenize in the webview
+import { invoke } from '../lib/invoke';
+
+export type TokenKind =
+  | 'comment'
+  | 'string'
+  | 'number'
+  | 'constant'
+  | 'operator'
+  | 'keyword'
+  | 'type'
+  | 'function'
+  | 'tag'
+  | 'attribute'
+  | 'parameter'
+  | 'variable';
+
+/**
+ * A highlighted [start, end) range of a line, usable with String.slice
+ */
+export interface TokenSpan {
+  start: number;
+  end: number;
+  kind: TokenKind;
+}
+
+export interface HighlightTokens {
+  /** Syntax used, e.g. "Rust"; "Plain Text" when none fit */
+  syntax: string;
+  /** Spans of each line, in order */
+  lines: TokenSpan[][];
+  /** Whether lines past the backend's limit were left unhighlighted */
+  truncated: boolean;
+}
+
+/**
+ * Get token spans for each line of a project file
+ */
+export async function getHighlightTokens(projectId: string, filePath: string): Promise<HighlightTokens> {
+  return await invoke<HighlightTokens>('get_highlight_tokens', { projectId, filePath });
+}
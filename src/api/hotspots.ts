@@ -0,0 +1,27 @@
+// Hotspots API
+// Files that change often and are complex, where agents are most likely to break things
+import { invoke } from '../lib/invoke';
+
+export interface Hotspot {
+  /** Relative to the project root */
+  path: string;
+  /** Commits touching the file in the last 180 days */
+  commits: number;
+  /** Lines added plus removed by those commits */
+  lines_changed: number;
+  /** Unix timestamp of the latest of them */
+  last_changed: number | null;
+  /** Non-blank lines */
+  lines: number;
+  /** Rough cyclomatic complexity: 1 plus branches, loops and boolean operators */
+  complexity: number;
+  /** 0 to 1: churn times complexity, each relative to the project's highest */
+  score: number;
+}
+
+/**
+ * Get the project's riskiest files, highest score first
+ */
+export async function getCodeHotspots(projectId: string): Promise<Hotspot[]> {
+  return await invoke<Hotspot[]>('get_code_hotspots', { projectId });
+}
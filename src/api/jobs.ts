@@ -0,0 +1,51 @@
+// Jobs API
+// Background jobs (analysis, exports, clones) and their progress
+import { invoke } from '../lib/invoke';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+
+export type JobStatus = 'queued' | 'running' | 'completed' | 'failed' | 'cancelled';
+
+export interface Job {
+  id: string;
+  /** What the job does, e.g. "project_export" */
+  kind: string;
+  project_id: string | null;
+  title: string;
+  status: JobStatus;
+  /** Fraction done, from 0 to 1, when the job reports it */
+  progress: number | null;
+  message: string | null;
+  /** JSON result of a completed job */
+  result: string | null;
+  error: string | null;
+  created_at: number;
+  started_at: number | null;
+  finished_at: number | null;
+}
+
+export function isJobActive(job: Job): boolean {
+  return job.status === 'queued' || job.status === 'running';
+}
+
+/**
+ * Recent jobs, newest first; all projects' unless a project is given
+ */
+export async function listJobs(projectId?: string, limit?: number): Promise<Job[]> {
+  return await invoke<Job[]>('list_jobs', { projectId, limit });
+}
+
+/**
+ * Cancel a queued or running job
+ * @returns false if the job had already finished
+ */
+export async function cancelJob(jobId: string): Promise<boolean> {
+  return await invoke<boolean>('cancel_job', { jobId });
+}
+
+/**
+ * Listen for jobs being queued, progressing and finishing
+ * @returns Unlisten function
+ */
+export async function listenToJobUpdates(callback: (job: Job) => void): Promise<UnlistenFn> {
+  return await listen<Job>('job-updated', (event) => callback(event.payload));
+}
@@ -0,0 +1,53 @@
+// Lint API
+// Running a project's linters and formatters
+import { invoke } from '../lib/invoke';
+
+export type Linter = 'eslint' | 'clippy' | 'ruff';
+export type Formatter = 'prettier' | 'rustfmt' | 'ruff';
+
+export interface LintFinding {
+  tool: Linter;
+  /** Relative to the project root */
+  file: string;
+  line: number | null;
+  column: number | null;
+  severity: 'error' | 'warning' | 'info';
+  rule: string | null;
+  message: string;
+}
+
+export interface LintReport {
+  tools: Linter[];
+  findings: LintFinding[];
+  /** Tools that couldn't run, e.g. because they aren't installed */
+  errors: string[];
+}
+
+export interface FormattedFile {
+  tool: Formatter;
+  path: string;
+  /** Unified diff of the change; empty once applied */
+  diff: string;
+}
+
+export interface FormatReport {
+  tools: Formatter[];
+  applied: boolean;
+  files: FormattedFile[];
+  errors: string[];
+}
+
+/**
+ * Run the linters the project is set up for (eslint, clippy, ruff)
+ */
+export async function runLinter(projectId: string): Promise<LintReport> {
+  return await invoke<LintReport>('run_linter', { projectId });
+}
+
+/**
+ * Run the project's formatters (prettier, rustfmt, ruff)
+ * @param apply - Rewrite the files; otherwise only return the diffs formatting would apply
+ */
+export async function runFormatter(projectId: string, apply: boolean): Promise<FormatReport> {
+  return await invoke<FormatReport>('run_formatter', { projectId, apply });
+}
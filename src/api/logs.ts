@@ -0,0 +1,31 @@
+// Application Logs API
+// Reads this machine's log files for bug reports
+import { invoke } from '../lib/invoke';
+
+export type LogLevel = 'error' | 'warn' | 'info' | 'debug' | 'trace';
+
+export interface LogEntry {
+  timestamp: string;
+  level: 'ERROR' | 'WARN' | 'INFO' | 'DEBUG' | 'TRACE';
+  target: string;
+  message: string;
+  /** Structured fields such as session_id and project_id */
+  fields: Record<string, unknown>;
+}
+
+/**
+ * Get the most recent log entries, oldest first
+ * @param level - Least severe level to include (default 'info')
+ * @param lines - Maximum number of entries (default 500)
+ */
+export async function getAppLogs(level?: LogLevel, lines?: number): Promise<LogEntry[]> {
+  return await invoke<LogEntry[]>('get_app_logs', { level, lines });
+}
+
+/**
+ * Open the log folder in the system file manager
+ * @returns The folder's path
+ */
+export async function openLogFolder(): Promise<string> {
+  return await invoke<string>('open_log_folder');
+}
@@ -0,0 +1,34 @@
+// LSP API
+// Diagnostics from the project's language servers
+import { invoke } from '../lib/invoke';
+
+export interface Diagnostic {
+  /** Relative to the project root */
+  file: string;
+  /** 1-based */
+  line: number;
+  column: number;
+  end_line: number;
+  end_column: number;
+  severity: 'error' | 'warning' | 'info' | 'hint';
+  message: string;
+  /** What produced it, e.g. rustc or typescript */
+  source: string | null;
+  code: string | null;
+}
+
+/**
+ * Get diagnostics from the project's language servers, starting them if needed
+ * @param file - Only this file, re-sent to its server if it changed; omit for every file checked so far
+ */
+export async function getDiagnostics(projectId: string, file?: string): Promise<Diagnostic[]> {
+  return await invoke<Diagnostic[]>('get_diagnostics', { projectId, file: file ?? null });
+}
+
+/**
+ * Shut down the project's language servers
+ * @returns false if none were running
+ */
+export async function stopLanguageServers(projectId: string): Promise<boolean> {
+  return await invoke<boolean>('stop_language_servers', { projectId });
+}
@@ -0,0 +1,38 @@
+// Maintenance API
+import { invoke } from '../lib/invoke';
+import { peerConnection } from '../services/peerConnection';
+
+export interface CleanupResult {
+  /** Sessions that never got a CLI session id */
+  sessions_deleted: number;
+  messages_deleted: number;
+  /** Sessions marked running whose process is gone */
+  stale_sessions_stopped: number;
+  /** Session watches whose chat tab no longer exists */
+  watches_stopped: number;
+  /** File changes left over from deleted projects */
+  file_changes_deleted: number;
+  /** True when nothing was changed and the counts are what a cleanup would do */
+  dry_run: boolean;
+}
+
+/**
+ * Delete orphaned sessions, stop stale sessions and watches without a tab, and
+ * drop file changes of deleted projects. Also runs every few hours on its own.
+ */
+export async function cleanupOrphanedSessions(): Promise<CleanupResult> {
+  if (peerConnection.isClient) {
+    return peerConnection.sendCommand<CleanupResult>('cleanup_orphaned_sessions', {});
+  }
+  return await invoke<CleanupResult>('cleanup_orphaned_sessions');
+}
+
+/**
+ * Report what a cleanup would do without changing anything
+ */
+export async function previewCleanup(): Promise<CleanupResult> {
+  if (peerConnection.isClient) {
+    return peerConnection.sendCommand<CleanupResult>('preview_cleanup', {});
+  }
+  return await invoke<CleanupResult>('preview_cleanup');
+}
@@ -0,0 +1,54 @@
+// Notes API
+// A project's markdown pages: decisions and background outside chat and the PRD
+import { invoke } from '../lib/invoke';
+
+export interface Note {
+  id: string;
+  project_id: string;
+  /** Unique within the project */
+  title: string;
+  /** Markdown */
+  content: string;
+  created_at: number; // Unix timestamp in seconds
+  updated_at: number;
+}
+
+/** Note that agent turn summaries go to when session_summaries_to_notes is on */
+export const SUMMARIES_NOTE_TITLE = 'Session summaries';
+
+/**
+ * A project's notes, most recently edited first
+ * @param search - Keep notes whose title or text contains this, ignoring case
+ */
+export async function getNotes(projectId: string, search?: string): Promise<Note[]> {
+  return await invoke<Note[]>('get_notes', { projectId, search: search || null });
+}
+
+export async function getNote(noteId: string): Promise<Note> {
+  return await invoke<Note>('get_note', { noteId });
+}
+
+/**
+ * Create a note; fails if the project already has one with this title
+ */
+export async function createNote(projectId: string, title: string, content?: string): Promise<Note> {
+  return await invoke<Note>('create_note', { projectId, title, content: content ?? null });
+}
+
+/**
+ * Rename a note and/or replace its content
+ */
+export async function updateNote(noteId: string, updates: { title?: string; content?: string }): Promise<Note> {
+  return await invoke<Note>('update_note', {
+    noteId,
+    title: updates.title ?? null,
+    content: updates.content ?? null,
+  });
+}
+
+/**
+ * @returns false if the note didn't exist
+ */
+export async function deleteNote(noteId: string): Promise<boolean> {
+  return await invoke<boolean>('delete_note', { noteId });
+}
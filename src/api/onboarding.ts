@@ -0,0 +1,35 @@
+// Onboarding API
+// First-run setup steps, to guide new users until they've run an agent
+import { invoke } from '../lib/invoke';
+import { peerConnection } from '../services/peerConnection';
+
+/** Setup steps, in order */
+export type OnboardingStep = 'plugins_detected' | 'first_project_created' | 'first_session_run';
+
+export interface OnboardingState {
+  /** Unix seconds each finished step was finished */
+  completed: { step: OnboardingStep; completed_at: number }[];
+  /** The step to guide the user through; null once all are done */
+  current_step: OnboardingStep | null;
+  finished: boolean;
+}
+
+/**
+ * Get the onboarding steps done so far and the one to show next
+ */
+export async function getOnboardingState(): Promise<OnboardingState> {
+  if (peerConnection.isClient) {
+    return peerConnection.sendCommand<OnboardingState>('get_onboarding_state', {});
+  }
+  return await invoke<OnboardingState>('get_onboarding_state');
+}
+
+/**
+ * Mark an onboarding step, and the ones before it, done
+ */
+export async function completeOnboardingStep(step: OnboardingStep): Promise<OnboardingState> {
+  if (peerConnection.isClient) {
+    return peerConnection.sendCommand<OnboardingState>('complete_onboarding_step', { step });
+  }
+  return await invoke<OnboardingState>('complete_onboarding_step', { step });
+}
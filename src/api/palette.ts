@@ -0,0 +1,30 @@
+// Command Palette API
+// One fuzzy search over everything the palette can open
+import { invoke } from '../lib/invoke';
+
+export type PaletteItemKind = 'project' | 'file' | 'task' | 'note' | 'chat_tab' | 'session';
+
+export interface PaletteItem {
+  kind: PaletteItemKind;
+  /** Id of the project, task, note, tab or session; the relative path for files */
+  id: string;
+  title: string;
+  subtitle: string | null;
+  /** Project the item belongs to (the project itself for projects) */
+  project_id: string;
+  /** Higher is a better match */
+  score: number;
+}
+
+/**
+ * Search projects, tasks, notes (titles and text), chat tabs, recent sessions and (within `projectId`) files
+ * @param projectId Current project; limits tasks, notes, tabs and sessions to it and enables file results
+ * @returns Best matches first
+ */
+export async function paletteSearch(query: string, projectId?: string, limit?: number): Promise<PaletteItem[]> {
+  return await invoke<PaletteItem[]>('palette_search', {
+    query,
+    projectId: projectId ?? null,
+    limit: limit ?? null,
+  });
+}
@@ -1,5 +1,5 @@
 // Plugin Settings API
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from '../lib/invoke';
 import { peerConnection } from '../services/peerConnection';
 
 // Helper to check if we should use WebRTC
@@ -0,0 +1,37 @@
+// PRD History API
+// Earlier versions of a project's PRD, diffs between them and restoring one
+import { invoke } from '../lib/invoke';
+import type { Project } from '../types/tauri';
+
+export type PrdAuthor = 'user' | 'ai';
+
+export interface PrdRevision {
+  id: string;
+  project_id: string;
+  content: string;
+  author: PrdAuthor;
+  created_at: number; // Unix timestamp in seconds
+}
+
+/**
+ * A project's PRD revisions, newest first
+ */
+export async function getPrdHistory(projectId: string): Promise<PrdRevision[]> {
+  return await invoke<PrdRevision[]>('get_prd_history', { projectId });
+}
+
+/**
+ * Make an earlier revision the project's PRD again
+ * @returns The updated project
+ */
+export async function restorePrdRevision(revisionId: string): Promise<Project> {
+  return await invoke<Project>('restore_prd_revision', { revisionId });
+}
+
+/**
+ * Unified diff from one revision to another, or to the current PRD when
+ * `toRevisionId` is omitted; empty when they match
+ */
+export async function diffPrdRevisions(fromRevisionId: string, toRevisionId?: string): Promise<string> {
+  return await invoke<string>('diff_prd_revisions', { fromRevisionId, toRevisionId });
+}
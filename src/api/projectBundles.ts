@@ -0,0 +1,63 @@
+// Project Bundles API
+// Moving a project's AtelierCode state between machines as a single archive
+import { invoke } from '../lib/invoke';
+import { open, save } from '@tauri-apps/plugin-dialog';
+
+export interface BundleManifest {
+  format_version: number;
+  app_version: string;
+  exported_at: number;
+  project_id: string;
+  project_name: string;
+  /** Project location on the exporting machine */
+  root_path: string;
+  /** Extra project roots on the exporting machine, imported unchanged */
+  extra_roots?: string[];
+  /** Rows exported per table */
+  row_counts: Record<string, number>;
+}
+
+const BUNDLE_FILTERS = [{ name: 'AtelierCode Bundle', extensions: ['zip'] }];
+
+/**
+ * Export a project's tasks, chats, reviews, tabs and settings to a bundle file
+ */
+export async function exportProjectBundle(projectId: string, path: string): Promise<BundleManifest> {
+  return await invoke<BundleManifest>('export_project_bundle', { projectId, path });
+}
+
+/**
+ * Read a bundle's manifest without importing it
+ */
+export async function readProjectBundle(path: string): Promise<BundleManifest> {
+  return await invoke<BundleManifest>('read_project_bundle', { path });
+}
+
+/**
+ * Import a bundle as a new project
+ * @param rootPath Where the project lives on this machine (defaults to the exported location)
+ * @returns The imported project's id
+ */
+export async function importProjectBundle(path: string, rootPath?: string): Promise<string> {
+  return await invoke<string>('import_project_bundle', { path, rootPath: rootPath ?? null });
+}
+
+/**
+ * Ask where to save a project's bundle and export it
+ * @returns The manifest, or null if cancelled
+ */
+export async function exportProjectBundleWithDialog(projectId: string, projectName: string): Promise<BundleManifest | null> {
+  const path = await save({ defaultPath: `${projectName}.ateliercode.zip`, filters: BUNDLE_FILTERS });
+  if (!path) return null;
+  return await exportProjectBundle(projectId, path);
+}
+
+/**
+ * Ask for a bundle file to import
+ * @returns The bundle path and manifest, or null if cancelled
+ */
+export async function pickProjectBundle(): Promise<{ path: string; manifest: BundleManifest } | null> {
+  const path = await open({ multiple: false, directory: false, filters: BUNDLE_FILTERS });
+  if (!path) return null;
+  return { path, manifest: await readProjectBundle(path) };
+}
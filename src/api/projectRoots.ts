@@ -0,0 +1,45 @@
+// Project Roots API
+import { invoke } from '../lib/invoke';
+import { peerConnection } from '../services/peerConnection';
+
+/** A folder a project spans besides its root path */
+export interface ProjectRoot {
+  id: string;
+  project_id: string;
+  /** Canonical absolute path */
+  path: string;
+  /** Shown instead of the folder name when set */
+  label: string | null;
+  created_at: number;
+}
+
+/**
+ * List the extra roots of a project
+ */
+export async function listProjectRoots(projectId: string): Promise<ProjectRoot[]> {
+  if (peerConnection.isClient) {
+    return peerConnection.sendCommand<ProjectRoot[]>('list_project_roots', { projectId });
+  }
+  return await invoke<ProjectRoot[]>('list_project_roots', { projectId });
+}
+
+/**
+ * Add a folder to a project. It shows up at the top of the file tree and is
+ * watched along with the project.
+ */
+export async function addProjectRoot(projectId: string, path: string, label?: string): Promise<ProjectRoot> {
+  if (peerConnection.isClient) {
+    return peerConnection.sendCommand<ProjectRoot>('add_project_root', { projectId, path, label });
+  }
+  return await invoke<ProjectRoot>('add_project_root', { projectId, path, label });
+}
+
+/**
+ * Remove a folder from a project; nothing on disk is touched
+ */
+export async function removeProjectRoot(rootId: string): Promise<void> {
+  if (peerConnection.isClient) {
+    return peerConnection.sendCommand<void>('remove_project_root', { rootId });
+  }
+  await invoke('remove_project_root', { rootId });
+}
@@ -0,0 +1,68 @@
+// Recent Projects API
+// Project visits and the CLI sessions worth resuming, for the home screen
+import { invoke } from '../lib/invoke';
+import { peerConnection } from '../services/peerConnection';
+import type { Project } from '../types/tauri';
+
+export interface RecentProject extends Project {
+  /** Unix seconds */
+  last_opened_at: number;
+  /** When the latest visit ended; null while open or if the app quit first */
+  last_closed_at: number | null;
+  open_count: number;
+}
+
+/** A CLI session that can be picked up again */
+export interface ResumableSession {
+  plugin_name: string;
+  agent_name: string;
+  cli_session_id: string;
+  started_at: number;
+  last_activity: number;
+  message_count: number;
+  /** The CLI's summary of the session, or else its first prompt */
+  summary: string | null;
+  /** Chat tab already showing the session */
+  tab_id: string | null;
+}
+
+/**
+ * Record that a project was opened
+ * @returns The visit id to pass to recordProjectClosed
+ */
+export async function recordProjectOpened(projectId: string): Promise<string> {
+  if (peerConnection.isClient) {
+    return peerConnection.sendCommand<string>('record_project_opened', { projectId });
+  }
+  return await invoke<string>('record_project_opened', { projectId });
+}
+
+/**
+ * Record that the user left a project
+ */
+export async function recordProjectClosed(visitId: string): Promise<void> {
+  if (peerConnection.isClient) {
+    return peerConnection.sendCommand<void>('record_project_closed', { visitId });
+  }
+  await invoke('record_project_closed', { visitId });
+}
+
+/**
+ * Projects by when they were last opened, most recent first
+ */
+export async function getRecentProjects(limit?: number): Promise<RecentProject[]> {
+  if (peerConnection.isClient) {
+    return peerConnection.sendCommand<RecentProject[]>('get_recent_projects', { limit });
+  }
+  return await invoke<RecentProject[]>('get_recent_projects', { limit });
+}
+
+/**
+ * A project's most recently active CLI sessions across installed agents
+ */
+export async function getResumableSessions(projectId: string, limit?: number): Promise<ResumableSession[]> {
+  if (peerConnection.isClient) {
+    return peerConnection.sendCommand<ResumableSession[]>('get_resumable_sessions', { projectId, limit });
+  }
+  return await invoke<ResumableSession[]>('get_resumable_sessions', { projectId, limit });
+}
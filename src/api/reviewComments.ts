@@ -1,5 +1,6 @@
 // Review Comments API functions for code review
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from '../lib/invoke';
+import type { FileChange } from './changes';
 
 /**
  * Review comment information
@@ -12,6 +13,20 @@ export interface ReviewComment {
   comment: string;
   timestamp: number;
   resolved: boolean;
+  suggestion_start_line: number | null; // First replaced line (1-based)
+  suggestion_end_line: number | null; // Last replaced line (inclusive)
+  suggestion: string | null; // Suggested replacement text
+}
+
+/**
+ * A suggested replacement for a range of lines
+ */
+export interface ReviewSuggestion {
+  text: string;
+  /** Defaults to the comment's line number */
+  startLine?: number;
+  /** Defaults to startLine */
+  endLine?: number;
 }
 
 /**
@@ -20,19 +35,24 @@ export interface ReviewComment {
  * @param author - The author of the comment (usually 'user')
  * @param comment - The comment text
  * @param lineNumber - Optional line number for inline comments
+ * @param suggestion - Optional replacement that applySuggestion can write to the file
  * @returns Promise with the created review comment
  */
 export async function addReviewComment(
   fileChangeId: string,
   author: string,
   comment: string,
-  lineNumber?: number
+  lineNumber?: number,
+  suggestion?: ReviewSuggestion
 ): Promise<ReviewComment> {
   return await invoke<ReviewComment>('add_review_comment', {
     fileChangeId,
     author,
     comment,
     lineNumber: lineNumber ?? null,
+    suggestion: suggestion?.text ?? null,
+    suggestionStartLine: suggestion?.startLine ?? null,
+    suggestionEndLine: suggestion?.endLine ?? null,
   });
 }
 
@@ -69,6 +89,17 @@ export async function unresolveReviewComment(commentId: string): Promise<ReviewC
   });
 }
 
+/**
+ * Apply a comment's suggestion to the file and resolve the comment
+ * @param commentId - The ID of a comment with a suggestion
+ * @returns Promise with the file change recorded for the edit
+ */
+export async function applySuggestion(commentId: string): Promise<FileChange> {
+  return await invoke<FileChange>('apply_suggestion', {
+    commentId,
+  });
+}
+
 /**
  * Delete a review comment
  * @param commentId - The ID of the comment
@@ -79,3 +110,27 @@ export async function deleteReviewComment(commentId: string): Promise<void> {
     commentId,
   });
 }
+
+/**
+ * Send a change's unresolved review comments to the agent as a fix request
+ * @param fileChangeId - The ID of the reviewed file change
+ * @param sessionId - Agent session to send to; defaults to the one that made the change
+ * @returns Promise with the ID of the session that received the request
+ */
+export async function requestChanges(fileChangeId: string, sessionId?: string): Promise<string> {
+  return await invoke<string>('request_changes', {
+    fileChangeId,
+    sessionId,
+  });
+}
+
+/**
+ * Run an AI review of a file change; findings are added as comments with author "ai"
+ * @param changeId - The ID of the file change
+ * @returns Promise with the comments the review added
+ */
+export async function aiReviewChange(changeId: string): Promise<ReviewComment[]> {
+  return await invoke<ReviewComment[]>('ai_review_change', {
+    changeId,
+  });
+}
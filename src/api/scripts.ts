@@ -0,0 +1,81 @@
+// Scripts API
+// Running a project's build, test and other scripts
+import { invoke } from '../lib/invoke';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+
+export interface ScriptConfig {
+  /** How the script is shown and run, e.g. "npm run build" */
+  name: string;
+  program: string;
+  args: string[];
+  /** File the script was found in */
+  source: string;
+}
+
+export type ScriptRunStatus = 'running' | 'succeeded' | 'failed' | 'cancelled';
+
+export interface ScriptRun {
+  id: string;
+  project_id: string;
+  name: string;
+  /** Full command line that ran */
+  command: string;
+  status: ScriptRunStatus;
+  exit_code: number | null;
+  /** Last lines of stdout and stderr */
+  output: string | null;
+  started_at: number;
+  finished_at: number | null;
+  duration_ms: number | null;
+}
+
+export interface ScriptOutput {
+  run_id: string;
+  stream: 'stdout' | 'stderr';
+  line: string;
+}
+
+/**
+ * The scripts the project defines (package.json, Cargo, Make, Go)
+ */
+export async function listScripts(projectId: string): Promise<ScriptConfig[]> {
+  return await invoke<ScriptConfig[]>('list_scripts', { projectId });
+}
+
+/**
+ * Start a script by name; returns the running run
+ */
+export async function runScript(projectId: string, name: string): Promise<ScriptRun> {
+  return await invoke<ScriptRun>('run_script', { projectId, name });
+}
+
+/**
+ * Stop a running script
+ * @returns false if it had already finished
+ */
+export async function cancelScript(runId: string): Promise<boolean> {
+  return await invoke<boolean>('cancel_script', { runId });
+}
+
+/**
+ * The project's recent runs, newest first
+ */
+export async function getScriptRuns(projectId: string, limit?: number): Promise<ScriptRun[]> {
+  return await invoke<ScriptRun[]>('get_script_runs', { projectId, limit });
+}
+
+/**
+ * Listen for lines printed by running scripts
+ * @returns Unlisten function
+ */
+export async function listenToScriptOutput(callback: (output: ScriptOutput) => void): Promise<UnlistenFn> {
+  return await listen<ScriptOutput>('script-output', (event) => callback(event.payload));
+}
+
+/**
+ * Listen for scripts finishing
+ * @returns Unlisten function
+ */
+export async function listenToScriptFinished(callback: (run: ScriptRun) => void): Promise<UnlistenFn> {
+  return await listen<ScriptRun>('script-finished', (event) => callback(event.payload));
+}
@@ -0,0 +1,114 @@
+// Session Replay API
+// Recorded agent session events, playing them back with their original timing,
+// and bookmarks marking moments in them
+import { invoke } from '../lib/invoke';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+
+/** An event parsed from agent output; `type` is e.g. file_changed or thinking */
+export interface ParsedAgentEvent {
+  type: string;
+  timestamp: number;
+  [field: string]: unknown;
+}
+
+export interface RecordedEvent {
+  /** 1-based order within the session */
+  seq: number;
+  /** Unix milliseconds when the event arrived */
+  recorded_at: number;
+  event: ParsedAgentEvent;
+}
+
+export interface SessionBookmark {
+  id: string;
+  session_id: string;
+  /** `seq` of the bookmarked event */
+  event_seq: number;
+  note: string;
+  /** Unix milliseconds when the bookmarked event arrived */
+  recorded_at: number;
+  /** Unix seconds */
+  created_at: number;
+}
+
+export interface ReplayInfo {
+  replay_id: string;
+  session_id: string;
+  events: number;
+  /** How long the replay takes, in milliseconds */
+  duration_ms: number;
+}
+
+export interface ReplayedEvent extends RecordedEvent {
+  replay_id: string;
+  session_id: string;
+}
+
+export interface ReplayFinished {
+  replay_id: string;
+  session_id: string;
+  /** Whether stopReplay ended it before its last event */
+  stopped: boolean;
+}
+
+/**
+ * A session's recorded events in order
+ */
+export async function getSessionEvents(sessionId: string): Promise<RecordedEvent[]> {
+  return await invoke<RecordedEvent[]>('get_session_events', { sessionId });
+}
+
+/**
+ * Play a session's events back `speed` times faster than they happened;
+ * pauses longer than 5 seconds are shortened
+ * @param fromSeq Start at this event, e.g. a bookmarked one
+ */
+export async function replaySession(sessionId: string, speed: number, fromSeq?: number): Promise<ReplayInfo> {
+  return await invoke<ReplayInfo>('replay_session', { sessionId, speed, fromSeq });
+}
+
+/**
+ * Stop a replay
+ * @returns false if it had already finished
+ */
+export async function stopReplay(replayId: string, sessionId: string): Promise<boolean> {
+  return await invoke<boolean>('stop_replay', { replayId, sessionId });
+}
+
+/**
+ * Bookmark a moment in a session's events; bookmarking an event again replaces its note
+ */
+export async function bookmarkSessionMoment(sessionId: string, eventSeq: number, note: string): Promise<SessionBookmark> {
+  return await invoke<SessionBookmark>('bookmark_session_moment', { sessionId, eventSeq, note });
+}
+
+/**
+ * A session's bookmarks in event order
+ */
+export async function listSessionBookmarks(sessionId: string): Promise<SessionBookmark[]> {
+  return await invoke<SessionBookmark[]>('list_session_bookmarks', { sessionId });
+}
+
+/**
+ * Remove a bookmark
+ * @returns false if it was already gone
+ */
+export async function deleteSessionBookmark(bookmarkId: string): Promise<boolean> {
+  return await invoke<boolean>('delete_session_bookmark', { bookmarkId });
+}
+
+/**
+ * Listen for events played back by replays
+ * @returns Unlisten function
+ */
+export async function listenToReplayEvents(callback: (event: ReplayedEvent) => void): Promise<UnlistenFn> {
+  return await listen<ReplayedEvent>('session-replay-event', (event) => callback(event.payload));
+}
+
+/**
+ * Listen for replays ending or being stopped
+ * @returns Unlisten function
+ */
+export async function listenToReplayFinished(callback: (finished: ReplayFinished) => void): Promise<UnlistenFn> {
+  return await listen<ReplayFinished>('session-replay-finished', (event) => callback(event.payload));
+}
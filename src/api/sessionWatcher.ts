@@ -3,7 +3,7 @@
  * Provides real-time updates for CLI session files
  */
 
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from '../lib/invoke';
 import { listen, UnlistenFn } from '@tauri-apps/api/event';
 
 // Types for the new plugin-based implementation
@@ -52,17 +52,42 @@ export interface SessionUpdate {
   status?: string;                     // for StatusChanged
 }
 
-interface SessionUpdateEvent {
+/** Payload of the `session-update://{tabId}` events */
+export interface SessionUpdateEvent {
+  tab_id: string;
   cli_session_id: string;
+  seq: number;                         // Counts up from 1 per tab
   update: SessionUpdate;
 }
 
+/** Event a tab's session updates arrive on */
+export function sessionUpdateEvent(tabId: string): string {
+  return `session-update://${tabId}`;
+}
+
+// The last seq each tab handled, kept across webview reloads
+const seqKey = (tabId: string) => `session-update-seq:${tabId}`;
+
+/**
+ * Get the events a tab missed after a seq and mark its window as listening
+ * @param tabId - The chat tab
+ * @param afterSeq - The last seq the tab handled
+ * @returns Promise with the buffered events after afterSeq, oldest first
+ */
+export async function resumeSessionUpdates(tabId: string, afterSeq: number): Promise<SessionUpdateEvent[]> {
+  return await invoke<SessionUpdateEvent[]>('resume_session_updates', { tabId, afterSeq });
+}
+
 /**
  * Start watching a CLI session with the new plugin-based implementation
  *
+ * Updates that arrived while the webview was reloading are replayed, and each
+ * update is delivered once even if it is both replayed and emitted.
+ *
  * @param pluginName - Name of the CLI plugin
  * @param projectPath - Path to the project directory
  * @param cliSessionId - Session ID to watch
+ * @param tabId - Chat tab the updates are for
  * @param callback - Function to call when updates occur
  * @returns Unsubscribe function that stops watching and removes the listener
  */
@@ -70,36 +95,60 @@ export async function startWatchingSession(
   pluginName: string,
   projectPath: string,
   cliSessionId: string,
+  tabId: string,
   callback: (update: SessionUpdate) => void
 ): Promise<() => Promise<void>> {
+  let lastSeq = Number(sessionStorage.getItem(seqKey(tabId)) ?? 0);
+  const deliver = (event: SessionUpdateEvent) => {
+    if (event.seq <= lastSeq) return;
+    lastSeq = event.seq;
+    sessionStorage.setItem(seqKey(tabId), String(lastSeq));
+    callback(event.update);
+  };
+
   // IMPORTANT: Set up event listener FIRST before starting the watcher
-  // This prevents a race condition where initial messages are sent before the listener is ready
+  // This prevents a race condition where initial messages are sent before the listener is ready.
+  // Live events are held until the replay below so they are handled in order.
+  let held: SessionUpdateEvent[] | null = [];
   const unlisten: UnlistenFn = await listen<SessionUpdateEvent>(
-    'session-update',
+    sessionUpdateEvent(tabId),
     (event) => {
-      // Filter events by cli_session_id
-      if (event.payload.cli_session_id === cliSessionId) {
-        callback(event.payload.update);
+      if (held) {
+        held.push(event.payload);
+      } else {
+        deliver(event.payload);
       }
     }
   );
 
-  // Now start watching the session - this will immediately send all existing messages
-  const watchId = await invoke<string>('start_watching_session', {
-    pluginName,
-    projectPath,
-    cliSessionId,
-  });
-
-  // Return unsubscribe function that includes all required parameters
-  return async () => {
-    // Stop the watcher - must pass all required parameters
-    await invoke('stop_watching_session', {
+  try {
+    // Now start watching the session - this will immediately send all existing messages
+    const watchId = await invoke<string>('start_watching_session', {
       pluginName,
-      watchId,
+      projectPath,
       cliSessionId,
+      tabId,
     });
-    // Remove the event listener
+
+    const missed = await resumeSessionUpdates(tabId, lastSeq);
+    missed.forEach(deliver);
+    held.forEach(deliver);
+    held = null;
+
+    // Return unsubscribe function that includes all required parameters
+    return async () => {
+      // Stop the watcher - must pass all required parameters
+      await invoke('stop_watching_session', {
+        pluginName,
+        watchId,
+        cliSessionId,
+      });
+      // Remove the event listener; the backend numbers the tab's updates from 1 again
+      unlisten();
+      sessionStorage.removeItem(seqKey(tabId));
+    };
+  } catch (error) {
     unlisten();
-  };
+    throw error;
+  }
 }
@@ -0,0 +1,43 @@
+// Text-to-speech API for reading agent replies aloud
+import { invoke } from '../lib/invoke';
+
+/**
+ * Options for speakText
+ */
+export interface SpeakOptions {
+  /** OS voice name; the system default when omitted */
+  voice?: string;
+  /** Words per minute */
+  rate?: number;
+  /** Read only the opening sentences of a long reply */
+  summarize?: boolean;
+}
+
+/**
+ * Speak text (Markdown is reduced to prose), stopping anything already playing
+ * @param text - The text to read
+ * @param options - Voice, rate and summarize
+ */
+export async function speakText(text: string, options?: SpeakOptions): Promise<void> {
+  await invoke('speak_text', {
+    text,
+    voice: options?.voice,
+    rate: options?.rate,
+    summarize: options?.summarize,
+  });
+}
+
+/**
+ * Stop speaking
+ * @returns Promise resolving to false if nothing was playing
+ */
+export async function stopSpeaking(): Promise<boolean> {
+  return await invoke<boolean>('stop_speaking');
+}
+
+/**
+ * Check whether text is currently being spoken
+ */
+export async function isSpeaking(): Promise<boolean> {
+  return await invoke<boolean>('is_speaking');
+}
@@ -1,5 +1,6 @@
 // Stats API wrapper for type-safe command invocations
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from '../lib/invoke';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
 import { peerConnection } from '../services/peerConnection';
 import type { ProjectStats } from '../types/tauri';
 
@@ -13,3 +14,144 @@ export async function getProjectStats(projectId: string): Promise<ProjectStats>
   }
   return await invoke<ProjectStats>('get_project_stats', { projectId });
 }
+
+/**
+ * Metrics available from getProjectTimeseries
+ */
+export type ProjectMetric =
+  | 'messages'
+  | 'file_changes'
+  | 'tasks_completed'
+  | 'agent_time'
+  | 'tokens'
+  | 'cost'
+  | 'response_time';
+
+/**
+ * One bucket of a time series; agent_time values are hours, cost is USD and
+ * response_time is the average seconds per reply
+ */
+export interface TimeseriesPoint {
+  bucket_start: number; // Unix timestamp in seconds (UTC)
+  value: number;
+}
+
+/**
+ * Get a project metric over time; buckets with no data are omitted
+ * @param projectId - The project ID
+ * @param metric - What to measure
+ * @param bucket - Bucket size; weeks start on Monday
+ * @param range - Optional Unix timestamp bounds in seconds
+ */
+export async function getProjectTimeseries(
+  projectId: string,
+  metric: ProjectMetric,
+  bucket: 'hour' | 'day' | 'week',
+  range?: { since?: number; until?: number }
+): Promise<TimeseriesPoint[]> {
+  const args = { projectId, metric, bucket, since: range?.since, until: range?.until };
+  if (peerConnection.isClient) {
+    return peerConnection.sendCommand<TimeseriesPoint[]>('get_project_timeseries', args);
+  }
+  return await invoke<TimeseriesPoint[]>('get_project_timeseries', args);
+}
+
+/**
+ * Spend and tokens for one project, agent or model
+ */
+export interface CostBreakdown {
+  key: string;
+  cost_usd: number;
+  input_tokens: number; // Includes cache reads and writes
+  output_tokens: number;
+}
+
+/**
+ * A project that has gone over its daily or monthly budget
+ */
+export interface BudgetAlert {
+  project_id: string;
+  project_name: string;
+  period: 'daily' | 'monthly';
+  spent_usd: number;
+  budget_usd: number;
+}
+
+/**
+ * Estimated agent spend over a period
+ */
+export interface CostSummary {
+  period: CostPeriod;
+  since: number; // Unix timestamp in seconds (UTC)
+  total_usd: number;
+  input_tokens: number;
+  output_tokens: number;
+  by_project: CostBreakdown[];
+  by_agent: CostBreakdown[];
+  by_model: CostBreakdown[];
+  by_day: { day_start: number; cost_usd: number }[];
+  budget_alerts: BudgetAlert[];
+}
+
+/**
+ * 'day' and 'month' are UTC calendar periods; 'week' is the last 7 days
+ */
+export type CostPeriod = 'day' | 'week' | 'month' | 'all';
+
+/**
+ * Estimate agent spend from session token usage
+ * @param projectId - Limit to one project, or omit for all projects
+ * @param period - How far back to look
+ */
+export async function getCostSummary(
+  projectId: string | undefined,
+  period: CostPeriod
+): Promise<CostSummary> {
+  if (peerConnection.isClient) {
+    return peerConnection.sendCommand<CostSummary>('get_cost_summary', { projectId, period });
+  }
+  return await invoke<CostSummary>('get_cost_summary', { projectId, period });
+}
+
+/**
+ * Listen for projects going over a budget
+ * @returns Unlisten function
+ */
+export async function listenToBudgetAlerts(
+  callback: (alert: BudgetAlert) => void
+): Promise<UnlistenFn> {
+  return await listen<BudgetAlert>('cost-budget-exceeded', (event) => callback(event.payload));
+}
+
+/**
+ * Lines an agent session changed in one file
+ */
+export interface FileChangeStat {
+  path: string;
+  lines_added: number;
+  lines_removed: number;
+}
+
+/**
+ * What an agent session did; files are sorted by lines changed
+ */
+export interface SessionChangeStats {
+  session_id: string;
+  files: FileChangeStat[];
+  lines_added: number;
+  lines_removed: number;
+  commands_run: number;
+  tests_passed: number;
+  tests_failed: number;
+}
+
+/**
+ * Summarize the files, commands and tests of an agent session
+ * @param sessionId - The agent session ID
+ */
+export async function getSessionChangeStats(sessionId: string): Promise<SessionChangeStats> {
+  if (peerConnection.isClient) {
+    return peerConnection.sendCommand<SessionChangeStats>('get_session_change_stats', { sessionId });
+  }
+  return await invoke<SessionChangeStats>('get_session_change_stats', { sessionId });
+}
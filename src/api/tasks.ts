@@ -1,5 +1,5 @@
 // Task API wrapper for type-safe command invocations
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from '../lib/invoke';
 import type {
   Task,
   CreateTaskInput,
@@ -0,0 +1,71 @@
+// Terminal API
+// Shells on a pseudo-terminal in the project's folder
+import { invoke } from '../lib/invoke';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+
+export interface TerminalInfo {
+  terminal_id: string;
+  project_id: string;
+  cwd: string;
+  created_at: number;
+}
+
+export interface TerminalOutput {
+  terminal_id: string;
+  data: string;
+}
+
+export interface TerminalExit {
+  terminal_id: string;
+  /** null when the shell was killed */
+  exit_code: number | null;
+}
+
+/**
+ * Open a terminal in the project's folder, or a folder inside it
+ */
+export async function openTerminal(projectId: string, options: { cwd?: string; cols?: number; rows?: number } = {}): Promise<TerminalInfo> {
+  return await invoke<TerminalInfo>('open_terminal', { projectId, ...options });
+}
+
+/**
+ * Send input to the terminal; Enter is "\r" and Ctrl+C is "\x03"
+ */
+export async function writeTerminal(terminalId: string, data: string): Promise<void> {
+  return await invoke<void>('write_terminal', { terminalId, data });
+}
+
+export async function resizeTerminal(terminalId: string, cols: number, rows: number): Promise<void> {
+  return await invoke<void>('resize_terminal', { terminalId, cols, rows });
+}
+
+/**
+ * Kill the terminal's shell
+ * @returns false if it had already exited
+ */
+export async function closeTerminal(terminalId: string): Promise<boolean> {
+  return await invoke<boolean>('close_terminal', { terminalId });
+}
+
+/**
+ * The project's open terminals, oldest first
+ */
+export async function listTerminals(projectId: string): Promise<TerminalInfo[]> {
+  return await invoke<TerminalInfo[]>('list_terminals', { projectId });
+}
+
+/**
+ * Listen for output from all terminals
+ * @returns Unlisten function
+ */
+export async function listenToTerminalOutput(callback: (output: TerminalOutput) => void): Promise<UnlistenFn> {
+  return await listen<TerminalOutput>('terminal-output', (event) => callback(event.payload));
+}
+
+/**
+ * Listen for terminals whose shell exited
+ * @returns Unlisten function
+ */
+export async function listenToTerminalExit(callback: (exit: TerminalExit) => void): Promise<UnlistenFn> {
+  return await listen<TerminalExit>('terminal-exit', (event) => callback(event.payload));
+}
@@ -0,0 +1,52 @@
+// Tool Calls API
+// The tools agents used in a session, e.g. every Bash command it ran
+import { invoke } from '../lib/invoke';
+import { peerConnection } from '../services/peerConnection';
+
+export interface ToolCall {
+  id: string; // The tool use id from the CLI
+  project_id: string;
+  cli_session_id: string;
+  message_id: string;
+  tool_name: string;
+  input: string; // JSON
+  result: string | null; // Null while the tool is running
+  is_error: boolean;
+  started_at: number; // Unix timestamp in seconds
+  completed_at: number | null;
+  duration_secs: number | null;
+}
+
+/**
+ * Tool calls in call order
+ * @param projectId - The project ID
+ * @param filter - Limit to one CLI session and/or one tool (e.g. 'Bash')
+ */
+export async function getToolCalls(
+  projectId: string,
+  filter?: { cliSessionId?: string; toolName?: string; limit?: number }
+): Promise<ToolCall[]> {
+  const args = {
+    projectId,
+    cliSessionId: filter?.cliSessionId,
+    toolName: filter?.toolName,
+    limit: filter?.limit,
+  };
+  if (peerConnection.isClient) {
+    return peerConnection.sendCommand<ToolCall[]>('get_tool_calls', args);
+  }
+  return await invoke<ToolCall[]>('get_tool_calls', args);
+}
+
+/**
+ * Each tool used with how many times, most used first
+ */
+export async function getToolCallCounts(
+  projectId: string,
+  cliSessionId?: string
+): Promise<[string, number][]> {
+  if (peerConnection.isClient) {
+    return peerConnection.sendCommand<[string, number][]>('get_tool_call_counts', { projectId, cliSessionId });
+  }
+  return await invoke<[string, number][]>('get_tool_call_counts', { projectId, cliSessionId });
+}
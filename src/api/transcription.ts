@@ -0,0 +1,171 @@
+// Transcription API for live dictation and voice prompts
+import { invoke } from '../lib/invoke';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+import type { TranscriptionOptions, TranscriptionResult } from '../services/backend/types';
+
+/**
+ * Result of transcribeAndSend
+ */
+export interface VoiceSendResult {
+  transcription: TranscriptionResult;
+  /** History entry for the transcript; null when it was empty */
+  transcription_id: string | null;
+  /** False when the text is waiting for confirmation or was empty */
+  sent: boolean;
+  session_id: string | null;
+}
+
+/**
+ * A dictated prompt kept in the project's transcription history
+ */
+export interface Transcription {
+  id: string;
+  project_id: string;
+  text: string;
+  duration: number | null; // Seconds of audio
+  provider: string;
+  model: string | null;
+  language: string | null;
+  message_id: string | null; // Set once the text was sent
+  created_at: number; // Unix timestamp in seconds
+}
+
+/**
+ * Voice settings used by transcribeAndSend
+ */
+export interface VoicePromptOptions extends TranscriptionOptions {
+  provider: 'local' | 'openai';
+  model?: string;
+  apiKey?: string;
+  /** Only transcribe; send the reviewed text later with sendToTab */
+  confirm?: boolean;
+}
+
+/**
+ * Payload of the transcription-partial event
+ */
+export interface TranscriptionPartial {
+  stream_id: string;
+  text: string;
+  is_final: boolean;
+}
+
+/**
+ * Start a streaming transcription; loads the Whisper model once
+ * @param model - Whisper model name (e.g. 'base')
+ * @param options - Spoken language and translate-to-English
+ * @returns The stream ID to push audio chunks to
+ */
+export async function startTranscriptionStream(
+  model: string,
+  options?: TranscriptionOptions
+): Promise<string> {
+  return await invoke<string>('start_transcription_stream', {
+    model,
+    language: options?.language,
+    translate: options?.translate,
+  });
+}
+
+/**
+ * Append a recorded chunk; chunks must be consecutive pieces of one recording
+ * @param streamId - The stream ID from startTranscriptionStream
+ * @param chunk - Raw audio bytes (e.g. a MediaRecorder timeslice)
+ */
+export async function pushTranscriptionChunk(streamId: string, chunk: Uint8Array): Promise<void> {
+  await invoke('push_transcription_chunk', { streamId, chunk: Array.from(chunk) });
+}
+
+/**
+ * Finish a stream and get the transcript of the whole recording
+ * @param streamId - The stream ID from startTranscriptionStream
+ */
+export async function finishTranscriptionStream(streamId: string): Promise<TranscriptionResult> {
+  return await invoke<TranscriptionResult>('finish_transcription_stream', { streamId });
+}
+
+/**
+ * Discard a stream without transcribing
+ * @param streamId - The stream ID from startTranscriptionStream
+ * @returns Promise resolving to false if the stream was already closed
+ */
+export async function cancelTranscriptionStream(streamId: string): Promise<boolean> {
+  return await invoke<boolean>('cancel_transcription_stream', { streamId });
+}
+
+/**
+ * Transcribe a recorded prompt and send it to a chat tab's session
+ * @param tabId - The tab whose session receives the prompt
+ * @param audioData - The recorded audio
+ * @param options - Provider, model and language settings
+ */
+export async function transcribeAndSend(
+  tabId: string,
+  audioData: Uint8Array,
+  options: VoicePromptOptions
+): Promise<VoiceSendResult> {
+  return await invoke<VoiceSendResult>('transcribe_and_send', {
+    tabId,
+    audioData: Array.from(audioData),
+    provider: options.provider,
+    model: options.model,
+    apiKey: options.apiKey,
+    language: options.language,
+    translate: options.translate,
+    confirm: options.confirm,
+  });
+}
+
+/**
+ * Listen for partial transcripts from all streams
+ * @param onPartial - Called with each partial transcript, and once with is_final set
+ * @returns Function that stops listening
+ */
+export async function listenToTranscription(
+  onPartial: (event: TranscriptionPartial) => void
+): Promise<UnlistenFn> {
+  return await listen<TranscriptionPartial>('transcription-partial', (e) => onPartial(e.payload));
+}
+
+/**
+ * Save a transcript to the project's history
+ * @param projectId - The project the prompt was dictated in
+ * @param text - The transcribed text
+ * @param details - Provider, model, language, duration and sent message
+ */
+export async function saveTranscription(
+  projectId: string,
+  text: string,
+  details: {
+    provider: string;
+    duration?: number;
+    model?: string;
+    language?: string;
+    messageId?: string;
+  }
+): Promise<Transcription> {
+  return await invoke<Transcription>('save_transcription', { projectId, text, ...details });
+}
+
+/**
+ * List a project's transcriptions, newest first
+ * @param projectId - The project ID
+ * @param limit - Maximum entries to return (default 50)
+ */
+export async function listTranscriptions(projectId: string, limit?: number): Promise<Transcription[]> {
+  return await invoke<Transcription[]>('list_transcriptions', { projectId, limit });
+}
+
+/**
+ * Record the message a transcript was sent as
+ */
+export async function linkTranscriptionMessage(transcriptionId: string, messageId: string): Promise<boolean> {
+  return await invoke<boolean>('link_transcription_message', { transcriptionId, messageId });
+}
+
+/**
+ * Delete a transcript from the history
+ */
+export async function deleteTranscription(transcriptionId: string): Promise<boolean> {
+  return await invoke<boolean>('delete_transcription', { transcriptionId });
+}
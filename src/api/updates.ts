@@ -0,0 +1,52 @@
+// App Updates API
+import { invoke } from '../lib/invoke';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+
+export type UpdateChannel = 'stable' | 'beta';
+
+export interface UpdateInfo {
+  version: string;
+  current_version: string;
+  channel: UpdateChannel;
+  /** Release notes */
+  notes: string | null;
+  /** Publish date */
+  date: string | null;
+}
+
+/**
+ * Check the selected channel for a newer version
+ * @returns The update, or null when up to date
+ */
+export async function checkForUpdates(): Promise<UpdateInfo | null> {
+  return await invoke<UpdateInfo | null>('check_for_updates');
+}
+
+/**
+ * Download and install the latest update; the app restarts when it's done
+ */
+export async function installUpdate(): Promise<void> {
+  return await invoke<void>('install_update');
+}
+
+/**
+ * Listen for newer versions found by background or manual checks
+ * @returns Unlisten function
+ */
+export async function listenToUpdateAvailable(
+  callback: (update: UpdateInfo) => void
+): Promise<UnlistenFn> {
+  return await listen<UpdateInfo>('update-available', (event) => callback(event.payload));
+}
+
+/**
+ * Listen for download progress while an update installs
+ * @returns Unlisten function
+ */
+export async function listenToUpdateProgress(
+  callback: (progress: { downloaded: number; total: number | null }) => void
+): Promise<UnlistenFn> {
+  return await listen<{ downloaded: number; total: number | null }>('update-download-progress', (event) =>
+    callback(event.payload)
+  );
+}
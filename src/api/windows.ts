@@ -0,0 +1,35 @@
+// Windows API
+// Opening projects in their own windows
+import { invoke } from '../lib/invoke';
+import { getCurrentWindow } from '@tauri-apps/api/window';
+
+/** Label of the window the app starts with */
+export const MAIN_WINDOW = 'main';
+
+export interface WindowContext {
+  label: string;
+  /** Set when the window was opened for a single project */
+  project_id: string | null;
+}
+
+/**
+ * Open a project in its own window, or focus the one it already has
+ * @returns The window label
+ */
+export async function openProjectWindow(projectId: string): Promise<string> {
+  return await invoke<string>('open_project_window', { projectId });
+}
+
+/**
+ * Get the label and project of the calling window
+ */
+export async function getWindowContext(): Promise<WindowContext> {
+  return await invoke<WindowContext>('get_window_context');
+}
+
+/**
+ * Whether this is the main window; tray and deep link navigation only happens there
+ */
+export function isMainWindow(): boolean {
+  return getCurrentWindow().label === MAIN_WINDOW;
+}
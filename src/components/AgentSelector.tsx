@@ -1,7 +1,9 @@
 import { useEffect, useState } from 'react';
-import { Bot, Check, AlertCircle } from 'lucide-react';
+import { Bot, Check, AlertCircle, LogIn } from 'lucide-react';
 import type { AgentType, AgentInfo } from '../types/project';
 import * as tauriApi from '../lib/tauri';
+import { getAgentAuthStatus, type AgentAuthStatus } from '../api/agentAuth';
+import AgentLoginModal from './modals/AgentLoginModal';
 
 interface AgentOption {
   type: AgentType;
@@ -46,6 +48,8 @@ const agentMetadata: Record<string, { displayName: string; description: string;
 export default function AgentSelector({ value, onChange, error }: AgentSelectorProps) {
   const [agents, setAgents] = useState<AgentOption[]>([]);
   const [isDetecting, setIsDetecting] = useState(true);
+  const [authStatus, setAuthStatus] = useState<Record<string, AgentAuthStatus>>({});
+  const [loginAgent, setLoginAgent] = useState<AgentOption | null>(null);
 
   // Detect installed agents on mount
   useEffect(() => {
@@ -97,6 +101,16 @@ export default function AgentSelector({ value, onChange, error }: AgentSelectorP
 
     detectInstalledAgents();
   }, []);
+
+  // Check the selected agent is signed in, so auth errors show up before the first session
+  const selectedInstalled = agents.some((agent) => agent.type === value && agent.installed);
+  useEffect(() => {
+    if (!value || !selectedInstalled) return;
+    getAgentAuthStatus(value)
+      .then((status) => setAuthStatus((current) => ({ ...current, [value]: status })))
+      .catch((err) => console.debug('No auth status for agent:', err));
+  }, [value, selectedInstalled]);
+
   return (
     <div className="form-control w-full">
       <label className="label">
@@ -170,6 +184,34 @@ export default function AgentSelector({ value, onChange, error }: AgentSelectorP
                 </div>
               )}
 
+              {/* Sign-in Status */}
+              {value === agent.type && authStatus[agent.type] && (
+                authStatus[agent.type].logged_in ? (
+                  <div className="text-xs text-success mt-3" title={authStatus[agent.type].detail}>
+                    <Check className="w-3 h-3 inline mr-1" />
+                    {authStatus[agent.type].method === 'api_key' ? 'Using an API key' : 'Signed in'}
+                  </div>
+                ) : (
+                  <div className="alert alert-warning mt-3 py-2 px-3">
+                    <AlertCircle className="w-4 h-4" />
+                    <span className="text-xs flex-1">{authStatus[agent.type].detail}</span>
+                    {authStatus[agent.type].can_login && (
+                      <button
+                        type="button"
+                        className="btn btn-xs gap-1"
+                        onClick={(e) => {
+                          e.stopPropagation();
+                          setLoginAgent(agent);
+                        }}
+                      >
+                        <LogIn className="w-3 h-3" />
+                        Sign in
+                      </button>
+                    )}
+                  </div>
+                )
+              )}
+
               {/* Radio Input (Hidden but accessible) */}
               <input
                 type="radio"
@@ -196,6 +238,19 @@ export default function AgentSelector({ value, onChange, error }: AgentSelectorP
           Choose an AI agent to assist with your project
         </span>
       </label>
+
+      {loginAgent && (
+        <AgentLoginModal
+          isOpen
+          pluginName={loginAgent.type}
+          displayName={loginAgent.name}
+          onClose={() => setLoginAgent(null)}
+          onFinished={(status) => {
+            setAuthStatus((current) => ({ ...current, [status.plugin_name]: status }));
+            if (status.logged_in) setLoginAgent(null);
+          }}
+        />
+      )}
     </div>
   );
 }
@@ -0,0 +1,84 @@
+import { useEffect, useState } from 'react';
+import { useNavigate } from 'react-router-dom';
+import { History, MessageSquare } from 'lucide-react';
+import { getRecentProjects, getResumableSessions, type RecentProject, type ResumableSession } from '../api/recentProjects';
+import { importCliSessions } from '../api/chatTabs';
+import type { DeepLink } from '../api/deepLinks';
+
+/** Sessions offered for the last opened project */
+const SESSION_COUNT = 3;
+
+/**
+ * Offers the last opened project's most recent agent sessions, opening the
+ * chosen one in its chat tab (imported first when it has none)
+ */
+export default function ContinueWhereLeftOff() {
+  const navigate = useNavigate();
+  const [project, setProject] = useState<RecentProject | null>(null);
+  const [sessions, setSessions] = useState<ResumableSession[]>([]);
+  const [resuming, setResuming] = useState<string | null>(null);
+
+  useEffect(() => {
+    let cancelled = false;
+    getRecentProjects(1)
+      .then(async ([last]) => {
+        if (!last || cancelled) return;
+        const found = await getResumableSessions(last.id, SESSION_COUNT);
+        if (cancelled) return;
+        setProject(last);
+        setSessions(found);
+      })
+      .catch((err) => console.debug('No sessions to resume:', err));
+    return () => {
+      cancelled = true;
+    };
+  }, []);
+
+  if (!project || sessions.length === 0) return null;
+
+  const resume = async (session: ResumableSession) => {
+    setResuming(session.cli_session_id);
+    try {
+      if (!session.tab_id) {
+        await importCliSessions(project.id, session.plugin_name, project.root_path, [session.cli_session_id]);
+      }
+      const deepLink: DeepLink = { action: 'open_session', project_id: project.id, session_id: session.cli_session_id };
+      navigate(`/workspace/${project.id}`, { state: { deepLink } });
+    } catch (err) {
+      alert(`Failed to resume session: ${err}`);
+      setResuming(null);
+    }
+  };
+
+  return (
+    <div>
+      <h2 className="text-2xl font-bold mb-4 flex items-center gap-2">
+        <History className="w-6 h-6" />
+        Continue in {project.name}
+      </h2>
+      <div className="grid grid-cols-1 md:grid-cols-3 gap-4">
+        {sessions.map((session) => (
+          <button
+            key={session.cli_session_id}
+            onClick={() => resume(session)}
+            disabled={resuming !== null}
+            className="card bg-base-200 hover:shadow-lg transition-all duration-200 text-left"
+          >
+            <div className="card-body p-4 gap-2">
+              <p className="text-sm line-clamp-2">{session.summary || 'Untitled session'}</p>
+              <div className="flex items-center gap-2 text-xs text-base-content/60">
+                {resuming === session.cli_session_id ? (
+                  <span className="loading loading-spinner loading-xs" />
+                ) : (
+                  <MessageSquare className="w-3 h-3" />
+                )}
+                <span>{session.message_count} messages</span>
+                <span className="badge badge-ghost badge-sm">{session.agent_name}</span>
+              </div>
+            </div>
+          </button>
+        ))}
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,35 @@
+import { useEffect } from 'react';
+import { useNavigate } from 'react-router-dom';
+import { listenToDeepLinks, takePendingDeepLinks, type DeepLink } from '../api/deepLinks';
+import { isMainWindow } from '../api/windows';
+import { isTauri } from '../lib/platform';
+
+/**
+ * Opens the workspace an ateliercode:// link points to
+ *
+ * Session and new-agent links are passed on to the workspace in the route state.
+ */
+export default function DeepLinkNavigator() {
+  const navigate = useNavigate();
+
+  useEffect(() => {
+    // Project windows stay on their own project
+    if (!isTauri() || !isMainWindow()) return;
+
+    const open = (link: DeepLink) => navigate(`/workspace/${link.project_id}`, { state: { deepLink: link } });
+    // Drain the queue rather than using the event payload, so a link is never handled twice
+    const openPending = () => {
+      takePendingDeepLinks()
+        .then((links) => links.forEach(open))
+        .catch((error) => console.error('Failed to read deep links:', error));
+    };
+
+    openPending();
+    const unlisten = listenToDeepLinks(openPending);
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [navigate]);
+
+  return null;
+}
@@ -0,0 +1,40 @@
+/**
+ * DefaultAgentSelect - Picks the agent new chat tabs start with, from the installed plugins
+ */
+
+import { useEffect, useState } from 'react';
+import { getPlugins } from '../api/agents';
+
+interface DefaultAgentSelectProps {
+  value: string | null | undefined;
+  onChange: (agent: string | null) => void;
+  /** Shown for "no default", e.g. what the fallback is */
+  emptyLabel: string;
+}
+
+export default function DefaultAgentSelect({ value, onChange, emptyLabel }: DefaultAgentSelectProps) {
+  const [plugins, setPlugins] = useState<{ name: string; display_name: string }[]>([]);
+
+  useEffect(() => {
+    getPlugins()
+      .then(setPlugins)
+      .catch((err) => console.error('Failed to list plugins:', err));
+  }, []);
+
+  return (
+    <select
+      className="select select-bordered select-sm w-full max-w-xs"
+      value={value ?? ''}
+      onChange={(e) => onChange(e.target.value || null)}
+    >
+      <option value="">{emptyLabel}</option>
+      {plugins.map((plugin) => (
+        <option key={plugin.name} value={plugin.name}>
+          {plugin.display_name}
+        </option>
+      ))}
+      {/* Keep a configured agent whose plugin is gone selectable */}
+      {value && !plugins.some((plugin) => plugin.name === value) && <option value={value}>{value}</option>}
+    </select>
+  );
+}
@@ -0,0 +1,96 @@
+import { useEffect, useState } from 'react';
+import { useNavigate } from 'react-router-dom';
+import { CheckCircle, Circle, Rocket } from 'lucide-react';
+import { completeOnboardingStep, getOnboardingState, type OnboardingState, type OnboardingStep } from '../api/onboarding';
+import { detectAgents } from '../api/agents';
+
+const STEPS: { step: OnboardingStep; title: string; hint: string }[] = [
+  {
+    step: 'plugins_detected',
+    title: 'Install an agent',
+    hint: 'Install an agent CLI such as Claude Code, Aider or Gemini, then check again.',
+  },
+  {
+    step: 'first_project_created',
+    title: 'Create a project',
+    hint: 'Point AtelierCode at a folder you want an agent to work in.',
+  },
+  {
+    step: 'first_session_run',
+    title: 'Run your first session',
+    hint: 'Open the project, go to Chat and send the agent a task.',
+  },
+];
+
+/**
+ * Checklist guiding new users through setup, hidden once every step is done
+ */
+export default function GettingStarted() {
+  const navigate = useNavigate();
+  const [state, setState] = useState<OnboardingState | null>(null);
+  const [isDetecting, setIsDetecting] = useState(false);
+
+  const detect = async () => {
+    setIsDetecting(true);
+    try {
+      const agents = await detectAgents();
+      if (agents.some((agent) => agent.available)) {
+        setState(await completeOnboardingStep('plugins_detected'));
+      }
+    } catch (err) {
+      console.error('Failed to detect agents:', err);
+    } finally {
+      setIsDetecting(false);
+    }
+  };
+
+  useEffect(() => {
+    getOnboardingState()
+      .then((loaded) => {
+        setState(loaded);
+        if (loaded.current_step === 'plugins_detected') detect();
+      })
+      .catch((err) => console.debug('No onboarding state:', err));
+  }, []);
+
+  if (!state || state.finished) return null;
+
+  const done = new Set(state.completed.map((c) => c.step));
+
+  return (
+    <div className="card bg-base-200 w-full max-w-md mb-8">
+      <div className="card-body gap-3">
+        <h2 className="card-title">
+          <Rocket className="w-5 h-5" />
+          Getting Started
+        </h2>
+        {STEPS.map(({ step, title, hint }) => {
+          const isCurrent = state.current_step === step;
+          return (
+            <div key={step} className="flex items-start gap-3">
+              {done.has(step) ? (
+                <CheckCircle className="w-5 h-5 text-success shrink-0" />
+              ) : (
+                <Circle className="w-5 h-5 text-base-content/40 shrink-0" />
+              )}
+              <div className="flex-1">
+                <p className={isCurrent ? 'font-semibold' : 'text-base-content/70'}>{title}</p>
+                {isCurrent && <p className="text-sm text-base-content/60">{hint}</p>}
+                {isCurrent && step === 'plugins_detected' && (
+                  <button onClick={detect} disabled={isDetecting} className="btn btn-xs btn-ghost mt-1">
+                    {isDetecting ? <span className="loading loading-spinner loading-xs" /> : 'Check again'}
+                  </button>
+                )}
+                {isCurrent && step === 'first_project_created' && (
+                  <button onClick={() => navigate('/wizard')} className="btn btn-xs btn-primary mt-1">
+                    New Project
+                  </button>
+                )}
+              </div>
+            </div>
+          );
+        })}
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,75 @@
+/**
+ * JobsIndicator - Footer dropdown showing background jobs that are still running
+ */
+
+import { useEffect, useState } from 'react';
+import { Loader2, X } from 'lucide-react';
+import { cancelJob, isJobActive, listJobs, listenToJobUpdates, type Job } from '../api/jobs';
+import { isTauri } from '../lib/platform';
+
+export default function JobsIndicator() {
+  const [jobs, setJobs] = useState<Job[]>([]);
+
+  useEffect(() => {
+    if (!isTauri()) return;
+
+    listJobs()
+      .then((recent) => setJobs(recent.filter(isJobActive)))
+      .catch((error) => console.error('Failed to load jobs:', error));
+
+    const unlisten = listenToJobUpdates((job) =>
+      setJobs((current) => {
+        const others = current.filter((j) => j.id !== job.id);
+        return isJobActive(job) ? [job, ...others] : others;
+      })
+    );
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  if (jobs.length === 0) return null;
+
+  const handleCancel = async (jobId: string) => {
+    try {
+      await cancelJob(jobId);
+    } catch (error) {
+      console.error('Failed to cancel job:', error);
+    }
+  };
+
+  return (
+    <div className="dropdown dropdown-top dropdown-end">
+      <div tabIndex={0} role="button" className="btn btn-ghost btn-xs gap-2">
+        <Loader2 className="w-3 h-3 animate-spin" />
+        {jobs.length} {jobs.length === 1 ? 'job' : 'jobs'} running
+      </div>
+      <ul tabIndex={0} className="dropdown-content z-[60] mb-2 w-80 rounded-box bg-base-200 p-2 shadow-lg border border-base-300">
+        {jobs.map((job) => (
+          <li key={job.id} className="p-2">
+            <div className="flex items-center gap-2">
+              <span className="flex-1 truncate text-sm" title={job.title}>
+                {job.title}
+              </span>
+              <button className="btn btn-ghost btn-xs btn-square" onClick={() => handleCancel(job.id)} title="Cancel">
+                <X className="w-3 h-3" />
+              </button>
+            </div>
+            {job.status === 'queued' ? (
+              <div className="text-xs text-base-content/60">Queued</div>
+            ) : (
+              <>
+                <progress
+                  className="progress progress-primary w-full"
+                  value={job.progress != null ? job.progress * 100 : undefined}
+                  max={100}
+                />
+                {job.message && <div className="text-xs text-base-content/60 truncate">{job.message}</div>}
+              </>
+            )}
+          </li>
+        ))}
+      </ul>
+    </div>
+  );
+}
@@ -0,0 +1,39 @@
+/**
+ * ReadOnlyIndicator - Footer badge shown while another machine's database is open read-only
+ */
+
+import { useEffect, useState } from 'react';
+import { Eye } from 'lucide-react';
+import { closeReadOnlyDatabase, getDatabaseMode, type DatabaseMode } from '../api/appSettings';
+import { isTauri } from '../lib/platform';
+
+export default function ReadOnlyIndicator() {
+  const [mode, setMode] = useState<DatabaseMode | null>(null);
+
+  useEffect(() => {
+    if (!isTauri()) return;
+    getDatabaseMode()
+      .then(setMode)
+      .catch((error) => console.error('Failed to get database mode:', error));
+  }, []);
+
+  if (!mode?.read_only) return null;
+
+  const handleClose = async () => {
+    try {
+      await closeReadOnlyDatabase();
+    } catch (error) {
+      console.error('Failed to close read-only database:', error);
+    }
+  };
+
+  return (
+    <div className="flex items-center gap-2 text-xs text-warning" title={mode.path ?? undefined}>
+      <Eye className="w-4 h-4" />
+      <span>Read-only: another machine's database</span>
+      <button onClick={handleClose} className="btn btn-xs btn-ghost">
+        Back to this machine
+      </button>
+    </div>
+  );
+}
@@ -0,0 +1,24 @@
+import { useEffect } from 'react';
+import { useNavigate } from 'react-router-dom';
+import { listenToTrayOpenProject } from '../api/appSettings';
+import { isMainWindow } from '../api/windows';
+import { isTauri } from '../lib/platform';
+
+/**
+ * Opens the workspace of a project picked from the system tray menu
+ */
+export default function TrayNavigator() {
+  const navigate = useNavigate();
+
+  useEffect(() => {
+    // Project windows stay on their own project
+    if (!isTauri() || !isMainWindow()) return;
+
+    const unlisten = listenToTrayOpenProject((projectId) => navigate(`/workspace/${projectId}`));
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [navigate]);
+
+  return null;
+}
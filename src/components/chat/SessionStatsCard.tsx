@@ -0,0 +1,73 @@
+/**
+ * SessionStatsCard - Summary of what the agent session changed, shown when a turn completes
+ */
+
+import { useState } from 'react';
+import { FileText, FlaskConical, Terminal, X } from 'lucide-react';
+import type { SessionChangeStats } from '../../api/stats';
+
+interface SessionStatsCardProps {
+  stats: SessionChangeStats;
+  onDismiss: () => void;
+}
+
+/** Files listed before "Show all" */
+const COLLAPSED_FILES = 5;
+
+export default function SessionStatsCard({ stats, onDismiss }: SessionStatsCardProps) {
+  const [showAll, setShowAll] = useState(false);
+
+  const testsRun = stats.tests_passed + stats.tests_failed;
+  if (stats.files.length === 0 && stats.commands_run === 0 && testsRun === 0) return null;
+
+  const files = showAll ? stats.files : stats.files.slice(0, COLLAPSED_FILES);
+
+  return (
+    <div className="card bg-base-200 border border-base-300 text-sm">
+      <div className="card-body p-3 gap-2">
+        <div className="flex items-center gap-4">
+          <span className="flex items-center gap-1.5">
+            <FileText className="w-4 h-4" />
+            {stats.files.length} {stats.files.length === 1 ? 'file' : 'files'}
+            <span className="text-success">+{stats.lines_added}</span>
+            <span className="text-error">-{stats.lines_removed}</span>
+          </span>
+          <span className="flex items-center gap-1.5">
+            <Terminal className="w-4 h-4" />
+            {stats.commands_run} {stats.commands_run === 1 ? 'command' : 'commands'}
+          </span>
+          {testsRun > 0 && (
+            <span className="flex items-center gap-1.5">
+              <FlaskConical className="w-4 h-4" />
+              <span className="text-success">{stats.tests_passed} passed</span>
+              {stats.tests_failed > 0 && <span className="text-error">{stats.tests_failed} failed</span>}
+            </span>
+          )}
+          <span className="flex-1" />
+          <button className="btn btn-ghost btn-xs btn-circle" onClick={onDismiss} title="Dismiss">
+            <X className="w-3 h-3" />
+          </button>
+        </div>
+
+        {files.length > 0 && (
+          <ul className="font-mono text-xs space-y-0.5">
+            {files.map((file) => (
+              <li key={file.path} className="flex gap-2">
+                <span className="truncate flex-1" title={file.path}>
+                  {file.path}
+                </span>
+                <span className="text-success w-12 text-right">+{file.lines_added}</span>
+                <span className="text-error w-12 text-right">-{file.lines_removed}</span>
+              </li>
+            ))}
+          </ul>
+        )}
+        {stats.files.length > COLLAPSED_FILES && (
+          <button className="btn btn-ghost btn-xs self-start" onClick={() => setShowAll(!showAll)}>
+            {showAll ? 'Show less' : `Show all ${stats.files.length} files`}
+          </button>
+        )}
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,127 @@
+import { useEffect, useState } from 'react';
+import { X, Terminal, Loader2, AlertCircle } from 'lucide-react';
+import { getToolCallCounts, getToolCalls, type ToolCall } from '../../api/toolCalls';
+
+export interface ToolCallsModalProps {
+  isOpen: boolean;
+  projectId: string;
+  cliSessionId: string;
+  onClose: () => void;
+}
+
+/**
+ * One-line summary of a call's input, e.g. the command Bash ran
+ */
+function inputSummary(call: ToolCall): string {
+  try {
+    const input = JSON.parse(call.input) as Record<string, unknown>;
+    const key = ['command', 'file_path', 'path', 'pattern', 'url', 'description'].find(
+      (k) => typeof input[k] === 'string'
+    );
+    return key ? (input[key] as string) : call.input;
+  } catch {
+    return call.input;
+  }
+}
+
+export default function ToolCallsModal({ isOpen, projectId, cliSessionId, onClose }: ToolCallsModalProps) {
+  const [counts, setCounts] = useState<[string, number][]>([]);
+  const [toolName, setToolName] = useState<string>('Bash');
+  const [calls, setCalls] = useState<ToolCall[]>([]);
+  const [expandedId, setExpandedId] = useState<string | null>(null);
+  const [isLoading, setIsLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (!isOpen) return;
+    setError(null);
+    getToolCallCounts(projectId, cliSessionId)
+      .then(setCounts)
+      .catch((err) => setError(String(err)));
+  }, [isOpen, projectId, cliSessionId]);
+
+  useEffect(() => {
+    if (!isOpen) return;
+    setIsLoading(true);
+    setExpandedId(null);
+    getToolCalls(projectId, { cliSessionId, toolName: toolName || undefined })
+      .then(setCalls)
+      .catch((err) => setError(String(err)))
+      .finally(() => setIsLoading(false));
+  }, [isOpen, projectId, cliSessionId, toolName]);
+
+  if (!isOpen) return null;
+
+  return (
+    <div className="modal modal-open">
+      <div className="modal-box max-w-4xl">
+        <div className="flex justify-between items-start mb-4">
+          <div className="flex items-center gap-2">
+            <Terminal className="w-5 h-5 text-primary" />
+            <h3 className="font-bold text-lg">Tool Calls</h3>
+          </div>
+          <button onClick={onClose} className="btn btn-sm btn-circle btn-ghost">
+            <X className="w-4 h-4" />
+          </button>
+        </div>
+
+        {error && <div className="alert alert-error text-sm mb-4">{error}</div>}
+
+        <div className="flex flex-wrap gap-1 mb-4">
+          <button
+            className={`btn btn-xs ${toolName === '' ? 'btn-primary' : 'btn-ghost'}`}
+            onClick={() => setToolName('')}
+          >
+            All
+          </button>
+          {counts.map(([name, count]) => (
+            <button
+              key={name}
+              className={`btn btn-xs ${toolName === name ? 'btn-primary' : 'btn-ghost'}`}
+              onClick={() => setToolName(name)}
+            >
+              {name}
+              <span className="badge badge-sm">{count}</span>
+            </button>
+          ))}
+        </div>
+
+        {isLoading ? (
+          <div className="flex justify-center py-8">
+            <Loader2 className="w-6 h-6 animate-spin text-primary" />
+          </div>
+        ) : calls.length === 0 ? (
+          <p className="text-sm text-base-content/60 py-8 text-center">No tool calls recorded for this session</p>
+        ) : (
+          <ul className="space-y-1 max-h-[60vh] overflow-y-auto">
+            {calls.map((call) => (
+              <li key={call.id} className="rounded bg-base-200">
+                <button
+                  className="w-full flex items-center gap-2 px-3 py-2 text-left"
+                  onClick={() => setExpandedId(expandedId === call.id ? null : call.id)}
+                >
+                  {call.is_error && <AlertCircle className="w-4 h-4 text-error shrink-0" />}
+                  {!toolName && <span className="badge badge-sm badge-outline shrink-0">{call.tool_name}</span>}
+                  <code className="text-xs truncate flex-1">{inputSummary(call)}</code>
+                  <span className="text-xs text-base-content/50 shrink-0">
+                    {call.result === null
+                      ? 'running'
+                      : call.duration_secs !== null
+                        ? `${call.duration_secs}s`
+                        : ''}
+                  </span>
+                </button>
+                {expandedId === call.id && (
+                  <pre className="text-xs px-3 pb-3 whitespace-pre-wrap break-all max-h-64 overflow-y-auto">
+                    {call.result ?? 'No result yet'}
+                  </pre>
+                )}
+              </li>
+            ))}
+          </ul>
+        )}
+      </div>
+      <div className="modal-backdrop" onClick={onClose}></div>
+    </div>
+  );
+}
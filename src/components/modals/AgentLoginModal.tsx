@@ -0,0 +1,125 @@
+import { useEffect, useRef, useState } from 'react';
+import { X, LogIn, Loader2 } from 'lucide-react';
+import { getAgentAuthStatus, startAgentLogin, type AgentAuthStatus } from '../../api/agentAuth';
+import { closeTerminal, listenToTerminalExit, listenToTerminalOutput, writeTerminal } from '../../api/terminal';
+import { appendOutput } from '../workspace/TerminalTab';
+
+export interface AgentLoginModalProps {
+  isOpen: boolean;
+  pluginName: string;
+  displayName: string;
+  onClose: () => void;
+  /** Called with the agent's status once the login flow exits */
+  onFinished: (status: AgentAuthStatus) => void;
+}
+
+export default function AgentLoginModal({ isOpen, pluginName, displayName, onClose, onFinished }: AgentLoginModalProps) {
+  const [terminalId, setTerminalId] = useState<string | null>(null);
+  const [output, setOutput] = useState('');
+  const [input, setInput] = useState('');
+  const [isRunning, setIsRunning] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const outputRef = useRef<HTMLPreElement>(null);
+  const onFinishedRef = useRef(onFinished);
+  onFinishedRef.current = onFinished;
+
+  useEffect(() => {
+    if (!isOpen) return;
+    let id: string | null = null;
+    let exited = false;
+    setOutput('');
+    setError(null);
+
+    const unlistenOutput = listenToTerminalOutput(({ terminal_id, data }) => {
+      if (terminal_id === id) setOutput((current) => appendOutput(current, data));
+    });
+    const unlistenExit = listenToTerminalExit(({ terminal_id, exit_code }) => {
+      if (terminal_id !== id) return;
+      exited = true;
+      setIsRunning(false);
+      setOutput((current) => `${current}\n[Login exited${exit_code != null ? ` with code ${exit_code}` : ''}]\n`);
+      getAgentAuthStatus(pluginName)
+        .then((status) => onFinishedRef.current(status))
+        .catch((err) => setError(String(err)));
+    });
+
+    // Listen before starting, so the CLI's first prompt isn't missed
+    Promise.all([unlistenOutput, unlistenExit])
+      .then(() => startAgentLogin(pluginName))
+      .then((terminal) => {
+        id = terminal.terminal_id;
+        setTerminalId(id);
+        setIsRunning(true);
+      })
+      .catch((err) => setError(String(err)));
+
+    return () => {
+      unlistenOutput.then((fn) => fn());
+      unlistenExit.then((fn) => fn());
+      if (id && !exited) {
+        closeTerminal(id).catch((err) => console.error('Failed to stop login:', err));
+      }
+      setTerminalId(null);
+      setIsRunning(false);
+    };
+  }, [isOpen, pluginName]);
+
+  useEffect(() => {
+    outputRef.current?.scrollTo({ top: outputRef.current.scrollHeight });
+  }, [output]);
+
+  if (!isOpen) return null;
+
+  const handleKeyDown = (e: React.KeyboardEvent<HTMLInputElement>) => {
+    if (!terminalId) return;
+    if (e.key === 'Enter') {
+      writeTerminal(terminalId, `${input}\r`).catch((err) => setError(String(err)));
+      setInput('');
+    } else if (e.key === 'c' && e.ctrlKey) {
+      e.preventDefault();
+      writeTerminal(terminalId, '\x03').catch((err) => setError(String(err)));
+    }
+  };
+
+  return (
+    <div className="modal modal-open">
+      <div className="modal-box max-w-3xl">
+        <div className="flex justify-between items-start mb-4">
+          <div className="flex items-center gap-2">
+            <LogIn className="w-5 h-5 text-primary" />
+            <h3 className="font-bold text-lg">Sign in to {displayName}</h3>
+            {isRunning && <Loader2 className="w-4 h-4 animate-spin text-base-content/50" />}
+          </div>
+          <button onClick={onClose} className="btn btn-sm btn-circle btn-ghost">
+            <X className="w-4 h-4" />
+          </button>
+        </div>
+
+        {error && <div className="alert alert-error text-sm mb-4">{error}</div>}
+
+        <p className="text-sm text-base-content/70 mb-2">
+          Follow the CLI's prompts. If it opens a browser and shows a code, paste the code below.
+        </p>
+
+        <pre
+          ref={outputRef}
+          className="bg-base-300 rounded-lg p-3 h-72 overflow-auto text-xs font-mono whitespace-pre-wrap break-all"
+        >
+          {output}
+        </pre>
+
+        <input
+          type="text"
+          value={input}
+          onChange={(e) => setInput(e.target.value)}
+          onKeyDown={handleKeyDown}
+          disabled={!isRunning}
+          placeholder="Type a response and press Enter"
+          className="input input-bordered input-sm w-full font-mono mt-2"
+          autoFocus
+        />
+      </div>
+      <div className="modal-backdrop" onClick={onClose}></div>
+    </div>
+  );
+}
@@ -0,0 +1,159 @@
+/**
+ * CloneRepositoryModal - Creates a project by cloning a git repository, showing clone progress
+ */
+
+import { useEffect, useState } from 'react';
+import { GitBranch, X } from 'lucide-react';
+import FolderPicker from '../FolderPicker';
+import * as agentApi from '../../api/agents';
+import type { DetectedAgent } from '../../api/agents';
+import { createProjectFromGit, repoName } from '../../api/gitClone';
+import { cancelJob, listenToJobUpdates, type Job } from '../../api/jobs';
+import type { Project } from '../../types/tauri';
+
+interface CloneRepositoryModalProps {
+  isOpen: boolean;
+  onClose: () => void;
+  onCreated: (project: Project) => void;
+}
+
+export default function CloneRepositoryModal({ isOpen, onClose, onCreated }: CloneRepositoryModalProps) {
+  const [url, setUrl] = useState('');
+  const [parentDir, setParentDir] = useState('');
+  const [agents, setAgents] = useState<DetectedAgent[]>([]);
+  const [agentType, setAgentType] = useState('');
+  const [job, setJob] = useState<Job | null>(null);
+  const [isCloning, setIsCloning] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (!isOpen) return;
+    agentApi
+      .detectAgents()
+      .then((detected) => {
+        setAgents(detected);
+        const firstAvailable = detected.find((a) => a.available) ?? detected[0];
+        setAgentType((current) => current || firstAvailable?.name || '');
+      })
+      .catch((err) => console.error('Failed to detect agents:', err));
+  }, [isOpen]);
+
+  // Follow the clone job while it runs
+  useEffect(() => {
+    if (!isCloning) return;
+    const title = `Clone ${url.trim()}`;
+    const unlisten = listenToJobUpdates((update) => {
+      if (update.kind === 'git_clone' && update.title === title) setJob(update);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [isCloning, url]);
+
+  if (!isOpen) return null;
+
+  const name = repoName(url);
+  const separator = parentDir.includes('\\') ? '\\' : '/';
+  const dest = parentDir && name ? `${parentDir.replace(/[/\\]+$/, '')}${separator}${name}` : '';
+
+  const handleClone = async () => {
+    if (!dest || !agentType) return;
+    setError(null);
+    setJob(null);
+    setIsCloning(true);
+    try {
+      const project = await createProjectFromGit(url.trim(), dest, agentType);
+      onCreated(project);
+    } catch (err) {
+      setError(String(err));
+    } finally {
+      setIsCloning(false);
+    }
+  };
+
+  const handleCancel = async () => {
+    if (isCloning && job) {
+      await cancelJob(job.id).catch((err) => console.error('Failed to cancel clone:', err));
+    } else {
+      onClose();
+    }
+  };
+
+  return (
+    <div className="modal modal-open">
+      <div className="modal-box max-w-lg">
+        <div className="flex items-center justify-between mb-4">
+          <h3 className="font-bold text-lg flex items-center gap-2">
+            <GitBranch className="w-5 h-5 text-primary" />
+            Clone Repository
+          </h3>
+          {!isCloning && (
+            <button onClick={onClose} className="btn btn-ghost btn-sm btn-circle">
+              <X className="w-4 h-4" />
+            </button>
+          )}
+        </div>
+
+        <div className="space-y-4">
+          <div className="form-control w-full">
+            <label className="label">
+              <span className="label-text font-medium">Repository URL</span>
+            </label>
+            <input
+              type="text"
+              value={url}
+              onChange={(e) => setUrl(e.target.value)}
+              placeholder="https://github.com/owner/repo.git"
+              className="input input-bordered w-full font-mono text-sm"
+              disabled={isCloning}
+            />
+          </div>
+
+          <FolderPicker value={parentDir} onChange={setParentDir} label="Clone Into" required />
+          {dest && <div className="text-xs text-base-content/60 font-mono truncate">{dest}</div>}
+
+          <div className="form-control w-full">
+            <label className="label">
+              <span className="label-text font-medium">AI Agent</span>
+            </label>
+            <select
+              className="select select-bordered w-full"
+              value={agentType}
+              onChange={(e) => setAgentType(e.target.value)}
+              disabled={isCloning}
+            >
+              {agents.map((agent) => (
+                <option key={agent.name} value={agent.name} disabled={!agent.available}>
+                  {agent.displayName || agent.name}
+                  {!agent.available && ' (not installed)'}
+                </option>
+              ))}
+            </select>
+          </div>
+
+          {isCloning && (
+            <div>
+              <progress
+                className="progress progress-primary w-full"
+                value={job?.progress != null ? job.progress * 100 : undefined}
+                max={100}
+              />
+              <div className="text-xs text-base-content/60">{job?.message ?? 'Starting clone...'}</div>
+            </div>
+          )}
+
+          {error && <div className="alert alert-error text-sm">{error}</div>}
+        </div>
+
+        <div className="modal-action">
+          <button className="btn btn-ghost" onClick={handleCancel}>
+            Cancel
+          </button>
+          <button className="btn btn-primary" onClick={handleClone} disabled={isCloning || !dest || !agentType}>
+            {isCloning ? <span className="loading loading-spinner loading-sm" /> : 'Clone'}
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,81 @@
+/**
+ * CommandApprovalModal - Asks before an agent runs a shell command on the project's deny list
+ */
+
+import { useEffect, useState } from 'react';
+import { ShieldAlert } from 'lucide-react';
+import {
+  approveCommand,
+  getPendingCommands,
+  listenToCommandApprovals,
+  listenToCommandApprovalsResolved,
+  type PendingCommand,
+} from '../../api/commandGate';
+import { isTauri } from '../../lib/platform';
+
+export default function CommandApprovalModal() {
+  const [pending, setPending] = useState<PendingCommand[]>([]);
+
+  useEffect(() => {
+    if (!isTauri()) return;
+
+    getPendingCommands()
+      .then(setPending)
+      .catch((error) => console.error('Failed to load pending commands:', error));
+
+    const unlistenRequested = listenToCommandApprovals((command) =>
+      setPending((current) => (current.some((c) => c.id === command.id) ? current : [...current, command]))
+    );
+    // Another window may have answered, or the request timed out
+    const unlistenResolved = listenToCommandApprovalsResolved((requestId) =>
+      setPending((current) => current.filter((c) => c.id !== requestId))
+    );
+    return () => {
+      unlistenRequested.then((fn) => fn());
+      unlistenResolved.then((fn) => fn());
+    };
+  }, []);
+
+  const current = pending[0];
+  if (!current) return null;
+
+  const answer = async (approved: boolean) => {
+    setPending((list) => list.filter((c) => c.id !== current.id));
+    try {
+      await approveCommand(current.id, approved);
+    } catch (error) {
+      console.error('Failed to answer command approval:', error);
+    }
+  };
+
+  return (
+    <div className="modal modal-open">
+      <div className="modal-box max-w-lg">
+        <h3 className="font-bold text-lg flex items-center gap-2 mb-4">
+          <ShieldAlert className="w-5 h-5 text-warning" />
+          Approve Command?
+        </h3>
+
+        <p className="text-sm text-base-content/70 mb-2">
+          The agent wants to run a command matching <code className="font-mono">{current.pattern}</code>.
+        </p>
+        <pre className="bg-base-200 rounded-lg p-3 text-sm font-mono whitespace-pre-wrap break-all mb-4">
+          {current.command}
+        </pre>
+
+        {pending.length > 1 && (
+          <p className="text-xs text-base-content/50 mb-2">{pending.length - 1} more waiting</p>
+        )}
+
+        <div className="modal-action">
+          <button className="btn btn-ghost" onClick={() => answer(false)}>
+            Deny
+          </button>
+          <button className="btn btn-warning" onClick={() => answer(true)}>
+            Run Command
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,133 @@
+import { useState } from 'react';
+import { History, MessageSquare } from 'lucide-react';
+import { importCliSessions, type DetectedCliSessions } from '../../api/chatTabs';
+import { useSessionStore } from '../../stores/sessionStore';
+
+interface ImportCliSessionsModalProps {
+  projectId: string;
+  projectPath: string;
+  detected: DetectedCliSessions[];
+  /** Called after importing or skipping */
+  onDone: () => void;
+}
+
+/**
+ * Offers to open the sessions agent CLIs already have for a new project as chat tabs
+ */
+export default function ImportCliSessionsModal({ projectId, projectPath, detected, onDone }: ImportCliSessionsModalProps) {
+  // "plugin:session" keys of the sessions to import; all of them to begin with
+  const [selected, setSelected] = useState<Set<string>>(
+    () => new Set(detected.flatMap((group) => group.sessions.map((s) => `${group.plugin_name}:${s.session_id}`)))
+  );
+  const [isImporting, setIsImporting] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const setActiveSession = useSessionStore((state) => state.setActiveSession);
+
+  const toggle = (key: string) => {
+    setSelected((current) => {
+      const next = new Set(current);
+      if (next.has(key)) {
+        next.delete(key);
+      } else {
+        next.add(key);
+      }
+      return next;
+    });
+  };
+
+  const handleImport = async () => {
+    setIsImporting(true);
+    setError(null);
+    try {
+      for (const group of detected) {
+        const ids = group.sessions
+          .map((s) => s.session_id)
+          .filter((id) => selected.has(`${group.plugin_name}:${id}`));
+        if (ids.length === 0) continue;
+
+        const tabs = await importCliSessions(projectId, group.plugin_name, projectPath, ids);
+        // Attach each session to its tab so the history loads when the tab is opened
+        const now = Date.now() / 1000;
+        for (const tab of tabs) {
+          setActiveSession(tab.id, {
+            session_id: tab.cli_session_id!,
+            project_id: projectId,
+            agent_type: group.plugin_name,
+            status: 'stopped',
+            pid: null,
+            started_at: now,
+            last_activity: now,
+            claude_session_id: tab.cli_session_id,
+          });
+        }
+      }
+      onDone();
+    } catch (err) {
+      setError(String(err));
+      setIsImporting(false);
+    }
+  };
+
+  const formatDate = (timestamp: number) => new Date(timestamp * 1000).toLocaleString();
+
+  return (
+    <div className="modal modal-open">
+      <div className="modal-box max-w-2xl">
+        <h3 className="font-bold text-lg flex items-center gap-2">
+          <History className="w-5 h-5" />
+          Import Existing Sessions
+        </h3>
+        <p className="text-sm text-base-content/70 mt-1">
+          Agent sessions from the terminal were found for this folder. Each one you import opens as a chat tab.
+        </p>
+
+        {error && <div className="alert alert-error text-sm mt-3">{error}</div>}
+
+        <div className="space-y-4 mt-4 max-h-96 overflow-y-auto">
+          {detected.map((group) => (
+            <div key={group.plugin_name}>
+              <h4 className="font-semibold text-sm mb-2">{group.display_name}</h4>
+              <div className="space-y-1">
+                {group.sessions.map((session) => {
+                  const key = `${group.plugin_name}:${session.session_id}`;
+                  return (
+                    <label key={key} className="flex items-center gap-3 p-2 rounded-lg hover:bg-base-200 cursor-pointer">
+                      <input
+                        type="checkbox"
+                        className="checkbox checkbox-sm"
+                        checked={selected.has(key)}
+                        onChange={() => toggle(key)}
+                      />
+                      <div className="flex-1 min-w-0">
+                        <div className="text-sm truncate">
+                          {session.last_message_preview || <span className="text-base-content/50">No preview</span>}
+                        </div>
+                        <div className="text-xs text-base-content/50 flex items-center gap-2">
+                          <span>{formatDate(session.created_at)}</span>
+                          <span className="flex items-center gap-1">
+                            <MessageSquare className="w-3 h-3" />
+                            {session.message_count}
+                          </span>
+                        </div>
+                      </div>
+                    </label>
+                  );
+                })}
+              </div>
+            </div>
+          ))}
+        </div>
+
+        <div className="modal-action">
+          <button className="btn btn-ghost" onClick={onDone} disabled={isImporting}>
+            Skip
+          </button>
+          <button className="btn btn-primary" onClick={handleImport} disabled={isImporting || selected.size === 0}>
+            {isImporting && <span className="loading loading-spinner loading-sm"></span>}
+            Import {selected.size} {selected.size === 1 ? 'session' : 'sessions'}
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+}
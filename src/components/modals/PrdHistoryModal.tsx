@@ -0,0 +1,126 @@
+import { useEffect, useState } from 'react';
+import { X, History, RotateCcw, Sparkles, User } from 'lucide-react';
+import { diffPrdRevisions, getPrdHistory, restorePrdRevision, type PrdRevision } from '../../api/prdHistory';
+import type { Project } from '../../types/tauri';
+
+export interface PrdHistoryModalProps {
+  isOpen: boolean;
+  projectId: string;
+  onClose: () => void;
+  onRestored: (project: Project) => void;
+}
+
+function diffLineClass(line: string): string {
+  if (line.startsWith('+++') || line.startsWith('---')) return 'text-base-content/50';
+  if (line.startsWith('+')) return 'bg-success/10 text-success';
+  if (line.startsWith('-')) return 'bg-error/10 text-error';
+  if (line.startsWith('@@')) return 'text-info';
+  return '';
+}
+
+export default function PrdHistoryModal({ isOpen, projectId, onClose, onRestored }: PrdHistoryModalProps) {
+  const [revisions, setRevisions] = useState<PrdRevision[]>([]);
+  const [selectedIndex, setSelectedIndex] = useState(0);
+  const [diff, setDiff] = useState<string | null>(null);
+  const [isRestoring, setIsRestoring] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (!isOpen) return;
+    setSelectedIndex(0);
+    setError(null);
+    getPrdHistory(projectId)
+      .then(setRevisions)
+      .catch((err) => setError(String(err)));
+  }, [isOpen, projectId]);
+
+  const selected = revisions[selectedIndex];
+  const previous = revisions[selectedIndex + 1];
+
+  // Show what the selected revision changed from the one before it
+  useEffect(() => {
+    setDiff(null);
+    if (!selected || !previous) return;
+    diffPrdRevisions(previous.id, selected.id)
+      .then(setDiff)
+      .catch((err) => setError(String(err)));
+  }, [selected, previous]);
+
+  if (!isOpen) return null;
+
+  const handleRestore = async () => {
+    if (!selected) return;
+    setIsRestoring(true);
+    setError(null);
+    try {
+      onRestored(await restorePrdRevision(selected.id));
+      onClose();
+    } catch (err) {
+      setError(String(err));
+    } finally {
+      setIsRestoring(false);
+    }
+  };
+
+  return (
+    <div className="modal modal-open">
+      <div className="modal-box max-w-4xl">
+        <div className="flex justify-between items-start mb-4">
+          <div className="flex items-center gap-2">
+            <History className="w-5 h-5 text-primary" />
+            <h3 className="font-bold text-lg">Description History</h3>
+          </div>
+          <button onClick={onClose} className="btn btn-sm btn-circle btn-ghost">
+            <X className="w-4 h-4" />
+          </button>
+        </div>
+
+        {error && <div className="alert alert-error text-sm mb-4">{error}</div>}
+
+        {revisions.length === 0 ? (
+          <p className="text-sm text-base-content/60 py-8 text-center">No earlier versions yet</p>
+        ) : (
+          <div className="flex gap-4 h-96">
+            <ul className="menu menu-sm bg-base-200 rounded-box w-56 shrink-0 overflow-y-auto flex-nowrap">
+              {revisions.map((revision, index) => (
+                <li key={revision.id}>
+                  <button className={index === selectedIndex ? 'active' : ''} onClick={() => setSelectedIndex(index)}>
+                    {revision.author === 'ai' ? <Sparkles className="w-4 h-4" /> : <User className="w-4 h-4" />}
+                    <span className="flex-1 text-left">{new Date(revision.created_at * 1000).toLocaleString()}</span>
+                    {index === 0 && <span className="badge badge-xs badge-primary">current</span>}
+                  </button>
+                </li>
+              ))}
+            </ul>
+
+            <div className="flex-1 overflow-auto bg-base-300 rounded p-3 font-mono text-xs">
+              {selected && !previous && <pre className="whitespace-pre-wrap">{selected.content}</pre>}
+              {previous && diff === '' && <p className="text-base-content/60">No changes from the previous version</p>}
+              {diff &&
+                diff.split('\n').map((line, index) => (
+                  <div key={index} className={`whitespace-pre-wrap ${diffLineClass(line)}`}>
+                    {line || ' '}
+                  </div>
+                ))}
+            </div>
+          </div>
+        )}
+
+        <div className="modal-action">
+          <button onClick={onClose} className="btn btn-ghost">
+            Close
+          </button>
+          <button
+            onClick={handleRestore}
+            className="btn btn-primary gap-2"
+            disabled={!selected || selectedIndex === 0 || isRestoring}
+          >
+            {isRestoring ? <span className="loading loading-spinner loading-xs"></span> : <RotateCcw className="w-4 h-4" />}
+            Restore This Version
+          </button>
+        </div>
+      </div>
+      <div className="modal-backdrop" onClick={onClose}></div>
+    </div>
+  );
+}
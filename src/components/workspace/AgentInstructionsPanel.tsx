@@ -0,0 +1,119 @@
+/**
+ * AgentInstructionsPanel - Edits the agent's instruction file (CLAUDE.md, GEMINI.md...) with an AI drafted starting point
+ */
+
+import { useEffect, useState } from 'react';
+import { FileText, Save, Sparkles } from 'lucide-react';
+import {
+  generateAgentInstructions,
+  getAgentInstructions,
+  setAgentInstructions,
+  type AgentInstructions,
+} from '../../api/agentInstructions';
+
+interface AgentInstructionsPanelProps {
+  projectId: string;
+}
+
+const AGENTS = [
+  { value: '', label: "Project's agent" },
+  { value: 'claude-code', label: 'Claude Code (CLAUDE.md)' },
+  { value: 'gemini', label: 'Gemini CLI (GEMINI.md)' },
+  { value: 'aider', label: 'Aider (CONVENTIONS.md)' },
+  { value: 'other', label: 'Other agents (AGENTS.md)' },
+];
+
+export default function AgentInstructionsPanel({ projectId }: AgentInstructionsPanelProps) {
+  const [agent, setAgent] = useState('');
+  const [file, setFile] = useState<AgentInstructions | null>(null);
+  const [draft, setDraft] = useState('');
+  const [isGenerating, setIsGenerating] = useState(false);
+  const [isSaving, setIsSaving] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    getAgentInstructions(projectId, agent || undefined)
+      .then((loaded) => {
+        setFile(loaded);
+        setDraft(loaded.content);
+        setError(null);
+      })
+      .catch((err) => setError(String(err)));
+  }, [projectId, agent]);
+
+  const handleGenerate = async () => {
+    if (draft.trim() && !confirm('Replace the current text with a generated draft?')) return;
+    setIsGenerating(true);
+    try {
+      setDraft(await generateAgentInstructions(projectId, agent || undefined));
+      setError(null);
+    } catch (err) {
+      setError(String(err));
+    } finally {
+      setIsGenerating(false);
+    }
+  };
+
+  const handleSave = async () => {
+    setIsSaving(true);
+    try {
+      const saved = await setAgentInstructions(projectId, draft, agent || undefined);
+      setFile(saved);
+      setDraft(saved.content);
+      setError(null);
+    } catch (err) {
+      setError(String(err));
+    } finally {
+      setIsSaving(false);
+    }
+  };
+
+  const isDirty = file !== null && draft !== file.content;
+
+  return (
+    <div className="card bg-base-200">
+      <div className="card-body gap-3">
+        <div className="flex items-center gap-2">
+          <h2 className="card-title flex-1">
+            <FileText className="w-5 h-5" />
+            Agent Instructions
+            {file && <code className="text-sm font-normal text-base-content/60">{file.path}</code>}
+          </h2>
+          <select className="select select-bordered select-sm" value={agent} onChange={(e) => setAgent(e.target.value)}>
+            {AGENTS.map((option) => (
+              <option key={option.value} value={option.value}>
+                {option.label}
+              </option>
+            ))}
+          </select>
+        </div>
+
+        {error && <div className="alert alert-error text-sm">{error}</div>}
+
+        <textarea
+          className="textarea textarea-bordered w-full font-mono text-sm"
+          rows={14}
+          value={draft}
+          onChange={(e) => setDraft(e.target.value)}
+          placeholder={file?.exists ? '' : `No ${file?.path ?? 'instruction file'} yet - write one or generate a draft`}
+        />
+
+        <div className="flex items-center gap-2">
+          <button className="btn btn-ghost btn-sm gap-1" onClick={handleGenerate} disabled={isGenerating}>
+            {isGenerating ? <span className="loading loading-spinner loading-xs"></span> : <Sparkles className="w-4 h-4" />}
+            Generate draft
+          </button>
+          <div className="flex-1" />
+          <button className="btn btn-primary btn-sm gap-1" onClick={handleSave} disabled={!isDirty || isSaving}>
+            <Save className="w-4 h-4" />
+            Save
+          </button>
+        </div>
+
+        <p className="text-xs text-base-content/60">
+          Drafts are built from the project analysis and PRD and aren't saved until you save them.
+        </p>
+      </div>
+    </div>
+  );
+}
@@ -1,5 +1,5 @@
 import { useState, useRef, useEffect, useCallback } from 'react';
-import { Send, Bot, Loader2, Sparkles, Power, PowerOff, History, X, Mic, Settings } from 'lucide-react';
+import { Send, Bot, Loader2, Sparkles, Power, PowerOff, History, X, Mic, Settings, Terminal } from 'lucide-react';
 import { Panel, PanelGroup } from 'react-resizable-panels';
 import * as agentSessionApi from '../../api/agentSession';
 import * as pluginChatApi from '../../lib/chat';
@@ -19,13 +19,19 @@ import QuickActions from '../chat/QuickActions';
 import MessageSkeleton from '../chat/MessageSkeleton';
 import MessageContent from '../chat/MessageContent';
 import SessionHistoryModal from '../chat/SessionHistoryModal';
+import ToolCallsModal from '../chat/ToolCallsModal';
 import VirtualizedMessageList from '../chat/VirtualizedMessageList';
 import UserPromptDialog from '../chat/UserPromptDialog';
 import ChatTabBar from '../chat/ChatTabBar';
 import VoiceRecordingModal from '../chat/VoiceRecordingModal';
+import SessionStatsCard from '../chat/SessionStatsCard';
 import PluginSettingsModal from '../modals/PluginSettingsModal';
 import { getPluginFlags } from '../../api/agents';
 import type { PluginFlag } from '../../api/pluginSettings';
+import { saveTranscription } from '../../api/transcription';
+import { getSessionChangeStats, type SessionChangeStats } from '../../api/stats';
+import type { TranscriptionResult } from '../../services/backend/types';
+import type { DeepLink } from '../../api/deepLinks';
 
 // Dynamic processing message generator - creates thousands of unique sayings
 const SAYING_VERBS = [
@@ -102,8 +108,13 @@ export type MessageStatus = 'sending' | 'sent' | 'streaming' | 'completed' | 'er
 export interface MessageMetadata {
   // Common fields
   tokensUsed?: number;
+  inputTokens?: number;
+  outputTokens?: number;
+  costUsd?: number;
+  toolCallIds?: string[];
   processingTime?: number;
   model?: string;
+  cancelled?: boolean;
   error?: string;
 
   // Tool message fields (set by plugins for merged tool messages)
@@ -127,6 +138,8 @@ export interface ChatMessage {
 
 export interface ChatTabProps {
   projectId?: string;
+  /** Session or new-agent link to act on once the tabs are loaded */
+  deepLink?: DeepLink;
 }
 
 
@@ -152,7 +165,7 @@ function convertMessage(backendMsg: BackendChatMessage): ChatMessage {
   };
 }
 
-export default function ChatTab({ projectId }: ChatTabProps) {
+export default function ChatTab({ projectId, deepLink }: ChatTabProps) {
   // Backend abstraction for Tauri/WebRTC
   const backend = useBackend();
 
@@ -213,6 +226,7 @@ export default function ChatTab({ projectId }: ChatTabProps) {
   // Session history is now loaded on-demand via plugin system in SessionHistoryModal
   const [isStartingSession, setIsStartingSession] = useState(false);
   const [showSessionHistory, setShowSessionHistory] = useState(false);
+  const [showToolCalls, setShowToolCalls] = useState(false);
   const [showPluginSettings, setShowPluginSettings] = useState(false);
   const [pluginFlags, setPluginFlags] = useState<PluginFlag[]>([]);
   const [isWaitingForInput, setIsWaitingForInput] = useState(false);
@@ -220,6 +234,10 @@ export default function ChatTab({ projectId }: ChatTabProps) {
   const [pendingUserPrompt, setPendingUserPrompt] = useState<UserPrompt | null>(null);
   const skipNextLoadRef = useRef(false); // Flag to skip the next loadData call
 
+  // Summary of the session's changes, shown once a turn completes
+  const [turnStats, setTurnStats] = useState<SessionChangeStats | null>(null);
+  const wasTypingRef = useRef(false);
+
   // Voice recording state
   const [showVoiceModal, setShowVoiceModal] = useState(false);
   const [isTranscribing, setIsTranscribing] = useState(false);
@@ -270,16 +288,49 @@ export default function ChatTab({ projectId }: ChatTabProps) {
   useEffect(() => {
     if (projectId) {
       loadTabs(projectId).then(() => {
-        // If no tabs exist, create a default tab with the project's agent
+        // If no tabs exist, create a default tab with the project's default agent
         // Only do this once per project
         const currentTabs = useChatTabStore.getState().tabsByProject.get(projectId) || [];
-        if (currentTabs.length === 0 && project?.agent_type && defaultTabCreatedRef.current !== projectId) {
+        if (currentTabs.length === 0 && defaultTabCreatedRef.current !== projectId) {
           defaultTabCreatedRef.current = projectId;
-          createTab(projectId, project.agent_type);
+          createTab(projectId);
         }
       });
     }
-  }, [projectId, loadTabs, createTab, project?.agent_type]);
+  }, [projectId, loadTabs, createTab]);
+
+  // Act on a deep link once per link: select the linked session's tab, or
+  // open a tab for the requested agent with the prompt in the composer (not sent)
+  const handledDeepLinkRef = useRef<DeepLink | null>(null);
+  useEffect(() => {
+    if (!projectId || !deepLink || deepLink.project_id !== projectId) return;
+    if (handledDeepLinkRef.current === deepLink || tabs.length === 0) return;
+    handledDeepLinkRef.current = deepLink;
+
+    if (deepLink.action === 'open_session') {
+      const tab = tabs.find(
+        (t) => t.cli_session_id === deepLink.session_id || t.session_id === deepLink.session_id || t.id === deepLink.session_id
+      );
+      if (tab) {
+        setActiveTabInStore(projectId, tab.id);
+      } else {
+        console.warn('[ChatTab] No tab for linked session:', deepLink.session_id);
+      }
+    } else if (deepLink.action === 'new_agent') {
+      const agentType = deepLink.agent_type || activeTab?.agent_type || project?.agent_type;
+      const openTab = agentType && agentType !== activeTab?.agent_type
+        ? createTab(projectId, agentType).then((tab) => setActiveTabInStore(projectId, tab.id))
+        : Promise.resolve();
+      openTab
+        .then(() => {
+          if (deepLink.prompt) {
+            setInputValue(deepLink.prompt);
+            textareaRef.current?.focus();
+          }
+        })
+        .catch((err) => console.error('[ChatTab] Failed to open linked agent:', err));
+    }
+  }, [projectId, deepLink, tabs, activeTab?.agent_type, project?.agent_type, createTab, setActiveTabInStore]);
 
   // Tab handlers
   const handleTabClick = useCallback(async (tabId: string) => {
@@ -320,21 +371,21 @@ export default function ChatTab({ projectId }: ChatTabProps) {
 
   const handleAddTab = useCallback(async () => {
     // Add a new tab with the project's default agent directly
-    console.log('[ChatTab] handleAddTab called, projectId:', projectId, 'agent_type:', project?.agent_type);
-    if (!projectId || !project?.agent_type) {
-      console.error('[ChatTab] Cannot add tab - missing projectId or agent type');
+    console.log('[ChatTab] handleAddTab called, projectId:', projectId);
+    if (!projectId) {
+      console.error('[ChatTab] Cannot add tab - missing projectId');
       return;
     }
     try {
       console.log('[ChatTab] Creating new tab...');
-      const newTab = await createTab(projectId, project.agent_type);
+      const newTab = await createTab(projectId);
       console.log('[ChatTab] New tab created:', newTab);
       await setActiveTabInStore(projectId, newTab.id);
       console.log('[ChatTab] Tab set as active');
     } catch (error) {
       console.error('[ChatTab] Failed to create new tab:', error);
     }
-  }, [projectId, project?.agent_type, createTab, setActiveTabInStore]);
+  }, [projectId, createTab, setActiveTabInStore]);
 
   const handleAgentSelect = useCallback(async (agentType: string) => {
     console.log('[ChatTab] handleAgentSelect called with agentType:', agentType, 'projectId:', projectId);
@@ -492,6 +543,25 @@ export default function ChatTab({ projectId }: ChatTabProps) {
     return () => clearInterval(interval);
   }, [isTyping]);
 
+  useEffect(() => {
+    wasTypingRef.current = false;
+    setTurnStats(null);
+  }, [activeTabId]);
+
+  // A turn has completed when the tab stops typing
+  useEffect(() => {
+    const wasTyping = wasTypingRef.current;
+    wasTypingRef.current = isTyping;
+    if (isTyping) {
+      setTurnStats(null);
+      return;
+    }
+    if (!wasTyping || !activeSession) return;
+    getSessionChangeStats(activeSession.session_id)
+      .then(setTurnStats)
+      .catch((err) => console.error('[ChatTab] Failed to load session stats:', err));
+  }, [isTyping, activeSession]);
+
   // Register UI callback for session watcher manager
   // This handles UI-specific updates like showing user prompt dialogs
   useEffect(() => {
@@ -543,7 +613,9 @@ export default function ChatTab({ projectId }: ChatTabProps) {
       const session = await agentSessionApi.startAgentSession(
         projectId,
         normalizedAgentType,
-        resumeSessionId
+        resumeSessionId,
+        undefined,
+        activeTab?.id
       );
       console.log('[ChatTab] Session started:', session);
 
@@ -1088,34 +1160,52 @@ export default function ChatTab({ projectId }: ChatTabProps) {
       const isWebm = first20[0] === 0x1A && first20[1] === 0x45 && first20[2] === 0xDF && first20[3] === 0xA3;
       console.log('[ChatTab] looks like valid webm:', isWebm);
 
-      let transcribedText: string;
+      // Language preferences belong to the speaker, so they always come from local settings
+      const languageOptions = {
+        language: whisperSettings.language,
+        translate: whisperSettings.translate,
+      };
 
+      let result: TranscriptionResult;
       if (whisperSettings.provider === 'openai') {
         // Use OpenAI API via backend
         console.log('[ChatTab] Using OpenAI Whisper API');
-        const result = await backend.transcription.transcribeOpenAI(
+        result = await backend.transcription.transcribeOpenAI(
           audioData,
-          whisperSettings.openaiApiKey
+          whisperSettings.openaiApiKey,
+          languageOptions
         );
-        transcribedText = result.text;
       } else {
         // Use local Whisper via backend
         console.log('[ChatTab] Using local Whisper with model:', whisperSettings.localModel);
-        const result = await backend.transcription.transcribeLocal(
+        result = await backend.transcription.transcribeLocal(
           audioData,
-          whisperSettings.localModel
+          whisperSettings.localModel,
+          languageOptions
         );
-        transcribedText = result.text;
       }
+      const transcribedText = result.text;
 
       console.log('[ChatTab] Transcription result:', transcribedText);
 
+      // Keep the transcript in the project's history (local only; remote hosts keep their own)
+      if (transcribedText.trim() && projectId && !peerConnection.isClient) {
+        saveTranscription(projectId, transcribedText.trim(), {
+          provider: whisperSettings.provider,
+          model: whisperSettings.provider === 'local' ? whisperSettings.localModel : 'whisper-1',
+          language: result.language,
+          duration: result.duration,
+        }).catch((err) => console.error('[ChatTab] Failed to save transcription:', err));
+      }
+
       // Close modal first
       setShowVoiceModal(false);
       setIsTranscribing(false);
 
-      // Send the transcribed text as a message
-      if (transcribedText.trim()) {
+      // With confirmation on, put the text in the input for review instead of sending
+      if (transcribedText.trim() && whisperSettings.confirmBeforeSend) {
+        setInputValue(transcribedText.trim());
+      } else if (transcribedText.trim()) {
         console.log('[ChatTab] Calling handleSend with transcribed text:', transcribedText.trim());
         // Use setTimeout to ensure state updates have propagated
         setTimeout(() => {
@@ -1247,6 +1337,17 @@ export default function ChatTab({ projectId }: ChatTabProps) {
             History
           </button>
 
+          {activeSession?.claude_session_id && (
+            <button
+              onClick={() => setShowToolCalls(true)}
+              className="btn btn-ghost btn-xs gap-1"
+              title="Tools this session used"
+            >
+              <Terminal className="w-3 h-3" />
+              Tools
+            </button>
+          )}
+
           <button
             onClick={async () => {
               const pluginName = currentAgentType?.toLowerCase().replace(/\s+/g, '-') || 'claude-code';
@@ -1354,6 +1455,10 @@ export default function ChatTab({ projectId }: ChatTabProps) {
               formatTimestamp={formatTimestamp}
             />
 
+            {turnStats && !isTyping && (
+              <SessionStatsCard stats={turnStats} onDismiss={() => setTurnStats(null)} />
+            )}
+
             {/* Typing Indicator with Cycling Messages */}
             {isTyping && (
               <div className="flex gap-3 items-center">
@@ -1502,6 +1607,16 @@ export default function ChatTab({ projectId }: ChatTabProps) {
         />
       )}
 
+      {/* Tool Calls Modal */}
+      {projectId && activeSession?.claude_session_id && (
+        <ToolCallsModal
+          isOpen={showToolCalls}
+          projectId={projectId}
+          cliSessionId={activeSession.claude_session_id}
+          onClose={() => setShowToolCalls(false)}
+        />
+      )}
+
       {/* Plugin Settings Modal */}
       {currentAgentType && (
         <PluginSettingsModal
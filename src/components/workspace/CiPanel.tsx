@@ -0,0 +1,99 @@
+/**
+ * CiPanel - Recent CI runs for the project's remote and whether HEAD passed
+ */
+
+import { useEffect, useState } from 'react';
+import { GitBranch, Loader2, ExternalLink } from 'lucide-react';
+import { getCiStatus, type CiRunStatus, type CiStatus } from '../../api/ci';
+
+interface CiPanelProps {
+  projectId: string;
+}
+
+const STATUS_BADGE: Record<CiRunStatus, string> = {
+  queued: 'badge-ghost',
+  running: 'badge-info',
+  success: 'badge-success',
+  failure: 'badge-error',
+  cancelled: 'badge-warning',
+  skipped: 'badge-ghost',
+};
+
+export default function CiPanel({ projectId }: CiPanelProps) {
+  const [status, setStatus] = useState<CiStatus | null>(null);
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const load = async () => {
+    setLoading(true);
+    setError(null);
+    try {
+      setStatus(await getCiStatus(projectId));
+    } catch (err) {
+      setError(String(err));
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  useEffect(() => {
+    setStatus(null);
+    load();
+  }, [projectId]);
+
+  // Nothing to show for projects without CI
+  if (status && status.configs.length === 0) return null;
+
+  const head = status?.head_run;
+
+  return (
+    <div className="card bg-base-200">
+      <div className="card-body p-4 gap-3">
+        <div className="flex items-center gap-2">
+          <h3 className="font-semibold flex-1 flex items-center gap-2">
+            <GitBranch className="w-4 h-4" />
+            CI
+            {head && <span className={`badge badge-sm ${STATUS_BADGE[head.status]}`}>HEAD {head.status}</span>}
+          </h3>
+          <button className="btn btn-ghost btn-sm gap-2" onClick={load} disabled={loading}>
+            {loading && <Loader2 className="w-4 h-4 animate-spin" />}
+            Refresh
+          </button>
+        </div>
+
+        {error && <div className="alert alert-error text-sm">{error}</div>}
+
+        {status && !status.provider && (
+          <p className="text-sm text-base-content/60">
+            Found {status.configs.flatMap((config) => config.files).join(', ')}, but the origin remote isn't on GitHub or GitLab
+          </p>
+        )}
+        {status?.provider && status.runs.length === 0 && (
+          <p className="text-sm text-base-content/60">No runs yet</p>
+        )}
+        {status && status.runs.length > 0 && (
+          <table className="table table-xs">
+            <tbody>
+              {status.runs.map((run) => (
+                <tr key={run.id} className={run.commit_sha === status.head_sha ? 'font-semibold' : ''}>
+                  <td>
+                    <span className={`badge badge-sm ${STATUS_BADGE[run.status]}`}>{run.status}</span>
+                  </td>
+                  <td className="truncate max-w-xs">{run.name}</td>
+                  <td className="font-mono">{run.branch}</td>
+                  <td className="font-mono">{run.commit_sha.slice(0, 7)}</td>
+                  <td className="text-right text-base-content/60">{new Date(run.created_at).toLocaleString()}</td>
+                  <td>
+                    <a href={run.url} target="_blank" rel="noopener noreferrer" className="btn btn-ghost btn-xs" title="Open run">
+                      <ExternalLink className="w-3 h-3" />
+                    </a>
+                  </td>
+                </tr>
+              ))}
+            </tbody>
+          </table>
+        )}
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,329 @@
+/**
+ * ClaudeConfigPanel - Edits the project's Claude Code subagents and hooks under .claude/
+ */
+
+import { useCallback, useEffect, useState } from 'react';
+import { Bot, Pencil, Plus, Trash2, Webhook, Save } from 'lucide-react';
+import {
+  deleteClaudeSubagent,
+  getClaudeConfigTemplates,
+  listClaudeHooks,
+  listClaudeSubagents,
+  saveClaudeHooks,
+  saveClaudeSubagent,
+  HOOK_EVENTS,
+  type ClaudeConfigTemplates,
+  type ClaudeHook,
+  type Subagent,
+} from '../../api/claudeConfig';
+
+interface ClaudeConfigPanelProps {
+  projectId: string;
+}
+
+const EMPTY_AGENT: Subagent = { name: '', description: '', tools: [], model: null, prompt: '' };
+const EMPTY_HOOK: ClaudeHook = { event: 'PostToolUse', matcher: null, command: '', timeout: null };
+
+export default function ClaudeConfigPanel({ projectId }: ClaudeConfigPanelProps) {
+  const [templates, setTemplates] = useState<ClaudeConfigTemplates>({ subagents: [], hooks: [] });
+  const [agents, setAgents] = useState<Subagent[]>([]);
+  // The agent being edited, and the name it was loaded with ('' for a new one)
+  const [draft, setDraft] = useState<Subagent | null>(null);
+  const [draftOriginalName, setDraftOriginalName] = useState('');
+  const [hooks, setHooks] = useState<ClaudeHook[]>([]);
+  const [localHooks, setLocalHooks] = useState(false);
+  const [hooksDirty, setHooksDirty] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    getClaudeConfigTemplates()
+      .then(setTemplates)
+      .catch((err) => console.error('Failed to load Claude config templates:', err));
+  }, []);
+
+  const loadAgents = useCallback(() => {
+    listClaudeSubagents(projectId)
+      .then(setAgents)
+      .catch((err) => setError(String(err)));
+  }, [projectId]);
+
+  useEffect(() => {
+    setDraft(null);
+    loadAgents();
+  }, [loadAgents]);
+
+  useEffect(() => {
+    listClaudeHooks(projectId, localHooks)
+      .then((loaded) => {
+        setHooks(loaded);
+        setHooksDirty(false);
+      })
+      .catch((err) => setError(String(err)));
+  }, [projectId, localHooks]);
+
+  const editAgent = (agent: Subagent, originalName: string) => {
+    setDraft({ ...agent });
+    setDraftOriginalName(originalName);
+    setError(null);
+  };
+
+  const handleSaveAgent = async () => {
+    if (!draft) return;
+    try {
+      await saveClaudeSubagent(projectId, draft, draftOriginalName || undefined);
+      setDraft(null);
+      loadAgents();
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  const handleDeleteAgent = async (name: string) => {
+    if (!confirm(`Delete the ${name} subagent?`)) return;
+    try {
+      await deleteClaudeSubagent(projectId, name);
+      loadAgents();
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  const updateHook = (index: number, change: Partial<ClaudeHook>) => {
+    setHooks((current) => current.map((hook, i) => (i === index ? { ...hook, ...change } : hook)));
+    setHooksDirty(true);
+  };
+
+  const addHook = (hook: ClaudeHook) => {
+    setHooks((current) => [...current, { ...hook }]);
+    setHooksDirty(true);
+  };
+
+  const removeHook = (index: number) => {
+    setHooks((current) => current.filter((_, i) => i !== index));
+    setHooksDirty(true);
+  };
+
+  const handleSaveHooks = async () => {
+    try {
+      setHooks(await saveClaudeHooks(projectId, hooks, localHooks));
+      setHooksDirty(false);
+      setError(null);
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  return (
+    <div className="card bg-base-200">
+      <div className="card-body gap-4">
+        <h2 className="card-title">
+          <Bot className="w-5 h-5" />
+          Claude Subagents &amp; Hooks
+        </h2>
+
+        {error && <div className="alert alert-error text-sm">{error}</div>}
+
+        {/* Subagents */}
+        <div className="space-y-2">
+          <div className="flex items-center gap-2">
+            <h3 className="font-semibold flex-1">Subagents</h3>
+            <select
+              className="select select-bordered select-sm"
+              value=""
+              onChange={(e) => {
+                const template = templates.subagents.find((t) => t.id === e.target.value);
+                if (template) editAgent(template.agent, '');
+              }}
+            >
+              <option value="">From template...</option>
+              {templates.subagents.map((template) => (
+                <option key={template.id} value={template.id}>
+                  {template.label}
+                </option>
+              ))}
+            </select>
+            <button className="btn btn-ghost btn-sm gap-1" onClick={() => editAgent(EMPTY_AGENT, '')}>
+              <Plus className="w-4 h-4" />
+              New
+            </button>
+          </div>
+
+          {agents.length === 0 && !draft && (
+            <p className="text-sm text-base-content/60">No subagents in .claude/agents</p>
+          )}
+          {agents.map((agent) => (
+            <div key={agent.name} className="flex items-center gap-2 text-sm group">
+              <code className="w-40 truncate">{agent.name}</code>
+              <span className="flex-1 truncate text-base-content/70">{agent.description}</span>
+              <button
+                className="btn btn-ghost btn-xs btn-circle opacity-0 group-hover:opacity-100"
+                onClick={() => editAgent(agent, agent.name)}
+                title="Edit"
+              >
+                <Pencil className="w-3 h-3" />
+              </button>
+              <button
+                className="btn btn-ghost btn-xs btn-circle opacity-0 group-hover:opacity-100"
+                onClick={() => handleDeleteAgent(agent.name)}
+                title="Delete"
+              >
+                <Trash2 className="w-3 h-3" />
+              </button>
+            </div>
+          ))}
+
+          {draft && (
+            <div className="space-y-2 rounded-lg bg-base-100 p-3">
+              <div className="flex gap-2">
+                <input
+                  type="text"
+                  className="input input-bordered input-sm w-48 font-mono"
+                  value={draft.name}
+                  onChange={(e) => setDraft({ ...draft, name: e.target.value })}
+                  placeholder="agent-name"
+                />
+                <input
+                  type="text"
+                  className="input input-bordered input-sm flex-1"
+                  value={draft.description}
+                  onChange={(e) => setDraft({ ...draft, description: e.target.value })}
+                  placeholder="When should Claude use this agent?"
+                />
+              </div>
+              <div className="flex gap-2">
+                <input
+                  type="text"
+                  className="input input-bordered input-sm flex-1 font-mono"
+                  value={draft.tools.join(', ')}
+                  onChange={(e) =>
+                    setDraft({ ...draft, tools: e.target.value.split(',').map((t) => t.trim()).filter(Boolean) })
+                  }
+                  placeholder="Tools, e.g. Read, Grep, Bash (empty allows all)"
+                />
+                <select
+                  className="select select-bordered select-sm"
+                  value={draft.model ?? ''}
+                  onChange={(e) => setDraft({ ...draft, model: e.target.value || null })}
+                >
+                  <option value="">Default model</option>
+                  <option value="inherit">Same as main</option>
+                  <option value="sonnet">Sonnet</option>
+                  <option value="opus">Opus</option>
+                  <option value="haiku">Haiku</option>
+                </select>
+              </div>
+              <textarea
+                className="textarea textarea-bordered w-full font-mono text-sm"
+                rows={8}
+                value={draft.prompt}
+                onChange={(e) => setDraft({ ...draft, prompt: e.target.value })}
+                placeholder="System prompt"
+              />
+              <div className="flex justify-end gap-2">
+                <button className="btn btn-ghost btn-sm" onClick={() => setDraft(null)}>
+                  Cancel
+                </button>
+                <button className="btn btn-primary btn-sm" onClick={handleSaveAgent}>
+                  Save subagent
+                </button>
+              </div>
+            </div>
+          )}
+        </div>
+
+        {/* Hooks */}
+        <div className="space-y-2">
+          <div className="flex items-center gap-2">
+            <h3 className="font-semibold flex-1 flex items-center gap-1">
+              <Webhook className="w-4 h-4" />
+              Hooks
+            </h3>
+            <div className="join">
+              <button
+                className={`btn btn-sm join-item ${!localHooks ? 'btn-active' : ''}`}
+                onClick={() => setLocalHooks(false)}
+                title=".claude/settings.json"
+              >
+                Shared
+              </button>
+              <button
+                className={`btn btn-sm join-item ${localHooks ? 'btn-active' : ''}`}
+                onClick={() => setLocalHooks(true)}
+                title=".claude/settings.local.json"
+              >
+                Local
+              </button>
+            </div>
+          </div>
+
+          {hooks.length === 0 && <p className="text-sm text-base-content/60">No hooks configured</p>}
+          {hooks.map((hook, index) => (
+            <div key={index} className="flex items-center gap-2">
+              <select
+                className="select select-bordered select-sm"
+                value={hook.event}
+                onChange={(e) => updateHook(index, { event: e.target.value })}
+              >
+                {HOOK_EVENTS.map((event) => (
+                  <option key={event} value={event}>
+                    {event}
+                  </option>
+                ))}
+              </select>
+              <input
+                type="text"
+                className="input input-bordered input-sm w-36 font-mono"
+                value={hook.matcher ?? ''}
+                onChange={(e) => updateHook(index, { matcher: e.target.value || null })}
+                placeholder="Matcher"
+                disabled={!hook.event.endsWith('ToolUse')}
+              />
+              <input
+                type="text"
+                className="input input-bordered input-sm flex-1 font-mono"
+                value={hook.command}
+                onChange={(e) => updateHook(index, { command: e.target.value })}
+                placeholder="Command"
+              />
+              <button className="btn btn-ghost btn-xs btn-circle" onClick={() => removeHook(index)} title="Remove">
+                <Trash2 className="w-3 h-3" />
+              </button>
+            </div>
+          ))}
+
+          <div className="flex items-center gap-2">
+            <button className="btn btn-ghost btn-xs gap-1" onClick={() => addHook(EMPTY_HOOK)}>
+              <Plus className="w-3 h-3" />
+              Add hook
+            </button>
+            <select
+              className="select select-bordered select-xs"
+              value=""
+              onChange={(e) => {
+                const template = templates.hooks.find((t) => t.id === e.target.value);
+                if (template) addHook(template.hook);
+              }}
+            >
+              <option value="">Add from template...</option>
+              {templates.hooks.map((template) => (
+                <option key={template.id} value={template.id}>
+                  {template.label}
+                </option>
+              ))}
+            </select>
+            <div className="flex-1" />
+            <button className="btn btn-primary btn-sm gap-1" onClick={handleSaveHooks} disabled={!hooksDirty}>
+              <Save className="w-4 h-4" />
+              Save hooks
+            </button>
+          </div>
+        </div>
+
+        <p className="text-xs text-base-content/60">
+          Claude Code picks these up from <code>.claude/</code> in the project; shared settings are meant to be
+          committed, local ones stay on this machine.
+        </p>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,160 @@
+/**
+ * DevServerPanel - Starts and stops the project's dev server and links its preview
+ */
+
+import { useEffect, useRef, useState } from 'react';
+import { ExternalLink, Globe, Loader2, Play, Square } from 'lucide-react';
+import { listScripts, type ScriptConfig } from '../../api/scripts';
+import {
+  getDevServer,
+  isDevServerActive,
+  listenToDevServerOutput,
+  listenToDevServerUpdates,
+  startDevServer,
+  stopDevServer,
+  type DevServerInfo,
+} from '../../api/devServer';
+
+interface DevServerPanelProps {
+  projectId: string;
+}
+
+/** Output lines kept in view */
+const MAX_LINES = 500;
+
+/** Script names that usually start a dev server */
+const DEV_SCRIPT = /\b(dev|start|serve|preview)\b/;
+
+export default function DevServerPanel({ projectId }: DevServerPanelProps) {
+  const [scripts, setScripts] = useState<ScriptConfig[]>([]);
+  const [selected, setSelected] = useState('');
+  const [server, setServer] = useState<DevServerInfo | null>(null);
+  const [log, setLog] = useState<string[]>([]);
+  const [showLog, setShowLog] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const logRef = useRef<HTMLPreElement>(null);
+
+  useEffect(() => {
+    listScripts(projectId)
+      .then((found) => {
+        setScripts(found);
+        setSelected((found.find((script) => DEV_SCRIPT.test(script.name)) ?? found[0])?.name ?? '');
+      })
+      .catch((err) => console.error('Failed to list scripts:', err));
+    getDevServer(projectId)
+      .then((state) => {
+        setServer(state?.info ?? null);
+        setLog(state?.log ?? []);
+      })
+      .catch((err) => console.error('Failed to load dev server:', err));
+
+    const unlistenUpdates = listenToDevServerUpdates((info) => {
+      if (info.project_id === projectId) setServer(info);
+    });
+    const unlistenOutput = listenToDevServerOutput(({ project_id, line }) => {
+      if (project_id === projectId) setLog((current) => [...current, line].slice(-MAX_LINES));
+    });
+    return () => {
+      unlistenUpdates.then((fn) => fn());
+      unlistenOutput.then((fn) => fn());
+    };
+  }, [projectId]);
+
+  useEffect(() => {
+    logRef.current?.scrollTo({ top: logRef.current.scrollHeight });
+  }, [log, showLog]);
+
+  if (scripts.length === 0 && !server) return null;
+
+  const active = server != null && isDevServerActive(server);
+
+  const handleStart = async () => {
+    const script = scripts.find((s) => s.name === selected);
+    if (!script) return;
+    setError(null);
+    setLog([]);
+    try {
+      setServer(await startDevServer(projectId, { program: script.program, args: script.args }));
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  const handleStop = () => {
+    stopDevServer(projectId).catch((err) => setError(String(err)));
+  };
+
+  return (
+    <div className="card bg-base-200">
+      <div className="card-body p-4 gap-3">
+        <div className="flex items-center gap-2">
+          <h3 className="font-semibold flex-1 flex items-center gap-2">
+            <Globe className="w-4 h-4" />
+            Dev Server
+          </h3>
+          {active ? (
+            <button className="btn btn-ghost btn-sm gap-2" onClick={handleStop}>
+              <Square className="w-4 h-4" />
+              Stop
+            </button>
+          ) : (
+            <>
+              <select
+                className="select select-bordered select-sm max-w-xs font-mono"
+                value={selected}
+                onChange={(e) => setSelected(e.target.value)}
+              >
+                {scripts.map((script) => (
+                  <option key={script.name} value={script.name}>
+                    {script.name}
+                  </option>
+                ))}
+              </select>
+              <button className="btn btn-primary btn-sm gap-2" onClick={handleStart} disabled={!selected}>
+                <Play className="w-4 h-4" />
+                Start
+              </button>
+            </>
+          )}
+        </div>
+
+        {error && <div className="alert alert-error text-sm">{error}</div>}
+
+        {server && (
+          <div className="flex items-center gap-2 text-sm">
+            {server.status === 'starting' && <Loader2 className="w-4 h-4 animate-spin" />}
+            <code className="text-xs flex-1 truncate">{server.command}</code>
+            {server.status === 'running' && server.url ? (
+              <a
+                href={server.url}
+                target="_blank"
+                rel="noopener noreferrer"
+                className="link link-primary flex items-center gap-1"
+              >
+                {server.url}
+                <ExternalLink className="w-3 h-3" />
+              </a>
+            ) : (
+              <span className={server.status === 'exited' ? 'text-error' : 'text-base-content/60'}>
+                {server.status}
+                {server.status === 'exited' && server.exit_code != null && ` with code ${server.exit_code}`}
+              </span>
+            )}
+            <button className="btn btn-ghost btn-xs" onClick={() => setShowLog(!showLog)}>
+              {showLog ? 'Hide output' : 'Show output'}
+            </button>
+          </div>
+        )}
+
+        {server && showLog && (
+          <pre
+            ref={logRef}
+            className="bg-base-300 rounded-lg p-3 max-h-64 overflow-auto text-xs font-mono whitespace-pre-wrap break-all"
+          >
+            {log.join('\n') || 'No output yet'}
+          </pre>
+        )}
+      </div>
+    </div>
+  );
+}
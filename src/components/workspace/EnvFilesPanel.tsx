@@ -0,0 +1,185 @@
+/**
+ * EnvFilesPanel - Lists the project's .env files with masked values and edits single keys
+ */
+
+import { useCallback, useEffect, useState } from 'react';
+import { Key, Pencil, Plus, Trash2, X, Check } from 'lucide-react';
+import { listEnvFiles, readEnvFile, setEnvVar, type EnvFile } from '../../api/envFiles';
+
+interface EnvFilesPanelProps {
+  projectId: string;
+}
+
+export default function EnvFilesPanel({ projectId }: EnvFilesPanelProps) {
+  const [files, setFiles] = useState<string[]>([]);
+  const [selected, setSelected] = useState('');
+  const [file, setFile] = useState<EnvFile | null>(null);
+  const [revealed, setRevealed] = useState(false);
+  const [editingKey, setEditingKey] = useState<string | null>(null);
+  const [draftKey, setDraftKey] = useState('');
+  const [draftValue, setDraftValue] = useState('');
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    listEnvFiles(projectId)
+      .then((found) => {
+        setFiles(found);
+        setSelected(found[0] ?? '');
+      })
+      .catch((err) => console.error('Failed to list env files:', err));
+  }, [projectId]);
+
+  const loadFile = useCallback(
+    (reveal: boolean) => {
+      if (!selected) return;
+      readEnvFile(projectId, selected, reveal)
+        .then((loaded) => {
+          setFile(loaded);
+          setRevealed(reveal);
+        })
+        .catch((err) => setError(String(err)));
+    },
+    [projectId, selected]
+  );
+
+  useEffect(() => {
+    setEditingKey(null);
+    loadFile(false);
+  }, [loadFile]);
+
+  const startEditing = (key: string) => {
+    setEditingKey(key);
+    setDraftKey(key);
+    setDraftValue('');
+    setError(null);
+  };
+
+  const handleSave = async () => {
+    const key = draftKey.trim();
+    if (!key) return;
+    try {
+      setFile(await setEnvVar(projectId, selected, key, draftValue));
+      setRevealed(false);
+      setEditingKey(null);
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  const handleRemove = async (key: string) => {
+    if (!confirm(`Remove ${key} from ${selected}?`)) return;
+    try {
+      setFile(await setEnvVar(projectId, selected, key, null));
+      setRevealed(false);
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  const editor = (
+    <div className="flex items-center gap-2">
+      <input
+        type="text"
+        className="input input-bordered input-sm w-48 font-mono"
+        value={draftKey}
+        onChange={(e) => setDraftKey(e.target.value)}
+        placeholder="KEY"
+        disabled={editingKey !== ''}
+      />
+      <input
+        type="password"
+        className="input input-bordered input-sm flex-1 font-mono"
+        value={draftValue}
+        onChange={(e) => setDraftValue(e.target.value)}
+        onKeyDown={(e) => e.key === 'Enter' && handleSave()}
+        placeholder="New value"
+        autoFocus
+      />
+      <button className="btn btn-ghost btn-xs btn-circle" onClick={handleSave} title="Save">
+        <Check className="w-4 h-4" />
+      </button>
+      <button className="btn btn-ghost btn-xs btn-circle" onClick={() => setEditingKey(null)} title="Cancel">
+        <X className="w-4 h-4" />
+      </button>
+    </div>
+  );
+
+  return (
+    <div className="card bg-base-200">
+      <div className="card-body gap-3">
+        <div className="flex items-center gap-2">
+          <h2 className="card-title flex-1">
+            <Key className="w-5 h-5" />
+            Environment Files
+          </h2>
+          {files.length > 0 && (
+            <select
+              className="select select-bordered select-sm font-mono"
+              value={selected}
+              onChange={(e) => setSelected(e.target.value)}
+            >
+              {files.map((name) => (
+                <option key={name} value={name}>
+                  {name}
+                </option>
+              ))}
+            </select>
+          )}
+          {file && file.entries.some((entry) => entry.masked || revealed) && (
+            <button className="btn btn-ghost btn-sm" onClick={() => loadFile(!revealed)}>
+              {revealed ? 'Hide values' : 'Reveal values'}
+            </button>
+          )}
+        </div>
+
+        {error && <div className="alert alert-error text-sm">{error}</div>}
+
+        {files.length === 0 ? (
+          <p className="text-sm text-base-content/60">No .env files in this project</p>
+        ) : (
+          file && (
+            <div className="space-y-1">
+              {file.entries.map((entry) =>
+                editingKey === entry.key ? (
+                  <div key={entry.key}>{editor}</div>
+                ) : (
+                  <div key={entry.key} className="flex items-center gap-2 text-sm group">
+                    <code className="w-48 truncate">{entry.key}</code>
+                    <code className="flex-1 truncate text-base-content/70">{entry.value}</code>
+                    <button
+                      className="btn btn-ghost btn-xs btn-circle opacity-0 group-hover:opacity-100"
+                      onClick={() => startEditing(entry.key)}
+                      title="Change value"
+                    >
+                      <Pencil className="w-3 h-3" />
+                    </button>
+                    <button
+                      className="btn btn-ghost btn-xs btn-circle opacity-0 group-hover:opacity-100"
+                      onClick={() => handleRemove(entry.key)}
+                      title="Remove"
+                    >
+                      <Trash2 className="w-3 h-3" />
+                    </button>
+                  </div>
+                )
+              )}
+              {editingKey === '' ? (
+                editor
+              ) : (
+                <button className="btn btn-ghost btn-xs gap-1" onClick={() => startEditing('')}>
+                  <Plus className="w-3 h-3" />
+                  Add variable
+                </button>
+              )}
+            </div>
+          )
+        )}
+
+        <p className="text-xs text-base-content/60">
+          List a file under an execution profile's <code>env_files</code> to set its variables for agent sessions,
+          so secrets never need to go into chat.
+        </p>
+      </div>
+    </div>
+  );
+}
@@ -18,11 +18,36 @@ import {
   Maximize2,
   Minimize2,
   ChevronLeft,
+  Send,
+  Sparkles,
+  Loader2,
+  RefreshCw,
 } from 'lucide-react';
 import { useHotkeys } from 'react-hotkeys-hook';
-import Editor from '@monaco-editor/react';
+import Editor, { type OnMount } from '@monaco-editor/react';
 import { Panel, PanelGroup, PanelResizeHandle } from 'react-resizable-panels';
-import { readProjectFiles, readFileContent, getFolderChildren, getGitStatus, type FileNode, type GitFileStatus } from '../../api/files';
+import {
+  readProjectFiles,
+  readFileContent,
+  getFolderChildren,
+  getGitStatus,
+  watchOpenFile,
+  unwatchOpenFile,
+  onOpenFileChanged,
+  type FileNode,
+  type GitFileStatus,
+  type OpenFileChanged,
+} from '../../api/files';
+import { sendSelectionToAgent } from '../../api/chatTabs';
+import { getDiagnostics, type Diagnostic } from '../../api/lsp';
+import HighlightedFileView from './HighlightedFileView';
+import { useChatTabStore } from '../../stores/chatTabStore';
+
+/** Larger files open in the backend-highlighted viewer instead of Monaco */
+const MAX_EDITOR_SIZE = 1024 * 1024;
+
+/** The backend refuses to read files past this */
+const MAX_VIEW_SIZE = 10 * 1024 * 1024;
 
 // File type icons mapping
 const getFileIcon = (fileName: string) => {
@@ -161,6 +186,8 @@ const formatDate = (timestamp: number): string => {
 // Extended FileNode with content for preview
 interface FileNodeWithContent extends FileNode {
   content?: string;
+  /** Entries in the folder, when more exist than have been loaded */
+  childrenTotal?: number;
 }
 
 // Simplified mock file structure - kept for fallback in non-Tauri environment
@@ -263,9 +290,23 @@ interface FileTreeItemProps {
   gitStatus: Map<string, GitFileStatus['status']>;
   onSelect: (node: FileNodeWithContent) => void;
   onToggle: (id: string, node: FileNodeWithContent) => void;
+  onLoadMore: (node: FileNodeWithContent) => void;
   searchQuery?: string;
 }
 
+// Row that loads the next page of a large folder
+function LoadMoreRow({ loaded, total, level, onClick }: { loaded: number; total: number; level: number; onClick: () => void }) {
+  return (
+    <button
+      className="btn btn-ghost btn-xs w-full justify-start text-base-content/60"
+      style={{ paddingLeft: `${level * 16 + 28}px` }}
+      onClick={onClick}
+    >
+      Load more ({loaded} of {total} shown)
+    </button>
+  );
+}
+
 function FileTreeItem({
   node,
   level,
@@ -275,6 +316,7 @@ function FileTreeItem({
   gitStatus,
   onSelect,
   onToggle,
+  onLoadMore,
   searchQuery = '',
 }: FileTreeItemProps) {
   const isExpanded = expandedIds.has(node.id);
@@ -354,9 +396,22 @@ function FileTreeItem({
               gitStatus={gitStatus}
               onSelect={onSelect}
               onToggle={onToggle}
+              onLoadMore={onLoadMore}
               searchQuery={searchQuery}
             />
           ))}
+          {node.childrenTotal !== undefined && node.childrenTotal > node.children.length && (
+            isLoading ? (
+              <span className="loading loading-spinner loading-xs" style={{ marginLeft: `${(level + 1) * 16 + 28}px` }}></span>
+            ) : (
+              <LoadMoreRow
+                loaded={node.children.length}
+                total={node.childrenTotal}
+                level={level + 1}
+                onClick={() => onLoadMore(node)}
+              />
+            )
+          )}
         </div>
       )}
     </div>
@@ -541,6 +596,7 @@ interface FilesTabProps {
 // Main FilesTab component
 export default function FilesTab({ projectId }: FilesTabProps) {
   const [fileTree, setFileTree] = useState<FileNodeWithContent[]>([]);
+  const [rootTotal, setRootTotal] = useState(0);
   const [selectedFile, setSelectedFile] = useState<FileNodeWithContent | null>(null);
   const [expandedIds, setExpandedIds] = useState<Set<string>>(new Set());
   const [loadingIds, setLoadingIds] = useState<Set<string>>(new Set());
@@ -552,6 +608,16 @@ export default function FilesTab({ projectId }: FilesTabProps) {
   const [error, setError] = useState<string | null>(null);
   const [showQuickSearch, setShowQuickSearch] = useState(false);
   const [editorCollapsed, setEditorCollapsed] = useState(false);
+  // Lines selected in the editor, 1-based and inclusive
+  const [selection, setSelection] = useState<{ start: number; end: number } | null>(null);
+  const [selectionInstruction, setSelectionInstruction] = useState('');
+  const [selectionStatus, setSelectionStatus] = useState<string | null>(null);
+  const [diagnostics, setDiagnostics] = useState<Diagnostic[]>([]);
+  const [checkingDiagnostics, setCheckingDiagnostics] = useState(false);
+  const [showDiagnostics, setShowDiagnostics] = useState(false);
+  const [changedOnDisk, setChangedOnDisk] = useState<OpenFileChanged | null>(null);
+  const editorRef = useRef<Parameters<OnMount>[0] | null>(null);
+  const monacoRef = useRef<Parameters<OnMount>[1] | null>(null);
   const searchInputRef = useRef<HTMLInputElement>(null);
 
   // Keyboard shortcuts
@@ -618,7 +684,8 @@ export default function FilesTab({ projectId }: FilesTabProps) {
           getGitStatus(projectId),
         ]);
 
-        setFileTree(files as FileNodeWithContent[]);
+        setFileTree(files.nodes as FileNodeWithContent[]);
+        setRootTotal(files.total);
 
         // Convert git status array to a Map for quick lookups
         const statusMap = new Map<string, GitFileStatus['status']>();
@@ -646,19 +713,49 @@ export default function FilesTab({ projectId }: FilesTabProps) {
   const updateNodeChildren = (
     nodes: FileNodeWithContent[],
     nodeId: string,
-    children: FileNodeWithContent[]
+    children: FileNodeWithContent[],
+    childrenTotal: number
   ): FileNodeWithContent[] => {
     return nodes.map((node) => {
       if (node.id === nodeId) {
-        return { ...node, children };
+        return { ...node, children, childrenTotal };
       }
       if (node.children) {
-        return { ...node, children: updateNodeChildren(node.children, nodeId, children) };
+        return { ...node, children: updateNodeChildren(node.children, nodeId, children, childrenTotal) };
       }
       return node;
     });
   };
 
+  // Append the next page of a large folder, or of the project root when node is null
+  const handleLoadMore = async (node: FileNodeWithContent | null) => {
+    if (!projectId) return;
+    const loadingId = node?.id ?? '';
+    setLoadingIds((prev) => new Set(prev).add(loadingId));
+    try {
+      if (node) {
+        const loaded = node.children ?? [];
+        const page = await getFolderChildren(projectId, node.path, { offset: loaded.length });
+        setFileTree((prev) =>
+          updateNodeChildren(prev, node.id, [...loaded, ...(page.nodes as FileNodeWithContent[])], page.total)
+        );
+      } else {
+        const page = await readProjectFiles(projectId, { offset: fileTree.length });
+        setFileTree((prev) => [...prev, ...(page.nodes as FileNodeWithContent[])]);
+        setRootTotal(page.total);
+      }
+    } catch (err) {
+      console.error('Failed to load more files:', err);
+      setError(err instanceof Error ? err.message : 'Failed to load more files');
+    } finally {
+      setLoadingIds((prev) => {
+        const next = new Set(prev);
+        next.delete(loadingId);
+        return next;
+      });
+    }
+  };
+
   const handleToggle = async (id: string, node: FileNodeWithContent) => {
     // If collapsing, just toggle the expanded state
     if (expandedIds.has(id)) {
@@ -675,9 +772,9 @@ export default function FilesTab({ projectId }: FilesTabProps) {
       setLoadingIds((prev) => new Set(prev).add(id));
 
       try {
-        const children = await getFolderChildren(projectId, node.path);
+        const page = await getFolderChildren(projectId, node.path);
         // Update the file tree with the loaded children
-        setFileTree((prev) => updateNodeChildren(prev, id, children as FileNodeWithContent[]));
+        setFileTree((prev) => updateNodeChildren(prev, id, page.nodes as FileNodeWithContent[], page.total));
       } catch (err) {
         console.error('Failed to load folder children:', err);
         setError(err instanceof Error ? err.message : 'Failed to load folder contents');
@@ -712,10 +809,12 @@ export default function FilesTab({ projectId }: FilesTabProps) {
       return;
     }
 
-    // Check file size limit (1MB = 1,048,576 bytes)
-    if (node.size && node.size > 1048576) {
+    // Files over 1MB skip the editor and are shown highlighted by the backend
+    if (node.size && node.size > MAX_EDITOR_SIZE) {
       setSelectedFile({ ...node, content: undefined });
-      setError('File is too large to display (> 1MB)');
+      if (node.size > MAX_VIEW_SIZE) {
+        setError('File is too large to display (> 10MB)');
+      }
       return;
     }
 
@@ -738,6 +837,130 @@ export default function FilesTab({ projectId }: FilesTabProps) {
     }
   };
 
+  const handleEditorMount: OnMount = (editor, monaco) => {
+    editorRef.current = editor;
+    monacoRef.current = monaco;
+    editor.onDidChangeCursorSelection(({ selection: range }) => {
+      if (range.isEmpty()) {
+        setSelection(null);
+        return;
+      }
+      // A selection ending at the start of a line doesn't include that line
+      const end = range.endColumn === 1 && range.endLineNumber > range.startLineNumber
+        ? range.endLineNumber - 1
+        : range.endLineNumber;
+      setSelection({ start: range.startLineNumber, end });
+    });
+  };
+
+  useEffect(() => {
+    setSelection(null);
+    setSelectionStatus(null);
+  }, [selectedFile?.path]);
+
+  // Ask the language servers about each opened file; changes on disk are picked up on reopen
+  const hasContent = selectedFile?.content !== undefined;
+  useEffect(() => {
+    setDiagnostics([]);
+    if (!projectId || !selectedFile || selectedFile.type !== 'file' || !hasContent) return;
+    let cancelled = false;
+    setCheckingDiagnostics(true);
+    getDiagnostics(projectId, selectedFile.path)
+      .then((found) => !cancelled && setDiagnostics(found))
+      // Projects without a language server just go without diagnostics
+      .catch((err) => console.debug('No diagnostics:', err))
+      .finally(() => !cancelled && setCheckingDiagnostics(false));
+    return () => {
+      cancelled = true;
+    };
+  }, [projectId, selectedFile?.path, hasContent]);
+
+  // Flag the open file when something rewrites or deletes it on disk
+  useEffect(() => {
+    setChangedOnDisk(null);
+    if (!projectId || !selectedFile || selectedFile.type !== 'file' || !hasContent) return;
+    const filePath = selectedFile.path;
+    let unlisten: (() => void) | undefined;
+    let cancelled = false;
+    onOpenFileChanged((change) => {
+      if (change.project_id === projectId && change.file_path === filePath) setChangedOnDisk(change);
+    }).then((fn) => (cancelled ? fn() : (unlisten = fn)));
+    watchOpenFile(projectId, filePath).catch((err) => console.debug('Not watching open file:', err));
+    return () => {
+      cancelled = true;
+      unlisten?.();
+      unwatchOpenFile(projectId, filePath).catch(() => {});
+    };
+  }, [projectId, selectedFile?.path, hasContent]);
+
+  const reloadSelectedFile = async () => {
+    if (!projectId || !selectedFile) return;
+    try {
+      const content = await readFileContent(projectId, selectedFile.path);
+      setSelectedFile((prev) => (prev && prev.path === selectedFile.path ? { ...prev, content } : prev));
+      setChangedOnDisk(null);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to reload file');
+    }
+  };
+
+  useEffect(() => {
+    const model = editorRef.current?.getModel();
+    const monaco = monacoRef.current;
+    if (!model || !monaco) return;
+    const severities = {
+      error: monaco.MarkerSeverity.Error,
+      warning: monaco.MarkerSeverity.Warning,
+      info: monaco.MarkerSeverity.Info,
+      hint: monaco.MarkerSeverity.Hint,
+    };
+    monaco.editor.setModelMarkers(
+      model,
+      'lsp',
+      diagnostics.map((d) => ({
+        startLineNumber: d.line,
+        startColumn: d.column,
+        endLineNumber: d.end_line,
+        endColumn: d.end_column,
+        severity: severities[d.severity],
+        message: d.message,
+        source: d.source ?? undefined,
+        code: d.code ?? undefined,
+      }))
+    );
+  }, [diagnostics]);
+
+  const revealDiagnostic = (diagnostic: Diagnostic) => {
+    const editor = editorRef.current;
+    if (!editor) return;
+    editor.revealLineInCenter(diagnostic.line);
+    editor.setPosition({ lineNumber: diagnostic.line, column: diagnostic.column });
+    editor.focus();
+  };
+
+  const errorCount = diagnostics.filter((d) => d.severity === 'error').length;
+  const warningCount = diagnostics.filter((d) => d.severity === 'warning').length;
+
+  const sendSelection = async (instruction: string) => {
+    if (!projectId || !selectedFile || !selection || !instruction.trim()) return;
+    const tabStore = useChatTabStore.getState();
+    if (tabStore.getTabs(projectId).length === 0) {
+      await tabStore.loadTabs(projectId);
+    }
+    const tab = useChatTabStore.getState().getActiveTab(projectId);
+    if (!tab) {
+      setSelectionStatus('Open a chat tab to send the selection to');
+      return;
+    }
+    try {
+      await sendSelectionToAgent(tab.id, selectedFile.path, selection.start, selection.end, instruction);
+      setSelectionInstruction('');
+      setSelectionStatus(`Sent to ${tab.label || 'chat'}`);
+    } catch (err) {
+      setSelectionStatus(String(err));
+    }
+  };
+
   const toggleEditor = () => {
     setEditorCollapsed(!editorCollapsed);
   };
@@ -853,20 +1076,30 @@ export default function FilesTab({ projectId }: FilesTabProps) {
                   </div>
                 </div>
               ) : (
-                displayedFileTree.map((node) => (
-                  <FileTreeItem
-                    key={node.id}
-                    node={node}
-                    level={0}
-                    selectedId={selectedFile?.id || null}
-                    expandedIds={expandedIds}
-                    loadingIds={loadingIds}
-                    gitStatus={gitStatus}
-                    onSelect={handleSelect}
-                    onToggle={handleToggle}
-                    searchQuery={debouncedSearchQuery}
-                  />
-                ))
+                <>
+                  {displayedFileTree.map((node) => (
+                    <FileTreeItem
+                      key={node.id}
+                      node={node}
+                      level={0}
+                      selectedId={selectedFile?.id || null}
+                      expandedIds={expandedIds}
+                      loadingIds={loadingIds}
+                      gitStatus={gitStatus}
+                      onSelect={handleSelect}
+                      onToggle={handleToggle}
+                      onLoadMore={handleLoadMore}
+                      searchQuery={debouncedSearchQuery}
+                    />
+                  ))}
+                  {!debouncedSearchQuery && rootTotal > fileTree.length && (
+                    loadingIds.has('') ? (
+                      <span className="loading loading-spinner loading-xs ml-7"></span>
+                    ) : (
+                      <LoadMoreRow loaded={fileTree.length} total={rootTotal} level={0} onClick={() => handleLoadMore(null)} />
+                    )
+                  )}
+                </>
               )}
             </div>
           </div>
@@ -903,8 +1136,108 @@ export default function FilesTab({ projectId }: FilesTabProps) {
                     </div>
                     <div className="flex items-center gap-3">
                       {getFileIcon(selectedFile.name)}
-                      <h3 className="font-semibold">{selectedFile.name}</h3>
+                      <h3 className="font-semibold flex-1">{selectedFile.name}</h3>
+                      {checkingDiagnostics ? (
+                        <Loader2 className="w-4 h-4 animate-spin text-base-content/60" />
+                      ) : (
+                        (errorCount > 0 || warningCount > 0) && (
+                          <button
+                            className="btn btn-ghost btn-xs gap-1"
+                            onClick={() => setShowDiagnostics(!showDiagnostics)}
+                            title="Language server diagnostics"
+                          >
+                            <AlertCircle className={`w-3 h-3 ${errorCount > 0 ? 'text-error' : 'text-warning'}`} />
+                            {errorCount} {errorCount === 1 ? 'error' : 'errors'}, {warningCount}{' '}
+                            {warningCount === 1 ? 'warning' : 'warnings'}
+                          </button>
+                        )
+                      )}
                     </div>
+                    {showDiagnostics && diagnostics.length > 0 && (
+                      <ul className="mt-2 max-h-32 overflow-auto text-xs space-y-0.5">
+                        {diagnostics.map((d, index) => (
+                          <li key={index}>
+                            <button
+                              className="w-full text-left flex gap-2 hover:bg-base-300 rounded px-1"
+                              onClick={() => revealDiagnostic(d)}
+                            >
+                              <span
+                                className={
+                                  d.severity === 'error'
+                                    ? 'text-error'
+                                    : d.severity === 'warning'
+                                      ? 'text-warning'
+                                      : 'text-base-content/60'
+                                }
+                              >
+                                {d.line}:{d.column}
+                              </span>
+                              <span className="flex-1 truncate">{d.message.split('\n')[0]}</span>
+                              {d.source && <span className="text-base-content/50">{d.source}</span>}
+                            </button>
+                          </li>
+                        ))}
+                      </ul>
+                    )}
+                    {selection && projectId && (
+                      <div className="flex items-center gap-2 mt-2">
+                        <span className="text-xs text-base-content/60 whitespace-nowrap">
+                          {selection.start === selection.end
+                            ? `Line ${selection.start}`
+                            : `Lines ${selection.start}-${selection.end}`}
+                        </span>
+                        <button className="btn btn-ghost btn-xs gap-1" onClick={() => sendSelection('Explain this code.')}>
+                          <Sparkles className="w-3 h-3" />
+                          Explain
+                        </button>
+                        <button className="btn btn-ghost btn-xs gap-1" onClick={() => sendSelection('Refactor this code.')}>
+                          <Sparkles className="w-3 h-3" />
+                          Refactor
+                        </button>
+                        <input
+                          type="text"
+                          className="input input-bordered input-xs flex-1"
+                          value={selectionInstruction}
+                          onChange={(e) => setSelectionInstruction(e.target.value)}
+                          onKeyDown={(e) => e.key === 'Enter' && sendSelection(selectionInstruction)}
+                          placeholder="Ask the agent about this selection..."
+                        />
+                        <button
+                          className="btn btn-ghost btn-xs btn-circle"
+                          onClick={() => sendSelection(selectionInstruction)}
+                          disabled={!selectionInstruction.trim()}
+                          title="Send to the active chat tab"
+                        >
+                          <Send className="w-3 h-3" />
+                        </button>
+                      </div>
+                    )}
+                    {selectionStatus && (
+                      <p className="text-xs text-base-content/60 mt-1">{selectionStatus}</p>
+                    )}
+                    {changedOnDisk && (
+                      <div className="alert alert-warning py-1 px-3 mt-2 text-sm">
+                        <AlertCircle className="w-4 h-4" />
+                        <span className="flex-1">
+                          {changedOnDisk.content_hash
+                            ? 'This file changed on disk; the view is out of date.'
+                            : 'This file was deleted on disk.'}
+                        </span>
+                        {changedOnDisk.content_hash && (
+                          <button className="btn btn-ghost btn-xs gap-1" onClick={reloadSelectedFile}>
+                            <RefreshCw className="w-3 h-3" />
+                            Reload
+                          </button>
+                        )}
+                        <button
+                          className="btn btn-ghost btn-xs btn-circle"
+                          onClick={() => setChangedOnDisk(null)}
+                          title="Dismiss"
+                        >
+                          <X className="w-3 h-3" />
+                        </button>
+                      </div>
+                    )}
                     <div className="flex items-center gap-4 mt-2 text-sm text-base-content/70">
                       {selectedFile.size && (
                         <div className="flex items-center gap-1">
@@ -947,7 +1280,9 @@ export default function FilesTab({ projectId }: FilesTabProps) {
                           <p className="text-sm mt-2">Cannot display binary files</p>
                         </div>
                       </div>
-                    ) : selectedFile.size && selectedFile.size > 1048576 ? (
+                    ) : projectId && selectedFile.size && selectedFile.size > MAX_EDITOR_SIZE && selectedFile.size <= MAX_VIEW_SIZE ? (
+                      <HighlightedFileView projectId={projectId} path={selectedFile.path} />
+                    ) : selectedFile.size && selectedFile.size > MAX_EDITOR_SIZE ? (
                       <div className="h-full flex items-center justify-center text-base-content/50">
                         <div className="text-center">
                           <AlertCircle className="w-16 h-16 mx-auto mb-4 opacity-20" />
@@ -955,7 +1290,7 @@ export default function FilesTab({ projectId }: FilesTabProps) {
                             File Too Large
                           </p>
                           <p className="text-sm mt-2">
-                            Files larger than 1MB cannot be displayed
+                            Files larger than 10MB cannot be displayed
                           </p>
                           <p className="text-xs mt-1">
                             Size: {formatFileSize(selectedFile.size)}
@@ -969,6 +1304,7 @@ export default function FilesTab({ projectId }: FilesTabProps) {
                         language={getMonacoLanguage(selectedFile.name)}
                         value={selectedFile.content}
                         theme="vs-dark"
+                        onMount={handleEditorMount}
                         options={{
                           readOnly: true,
                           minimap: { enabled: true },
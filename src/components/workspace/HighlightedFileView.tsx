@@ -0,0 +1,99 @@
+/**
+ * HighlightedFileView - Read-only view of a file too large for the editor,
+ * highlighted by the backend and rendered a screenful at a time
+ */
+
+import { useEffect, useState, type ReactNode } from 'react';
+import { Virtuoso } from 'react-virtuoso';
+import { Loader2 } from 'lucide-react';
+import { readFileContent } from '../../api/files';
+import { getHighlightTokens, type TokenKind, type TokenSpan } from '../../api/highlight';
+
+interface HighlightedFileViewProps {
+  projectId: string;
+  path: string;
+}
+
+const KIND_CLASSES: Record<TokenKind, string> = {
+  comment: 'text-base-content/50 italic',
+  string: 'text-success',
+  number: 'text-warning',
+  constant: 'text-warning',
+  operator: 'text-base-content/80',
+  keyword: 'text-secondary',
+  type: 'text-info',
+  function: 'text-primary',
+  tag: 'text-error',
+  attribute: 'text-warning',
+  parameter: 'text-accent',
+  variable: '',
+};
+
+function HighlightedLine({ text, tokens }: { text: string; tokens: TokenSpan[] }) {
+  const parts: ReactNode[] = [];
+  let pos = 0;
+  tokens.forEach((token, index) => {
+    if (token.start > pos) parts.push(<span key={`t${index}`}>{text.slice(pos, token.start)}</span>);
+    parts.push(
+      <span key={index} className={KIND_CLASSES[token.kind]}>
+        {text.slice(token.start, token.end)}
+      </span>
+    );
+    pos = token.end;
+  });
+  if (pos < text.length) parts.push(<span key="rest">{text.slice(pos)}</span>);
+  return <>{parts}</>;
+}
+
+export default function HighlightedFileView({ projectId, path }: HighlightedFileViewProps) {
+  const [lines, setLines] = useState<string[] | null>(null);
+  const [tokens, setTokens] = useState<TokenSpan[][]>([]);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    let cancelled = false;
+    setLines(null);
+    setTokens([]);
+    setError(null);
+    readFileContent(projectId, path)
+      .then((content) => !cancelled && setLines(content.split(/\r?\n/)))
+      .catch((err) => !cancelled && setError(String(err)));
+    // Plain text shows first; highlighting fills in once the backend is done
+    getHighlightTokens(projectId, path)
+      .then((highlighted) => !cancelled && setTokens(highlighted.lines))
+      .catch((err) => console.error('Failed to highlight file:', err));
+    return () => {
+      cancelled = true;
+    };
+  }, [projectId, path]);
+
+  if (error) {
+    return <div className="alert alert-error text-sm m-4">{error}</div>;
+  }
+  if (!lines) {
+    return (
+      <div className="h-full flex items-center justify-center">
+        <Loader2 className="w-6 h-6 animate-spin text-base-content/50" />
+      </div>
+    );
+  }
+
+  const gutter = `${String(lines.length).length + 1}ch`;
+  return (
+    <Virtuoso
+      style={{ height: '100%' }}
+      className="bg-base-300 font-mono text-xs"
+      totalCount={lines.length}
+      itemContent={(index) => (
+        <div className="flex whitespace-pre leading-5">
+          <span className="text-right pr-3 text-base-content/40 select-none shrink-0" style={{ width: gutter }}>
+            {index + 1}
+          </span>
+          <span>
+            <HighlightedLine text={lines[index]} tokens={tokens[index] ?? []} />
+          </span>
+        </div>
+      )}
+    />
+  );
+}
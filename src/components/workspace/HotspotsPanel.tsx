@@ -0,0 +1,97 @@
+/**
+ * HotspotsPanel - Ranks files by churn times complexity to show where to look first
+ */
+
+import { useState } from 'react';
+import { Activity, Loader2 } from 'lucide-react';
+import { getCodeHotspots, type Hotspot } from '../../api/hotspots';
+
+interface HotspotsPanelProps {
+  projectId: string;
+}
+
+/** Rows shown before "Show all" */
+const COLLAPSED_ROWS = 10;
+
+export default function HotspotsPanel({ projectId }: HotspotsPanelProps) {
+  const [hotspots, setHotspots] = useState<Hotspot[] | null>(null);
+  const [loading, setLoading] = useState(false);
+  const [showAll, setShowAll] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const handleAnalyze = async () => {
+    setLoading(true);
+    setError(null);
+    try {
+      setHotspots(await getCodeHotspots(projectId));
+    } catch (err) {
+      setError(String(err));
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const shown = showAll ? hotspots ?? [] : (hotspots ?? []).slice(0, COLLAPSED_ROWS);
+
+  return (
+    <div className="card bg-base-200">
+      <div className="card-body p-4 gap-3">
+        <div className="flex items-center gap-2">
+          <h3 className="font-semibold flex-1 flex items-center gap-2">
+            <Activity className="w-4 h-4" />
+            Hotspots
+          </h3>
+          <button className="btn btn-ghost btn-sm gap-2" onClick={handleAnalyze} disabled={loading}>
+            {loading && <Loader2 className="w-4 h-4 animate-spin" />}
+            {hotspots ? 'Refresh' : 'Analyze'}
+          </button>
+        </div>
+
+        {error && <div className="alert alert-error text-sm">{error}</div>}
+
+        {hotspots && hotspots.length === 0 && (
+          <p className="text-sm text-base-content/60">No source files found</p>
+        )}
+        {shown.length > 0 && (
+          <table className="table table-xs">
+            <thead>
+              <tr>
+                <th>File</th>
+                <th className="w-32">Score</th>
+                <th className="text-right" title="Commits in the last 180 days">
+                  Commits
+                </th>
+                <th className="text-right">Complexity</th>
+                <th className="text-right">Lines</th>
+              </tr>
+            </thead>
+            <tbody>
+              {shown.map((hotspot) => (
+                <tr key={hotspot.path}>
+                  <td className="font-mono truncate max-w-xs" title={hotspot.path}>
+                    {hotspot.path}
+                  </td>
+                  <td>
+                    <progress
+                      className={`progress w-24 ${hotspot.score > 0.5 ? 'progress-error' : 'progress-warning'}`}
+                      value={hotspot.score}
+                      max={1}
+                    />
+                  </td>
+                  <td className="text-right">{hotspot.commits}</td>
+                  <td className="text-right">{hotspot.complexity}</td>
+                  <td className="text-right">{hotspot.lines}</td>
+                </tr>
+              ))}
+            </tbody>
+          </table>
+        )}
+        {hotspots && hotspots.length > COLLAPSED_ROWS && (
+          <button className="btn btn-ghost btn-xs self-start" onClick={() => setShowAll(!showAll)}>
+            {showAll ? 'Show fewer' : `Show all ${hotspots.length}`}
+          </button>
+        )}
+      </div>
+    </div>
+  );
+}
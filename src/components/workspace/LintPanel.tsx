@@ -0,0 +1,149 @@
+/**
+ * LintPanel - Runs the project's linters and previews or applies its formatters
+ */
+
+import { useState } from 'react';
+import { AlertCircle, CheckCircle, Loader2, Search, Wrench } from 'lucide-react';
+import { runFormatter, runLinter, type FormatReport, type LintReport } from '../../api/lint';
+
+interface LintPanelProps {
+  projectId: string;
+}
+
+type Running = 'lint' | 'preview' | 'apply' | null;
+
+export default function LintPanel({ projectId }: LintPanelProps) {
+  const [running, setRunning] = useState<Running>(null);
+  const [lintReport, setLintReport] = useState<LintReport | null>(null);
+  const [formatReport, setFormatReport] = useState<FormatReport | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const handleLint = async () => {
+    setRunning('lint');
+    setError(null);
+    try {
+      setLintReport(await runLinter(projectId));
+    } catch (err) {
+      setError(String(err));
+    } finally {
+      setRunning(null);
+    }
+  };
+
+  const handleFormat = async (apply: boolean) => {
+    setRunning(apply ? 'apply' : 'preview');
+    setError(null);
+    try {
+      setFormatReport(await runFormatter(projectId, apply));
+    } catch (err) {
+      setError(String(err));
+    } finally {
+      setRunning(null);
+    }
+  };
+
+  const toolErrors = [...(lintReport?.errors ?? []), ...(formatReport?.errors ?? [])];
+
+  return (
+    <div className="card bg-base-200">
+      <div className="card-body p-4 gap-3">
+        <div className="flex items-center gap-2">
+          <h3 className="font-semibold flex-1">Lint &amp; Format</h3>
+          <button className="btn btn-ghost btn-sm gap-2" onClick={handleLint} disabled={running !== null}>
+            {running === 'lint' ? <Loader2 className="w-4 h-4 animate-spin" /> : <Search className="w-4 h-4" />}
+            Lint
+          </button>
+          <button className="btn btn-ghost btn-sm gap-2" onClick={() => handleFormat(false)} disabled={running !== null}>
+            {running === 'preview' ? <Loader2 className="w-4 h-4 animate-spin" /> : <Wrench className="w-4 h-4" />}
+            Preview Formatting
+          </button>
+          {formatReport && !formatReport.applied && formatReport.files.length > 0 && (
+            <button className="btn btn-primary btn-sm" onClick={() => handleFormat(true)} disabled={running !== null}>
+              {running === 'apply' && <Loader2 className="w-4 h-4 animate-spin" />}
+              Apply
+            </button>
+          )}
+        </div>
+
+        {error && <div className="alert alert-error text-sm">{error}</div>}
+        {toolErrors.map((toolError) => (
+          <div key={toolError} className="flex items-center gap-2 text-sm text-warning">
+            <AlertCircle className="w-4 h-4" />
+            {toolError}
+          </div>
+        ))}
+
+        {lintReport && (
+          <div className="space-y-1">
+            {lintReport.tools.length === 0 ? (
+              <p className="text-sm text-base-content/60">No linters found for this project</p>
+            ) : lintReport.findings.length === 0 ? (
+              <p className="flex items-center gap-2 text-sm text-success">
+                <CheckCircle className="w-4 h-4" />
+                No problems found by {lintReport.tools.join(', ')}
+              </p>
+            ) : (
+              <ul className="max-h-64 overflow-auto space-y-1">
+                {lintReport.findings.map((finding, index) => (
+                  <li key={index} className="text-sm flex gap-2">
+                    <span className={finding.severity === 'error' ? 'text-error' : 'text-warning'}>
+                      {finding.severity}
+                    </span>
+                    <code className="text-xs shrink-0">
+                      {finding.file}
+                      {finding.line != null && `:${finding.line}`}
+                      {finding.column != null && `:${finding.column}`}
+                    </code>
+                    <span className="flex-1">{finding.message}</span>
+                    {finding.rule && <span className="text-xs text-base-content/50">{finding.rule}</span>}
+                  </li>
+                ))}
+              </ul>
+            )}
+          </div>
+        )}
+
+        {formatReport && (
+          <div className="space-y-2">
+            {formatReport.tools.length === 0 ? (
+              <p className="text-sm text-base-content/60">No formatters found for this project</p>
+            ) : formatReport.files.length === 0 ? (
+              <p className="flex items-center gap-2 text-sm text-success">
+                <CheckCircle className="w-4 h-4" />
+                Already formatted
+              </p>
+            ) : formatReport.applied ? (
+              <p className="text-sm">
+                Formatted {formatReport.files.length} file{formatReport.files.length === 1 ? '' : 's'}
+              </p>
+            ) : (
+              formatReport.files.map((file) => (
+                <details key={`${file.tool}:${file.path}`} className="bg-base-300 rounded-lg">
+                  <summary className="cursor-pointer px-3 py-2 text-sm">
+                    <code>{file.path}</code> <span className="text-base-content/50">({file.tool})</span>
+                  </summary>
+                  <pre className="px-3 pb-3 max-h-80 overflow-auto text-xs font-mono">
+                    {file.diff.split('\n').map((line, index) => (
+                      <div
+                        key={index}
+                        className={
+                          line.startsWith('+') && !line.startsWith('+++')
+                            ? 'text-success'
+                            : line.startsWith('-') && !line.startsWith('---')
+                              ? 'text-error'
+                              : ''
+                        }
+                      >
+                        {line}
+                      </div>
+                    ))}
+                  </pre>
+                </details>
+              ))
+            )}
+          </div>
+        )}
+      </div>
+    </div>
+  );
+}
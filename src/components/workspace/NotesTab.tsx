@@ -0,0 +1,197 @@
+/**
+ * NotesTab - The project's wiki: markdown pages for decisions that belong
+ * neither in chat nor in the PRD
+ */
+
+import { useEffect, useState } from 'react';
+import { Pencil, Plus, Save, Search, Trash2, Eye } from 'lucide-react';
+import MessageContent from '../chat/MessageContent';
+import { createNote, deleteNote, getNotes, updateNote, type Note } from '../../api/notes';
+import type { ProjectSettings } from '../../types/tauri';
+
+interface NotesTabProps {
+  projectId: string;
+  /** Project settings JSON, for the session summaries option */
+  projectSettings?: string | null;
+  onSettingsChange: (settings: string) => Promise<void>;
+}
+
+function parseSettings(json?: string | null): ProjectSettings {
+  try {
+    return json ? (JSON.parse(json) as ProjectSettings) : {};
+  } catch {
+    return {};
+  }
+}
+
+export default function NotesTab({ projectId, projectSettings, onSettingsChange }: NotesTabProps) {
+  const [notes, setNotes] = useState<Note[]>([]);
+  const [search, setSearch] = useState('');
+  const [selectedId, setSelectedId] = useState<string | null>(null);
+  const [title, setTitle] = useState('');
+  const [content, setContent] = useState('');
+  const [editing, setEditing] = useState(false);
+  const [saving, setSaving] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const settings = parseSettings(projectSettings);
+  const selected = notes.find((note) => note.id === selectedId) ?? null;
+  const dirty = selected != null && (title !== selected.title || content !== selected.content);
+
+  useEffect(() => {
+    const timer = setTimeout(() => {
+      getNotes(projectId, search)
+        .then(setNotes)
+        .catch((err) => setError(String(err)));
+    }, 200);
+    return () => clearTimeout(timer);
+  }, [projectId, search]);
+
+  const selectNote = (note: Note) => {
+    setSelectedId(note.id);
+    setTitle(note.title);
+    setContent(note.content);
+    setEditing(false);
+    setError(null);
+  };
+
+  const handleCreate = async () => {
+    const existing = new Set(notes.map((note) => note.title));
+    let newTitle = 'Untitled';
+    for (let n = 2; existing.has(newTitle); n++) newTitle = `Untitled ${n}`;
+    try {
+      const note = await createNote(projectId, newTitle);
+      setNotes((current) => [note, ...current]);
+      selectNote(note);
+      setEditing(true);
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  const handleSave = async () => {
+    if (!selected) return;
+    setSaving(true);
+    setError(null);
+    try {
+      const saved = await updateNote(selected.id, { title, content });
+      setNotes((current) => [saved, ...current.filter((note) => note.id !== saved.id)]);
+      setEditing(false);
+    } catch (err) {
+      setError(String(err));
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  const handleDelete = async () => {
+    if (!selected || !confirm(`Delete "${selected.title}"?`)) return;
+    try {
+      await deleteNote(selected.id);
+      setNotes((current) => current.filter((note) => note.id !== selected.id));
+      setSelectedId(null);
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  const toggleSummaries = async (enabled: boolean) => {
+    try {
+      await onSettingsChange(JSON.stringify({ ...settings, session_summaries_to_notes: enabled }));
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  return (
+    <div className="flex gap-4 h-[calc(100vh-16rem)]">
+      <div className="w-64 shrink-0 flex flex-col gap-2">
+        <div className="flex gap-2">
+          <label className="input input-bordered input-sm flex items-center gap-2 flex-1">
+            <Search className="w-4 h-4 opacity-50" />
+            <input
+              type="text"
+              className="grow"
+              placeholder="Search notes"
+              value={search}
+              onChange={(e) => setSearch(e.target.value)}
+            />
+          </label>
+          <button className="btn btn-primary btn-sm btn-square" onClick={handleCreate} title="New note">
+            <Plus className="w-4 h-4" />
+          </button>
+        </div>
+        <ul className="menu menu-sm bg-base-200 rounded-box flex-1 overflow-y-auto flex-nowrap">
+          {notes.length === 0 && <li className="disabled"><span>{search ? 'No matching notes' : 'No notes yet'}</span></li>}
+          {notes.map((note) => (
+            <li key={note.id}>
+              <button className={note.id === selectedId ? 'active' : ''} onClick={() => selectNote(note)}>
+                <span className="truncate">{note.title}</span>
+              </button>
+            </li>
+          ))}
+        </ul>
+        <label className="label cursor-pointer justify-start gap-2" title="Append the agent's closing message of each finished turn to the Session summaries note">
+          <input
+            type="checkbox"
+            className="toggle toggle-sm toggle-primary"
+            checked={settings.session_summaries_to_notes ?? false}
+            onChange={(e) => toggleSummaries(e.target.checked)}
+          />
+          <span className="label-text text-xs">Save agent summaries</span>
+        </label>
+      </div>
+
+      <div className="flex-1 card bg-base-200 min-w-0">
+        <div className="card-body p-4 gap-3 min-h-0">
+          {error && <div className="alert alert-error text-sm">{error}</div>}
+          {!selected ? (
+            <p className="text-sm text-base-content/60 m-auto">Select a note or create one</p>
+          ) : (
+            <>
+              <div className="flex items-center gap-2">
+                {editing ? (
+                  <input
+                    type="text"
+                    className="input input-bordered input-sm flex-1 font-semibold"
+                    value={title}
+                    onChange={(e) => setTitle(e.target.value)}
+                  />
+                ) : (
+                  <h2 className="font-semibold text-lg flex-1 truncate">{selected.title}</h2>
+                )}
+                <button className="btn btn-ghost btn-sm gap-2" onClick={() => setEditing(!editing)}>
+                  {editing ? <Eye className="w-4 h-4" /> : <Pencil className="w-4 h-4" />}
+                  {editing ? 'Preview' : 'Edit'}
+                </button>
+                <button className="btn btn-success btn-sm gap-2" onClick={handleSave} disabled={!dirty || saving}>
+                  {saving ? <span className="loading loading-spinner loading-xs"></span> : <Save className="w-4 h-4" />}
+                  Save
+                </button>
+                <button className="btn btn-ghost btn-sm btn-square text-error" onClick={handleDelete} title="Delete note">
+                  <Trash2 className="w-4 h-4" />
+                </button>
+              </div>
+              {editing ? (
+                <textarea
+                  className="textarea textarea-bordered flex-1 font-mono text-sm"
+                  value={content}
+                  onChange={(e) => setContent(e.target.value)}
+                  placeholder="Write in markdown"
+                />
+              ) : (
+                <div className="flex-1 overflow-y-auto">
+                  {content.trim() ? (
+                    <MessageContent content={content} />
+                  ) : (
+                    <p className="text-sm text-base-content/60">Empty note</p>
+                  )}
+                </div>
+              )}
+            </>
+          )}
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -10,11 +10,19 @@ import {
   Copy,
   Check,
   Loader2,
+  Container,
 } from 'lucide-react';
 import StatsPanel from './StatsPanel';
+import ScriptsPanel from './ScriptsPanel';
+import LintPanel from './LintPanel';
+import DevServerPanel from './DevServerPanel';
+import HotspotsPanel from './HotspotsPanel';
+import CiPanel from './CiPanel';
+import SessionReplayPanel from './SessionReplayPanel';
 import { getActivities } from '../../api/activity';
 import { getProjectStats } from '../../api/stats';
-import type { ActivityLog, ProjectStats } from '../../types/tauri';
+import { getContainerEnvironment } from '../../api/containers';
+import type { ActivityLog, ContainerEnvironment, ProjectSettings, ProjectStats } from '../../types/tauri';
 import { useSessionStore } from '../../stores/sessionStore';
 import { useChatStore } from '../../stores/chatStore';
 import * as agentSessionApi from '../../api/agentSession';
@@ -26,6 +34,8 @@ export interface OverviewTabProps {
   projectId: string;
   agentType: string;
   projectPath: string;
+  /** Project settings JSON, for the execution profiles a session can use */
+  projectSettings?: string | null;
 }
 
 interface QuickMessage {
@@ -35,7 +45,15 @@ interface QuickMessage {
   timestamp: Date;
 }
 
-export default function OverviewTab({ projectId, agentType, projectPath }: OverviewTabProps) {
+function parseSettings(json?: string | null): ProjectSettings {
+  try {
+    return json ? (JSON.parse(json) as ProjectSettings) : {};
+  } catch {
+    return {};
+  }
+}
+
+export default function OverviewTab({ projectId, agentType, projectPath, projectSettings }: OverviewTabProps) {
   // Session from store (shared across components)
   const activeSession = useSessionStore((state) => state.getActiveSession(projectId));
   const setActiveSession = useSessionStore((state) => state.setActiveSession);
@@ -48,6 +66,12 @@ export default function OverviewTab({ projectId, agentType, projectPath }: Overv
   const [elapsedTime, setElapsedTime] = useState(0);
   const [copiedSessionId, setCopiedSessionId] = useState(false);
 
+  // Execution profile the next session starts with
+  const settings = parseSettings(projectSettings);
+  const executionProfiles = settings.execution_profiles ?? [];
+  const [executionProfile, setExecutionProfile] = useState(settings.default_execution_profile ?? '');
+  const [containerEnv, setContainerEnv] = useState<ContainerEnvironment | null>(null);
+
   // Quick chat state
   const [quickChatInput, setQuickChatInput] = useState('');
   const [quickMessages, setQuickMessages] = useState<QuickMessage[]>([]);
@@ -58,6 +82,9 @@ export default function OverviewTab({ projectId, agentType, projectPath }: Overv
   useEffect(() => {
     loadStats();
     checkForActiveSession();
+    getContainerEnvironment(projectId)
+      .then(setContainerEnv)
+      .catch((error) => console.error('Failed to detect container environment:', error));
   }, [projectId]);
 
   // Update elapsed time
@@ -124,7 +151,9 @@ export default function OverviewTab({ projectId, agentType, projectPath }: Overv
       const normalizedAgentType = agentType.toLowerCase().replace(/\s+/g, '-');
       const session = await agentSessionApi.startAgentSession(
         projectId,
-        normalizedAgentType
+        normalizedAgentType,
+        undefined,
+        executionProfile || undefined
       );
 
       setActiveSession(projectId, session);
@@ -343,7 +372,23 @@ export default function OverviewTab({ projectId, agentType, projectPath }: Overv
             </div>
 
             {/* Right: Action Button */}
-            <div>
+            <div className="flex items-center gap-2">
+              {!activeSession && executionProfiles.length > 0 && (
+                <select
+                  className="select select-bordered select-sm"
+                  value={executionProfile}
+                  onChange={(e) => setExecutionProfile(e.target.value)}
+                  title="Execution profile"
+                >
+                  <option value="">{settings.default_execution_profile ? 'Project default' : 'Unrestricted'}</option>
+                  {executionProfiles.map((profile) => (
+                    <option key={profile.name} value={profile.name}>
+                      {profile.name}
+                      {profile.run_in_container && ' (in container)'}
+                    </option>
+                  ))}
+                </select>
+              )}
               {activeSession ? (
                 <button
                   onClick={handleStopSession}
@@ -376,6 +421,46 @@ export default function OverviewTab({ projectId, agentType, projectPath }: Overv
         </div>
       </div>
 
+      {/* Container environment */}
+      {containerEnv && (
+        <div className="flex items-center gap-2 text-sm text-base-content/70">
+          <Container className="w-4 h-4" />
+          {containerEnv.devcontainer ? (
+            <span>
+              Dev container{containerEnv.devcontainer.name && <> <strong>{containerEnv.devcontainer.name}</strong></>} in{' '}
+              <code className="text-xs">{containerEnv.devcontainer.config_path}</code>
+            </span>
+          ) : (
+            <span>
+              Docker Compose in <code className="text-xs">{containerEnv.compose_files.join(', ')}</code>
+            </span>
+          )}
+          {!executionProfiles.some((profile) => profile.run_in_container) && (
+            <span className="text-base-content/50">
+              · Add an execution profile with run_in_container to run sessions inside it
+            </span>
+          )}
+        </div>
+      )}
+
+      {/* Dev server */}
+      <DevServerPanel projectId={projectId} />
+
+      {/* Project scripts */}
+      <ScriptsPanel projectId={projectId} />
+
+      {/* Linters and formatters */}
+      <LintPanel projectId={projectId} />
+
+      {/* CI runs for the remote */}
+      <CiPanel projectId={projectId} />
+
+      {/* Churn and complexity hotspots */}
+      <HotspotsPanel projectId={projectId} />
+
+      {/* Replay of past agent sessions */}
+      <SessionReplayPanel projectId={projectId} />
+
       {/* Stats Grid */}
       {isLoadingStats ? (
         <div className="flex justify-center py-8">
@@ -0,0 +1,99 @@
+/**
+ * ProjectRootsPanel - Lists the folders a project spans besides its root and adds or removes them
+ */
+
+import { useEffect, useState } from 'react';
+import { FolderPlus, FolderTree, Trash2 } from 'lucide-react';
+import * as tauriApi from '../../lib/tauri';
+import { addProjectRoot, listProjectRoots, removeProjectRoot, type ProjectRoot } from '../../api/projectRoots';
+
+interface ProjectRootsPanelProps {
+  projectId: string;
+}
+
+export default function ProjectRootsPanel({ projectId }: ProjectRootsPanelProps) {
+  const [roots, setRoots] = useState<ProjectRoot[]>([]);
+  const [label, setLabel] = useState('');
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    listProjectRoots(projectId)
+      .then(setRoots)
+      .catch((err) => console.error('Failed to list project roots:', err));
+  }, [projectId]);
+
+  const handleAdd = async () => {
+    try {
+      const path = await tauriApi.selectFolder();
+      if (!path) return;
+      const root = await addProjectRoot(projectId, path, label.trim() || undefined);
+      setRoots((prev) => [...prev, root]);
+      setLabel('');
+      setError(null);
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  const handleRemove = async (root: ProjectRoot) => {
+    if (!confirm(`Remove ${root.path} from this project? Nothing on disk is deleted.`)) return;
+    try {
+      await removeProjectRoot(root.id);
+      setRoots((prev) => prev.filter((r) => r.id !== root.id));
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  return (
+    <div className="card bg-base-200">
+      <div className="card-body gap-3">
+        <h2 className="card-title">
+          <FolderTree className="w-5 h-5" />
+          Additional Folders
+        </h2>
+
+        {error && <div className="alert alert-error text-sm">{error}</div>}
+
+        {roots.length === 0 ? (
+          <p className="text-sm text-base-content/60">Only the project folder is included</p>
+        ) : (
+          <div className="space-y-1">
+            {roots.map((root) => (
+              <div key={root.id} className="flex items-center gap-2 text-sm group">
+                {root.label && <span className="font-medium">{root.label}</span>}
+                <code className="flex-1 truncate text-base-content/70">{root.path}</code>
+                <button
+                  className="btn btn-ghost btn-xs btn-circle opacity-0 group-hover:opacity-100"
+                  onClick={() => handleRemove(root)}
+                  title="Remove"
+                >
+                  <Trash2 className="w-3 h-3" />
+                </button>
+              </div>
+            ))}
+          </div>
+        )}
+
+        <div className="flex items-center gap-2">
+          <input
+            type="text"
+            className="input input-bordered input-sm w-48"
+            value={label}
+            onChange={(e) => setLabel(e.target.value)}
+            placeholder="Label (optional)"
+          />
+          <button className="btn btn-ghost btn-sm gap-1" onClick={handleAdd}>
+            <FolderPlus className="w-4 h-4" />
+            Add folder
+          </button>
+        </div>
+
+        <p className="text-xs text-base-content/60">
+          Folders such as a shared library next to the app show up at the top of the file tree, and their changes
+          are tracked with the project's.
+        </p>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,167 @@
+/**
+ * ScriptsPanel - Runs the project's scripts (npm, cargo, make, go) and shows their output
+ */
+
+import { useEffect, useRef, useState } from 'react';
+import { Play, Square, CheckCircle, XCircle, Loader2, MinusCircle } from 'lucide-react';
+import {
+  cancelScript,
+  getScriptRuns,
+  listScripts,
+  listenToScriptFinished,
+  listenToScriptOutput,
+  runScript,
+  type ScriptConfig,
+  type ScriptRun,
+} from '../../api/scripts';
+
+interface ScriptsPanelProps {
+  projectId: string;
+}
+
+/** Lines of live output kept for the shown run */
+const MAX_LINES = 1000;
+
+function formatDuration(ms: number | null): string {
+  if (ms == null) return '';
+  return ms < 1000 ? `${ms}ms` : `${(ms / 1000).toFixed(1)}s`;
+}
+
+function StatusIcon({ status }: { status: ScriptRun['status'] }) {
+  switch (status) {
+    case 'running':
+      return <Loader2 className="w-4 h-4 animate-spin text-info" />;
+    case 'succeeded':
+      return <CheckCircle className="w-4 h-4 text-success" />;
+    case 'failed':
+      return <XCircle className="w-4 h-4 text-error" />;
+    default:
+      return <MinusCircle className="w-4 h-4 text-base-content/50" />;
+  }
+}
+
+export default function ScriptsPanel({ projectId }: ScriptsPanelProps) {
+  const [scripts, setScripts] = useState<ScriptConfig[]>([]);
+  const [selected, setSelected] = useState('');
+  const [runs, setRuns] = useState<ScriptRun[]>([]);
+  const [shownRunId, setShownRunId] = useState<string | null>(null);
+  const [liveOutput, setLiveOutput] = useState<Record<string, string[]>>({});
+  const [error, setError] = useState<string | null>(null);
+  const outputRef = useRef<HTMLPreElement>(null);
+
+  useEffect(() => {
+    listScripts(projectId)
+      .then((found) => {
+        setScripts(found);
+        setSelected(found[0]?.name ?? '');
+      })
+      .catch((err) => console.error('Failed to list scripts:', err));
+    getScriptRuns(projectId, 10)
+      .then(setRuns)
+      .catch((err) => console.error('Failed to load script runs:', err));
+
+    const unlistenOutput = listenToScriptOutput(({ run_id, line }) =>
+      setLiveOutput((current) => ({ ...current, [run_id]: [...(current[run_id] ?? []), line].slice(-MAX_LINES) }))
+    );
+    const unlistenFinished = listenToScriptFinished((run) => {
+      if (run.project_id !== projectId) return;
+      setRuns((current) => current.map((r) => (r.id === run.id ? run : r)));
+    });
+    return () => {
+      unlistenOutput.then((fn) => fn());
+      unlistenFinished.then((fn) => fn());
+    };
+  }, [projectId]);
+
+  useEffect(() => {
+    outputRef.current?.scrollTo({ top: outputRef.current.scrollHeight });
+  }, [liveOutput, shownRunId]);
+
+  if (scripts.length === 0 && runs.length === 0) return null;
+
+  const handleRun = async () => {
+    if (!selected) return;
+    setError(null);
+    try {
+      const run = await runScript(projectId, selected);
+      setRuns((current) => [run, ...current].slice(0, 10));
+      setShownRunId(run.id);
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  const shownRun = runs.find((run) => run.id === shownRunId);
+  const shownOutput = shownRun ? liveOutput[shownRun.id]?.join('\n') ?? shownRun.output ?? '' : null;
+
+  return (
+    <div className="card bg-base-200">
+      <div className="card-body p-4 gap-3">
+        <div className="flex items-center gap-2">
+          <h3 className="font-semibold flex-1">Scripts</h3>
+          <select
+            className="select select-bordered select-sm max-w-xs font-mono"
+            value={selected}
+            onChange={(e) => setSelected(e.target.value)}
+          >
+            {scripts.map((script) => (
+              <option key={script.name} value={script.name}>
+                {script.name}
+              </option>
+            ))}
+          </select>
+          <button className="btn btn-primary btn-sm gap-2" onClick={handleRun} disabled={!selected}>
+            <Play className="w-4 h-4" />
+            Run
+          </button>
+        </div>
+
+        {error && <div className="alert alert-error text-sm">{error}</div>}
+
+        {runs.length > 0 && (
+          <ul className="space-y-1">
+            {runs.map((run) => (
+              <li
+                key={run.id}
+                className={`flex items-center gap-2 text-sm rounded px-2 py-1 cursor-pointer hover:bg-base-300 ${
+                  run.id === shownRunId ? 'bg-base-300' : ''
+                }`}
+                onClick={() => setShownRunId(run.id === shownRunId ? null : run.id)}
+              >
+                <StatusIcon status={run.status} />
+                <code className="flex-1 truncate">{run.name}</code>
+                {run.exit_code != null && run.status === 'failed' && (
+                  <span className="text-xs text-error">exit {run.exit_code}</span>
+                )}
+                <span className="text-xs text-base-content/60">
+                  {run.status === 'running' ? 'running' : formatDuration(run.duration_ms)}
+                </span>
+                {run.status === 'running' && (
+                  <button
+                    className="btn btn-ghost btn-xs gap-1"
+                    onClick={(e) => {
+                      e.stopPropagation();
+                      cancelScript(run.id).catch((err) => console.error('Failed to cancel script:', err));
+                    }}
+                  >
+                    <Square className="w-3 h-3" />
+                    Stop
+                  </button>
+                )}
+              </li>
+            ))}
+          </ul>
+        )}
+
+        {shownOutput != null && (
+          <pre
+            ref={outputRef}
+            className="bg-base-300 rounded-lg p-3 max-h-80 overflow-auto text-xs font-mono whitespace-pre-wrap break-all"
+          >
+            {shownOutput || 'No output'}
+          </pre>
+        )}
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,231 @@
+/**
+ * SessionReplayPanel - Plays a past agent session's events back with their original timing,
+ * with bookmarks to mark moments and replay from them
+ */
+
+import { useEffect, useRef, useState } from 'react';
+import { Bookmark, Loader2, Play, Square, X } from 'lucide-react';
+import { getProjectSessions, type DbAgentSession } from '../../api/agentSession';
+import {
+  bookmarkSessionMoment,
+  deleteSessionBookmark,
+  listSessionBookmarks,
+  listenToReplayEvents,
+  listenToReplayFinished,
+  replaySession,
+  stopReplay,
+  type ParsedAgentEvent,
+  type ReplayedEvent,
+  type SessionBookmark,
+} from '../../api/sessionReplay';
+
+interface SessionReplayPanelProps {
+  projectId: string;
+}
+
+const SPEEDS = [1, 2, 5, 10, 50];
+
+/** One line describing a parsed event */
+function describe(event: ParsedAgentEvent): string {
+  const detail = event.path ?? event.command ?? event.message ?? event.content ?? event.description ?? event.name;
+  const label = event.type.replace(/_/g, ' ');
+  return typeof detail === 'string' ? `${label}: ${detail}` : label;
+}
+
+export default function SessionReplayPanel({ projectId }: SessionReplayPanelProps) {
+  const [sessions, setSessions] = useState<DbAgentSession[]>([]);
+  const [selected, setSelected] = useState('');
+  const [speed, setSpeed] = useState(10);
+  const [replayId, setReplayId] = useState<string | null>(null);
+  const [events, setEvents] = useState<ReplayedEvent[]>([]);
+  const [bookmarks, setBookmarks] = useState<SessionBookmark[]>([]);
+  const [starting, setStarting] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const replayRef = useRef<string | null>(null);
+  const listRef = useRef<HTMLDivElement>(null);
+
+  useEffect(() => {
+    getProjectSessions(projectId)
+      .then((found) => {
+        setSessions(found);
+        setSelected(found[0]?.id ?? '');
+      })
+      .catch((err) => console.error('Failed to load sessions:', err));
+  }, [projectId]);
+
+  useEffect(() => {
+    setBookmarks([]);
+    if (!selected) return;
+    listSessionBookmarks(selected)
+      .then(setBookmarks)
+      .catch((err) => console.error('Failed to load bookmarks:', err));
+  }, [selected]);
+
+  useEffect(() => {
+    const unlistenEvents = listenToReplayEvents((event) => {
+      if (event.replay_id === replayRef.current) setEvents((current) => [...current, event]);
+    });
+    const unlistenFinished = listenToReplayFinished(({ replay_id }) => {
+      if (replay_id === replayRef.current) {
+        replayRef.current = null;
+        setReplayId(null);
+      }
+    });
+    return () => {
+      unlistenEvents.then((fn) => fn());
+      unlistenFinished.then((fn) => fn());
+    };
+  }, []);
+
+  useEffect(() => {
+    listRef.current?.scrollTo({ top: listRef.current.scrollHeight });
+  }, [events]);
+
+  if (sessions.length === 0) return null;
+
+  const handleReplay = async (fromSeq?: number) => {
+    setStarting(true);
+    setError(null);
+    setEvents([]);
+    try {
+      const info = await replaySession(selected, speed, fromSeq);
+      replayRef.current = info.replay_id;
+      setReplayId(info.replay_id);
+    } catch (err) {
+      setError(String(err));
+    } finally {
+      setStarting(false);
+    }
+  };
+
+  const handleStop = async () => {
+    if (!replayId) return;
+    try {
+      await stopReplay(replayId, selected);
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  const handleBookmark = async (eventSeq: number) => {
+    const existing = bookmarks.find((b) => b.event_seq === eventSeq);
+    const note = window.prompt('Note for this moment', existing?.note ?? '');
+    if (note === null) return;
+    try {
+      const bookmark = await bookmarkSessionMoment(selected, eventSeq, note);
+      setBookmarks((current) =>
+        [...current.filter((b) => b.id !== bookmark.id), bookmark].sort((a, b) => a.event_seq - b.event_seq)
+      );
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  const handleDeleteBookmark = async (bookmarkId: string) => {
+    try {
+      await deleteSessionBookmark(bookmarkId);
+      setBookmarks((current) => current.filter((b) => b.id !== bookmarkId));
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  const startedAt = events[0]?.recorded_at ?? 0;
+  const bookmarked = new Set(bookmarks.map((b) => b.event_seq));
+
+  return (
+    <div className="card bg-base-200">
+      <div className="card-body p-4 gap-3">
+        <div className="flex items-center gap-2">
+          <h3 className="font-semibold flex-1">Session Replay</h3>
+          <select
+            className="select select-bordered select-sm max-w-xs"
+            value={selected}
+            onChange={(e) => setSelected(e.target.value)}
+            disabled={replayId != null}
+          >
+            {sessions.map((session) => (
+              <option key={session.id} value={session.id}>
+                {session.agent_type} · {new Date(session.started_at * 1000).toLocaleString()}
+              </option>
+            ))}
+          </select>
+          <select
+            className="select select-bordered select-sm"
+            value={speed}
+            onChange={(e) => setSpeed(Number(e.target.value))}
+            disabled={replayId != null}
+          >
+            {SPEEDS.map((s) => (
+              <option key={s} value={s}>
+                {s}x
+              </option>
+            ))}
+          </select>
+          {replayId ? (
+            <button className="btn btn-ghost btn-sm gap-2" onClick={handleStop}>
+              <Square className="w-4 h-4" />
+              Stop
+            </button>
+          ) : (
+            <button className="btn btn-primary btn-sm gap-2" onClick={() => handleReplay()} disabled={!selected || starting}>
+              {starting ? <Loader2 className="w-4 h-4 animate-spin" /> : <Play className="w-4 h-4" />}
+              Replay
+            </button>
+          )}
+        </div>
+
+        {error && <div className="alert alert-error text-sm">{error}</div>}
+
+        {bookmarks.length > 0 && (
+          <div className="space-y-1">
+            {bookmarks.map((bookmark) => (
+              <div key={bookmark.id} className="flex items-center gap-2 text-sm">
+                <Bookmark className="w-4 h-4 text-warning shrink-0" />
+                <span className="text-base-content/50 shrink-0">
+                  Event {bookmark.event_seq} · {new Date(bookmark.recorded_at).toLocaleTimeString()}
+                </span>
+                <span className="flex-1 truncate">{bookmark.note}</span>
+                <button
+                  className="btn btn-ghost btn-xs gap-1"
+                  onClick={() => handleReplay(bookmark.event_seq)}
+                  disabled={replayId != null || starting}
+                  title="Replay from here"
+                >
+                  <Play className="w-3 h-3" />
+                </button>
+                <button
+                  className="btn btn-ghost btn-xs"
+                  onClick={() => handleDeleteBookmark(bookmark.id)}
+                  title="Remove bookmark"
+                >
+                  <X className="w-3 h-3" />
+                </button>
+              </div>
+            ))}
+          </div>
+        )}
+
+        {events.length > 0 && (
+          <div ref={listRef} className="bg-base-300 rounded p-2 max-h-64 overflow-auto font-mono text-xs space-y-0.5">
+            {events.map((event) => (
+              <div key={event.seq} className="group flex items-center gap-3">
+                <span className="text-base-content/40 shrink-0 w-14 text-right">
+                  +{((event.recorded_at - startedAt) / 1000).toFixed(1)}s
+                </span>
+                <span className="truncate flex-1">{describe(event.event)}</span>
+                <button
+                  className={`shrink-0 ${bookmarked.has(event.seq) ? 'text-warning' : 'opacity-0 group-hover:opacity-60'}`}
+                  onClick={() => handleBookmark(event.seq)}
+                  title="Bookmark this moment"
+                >
+                  <Bookmark className="w-3 h-3" />
+                </button>
+              </div>
+            ))}
+          </div>
+        )}
+      </div>
+    </div>
+  );
+}
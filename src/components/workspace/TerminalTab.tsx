@@ -0,0 +1,172 @@
+import { useState, useEffect, useRef } from 'react';
+import { Plus, X, Terminal as TerminalIcon } from 'lucide-react';
+import {
+  closeTerminal,
+  listTerminals,
+  listenToTerminalExit,
+  listenToTerminalOutput,
+  openTerminal,
+  writeTerminal,
+  type TerminalInfo,
+} from '../../api/terminal';
+
+interface TerminalTabProps {
+  projectId: string;
+}
+
+/** Output kept per terminal, in characters */
+const MAX_OUTPUT = 200_000;
+
+// eslint-disable-next-line no-control-regex
+const ANSI_ESCAPE = /\x1b(?:\[[0-?]*[ -/]*[@-~]|\][^\x07\x1b]*(?:\x07|\x1b\\)|[()][A-Za-z0-9]|[=>NOM78c])/g;
+
+/**
+ * Append terminal output as plain text, dropping escape sequences, bells and
+ * carriage returns, and applying backspaces
+ */
+export function appendOutput(text: string, data: string): string {
+  let out = text;
+  for (const ch of data.replace(ANSI_ESCAPE, '')) {
+    if (ch === '\b') {
+      if (!out.endsWith('\n')) out = out.slice(0, -1);
+    } else if (ch !== '\r' && ch !== '\x07') {
+      out += ch;
+    }
+  }
+  return out.length > MAX_OUTPUT ? out.slice(out.length - MAX_OUTPUT) : out;
+}
+
+export default function TerminalTab({ projectId }: TerminalTabProps) {
+  const [terminals, setTerminals] = useState<TerminalInfo[]>([]);
+  const [activeId, setActiveId] = useState<string | null>(null);
+  const [output, setOutput] = useState<Record<string, string>>({});
+  const [input, setInput] = useState('');
+  const [error, setError] = useState<string | null>(null);
+  const outputRef = useRef<HTMLPreElement>(null);
+
+  useEffect(() => {
+    const unlistenOutput = listenToTerminalOutput(({ terminal_id, data }) =>
+      setOutput((current) => ({ ...current, [terminal_id]: appendOutput(current[terminal_id] ?? '', data) }))
+    );
+    const unlistenExit = listenToTerminalExit(({ terminal_id, exit_code }) => {
+      setOutput((current) => ({
+        ...current,
+        [terminal_id]: `${current[terminal_id] ?? ''}\n[Process exited${exit_code != null ? ` with code ${exit_code}` : ''}]\n`,
+      }));
+      setTerminals((current) => current.filter((t) => t.terminal_id !== terminal_id));
+    });
+
+    // Reattach to terminals left open, or start one
+    listTerminals(projectId)
+      .then(async (open) => {
+        if (open.length === 0) {
+          open = [await openTerminal(projectId)];
+        }
+        setTerminals(open);
+        setActiveId(open[0].terminal_id);
+      })
+      .catch((err) => setError(String(err)));
+
+    return () => {
+      unlistenOutput.then((fn) => fn());
+      unlistenExit.then((fn) => fn());
+    };
+  }, [projectId]);
+
+  useEffect(() => {
+    outputRef.current?.scrollTo({ top: outputRef.current.scrollHeight });
+  }, [output, activeId]);
+
+  const handleNew = async () => {
+    try {
+      const terminal = await openTerminal(projectId);
+      setTerminals((current) => [...current, terminal]);
+      setActiveId(terminal.terminal_id);
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  const handleClose = async (terminalId: string) => {
+    await closeTerminal(terminalId).catch((err) => console.error('Failed to close terminal:', err));
+    setTerminals((current) => current.filter((t) => t.terminal_id !== terminalId));
+    if (activeId === terminalId) setActiveId(null);
+  };
+
+  const shown = activeId ?? terminals[0]?.terminal_id ?? null;
+
+  const send = (data: string) => {
+    if (!shown) return;
+    writeTerminal(shown, data).catch((err) => setError(String(err)));
+  };
+
+  const handleKeyDown = (e: React.KeyboardEvent<HTMLInputElement>) => {
+    if (e.key === 'Enter') {
+      send(`${input}\r`);
+      setInput('');
+    } else if (e.key === 'c' && e.ctrlKey) {
+      e.preventDefault();
+      send('\x03');
+      setInput('');
+    } else if (e.key === 'Tab') {
+      e.preventDefault();
+      send(`${input}\t`);
+      setInput('');
+    }
+  };
+
+  return (
+    <div className="card bg-base-200">
+      <div className="card-body p-4 gap-3">
+        <div className="flex items-center gap-2">
+          <div className="tabs tabs-boxed bg-transparent flex-1 overflow-x-auto">
+            {terminals.map((terminal, index) => (
+              <div
+                key={terminal.terminal_id}
+                className={`tab gap-2 ${terminal.terminal_id === shown ? 'tab-active' : ''}`}
+                onClick={() => setActiveId(terminal.terminal_id)}
+                title={terminal.cwd}
+              >
+                <TerminalIcon className="w-4 h-4" />
+                Terminal {index + 1}
+                <button
+                  className="btn btn-ghost btn-xs btn-circle"
+                  onClick={(e) => {
+                    e.stopPropagation();
+                    handleClose(terminal.terminal_id);
+                  }}
+                >
+                  <X className="w-3 h-3" />
+                </button>
+              </div>
+            ))}
+          </div>
+          <button className="btn btn-ghost btn-sm gap-2" onClick={handleNew}>
+            <Plus className="w-4 h-4" />
+            New Terminal
+          </button>
+        </div>
+
+        {error && <div className="alert alert-error text-sm">{error}</div>}
+
+        <pre
+          ref={outputRef}
+          className="bg-base-300 rounded-lg p-3 h-[28rem] overflow-auto text-xs font-mono whitespace-pre-wrap break-all"
+        >
+          {shown ? output[shown] ?? '' : 'No terminal open'}
+        </pre>
+
+        <input
+          type="text"
+          value={input}
+          onChange={(e) => setInput(e.target.value)}
+          onKeyDown={handleKeyDown}
+          disabled={!shown}
+          placeholder="Type a command and press Enter (Ctrl+C to interrupt)"
+          className="input input-bordered input-sm w-full font-mono"
+          autoFocus
+        />
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,69 @@
+/**
+ * App Errors
+ *
+ * Commands reject with { kind, message, details }. AppError wraps that so
+ * callers can branch on `kind`, while `${err}` and `String(err)` still give
+ * the plain message that commands used to reject with.
+ */
+
+export type AppErrorKind =
+  | 'not_found'
+  | 'invalid_input'
+  | 'conflict'
+  | 'permission_denied'
+  | 'database_locked'
+  | 'database'
+  | 'io'
+  | 'network'
+  | 'process'
+  | 'internal';
+
+export interface AppErrorPayload {
+  kind: AppErrorKind;
+  message: string;
+  details?: string;
+}
+
+export class AppError extends Error {
+  kind: AppErrorKind;
+  details?: string;
+
+  constructor({ kind, message, details }: AppErrorPayload) {
+    super(message);
+    this.name = 'AppError';
+    this.kind = kind;
+    this.details = details;
+  }
+
+  toString(): string {
+    return this.message;
+  }
+}
+
+function isAppErrorPayload(value: unknown): value is AppErrorPayload {
+  return (
+    typeof value === 'object' &&
+    value !== null &&
+    typeof (value as AppErrorPayload).kind === 'string' &&
+    typeof (value as AppErrorPayload).message === 'string'
+  );
+}
+
+/**
+ * Normalize anything a command can reject with into an AppError
+ *
+ * Commands that still return plain strings become 'internal' errors.
+ */
+export function toAppError(error: unknown): AppError {
+  if (error instanceof AppError) return error;
+  if (isAppErrorPayload(error)) return new AppError(error);
+  if (error instanceof Error) return new AppError({ kind: 'internal', message: error.message });
+  return new AppError({ kind: 'internal', message: String(error) });
+}
+
+/**
+ * Whether an error is of the given kind, e.g. isErrorKind(err, 'not_found')
+ */
+export function isErrorKind(error: unknown, kind: AppErrorKind): boolean {
+  return toAppError(error).kind === kind;
+}
@@ -0,0 +1,16 @@
+/**
+ * Command invocation
+ *
+ * Tauri's invoke(), rejecting with an AppError instead of the raw payload.
+ */
+
+import { invoke as tauriInvoke, type InvokeArgs, type InvokeOptions } from '@tauri-apps/api/core';
+import { toAppError } from './errors';
+
+export async function invoke<T>(command: string, args?: InvokeArgs, options?: InvokeOptions): Promise<T> {
+  try {
+    return await tauriInvoke<T>(command, args, options);
+  } catch (error) {
+    throw toAppError(error);
+  }
+}
@@ -1,5 +1,5 @@
 // Tauri API wrapper for type-safe command invocations
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from './invoke';
 import type {
   Project,
   CreateProjectInput,
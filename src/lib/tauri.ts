@@ -3,7 +3,7 @@
  * Type-safe wrapper functions for all Tauri backend commands
  */
 
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from './invoke';
 import type { Project, CreateProjectInput, AgentType } from '../types/project';
 
 /**
@@ -55,6 +55,7 @@ interface BackendUpdateProjectInput {
   agent_type?: string;
   status?: string;
   prd_content?: string;
+  prd_author?: 'user' | 'ai';
   settings?: string;
 }
 
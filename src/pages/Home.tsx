@@ -1,10 +1,15 @@
 import { useNavigate } from 'react-router-dom';
 import { useProjectStore } from '../stores/projectStore';
-import { Plus, Folder, Clock, Archive, CheckCircle, LogOut, Monitor, Globe } from 'lucide-react';
+import { Plus, Folder, Clock, Archive, CheckCircle, LogOut, Monitor, Globe, Package, GitBranch, Sparkles } from 'lucide-react';
 import { useEffect, useState } from 'react';
 import { useAuthStore } from '../stores/authStore';
 import { useMachineStore } from '../stores/machineStore';
 import { isWeb } from '../lib/platform';
+import { importProjectBundle, pickProjectBundle } from '../api/projectBundles';
+import CloneRepositoryModal from '../components/modals/CloneRepositoryModal';
+import ContinueWhereLeftOff from '../components/ContinueWhereLeftOff';
+import GettingStarted from '../components/GettingStarted';
+import { createDemoProject } from '../api/demoProject';
 
 export default function Home() {
   const navigate = useNavigate();
@@ -12,6 +17,8 @@ export default function Home() {
   const loadProjects = useProjectStore((state) => state.loadProjects);
   const updateProject = useProjectStore((state) => state.updateProject);
   const [showAll, setShowAll] = useState(false);
+  const [showClone, setShowClone] = useState(false);
+  const [isCreatingDemo, setIsCreatingDemo] = useState(false);
 
   // Auth state for user indicator
   const { user, isAuthenticated, logout } = useAuthStore();
@@ -44,6 +51,43 @@ export default function Home() {
     }
   };
 
+  const handleImportBundle = async () => {
+    try {
+      const picked = await pickProjectBundle();
+      if (!picked) return;
+      const { path, manifest } = picked;
+      // The project usually lives somewhere else on this machine; extra roots
+      // keep their exported paths and may need re-adding
+      const extraRoots = manifest.extra_roots ?? [];
+      const extraNote = extraRoots.length
+        ? `\n\nExtra folders are imported with their original paths:\n${extraRoots.join('\n')}`
+        : '';
+      const rootPath = window.prompt(
+        `Import "${manifest.project_name}". Project folder on this machine:${extraNote}`,
+        manifest.root_path
+      );
+      if (rootPath === null) return;
+      const projectId = await importProjectBundle(path, rootPath.trim() || undefined);
+      await loadProjects();
+      navigate(`/workspace/${projectId}`);
+    } catch (error) {
+      alert(`Failed to import project: ${error}`);
+    }
+  };
+
+  const handleCreateDemo = async () => {
+    setIsCreatingDemo(true);
+    try {
+      const project = await createDemoProject();
+      await loadProjects();
+      navigate(`/workspace/${project.id}`);
+    } catch (error) {
+      alert(`Failed to create demo project: ${error}`);
+    } finally {
+      setIsCreatingDemo(false);
+    }
+  };
+
   return (
     <div className="min-h-screen bg-base-100">
       {/* Header */}
@@ -70,6 +114,18 @@ export default function Home() {
             <Folder className="w-4 h-4" />
             Add Existing
           </button>
+          {!webMode && (
+            <button onClick={() => setShowClone(true)} className="btn btn-ghost gap-2" title="Clone a git repository">
+              <GitBranch className="w-4 h-4" />
+              Clone
+            </button>
+          )}
+          {!webMode && (
+            <button onClick={handleImportBundle} className="btn btn-ghost gap-2" title="Import a project bundle">
+              <Package className="w-4 h-4" />
+              Import
+            </button>
+          )}
           <button
             onClick={() => navigate('/wizard')}
             className="btn btn-primary gap-2"
@@ -122,6 +178,7 @@ export default function Home() {
         {recentProjects.length === 0 ? (
           // Empty State
           <div className="flex flex-col items-center justify-center min-h-[60vh]">
+            <GettingStarted />
             <div className="w-24 h-24 rounded-full bg-base-200 flex items-center justify-center mb-6">
               <Folder className="w-12 h-12 text-base-content/40" />
             </div>
@@ -136,10 +193,24 @@ export default function Home() {
               <Plus className="w-5 h-5" />
               Create Your First Project
             </button>
+            {!webMode && (
+              <button
+                onClick={handleCreateDemo}
+                disabled={isCreatingDemo}
+                className="btn btn-ghost gap-2 mt-2"
+                title="A sample project with tasks, a change to review and a scripted agent"
+              >
+                {isCreatingDemo ? <span className="loading loading-spinner loading-sm" /> : <Sparkles className="w-4 h-4" />}
+                Try a Demo Project
+              </button>
+            )}
           </div>
         ) : (
           // Projects List
           <div className="space-y-8">
+            <GettingStarted />
+            <ContinueWhereLeftOff />
+
             <div>
               <h2 className="text-2xl font-bold mb-4 flex items-center gap-2">
                 <Clock className="w-6 h-6" />
@@ -255,6 +326,16 @@ export default function Home() {
           </div>
         )}
       </div>
+
+      <CloneRepositoryModal
+        isOpen={showClone}
+        onClose={() => setShowClone(false)}
+        onCreated={async (project) => {
+          setShowClone(false);
+          await loadProjects();
+          navigate(`/workspace/${project.id}`);
+        }}
+      />
     </div>
   );
 }
@@ -8,6 +8,8 @@ import type { AgentType } from '../types/project';
 import type { ProjectAnalysisResult } from '../types/tauri';
 import AgentSelector from '../components/AgentSelector';
 import FolderPicker from '../components/FolderPicker';
+import ImportCliSessionsModal from '../components/modals/ImportCliSessionsModal';
+import { findCliSessions, type DetectedCliSessions } from '../api/chatTabs';
 
 interface FormData {
   name: string;
@@ -57,6 +59,8 @@ export default function ProjectWizard() {
   const [errors, setErrors] = useState<FormErrors>({});
   const [isAnalyzing, setIsAnalyzing] = useState(false);
   const [aiAnalysis, setAiAnalysis] = useState<ProjectAnalysisResult | null>(null);
+  // Set once an existing folder turns out to have agent sessions worth importing
+  const [sessionImport, setSessionImport] = useState<{ projectId: string; detected: DetectedCliSessions[] } | null>(null);
 
   const updateFormData = (field: keyof FormData, value: any) => {
     setFormData((prev) => ({ ...prev, [field]: value }));
@@ -206,6 +210,18 @@ export default function ProjectWizard() {
 
       const project = await createProject(projectInput);
 
+      // Offer to bring in the terminal sessions agents already have for this folder
+      if (mode === 'existing') {
+        const detected = await findCliSessions(project.root_path).catch((err) => {
+          console.error('Failed to look for existing CLI sessions:', err);
+          return [];
+        });
+        if (detected.length > 0) {
+          setSessionImport({ projectId: project.id, detected });
+          return;
+        }
+      }
+
       // Navigate to the workspace
       navigate(`/workspace/${project.id}`);
     } catch (error) {
@@ -713,6 +729,15 @@ export default function ProjectWizard() {
           </div>
         </form>
       </div>
+
+      {sessionImport && (
+        <ImportCliSessionsModal
+          projectId={sessionImport.projectId}
+          projectPath={formData.path}
+          detected={sessionImport.detected}
+          onDone={() => navigate(`/workspace/${sessionImport.projectId}`)}
+        />
+      )}
     </div>
   );
 }
@@ -1,9 +1,28 @@
 import { useState, useEffect } from 'react';
-import { Settings as SettingsIcon, Mic, Key, Download, Check, Loader2, AlertCircle, ChevronRight, Globe, Monitor, Wifi, WifiOff, LogIn, UserPlus, Trash2, Pencil, X } from 'lucide-react';
+import { Settings as SettingsIcon, Mic, Key, Download, Check, Loader2, AlertCircle, ChevronRight, Globe, Monitor, Wifi, WifiOff, Smartphone, RefreshCw, LogIn, UserPlus, Trash2, Pencil, X, Volume2, GitBranch, Bot, Eye } from 'lucide-react';
 import { useSettingsStore, type WhisperModel } from '../stores/settingsStore';
 import { useMachineStore, type MachineInfo } from '../stores/machineStore';
 import { serverConnection } from '../services/serverConnection';
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from '../lib/invoke';
+import { speakText } from '../api/speech';
+import {
+  getAppSettings,
+  setAppSettings,
+  getApiServerStatus,
+  regenerateApiToken,
+  getDatabaseMode,
+  pickDatabaseFile,
+  openReadOnlyDatabase,
+  closeReadOnlyDatabase,
+  type AppSettings,
+  type DatabaseMode,
+  type ApiServerSettings,
+  type ApiServerStatus,
+} from '../api/appSettings';
+import { isTauri } from '../lib/platform';
+import DefaultAgentSelect from '../components/DefaultAgentSelect';
+import { openLogFolder } from '../api/logs';
+import { checkForUpdates, installUpdate, type UpdateInfo, type UpdateChannel } from '../api/updates';
 
 // Fetch hostname from system
 async function getSystemHostname(): Promise<string> {
@@ -14,15 +33,44 @@ async function getSystemHostname(): Promise<string> {
   }
 }
 
+// Languages offered for transcription; Whisper accepts any ISO 639-1 code
+const transcriptionLanguages: [string, string][] = [
+  ['auto', 'Auto-detect'],
+  ['en', 'English'],
+  ['es', 'Spanish'],
+  ['fr', 'French'],
+  ['de', 'German'],
+  ['it', 'Italian'],
+  ['pt', 'Portuguese'],
+  ['nl', 'Dutch'],
+  ['pl', 'Polish'],
+  ['ru', 'Russian'],
+  ['uk', 'Ukrainian'],
+  ['tr', 'Turkish'],
+  ['ar', 'Arabic'],
+  ['hi', 'Hindi'],
+  ['ja', 'Japanese'],
+  ['ko', 'Korean'],
+  ['zh', 'Chinese'],
+];
+
 export default function Settings() {
   const {
     whisper,
+    speech,
     server,
     setWhisperProvider,
     setOpenAIApiKey,
     setLocalModel,
     setLocalInstalled,
     setLocalModelDownloaded,
+    setTranscriptionLanguage,
+    setTranslateToEnglish,
+    setConfirmBeforeSend,
+    setReadRepliesAloud,
+    setMinTurnSeconds,
+    setSpeechVoice,
+    setSpeechRate,
     setServerEnabled,
     setServerUrl,
     setMachineName,
@@ -52,6 +100,103 @@ export default function Settings() {
   const [isDeleting, setIsDeleting] = useState(false);
   const [isRenaming, setIsRenaming] = useState(false);
 
+  // App-wide desktop settings
+  const [appSettings, setAppSettingsState] = useState<AppSettings | null>(null);
+
+  useEffect(() => {
+    if (!isTauri()) return;
+    getAppSettings()
+      .then(setAppSettingsState)
+      .catch((err) => console.error('Failed to load app settings:', err));
+  }, []);
+
+  // Another machine's database, when watching it read-only
+  const [databaseMode, setDatabaseMode] = useState<DatabaseMode | null>(null);
+
+  useEffect(() => {
+    if (!isTauri()) return;
+    getDatabaseMode()
+      .then(setDatabaseMode)
+      .catch((err) => console.error('Failed to get database mode:', err));
+  }, []);
+
+  const handleWatchMachine = async () => {
+    try {
+      const path = await pickDatabaseFile();
+      if (path) await openReadOnlyDatabase(path);
+    } catch (err) {
+      setError(`Failed to open database: ${err}`);
+    }
+  };
+
+  const handleStopWatching = async () => {
+    try {
+      await closeReadOnlyDatabase();
+    } catch (err) {
+      setError(`Failed to reopen this machine's database: ${err}`);
+    }
+  };
+
+  const [availableUpdate, setAvailableUpdate] = useState<UpdateInfo | null>(null);
+  const [updateStatus, setUpdateStatus] = useState<'idle' | 'checking' | 'up-to-date' | 'installing'>('idle');
+
+  const handleCheckForUpdates = async () => {
+    setUpdateStatus('checking');
+    try {
+      const update = await checkForUpdates();
+      setAvailableUpdate(update);
+      setUpdateStatus(update ? 'idle' : 'up-to-date');
+    } catch (err) {
+      setUpdateStatus('idle');
+      setError(`${err}`);
+    }
+  };
+
+  const handleInstallUpdate = async () => {
+    setUpdateStatus('installing');
+    try {
+      await installUpdate();
+    } catch (err) {
+      setUpdateStatus('idle');
+      setError(`${err}`);
+    }
+  };
+
+  const [apiStatus, setApiStatus] = useState<ApiServerStatus | null>(null);
+
+  useEffect(() => {
+    if (!isTauri()) return;
+    getApiServerStatus()
+      .then(setApiStatus)
+      .catch((err) => console.error('Failed to load local API status:', err));
+  }, []);
+
+  const updateAppSettings = async (changes: Partial<AppSettings>) => {
+    if (!appSettings) return;
+    try {
+      setAppSettingsState(await setAppSettings({ ...appSettings, ...changes }));
+      if (changes.api_server) {
+        setApiStatus(await getApiServerStatus());
+      }
+    } catch (err) {
+      setError(`Failed to save app settings: ${err}`);
+    }
+  };
+
+  const updateApiServer = (changes: Partial<ApiServerSettings>) => {
+    if (!appSettings) return;
+    updateAppSettings({ api_server: { ...appSettings.api_server, ...changes } });
+  };
+
+  const handleRegenerateApiToken = async () => {
+    try {
+      setAppSettingsState(await regenerateApiToken());
+      setApiStatus(await getApiServerStatus());
+    } catch (err) {
+      setError(`Failed to regenerate token: ${err}`);
+    }
+  };
+
   // Auto-populate machine name from system hostname
   useEffect(() => {
     if (!server.machineName) {
@@ -721,6 +866,53 @@ export default function Settings() {
                 </div>
               )}
 
+              {/* Language Settings */}
+              {whisper.provider !== 'none' && (
+                <div className="bg-base-300/50 rounded-lg p-4 mb-4">
+                  <div className="form-control mb-2">
+                    <label className="label">
+                      <span className="label-text">Spoken Language</span>
+                    </label>
+                    <select
+                      value={whisper.language}
+                      onChange={(e) => setTranscriptionLanguage(e.target.value)}
+                      className="select select-bordered w-full"
+                    >
+                      {transcriptionLanguages.map(([code, name]) => (
+                        <option key={code} value={code}>
+                          {name}
+                        </option>
+                      ))}
+                    </select>
+                    <label className="label">
+                      <span className="label-text-alt text-base-content/60">
+                        Setting the language skips detection and avoids misrecognized short prompts.
+                      </span>
+                    </label>
+                  </div>
+
+                  <label className="label cursor-pointer justify-start gap-3">
+                    <input
+                      type="checkbox"
+                      checked={whisper.translate}
+                      onChange={(e) => setTranslateToEnglish(e.target.checked)}
+                      className="checkbox checkbox-primary checkbox-sm"
+                    />
+                    <span className="label-text">Translate to English</span>
+                  </label>
+
+                  <label className="label cursor-pointer justify-start gap-3">
+                    <input
+                      type="checkbox"
+                      checked={whisper.confirmBeforeSend}
+                      onChange={(e) => setConfirmBeforeSend(e.target.checked)}
+                      className="checkbox checkbox-primary checkbox-sm"
+                    />
+                    <span className="label-text">Review dictated prompts before sending</span>
+                  </label>
+                </div>
+              )}
+
               {/* Error Message */}
               {error && (
                 <div className="alert alert-error">
@@ -748,6 +940,386 @@ export default function Settings() {
             </div>
           </div>
         </section>
+
+        {/* Read Aloud Section */}
+        <section className="mb-8">
+          <div className="flex items-center gap-2 mb-4">
+            <Volume2 className="w-5 h-5 text-primary" />
+            <h2 className="text-lg font-semibold">Read Aloud</h2>
+          </div>
+
+          <div className="card bg-base-200 shadow-sm">
+            <div className="card-body">
+              <p className="text-sm text-base-content/70 mb-4">
+                Hear a short summary of the agent's reply when a long-running turn finishes, using your
+                system's voices.
+              </p>
+
+              <label className="label cursor-pointer justify-start gap-3">
+                <input
+                  type="checkbox"
+                  checked={speech.readRepliesAloud}
+                  onChange={(e) => setReadRepliesAloud(e.target.checked)}
+                  className="checkbox checkbox-primary checkbox-sm"
+                />
+                <span className="label-text">Read replies aloud after long turns</span>
+              </label>
+
+              <div className="grid grid-cols-1 md:grid-cols-3 gap-4 mt-2">
+                <div className="form-control">
+                  <label className="label">
+                    <span className="label-text">Minimum turn length (seconds)</span>
+                  </label>
+                  <input
+                    type="number"
+                    min={0}
+                    value={speech.minTurnSeconds}
+                    onChange={(e) => setMinTurnSeconds(Math.max(0, Number(e.target.value) || 0))}
+                    className="input input-bordered w-full"
+                  />
+                </div>
+
+                <div className="form-control">
+                  <label className="label">
+                    <span className="label-text">Voice</span>
+                  </label>
+                  <input
+                    type="text"
+                    value={speech.voice}
+                    onChange={(e) => setSpeechVoice(e.target.value)}
+                    placeholder="System default"
+                    className="input input-bordered w-full"
+                  />
+                </div>
+
+                <div className="form-control">
+                  <label className="label">
+                    <span className="label-text">Speed (words per minute)</span>
+                  </label>
+                  <input
+                    type="number"
+                    min={80}
+                    max={400}
+                    value={speech.rate}
+                    onChange={(e) => setSpeechRate(Number(e.target.value) || 180)}
+                    className="input input-bordered w-full"
+                  />
+                </div>
+              </div>
+
+              <div className="mt-4">
+                <button
+                  onClick={() =>
+                    speakText('This is how agent replies will sound.', {
+                      voice: speech.voice || undefined,
+                      rate: speech.rate,
+                    }).catch((err) => setError(`Failed to speak: ${err}`))
+                  }
+                  className="btn btn-sm btn-outline gap-2"
+                >
+                  <Volume2 className="w-4 h-4" />
+                  Test Voice
+                </button>
+              </div>
+            </div>
+          </div>
+        </section>
+
+        {/* Default Agent Section (desktop only) */}
+        {appSettings && (
+          <section className="mb-8">
+            <div className="flex items-center gap-2 mb-4">
+              <Bot className="w-5 h-5 text-primary" />
+              <h2 className="text-lg font-semibold">Default Agent</h2>
+            </div>
+
+            <div className="card bg-base-200 shadow-sm">
+              <div className="card-body">
+                <p className="text-sm text-base-content/70 mb-4">
+                  New chat tabs use the project's own agent. When that agent isn't installed, they start with this one.
+                </p>
+
+                <DefaultAgentSelect
+                  value={appSettings.default_agent}
+                  onChange={(agent) => updateAppSettings({ default_agent: agent })}
+                  emptyLabel="Any installed agent"
+                />
+              </div>
+            </div>
+          </section>
+        )}
+
+        {/* Watch Another Machine Section (desktop only) */}
+        {databaseMode && (
+          <section className="mb-8">
+            <div className="flex items-center gap-2 mb-4">
+              <Eye className="w-5 h-5 text-primary" />
+              <h2 className="text-lg font-semibold">Watch Another Machine</h2>
+            </div>
+
+            <div className="card bg-base-200 shadow-sm">
+              <div className="card-body">
+                <p className="text-sm text-base-content/70 mb-4">
+                  Open a copy of another machine's <code>ateliercode.db</code>, synced here with a tool like Syncthing
+                  or Dropbox, to follow its sessions. It's opened read-only and never written to, and AtelierCode
+                  restarts to switch databases.
+                </p>
+
+                {databaseMode.read_only ? (
+                  <div className="flex flex-wrap items-center gap-3">
+                    <span className="text-sm font-mono break-all">{databaseMode.path}</span>
+                    <button onClick={handleStopWatching} className="btn btn-sm btn-outline">
+                      Back to This Machine
+                    </button>
+                  </div>
+                ) : (
+                  <button onClick={handleWatchMachine} className="btn btn-sm btn-outline gap-2 w-fit">
+                    <Eye className="w-4 h-4" />
+                    Open Synced Database...
+                  </button>
+                )}
+              </div>
+            </div>
+          </section>
+        )}
+
+        {/* Background Section (desktop only) */}
+        {appSettings && (
+          <section className="mb-8">
+            <div className="flex items-center gap-2 mb-4">
+              <Monitor className="w-5 h-5 text-primary" />
+              <h2 className="text-lg font-semibold">Background</h2>
+            </div>
+
+            <div className="card bg-base-200 shadow-sm">
+              <div className="card-body">
+                <p className="text-sm text-base-content/70 mb-4">
+                  Keep agents and file watchers running after the window is closed. Reopen AtelierCode or
+                  quit it from the tray icon.
+                </p>
+
+                <label className="label cursor-pointer justify-start gap-3">
+                  <input
+                    type="checkbox"
+                    checked={appSettings.background_mode}
+                    onChange={(e) => updateAppSettings({ background_mode: e.target.checked })}
+                    className="checkbox checkbox-primary checkbox-sm"
+                  />
+                  <span className="label-text">Keep running in the tray when the window is closed</span>
+                </label>
+              </div>
+            </div>
+          </section>
+        )}
+
+        {/* Remote Access Section (desktop only) */}
+        {appSettings && (
+          <section className="mb-8">
+            <div className="flex items-center gap-2 mb-4">
+              <Smartphone className="w-5 h-5 text-primary" />
+              <h2 className="text-lg font-semibold">Remote Access</h2>
+            </div>
+
+            <div className="card bg-base-200 shadow-sm">
+              <div className="card-body">
+                <p className="text-sm text-base-content/70 mb-4">
+                  A read-only HTTP API for checking projects, sessions and activity from another device, with live
+                  events at <code>/api/events</code>. Every request needs the access token.
+                </p>
+
+                <label className="label cursor-pointer justify-start gap-3">
+                  <input
+                    type="checkbox"
+                    checked={appSettings.api_server.enabled}
+                    onChange={(e) => updateApiServer({ enabled: e.target.checked })}
+                    className="checkbox checkbox-primary checkbox-sm"
+                  />
+                  <span className="label-text">Enable the local API</span>
+                </label>
+
+                <label className="label cursor-pointer justify-start gap-3">
+                  <input
+                    type="checkbox"
+                    checked={appSettings.api_server.lan_access}
+                    onChange={(e) => updateApiServer({ lan_access: e.target.checked })}
+                    className="checkbox checkbox-primary checkbox-sm"
+                  />
+                  <span className="label-text">Allow other devices on my network</span>
+                </label>
+
+                <div className="form-control max-w-xs">
+                  <label className="label">
+                    <span className="label-text">Port</span>
+                  </label>
+                  <input
+                    type="number"
+                    min={1024}
+                    max={65535}
+                    defaultValue={appSettings.api_server.port}
+                    onBlur={(e) => {
+                      const port = Number(e.target.value);
+                      if (port >= 1024 && port <= 65535 && port !== appSettings.api_server.port) {
+                        updateApiServer({ port });
+                      }
+                    }}
+                    className="input input-bordered w-full"
+                  />
+                </div>
+
+                {appSettings.api_server.token && (
+                  <div className="form-control mt-2">
+                    <label className="label">
+                      <span className="label-text">Access token</span>
+                    </label>
+                    <div className="flex items-center gap-2">
+                      <code className="bg-base-300 rounded px-2 py-1 text-sm break-all">{appSettings.api_server.token}</code>
+                      <button onClick={handleRegenerateApiToken} className="btn btn-sm btn-ghost gap-1" title="Regenerate token">
+                        <RefreshCw className="w-4 h-4" />
+                        Regenerate
+                      </button>
+                    </div>
+                  </div>
+                )}
+
+                {apiStatus?.running && apiStatus.url && (
+                  <p className="text-sm text-success flex items-center gap-1 mt-4">
+                    <Check className="w-4 h-4" />
+                    Listening at <code>{apiStatus.url}</code>
+                  </p>
+                )}
+                {apiStatus?.error && (
+                  <p className="text-sm text-error flex items-center gap-1 mt-4">
+                    <AlertCircle className="w-4 h-4" />
+                    {apiStatus.error}
+                  </p>
+                )}
+              </div>
+            </div>
+          </section>
+        )}
+
+        {/* CI Section (desktop only) */}
+        {appSettings && (
+          <section className="mb-8">
+            <div className="flex items-center gap-2 mb-4">
+              <GitBranch className="w-5 h-5 text-primary" />
+              <h2 className="text-lg font-semibold">CI Status</h2>
+            </div>
+
+            <div className="card bg-base-200 shadow-sm">
+              <div className="card-body">
+                <p className="text-sm text-base-content/70 mb-4">
+                  Tokens for reading workflow runs of your projects' remotes. Leave empty to use GITHUB_TOKEN or
+                  GITLAB_TOKEN from the environment; public GitHub repositories don't need one.
+                </p>
+
+                {(['github_token', 'gitlab_token'] as const).map((key) => (
+                  <div key={key} className="form-control max-w-md">
+                    <label className="label">
+                      <span className="label-text">{key === 'github_token' ? 'GitHub token' : 'GitLab token'}</span>
+                    </label>
+                    <input
+                      type="password"
+                      defaultValue={appSettings.ci[key]}
+                      onBlur={(e) => {
+                        if (e.target.value !== appSettings.ci[key]) {
+                          updateAppSettings({ ci: { ...appSettings.ci, [key]: e.target.value } });
+                        }
+                      }}
+                      className="input input-bordered w-full"
+                    />
+                  </div>
+                ))}
+              </div>
+            </div>
+          </section>
+        )}
+
+        {/* Updates Section (desktop only) */}
+        {appSettings && (
+          <section className="mb-8">
+            <div className="flex items-center gap-2 mb-4">
+              <Download className="w-5 h-5 text-primary" />
+              <h2 className="text-lg font-semibold">Updates</h2>
+            </div>
+
+            <div className="card bg-base-200 shadow-sm">
+              <div className="card-body">
+                <div className="form-control max-w-xs">
+                  <label className="label">
+                    <span className="label-text">Release channel</span>
+                  </label>
+                  <select
+                    value={appSettings.update_channel}
+                    onChange={(e) => {
+                      setAvailableUpdate(null);
+                      setUpdateStatus('idle');
+                      updateAppSettings({ update_channel: e.target.value as UpdateChannel });
+                    }}
+                    className="select select-bordered w-full"
+                  >
+                    <option value="stable">Stable</option>
+                    <option value="beta">Beta</option>
+                  </select>
+                </div>
+
+                <div className="flex items-center gap-3 mt-4">
+                  {availableUpdate ? (
+                    <button
+                      onClick={handleInstallUpdate}
+                      disabled={updateStatus === 'installing'}
+                      className="btn btn-sm btn-primary gap-2"
+                    >
+                      {updateStatus === 'installing' && <Loader2 className="w-4 h-4 animate-spin" />}
+                      Install {availableUpdate.version} and Restart
+                    </button>
+                  ) : (
+                    <button
+                      onClick={handleCheckForUpdates}
+                      disabled={updateStatus === 'checking'}
+                      className="btn btn-sm btn-outline gap-2"
+                    >
+                      {updateStatus === 'checking' && <Loader2 className="w-4 h-4 animate-spin" />}
+                      Check for Updates
+                    </button>
+                  )}
+                  {updateStatus === 'up-to-date' && (
+                    <span className="text-sm text-success flex items-center gap-1">
+                      <Check className="w-4 h-4" />
+                      You're on the latest version
+                    </span>
+                  )}
+                </div>
+              </div>
+            </div>
+          </section>
+        )}
+
+        {/* Diagnostics Section (desktop only) */}
+        {isTauri() && (
+          <section className="mb-8">
+            <div className="flex items-center gap-2 mb-4">
+              <AlertCircle className="w-5 h-5 text-primary" />
+              <h2 className="text-lg font-semibold">Diagnostics</h2>
+            </div>
+
+            <div className="card bg-base-200 shadow-sm">
+              <div className="card-body">
+                <p className="text-sm text-base-content/70 mb-4">
+                  AtelierCode keeps a week of logs. Attach the latest file when reporting a bug.
+                </p>
+                <div>
+                  <button
+                    onClick={() => openLogFolder().catch((err) => setError(`Failed to open log folder: ${err}`))}
+                    className="btn btn-sm btn-outline"
+                  >
+                    Open Log Folder
+                  </button>
+                </div>
+              </div>
+            </div>
+          </section>
+        )}
       </div>
     </div>
   );
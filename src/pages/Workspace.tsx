@@ -1,23 +1,50 @@
-import { useParams, useNavigate } from 'react-router-dom';
+import { useParams, useNavigate, useLocation } from 'react-router-dom';
 import { useEffect, useState } from 'react';
 import { useProjectStore } from '../stores/projectStore';
-import { Folder, Bot, LayoutDashboard, MessageSquare, FileCode, ListTodo, Settings, Sparkles, Save, GitCompare } from 'lucide-react';
+import { Folder, Bot, LayoutDashboard, MessageSquare, FileCode, ListTodo, Settings, Sparkles, Save, GitCompare, ExternalLink, Package, Terminal, History, StickyNote } from 'lucide-react';
 import type { Project } from '../services/backend/types';
+import type { ProjectSettings } from '../types/tauri';
 import OverviewTab from '../components/workspace/OverviewTab';
 import TasksTab from '../components/workspace/TasksTab';
 import FilesTab from '../components/workspace/FilesTab';
 import ChatTab from '../components/workspace/ChatTab';
 import ChangesTab from '../components/workspace/ChangesTab';
+import TerminalTab from '../components/workspace/TerminalTab';
+import NotesTab from '../components/workspace/NotesTab';
+import EnvFilesPanel from '../components/workspace/EnvFilesPanel';
+import ClaudeConfigPanel from '../components/workspace/ClaudeConfigPanel';
+import AgentInstructionsPanel from '../components/workspace/AgentInstructionsPanel';
+import ProjectRootsPanel from '../components/workspace/ProjectRootsPanel';
+import DefaultAgentSelect from '../components/DefaultAgentSelect';
 import AIProjectDetailsModal, { type AIProjectDetails } from '../components/modals/AIProjectDetailsModal';
-import { invoke } from '@tauri-apps/api/core';
-
-type TabType = 'overview' | 'chat' | 'files' | 'tasks' | 'changes' | 'settings';
+import PrdHistoryModal from '../components/modals/PrdHistoryModal';
+import { invoke } from '../lib/invoke';
+import { toAppError, type AppError } from '../lib/errors';
+import type { DeepLink } from '../api/deepLinks';
+import { recordProjectOpened, recordProjectClosed } from '../api/recentProjects';
+import { isMainWindow, openProjectWindow } from '../api/windows';
+import { exportProjectBundleWithDialog } from '../api/projectBundles';
+import { isTauri } from '../lib/platform';
+
+type TabType = 'overview' | 'chat' | 'files' | 'tasks' | 'changes' | 'notes' | 'terminal' | 'settings';
+
+function parseSettings(json?: string | null): ProjectSettings {
+  try {
+    return json ? (JSON.parse(json) as ProjectSettings) : {};
+  } catch {
+    return {};
+  }
+}
 
 export default function Workspace() {
   const { id } = useParams<{ id: string }>();
   const navigate = useNavigate();
+  const location = useLocation();
+  const deepLink = (location.state as { deepLink?: DeepLink } | null)?.deepLink;
   const [project, setProject] = useState<Project | null>(null);
   const [isLoading, setIsLoading] = useState(true);
+  const [loadError, setLoadError] = useState<AppError | null>(null);
+  const [loadAttempt, setLoadAttempt] = useState(0);
   const [activeTab, setActiveTab] = useState<TabType>('overview');
   const getProject = useProjectStore((state) => state.getProject);
   const setCurrentProject = useProjectStore((state) => state.setCurrentProject);
@@ -36,6 +63,9 @@ export default function Workspace() {
   // AI modal state
   const [showAIModal, setShowAIModal] = useState(false);
   const [aiGeneratedDetails, setAiGeneratedDetails] = useState<AIProjectDetails>({ name: '', description: '' });
+  // Whether the edited description is as the AI wrote it, for the PRD history
+  const [descriptionFromAI, setDescriptionFromAI] = useState(false);
+  const [showPrdHistory, setShowPrdHistory] = useState(false);
 
   useEffect(() => {
     const loadProject = async () => {
@@ -46,6 +76,7 @@ export default function Workspace() {
 
       try {
         setIsLoading(true);
+        setLoadError(null);
         // Set as current project (updates last_activity in backend)
         await setCurrentProject(id);
         // Get the full project data
@@ -63,13 +94,33 @@ export default function Workspace() {
       } catch (error) {
         console.error('Failed to load project:', error);
         setProject(null);
+        setLoadError(toAppError(error));
       } finally {
         setIsLoading(false);
       }
     };
 
     loadProject();
-  }, [id, getProject, setCurrentProject]);
+  }, [id, getProject, setCurrentProject, loadAttempt]);
+
+  // Record the visit for the home screen's recent projects
+  useEffect(() => {
+    if (!id) return;
+    const visit = recordProjectOpened(id).catch((err) => {
+      console.debug('Failed to record project visit:', err);
+      return null;
+    });
+    return () => {
+      visit.then((visitId) => visitId && recordProjectClosed(visitId)).catch(() => {});
+    };
+  }, [id]);
+
+  // Session and new-agent links open the chat
+  useEffect(() => {
+    if (deepLink && deepLink.action !== 'open_project') {
+      setActiveTab('chat');
+    }
+  }, [deepLink]);
 
   // Track changes in settings
   useEffect(() => {
@@ -108,6 +159,7 @@ export default function Workspace() {
   const handleApplyAIDetails = (details: AIProjectDetails) => {
     setEditedName(details.name);
     setEditedDescription(details.description);
+    setDescriptionFromAI(true);
     setHasChanges(true);
   };
 
@@ -122,6 +174,7 @@ export default function Workspace() {
         updates: {
           name: editedName,
           prd_content: editedDescription,
+          prd_author: descriptionFromAI ? 'ai' : 'user',
           status: editedStatus,
           icon: editedIcon || undefined,
           color: editedColor || undefined,
@@ -140,6 +193,7 @@ export default function Workspace() {
         color: editedColor || undefined,
       });
 
+      setDescriptionFromAI(false);
       setHasChanges(false);
     } catch (error) {
       console.error('Failed to save changes:', error);
@@ -149,6 +203,16 @@ export default function Workspace() {
     }
   };
 
+  const handleDefaultAgentChange = async (agent: string | null) => {
+    if (!id || !project) return;
+    try {
+      const settings = JSON.stringify({ ...parseSettings(project.settings), default_agent: agent });
+      setProject(await invoke<Project>('update_project', { id, updates: { settings } }));
+    } catch (error) {
+      console.error('Failed to save default agent:', error);
+    }
+  };
+
   if (isLoading) {
     return (
       <div className="min-h-screen bg-base-100 flex items-center justify-center">
@@ -157,6 +221,29 @@ export default function Workspace() {
     );
   }
 
+  if (!project && loadError) {
+    return (
+      <div className="min-h-screen bg-base-100 flex items-center justify-center">
+        <div className="text-center max-w-md">
+          <h2 className="text-2xl font-bold mb-4">Couldn't Load Project</h2>
+          <p className="text-base-content/70 mb-6">
+            {loadError.kind === 'database_locked'
+              ? 'The database is busy, probably with another AtelierCode window. Try again in a moment.'
+              : loadError.message}
+          </p>
+          <div className="flex gap-2 justify-center">
+            <button onClick={() => setLoadAttempt((n) => n + 1)} className="btn btn-primary">
+              Retry
+            </button>
+            <button onClick={() => navigate('/')} className="btn btn-ghost">
+              Go Home
+            </button>
+          </div>
+        </div>
+      </div>
+    );
+  }
+
   if (!project) {
     return (
       <div className="min-h-screen bg-base-100 flex items-center justify-center">
@@ -176,6 +263,8 @@ export default function Workspace() {
     );
   }
 
+  const projectSettings = parseSettings(project.settings);
+
   return (
     <div className="min-h-screen bg-base-100">
       {/* Header */}
@@ -188,6 +277,26 @@ export default function Workspace() {
           </div>
         </div>
         <div className="flex-none gap-2">
+          {isTauri() && (
+            <button
+              className="btn btn-ghost btn-sm gap-2"
+              onClick={() => exportProjectBundleWithDialog(project.id, project.name).catch((error) => alert(`Failed to export project: ${error}`))}
+              title="Export tasks, chats, reviews and settings to a bundle file"
+            >
+              <Package className="w-4 h-4" />
+              Export
+            </button>
+          )}
+          {isTauri() && isMainWindow() && (
+            <button
+              className="btn btn-ghost btn-sm gap-2"
+              onClick={() => openProjectWindow(project.id).catch((error) => console.error('Failed to open project window:', error))}
+              title="Open this project in its own window"
+            >
+              <ExternalLink className="w-4 h-4" />
+              New Window
+            </button>
+          )}
           {project.agent_type && (
             <div className="badge badge-primary gap-2">
               <Bot className="w-3 h-3" />
@@ -237,6 +346,22 @@ export default function Workspace() {
               <GitCompare className="w-4 h-4" />
               Changes
             </button>
+            <button
+              className={`tab gap-2 ${activeTab === 'notes' ? 'tab-active' : ''}`}
+              onClick={() => setActiveTab('notes')}
+            >
+              <StickyNote className="w-4 h-4" />
+              Notes
+            </button>
+            {isTauri() && (
+              <button
+                className={`tab gap-2 ${activeTab === 'terminal' ? 'tab-active' : ''}`}
+                onClick={() => setActiveTab('terminal')}
+              >
+                <Terminal className="w-4 h-4" />
+                Terminal
+              </button>
+            )}
             <button
               className={`tab gap-2 ${activeTab === 'settings' ? 'tab-active' : ''}`}
               onClick={() => setActiveTab('settings')}
@@ -255,11 +380,12 @@ export default function Workspace() {
             projectId={id}
             agentType={project.agent_type}
             projectPath={project.root_path}
+            projectSettings={project.settings}
           />
         )}
 
         {activeTab === 'chat' && (
-          <ChatTab projectId={id} />
+          <ChatTab projectId={id} deepLink={deepLink} />
         )}
 
         {activeTab === 'files' && (
@@ -274,6 +400,20 @@ export default function Workspace() {
           <ChangesTab projectId={id} />
         )}
 
+        {activeTab === 'notes' && id && (
+          <NotesTab
+            projectId={id}
+            projectSettings={project.settings}
+            onSettingsChange={async (settings) => {
+              setProject(await invoke<Project>('update_project', { id, updates: { settings } }));
+            }}
+          />
+        )}
+
+        {activeTab === 'terminal' && id && (
+          <TerminalTab projectId={id} />
+        )}
+
         {activeTab === 'settings' && (
           <div className="card bg-base-200">
             <div className="card-body">
@@ -315,11 +455,18 @@ export default function Workspace() {
                 <div>
                   <label className="label">
                     <span className="label-text">Description</span>
+                    <button className="btn btn-ghost btn-xs gap-1" onClick={() => setShowPrdHistory(true)}>
+                      <History className="w-3 h-3" />
+                      History
+                    </button>
                   </label>
                   <textarea
                     className="textarea textarea-bordered w-full"
                     value={editedDescription}
-                    onChange={(e) => setEditedDescription(e.target.value)}
+                    onChange={(e) => {
+                      setEditedDescription(e.target.value);
+                      setDescriptionFromAI(false);
+                    }}
                     placeholder="Enter project description"
                     rows={3}
                   />
@@ -463,6 +610,17 @@ export default function Workspace() {
                   </label>
                 </div>
 
+                <div>
+                  <label className="label">
+                    <span className="label-text">Agent for New Tabs</span>
+                  </label>
+                  <DefaultAgentSelect
+                    value={projectSettings.default_agent}
+                    onChange={handleDefaultAgentChange}
+                    emptyLabel={`Project agent (${project.agent_type || 'Unknown'})`}
+                  />
+                </div>
+
                 <div className="divider"></div>
 
                 <div className="flex justify-end gap-2">
@@ -470,6 +628,7 @@ export default function Workspace() {
                     onClick={() => {
                       setEditedName(project.name);
                       setEditedDescription(project.prd_content || '');
+                      setDescriptionFromAI(false);
                       setEditedStatus(project.status);
                       setEditedIcon(project.icon || null);
                       setEditedColor(project.color || null);
@@ -502,6 +661,15 @@ export default function Workspace() {
             </div>
           </div>
         )}
+
+        {activeTab === 'settings' && id && (
+          <div className="mt-6 space-y-6">
+            <ProjectRootsPanel projectId={id} />
+            <EnvFilesPanel projectId={id} />
+            <AgentInstructionsPanel projectId={id} />
+            <ClaudeConfigPanel projectId={id} />
+          </div>
+        )}
       </div>
 
       {/* AI Project Details Modal */}
@@ -512,6 +680,20 @@ export default function Workspace() {
         initialDetails={aiGeneratedDetails}
         isLoading={isRegenerating}
       />
+
+      {id && (
+        <PrdHistoryModal
+          isOpen={showPrdHistory}
+          projectId={id}
+          onClose={() => setShowPrdHistory(false)}
+          onRestored={(restored) => {
+            setProject(restored);
+            setEditedDescription(restored.prd_content || '');
+            setDescriptionFromAI(false);
+            updateProjectInStore(restored.id, { prd_content: restored.prd_content || '' });
+          }}
+        />
+      )}
     </div>
   );
 }
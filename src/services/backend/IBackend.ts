@@ -22,6 +22,7 @@ import type {
   ProjectAnalysisResult,
   FileNode,
   TranscriptionResult,
+  TranscriptionOptions,
   SessionUpdateCallback,
   UnsubscribeFn,
 } from './types';
@@ -143,6 +144,7 @@ export interface IChatBackend {
     pluginName: string,
     cliSessionId: string,
     projectPath: string,
+    tabId: string,
     callback: SessionUpdateCallback
   ): Promise<UnsubscribeFn>;
 }
@@ -206,12 +208,12 @@ export interface ITranscriptionBackend {
   /**
    * Transcribe audio using local Whisper
    */
-  transcribeLocal(audioData: number[], model: string): Promise<TranscriptionResult>;
+  transcribeLocal(audioData: number[], model: string, options?: TranscriptionOptions): Promise<TranscriptionResult>;
 
   /**
    * Transcribe audio using OpenAI Whisper API
    */
-  transcribeOpenAI(audioData: number[], apiKey: string): Promise<TranscriptionResult>;
+  transcribeOpenAI(audioData: number[], apiKey: string, options?: TranscriptionOptions): Promise<TranscriptionResult>;
 }
 
 /**
@@ -5,8 +5,7 @@
  * Wraps all @tauri-apps/api invoke() calls.
  */
 
-import { invoke } from '@tauri-apps/api/core';
-import { listen, UnlistenFn } from '@tauri-apps/api/event';
+import { invoke } from '../../lib/invoke';
 import type {
   IBackend,
   IProjectBackend,
@@ -36,12 +35,12 @@ import type {
   ProjectAnalysisResult,
   FileNode,
   TranscriptionResult,
+  TranscriptionOptions,
   SessionUpdateCallback,
   UnsubscribeFn,
 } from './types';
 
-// Import session update types from sessionWatcher
-import type { SessionUpdate as WatcherSessionUpdate } from '../../api/sessionWatcher';
+import { startWatchingSession } from '../../api/sessionWatcher';
 
 /**
  * Project backend implementation for Tauri
@@ -179,38 +178,19 @@ class TauriChatBackend implements IChatBackend {
     pluginName: string,
     cliSessionId: string,
     projectPath: string,
+    tabId: string,
     callback: SessionUpdateCallback
   ): Promise<UnsubscribeFn> {
-    // Set up event listener first
-    const unlisten: UnlistenFn = await listen<{ cli_session_id: string; update: WatcherSessionUpdate }>(
-      'session-update',
-      (event) => {
-        if (event.payload.cli_session_id === cliSessionId) {
-          const update = event.payload.update;
-          // Transform to our SessionUpdate format
-          if (update.type === 'NewMessage' && update.message && typeof update.message !== 'string') {
-            callback({
-              sessionId: cliSessionId,
-              messages: [update.message as ChatMessage],
-              hasMoreMessages: false,
-            });
-          }
-        }
+    return await startWatchingSession(pluginName, projectPath, cliSessionId, tabId, (update) => {
+      // Transform to our SessionUpdate format
+      if (update.type === 'NewMessage' && update.message && typeof update.message !== 'string') {
+        callback({
+          sessionId: cliSessionId,
+          messages: [update.message as ChatMessage],
+          hasMoreMessages: false,
+        });
       }
-    );
-
-    // Start watching
-    const watchId = await invoke<string>('start_watching_session', {
-      pluginName,
-      projectPath,
-      cliSessionId,
     });
-
-    // Return unsubscribe function
-    return async () => {
-      await invoke('stop_watching_session', { pluginName, watchId, cliSessionId });
-      unlisten();
-    };
   }
 }
 
@@ -276,14 +256,22 @@ class TauriTranscriptionBackend implements ITranscriptionBackend {
     return await invoke('install_whisper', { model });
   }
 
-  async transcribeLocal(audioData: number[], model: string): Promise<TranscriptionResult> {
-    const result = await invoke<{ text: string }>('transcribe_local', { audioData, model });
-    return { text: result.text };
+  async transcribeLocal(audioData: number[], model: string, options?: TranscriptionOptions): Promise<TranscriptionResult> {
+    return await invoke<TranscriptionResult>('transcribe_local', {
+      audioData,
+      model,
+      language: options?.language,
+      translate: options?.translate,
+    });
   }
 
-  async transcribeOpenAI(audioData: number[], apiKey: string): Promise<TranscriptionResult> {
-    const result = await invoke<{ text: string }>('transcribe_openai', { audioData, apiKey });
-    return { text: result.text };
+  async transcribeOpenAI(audioData: number[], apiKey: string, options?: TranscriptionOptions): Promise<TranscriptionResult> {
+    return await invoke<TranscriptionResult>('transcribe_openai', {
+      audioData,
+      apiKey,
+      language: options?.language,
+      translate: options?.translate,
+    });
   }
 }
 
@@ -35,6 +35,7 @@ import type {
   ProjectAnalysisResult,
   FileNode,
   TranscriptionResult,
+  TranscriptionOptions,
   SessionUpdateCallback,
   UnsubscribeFn,
 } from './types';
@@ -175,6 +176,7 @@ class WebRTCChatBackend implements IChatBackend {
     pluginName: string,
     cliSessionId: string,
     projectPath: string,
+    tabId: string,
     callback: SessionUpdateCallback
   ): Promise<UnsubscribeFn> {
     // Start watching via command
@@ -182,13 +184,17 @@ class WebRTCChatBackend implements IChatBackend {
       pluginName,
       projectPath,
       cliSessionId,
+      tabId,
     });
 
-    // Subscribe to events from the peer connection
+    // Subscribe to events from the peer connection; the host may replay
+    // updates the tab already has, so skip seqs already seen
+    let lastSeq = 0;
     const unsubscribeEvent = peerConnection.onEvent((event, payload) => {
       if (event === 'session-update') {
-        const eventPayload = payload as { cli_session_id: string; update: { type: string; message?: ChatMessage } };
-        if (eventPayload.cli_session_id === cliSessionId) {
+        const eventPayload = payload as { tab_id: string; seq: number; update: { type: string; message?: ChatMessage } };
+        if (eventPayload.tab_id === tabId && eventPayload.seq > lastSeq) {
+          lastSeq = eventPayload.seq;
           const update = eventPayload.update;
           if (update.type === 'NewMessage' && update.message) {
             callback({
@@ -274,16 +280,24 @@ class WebRTCTranscriptionBackend implements ITranscriptionBackend {
     await peerConnection.sendCommand('install_whisper', { model });
   }
 
-  async transcribeLocal(audioData: number[], model: string): Promise<TranscriptionResult> {
+  async transcribeLocal(audioData: number[], model: string, options?: TranscriptionOptions): Promise<TranscriptionResult> {
     // Note: This sends audio data over WebRTC to be transcribed on the remote machine
-    const result = await peerConnection.sendCommand<{ text: string }>('transcribe_local', { audioData, model });
-    return { text: result.text };
+    return await peerConnection.sendCommand<TranscriptionResult>('transcribe_local', {
+      audioData,
+      model,
+      language: options?.language,
+      translate: options?.translate,
+    });
   }
 
-  async transcribeOpenAI(audioData: number[], apiKey: string): Promise<TranscriptionResult> {
+  async transcribeOpenAI(audioData: number[], apiKey: string, options?: TranscriptionOptions): Promise<TranscriptionResult> {
     // Note: This sends audio data over WebRTC, the remote machine calls OpenAI
-    const result = await peerConnection.sendCommand<{ text: string }>('transcribe_openai', { audioData, apiKey });
-    return { text: result.text };
+    return await peerConnection.sendCommand<TranscriptionResult>('transcribe_openai', {
+      audioData,
+      apiKey,
+      language: options?.language,
+      translate: options?.translate,
+    });
   }
 }
 
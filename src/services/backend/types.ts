@@ -61,9 +61,21 @@ export interface FileNode {
 export interface TranscriptionResult {
   text: string;
   language?: string;
+  /** Probability of the detected language, when it was auto-detected */
+  language_confidence?: number;
   duration?: number;
 }
 
+/**
+ * Transcription language options
+ */
+export interface TranscriptionOptions {
+  /** ISO 639-1 code, or 'auto' to detect the spoken language */
+  language?: string;
+  /** Output English regardless of the spoken language */
+  translate?: boolean;
+}
+
 /**
  * Unsubscribe function for event listeners
  */
@@ -7,6 +7,7 @@
 import { serverConnection } from './serverConnection';
 import { useSettingsStore } from '../stores/settingsStore';
 import { handleWebRTCCommand, isWebRTCHostAvailable } from './webrtcHost';
+import { AppError, type AppErrorKind } from '../lib/errors';
 
 interface ICEServer {
   urls: string;
@@ -22,6 +23,7 @@ interface PeerMessage {
   success?: boolean;
   data?: unknown;
   error?: string;
+  errorKind?: AppErrorKind;
   event?: string;
   payload?: unknown;
   // Chunking fields
@@ -442,7 +444,7 @@ class PeerConnection {
           if (message.success) {
             resolve(message.data);
           } else {
-            reject(new Error(message.error || 'Unknown error'));
+            reject(new AppError({ kind: message.errorKind ?? 'internal', message: message.error || 'Unknown error' }));
           }
         }
         break;
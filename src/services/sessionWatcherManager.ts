@@ -14,13 +14,42 @@
  */
 
 import * as sessionWatcher from '../api/sessionWatcher';
-import type { SessionUpdate, HistoryMessage } from '../api/sessionWatcher';
+import type { SessionUpdate, SessionUpdateEvent, HistoryMessage } from '../api/sessionWatcher';
 import { useChatStore } from '../stores/chatStore';
 import { useChatTabStore } from '../stores/chatTabStore';
+import { useSessionStore } from '../stores/sessionStore';
 import { useProjectActivityStore } from '../stores/projectActivityStore';
 import { parseAskUserQuestion } from '../lib/parseAskUserQuestion';
 import type { ChatMessage, MessageMetadata } from '../components/workspace/ChatTab';
 import { peerConnection } from './peerConnection';
+import { useSettingsStore } from '../stores/settingsStore';
+import { speakText } from '../api/speech';
+
+// When each tab's current agent turn started (for reading long turns aloud)
+const turnStartedAt = new Map<string, number>();
+
+/**
+ * Read the last assistant reply aloud if the turn that produced it ran long enough
+ */
+function readFinishedTurn(tabId: string): void {
+  const startedAt = turnStartedAt.get(tabId);
+  turnStartedAt.delete(tabId);
+
+  const { speech } = useSettingsStore.getState();
+  if (!speech.readRepliesAloud || startedAt === undefined || isWebMode()) return;
+  if (Date.now() - startedAt < speech.minTurnSeconds * 1000) return;
+
+  const reply = [...useChatStore.getState().getMessages(tabId)]
+    .reverse()
+    .find((m) => m.role === 'assistant');
+  if (!reply) return;
+
+  speakText(reply.content, {
+    voice: speech.voice || undefined,
+    rate: speech.rate,
+    summarize: true,
+  }).catch((err) => console.error('[SessionWatcherManager] Failed to read reply aloud:', err));
+}
 
 // Check if we're in web mode (connected via WebRTC as CLIENT, not local Tauri)
 // Only clients should use WebRTC for session watching - hosts use local Tauri API
@@ -31,6 +60,9 @@ function isWebMode(): boolean {
 // Track whether we've set up the WebRTC event listener
 let webRTCEventListenerSetup = false;
 
+// The last seq handled per tab for updates forwarded over WebRTC
+const remoteSeq = new Map<string, number>();
+
 /**
  * Set up listener for session events forwarded over WebRTC
  */
@@ -39,7 +71,10 @@ function setupWebRTCEventListener(): void {
 
   peerConnection.onEvent((event, payload) => {
     if (event === 'session-update') {
-      const data = payload as { cli_session_id: string; update: SessionUpdate };
+      const data = payload as SessionUpdateEvent;
+      // The host replays buffered updates when a watch starts; skip ones already handled
+      if (data.seq <= (remoteSeq.get(data.tab_id) ?? 0)) return;
+      remoteSeq.set(data.tab_id, data.seq);
       console.log('[SessionWatcherManager] Received WebRTC session update for:', data.cli_session_id);
       handleSessionUpdate(data.cli_session_id, data.update);
     }
@@ -133,6 +168,7 @@ export async function startWatching(
         pluginName,
         projectPath,
         cliSessionId,
+        tabId,
       });
 
       console.log('[SessionWatcherManager] WebRTC watcher started, watchId:', watchId);
@@ -144,6 +180,7 @@ export async function startWatching(
           watchId,
           cliSessionId,
         });
+        remoteSeq.delete(tabId);
       };
     } else {
       // In local Tauri mode, use direct API
@@ -151,6 +188,7 @@ export async function startWatching(
         pluginName,
         projectPath,
         cliSessionId,
+        tabId,
         (update) => handleSessionUpdate(cliSessionId, update)
       );
     }
@@ -313,8 +351,20 @@ function handleSessionUpdate(cliSessionId: string, update: SessionUpdate): void
     // Update typing indicator based on process status
     if (update.status === 'working') {
       useChatStore.getState().setTyping(tabId, true);
+      if (!turnStartedAt.has(tabId)) {
+        turnStartedAt.set(tabId, Date.now());
+      }
     } else if (update.status === 'idle') {
       useChatStore.getState().setTyping(tabId, false);
+      readFinishedTurn(tabId);
+    } else if (update.status === 'stopped' || update.status === 'error') {
+      // The health watchdog found the session's process gone
+      useChatStore.getState().setTyping(tabId, false);
+      turnStartedAt.delete(tabId);
+      const session = useSessionStore.getState().getActiveSession(tabId);
+      if (session && session.status !== update.status) {
+        useSessionStore.getState().setActiveSession(tabId, { ...session, status: update.status });
+      }
     }
   } else if (update.type === 'Error') {
     console.error('[SessionWatcherManager] Session error:', update.message);
@@ -4,10 +4,12 @@
  * Routes commands to Tauri and sends responses back to the web client.
  */
 
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from '../lib/invoke';
+import { toAppError, type AppErrorKind } from '../lib/errors';
 import { listen, UnlistenFn } from '@tauri-apps/api/event';
 import type { Project, CreateProjectInput, UpdateProjectInput, ProjectAnalysisResult, AgentInfo, Task, CreateTaskInput, UpdateTaskInput } from '../types/tauri';
 import { peerConnection } from './peerConnection';
+import { resumeSessionUpdates, sessionUpdateEvent, type SessionUpdateEvent } from '../api/sessionWatcher';
 
 interface PeerMessage {
   type: 'request' | 'response' | 'event';
@@ -17,6 +19,7 @@ interface PeerMessage {
   success?: boolean;
   data?: unknown;
   error?: string;
+  errorKind?: AppErrorKind;
 }
 
 type CommandHandler = (params: Record<string, unknown>) => Promise<unknown>;
@@ -102,8 +105,10 @@ const commandHandlers: Record<string, CommandHandler> = {
   start_agent_session: async (params) => {
     return await invoke<unknown>('start_agent_session', {
       projectId: params.projectId as string,
-      agentType: params.agentType as string,
+      agentType: (params.agentType as string | null) ?? null,
       resumeSessionId: params.resumeSessionId as string | null,
+      executionProfile: (params.executionProfile as string | null) ?? null,
+      tabId: (params.tabId as string | null) ?? null,
     });
   },
 
@@ -138,6 +143,10 @@ const commandHandlers: Record<string, CommandHandler> = {
     return await invoke<boolean>('check_agent_health', { sessionId: params.sessionId as string });
   },
 
+  reconcile_sessions: async () => {
+    return await invoke<unknown>('reconcile_sessions');
+  },
+
   sync_claude_session_id: async (params) => {
     return await invoke<string | null>('sync_claude_session_id', { sessionId: params.sessionId as string });
   },
@@ -191,26 +200,34 @@ const commandHandlers: Record<string, CommandHandler> = {
     const pluginName = params.pluginName as string;
     const projectPath = params.projectPath as string;
     const cliSessionId = params.cliSessionId as string;
-
-    // Set up event listener FIRST to forward session events to the web client
-    const unlisten = await listen<{ cli_session_id: string; update: unknown }>(
-      'session-update',
-      (event) => {
-        // Filter events by cli_session_id and forward to web client
-        if (event.payload.cli_session_id === cliSessionId) {
-          console.log('[WebRTCHost] Forwarding session update for:', cliSessionId);
-          peerConnection.sendEvent('session-update', event.payload);
-        }
+    const tabId = params.tabId as string;
+
+    // Set up event listener FIRST to forward session events to the web client,
+    // holding live events until the buffered ones have been sent
+    let held: SessionUpdateEvent[] | null = [];
+    const forward = (event: SessionUpdateEvent) => peerConnection.sendEvent('session-update', event);
+    const unlisten = await listen<SessionUpdateEvent>(sessionUpdateEvent(tabId), (event) => {
+      console.log('[WebRTCHost] Forwarding session update for:', cliSessionId);
+      if (held) {
+        held.push(event.payload);
+      } else {
+        forward(event.payload);
       }
-    );
+    });
 
     // Start the watcher on the backend
     const watchId = await invoke<string>('start_watching_session', {
       pluginName,
       projectPath,
       cliSessionId,
+      tabId,
     });
 
+    // The client skips seqs it has already seen
+    (await resumeSessionUpdates(tabId, 0)).forEach(forward);
+    held.forEach(forward);
+    held = null;
+
     // Store the unlisten function for cleanup
     activeSessionWatchers.set(watchId, { cliSessionId, unlisten });
     console.log('[WebRTCHost] Started session watcher:', watchId, 'for session:', cliSessionId);
@@ -271,6 +288,109 @@ const commandHandlers: Record<string, CommandHandler> = {
     return await invoke<unknown>('get_project_stats', { projectId: params.projectId as string });
   },
 
+  get_project_timeseries: async (params) => {
+    return await invoke<unknown[]>('get_project_timeseries', {
+      projectId: params.projectId as string,
+      metric: params.metric as string,
+      bucket: params.bucket as string,
+      since: params.since as number | undefined,
+      until: params.until as number | undefined,
+    });
+  },
+
+  get_session_change_stats: async (params) => {
+    return await invoke<unknown>('get_session_change_stats', { sessionId: params.sessionId as string });
+  },
+
+  get_tool_calls: async (params) => {
+    return await invoke<unknown>('get_tool_calls', {
+      projectId: params.projectId as string,
+      cliSessionId: params.cliSessionId as string | undefined,
+      toolName: params.toolName as string | undefined,
+      limit: params.limit as number | undefined,
+    });
+  },
+
+  get_tool_call_counts: async (params) => {
+    return await invoke<unknown>('get_tool_call_counts', {
+      projectId: params.projectId as string,
+      cliSessionId: params.cliSessionId as string | undefined,
+    });
+  },
+
+  get_agent_auth_status: async (params) => {
+    return await invoke<unknown>('get_agent_auth_status', { pluginName: params.pluginName as string });
+  },
+
+  get_ci_status: async (params) => {
+    return await invoke<unknown>('get_ci_status', {
+      projectId: params.projectId as string,
+      limit: params.limit as number | undefined,
+    });
+  },
+
+  run_doctor: async () => {
+    return await invoke<unknown>('run_doctor');
+  },
+
+  cleanup_orphaned_sessions: async () => {
+    return await invoke<unknown>('cleanup_orphaned_sessions');
+  },
+
+  preview_cleanup: async () => {
+    return await invoke<unknown>('preview_cleanup');
+  },
+
+  list_project_roots: async (params) => {
+    return await invoke<unknown>('list_project_roots', { projectId: params.projectId as string });
+  },
+
+  add_project_root: async (params) => {
+    return await invoke<unknown>('add_project_root', {
+      projectId: params.projectId as string,
+      path: params.path as string,
+      label: params.label as string | undefined,
+    });
+  },
+
+  remove_project_root: async (params) => {
+    return await invoke<unknown>('remove_project_root', { rootId: params.rootId as string });
+  },
+
+  record_project_opened: async (params) => {
+    return await invoke<unknown>('record_project_opened', { projectId: params.projectId as string });
+  },
+
+  record_project_closed: async (params) => {
+    return await invoke<unknown>('record_project_closed', { visitId: params.visitId as string });
+  },
+
+  get_recent_projects: async (params) => {
+    return await invoke<unknown>('get_recent_projects', { limit: params.limit as number | undefined });
+  },
+
+  get_resumable_sessions: async (params) => {
+    return await invoke<unknown>('get_resumable_sessions', {
+      projectId: params.projectId as string,
+      limit: params.limit as number | undefined,
+    });
+  },
+
+  get_onboarding_state: async () => {
+    return await invoke<unknown>('get_onboarding_state');
+  },
+
+  complete_onboarding_step: async (params) => {
+    return await invoke<unknown>('complete_onboarding_step', { step: params.step as string });
+  },
+
+  get_cost_summary: async (params) => {
+    return await invoke<unknown>('get_cost_summary', {
+      projectId: params.projectId as string | undefined,
+      period: params.period as string,
+    });
+  },
+
   // Chat tab commands
   get_chat_tabs: async (params) => {
     return await invoke<unknown[]>('get_chat_tabs', { projectId: params.projectId as string });
@@ -279,7 +399,7 @@ const commandHandlers: Record<string, CommandHandler> = {
   create_chat_tab: async (params) => {
     return await invoke<unknown>('create_chat_tab', {
       projectId: params.projectId as string,
-      agentType: params.agentType as string,
+      agentType: (params.agentType as string | null) ?? null,
       label: params.label as string | undefined,
     });
   },
@@ -293,6 +413,49 @@ const commandHandlers: Record<string, CommandHandler> = {
     });
   },
 
+  set_tab_flag_overrides: async (params) => {
+    return await invoke<unknown>('set_tab_flag_overrides', {
+      tabId: params.tabId as string,
+      overrides: params.overrides as Record<string, string>,
+    });
+  },
+
+  get_tab_effective_flags: async (params) => {
+    return await invoke<unknown>('get_tab_effective_flags', {
+      tabId: params.tabId as string,
+      pluginName: params.pluginName as string,
+    });
+  },
+
+  send_to_tab: async (params) => {
+    return await invoke<string>('send_to_tab', {
+      tabId: params.tabId as string,
+      message: params.message as string,
+    });
+  },
+
+  send_selection_to_agent: async (params) => {
+    return await invoke<string>('send_selection_to_agent', {
+      tabId: params.tabId as string,
+      filePath: params.filePath as string,
+      startLine: params.startLine as number,
+      endLine: params.endLine as number,
+      instruction: params.instruction as string,
+    });
+  },
+
+  duplicate_chat_tab: async (params) => {
+    return await invoke<unknown>('duplicate_chat_tab', {
+      tabId: params.tabId as string,
+    });
+  },
+
+  auto_label_tab: async (params) => {
+    return await invoke<unknown>('auto_label_tab', {
+      tabId: params.tabId as string,
+    });
+  },
+
   set_active_tab: async (params) => {
     return await invoke<void>('set_active_tab', {
       projectId: params.projectId as string,
@@ -311,6 +474,19 @@ const commandHandlers: Record<string, CommandHandler> = {
     });
   },
 
+  find_cli_sessions: async (params) => {
+    return await invoke<unknown[]>('find_cli_sessions', { projectPath: params.projectPath as string });
+  },
+
+  import_cli_sessions: async (params) => {
+    return await invoke<unknown[]>('import_cli_sessions', {
+      projectId: params.projectId as string,
+      pluginName: params.pluginName as string,
+      projectPath: params.projectPath as string,
+      cliSessionIds: params.cliSessionIds as string[],
+    });
+  },
+
   // System commands
   detect_agents: async () => {
     return await invoke<AgentInfo[]>('detect_agents');
@@ -356,6 +532,8 @@ const commandHandlers: Record<string, CommandHandler> = {
     return await invoke<{ text: string }>('transcribe_local', {
       audioData,
       model,
+      language: params.language as string | undefined,
+      translate: params.translate as boolean | undefined,
     });
   },
 
@@ -363,6 +541,8 @@ const commandHandlers: Record<string, CommandHandler> = {
     return await invoke<{ text: string }>('transcribe_openai', {
       audioData: params.audioData as number[],
       apiKey: params.apiKey as string,
+      language: params.language as string | undefined,
+      translate: params.translate as boolean | undefined,
     });
   },
 
@@ -417,11 +597,13 @@ export async function handleWebRTCCommand(message: PeerMessage): Promise<PeerMes
     };
   } catch (error) {
     console.error(`[WebRTCHost] Command ${message.command} failed:`, error);
+    const appError = toAppError(error);
     return {
       type: 'response',
       id: message.id,
       success: false,
-      error: error instanceof Error ? error.message : 'Command execution failed',
+      error: appError.message,
+      errorKind: appError.kind,
     };
   }
 }
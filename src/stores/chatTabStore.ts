@@ -17,7 +17,7 @@ interface ChatTabState {
 
   // Actions
   loadTabs: (projectId: string) => Promise<void>;
-  createTab: (projectId: string, agentType: string, label?: string) => Promise<ChatTab>;
+  createTab: (projectId: string, agentType?: string, label?: string) => Promise<ChatTab>;
   updateTab: (tabId: string, updates: { label?: string; sessionId?: string; cliSessionId?: string }) => Promise<void>;
   setActiveTab: (projectId: string, tabId: string) => Promise<void>;
   closeTab: (projectId: string, tabId: string) => Promise<void>;
@@ -61,7 +61,7 @@ export const useChatTabStore = create<ChatTabState>((set, get) => ({
   /**
    * Create a new tab for a project
    */
-  createTab: async (projectId: string, agentType: string, label?: string) => {
+  createTab: async (projectId: string, agentType?: string, label?: string) => {
     set({ isLoading: true, error: null });
     try {
       const newTab = await chatTabsApi.createChatTab(projectId, agentType, label);
@@ -147,8 +147,9 @@ export const useProjectStore = create<ProjectState>()(
           const backend = getBackend();
           return await backend.projects.get(projectId);
         } catch (error) {
+          // Callers tell a missing project (null) from a failed lookup
           console.error('Failed to get project:', error);
-          return null;
+          throw error;
         }
       },
     }),
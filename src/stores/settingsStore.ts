@@ -18,6 +18,23 @@ interface WhisperSettings {
   localModel: WhisperModel;
   localInstalled: boolean;
   localModelDownloaded: boolean;
+  /** ISO 639-1 code of the spoken language, or 'auto' to detect it */
+  language: string;
+  /** Output English regardless of the spoken language */
+  translate: boolean;
+  /** Show dictated prompts for editing instead of sending them right away */
+  confirmBeforeSend: boolean;
+}
+
+interface SpeechSettings {
+  /** Read the reply aloud when a long agent turn finishes */
+  readRepliesAloud: boolean;
+  /** Only turns at least this long are read */
+  minTurnSeconds: number;
+  /** OS voice name; empty for the system default */
+  voice: string;
+  /** Words per minute */
+  rate: number;
 }
 
 interface ServerSettings {
@@ -29,6 +46,7 @@ interface ServerSettings {
 
 interface SettingsState {
   whisper: WhisperSettings;
+  speech: SpeechSettings;
   server: ServerSettings;
 
   // Whisper Actions
@@ -37,6 +55,15 @@ interface SettingsState {
   setLocalModel: (model: WhisperModel) => void;
   setLocalInstalled: (installed: boolean) => void;
   setLocalModelDownloaded: (downloaded: boolean) => void;
+  setTranscriptionLanguage: (language: string) => void;
+  setTranslateToEnglish: (translate: boolean) => void;
+  setConfirmBeforeSend: (confirm: boolean) => void;
+
+  // Speech Actions
+  setReadRepliesAloud: (enabled: boolean) => void;
+  setMinTurnSeconds: (seconds: number) => void;
+  setSpeechVoice: (voice: string) => void;
+  setSpeechRate: (rate: number) => void;
 
   // Server Actions
   setServerEnabled: (enabled: boolean) => void;
@@ -58,6 +85,16 @@ export const useSettingsStore = create<SettingsState>()(
         localModel: 'base',
         localInstalled: false,
         localModelDownloaded: false,
+        language: 'auto',
+        translate: false,
+        confirmBeforeSend: false,
+      },
+
+      speech: {
+        readRepliesAloud: false,
+        minTurnSeconds: 60,
+        voice: '',
+        rate: 180,
       },
 
       server: {
@@ -92,6 +129,41 @@ export const useSettingsStore = create<SettingsState>()(
           whisper: { ...state.whisper, localModelDownloaded: downloaded },
         })),
 
+      setTranscriptionLanguage: (language) =>
+        set((state) => ({
+          whisper: { ...state.whisper, language },
+        })),
+
+      setTranslateToEnglish: (translate) =>
+        set((state) => ({
+          whisper: { ...state.whisper, translate },
+        })),
+
+      setConfirmBeforeSend: (confirmBeforeSend) =>
+        set((state) => ({
+          whisper: { ...state.whisper, confirmBeforeSend },
+        })),
+
+      setReadRepliesAloud: (readRepliesAloud) =>
+        set((state) => ({
+          speech: { ...state.speech, readRepliesAloud },
+        })),
+
+      setMinTurnSeconds: (minTurnSeconds) =>
+        set((state) => ({
+          speech: { ...state.speech, minTurnSeconds },
+        })),
+
+      setSpeechVoice: (voice) =>
+        set((state) => ({
+          speech: { ...state.speech, voice },
+        })),
+
+      setSpeechRate: (rate) =>
+        set((state) => ({
+          speech: { ...state.speech, rate },
+        })),
+
       setServerEnabled: (enabled) =>
         set((state) => ({
           server: { ...state.server, enabled },
@@ -130,6 +202,17 @@ export const useSettingsStore = create<SettingsState>()(
     }),
     {
       name: 'ateliercode-settings',
+      // Fill in fields added after settings were first persisted
+      merge: (persisted, current) => {
+        const saved = (persisted ?? {}) as Partial<SettingsState>;
+        return {
+          ...current,
+          ...saved,
+          whisper: { ...current.whisper, ...saved.whisper },
+          speech: { ...current.speech, ...saved.speech },
+          server: { ...current.server, ...saved.server },
+        };
+      },
     }
   )
 );
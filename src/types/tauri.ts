@@ -1,6 +1,8 @@
 // TypeScript types for Tauri IPC commands
 // Auto-generated to match Rust backend types
 
+import type { CiConfig } from '../api/ci';
+
 export interface Project {
   id: string;
   name: string;
@@ -17,6 +19,88 @@ export interface Project {
   color: string | null;
 }
 
+/**
+ * Project-level settings, stored as JSON in Project.settings
+ */
+export interface ProjectSettings {
+  /** Run an AI review pass on every recorded file change */
+  ai_review?: boolean;
+  /** Refuse to commit files whose recorded changes haven't been reviewed */
+  require_review_before_commit?: boolean;
+  /** Daily spend limit in USD; going over raises a budget alert */
+  daily_budget_usd?: number;
+  /** Monthly spend limit in USD; going over raises a budget alert */
+  monthly_budget_usd?: number;
+  /** OS notifications while the app is in the background; all default to on */
+  notifications?: NotificationPrefs;
+  /** Secrets masking in stored agent output and chat messages; on by default */
+  redaction?: RedactionSettings;
+  /** Approval of agent shell commands matching a deny list; Claude sessions only */
+  command_gate?: CommandGateSettings;
+  /** Named restrictions for agent processes, picked when starting a session */
+  execution_profiles?: ExecutionProfile[];
+  /** Profile used when a session doesn't pick one */
+  default_execution_profile?: string | null;
+  /** Language servers for diagnostics; empty picks them from the project's files when installed */
+  language_servers?: LanguageServerConfig[];
+  /** Append the agent's closing message of each finished turn to the "Session summaries" note */
+  session_summaries_to_notes?: boolean;
+  /** Keep deleted files' content so rejecting the deletion restores them */
+  capture_deleted_files?: boolean;
+  /** Agent for new chat tabs and sessions that don't pick one; defaults to the project's agent */
+  default_agent?: string | null;
+}
+
+export interface LanguageServerConfig {
+  /** LSP language id, e.g. "rust" or "typescript" */
+  language: string;
+  /** Program speaking LSP over stdio */
+  command: string;
+  args?: string[];
+  /** Extensions without the dot, e.g. ["ts", "tsx"] */
+  file_extensions: string[];
+}
+
+export interface ExecutionProfile {
+  name: string;
+  /** Environment variables passed to the agent besides the basics; null passes everything */
+  env_allowlist?: string[] | null;
+  /** Directories making up the agent's PATH; null keeps the app's PATH */
+  path?: string[] | null;
+  /** No network access (Linux and macOS); also cuts off hosted model APIs */
+  network_disabled?: boolean;
+  /** CPU seconds per agent process (Unix) */
+  max_cpu_seconds?: number | null;
+  /** Wall-clock seconds per turn before the agent is killed */
+  max_runtime_seconds?: number | null;
+  /** Run the agent with `docker exec` in the project's running dev container or compose service */
+  run_in_container?: boolean;
+  /** Container to run in, when it can't be found from the project's config */
+  container_name?: string | null;
+  /** Project env files (e.g. ".env") whose variables are set for the agent; later files win */
+  env_files?: string[];
+}
+
+export interface CommandGateSettings {
+  enabled?: boolean;
+  /** Commands that never need approval; `*` matches any text */
+  allow?: string[];
+  /** Commands that wait for approval, e.g. "git push" */
+  deny?: string[];
+}
+
+export interface RedactionSettings {
+  enabled?: boolean;
+  /** Extra regex patterns masked on top of the built-in key formats */
+  patterns?: string[];
+}
+
+export interface NotificationPrefs {
+  turn_finished?: boolean;
+  errors?: boolean;
+  input_requested?: boolean;
+}
+
 export interface CreateProjectInput {
   name: string;
   root_path: string;
@@ -31,6 +115,8 @@ export interface UpdateProjectInput {
   agent_type?: string;
   status?: string;
   prd_content?: string;
+  /** Who wrote prd_content; defaults to user */
+  prd_author?: 'user' | 'ai';
   settings?: string;
   /** Project icon (emoji, icon name, or path to custom icon) */
   icon?: string;
@@ -89,6 +175,27 @@ export interface ChatMessage {
   metadata: string | null;
 }
 
+export interface MessagePage {
+  messages: ChatMessage[];
+  has_more: boolean;
+}
+
+/** Cursor for loading messages older than a given message */
+export interface MessageCursor {
+  beforeTimestamp?: number;
+  beforeId?: string;
+  limit?: number;
+}
+
+export interface SessionTokenEstimate {
+  session_id: string;
+  message_count: number;
+  estimated_tokens: number;
+  context_window: number;
+  usage_ratio: number;
+  should_summarize: boolean;
+}
+
 export interface ActivityLog {
   id: string;
   project_id: string;
@@ -114,6 +221,28 @@ export interface ProjectAnalysisResult {
   detected_frameworks: string[];
   file_count: number;
   has_git: boolean;
+  /** Dev container and compose files, when the project has them */
+  container?: ContainerEnvironment | null;
+  /** GitHub Actions and GitLab CI configs */
+  ci?: CiConfig[];
+}
+
+export interface Devcontainer {
+  /** Config file, relative to the project root */
+  config_path: string;
+  name: string | null;
+  image: string | null;
+  /** Compose files the container comes from, relative to the project root */
+  docker_compose_files: string[];
+  service: string | null;
+  /** Where the project is mounted inside the container */
+  workspace_folder: string | null;
+}
+
+export interface ContainerEnvironment {
+  devcontainer: Devcontainer | null;
+  /** Compose files at the project root */
+  compose_files: string[];
 }
 
 // Tauri command wrapper types